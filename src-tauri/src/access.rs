@@ -0,0 +1,238 @@
+use crate::json_store;
+use crate::paths::path_key;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+const SOURCES_FILE: &str = "case_sources.json";
+
+/// Folders and files a case has scanned, synced or ingested. Only paths under
+/// one of these are served to the webview for that case.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CaseSources {
+    case_number: Option<String>,
+    /// Resolved paths, which access checks compare against
+    roots: BTreeSet<String>,
+    /// Each resolved root's path as the user picked it, when that differs
+    #[serde(default)]
+    picked: BTreeMap<String, String>,
+}
+
+/// One of a case's sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseSource {
+    /// As the user picked it; files scanned from the source carry this spelling
+    pub path: String,
+    /// With symlinks followed (and on Windows, `\\?\` prefixed), as access
+    /// checks compare it
+    pub resolved: String,
+}
+
+impl CaseSource {
+    /// `path` spelled under the source as picked, if it lies under the source
+    /// either as picked or once resolved
+    pub fn rebase(&self, path: &str, case_insensitive: bool) -> Option<String> {
+        let (key, root) = (path_key(path, case_insensitive), path_key(&self.path, case_insensitive));
+        if key.strip_prefix(root.trim_end_matches('/')).is_some_and(|rest| rest.is_empty() || rest.starts_with('/')) {
+            return Some(path.to_string());
+        }
+        let rest = resolve(Path::new(path)).strip_prefix(&self.resolved).ok()?.to_path_buf();
+        Some(Path::new(&self.path).join(rest).to_string_lossy().to_string())
+    }
+}
+
+fn sources_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(SOURCES_FILE)
+}
+
+/// Blank case numbers are treated as no case, as the frontend sends either
+fn case_key(case_number: Option<&str>) -> Option<&str> {
+    case_number.map(str::trim).filter(|case_number| !case_number.is_empty())
+}
+
+/// `path` with symlinks followed as far as it exists, so files that have
+/// since gone still compare with resolved sources
+pub fn resolve(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        if let Ok(resolved) = existing.canonicalize() {
+            return missing.iter().rev().fold(resolved, |resolved, name| resolved.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// Whether `path` lies under `root`, following symlinks as far as each
+/// exists, so a file that has gone (e.g. one served from the vault) still
+/// matches the source it was scanned from
+pub fn is_within(root: &Path, path: &Path) -> bool {
+    if path.components().any(|component| component == Component::ParentDir) {
+        return false;
+    }
+    resolve(path).starts_with(resolve(root))
+}
+
+/// Records folders or files as sources of a case, so their contents can be
+/// previewed. Access checks use the resolved path, which follows symlinks and
+/// on Windows carries a `\\?\` prefix; the path as the user picked it is kept
+/// alongside, since that is how the files scanned from it are spelled.
+pub fn grant(data_dir: &Path, case_number: Option<&str>, paths: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    let case_number = case_key(case_number);
+    let resolved: Vec<(String, String)> = paths
+        .iter()
+        .filter(|path| !path.trim().is_empty())
+        .map(|path| (resolve(Path::new(path)).to_string_lossy().to_string(), path.to_string()))
+        .collect();
+    if resolved.is_empty() {
+        return Ok(());
+    }
+    json_store::update(&sources_path(data_dir), |all: &mut Vec<CaseSources>| {
+        let index = match all.iter().position(|sources| sources.case_number.as_deref() == case_number) {
+            Some(index) => index,
+            None => {
+                all.push(CaseSources {
+                    case_number: case_number.map(|s| s.to_string()),
+                    roots: BTreeSet::new(),
+                    picked: BTreeMap::new(),
+                });
+                all.len() - 1
+            }
+        };
+        let sources = &mut all[index];
+        for (path, picked) in resolved {
+            if !sources.roots.iter().any(|root| Path::new(&path).starts_with(root)) {
+                if picked != path {
+                    sources.picked.insert(path.clone(), picked);
+                }
+                sources.roots.insert(path);
+            }
+        }
+    })
+}
+
+/// Whether `path` is one of the case's sources or lies under one
+pub fn is_allowed(data_dir: &Path, case_number: Option<&str>, path: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+    let case_number = case_key(case_number);
+    let all: Vec<CaseSources> = json_store::read(&sources_path(data_dir))?;
+    Ok(all
+        .iter()
+        .filter(|sources| sources.case_number.as_deref() == case_number)
+        .flat_map(|sources| &sources.roots)
+        .any(|root| is_within(Path::new(root), path)))
+}
+
+/// The case's recorded sources, folders and single files alike
+pub fn case_sources(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<CaseSource>, Box<dyn std::error::Error>> {
+    let case_number = case_key(case_number);
+    let all: Vec<CaseSources> = json_store::read(&sources_path(data_dir))?;
+    Ok(all
+        .into_iter()
+        .filter(|sources| sources.case_number.as_deref() == case_number)
+        .flat_map(|mut sources| {
+            let roots = std::mem::take(&mut sources.roots);
+            roots.into_iter().map(move |resolved| CaseSource {
+                path: sources.picked.remove(&resolved).unwrap_or_else(|| resolved.clone()),
+                resolved,
+            })
+        })
+        .collect())
+}
+
+/// Removes one of the case's sources, given as picked or resolved, so files
+/// under it are no longer served. Returns whether it was a source.
+pub fn revoke(data_dir: &Path, case_number: Option<&str>, path: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let case_number = case_key(case_number);
+    let resolved = resolve(Path::new(path)).to_string_lossy().to_string();
+    json_store::update(&sources_path(data_dir), |all: &mut Vec<CaseSources>| {
+        all.iter_mut()
+            .filter(|sources| sources.case_number.as_deref() == case_number)
+            .any(|sources| {
+                let root = sources
+                    .roots
+                    .iter()
+                    .find(|root| *root == path || **root == resolved || sources.picked.get(*root).is_some_and(|picked| picked == path))
+                    .cloned();
+                root.is_some_and(|root| {
+                    sources.picked.remove(&root);
+                    sources.roots.remove(&root)
+                })
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn sources_can_be_granted_listed_and_revoked() {
+        let dir = std::env::temp_dir().join(format!("access-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let bank = dir.join("files").join("Bank");
+        fs::create_dir_all(&bank).unwrap();
+        fs::write(bank.join("jan.pdf"), "x").unwrap();
+        let bank = bank.canonicalize().unwrap();
+        let files = bank.parent().unwrap().to_string_lossy().to_string();
+        let bank_path = bank.to_string_lossy().to_string();
+
+        grant(&dir, Some("A-1"), &[&bank_path]).unwrap();
+        // A folder under an existing source is already covered
+        grant(&dir, Some(" A-1 "), &[&bank_path, &files]).unwrap();
+        grant(&dir, Some("A-1"), &[&bank_path]).unwrap();
+        let mut sources: Vec<String> = case_sources(&dir, Some("A-1")).unwrap().into_iter().map(|source| source.path).collect();
+        sources.sort();
+        assert_eq!(sources, [files.clone(), bank_path.clone()]);
+        assert!(is_allowed(&dir, Some("A-1"), &bank.join("jan.pdf")).unwrap());
+        assert!(!is_allowed(&dir, Some("B-2"), &bank.join("jan.pdf")).unwrap());
+        assert!(!is_allowed(&dir, Some("A-1"), &bank.join("..").join("Bank").join("jan.pdf")).unwrap());
+
+        assert!(revoke(&dir, Some("A-1"), &files).unwrap());
+        assert!(!revoke(&dir, Some("A-1"), &files).unwrap());
+        assert!(revoke(&dir, Some("A-1"), &bank_path).unwrap());
+        assert!(!is_allowed(&dir, Some("A-1"), &bank.join("jan.pdf")).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sources_keep_the_path_as_picked_next_to_the_resolved_one() {
+        let dir = std::env::temp_dir().join(format!("access-picked-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let bank = dir.join("files").join("Bank");
+        fs::create_dir_all(&bank).unwrap();
+        fs::write(bank.join("jan.pdf"), "x").unwrap();
+        let link = dir.join("link");
+        std::os::unix::fs::symlink(&bank, &link).unwrap();
+        let bank = bank.canonicalize().unwrap();
+        let picked = link.to_string_lossy().to_string();
+
+        grant(&dir, Some("A-1"), &[&picked]).unwrap();
+        let sources = case_sources(&dir, Some("A-1")).unwrap();
+        assert_eq!(sources, [CaseSource { path: picked.clone(), resolved: bank.to_string_lossy().to_string() }]);
+        assert!(is_allowed(&dir, Some("A-1"), &link.join("jan.pdf")).unwrap());
+        assert!(is_allowed(&dir, Some("A-1"), &bank.join("jan.pdf")).unwrap());
+
+        // Files reached either way, even once gone, are spelled as picked
+        let source = &sources[0];
+        let as_picked = link.join("jan.pdf").to_string_lossy().to_string();
+        assert_eq!(source.rebase(&as_picked, false), Some(as_picked.clone()));
+        assert_eq!(source.rebase(&bank.join("jan.pdf").to_string_lossy(), false), Some(as_picked.clone()));
+        let gone = bank.join("old").join("dec.pdf");
+        assert_eq!(source.rebase(&gone.to_string_lossy(), false), Some(link.join("old").join("dec.pdf").to_string_lossy().to_string()));
+        assert_eq!(source.rebase(&format!("{}2/jan.pdf", picked), false), None);
+        assert_eq!(source.rebase(&as_picked.to_uppercase(), true), Some(as_picked.to_uppercase()));
+        assert_eq!(source.rebase(&as_picked.to_uppercase(), false), None);
+
+        assert!(revoke(&dir, Some("A-1"), &picked).unwrap());
+        assert!(case_sources(&dir, Some("A-1")).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
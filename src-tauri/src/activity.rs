@@ -0,0 +1,114 @@
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const ACTIVITY_LOG_FILE: &str = "activity.jsonl";
+
+/// A single line of the structured activity log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    pub timestamp: String,
+    /// Operation that produced the entry: "scan", "sync", "export", ...
+    pub kind: String,
+    pub case_number: Option<String>,
+    pub message: String,
+    pub success: bool,
+}
+
+impl ActivityEntry {
+    pub fn new(kind: &str, case_number: Option<&str>, message: String, success: bool) -> Self {
+        Self {
+            timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            kind: kind.to_string(),
+            case_number: case_number.map(|s| s.to_string()),
+            message,
+            success,
+        }
+    }
+}
+
+/// The global log in `log_dir`, or a case's own log in its data directory
+fn log_path(log_dir: &Path, case_number: Option<&str>) -> PathBuf {
+    match case_number.filter(|c| !c.trim().is_empty()) {
+        Some(case_number) => json_store::case_data_dir(log_dir, case_number).join(ACTIVITY_LOG_FILE),
+        None => log_dir.join(ACTIVITY_LOG_FILE),
+    }
+}
+
+fn append_line(path: &Path, line: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Appends an entry to the global activity log in `log_dir` and, for an
+/// entry with a case, to that case's own log as well
+pub fn append_activity(log_dir: &Path, entry: &ActivityEntry) -> std::io::Result<()> {
+    let line = serde_json::to_string(entry)?;
+    append_line(&log_path(log_dir, None), &line)?;
+    if entry.case_number.as_deref().is_some_and(|c| !c.trim().is_empty()) {
+        append_line(&log_path(log_dir, entry.case_number.as_deref()), &line)?;
+    }
+    Ok(())
+}
+
+/// Reads the most recent entries (newest first): a case's own log, or the
+/// global log when no case is given
+pub fn read_recent_activity(
+    log_dir: &Path,
+    case_number: Option<&str>,
+    limit: usize,
+) -> std::io::Result<Vec<ActivityEntry>> {
+    let path = log_path(log_dir, case_number);
+    let mut entries: Vec<ActivityEntry> = json_store::read_jsonl::<ActivityEntry>(&path)?
+        .into_iter()
+        .filter(|entry| case_number.is_none_or(|c| entry.case_number.as_deref() == Some(c)))
+        .collect();
+
+    entries.reverse();
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("activity-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn case_entries_go_to_the_case_log_and_the_global_log() {
+        let dir = scratch_dir("per-case");
+        append_activity(&dir, &ActivityEntry::new("scan", Some("A-1"), "first".to_string(), true)).unwrap();
+        append_activity(&dir, &ActivityEntry::new("scan", Some("B-2"), "second".to_string(), true)).unwrap();
+        append_activity(&dir, &ActivityEntry::new("sync", None, "third".to_string(), false)).unwrap();
+
+        assert!(json_store::case_data_dir(&dir, "A-1").join(ACTIVITY_LOG_FILE).exists());
+        let case_a = read_recent_activity(&dir, Some("A-1"), 10).unwrap();
+        assert_eq!(case_a.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(), ["first"]);
+        let global = read_recent_activity(&dir, None, 10).unwrap();
+        assert_eq!(global.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(), ["third", "second", "first"]);
+        assert!(read_recent_activity(&dir, Some("C-3"), 10).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn case_folder_names_are_distinct_and_stay_in_the_cases_folder() {
+        let dir = Path::new("data");
+        assert_eq!(json_store::case_data_dir(dir, " A/1 "), dir.join("cases").join("412f31"));
+        assert_eq!(json_store::case_data_dir(dir, ".."), dir.join("cases").join("2e2e"));
+        assert_eq!(json_store::case_data_dir(dir, " "), dir.join("cases").join("_"));
+        let folders: std::collections::HashSet<PathBuf> =
+            ["A/1", "A_1", "A:1", "a-1", "A-1"].iter().map(|case| json_store::case_data_dir(dir, case)).collect();
+        assert_eq!(folders.len(), 5);
+    }
+}
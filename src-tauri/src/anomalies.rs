@@ -0,0 +1,183 @@
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+/// Below this many items a single file of a type is not unusual
+const MIN_ITEMS_FOR_RARE_TYPES: usize = 20;
+
+/// Folders need this many files for a size comparison to mean anything
+const MIN_FOLDER_FILES: usize = 5;
+
+/// A file is flagged when it is this many times the folder's median size...
+const LARGE_FILE_FACTOR: u64 = 10;
+
+/// ...and at least this large, so folders of tiny files do not flag 20 KB files
+const LARGE_FILE_MIN_BYTES: u64 = 1024 * 1024;
+
+/// Interquartile ranges beyond the quartiles at which a year is an outlier
+const YEAR_OUTLIER_IQRS: f64 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Document year far outside the case's date range
+    DateOutlier,
+    /// The only file of its type in the case
+    RareType,
+    /// Much larger than the other files in its folder
+    LargeInFolder,
+}
+
+/// A review hint: something statistically unusual about one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Anomaly {
+    pub absolute_path: String,
+    pub file_name: String,
+    pub kind: AnomalyKind,
+    pub detail: String,
+}
+
+/// Years outside [q1 - k*iqr, q3 + k*iqr] of the case's document years
+fn year_bounds(items: &[InventoryItem]) -> Option<(i32, i32)> {
+    let mut years: Vec<i32> = items.iter().map(|item| item.doc_year).filter(|year| *year > 0).collect();
+    if years.len() < 4 {
+        return None;
+    }
+    years.sort_unstable();
+    let quartile = |q: f64| years[((years.len() - 1) as f64 * q).round() as usize] as f64;
+    let (q1, q3) = (quartile(0.25), quartile(0.75));
+    // At least a year of spread, so a single-year case still tolerates neighbours
+    let iqr = (q3 - q1).max(1.0);
+    Some((
+        (q1 - YEAR_OUTLIER_IQRS * iqr).floor() as i32,
+        (q3 + YEAR_OUTLIER_IQRS * iqr).ceil() as i32,
+    ))
+}
+
+fn anomaly(item: &InventoryItem, kind: AnomalyKind, detail: String) -> Anomaly {
+    Anomaly {
+        absolute_path: item.absolute_path.clone(),
+        file_name: item.file_name.clone(),
+        kind,
+        detail,
+    }
+}
+
+/// Flags statistical outliers among the case's items. `year_range` overrides
+/// the date range inferred from the items themselves.
+pub fn find_anomalies(items: &[InventoryItem], year_range: Option<(i32, i32)>) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+
+    if let Some((first, last)) = year_range.or_else(|| year_bounds(items)) {
+        for item in items.iter().filter(|item| item.doc_year > 0) {
+            if item.doc_year < first || item.doc_year > last {
+                anomalies.push(anomaly(
+                    item,
+                    AnomalyKind::DateOutlier,
+                    format!("Year {} is outside {}-{}", item.doc_year, first, last),
+                ));
+            }
+        }
+    }
+
+    if items.len() >= MIN_ITEMS_FOR_RARE_TYPES {
+        let mut type_counts: HashMap<String, usize> = HashMap::new();
+        for item in items {
+            *type_counts.entry(item.file_type.to_uppercase()).or_insert(0) += 1;
+        }
+        for item in items.iter().filter(|item| type_counts[&item.file_type.to_uppercase()] == 1) {
+            let file_type = if item.file_type.is_empty() { "(none)" } else { item.file_type.as_str() };
+            anomalies.push(anomaly(
+                item,
+                AnomalyKind::RareType,
+                format!("Only {} file in the case", file_type),
+            ));
+        }
+    }
+
+    let mut by_folder: HashMap<&str, Vec<(&InventoryItem, u64)>> = HashMap::new();
+    for item in items.iter().filter(|item| !item.absolute_path.is_empty()) {
+        if let Ok(metadata) = fs::metadata(&item.absolute_path) {
+            by_folder.entry(item.folder_path.as_str()).or_default().push((item, metadata.len()));
+        }
+    }
+    let mut folders: Vec<_> = by_folder.into_iter().filter(|(_, files)| files.len() >= MIN_FOLDER_FILES).collect();
+    folders.sort_by(|a, b| a.0.cmp(b.0));
+    for (_, files) in folders {
+        let mut sizes: Vec<u64> = files.iter().map(|(_, size)| *size).collect();
+        sizes.sort_unstable();
+        let median = sizes[sizes.len() / 2];
+        for (item, size) in files {
+            if size >= LARGE_FILE_MIN_BYTES && size > median.max(1) * LARGE_FILE_FACTOR {
+                anomalies.push(anomaly(
+                    item,
+                    AnomalyKind::LargeInFolder,
+                    format!("{} bytes; folder median is {} bytes", size, median),
+                ));
+            }
+        }
+    }
+
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+
+    fn item(file_name: &str, doc_year: i32, file_type: &str) -> InventoryItem {
+        InventoryItem { doc_year, file_type: file_type.to_string(), ..test_item(file_name) }
+    }
+
+    fn flagged(anomalies: &[Anomaly], kind: AnomalyKind) -> Vec<&str> {
+        anomalies.iter().filter(|anomaly| anomaly.kind == kind).map(|anomaly| anomaly.file_name.as_str()).collect()
+    }
+
+    #[test]
+    fn outlying_years_and_one_off_types_are_flagged() {
+        let mut items: Vec<InventoryItem> =
+            (0..MIN_ITEMS_FOR_RARE_TYPES).map(|n| item(&format!("{}.pdf", n), 2020 + (n % 3) as i32, "PDF")).collect();
+        items.push(item("old.pdf", 1995, "pdf"));
+        items.push(item("undated.pdf", 0, "PDF"));
+        items.push(item("setup.exe", 2021, "EXE"));
+
+        let anomalies = find_anomalies(&items, None);
+        assert_eq!(flagged(&anomalies, AnomalyKind::DateOutlier), ["old.pdf"]);
+        assert_eq!(anomalies[0].detail, "Year 1995 is outside 2014-2028");
+        assert_eq!(flagged(&anomalies, AnomalyKind::RareType), ["setup.exe"]);
+
+        let anomalies = find_anomalies(&items, Some((2021, 2021)));
+        assert_eq!(flagged(&anomalies, AnomalyKind::DateOutlier).len(), 14);
+        // Too few items for a type to count as rare
+        assert!(flagged(&find_anomalies(&items[18..], None), AnomalyKind::RareType).is_empty());
+    }
+
+    #[test]
+    fn files_far_larger_than_their_folder_are_flagged() {
+        let dir = std::env::temp_dir().join(format!("anomalies-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let sizes = [10_000, 12_000, 9_000, 11_000, 20 * 1024 * 1024, 150_000];
+        let items: Vec<InventoryItem> = sizes
+            .iter()
+            .enumerate()
+            .map(|(n, size)| {
+                let path = dir.join(format!("{}.pdf", n));
+                fs::File::create(&path).unwrap().set_len(*size).unwrap();
+                InventoryItem {
+                    absolute_path: path.to_string_lossy().to_string(),
+                    folder_path: "Bank".to_string(),
+                    ..item(&format!("{}.pdf", n), 2024, "PDF")
+                }
+            })
+            .collect();
+
+        let anomalies = find_anomalies(&items, None);
+        assert_eq!(flagged(&anomalies, AnomalyKind::LargeInFolder), ["4.pdf"]);
+        // Folders with fewer files are not compared
+        assert!(find_anomalies(&items[2..], None).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
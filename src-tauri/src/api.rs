@@ -0,0 +1,625 @@
+use crate::search::{self, SearchAllOptions, SearchSource};
+use crate::lock::LockState;
+use crate::{links, notes, InventoryItem};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Clients that stall, or take longer than this to send their request, are
+/// dropped
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Request heads larger than this are refused
+const MAX_REQUEST_HEAD_BYTES: usize = 16 * 1024;
+
+/// Request or header lines longer than this are refused
+const MAX_LINE_BYTES: usize = 8 * 1024;
+
+/// Connections served at once; more are turned away until one finishes
+const MAX_CONNECTIONS: usize = 16;
+
+/// Files returned per page when the client gives no limit
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+struct PublishedInventory {
+    /// Shared so requests can work on the items without holding the lock
+    items: Arc<Vec<InventoryItem>>,
+    published_at: String,
+    /// Tick of the last publish or read, for unloading the least recently
+    /// used case first
+    last_used: u64,
+}
+
+/// Caps on the inventories held in memory; `None` is no cap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryLimits {
+    /// Cases held at once; publishing one more unloads the least recently
+    /// used
+    pub max_cases: Option<usize>,
+    /// Files one case may publish
+    pub max_case_files: Option<usize>,
+}
+
+/// One published case's share of memory
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishedUsage {
+    pub case_number: String,
+    pub file_count: usize,
+    pub published_at: String,
+}
+
+/// Inventories the case windows have published, by case number. The
+/// backend keeps no inventory of its own, so the API and scheduled exports
+/// work from what the windows last sent.
+pub struct PublishedInventories {
+    cases: Mutex<BTreeMap<String, PublishedInventory>>,
+    limits: Mutex<InventoryLimits>,
+    clock: AtomicU64,
+}
+
+impl PublishedInventories {
+    pub const fn new() -> Self {
+        Self {
+            cases: Mutex::new(BTreeMap::new()),
+            limits: Mutex::new(InventoryLimits {
+                max_cases: None,
+                max_case_files: None,
+            }),
+            clock: AtomicU64::new(0),
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn limits(&self) -> InventoryLimits {
+        *self.limits.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Holds the case's items, unloading the least recently used cases past
+    /// the case limit. Fails when the case has more files than one case may
+    /// publish. Returns the cases unloaded.
+    pub fn publish(&self, case_number: &str, items: Vec<InventoryItem>, published_at: &str) -> Result<Vec<String>, String> {
+        let limits = self.limits();
+        if let Some(max) = limits.max_case_files.filter(|max| items.len() > *max) {
+            return Err(format!("case {} has {} files, more than the limit of {}", case_number, items.len(), max));
+        }
+        let mut cases = self.cases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        cases.insert(
+            case_number.to_string(),
+            PublishedInventory {
+                items: Arc::new(items),
+                published_at: published_at.to_string(),
+                last_used: self.tick(),
+            },
+        );
+        Ok(unload_over_limits(&mut cases, limits))
+    }
+
+    /// The case's last published items
+    pub fn items(&self, case_number: &str) -> Option<Vec<InventoryItem>> {
+        let mut cases = self.cases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let inventory = cases.get_mut(case_number)?;
+        inventory.last_used = self.tick();
+        Some(inventory.items.as_ref().clone())
+    }
+
+    /// Drops a case's items, as when its window closes; returns whether any
+    /// were held
+    pub fn unload(&self, case_number: &str) -> bool {
+        let mut cases = self.cases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        cases.remove(case_number).is_some()
+    }
+
+    /// Sets the caps and unloads whatever is over them. Returns the cases
+    /// unloaded.
+    pub fn set_limits(&self, limits: InventoryLimits) -> Vec<String> {
+        *self.limits.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = limits;
+        let mut cases = self.cases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        unload_over_limits(&mut cases, limits)
+    }
+
+    pub fn get_limits(&self) -> InventoryLimits {
+        self.limits()
+    }
+
+    /// The cases held, most recently used first
+    pub fn usage(&self) -> Vec<PublishedUsage> {
+        let cases = self.cases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut held: Vec<(&String, &PublishedInventory)> = cases.iter().collect();
+        held.sort_by_key(|(_, inventory)| std::cmp::Reverse(inventory.last_used));
+        held.into_iter()
+            .map(|(case_number, inventory)| PublishedUsage {
+                case_number: case_number.clone(),
+                file_count: inventory.items.len(),
+                published_at: inventory.published_at.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Unloads cases with more files than allowed, then the least recently
+/// used until no more than the case limit remain
+fn unload_over_limits(cases: &mut BTreeMap<String, PublishedInventory>, limits: InventoryLimits) -> Vec<String> {
+    let mut unloaded: Vec<String> = match limits.max_case_files {
+        Some(max) => cases.iter().filter(|(_, inventory)| inventory.items.len() > max).map(|(case_number, _)| case_number.clone()).collect(),
+        None => Vec::new(),
+    };
+    for case_number in &unloaded {
+        cases.remove(case_number);
+    }
+    if let Some(max) = limits.max_cases {
+        while cases.len() > max {
+            let Some(oldest) = cases.iter().min_by_key(|(_, inventory)| inventory.last_used).map(|(case_number, _)| case_number.clone()) else {
+                break;
+            };
+            cases.remove(&oldest);
+            unloaded.push(oldest);
+        }
+    }
+    unloaded
+}
+
+#[derive(Debug, Serialize)]
+struct CaseSummary<'a> {
+    case_number: &'a str,
+    file_count: usize,
+    published_at: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct FilePage<'a> {
+    total: usize,
+    offset: usize,
+    files: &'a [InventoryItem],
+}
+
+/// A parsed GET request
+#[derive(Debug, Default)]
+pub struct ApiRequest {
+    pub method: String,
+    /// Decoded path segments
+    pub segments: Vec<String>,
+    pub query: HashMap<String, String>,
+    pub bearer_token: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl ApiResponse {
+    fn json<T: Serialize>(value: &T) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Self { status: 200, body },
+            Err(e) => Self::error(500, &e.to_string()),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "error": message }).to_string(),
+        }
+    }
+}
+
+/// A running API server; dropping it without `stop` leaves it running
+pub struct ApiServer {
+    pub port: u16,
+    pub token: String,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ApiServer {
+    /// Stops accepting connections and waits for the server thread to end
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the blocking accept so the thread sees the flag
+        let _ = TcpStream::connect((Ipv4Addr::LOCALHOST, self.port));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Compares tokens without stopping at the first differing byte
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len() && given.bytes().zip(expected.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes.get(index + 1..index + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+        match (bytes[index], hex.and_then(|hex| u8::from_str_radix(hex, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Parses the request line and headers; the body is never read
+pub fn parse_request(head: &str) -> Option<ApiRequest> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let bearer_token = lines.find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        let value = value.trim();
+        let token = value.strip_prefix("Bearer ").or_else(|| value.strip_prefix("bearer "))?;
+        name.trim().eq_ignore_ascii_case("authorization").then(|| token.trim().to_string())
+    });
+    Some(ApiRequest {
+        method,
+        segments: path.split('/').filter(|s| !s.is_empty()).map(percent_decode).collect(),
+        query: query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(name), percent_decode(value))
+            })
+            .collect(),
+        bearer_token,
+    })
+}
+
+fn query_number(request: &ApiRequest, name: &str) -> Result<Option<usize>, ApiResponse> {
+    match request.query.get(name) {
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| ApiResponse::error(400, &format!("{} must be a number", name))),
+        None => Ok(None),
+    }
+}
+
+fn search_case(data_dir: &Path, case_number: &str, items: &[InventoryItem], request: &ApiRequest) -> Result<ApiResponse, ApiResponse> {
+    let query = request.query.get("q").map(String::as_str).unwrap_or_default();
+    let mut sources = Vec::new();
+    for source in request.query.get("sources").map(String::as_str).unwrap_or_default().split(',') {
+        match source.trim() {
+            "" => {}
+            "file" => sources.push(SearchSource::File),
+            "note" => sources.push(SearchSource::Note),
+            "link" => sources.push(SearchSource::Link),
+            other => return Err(ApiResponse::error(400, &format!("unknown source: {}", other))),
+        }
+    }
+    let options = SearchAllOptions {
+        sources,
+        offset: query_number(request, "offset")?.unwrap_or(0),
+        limit_per_source: Some(query_number(request, "limit")?.unwrap_or(DEFAULT_PAGE_SIZE)),
+        ..SearchAllOptions::default()
+    };
+
+    let case = Some(case_number);
+    let internal = |e: Box<dyn std::error::Error>| ApiResponse::error(500, &e.to_string());
+    let notes: Vec<_> = notes::list_notes(data_dir, case, None)
+        .map_err(internal)?
+        .into_iter()
+        .map(|listed| listed.note)
+        .collect();
+    let links = links::case_links(data_dir, case).map_err(internal)?;
+    Ok(ApiResponse::json(&search::search_all(items, &notes, &links, query, &options)))
+}
+
+/// Answers one request. Every endpoint is read-only and needs the token as
+/// `Authorization: Bearer <token>`:
+///
+/// - `GET /cases`: published cases with their file counts
+/// - `GET /cases/{case}/files?offset=&limit=`: a page of the case's files
+/// - `GET /cases/{case}/search?q=&sources=file,note,link&offset=&limit=`:
+///   the same search as the app's global search
+pub fn route(published: &PublishedInventories, lock: &LockState, data_dir: &Path, token: &str, request: &ApiRequest) -> ApiResponse {
+    if !request.bearer_token.as_deref().is_some_and(|given| tokens_match(given, token)) {
+        return ApiResponse::error(401, "missing or wrong token");
+    }
+    if request.method != "GET" {
+        return ApiResponse::error(405, "the API is read-only");
+    }
+    if lock.refuses_outside_request(Instant::now()) {
+        return ApiResponse::error(403, "the app is locked");
+    }
+
+    let segments: Vec<&str> = request.segments.iter().map(String::as_str).collect();
+    let (case_number, resource) = match segments.as_slice() {
+        ["cases"] => {
+            let cases = published.cases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let summaries: Vec<CaseSummary> = cases
+                .iter()
+                .map(|(case_number, inventory)| CaseSummary {
+                    case_number,
+                    file_count: inventory.items.len(),
+                    published_at: &inventory.published_at,
+                })
+                .collect();
+            return ApiResponse::json(&summaries);
+        }
+        ["cases", case_number, resource] => (*case_number, *resource),
+        _ => return ApiResponse::error(404, "no such endpoint"),
+    };
+    // Searches read notes and links from disk, so the lock is only held to
+    // take the items
+    let items = {
+        let mut cases = published.cases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(inventory) = cases.get_mut(case_number) else {
+            return ApiResponse::error(404, &format!("case not published: {}", case_number));
+        };
+        inventory.last_used = published.tick();
+        inventory.items.clone()
+    };
+
+    let result = match resource {
+        "files" => (|| {
+            let total = items.len();
+            let offset = query_number(request, "offset")?.unwrap_or(0).min(total);
+            let limit = query_number(request, "limit")?.unwrap_or(DEFAULT_PAGE_SIZE);
+            let end = offset.saturating_add(limit).min(total);
+            Ok(ApiResponse::json(&FilePage { total, offset, files: &items[offset..end] }))
+        })(),
+        "search" => search_case(data_dir, case_number, &items, request),
+        _ => Err(ApiResponse::error(404, "no such endpoint")),
+    };
+    result.unwrap_or_else(|response| response)
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Reads the request head up to the blank line. Lines and heads past their
+/// limits are refused before they are held in memory, and a client sending
+/// a byte at a time cannot hold the connection past CLIENT_TIMEOUT.
+fn read_head(reader: &mut impl BufRead, started: Instant) -> Result<String, ApiResponse> {
+    let mut head = String::new();
+    loop {
+        if started.elapsed() > CLIENT_TIMEOUT {
+            return Err(ApiResponse::error(408, "request took too long"));
+        }
+        let limit = MAX_LINE_BYTES.min(MAX_REQUEST_HEAD_BYTES - head.len());
+        let mut line = String::new();
+        let read = reader.take(limit as u64 + 1).read_line(&mut line).map_err(|e| match e.kind() {
+            ErrorKind::TimedOut | ErrorKind::WouldBlock => ApiResponse::error(408, "request took too long"),
+            _ => ApiResponse::error(400, "malformed request"),
+        })?;
+        if read > limit {
+            return Err(ApiResponse::error(431, "request head too large"));
+        }
+        if read == 0 || line == "\r\n" || line == "\n" {
+            return Ok(head);
+        }
+        head.push_str(&line);
+        if head.len() >= MAX_REQUEST_HEAD_BYTES {
+            return Err(ApiResponse::error(431, "request head too large"));
+        }
+    }
+}
+
+fn write_response(mut stream: &TcpStream, response: &ApiResponse) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason_phrase(response.status),
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+fn serve_connection(
+    stream: TcpStream,
+    published: &PublishedInventories,
+    lock: &LockState,
+    data_dir: &Path,
+    token: &str,
+) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let response = match read_head(&mut reader, Instant::now()) {
+        Ok(head) => match parse_request(&head) {
+            Some(request) => route(published, lock, data_dir, token, &request),
+            None => ApiResponse::error(400, "malformed request"),
+        },
+        Err(response) => response,
+    };
+    write_response(&stream, &response)
+}
+
+/// Starts the API on 127.0.0.1 with a new random token. Port 0 picks a free
+/// port. Each connection is served on its own thread, up to MAX_CONNECTIONS
+/// at once; requests are refused while the app is locked.
+pub fn start_server(
+    published: &'static PublishedInventories,
+    lock: &'static LockState,
+    data_dir: PathBuf,
+    port: u16,
+) -> std::io::Result<ApiServer> {
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))?;
+    let port = listener.local_addr()?.port();
+    let token = new_token();
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let thread = {
+        let token = token.clone();
+        let stop = stop.clone();
+        let active = Arc::new(AtomicUsize::new(0));
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    let _ = stream.set_write_timeout(Some(CLIENT_TIMEOUT));
+                    let _ = write_response(&stream, &ApiResponse::error(503, "too many connections"));
+                    continue;
+                }
+                let (active, data_dir, token) = (active.clone(), data_dir.clone(), token.clone());
+                std::thread::spawn(move || {
+                    if let Err(e) = serve_connection(stream, published, lock, &data_dir, &token) {
+                        eprintln!("Error serving API request: {}", e);
+                    }
+                    active.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        })
+    };
+    Ok(ApiServer {
+        port,
+        token,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+    use std::io::Cursor;
+
+    fn item(file_name: &str, document_description: &str) -> InventoryItem {
+        InventoryItem {
+            document_type: "Statement".to_string(),
+            document_description: document_description.to_string(),
+            folder_name: "Bank".to_string(),
+            folder_path: "Bank".to_string(),
+            ..test_item(&format!("/c/Bank/{}", file_name))
+        }
+    }
+
+    fn get(target: &str, token: &str) -> ApiRequest {
+        parse_request(&format!("GET {} HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\n", target, token)).unwrap()
+    }
+
+    #[test]
+    fn routes_need_the_token_and_serve_published_cases() {
+        let dir = std::env::temp_dir().join(format!("api-{}", std::process::id()));
+        let published = PublishedInventories::new();
+        let lock = LockState::new();
+        published.publish("A 1", vec![item("jan.pdf", "Wire"), item("feb.pdf", "Deposit")], "2026-03-01 09:00:00").unwrap();
+
+        assert_eq!(route(&published, &lock, &dir, "secret", &get("/cases", "wrong")).status, 401);
+        let cases = route(&published, &lock, &dir, "secret", &get("/cases", "secret"));
+        assert_eq!(cases.status, 200);
+        assert!(cases.body.contains(r#""case_number":"A 1","file_count":2"#));
+
+        let page = route(&published, &lock, &dir, "secret", &get("/cases/A%201/files?offset=1&limit=5", "secret"));
+        assert!(page.body.starts_with(r#"{"total":2,"offset":1,"files":[{"#));
+        assert!(page.body.contains("feb.pdf") && !page.body.contains("jan.pdf"));
+
+        let search = route(&published, &lock, &dir, "secret", &get("/cases/A+1/search?q=wire&sources=file", "secret"));
+        assert!(search.body.contains("/c/Bank/jan.pdf"));
+        assert_eq!(route(&published, &lock, &dir, "secret", &get("/cases/B-2/files", "secret")).status, 404);
+        assert_eq!(route(&published, &lock, &dir, "secret", &get("/cases/A%201/files?limit=x", "secret")).status, 400);
+
+        let mut post = get("/cases", "secret");
+        post.method = "POST".to_string();
+        assert_eq!(route(&published, &lock, &dir, "secret", &post).status, 405);
+
+        lock.lock();
+        assert_eq!(route(&published, &lock, &dir, "secret", &get("/cases", "secret")).status, 403);
+    }
+
+    #[test]
+    fn request_heads_are_read_within_limits() {
+        let head = read_head(&mut Cursor::new("GET /cases HTTP/1.1\r\nHost: x\r\n\r\nbody"), Instant::now()).unwrap();
+        assert_eq!(head, "GET /cases HTTP/1.1\r\nHost: x\r\n");
+
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE_BYTES));
+        assert_eq!(read_head(&mut Cursor::new(long_line), Instant::now()).unwrap_err().status, 431);
+        let many_lines = "X-Pad: aaaaaaaaaaaaaaaa\r\n".repeat(MAX_REQUEST_HEAD_BYTES / 16);
+        assert_eq!(read_head(&mut Cursor::new(many_lines), Instant::now()).unwrap_err().status, 431);
+
+        let started = Instant::now() - CLIENT_TIMEOUT - Duration::from_secs(1);
+        assert_eq!(read_head(&mut Cursor::new("GET / HTTP/1.1\r\n"), started).unwrap_err().status, 408);
+    }
+
+    #[test]
+    fn limits_unload_the_least_recently_used_cases() {
+        let published = PublishedInventories::new();
+        published.publish("A-1", vec![item("a.pdf", "")], "2026-03-01 09:00:00").unwrap();
+        published.publish("B-2", vec![item("b.pdf", "")], "2026-03-01 09:01:00").unwrap();
+        assert!(published.items("A-1").is_some());
+
+        let limits = InventoryLimits { max_cases: Some(2), max_case_files: Some(1) };
+        assert!(published.set_limits(limits).is_empty());
+        assert_eq!(published.publish("C-3", vec![item("c.pdf", "")], "2026-03-01 09:02:00").unwrap(), ["B-2"]);
+        let held: Vec<String> = published.usage().into_iter().map(|usage| usage.case_number).collect();
+        assert_eq!(held, ["C-3", "A-1"]);
+        assert!(published.publish("D-4", vec![item("d.pdf", ""), item("e.pdf", "")], "2026-03-01 09:03:00").is_err());
+
+        assert_eq!(published.set_limits(InventoryLimits { max_cases: Some(1), max_case_files: None }), ["A-1"]);
+        assert!(published.unload("C-3"));
+        assert!(published.usage().is_empty());
+    }
+
+    #[test]
+    fn server_answers_over_http_until_stopped() {
+        static PUBLISHED: PublishedInventories = PublishedInventories::new();
+        static LOCK: LockState = LockState::new();
+        let server = start_server(&PUBLISHED, &LOCK, std::env::temp_dir(), 0).unwrap();
+
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, server.port)).unwrap();
+        write!(stream, "GET /cases HTTP/1.1\r\nAuthorization: Bearer {}\r\n\r\n", server.token).unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        assert!(reply.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(reply.ends_with("\r\n\r\n[]"));
+
+        let port = server.port;
+        server.stop();
+        assert!(TcpStream::connect((Ipv4Addr::LOCALHOST, port)).is_err());
+    }
+}
@@ -0,0 +1,667 @@
+use crate::profiling::{has_sqlite_header, open_read_only};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Seconds between 1601-01-01 (the WebKit/Windows epoch Chrome uses) and 1970-01-01
+const WEBKIT_EPOCH_OFFSET_SECONDS: i64 = 11_644_473_600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Browser {
+    Chrome,
+    Firefox,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactEventKind {
+    Visit,
+    Download,
+}
+
+/// One dated event recovered from an artifact, pointing back at its source file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactEvent {
+    /// UTC, `YYYY-MM-DD HH:MM:SS`
+    pub timestamp: String,
+    pub kind: ArtifactEventKind,
+    pub url: String,
+    pub title: String,
+    /// Where a download was saved; empty for visits
+    pub target_path: String,
+    pub source_path: String,
+}
+
+/// Events parsed from one browser history database, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserArtifact {
+    pub path: String,
+    pub browser: Option<Browser>,
+    pub events: Vec<ArtifactEvent>,
+    pub error: Option<String>,
+}
+
+fn has_table(conn: &Connection, name: &str) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [name],
+        |_| Ok(()),
+    )
+    .is_ok()
+}
+
+fn detect_browser(conn: &Connection) -> Option<Browser> {
+    if has_table(conn, "urls") && has_table(conn, "visits") {
+        Some(Browser::Chrome)
+    } else if has_table(conn, "moz_places") && has_table(conn, "moz_historyvisits") {
+        Some(Browser::Firefox)
+    } else {
+        None
+    }
+}
+
+fn format_unix_micros(micros: i64) -> Option<String> {
+    DateTime::<Utc>::from_timestamp_micros(micros).map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn format_webkit_micros(micros: i64) -> Option<String> {
+    // Zero means "never" in Chrome's tables
+    if micros <= 0 {
+        return None;
+    }
+    format_unix_micros(micros - WEBKIT_EPOCH_OFFSET_SECONDS * 1_000_000)
+}
+
+/// Runs an event query whose columns are (time, url, title, target_path)
+fn query_events(
+    conn: &Connection,
+    sql: &str,
+    kind: ArtifactEventKind,
+    format_time: fn(i64) -> Option<String>,
+    source_path: &str,
+) -> rusqlite::Result<Vec<ArtifactEvent>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, Option<i64>>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+
+    let mut events = Vec::new();
+    for row in rows {
+        let (time, url, title, target_path) = row?;
+        let Some(timestamp) = time.and_then(format_time) else {
+            continue;
+        };
+        events.push(ArtifactEvent {
+            timestamp,
+            kind,
+            url: url.unwrap_or_default(),
+            title: title.unwrap_or_default(),
+            target_path: target_path.unwrap_or_default(),
+            source_path: source_path.to_string(),
+        });
+    }
+    Ok(events)
+}
+
+fn chrome_events(conn: &Connection, path: &str) -> rusqlite::Result<Vec<ArtifactEvent>> {
+    let mut events = query_events(
+        conn,
+        "SELECT visits.visit_time, urls.url, urls.title, NULL
+         FROM visits JOIN urls ON urls.id = visits.url",
+        ArtifactEventKind::Visit,
+        format_webkit_micros,
+        path,
+    )?;
+    if has_table(conn, "downloads") {
+        events.extend(query_events(
+            conn,
+            "SELECT start_time, tab_url, NULL, target_path FROM downloads",
+            ArtifactEventKind::Download,
+            format_webkit_micros,
+            path,
+        )?);
+    }
+    Ok(events)
+}
+
+fn firefox_events(conn: &Connection, path: &str) -> rusqlite::Result<Vec<ArtifactEvent>> {
+    let mut events = query_events(
+        conn,
+        "SELECT moz_historyvisits.visit_date, moz_places.url, moz_places.title, NULL
+         FROM moz_historyvisits JOIN moz_places ON moz_places.id = moz_historyvisits.place_id",
+        ArtifactEventKind::Visit,
+        format_unix_micros,
+        path,
+    )?;
+    // Firefox keeps download destinations as page annotations
+    if has_table(conn, "moz_annos") && has_table(conn, "moz_anno_attributes") {
+        events.extend(query_events(
+            conn,
+            "SELECT moz_annos.dateAdded, moz_places.url, moz_places.title, moz_annos.content
+             FROM moz_annos
+             JOIN moz_anno_attributes ON moz_anno_attributes.id = moz_annos.anno_attribute_id
+             JOIN moz_places ON moz_places.id = moz_annos.place_id
+             WHERE moz_anno_attributes.name = 'downloads/destinationFileURI'",
+            ArtifactEventKind::Download,
+            format_unix_micros,
+            path,
+        )?);
+    }
+    Ok(events)
+}
+
+/// Parses a Chrome or Firefox history database. Returns None for SQLite
+/// files that are not browser history, and for non-SQLite files.
+pub fn parse_browser_history(path: &str) -> Option<BrowserArtifact> {
+    if !has_sqlite_header(path) {
+        return None;
+    }
+    let conn = match open_read_only(path) {
+        Ok(conn) => conn,
+        Err(e) => {
+            return Some(BrowserArtifact {
+                path: path.to_string(),
+                browser: None,
+                events: Vec::new(),
+                error: Some(e.to_string()),
+            })
+        }
+    };
+
+    let browser = detect_browser(&conn)?;
+    let result = match browser {
+        Browser::Chrome => chrome_events(&conn, path),
+        Browser::Firefox => firefox_events(&conn, path),
+    };
+    let (mut events, error) = match result {
+        Ok(events) => (events, None),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+    events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+    Some(BrowserArtifact {
+        path: path.to_string(),
+        browser: Some(browser),
+        events,
+        error,
+    })
+}
+
+/// Size of the fixed Shell Link header; also its first four bytes
+const SHELL_LINK_HEADER_SIZE: usize = 0x4C;
+
+/// Shortcuts are a few KB; anything larger is not a shell link
+const MAX_SHORTCUT_BYTES: u64 = 1024 * 1024;
+
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x01;
+const HAS_LINK_INFO: u32 = 0x02;
+const HAS_NAME: u32 = 0x04;
+const HAS_RELATIVE_PATH: u32 = 0x08;
+const HAS_WORKING_DIR: u32 = 0x10;
+const HAS_ARGUMENTS: u32 = 0x20;
+const IS_UNICODE: u32 = 0x80;
+
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x01;
+const COMMON_NETWORK_RELATIVE_LINK: u32 = 0x02;
+
+/// Volume the shortcut target lived on when the link was created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutVolume {
+    /// e.g. "fixed", "removable", "network"
+    pub drive_type: String,
+    /// Hex, as shown by `vol`
+    pub serial_number: String,
+    pub label: String,
+}
+
+/// What a Windows shortcut (.lnk) points at, with the target's timestamps as
+/// recorded when the link was last updated
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShortcutInfo {
+    pub path: String,
+    pub target_path: String,
+    /// UNC share when the target was on a network drive
+    pub network_share: String,
+    pub volume: Option<ShortcutVolume>,
+    /// UTC, `YYYY-MM-DD HH:MM:SS`
+    pub target_created: Option<String>,
+    pub target_accessed: Option<String>,
+    pub target_modified: Option<String>,
+    pub target_size: u32,
+    pub description: String,
+    pub relative_path: String,
+    pub working_dir: String,
+    pub arguments: String,
+    pub error: Option<String>,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8).map(|b| {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(b);
+        u64::from_le_bytes(bytes)
+    })
+}
+
+/// Null-terminated single-byte string; shortcuts use the system code page,
+/// which is read as Latin-1
+fn read_ansi_z(data: &[u8], offset: usize) -> String {
+    data.get(offset..)
+        .unwrap_or_default()
+        .iter()
+        .take_while(|b| **b != 0)
+        .map(|b| *b as char)
+        .collect()
+}
+
+fn read_unicode_z(data: &[u8], offset: usize) -> String {
+    let units: Vec<u16> = data
+        .get(offset..)
+        .unwrap_or_default()
+        .chunks_exact(2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// FILETIME (100 ns ticks since 1601) as UTC; zero means "not set"
+fn format_filetime(ticks: u64) -> Option<String> {
+    if ticks == 0 {
+        return None;
+    }
+    format_unix_micros((ticks / 10) as i64 - WEBKIT_EPOCH_OFFSET_SECONDS * 1_000_000)
+}
+
+fn drive_type_name(drive_type: u32) -> &'static str {
+    match drive_type {
+        1 => "no root",
+        2 => "removable",
+        3 => "fixed",
+        4 => "network",
+        5 => "cdrom",
+        6 => "ramdisk",
+        _ => "unknown",
+    }
+}
+
+fn parse_volume_id(data: &[u8], offset: usize) -> Option<ShortcutVolume> {
+    let drive_type = read_u32(data, offset + 4)?;
+    let serial = read_u32(data, offset + 8)?;
+    let label_offset = read_u32(data, offset + 12)? as usize;
+    // An offset of 0x14 means the label is stored as Unicode at a second offset
+    let label = if label_offset == 0x14 {
+        read_unicode_z(data, offset + read_u32(data, offset + 16)? as usize)
+    } else {
+        read_ansi_z(data, offset + label_offset)
+    };
+    Some(ShortcutVolume {
+        drive_type: drive_type_name(drive_type).to_string(),
+        serial_number: format!("{:04X}-{:04X}", serial >> 16, serial & 0xFFFF),
+        label,
+    })
+}
+
+/// Fills target path, share and volume from the LinkInfo structure
+fn parse_link_info(data: &[u8], start: usize, info: &mut ShortcutInfo) -> Option<()> {
+    let header_size = read_u32(data, start + 4)? as usize;
+    let flags = read_u32(data, start + 8)?;
+    let volume_offset = read_u32(data, start + 12)? as usize;
+    let local_base_offset = read_u32(data, start + 16)? as usize;
+    let network_offset = read_u32(data, start + 20)? as usize;
+    let suffix_offset = read_u32(data, start + 24)? as usize;
+    // Headers of 0x24 bytes or more add Unicode copies of the two paths
+    let unicode = header_size >= 0x24;
+
+    let suffix = if unicode && read_u32(data, start + 32)? != 0 {
+        read_unicode_z(data, start + read_u32(data, start + 32)? as usize)
+    } else {
+        read_ansi_z(data, start + suffix_offset)
+    };
+
+    if flags & VOLUME_ID_AND_LOCAL_BASE_PATH != 0 {
+        info.volume = parse_volume_id(data, start + volume_offset);
+        let base = if unicode && read_u32(data, start + 28)? != 0 {
+            read_unicode_z(data, start + read_u32(data, start + 28)? as usize)
+        } else {
+            read_ansi_z(data, start + local_base_offset)
+        };
+        info.target_path = format!("{}{}", base, suffix);
+    }
+    if flags & COMMON_NETWORK_RELATIVE_LINK != 0 {
+        let link = start + network_offset;
+        info.network_share = read_ansi_z(data, link + read_u32(data, link + 8)? as usize);
+        if info.target_path.is_empty() {
+            info.target_path = if suffix.is_empty() {
+                info.network_share.clone()
+            } else {
+                format!("{}\\{}", info.network_share.trim_end_matches('\\'), suffix)
+            };
+        }
+    }
+    Some(())
+}
+
+/// Reads one counted StringData entry; returns the string and the offset after it
+fn read_string_data(data: &[u8], offset: usize, unicode: bool) -> Option<(String, usize)> {
+    let count = read_u16(data, offset)? as usize;
+    let start = offset + 2;
+    if unicode {
+        let bytes = data.get(start..start + count * 2)?;
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect();
+        Some((String::from_utf16_lossy(&units), start + count * 2))
+    } else {
+        let bytes = data.get(start..start + count)?;
+        Some((bytes.iter().map(|b| *b as char).collect(), start + count))
+    }
+}
+
+fn parse_shell_link(data: &[u8], info: &mut ShortcutInfo) -> Result<(), String> {
+    let truncated = || "Shortcut is truncated".to_string();
+    if data.len() < SHELL_LINK_HEADER_SIZE || read_u32(data, 0) != Some(SHELL_LINK_HEADER_SIZE as u32) {
+        return Err("Not a Windows shortcut".to_string());
+    }
+    let flags = read_u32(data, 20).ok_or_else(truncated)?;
+    info.target_created = format_filetime(read_u64(data, 28).ok_or_else(truncated)?);
+    info.target_accessed = format_filetime(read_u64(data, 36).ok_or_else(truncated)?);
+    info.target_modified = format_filetime(read_u64(data, 44).ok_or_else(truncated)?);
+    info.target_size = read_u32(data, 52).ok_or_else(truncated)?;
+
+    let mut offset = SHELL_LINK_HEADER_SIZE;
+    if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        offset += 2 + read_u16(data, offset).ok_or_else(truncated)? as usize;
+    }
+    if flags & HAS_LINK_INFO != 0 {
+        let size = read_u32(data, offset).ok_or_else(truncated)? as usize;
+        parse_link_info(data, offset, info).ok_or_else(truncated)?;
+        offset += size;
+    }
+
+    let unicode = flags & IS_UNICODE != 0;
+    let strings: [(u32, &mut String); 4] = [
+        (HAS_NAME, &mut info.description),
+        (HAS_RELATIVE_PATH, &mut info.relative_path),
+        (HAS_WORKING_DIR, &mut info.working_dir),
+        (HAS_ARGUMENTS, &mut info.arguments),
+    ];
+    for (flag, target) in strings {
+        if flags & flag != 0 {
+            let (value, next) = read_string_data(data, offset, unicode).ok_or_else(truncated)?;
+            *target = value;
+            offset = next;
+        }
+    }
+    Ok(())
+}
+
+/// Parses a Windows shortcut. Returns None for files without a .lnk extension.
+pub fn parse_shortcut(path: &str) -> Option<ShortcutInfo> {
+    let is_lnk = std::path::Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"));
+    if !is_lnk {
+        return None;
+    }
+
+    let mut info = ShortcutInfo {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let result = std::fs::metadata(path)
+        .map_err(|e| e.to_string())
+        .and_then(|metadata| {
+            if metadata.len() > MAX_SHORTCUT_BYTES {
+                Err("Not a Windows shortcut".to_string())
+            } else {
+                std::fs::read(path).map_err(|e| e.to_string())
+            }
+        })
+        .and_then(|data| parse_shell_link(&data, &mut info));
+    if let Err(e) = result {
+        info.error = Some(e);
+    }
+    Some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2025-01-01 00:00:00 UTC in Unix microseconds
+    const NEW_YEAR_MICROS: i64 = 1_735_689_600_000_000;
+
+    fn history_db(name: &str, sql: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{}-{}.sqlite", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path).unwrap().execute_batch(sql).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn parses_chrome_visits_and_downloads_oldest_first() {
+        let webkit = NEW_YEAR_MICROS + WEBKIT_EPOCH_OFFSET_SECONDS * 1_000_000;
+        let path = history_db(
+            "chrome-history",
+            &format!(
+                "CREATE TABLE urls (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+                 CREATE TABLE visits (id INTEGER PRIMARY KEY, url INTEGER, visit_time INTEGER);
+                 CREATE TABLE downloads (id INTEGER PRIMARY KEY, start_time INTEGER, tab_url TEXT, target_path TEXT);
+                 INSERT INTO urls VALUES (1, 'https://bank.example/statements', 'Statements');
+                 INSERT INTO visits VALUES (1, 1, {later}), (2, 1, {webkit}), (3, 1, 0);
+                 INSERT INTO downloads VALUES (1, {download}, 'https://bank.example/jan.pdf', 'C:\\Downloads\\jan.pdf');",
+                later = webkit + 3_600_000_000,
+                download = webkit + 60_000_000,
+            ),
+        );
+
+        let artifact = parse_browser_history(&path).unwrap();
+        assert_eq!(artifact.browser, Some(Browser::Chrome));
+        assert_eq!(artifact.error, None);
+        let events: Vec<(&str, ArtifactEventKind, &str)> = artifact
+            .events
+            .iter()
+            .map(|event| (event.timestamp.as_str(), event.kind, event.target_path.as_str()))
+            .collect();
+        assert_eq!(
+            events,
+            [
+                ("2025-01-01 00:00:00", ArtifactEventKind::Visit, ""),
+                ("2025-01-01 00:01:00", ArtifactEventKind::Download, "C:\\Downloads\\jan.pdf"),
+                ("2025-01-01 01:00:00", ArtifactEventKind::Visit, ""),
+            ]
+        );
+        assert_eq!(artifact.events[0].title, "Statements");
+        assert_eq!(artifact.events[0].source_path, path);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn parses_firefox_visits_and_downloads() {
+        let path = history_db(
+            "firefox-history",
+            &format!(
+                "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+                 CREATE TABLE moz_historyvisits (id INTEGER PRIMARY KEY, place_id INTEGER, visit_date INTEGER);
+                 CREATE TABLE moz_anno_attributes (id INTEGER PRIMARY KEY, name TEXT);
+                 CREATE TABLE moz_annos (id INTEGER PRIMARY KEY, place_id INTEGER, anno_attribute_id INTEGER, content TEXT, dateAdded INTEGER);
+                 INSERT INTO moz_places VALUES (1, 'https://bank.example/jan.pdf', NULL);
+                 INSERT INTO moz_historyvisits VALUES (1, 1, {visit});
+                 INSERT INTO moz_anno_attributes VALUES (1, 'downloads/destinationFileURI'), (2, 'other');
+                 INSERT INTO moz_annos VALUES (1, 1, 1, 'file:///home/a/jan.pdf', {visit}), (2, 1, 2, 'ignored', {visit});",
+                visit = NEW_YEAR_MICROS,
+            ),
+        );
+
+        let artifact = parse_browser_history(&path).unwrap();
+        assert_eq!(artifact.browser, Some(Browser::Firefox));
+        assert_eq!(artifact.events.len(), 2);
+        assert!(artifact.events.iter().all(|event| event.timestamp == "2025-01-01 00:00:00" && event.title.is_empty()));
+        let download = artifact.events.iter().find(|event| event.kind == ArtifactEventKind::Download).unwrap();
+        assert_eq!(download.target_path, "file:///home/a/jan.pdf");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn other_databases_are_not_browser_history() {
+        let path = history_db("not-history", "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT);");
+        assert!(parse_browser_history(&path).is_none());
+        std::fs::write(&path, "not a database").unwrap();
+        assert!(parse_browser_history(&path).is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// 2025-01-01 00:00:00 UTC as a FILETIME
+    const MODIFIED_TICKS: u64 = 133_801_632_000_000_000;
+
+    fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn unicode_string_data(value: &str) -> Vec<u8> {
+        let units: Vec<u16> = value.encode_utf16().collect();
+        let mut out = (units.len() as u16).to_le_bytes().to_vec();
+        out.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+        out
+    }
+
+    /// LinkInfo for a local target: volume "DATA", base `C:\Docs\`, suffix `a.txt`
+    fn link_info() -> Vec<u8> {
+        let mut volume = vec![0u8; 16];
+        put_u32(&mut volume, 4, 3);
+        put_u32(&mut volume, 8, 0x1234_abcd);
+        put_u32(&mut volume, 12, 16);
+        volume.extend(b"DATA\0");
+
+        let mut info = vec![0u8; 28];
+        let volume_offset = info.len();
+        info.extend(&volume);
+        let base_offset = info.len();
+        info.extend(b"C:\\Docs\\\0");
+        let suffix_offset = info.len();
+        info.extend(b"a.txt\0");
+
+        let size = info.len() as u32;
+        put_u32(&mut info, 0, size);
+        put_u32(&mut info, 4, 28);
+        put_u32(&mut info, 8, VOLUME_ID_AND_LOCAL_BASE_PATH);
+        put_u32(&mut info, 12, volume_offset as u32);
+        put_u32(&mut info, 16, base_offset as u32);
+        put_u32(&mut info, 24, suffix_offset as u32);
+        info
+    }
+
+    fn shortcut() -> Vec<u8> {
+        let mut data = vec![0u8; SHELL_LINK_HEADER_SIZE];
+        put_u32(&mut data, 0, SHELL_LINK_HEADER_SIZE as u32);
+        put_u32(&mut data, 20, HAS_LINK_INFO | HAS_NAME | HAS_ARGUMENTS | IS_UNICODE);
+        data[44..52].copy_from_slice(&MODIFIED_TICKS.to_le_bytes());
+        put_u32(&mut data, 52, 4096);
+        data.extend(link_info());
+        data.extend(unicode_string_data("Report"));
+        data.extend(unicode_string_data("/view"));
+        data
+    }
+
+    #[test]
+    fn parses_a_local_shortcut() {
+        let mut info = ShortcutInfo::default();
+        parse_shell_link(&shortcut(), &mut info).unwrap();
+        assert_eq!(info.target_path, "C:\\Docs\\a.txt");
+        assert_eq!(info.description, "Report");
+        assert_eq!(info.arguments, "/view");
+        assert_eq!(info.target_size, 4096);
+        assert_eq!(info.target_created, None);
+        assert_eq!(info.target_modified.as_deref(), Some("2025-01-01 00:00:00"));
+        let volume = info.volume.unwrap();
+        assert_eq!(volume.drive_type, "fixed");
+        assert_eq!(volume.serial_number, "1234-ABCD");
+        assert_eq!(volume.label, "DATA");
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_shortcuts() {
+        let mut wrong_size = shortcut();
+        put_u32(&mut wrong_size, 0, 0x50);
+        let cases: &[(&str, &[u8])] = &[
+            ("empty", &[]),
+            ("short header", &[0x4c, 0, 0, 0]),
+            ("wrong header size", &wrong_size),
+            ("text", &[b'x'; SHELL_LINK_HEADER_SIZE]),
+        ];
+        for (name, data) in cases {
+            let mut info = ShortcutInfo::default();
+            assert_eq!(
+                parse_shell_link(data, &mut info),
+                Err("Not a Windows shortcut".to_string()),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_shortcuts() {
+        let data = shortcut();
+        for len in SHELL_LINK_HEADER_SIZE..data.len() {
+            let mut info = ShortcutInfo::default();
+            assert!(parse_shell_link(&data[..len], &mut info).is_err(), "truncated to {} bytes", len);
+        }
+    }
+
+    #[test]
+    fn survives_out_of_range_offsets() {
+        let mut data = shortcut();
+        let start = SHELL_LINK_HEADER_SIZE;
+        for field in [12, 16, 24] {
+            put_u32(&mut data, start + field, u32::MAX);
+        }
+        let mut info = ShortcutInfo::default();
+        parse_shell_link(&data, &mut info).unwrap();
+        assert!(info.volume.is_none());
+        assert_eq!(info.target_path, "");
+
+        // An ID list or LinkInfo larger than the file runs the strings off the end
+        for flags in [HAS_LINK_TARGET_ID_LIST | HAS_NAME, HAS_LINK_INFO | HAS_NAME] {
+            let mut data = shortcut();
+            put_u32(&mut data, 20, flags);
+            put_u32(&mut data, start, u32::MAX);
+            let mut info = ShortcutInfo::default();
+            assert_eq!(parse_shell_link(&data, &mut info), Err("Shortcut is truncated".to_string()));
+        }
+    }
+
+    /// (data, unicode, expected string and next offset)
+    type StringDataCase = (&'static [u8], bool, Option<(&'static str, usize)>);
+
+    #[test]
+    fn read_string_data_checks_the_count() {
+        let cases: &[StringDataCase] = &[
+            (&[], false, None),
+            (&[0x01], false, None),
+            (&[0x00, 0x00], false, Some(("", 2))),
+            (&[0x02, 0x00, b'o', b'k'], false, Some(("ok", 4))),
+            (&[0x03, 0x00, b'o', b'k'], false, None),
+            (&[0x01, 0x00, b'o', 0x00], true, Some(("o", 4))),
+            (&[0x02, 0x00, b'o', 0x00, b'k'], true, None),
+            (&[0xff, 0xff, b'o', 0x00], true, None),
+        ];
+        for (data, unicode, expected) in cases {
+            let expected = expected.map(|(value, next)| (value.to_string(), next));
+            assert_eq!(read_string_data(data, 0, *unicode), expected, "{:?}", data);
+        }
+    }
+}
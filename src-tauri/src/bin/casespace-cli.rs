@@ -0,0 +1,177 @@
+//! Headless companion to the desktop app for scripted scans, exports and reports.
+//!
+//! Builds without the desktop feature, so it runs on servers without GTK or
+//! webkit: `cargo build --bin casespace-cli --no-default-features`.
+//!
+//! Usage:
+//!   casespace-cli scan <folder>
+//!   casespace-cli ingest <file>... [--copy-to <folder>]
+//!   casespace-cli export <folder> <output> [--format xlsx|csv|json|sqlite] [--case <number>]
+//!   casespace-cli verify-hashes <folder> <manifest> [--lookup]
+//!   casespace-cli report <folder> <output> [--case <number>]
+
+use inventory_generator_lib::{
+    export_items, generate_report_file, ingest_file, lookup_manifest_hashes, reconcile_manifest_file,
+    scan_directory_items, ExportOptions, ReportTemplate,
+};
+use serde::Serialize;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage:
+  casespace-cli scan <folder>
+  casespace-cli ingest <file>... [--copy-to <folder>]
+  casespace-cli export <folder> <output> [--format xlsx|csv|json|sqlite] [--case <number>]
+  casespace-cli verify-hashes <folder> <manifest> [--lookup]
+  casespace-cli report <folder> <output> [--case <number>]";
+
+/// Positional arguments plus the option values
+struct Args {
+    positional: Vec<String>,
+    format: Option<String>,
+    case_number: Option<String>,
+    copy_to: Option<String>,
+    /// Find the manifest's hashes anywhere in the folder instead of at their listed paths
+    lookup: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+        positional: Vec::new(),
+        format: None,
+        case_number: None,
+        copy_to: None,
+        lookup: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => parsed.format = Some(args.next().ok_or("--format requires a value")?),
+            "--case" => parsed.case_number = Some(args.next().ok_or("--case requires a value")?),
+            "--copy-to" => parsed.copy_to = Some(args.next().ok_or("--copy-to requires a value")?),
+            "--lookup" => parsed.lookup = true,
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            _ => parsed.positional.push(arg),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Infers the export format from the output extension when `--format` is not given
+fn detect_format(output_path: &str) -> String {
+    std::path::Path::new(output_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_else(|| "xlsx".to_string())
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn run(command: &str, args: Args) -> Result<(), String> {
+    match (command, args.positional.as_slice()) {
+        ("scan", [folder]) => print_json(&scan_directory_items(folder.clone())?),
+        ("ingest", files) if !files.is_empty() => {
+            // Keep going past a bad file so one failure doesn't stop a nightly batch
+            let mut ingested = Vec::new();
+            let mut failed = 0;
+            for file in files {
+                match ingest_file(file, args.copy_to.as_deref(), None) {
+                    Ok(result) => ingested.push(result),
+                    Err(e) => {
+                        eprintln!("{}: {}", file, e);
+                        failed += 1;
+                    }
+                }
+            }
+            print_json(&ingested)?;
+            if failed > 0 {
+                return Err(format!("{} of {} files could not be ingested", failed, files.len()));
+            }
+            Ok(())
+        }
+        ("export", [folder, output]) => {
+            let items = scan_directory_items(folder.clone())?;
+            let count = items.len();
+            let format = args.format.unwrap_or_else(|| detect_format(output));
+            export_items(
+                items,
+                &format,
+                output,
+                args.case_number.as_deref(),
+                Some(folder),
+                &ExportOptions::default(),
+            )?;
+            eprintln!("Exported {} items to {}", count, output);
+            Ok(())
+        }
+        ("verify-hashes", [folder, manifest]) if args.lookup => {
+            let paths: Vec<String> = scan_directory_items(folder.clone())?
+                .into_iter()
+                .map(|item| item.absolute_path)
+                .collect();
+            let lookup = lookup_manifest_hashes(&paths, Vec::new(), Some(manifest), None)?;
+            print_json(&lookup)?;
+            if !lookup.missing.is_empty() || !lookup.invalid.is_empty() {
+                return Err(format!(
+                    "{} hashes not found, {} invalid",
+                    lookup.missing.len(),
+                    lookup.invalid.len()
+                ));
+            }
+            Ok(())
+        }
+        ("verify-hashes", [folder, manifest]) => {
+            let reconciliation = reconcile_manifest_file(manifest, folder, None, Default::default())?;
+            print_json(&reconciliation)?;
+            let problems = reconciliation.different.len() + reconciliation.missing.len() + reconciliation.unreadable.len();
+            if problems > 0 {
+                return Err(format!(
+                    "{} of {} manifest entries did not verify ({} different, {} missing, {} unreadable)",
+                    problems,
+                    reconciliation.manifest_entries,
+                    reconciliation.different.len(),
+                    reconciliation.missing.len(),
+                    reconciliation.unreadable.len()
+                ));
+            }
+            eprintln!("Verified {} manifest entries", reconciliation.matched);
+            Ok(())
+        }
+        ("report", [folder, output]) => {
+            let items = scan_directory_items(folder.clone())?;
+            generate_report_file(
+                items,
+                &ReportTemplate::default(),
+                output,
+                args.case_number.as_deref(),
+                Some(folder),
+                &[],
+            )?;
+            eprintln!("Wrote report to {}", output);
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(command) = args.next() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let result = parse_args(args).and_then(|parsed| run(&command, parsed));
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
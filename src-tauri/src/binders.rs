@@ -0,0 +1,125 @@
+use crate::json_store;
+use crate::search::item_matches;
+use crate::stats::matches_filter;
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+const BINDERS_FILE: &str = "binders.json";
+
+/// A named, saved set of files ("binder") used to scope searches and exports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Binder {
+    pub name: String,
+    pub case_number: Option<String>,
+    /// Member files by absolute path, sorted
+    pub paths: BTreeSet<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+fn binders_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(BINDERS_FILE)
+}
+
+fn now() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Reads all saved binders; a missing file means none have been created
+pub fn read_binders(data_dir: &Path) -> Result<Vec<Binder>, Box<dyn std::error::Error>> {
+    json_store::read(&binders_path(data_dir))
+}
+
+fn same_binder(binder: &Binder, case_number: Option<&str>, name: &str) -> bool {
+    binder.case_number.as_deref() == case_number && binder.name.eq_ignore_ascii_case(name.trim())
+}
+
+/// Binders for one case (or those without a case), sorted by name
+pub fn list_binders(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<Binder>, Box<dyn std::error::Error>> {
+    let mut binders: Vec<Binder> = read_binders(data_dir)?
+        .into_iter()
+        .filter(|binder| binder.case_number.as_deref() == case_number)
+        .collect();
+    binders.sort_by_key(|binder| binder.name.to_lowercase());
+    Ok(binders)
+}
+
+pub fn find_binder(data_dir: &Path, case_number: Option<&str>, name: &str) -> Result<Option<Binder>, Box<dyn std::error::Error>> {
+    Ok(read_binders(data_dir)?
+        .into_iter()
+        .find(|binder| same_binder(binder, case_number, name)))
+}
+
+/// Adds paths to a binder, creating it if needed; returns the updated binder
+pub fn add_to_binder(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    name: &str,
+    paths: &[String],
+) -> Result<Binder, Box<dyn std::error::Error>> {
+    json_store::update(&binders_path(data_dir), |binders: &mut Vec<Binder>| {
+        let index = match binders.iter().position(|binder| same_binder(binder, case_number, name)) {
+            Some(index) => index,
+            None => {
+                binders.push(Binder {
+                    name: name.trim().to_string(),
+                    case_number: case_number.map(|s| s.to_string()),
+                    paths: BTreeSet::new(),
+                    created_at: now(),
+                    updated_at: now(),
+                });
+                binders.len() - 1
+            }
+        };
+        binders[index].paths.extend(paths.iter().cloned());
+        binders[index].updated_at = now();
+        binders[index].clone()
+    })
+}
+
+/// Removes paths from a binder; returns None if the binder does not exist
+pub fn remove_from_binder(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    name: &str,
+    paths: &[String],
+) -> Result<Option<Binder>, Box<dyn std::error::Error>> {
+    json_store::update(&binders_path(data_dir), |binders: &mut Vec<Binder>| {
+        let binder = binders.iter_mut().find(|binder| same_binder(binder, case_number, name))?;
+        for path in paths {
+            binder.paths.remove(path);
+        }
+        binder.updated_at = now();
+        Some(binder.clone())
+    })
+}
+
+/// Deletes a binder; returns whether it existed
+pub fn delete_binder(data_dir: &Path, case_number: Option<&str>, name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    json_store::update(&binders_path(data_dir), |binders: &mut Vec<Binder>| {
+        let before = binders.len();
+        binders.retain(|binder| !same_binder(binder, case_number, name));
+        binders.len() != before
+    })
+}
+
+/// Keeps only the items that belong to the binder, preserving order
+pub fn items_in_binder(items: Vec<InventoryItem>, binder: &Binder) -> Vec<InventoryItem> {
+    items
+        .into_iter()
+        .filter(|item| binder.paths.contains(&item.absolute_path))
+        .collect()
+}
+
+/// Paths of items matching every exact-match filter and, if given, the query
+pub fn matching_paths(items: &[InventoryItem], filter: &HashMap<String, String>, query: Option<&str>) -> Vec<String> {
+    let query = query.map(|q| q.trim().to_lowercase()).filter(|q| !q.is_empty());
+    items
+        .iter()
+        .filter(|item| matches_filter(item, filter))
+        .filter(|item| query.as_deref().is_none_or(|q| item_matches(item, q)))
+        .map(|item| item.absolute_path.clone())
+        .collect()
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use crate::hashing::{copy_hashed, verification_error};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use zip::read::ZipArchive;
+use zip::write::SimpleFileOptions;
+use zip::{AesMode, CompressionMethod, ZipWriter};
+
+/// How a case bundle is put together
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BundleOptions {
+    /// Format of the inventory inside the bundle: "xlsx", "csv", "json" or
+    /// "sqlite"
+    pub format: String,
+    /// Also pack the inventoried documents under `documents/`
+    pub include_documents: bool,
+    /// Encrypts every entry with AES-256 when set, in the WinZip AE-2 format
+    /// (PBKDF2 key stretching and an HMAC-SHA1 tag per entry) that 7-Zip and
+    /// WinZip open
+    pub passphrase: Option<String>,
+    /// Records the bundle as a production set of this name, so the case
+    /// knows which documents went out in it
+    pub production: Option<String>,
+    /// How the produced documents were chosen, kept with the production
+    pub filter: String,
+}
+
+/// One file to pack, under its name inside the bundle
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleEntry {
+    pub name: String,
+    pub source: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSummary {
+    pub output_path: String,
+    pub entries: usize,
+    pub encrypted: bool,
+    /// Entries read back from the finished zip that matched their sources
+    pub verified: usize,
+    /// Documents that could not be read and were left out
+    pub missing: Vec<String>,
+}
+
+/// What `write_bundle` packed
+#[derive(Debug, Clone, PartialEq)]
+pub struct WrittenBundle {
+    /// Entries read back and checked against their sources' hashes
+    pub verified: usize,
+    /// Sources that could not be opened and were left out
+    pub missing: Vec<String>,
+}
+
+/// A document's name inside the bundle: its inventory folder and file name
+/// under `documents/`, without components that could step outside it
+pub fn document_entry_name(folder_path: &str, file_name: &str) -> String {
+    let relative: Vec<String> = Path::new(folder_path)
+        .join(file_name)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+    format!("documents/{}", relative.join("/"))
+}
+
+/// Makes a name unique among those already used, e.g. `a (2).pdf`
+fn unique_name(name: &str, used: &mut HashSet<String>) -> String {
+    let mut candidate = name.to_string();
+    let (stem, extension) = match name.rfind('.').filter(|dot| !name[*dot..].contains('/')) {
+        Some(dot) => (&name[..dot], &name[dot..]),
+        None => (name, ""),
+    };
+    let mut counter = 2;
+    while !used.insert(candidate.to_lowercase()) {
+        candidate = format!("{} ({}){}", stem, counter, extension);
+        counter += 1;
+    }
+    candidate
+}
+
+/// Reads every entry back from the finished zip and checks it against the
+/// hash its source had while being packed
+fn verify_bundle(output: &Path, packed: &[(String, String)], passphrase: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let mut archive = ZipArchive::new(File::open(output)?)?;
+    for (name, expected) in packed {
+        let entry = match passphrase {
+            Some(passphrase) => archive.by_name_decrypt(name, passphrase.as_bytes())?,
+            None => archive.by_name(name)?,
+        };
+        let found = copy_hashed(entry, &mut io::sink())?;
+        if &found != expected {
+            return Err(verification_error(name, expected, &found).into());
+        }
+    }
+    Ok(())
+}
+
+/// Writes the entries to a zip at `output`, encrypted with the passphrase
+/// if one is given, then reads each entry back to check it against its
+/// source. Entries whose source cannot be opened are left out and returned;
+/// any other failure, a mismatch included, removes the zip.
+pub fn write_bundle(output: &Path, entries: &[BundleEntry], passphrase: Option<&str>) -> Result<WrittenBundle, Box<dyn std::error::Error>> {
+    let result = (|| -> Result<WrittenBundle, Box<dyn std::error::Error>> {
+        let mut zip = ZipWriter::new(File::create(output)?);
+        let mut used = HashSet::new();
+        let mut missing = Vec::new();
+        let mut packed = Vec::new();
+        for entry in entries {
+            let Ok(mut source) = File::open(&entry.source) else {
+                missing.push(entry.source.to_string_lossy().to_string());
+                continue;
+            };
+            let size = source.metadata()?.len();
+            let options = SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .large_file(size >= u32::MAX as u64);
+            let options = match passphrase {
+                Some(passphrase) => options.with_aes_encryption(AesMode::Aes256, passphrase),
+                None => options,
+            };
+            let name = unique_name(&entry.name, &mut used);
+            zip.start_file(name.as_str(), options)?;
+            packed.push((name, copy_hashed(&mut source, &mut zip)?));
+        }
+        zip.finish()?;
+        verify_bundle(output, &packed, passphrase)?;
+        Ok(WrittenBundle { verified: packed.len(), missing })
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(output);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn entries_are_encrypted_and_named_inside_documents() {
+        let dir = std::env::temp_dir().join(format!("bundle-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.pdf"), "first").unwrap();
+        fs::write(dir.join("b.pdf"), "second").unwrap();
+
+        assert_eq!(document_entry_name("Bank/../../etc", "a.pdf"), "documents/Bank/etc/a.pdf");
+        let entries = [
+            BundleEntry { name: "documents/Bank/a.pdf".to_string(), source: dir.join("a.pdf") },
+            BundleEntry { name: "documents/Bank/a.pdf".to_string(), source: dir.join("b.pdf") },
+            BundleEntry { name: "documents/gone.pdf".to_string(), source: dir.join("gone.pdf") },
+        ];
+        let output = dir.join("bundle.zip");
+        let written = write_bundle(&output, &entries, Some("s3cret")).unwrap();
+        assert_eq!(written.verified, 2);
+        assert_eq!(written.missing, [dir.join("gone.pdf").to_string_lossy().to_string()]);
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert!(names.contains(&"documents/Bank/a (2).pdf"));
+        assert!(archive.by_name_decrypt("documents/Bank/a.pdf", b"wrong").is_err());
+        let mut content = String::new();
+        archive
+            .by_name_decrypt("documents/Bank/a (2).pdf", b"s3cret")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "second");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,76 @@
+use crate::scanner::is_cloud_placeholder;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Broad file categories used for grouping, filtering and icons
+pub const FILE_CATEGORIES: &[&str] = &[
+    "Documents",
+    "Spreadsheets",
+    "Email",
+    "Images",
+    "AV",
+    "Archives",
+    "Databases",
+    "Other",
+];
+
+const OTHER: &str = "Other";
+
+/// Category for a file type (extension, any case, with or without a leading
+/// dot). Returns "Other" for unknown types.
+pub fn category_for_type(file_type: &str) -> &'static str {
+    match file_type.trim_start_matches('.').to_lowercase().as_str() {
+        "pdf" | "doc" | "docx" | "docm" | "rtf" | "txt" | "odt" | "wpd" | "pages" | "md" | "htm" | "html"
+        | "xml" | "ppt" | "pptx" | "odp" | "key" => "Documents",
+        "xls" | "xlsx" | "xlsm" | "xlsb" | "csv" | "tsv" | "ods" | "numbers" => "Spreadsheets",
+        "eml" | "emlx" | "msg" | "mbox" | "pst" | "ost" => "Email",
+        "jpg" | "jpeg" | "png" | "gif" | "bmp" | "tif" | "tiff" | "heic" | "webp" | "svg" | "cr2" | "nef"
+        | "dng" => "Images",
+        "mp3" | "wav" | "m4a" | "aac" | "flac" | "wma" | "ogg" | "mp4" | "m4v" | "mov" | "avi" | "mkv"
+        | "wmv" | "webm" | "3gp" => "AV",
+        "zip" | "rar" | "7z" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "iso" => "Archives",
+        "db" | "sqlite" | "sqlite3" | "mdb" | "accdb" | "sql" | "dbf" => "Databases",
+        _ => OTHER,
+    }
+}
+
+/// Category from the file's leading bytes, for files with a missing or
+/// unrecognized extension
+fn category_from_signature(header: &[u8]) -> &'static str {
+    let starts = |magic: &[u8]| header.starts_with(magic);
+    if starts(b"%PDF") || starts(b"{\\rtf") || starts(&[0xD0, 0xCF, 0x11, 0xE0]) {
+        "Documents"
+    } else if starts(b"\x89PNG") || starts(&[0xFF, 0xD8, 0xFF]) || starts(b"GIF8") || starts(b"II*\0")
+        || starts(b"MM\0*")
+    {
+        "Images"
+    } else if starts(b"ID3") || starts(b"OggS") || starts(b"fLaC") || starts(b"RIFF") || header.get(4..8) == Some(b"ftyp") {
+        "AV"
+    } else if starts(b"PK\x03\x04") || starts(b"Rar!") || starts(&[0x37, 0x7A, 0xBC, 0xAF]) || starts(&[0x1F, 0x8B]) {
+        "Archives"
+    } else if starts(b"SQLite format 3\0") || starts(b"!BDN") {
+        "Databases"
+    } else if starts(b"Return-Path:") || starts(b"Received:") || starts(b"From ") || starts(b"MIME-Version:") {
+        "Email"
+    } else {
+        OTHER
+    }
+}
+
+/// Category from the extension, falling back to the file signature when the
+/// extension is unknown. Cloud placeholders are never opened, since reading
+/// them would start a download.
+pub fn categorize(path: &Path, file_type: &str) -> &'static str {
+    let category = category_for_type(file_type);
+    if category != OTHER {
+        return category;
+    }
+    if path.metadata().map_or(true, |metadata| is_cloud_placeholder(&metadata)) {
+        return OTHER;
+    }
+
+    let mut header = [0u8; 16];
+    let read = File::open(path).and_then(|mut file| file.read(&mut header)).unwrap_or(0);
+    category_from_signature(&header[..read])
+}
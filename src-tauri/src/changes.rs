@@ -0,0 +1,123 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+/// Changes kept per case; a cursor older than these gets a full refresh
+const MAX_CHANGES_PER_CASE: usize = 1000;
+
+/// Kind of change announced by a change event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// One recorded change. Files are identified by absolute path (links by
+/// their source file), notes by id, binders, watches and rules by name.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub cursor: u64,
+    /// What changed: `files`, `notes`, `binders` and so on
+    pub entity: String,
+    pub change: ChangeKind,
+    pub ids: Vec<String>,
+}
+
+/// Changes after a cursor
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangesSince {
+    /// The case's latest cursor, to pass next time
+    pub cursor: u64,
+    /// False when changes after the given cursor are no longer kept, or the
+    /// cursor is from before the app started; the caller re-reads everything
+    pub complete: bool,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Default)]
+struct CaseFeed {
+    latest: u64,
+    changes: VecDeque<Change>,
+}
+
+/// Recent changes per case, in memory. Cursors count up from 0 each time
+/// the app starts.
+pub struct ChangeFeed {
+    cases: Mutex<BTreeMap<Option<String>, CaseFeed>>,
+}
+
+impl ChangeFeed {
+    pub const fn new() -> Self {
+        Self {
+            cases: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Records a change and returns its cursor
+    pub fn record(&self, case_number: Option<&str>, entity: &str, change: ChangeKind, ids: Vec<String>) -> u64 {
+        let mut cases = self.cases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let feed = cases.entry(case_number.map(|s| s.to_string())).or_default();
+        feed.latest += 1;
+        feed.changes.push_back(Change {
+            cursor: feed.latest,
+            entity: entity.to_string(),
+            change,
+            ids,
+        });
+        if feed.changes.len() > MAX_CHANGES_PER_CASE {
+            feed.changes.pop_front();
+        }
+        feed.latest
+    }
+
+    /// The case's changes after `cursor`, oldest first
+    pub fn since(&self, case_number: Option<&str>, cursor: u64) -> ChangesSince {
+        let cases = self.cases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(feed) = cases.get(&case_number.map(|s| s.to_string())) else {
+            return ChangesSince { cursor: 0, complete: cursor == 0, changes: Vec::new() };
+        };
+        let oldest_kept = feed.changes.front().map_or(feed.latest + 1, |change| change.cursor);
+        ChangesSince {
+            cursor: feed.latest,
+            complete: cursor <= feed.latest && cursor + 1 >= oldest_kept,
+            changes: feed.changes.iter().filter(|change| change.cursor > cursor).cloned().collect(),
+        }
+    }
+}
+
+/// The entity named by a change event such as `case://notes-changed`
+pub fn entity_of(event: &str) -> &str {
+    event.trim_start_matches("case://").trim_end_matches("-changed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_are_returned_after_a_cursor_until_trimmed() {
+        let feed = ChangeFeed::new();
+        assert!(feed.since(Some("A-1"), 0).complete);
+
+        let first = feed.record(Some("A-1"), entity_of("case://files-changed"), ChangeKind::Created, vec!["/c/a.pdf".to_string()]);
+        feed.record(Some("B-2"), "notes", ChangeKind::Updated, vec!["7".to_string()]);
+        feed.record(Some("A-1"), "pins", ChangeKind::Deleted, vec!["/c/a.pdf".to_string()]);
+
+        let since = feed.since(Some("A-1"), first);
+        assert!(since.complete);
+        assert_eq!(since.cursor, 2);
+        assert_eq!(since.changes.len(), 1);
+        assert_eq!((since.changes[0].entity.as_str(), since.changes[0].change), ("pins", ChangeKind::Deleted));
+        assert_eq!(feed.since(Some("A-1"), 0).changes[0].entity, "files");
+        assert!(!feed.since(Some("A-1"), 9).complete);
+
+        for _ in 0..MAX_CHANGES_PER_CASE {
+            feed.record(Some("A-1"), "files", ChangeKind::Updated, vec!["/c/a.pdf".to_string()]);
+        }
+        assert!(!feed.since(Some("A-1"), first).complete);
+        assert!(feed.since(Some("A-1"), 2).complete);
+        assert_eq!(feed.since(None, 0).cursor, 0);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::hashing::{hash_file, HashAlgorithm};
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const CHECKOUTS_FILE: &str = "checkouts.json";
+
+/// A file opened for editing outside the app, with its content as it was
+/// when checked out
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Checkout {
+    pub case_number: Option<String>,
+    pub absolute_path: String,
+    /// SHA-256 of the content at checkout
+    pub hash: String,
+    pub size_bytes: u64,
+    pub checked_out_at: String,
+}
+
+/// Whether a checked-out file came back changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckinResult {
+    pub checkout: Checkout,
+    pub changed: bool,
+    pub new_hash: String,
+    pub new_size_bytes: u64,
+}
+
+fn checkouts_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(CHECKOUTS_FILE)
+}
+
+fn same_checkout(checkout: &Checkout, case_number: Option<&str>, path: &str) -> bool {
+    checkout.case_number.as_deref() == case_number && checkout.absolute_path == path
+}
+
+/// Files checked out in a case, oldest first
+pub fn case_checkouts(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<Checkout>, Box<dyn std::error::Error>> {
+    let checkouts: Vec<Checkout> = json_store::read(&checkouts_path(data_dir))?;
+    Ok(checkouts
+        .into_iter()
+        .filter(|checkout| checkout.case_number.as_deref() == case_number)
+        .collect())
+}
+
+/// Snapshots the file's hash and size. Checking out a file that is already
+/// checked out takes a new snapshot.
+pub fn check_out(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    path: &Path,
+    checked_out_at: &str,
+) -> Result<Checkout, Box<dyn std::error::Error>> {
+    let checkout = Checkout {
+        case_number: case_number.map(|s| s.to_string()),
+        absolute_path: path.to_string_lossy().to_string(),
+        hash: hash_file(path, HashAlgorithm::Sha256)?,
+        size_bytes: std::fs::metadata(path)?.len(),
+        checked_out_at: checked_out_at.to_string(),
+    };
+    json_store::update(&checkouts_path(data_dir), |all: &mut Vec<Checkout>| {
+        all.retain(|existing| !same_checkout(existing, case_number, &checkout.absolute_path));
+        all.push(checkout.clone());
+    })?;
+    Ok(checkout)
+}
+
+/// Compares the file with its checkout snapshot and ends the checkout.
+/// Returns `None` if the file is not checked out.
+pub fn check_in(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    path: &Path,
+) -> Result<Option<CheckinResult>, Box<dyn std::error::Error>> {
+    let key = path.to_string_lossy().to_string();
+    let Some(checkout) = case_checkouts(data_dir, case_number)?
+        .into_iter()
+        .find(|checkout| checkout.absolute_path == key)
+    else {
+        return Ok(None);
+    };
+
+    // Hash before removing the checkout so an unreadable file stays checked out
+    let new_hash = hash_file(path, HashAlgorithm::Sha256)?;
+    let new_size_bytes = std::fs::metadata(path)?.len();
+    json_store::update(&checkouts_path(data_dir), |all: &mut Vec<Checkout>| {
+        all.retain(|existing| !same_checkout(existing, case_number, &key));
+    })?;
+    Ok(Some(CheckinResult {
+        changed: new_hash != checkout.hash,
+        checkout,
+        new_hash,
+        new_size_bytes,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkin_detects_changes_and_ends_the_checkout() {
+        let dir = std::env::temp_dir().join(format!("checkouts-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("draft.txt");
+        std::fs::write(&file, "first").unwrap();
+
+        let case = Some("A-1");
+        check_out(&dir, case, &file, "2026-03-01 09:00:00").unwrap();
+        assert_eq!(case_checkouts(&dir, case).unwrap().len(), 1);
+        assert!(case_checkouts(&dir, Some("B-2")).unwrap().is_empty());
+        let unchanged = check_in(&dir, case, &file).unwrap().unwrap();
+        assert!(!unchanged.changed);
+        assert!(check_in(&dir, case, &file).unwrap().is_none());
+
+        check_out(&dir, case, &file, "2026-03-01 10:00:00").unwrap();
+        std::fs::write(&file, "second draft").unwrap();
+        let changed = check_in(&dir, case, &file).unwrap().unwrap();
+        assert!(changed.changed);
+        assert_eq!(changed.checkout.size_bytes, 5);
+        assert_eq!(changed.new_size_bytes, 12);
+        assert!(case_checkouts(&dir, case).unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,191 @@
+use crate::terms::{item_texts, tokenize};
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Terms reported per cluster to label it
+const LABEL_TERMS: usize = 3;
+
+/// A group of items whose text is similar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentCluster {
+    pub id: usize,
+    /// Highest-weighted terms of the cluster centroid, used as a label
+    pub top_terms: Vec<String>,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterReport {
+    /// Clusters with more than one member, largest first
+    pub clusters: Vec<DocumentCluster>,
+    /// Items with no text or nothing similar to them
+    pub unclustered: Vec<String>,
+}
+
+type Vector = HashMap<usize, f64>;
+
+fn normalize(vector: &mut Vector) {
+    let norm = vector.values().map(|w| w * w).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        vector.values_mut().for_each(|w| *w /= norm);
+    }
+}
+
+/// Cosine similarity of two unit vectors
+fn cosine(a: &Vector, b: &Vector) -> f64 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small.iter().filter_map(|(term, w)| large.get(term).map(|v| w * v)).sum()
+}
+
+/// TF-IDF vectors over the terms of each item, unit length
+fn tfidf_vectors(items: &[InventoryItem], vocabulary: &mut Vec<String>) -> Vec<Vector> {
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let counts: Vec<HashMap<usize, f64>> = items
+        .iter()
+        .map(|item| {
+            let mut counts = HashMap::new();
+            for token in item_texts(item).iter().flat_map(|text| tokenize(text)) {
+                let next = index.len();
+                let id = *index.entry(token.clone()).or_insert_with(|| {
+                    vocabulary.push(token);
+                    next
+                });
+                *counts.entry(id).or_insert(0.0) += 1.0;
+            }
+            counts
+        })
+        .collect();
+
+    let mut document_frequency = vec![0usize; vocabulary.len()];
+    for term in counts.iter().flat_map(|counts| counts.keys()) {
+        document_frequency[*term] += 1;
+    }
+
+    let total = items.len() as f64;
+    counts
+        .into_iter()
+        .map(|counts| {
+            let mut vector: Vector = counts
+                .into_iter()
+                .map(|(term, tf)| (term, tf * (1.0 + total / document_frequency[term] as f64).ln()))
+                .collect();
+            normalize(&mut vector);
+            vector
+        })
+        .collect()
+}
+
+/// Groups items by similarity of their file names, types, descriptions and
+/// notes. Single pass: each item joins the most similar existing cluster if
+/// its centroid is at least `threshold` similar, otherwise starts a new one.
+pub fn cluster_documents(items: &[InventoryItem], threshold: f64) -> ClusterReport {
+    let mut vocabulary = Vec::new();
+    let vectors = tfidf_vectors(items, &mut vocabulary);
+
+    // Centroids are kept as unnormalized sums alongside their unit form
+    let mut sums: Vec<Vector> = Vec::new();
+    let mut centroids: Vec<Vector> = Vec::new();
+    let mut members: Vec<Vec<usize>> = Vec::new();
+    let mut unclustered = Vec::new();
+
+    for (position, vector) in vectors.iter().enumerate() {
+        if vector.is_empty() {
+            unclustered.push(items[position].absolute_path.clone());
+            continue;
+        }
+        let best = centroids
+            .iter()
+            .enumerate()
+            .map(|(id, centroid)| (id, cosine(vector, centroid)))
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        let id = match best {
+            Some((id, _)) => id,
+            None => {
+                sums.push(Vector::new());
+                centroids.push(Vector::new());
+                members.push(Vec::new());
+                sums.len() - 1
+            }
+        };
+        for (term, weight) in vector {
+            *sums[id].entry(*term).or_insert(0.0) += weight;
+        }
+        let mut centroid = sums[id].clone();
+        normalize(&mut centroid);
+        centroids[id] = centroid;
+        members[id].push(position);
+    }
+
+    let mut clusters: Vec<DocumentCluster> = Vec::new();
+    for (id, positions) in members.into_iter().enumerate() {
+        if positions.len() < 2 {
+            unclustered.extend(positions.iter().map(|p| items[*p].absolute_path.clone()));
+            continue;
+        }
+        let mut weights: Vec<(&usize, &f64)> = sums[id].iter().collect();
+        weights.sort_by(|a, b| b.1.total_cmp(a.1).then(vocabulary[*a.0].cmp(&vocabulary[*b.0])));
+        clusters.push(DocumentCluster {
+            id,
+            top_terms: weights.iter().take(LABEL_TERMS).map(|(term, _)| vocabulary[**term].clone()).collect(),
+            paths: positions.iter().map(|p| items[*p].absolute_path.clone()).collect(),
+        });
+    }
+    clusters.sort_by(|a, b| b.paths.len().cmp(&a.paths.len()).then(a.id.cmp(&b.id)));
+    // Renumber so ids follow the reported order
+    for (id, cluster) in clusters.iter_mut().enumerate() {
+        cluster.id = id + 1;
+    }
+
+    ClusterReport { clusters, unclustered }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+
+    fn item(absolute_path: &str, document_description: &str) -> InventoryItem {
+        // Clustered on the description alone, without the file name's words
+        InventoryItem {
+            document_description: document_description.to_string(),
+            file_name: String::new(),
+            ..test_item(absolute_path)
+        }
+    }
+
+    #[test]
+    fn similar_items_cluster_and_the_rest_stay_apart() {
+        let items = vec![
+            item("/c/lease1.pdf", "Office lease agreement Main Street"),
+            item("/c/wire1.pdf", "Wire transfer escrow account"),
+            item("/c/lease2.pdf", "Lease agreement Main Street amendment"),
+            item("/c/wire2.pdf", "Wire transfer escrow refund"),
+            item("/c/lease3.pdf", "Main Street lease agreement renewal"),
+            item("/c/photo.jpg", "Birthday party"),
+            item("/c/blank.pdf", ""),
+        ];
+
+        let report = cluster_documents(&items, 0.3);
+        let clusters: Vec<(usize, Vec<&str>)> = report
+            .clusters
+            .iter()
+            .map(|cluster| (cluster.id, cluster.paths.iter().map(String::as_str).collect()))
+            .collect();
+        assert_eq!(
+            clusters,
+            [
+                (1, vec!["/c/lease1.pdf", "/c/lease2.pdf", "/c/lease3.pdf"]),
+                (2, vec!["/c/wire1.pdf", "/c/wire2.pdf"]),
+            ]
+        );
+        assert_eq!(report.clusters[0].top_terms.len(), LABEL_TERMS);
+        assert!(report.clusters[1].top_terms.iter().all(|term| ["wire", "transfer", "escrow"].contains(&term.as_str())));
+        assert_eq!(report.unclustered, ["/c/blank.pdf", "/c/photo.jpg"]);
+
+        // Nothing is similar enough to a threshold above 1
+        assert!(cluster_documents(&items, 1.1).clusters.is_empty());
+    }
+}
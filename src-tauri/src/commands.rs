@@ -0,0 +1,4278 @@
+//! Tauri commands and app setup for the desktop build. The scanning, export
+//! and hashing entry points they share with the CLI live in the crate root.
+
+use crate::{
+    export_items, generate_report_file, ingest_file, item_from_scan, items_to_rows,
+    lookup_manifest_hashes, reconcile_manifest_file, scan_directory_items, scan_directory_with_report,
+    sorted_extension_stats, validate_directory_path, validate_file_path, validate_scan_options, IngestedFile,
+    InventoryItem, ScanReport, INVENTORY_FIELDS,
+};
+use crate::{ExportOptions, ReportTemplate};
+use crate::report::SavedReportTemplate;
+use crate::links::{FileLink, FileLinks};
+use crate::query::{CaseQuery, QueryResult};
+use crate::access::CaseSource;
+use crate::history::{FileEvent, FileEventKind};
+use crate::lock::LockState;
+use crate::checkouts::{CheckinResult, Checkout};
+use crate::versions::FileVersion;
+use crate::notes::{FileNote, ListedNote, NoteTarget};
+use crate::recents::{RecentFile, ReviewStats};
+use crate::api::{ApiServer, InventoryLimits, PublishedInventories, PublishedUsage};
+use crate::tools::ExternalTool;
+use crate::schedules::{ExportRun, ExportSchedule};
+use crate::bundle::{BundleEntry, BundleOptions, BundleSummary};
+use crate::privilege::{FamilyMemberStatus, PrivilegeDesignation};
+use crate::productions::{Production, ProductionSummary};
+use crate::vault::VaultReport;
+use crate::health::HealthReport;
+use crate::configs::{ConfigEntries, ConfigKind, EffectiveConfig};
+use crate::changes::{ChangeFeed, ChangeKind, ChangesSince};
+use crate::{
+    access, activity, api, anomalies, artifacts, binders, bundle, categories, changes, checkouts, clusters, configs, consistency, dates, decisions, dedup, diagnostics,
+    diff, duplicates, export, finalize, hashing, health, history, json_store, keywords, links, lock, mappings, metrics, notes, organize, pins, privilege, productions, recents, preview, profiling, qc,
+    query, reextract, report, retry_queue, rules, schedules, search, stats, terms, timestamp, tools, validation, vault, versions, watches, workspace,
+};
+use crate::scanner::{scan_folder_streaming, scan_folder_with_options, rescan_folder, read_metadata_with_retry, count_files, FailedFile, ScanOptions, SmallFile};
+use crate::mappings::{PeriodKind, StatementPeriod, DEFAULT_PERIOD_KINDS};
+use crate::export::{CollisionPolicy, FilenameContext};
+use crate::export::{read_xlsx, read_csv, read_json};
+use crate::error::AppError;
+use crate::activity::ActivityEntry;
+use crate::retry_queue::QueuedFile;
+use crate::metrics::{MetricSample, OperationMetrics};
+use crate::stats::{FacetCount, HistogramBin, HistogramBucket};
+use crate::search::{FolderSearchResult, SearchAllOptions, SearchAllResults};
+use crate::diff::FileDiff;
+use crate::diagnostics::{AccessDiagnosis, SourceStatus};
+use crate::decisions::DecisionImportResult;
+use crate::rules::{AutomationRule, RuleAction, RuleRunResult};
+use crate::validation::{FieldRule, FinalizationCheck};
+use crate::qc::QcReport;
+use crate::binders::Binder;
+use crate::watches::{Watch, WatchHit};
+use crate::terms::TermFrequencies;
+use crate::clusters::ClusterReport;
+use crate::profiling::{DatabaseProfile, SpreadsheetProfile};
+use crate::artifacts::{BrowserArtifact, ShortcutInfo};
+use crate::duplicates::DuplicatesReport;
+use crate::dedup::DedupPolicy;
+use crate::reextract::{FieldChange, ReextractReport};
+use crate::anomalies::Anomaly;
+use crate::consistency::DateInconsistency;
+use crate::finalize::{CaseVerification, FinalizedCase};
+use crate::timestamp::TimestampReceipt;
+use crate::paths::{path_key, PathCase};
+use crate::keywords::{KeywordList, KeywordReport};
+use crate::hashing::{FileHash, HashAlgorithm, HashLookup, HashReport};
+use crate::manifest::ManifestReconciliation;
+use crate::dates::{ComputedDate, DateOffset};
+use crate::organize::{RenameReport, ReorganizationPlan, ReorganizationReport, ReorganizationRules, TransferMode};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::ipc::{Channel, Invoke, InvokeBody};
+use tauri::{AppHandle, Emitter, EventTarget, Manager, WebviewWindow};
+
+/// Counts the files a scan with `options` would read, leaving out ignored names
+#[tauri::command]
+fn count_directory_files(path: String, options: Option<ScanOptions>) -> Result<usize, String> {
+    let root_path = PathBuf::from(&path);
+    
+    if !root_path.exists() {
+        return Err(AppError::PathNotFound(path).to_string_message());
+    }
+    
+    if !root_path.is_dir() {
+        return Err(AppError::NotADirectory(path).to_string_message());
+    }
+    
+    count_files(&root_path, &options.unwrap_or_default())
+        .map_err(|e| AppError::ScanError(e.to_string()).to_string_message())
+}
+
+/// Adds entries to the case's file history. Like activity logging, failures
+/// are reported to stderr but never fail the command itself.
+fn record_file_events(app: &AppHandle, case_number: Option<&str>, events: Vec<FileEvent>) {
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        return;
+    };
+    if let Err(e) = history::append_events(&data_dir, case_number, &events) {
+        eprintln!("Error writing file history: {}", e);
+    }
+}
+
+/// Records the outcome of a command in the activity log. Logging failures are
+/// reported to stderr but never fail the command itself.
+fn record_activity<T>(
+    app: &AppHandle,
+    kind: &str,
+    case_number: Option<&str>,
+    result: &Result<T, String>,
+    describe: impl FnOnce(&T) -> String,
+) {
+    let Ok(log_dir) = app.path().app_data_dir() else {
+        return;
+    };
+    let entry = match result {
+        Ok(value) => ActivityEntry::new(kind, case_number, describe(value), true),
+        Err(e) => ActivityEntry::new(kind, case_number, e.clone(), false),
+    };
+    if let Err(e) = activity::append_activity(&log_dir, &entry) {
+        eprintln!("Error writing activity log: {}", e);
+    }
+}
+
+/// Events emitted after a command changes case data, so other windows and
+/// panels can refresh without polling
+const FILES_CHANGED_EVENT: &str = "case://files-changed";
+const BINDERS_CHANGED_EVENT: &str = "case://binders-changed";
+const PINS_CHANGED_EVENT: &str = "case://pins-changed";
+const WATCHES_CHANGED_EVENT: &str = "case://watches-changed";
+const RULES_CHANGED_EVENT: &str = "case://rules-changed";
+const LINKS_CHANGED_EVENT: &str = "case://links-changed";
+const NOTES_CHANGED_EVENT: &str = "case://notes-changed";
+const DEDUP_CHANGED_EVENT: &str = "case://dedup-changed";
+const PRIVILEGE_CHANGED_EVENT: &str = "case://privilege-changed";
+
+/// Minimal change event payload: listeners re-read what they need. Files are
+/// identified by absolute path (links by their source file), notes by id,
+/// binders, watches and rules by name.
+#[derive(Debug, Clone, Serialize)]
+struct ChangeEvent<'a> {
+    case_number: Option<&'a str>,
+    change: ChangeKind,
+    ids: Vec<String>,
+    /// Position of this change in the case's change feed
+    cursor: u64,
+}
+
+/// Every change announced since the app started, for windows that missed
+/// the events or refresh on their own schedule
+static CHANGE_FEED: ChangeFeed = ChangeFeed::new();
+
+/// Labels of windows opened for a single case start with this
+const CASE_WINDOW_PREFIX: &str = "case-";
+
+/// Labels only allow a few characters, so the case number is hex-encoded
+fn case_window_label(case_number: &str) -> String {
+    format!(
+        "{}{}",
+        CASE_WINDOW_PREFIX,
+        case_number.trim().bytes().map(|b| format!("{:02x}", b)).collect::<String>()
+    )
+}
+
+/// Case windows only see events for their own case, so a job running in one
+/// window never shows up in another. Other windows (the main window switches
+/// between cases) see every case's events.
+fn shows_case(target: &EventTarget, case_number: Option<&str>) -> bool {
+    let label = match target {
+        EventTarget::AnyLabel { label }
+        | EventTarget::Window { label }
+        | EventTarget::Webview { label }
+        | EventTarget::WebviewWindow { label } => label,
+        _ => return true,
+    };
+    !label.starts_with(CASE_WINDOW_PREFIX) || case_number.is_some_and(|case_number| case_window_label(case_number) == *label)
+}
+
+/// Emits an event to the windows showing `case_number`; like the activity
+/// log, failures go to stderr only
+fn emit_to_case<S: Serialize + Clone>(app: &AppHandle, event: &str, case_number: Option<&str>, payload: S) {
+    if let Err(e) = app.emit_filter(event, payload, |target| shows_case(target, case_number)) {
+        eprintln!("Error emitting {}: {}", event, e);
+    }
+}
+
+/// Records a change in the case's change feed and emits a change event to
+/// the windows showing the case
+fn notify_change(app: &AppHandle, event: &str, case_number: Option<&str>, change: ChangeKind, ids: Vec<String>) {
+    if ids.is_empty() {
+        return;
+    }
+    let cursor = CHANGE_FEED.record(case_number, changes::entity_of(event), change, ids.clone());
+    emit_to_case(app, event, case_number, ChangeEvent { case_number, change, ids, cursor });
+}
+
+/// The case's changes after `cursor` (0 for every change kept), so a window
+/// can refresh only what changed after a background sync. When the result is
+/// not complete the window re-reads everything.
+#[tauri::command]
+fn get_changes_since(case_number: Option<String>, cursor: u64) -> ChangesSince {
+    CHANGE_FEED.since(case_number.as_deref(), cursor)
+}
+
+/// Records how long an operation took in the local metrics file. Like the
+/// activity log, failures to write are reported to stderr only.
+fn record_metric<T>(
+    app: &AppHandle,
+    operation: &str,
+    case_number: Option<&str>,
+    started: Instant,
+    result: &Result<T, String>,
+    count_items: impl FnOnce(&T) -> usize,
+) {
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        return;
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let sample = match result {
+        Ok(value) => MetricSample::new(operation, case_number, duration_ms, count_items(value), true),
+        Err(_) => MetricSample::new(operation, case_number, duration_ms, 0, false),
+    };
+    if let Err(e) = metrics::append_sample(&data_dir, &sample) {
+        eprintln!("Error writing metrics: {}", e);
+    }
+}
+
+/// Timing summary per operation (scan, ingest, query, export, sync), read
+/// from the local metrics file; nothing is sent off the device
+#[tauri::command]
+fn get_performance_metrics(
+    app: AppHandle,
+    case_number: Option<String>,
+) -> Result<Vec<OperationMetrics>, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::MetricsError(e.to_string()).to_string_message())?;
+    
+    metrics::read_metrics(&data_dir, case_number.as_deref())
+        .map_err(|e| AppError::MetricsError(e.to_string()).to_string_message())
+}
+
+/// Logs reads and writes of the JSON stores that take longer than
+/// `threshold_ms` to stderr, with the file size and where the time went, or
+/// stops logging when it is omitted. Off by default.
+#[tauri::command]
+fn set_store_slow_log(threshold_ms: Option<u64>) {
+    json_store::set_slow_log(threshold_ms);
+}
+
+fn health_check(app: &AppHandle) -> Result<HealthReport, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::HealthCheckError(e.to_string()).to_string_message())?;
+    let checked_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let result = health::run_health_check(&data_dir, &checked_at)
+        .map_err(|e| AppError::HealthCheckError(e.to_string()).to_string_message());
+    if result.as_ref().map_or(true, HealthReport::has_findings) {
+        record_activity(app, "health check", None, &result, |report| {
+            format!(
+                "{} unreadable stores, {} leftover temporary files removed, {} vault entries without a copy dropped",
+                report.unreadable_stores.len(),
+                report.removed_partials.len(),
+                report.dropped_vault_entries
+            )
+        });
+    }
+    result
+}
+
+/// Checks that the app's stores are readable and makes the repairs that
+/// lose nothing, as is done at startup. Findings are also logged to the
+/// activity log.
+#[tauri::command(async)]
+fn run_health_check(app: AppHandle) -> Result<HealthReport, String> {
+    health_check(&app)
+}
+
+#[tauri::command]
+fn get_recent_activity(
+    app: AppHandle,
+    case_number: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ActivityEntry>, String> {
+    let log_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::ActivityLogError(e.to_string()).to_string_message())?;
+    
+    activity::read_recent_activity(&log_dir, case_number.as_deref(), limit.unwrap_or(100))
+        .map_err(|e| AppError::ActivityLogError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn scan_directory(app: AppHandle, path: String, case_number: Option<String>) -> Result<Vec<InventoryItem>, String> {
+    let started = Instant::now();
+    let result = scan_directory_items(path.clone());
+    grant_sources(&app, case_number.as_deref(), &result, &[&path]);
+    record_metric(&app, "scan", case_number.as_deref(), started, &result, |items| items.len());
+    record_activity(&app, "scan", case_number.as_deref(), &result, |items| {
+        format!("Scanned {} ({} files)", path, items.len())
+    });
+    result
+}
+
+#[tauri::command]
+fn scan_directory_report(
+    app: AppHandle,
+    path: String,
+    options: Option<ScanOptions>,
+    case_number: Option<String>,
+) -> Result<ScanReport, String> {
+    let started = Instant::now();
+    let result = scan_directory_with_report(path.clone(), &options.unwrap_or_default());
+    queue_failed_files(&app, case_number.as_deref(), &path, &result);
+    grant_sources(&app, case_number.as_deref(), &result, &[&path]);
+    record_metric(&app, "scan", case_number.as_deref(), started, &result, |report| report.items.len());
+    record_activity(&app, "scan", case_number.as_deref(), &result, |report| {
+        format!(
+            "Scanned {} ({} files, {} unreadable, {} placeholders, {} ignored)",
+            path,
+            report.items.len(),
+            report.failed_files.len(),
+            report.placeholders.len(),
+            report.ignored_files
+        )
+    });
+    result
+}
+
+/// Items sent per batch by `scan_directory_streaming` when no size is given
+const DEFAULT_STREAM_BATCH_SIZE: usize = 500;
+
+/// Scans a directory and streams items to the frontend in batches as they are
+/// read, so very large trees can be ingested without building the whole
+/// inventory in memory first. The returned report carries failures and
+/// placeholders only; its `items` are empty because they were already sent.
+#[tauri::command(async)]
+fn scan_directory_streaming(
+    app: AppHandle,
+    path: String,
+    options: Option<ScanOptions>,
+    batch_size: Option<usize>,
+    on_batch: Channel<Vec<InventoryItem>>,
+    case_number: Option<String>,
+) -> Result<ScanReport, String> {
+    let options = options.unwrap_or_default();
+    let started = Instant::now();
+    let mut sent = 0;
+    let result = validate_directory_path(&path).and_then(|root_path| {
+        let time_zone = validate_scan_options(&options)?;
+        let outcome = scan_folder_streaming(
+            &root_path,
+            &options,
+            batch_size.unwrap_or(DEFAULT_STREAM_BATCH_SIZE),
+            |batch| {
+                let items: Vec<InventoryItem> = batch
+                    .into_iter()
+                    .map(|file_metadata| item_from_scan(file_metadata, &options))
+                    .collect();
+                sent += items.len();
+                on_batch.send(items).map_err(|e| std::io::Error::other(e.to_string()))
+            },
+        )
+        .map_err(|e| AppError::ScanError(e.to_string()).to_string_message())?;
+        
+        Ok(ScanReport {
+            items: Vec::new(),
+            failed_files: outcome.failed,
+            placeholders: outcome.placeholders,
+            ignored_files: outcome.ignored_files,
+            ignored_folders: outcome.ignored_folders,
+            small_files: outcome.small_files,
+            extension_stats: sorted_extension_stats(outcome.extension_stats),
+            time_zone,
+        })
+    });
+    queue_failed_files(&app, case_number.as_deref(), &path, &result);
+    grant_sources(&app, case_number.as_deref(), &result, &[&path]);
+    record_metric(&app, "scan", case_number.as_deref(), started, &result, |_| sent);
+    record_activity(&app, "scan", case_number.as_deref(), &result, |report| {
+        format!(
+            "Scanned {} ({} files, {} unreadable, {} placeholders, {} ignored)",
+            path,
+            sent,
+            report.failed_files.len(),
+            report.placeholders.len(),
+            report.ignored_files
+        )
+    });
+    result
+}
+
+fn retry_queue_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::RetryQueueError(e.to_string()).to_string_message())
+}
+
+/// Adds a scan's unreadable files to the case's retry queue. Failing to
+/// queue never fails the scan itself.
+fn queue_failed_files(app: &AppHandle, case_number: Option<&str>, folder_path: &str, result: &Result<ScanReport, String>) {
+    if let (Ok(report), Ok(data_dir)) = (result, retry_queue_dir(app)) {
+        let _ = retry_queue::record_attempts(&data_dir, case_number, folder_path, &report.failed_files, &[]);
+    }
+}
+
+fn access_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::CaseSourcesError(e.to_string()).to_string_message())
+}
+
+/// Records folders or files the user scanned, synced or added as sources of
+/// the case, so previews may read them. Failing to record never fails the
+/// command that found them.
+fn grant_sources<T>(app: &AppHandle, case_number: Option<&str>, result: &Result<T, String>, paths: &[&str]) {
+    if let (Ok(_), Ok(data_dir)) = (result, access_dir(app)) {
+        let _ = access::grant(&data_dir, case_number, paths);
+    }
+}
+
+/// Refuses reads of files outside the case's sources
+fn ensure_case_source(app: &AppHandle, case_number: Option<&str>, path: &Path) -> Result<(), String> {
+    let allowed = access::is_allowed(&access_dir(app)?, case_number, path)
+        .map_err(|e| AppError::CaseSourcesError(e.to_string()).to_string_message())?;
+    if !allowed {
+        return Err(AppError::NotInCase(path.to_string_lossy().to_string()).to_string_message());
+    }
+    Ok(())
+}
+
+/// The folders and files a case may read: those it scanned, synced or
+/// ingested, and those added by hand
+#[tauri::command]
+fn list_case_sources(app: AppHandle, case_number: Option<String>) -> Result<Vec<String>, String> {
+    let sources = access::case_sources(&access_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::CaseSourcesError(e.to_string()).to_string_message())?;
+    Ok(sources.into_iter().map(|source| source.path).collect())
+}
+
+/// Allows the case to read files under a folder, or a single file, it has
+/// not scanned, e.g. a folder of exhibits kept elsewhere. Like a scanned
+/// folder, an added folder is included when all of the case's sources are
+/// synced.
+#[tauri::command]
+fn add_case_source(app: AppHandle, case_number: Option<String>, path: String) -> Result<(), String> {
+    if !Path::new(&path).exists() {
+        return Err(AppError::PathNotFound(path).to_string_message());
+    }
+    let result = access::grant(&access_dir(&app)?, case_number.as_deref(), &[&path])
+        .map_err(|e| AppError::CaseSourcesError(e.to_string()).to_string_message());
+    record_activity(&app, "case sources", case_number.as_deref(), &result, |_| format!("Allowed {}", path));
+    result
+}
+
+/// Stops the case from reading files under one of its sources. Files under
+/// another of its sources stay readable.
+#[tauri::command]
+fn remove_case_source(app: AppHandle, case_number: Option<String>, path: String) -> Result<(), String> {
+    let result = access::revoke(&access_dir(&app)?, case_number.as_deref(), &path)
+        .map_err(|e| AppError::CaseSourcesError(e.to_string()).to_string_message())
+        .and_then(|removed| {
+            if removed {
+                Ok(())
+            } else {
+                Err(AppError::NotCaseSource(path.clone()).to_string_message())
+            }
+        });
+    record_activity(&app, "case sources", case_number.as_deref(), &result, |_| format!("Removed {}", path));
+    result
+}
+
+/// Files waiting in a case's retry queue
+#[tauri::command]
+fn get_retry_queue(app: AppHandle, case_number: Option<String>) -> Result<Vec<QueuedFile>, String> {
+    retry_queue::queued_files(&retry_queue_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::RetryQueueError(e.to_string()).to_string_message())
+}
+
+/// Re-attempts files that failed during a previous scan of `folder_path`:
+/// the given paths, or every file queued for the case under that folder.
+/// Queued folders that could not be listed are scanned again.
+/// Recovered files leave the queue; files that still fail stay queued.
+#[tauri::command]
+fn retry_failed_files(
+    app: AppHandle,
+    case_number: Option<String>,
+    folder_path: String,
+    paths: Option<Vec<String>>,
+    options: Option<ScanOptions>,
+) -> Result<ScanReport, String> {
+    let root_path = validate_directory_path(&folder_path)?;
+    let options = options.unwrap_or_default();
+    let time_zone = validate_scan_options(&options)?;
+    let data_dir = retry_queue_dir(&app)?;
+    let paths = match paths {
+        Some(paths) => paths,
+        None => retry_queue::queued_files(&data_dir, case_number.as_deref())
+            .map_err(|e| AppError::RetryQueueError(e.to_string()).to_string_message())?
+            .into_iter()
+            .filter(|entry| entry.folder_path == folder_path)
+            .map(|entry| entry.path)
+            .collect(),
+    };
+    if let Some(outside) = paths.iter().find(|path| !access::is_within(&root_path, Path::new(path))) {
+        return Err(AppError::OutsideSourceFolder(outside.clone()).to_string_message());
+    }
+    
+    let mut report = ScanReport {
+        items: Vec::new(),
+        failed_files: Vec::new(),
+        placeholders: Vec::new(),
+        ignored_files: 0,
+        ignored_folders: 0,
+        small_files: Vec::new(),
+        extension_stats: Vec::new(),
+        time_zone,
+    };
+    
+    let mut recovered = Vec::new();
+    for path in paths {
+        // A queued folder is one that could not be listed; scan it again
+        if Path::new(&path).is_dir() {
+            match rescan_folder(&root_path, Path::new(&path), &options) {
+                Ok(outcome) => {
+                    if !outcome.failed.iter().any(|failed| failed.path == path) {
+                        recovered.push(path);
+                    }
+                    report.items.extend(outcome.files.into_iter().map(|file_metadata| item_from_scan(file_metadata, &options)));
+                    report.failed_files.extend(outcome.failed);
+                }
+                Err(e) => report.failed_files.push(FailedFile {
+                    path,
+                    error: e.to_string(),
+                }),
+            }
+            continue;
+        }
+        match read_metadata_with_retry(&root_path, &PathBuf::from(&path), &options) {
+            Ok(file_metadata) => {
+                report.items.push(item_from_scan(file_metadata, &options));
+                recovered.push(path);
+            }
+            Err(e) => report.failed_files.push(FailedFile {
+                path,
+                error: e.to_string(),
+            }),
+        }
+    }
+    
+    retry_queue::record_attempts(&data_dir, case_number.as_deref(), &folder_path, &report.failed_files, &recovered)
+        .map_err(|e| AppError::RetryQueueError(e.to_string()).to_string_message())?;
+    Ok(report)
+}
+
+#[tauri::command]
+fn export_inventory(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    format: String,
+    output_path: String,
+    case_number: Option<String>,
+    folder_path: Option<String>,
+    options: Option<ExportOptions>,
+) -> Result<(), String> {
+    let options = options.unwrap_or_default();
+    let items = in_review_scope(&app, case_number.as_deref(), items, options.include_suppressed)?;
+    let item_count = items.len();
+    let started = Instant::now();
+    let result = export_items(
+        items,
+        &format,
+        &output_path,
+        case_number.as_deref(),
+        folder_path.as_deref(),
+        &options,
+    );
+    record_metric(&app, "export", case_number.as_deref(), started, &result, |_| item_count);
+    record_activity(&app, "export", case_number.as_deref(), &result, |_| {
+        if options.redacted_fields.is_empty() {
+            format!("Exported {} items as {} to {}", item_count, format, output_path)
+        } else {
+            format!(
+                "Exported {} items as {} to {} (redacted: {})",
+                item_count,
+                format,
+                output_path,
+                options.redacted_fields.join(", ")
+            )
+        }
+    });
+    result
+}
+
+/// Writes a zip for delivery holding the inventory export and, if asked,
+/// the inventoried documents, AES-encrypted when a passphrase is given
+#[tauri::command(async)]
+fn export_case_bundle(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    output_path: String,
+    case_number: Option<String>,
+    folder_path: Option<String>,
+    bundle: BundleOptions,
+    options: Option<ExportOptions>,
+) -> Result<BundleSummary, String> {
+    let options = options.unwrap_or_default();
+    if bundle.passphrase.as_deref().is_some_and(str::is_empty) {
+        return Err(AppError::BundleError("the passphrase cannot be empty".to_string()).to_string_message());
+    }
+    let production = bundle.production.as_deref().map(str::trim).filter(|name| !name.is_empty());
+    if production.is_some() && !bundle.include_documents {
+        return Err(AppError::BundleError("a production must include the documents".to_string()).to_string_message());
+    }
+    let items = in_review_scope(&app, case_number.as_deref(), items, options.include_suppressed)?;
+    let started = Instant::now();
+    
+    // The inventory is written unencrypted before it is zipped, so it goes in
+    // a directory only this user can read, removed however the export ends
+    let staging = tempfile::Builder::new()
+        .prefix("bundle-")
+        .tempdir()
+        .map_err(|e| AppError::BundleError(e.to_string()).to_string_message())?;
+    let inventory_name = format!("inventory.{}", bundle.format);
+    let inventory_path = staging.path().join(&inventory_name);
+    let mut entries = vec![BundleEntry {
+        name: inventory_name,
+        source: inventory_path.clone(),
+    }];
+    if bundle.include_documents {
+        entries.extend(items.iter().map(|item| BundleEntry {
+            name: bundle::document_entry_name(&item.folder_path, &item.file_name),
+            source: PathBuf::from(&item.absolute_path),
+        }));
+    }
+    let result = export_items(
+        items.clone(),
+        &bundle.format,
+        &inventory_path.to_string_lossy(),
+        case_number.as_deref(),
+        folder_path.as_deref(),
+        &options,
+    )
+    .and_then(|_| {
+        bundle::write_bundle(Path::new(&output_path), &entries, bundle.passphrase.as_deref())
+            .map_err(|e| AppError::BundleError(e.to_string()).to_string_message())
+    })
+    .map(|written| BundleSummary {
+        output_path: output_path.clone(),
+        entries: entries.len() - written.missing.len(),
+        encrypted: bundle.passphrase.is_some(),
+        verified: written.verified,
+        missing: written.missing,
+    });
+    drop(staging);
+    let result = match (result, production) {
+        (Ok(summary), Some(name)) => {
+            let produced: Vec<&InventoryItem> = items
+                .iter()
+                .filter(|item| !summary.missing.contains(&item.absolute_path))
+                .collect();
+            let verified = summary.verified == summary.entries;
+            record_bundle_production(&app, case_number.as_deref(), name, &bundle.filter, &produced, &output_path, verified)
+                .map(|_| summary)
+        }
+        (result, _) => result,
+    };
+    
+    record_metric(&app, "export", case_number.as_deref(), started, &result, |summary| summary.entries);
+    record_activity(&app, "export", case_number.as_deref(), &result, |summary| {
+        format!(
+            "Exported a {}bundle of {} files to {} ({} verified)",
+            if summary.encrypted { "encrypted " } else { "" },
+            summary.entries,
+            output_path,
+            summary.verified
+        )
+    });
+    result
+}
+
+fn productions_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::ProductionError(e.to_string()).to_string_message())
+}
+
+/// Records a bundle as a production set, hashing the delivered archive.
+/// `verified` is whether every entry was checked against its source.
+fn record_bundle_production(
+    app: &AppHandle,
+    case_number: Option<&str>,
+    name: &str,
+    filter: &str,
+    produced: &[&InventoryItem],
+    output_path: &str,
+    verified: bool,
+) -> Result<(), String> {
+    let manifest_hash = hashing::hash_file(Path::new(output_path), HashAlgorithm::Sha256)
+        .map_err(|e| AppError::ProductionError(e.to_string()).to_string_message())?;
+    let mut production = Production::new(case_number, name, filter, produced, output_path, &manifest_hash);
+    production.verified = verified;
+    productions::record_production(&productions_dir(app)?, production)
+        .map_err(|e| AppError::ProductionError(e.to_string()).to_string_message())
+}
+
+/// A case's production sets, oldest first
+#[tauri::command]
+fn list_productions(app: AppHandle, case_number: Option<String>) -> Result<Vec<ProductionSummary>, String> {
+    productions::list_productions(&productions_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::ProductionError(e.to_string()).to_string_message())
+}
+
+/// The production sets a file went out in; empty if it has not been produced
+#[tauri::command]
+fn get_file_productions(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<Vec<ProductionSummary>, String> {
+    productions::file_productions(&productions_dir(&app)?, case_number.as_deref(), &absolute_path)
+        .map_err(|e| AppError::ProductionError(e.to_string()).to_string_message())
+}
+
+fn vault_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::VaultError(e.to_string()).to_string_message())
+}
+
+/// Copies added files into the case's vault when it is on. Failures are
+/// recorded in the activity log but never fail the ingest or sync that added
+/// them.
+fn vault_if_enabled(app: &AppHandle, case_number: Option<&str>, paths: &[&str]) {
+    let Ok(data_dir) = vault_dir(app) else {
+        return;
+    };
+    match vault::vault_enabled(&data_dir, case_number) {
+        Ok(false) => {}
+        Ok(true) => {
+            let stored_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            for path in paths {
+                // A copy that fails verification goes in the activity log
+                let result = vault::store_file(&data_dir, case_number, Path::new(path), &stored_at)
+                    .map_err(|e| AppError::VaultError(format!("{}: {}", path, e)).to_string_message());
+                if result.is_err() {
+                    record_activity(app, "vault", case_number, &result, |_| String::new());
+                }
+            }
+        }
+        Err(e) => eprintln!("Error reading vault settings: {}", e),
+    }
+}
+
+/// Turns the evidence vault on or off for a case. With it on, files added
+/// by ingest or sync are copied into the case's data directory, so the
+/// inventory stays usable if the originals go away.
+#[tauri::command]
+fn set_vault_enabled(app: AppHandle, case_number: Option<String>, enabled: bool) -> Result<(), String> {
+    vault::set_vault_enabled(&vault_dir(&app)?, case_number.as_deref(), enabled)
+        .map_err(|e| AppError::VaultError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn get_vault_enabled(app: AppHandle, case_number: Option<String>) -> Result<bool, String> {
+    vault::vault_enabled(&vault_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::VaultError(e.to_string()).to_string_message())
+}
+
+/// Copies the case's current files into its vault, e.g. after turning the
+/// vault on for an existing case. Files that cannot be read are skipped;
+/// returns how many were stored.
+#[tauri::command(async)]
+fn vault_case_files(app: AppHandle, case_number: Option<String>, items: Vec<InventoryItem>) -> Result<usize, String> {
+    let data_dir = vault_dir(&app)?;
+    let started = Instant::now();
+    let stored_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let stored = items
+        .iter()
+        .filter(|item| vault::store_file(&data_dir, case_number.as_deref(), Path::new(&item.absolute_path), &stored_at).is_ok())
+        .count();
+    let result: Result<usize, String> = Ok(stored);
+    record_metric(&app, "vault", case_number.as_deref(), started, &result, |stored| *stored);
+    record_activity(&app, "vault", case_number.as_deref(), &result, |stored| {
+        format!("Stored {} of {} files in the vault", stored, items.len())
+    });
+    result
+}
+
+/// How many files the case's vault holds and the space it takes
+#[tauri::command]
+fn get_vault_report(app: AppHandle, case_number: Option<String>) -> Result<VaultReport, String> {
+    vault::vault_report(&vault_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::VaultError(e.to_string()).to_string_message())
+}
+
+/// Path of the vaulted copy of a file, for opening or restoring it when the
+/// original is gone
+#[tauri::command]
+fn get_vault_copy(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<String, String> {
+    vault::vault_copy(&vault_dir(&app)?, case_number.as_deref(), &absolute_path)
+        .map_err(|e| AppError::VaultError(e.to_string()).to_string_message())?
+        .map(|copy| copy.to_string_lossy().to_string())
+        .ok_or_else(|| AppError::NotInVault(absolute_path).to_string_message())
+}
+
+fn configs_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::ConfigError(e.to_string()).to_string_message())
+}
+
+/// Applies the checks the save commands make to every entry of a config
+fn check_config(config: &ConfigEntries) -> Result<(), String> {
+    match config {
+        ConfigEntries::AutomationRules(all) => {
+            check_rule_targets(all)?;
+            all.iter().try_for_each(|rule| check_filter_fields(&rule.filter))
+        }
+        ConfigEntries::Watches(all) => all.iter().try_for_each(|watch| check_filter_fields(&watch.filter)),
+        ConfigEntries::ReportTemplates(_) | ConfigEntries::KeywordLists(_) => Ok(()),
+    }
+}
+
+/// Tells the windows showing the case that its config of this kind changed
+fn notify_config_change(app: &AppHandle, case_number: Option<&str>, kind: ConfigKind, names: Vec<String>) {
+    match kind {
+        ConfigKind::AutomationRules => notify_change(app, RULES_CHANGED_EVENT, case_number, ChangeKind::Updated, names),
+        ConfigKind::Watches => notify_change(app, WATCHES_CHANGED_EVENT, case_number, ChangeKind::Updated, names),
+        ConfigKind::ReportTemplates | ConfigKind::KeywordLists => {}
+    }
+}
+
+/// Writes a case's rules, report templates, keyword lists or watches to a
+/// file that can be loaded into another case or install. Returns how many
+/// entries were written.
+#[tauri::command]
+fn export_config(app: AppHandle, kind: ConfigKind, case_number: Option<String>, path: String) -> Result<usize, String> {
+    let exported_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    configs::export_config(&configs_dir(&app)?, kind, case_number.as_deref(), Path::new(&path), &exported_at)
+        .map_err(|e| AppError::ConfigError(e.to_string()).to_string_message())
+}
+
+/// Loads a config file written by `export_config` into a case, replacing
+/// entries of the same name and keeping the rest. Nothing is saved unless
+/// every entry passes the checks its save command makes. Returns the names
+/// loaded.
+#[tauri::command]
+fn import_config(app: AppHandle, kind: ConfigKind, case_number: Option<String>, path: String) -> Result<Vec<String>, String> {
+    let file_path = validate_file_path(&path)?;
+    let config = configs::read_config_file(&file_path, kind)
+        .map_err(|e| AppError::ConfigError(e.to_string()).to_string_message())?;
+    check_config(&config)?;
+    
+    let result = configs::save_config(&configs_dir(&app)?, case_number.as_deref(), config)
+        .map_err(|e| AppError::ConfigError(e.to_string()).to_string_message());
+    record_activity(&app, "import_config", case_number.as_deref(), &result, |names| {
+        format!("Loaded {} config entries from {}", names.len(), path)
+    });
+    let names = result?;
+    notify_config_change(&app, case_number.as_deref(), kind, names.clone());
+    Ok(names)
+}
+
+/// The rules, report templates, keyword lists or watches that apply to a
+/// case. A case with none of its own of a kind inherits the global ones
+/// (saved without a case); once it has any of its own, only those apply.
+#[tauri::command]
+fn get_effective_config(app: AppHandle, kind: ConfigKind, case_number: Option<String>) -> Result<EffectiveConfig, String> {
+    configs::effective_config(&configs_dir(&app)?, kind, case_number.as_deref())
+        .map_err(|e| AppError::ConfigError(e.to_string()).to_string_message())
+}
+
+/// Removes a case's own entries of one kind so it inherits the global ones
+/// again. Returns the names removed.
+#[tauri::command]
+fn reset_case_config(app: AppHandle, kind: ConfigKind, case_number: String) -> Result<Vec<String>, String> {
+    let result = configs::reset_to_global(&configs_dir(&app)?, kind, &case_number)
+        .map_err(|e| AppError::ConfigError(e.to_string()).to_string_message());
+    record_activity(&app, "reset_case_config", Some(&case_number), &result, |names| {
+        format!("Reset {:?} to the global defaults, removing {} case entries", kind, names.len())
+    });
+    let names = result?;
+    match kind {
+        ConfigKind::AutomationRules => notify_change(&app, RULES_CHANGED_EVENT, Some(&case_number), ChangeKind::Deleted, names.clone()),
+        ConfigKind::Watches => notify_change(&app, WATCHES_CHANGED_EVENT, Some(&case_number), ChangeKind::Deleted, names.clone()),
+        ConfigKind::ReportTemplates | ConfigKind::KeywordLists => {}
+    }
+    Ok(names)
+}
+
+/// Builds an export path from a file name pattern such as
+/// `{case_id}_{date}_{profile}.xlsx`, resolving name collisions by policy
+#[tauri::command]
+fn resolve_export_filename(
+    directory: String,
+    pattern: String,
+    format: String,
+    case_number: Option<String>,
+    profile: Option<String>,
+    collision: Option<CollisionPolicy>,
+) -> Result<String, String> {
+    let directory = validate_directory_path(&directory)?;
+    let context = FilenameContext {
+        case_number: case_number.as_deref(),
+        profile: profile.as_deref(),
+        format: &format,
+    };
+    
+    let file_name = export::expand_filename_pattern(&pattern, &context)
+        .map_err(|placeholder| AppError::InvalidFilenamePattern(placeholder).to_string_message())?;
+    export::resolve_export_path(&directory, &file_name, collision.unwrap_or_default())
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|path| AppError::ExportExists(path).to_string_message())
+}
+
+fn report_templates_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::ReportTemplateError(e.to_string()).to_string_message())
+}
+
+/// Saves a report template with its case, replacing one with the same name
+#[tauri::command]
+fn save_report_template(app: AppHandle, template: SavedReportTemplate) -> Result<(), String> {
+    report::save_report_template(&report_templates_dir(&app)?, template)
+        .map_err(|e| AppError::ReportTemplateError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn list_report_templates(app: AppHandle, case_number: Option<String>) -> Result<Vec<SavedReportTemplate>, String> {
+    report::case_report_templates(&report_templates_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::ReportTemplateError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn remove_report_template(app: AppHandle, case_number: Option<String>, name: String) -> Result<(), String> {
+    let removed = report::remove_report_template(&report_templates_dir(&app)?, case_number.as_deref(), &name)
+        .map_err(|e| AppError::ReportTemplateError(e.to_string()).to_string_message())?;
+    if removed {
+        Ok(())
+    } else {
+        Err(AppError::ReportTemplateNotFound(name).to_string_message())
+    }
+}
+
+#[tauri::command]
+fn generate_report(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    template: Option<ReportTemplate>,
+    output_path: String,
+    case_number: Option<String>,
+    folder_path: Option<String>,
+    include_suppressed: Option<bool>,
+) -> Result<(), String> {
+    let links = report_links(&app, case_number.as_deref())?;
+    let items = in_review_scope(&app, case_number.as_deref(), items, include_suppressed.unwrap_or(false))?;
+    generate_report_file(
+        items,
+        &template.unwrap_or_default(),
+        &output_path,
+        case_number.as_deref(),
+        folder_path.as_deref(),
+        &links,
+    )
+}
+
+/// Writes an HTML report from one of the case's saved templates
+#[tauri::command]
+fn generate_saved_report(
+    app: AppHandle,
+    case_number: Option<String>,
+    template_name: String,
+    items: Vec<InventoryItem>,
+    output_path: String,
+    folder_path: Option<String>,
+    include_suppressed: Option<bool>,
+) -> Result<(), String> {
+    let template = configs::find_report_template(&report_templates_dir(&app)?, case_number.as_deref(), &template_name)
+        .map_err(|e| AppError::ReportTemplateError(e.to_string()).to_string_message())?
+        .ok_or_else(|| AppError::ReportTemplateNotFound(template_name).to_string_message())?;
+    let links = report_links(&app, case_number.as_deref())?;
+    let items = in_review_scope(&app, case_number.as_deref(), items, include_suppressed.unwrap_or(false))?;
+    generate_report_file(items, &template, &output_path, case_number.as_deref(), folder_path.as_deref(), &links)
+}
+
+/// Writes the visible columns of the (filtered) items as a paginated,
+/// landscape print view with repeated headers and page numbers
+#[tauri::command]
+fn export_print_view(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    columns: Vec<String>,
+    output_path: String,
+    case_number: Option<String>,
+    include_suppressed: Option<bool>,
+) -> Result<(), String> {
+    let rows = items_to_rows(in_review_scope(&app, case_number.as_deref(), items, include_suppressed.unwrap_or(false))?);
+    let title = match case_number.as_deref() {
+        Some(case_no) => format!("Document Inventory - Case No. {}", case_no),
+        None => "Document Inventory".to_string(),
+    };
+    
+    let html = report::render_print_table(&rows, &columns, &title)
+        .map_err(|field| AppError::UnknownField(field).to_string_message())?;
+    std::fs::write(&output_path, html)
+        .map_err(|e| AppError::ReportError(e.to_string()).to_string_message())
+}
+
+/// File categories in display order, so every view groups and colors them alike
+#[tauri::command]
+fn get_file_categories() -> Vec<String> {
+    categories::FILE_CATEGORIES.iter().map(|c| c.to_string()).collect()
+}
+
+/// Fills in `file_category` for items loaded before categories existed
+#[tauri::command]
+fn categorize_items(mut items: Vec<InventoryItem>) -> Vec<InventoryItem> {
+    for item in items.iter_mut().filter(|item| item.file_category.is_empty()) {
+        item.file_category = categories::categorize(Path::new(&item.absolute_path), &item.file_type).to_string();
+    }
+    items
+}
+
+/// Recomputes only the given derived fields (e.g. just `doc_date_range` after
+/// a pattern fix) for the items at `paths`, or for every item when omitted
+#[tauri::command(async)]
+fn reextract_fields(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    paths: Option<Vec<String>>,
+    fields: Vec<String>,
+    case_number: String,
+) -> Result<ReextractReport, String> {
+    if let Some(field) = fields.iter().find(|field| !reextract::REEXTRACTABLE_FIELDS.contains(&field.as_str())) {
+        return Err(AppError::NotReextractable(field.clone()).to_string_message());
+    }
+    
+    let paths: Option<HashSet<String>> = paths.map(|paths| paths.into_iter().collect());
+    let report = reextract::reextract_fields(
+        items,
+        |item| paths.as_ref().is_none_or(|paths| paths.contains(&item.absolute_path)),
+        &fields,
+    );
+    record_field_changes(&app, Some(&case_number), FileEventKind::Refreshed, "reextract", &report.changes);
+    Ok(report)
+}
+
+/// Records recomputed or edited fields in the files' history
+fn record_field_changes(app: &AppHandle, case_number: Option<&str>, kind: FileEventKind, source: &str, changes: &[FieldChange]) {
+    let events = changes
+        .iter()
+        .map(|change| {
+            FileEvent::field_change(&change.absolute_path, kind, source, &change.field, &change.old_value, &change.new_value)
+        })
+        .collect();
+    record_file_events(app, case_number, events);
+}
+
+/// Re-runs document type and description mapping for the items under one
+/// folder only (e.g. a single production batch), then applies `rules` to
+/// those items so a newly added classification rule takes effect there
+#[tauri::command(async)]
+fn reclassify_folder(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    folder_path_prefix: String,
+    rules: Option<Vec<AutomationRule>>,
+    case_number: String,
+) -> Result<ReextractReport, String> {
+    let rules = rules.unwrap_or_default();
+    if let Some(rule) = rules.iter().find(|rule| !INVENTORY_FIELDS.contains(&rule.set_field.as_str())) {
+        return Err(AppError::UnknownField(rule.set_field.clone()).to_string_message());
+    }
+    
+    let in_folder = |item: &InventoryItem| search::is_in_folder(&item.folder_path, &folder_path_prefix);
+    let fields = ["document_type".to_string(), "document_description".to_string()];
+    let mut report = reextract::reextract_fields(items, in_folder, &fields);
+    
+    if !rules.is_empty() {
+        let positions: Vec<usize> = (0..report.items.len()).filter(|i| in_folder(&report.items[*i])).collect();
+        let folder_items = positions.iter().map(|i| report.items[*i].clone()).collect();
+        let result = rules::apply_rules(folder_items, &rules);
+        for (position, item) in positions.into_iter().zip(result.items) {
+            report.items[position] = item;
+        }
+        report.changes.extend(result.actions.into_iter().map(|action| FieldChange {
+            absolute_path: action.absolute_path,
+            field: action.field,
+            old_value: action.old_value,
+            new_value: action.new_value,
+        }));
+    }
+    
+    record_field_changes(&app, Some(&case_number), FileEventKind::Refreshed, "reclassify", &report.changes);
+    let result: Result<&ReextractReport, String> = Ok(&report);
+    record_activity(&app, "reclassify", Some(&case_number), &result, |report| {
+        format!("Reclassified {} ({} fields changed)", folder_path_prefix, report.changes.len())
+    });
+    Ok(report)
+}
+
+#[tauri::command]
+fn get_field_facets(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    field: String,
+    filter: Option<HashMap<String, String>>,
+    case_number: Option<String>,
+    include_suppressed: Option<bool>,
+) -> Result<Vec<FacetCount>, String> {
+    let items = in_review_scope(&app, case_number.as_deref(), items, include_suppressed.unwrap_or(false))?;
+    stats::field_facets(&items, &field, &filter.unwrap_or_default())
+        .ok_or_else(|| AppError::UnknownField(field).to_string_message())
+}
+
+/// Runs a structured query (conditions, grouping, aggregates) over the
+/// case's items, for custom counts and pivot tables
+#[tauri::command]
+fn run_case_query(
+    app: AppHandle,
+    case_number: Option<String>,
+    items: Vec<InventoryItem>,
+    query: CaseQuery,
+    include_suppressed: Option<bool>,
+) -> Result<QueryResult, String> {
+    let items = in_review_scope(&app, case_number.as_deref(), items, include_suppressed.unwrap_or(false))?;
+    let item_count = items.len();
+    let started = Instant::now();
+    let result = query::run_query(&items, &query).map_err(|field| AppError::UnknownField(field).to_string_message());
+    record_metric(&app, "query", case_number.as_deref(), started, &result, |_| item_count);
+    result
+}
+
+/// Top terms and two-word phrases in file names, descriptions and notes,
+/// to suggest search terms for an unfamiliar document population
+#[tauri::command]
+fn compute_term_frequencies(
+    items: Vec<InventoryItem>,
+    filter: Option<HashMap<String, String>>,
+    limit: Option<usize>,
+) -> Result<TermFrequencies, String> {
+    let filter = filter.unwrap_or_default();
+    if let Some(field) = filter.keys().find(|field| !INVENTORY_FIELDS.contains(&field.as_str())) {
+        return Err(AppError::UnknownField(field.clone()).to_string_message());
+    }
+    
+    Ok(terms::compute_term_frequencies(&items, &filter, limit.unwrap_or(50)))
+}
+
+/// Groups similar items for batch review; threshold is the minimum cosine
+/// similarity (0-1) for an item to join a cluster
+#[tauri::command(async)]
+fn cluster_documents(items: Vec<InventoryItem>, threshold: Option<f64>) -> Result<ClusterReport, String> {
+    let threshold = threshold.unwrap_or(0.5);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(AppError::InvalidSimilarityThreshold(threshold).to_string_message());
+    }
+    
+    Ok(clusters::cluster_documents(&items, threshold))
+}
+
+#[tauri::command]
+fn get_date_histogram(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    field: String,
+    bucket: HistogramBucket,
+    case_number: Option<String>,
+    include_suppressed: Option<bool>,
+) -> Result<Vec<HistogramBin>, String> {
+    let items = in_review_scope(&app, case_number.as_deref(), items, include_suppressed.unwrap_or(false))?;
+    stats::date_histogram(&items, &field, bucket)
+        .ok_or_else(|| AppError::NotADateField(field).to_string_message())
+}
+
+#[tauri::command]
+fn search_in_folder(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    folder_path_prefix: String,
+    query: String,
+    case_number: Option<String>,
+    include_suppressed: Option<bool>,
+) -> Result<FolderSearchResult, String> {
+    let started = Instant::now();
+    let items = in_review_scope(&app, case_number.as_deref(), items, include_suppressed.unwrap_or(false))?;
+    let item_count = items.len();
+    let result = search::search_in_folder(items, &folder_path_prefix, &query);
+    record_metric(&app, "query", case_number.as_deref(), started, &Ok::<_, String>(()), |_| item_count);
+    Ok(result)
+}
+
+/// Searches a whole case: inventory fields of the items in review scope, the
+/// case's notes, and its link relationships and link notes. Each hit says
+/// where it was found; `options` picks the sources, filters file hits and
+/// pages each source, and the result counts every source's hits.
+#[tauri::command]
+fn search_all(
+    app: AppHandle,
+    case_number: Option<String>,
+    items: Vec<InventoryItem>,
+    query: String,
+    options: Option<SearchAllOptions>,
+    include_suppressed: Option<bool>,
+) -> Result<SearchAllResults, String> {
+    let started = Instant::now();
+    let options = options.unwrap_or_default();
+    if let Some(field) = options.filter.keys().find(|field| !INVENTORY_FIELDS.contains(&field.as_str())) {
+        return Err(AppError::UnknownField(field.clone()).to_string_message());
+    }
+    let items = in_review_scope(&app, case_number.as_deref(), items, include_suppressed.unwrap_or(false))?;
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::SearchError(e.to_string()).to_string_message())?;
+    let notes: Vec<FileNote> = notes::list_notes(&data_dir, case_number.as_deref(), None)
+        .map_err(|e| AppError::SearchError(e.to_string()).to_string_message())?
+        .into_iter()
+        .map(|listed| listed.note)
+        .collect();
+    let links = links::case_links(&data_dir, case_number.as_deref())
+        .map_err(|e| AppError::SearchError(e.to_string()).to_string_message())?;
+    let results = search::search_all(&items, &notes, &links, &query, &options);
+    record_metric(&app, "query", case_number.as_deref(), started, &Ok::<_, String>(()), |_| items.len());
+    Ok(results)
+}
+
+/// A slice of a file's bytes for previews
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileChunk {
+    pub offset: u64,
+    /// Size of the whole file, so callers know how many chunks remain
+    pub total_size: u64,
+    pub data: Vec<u8>,
+}
+
+/// Reads up to `length` bytes from `offset`, capped at 4 MiB per call. Reading
+/// at or past the end returns an empty chunk. Only files under the case's
+/// sources can be read.
+#[tauri::command(async)]
+fn read_file_range(
+    app: AppHandle,
+    file_path: String,
+    offset: u64,
+    length: u64,
+    case_number: Option<String>,
+) -> Result<FileChunk, String> {
+    let path = validate_file_path(&file_path)?;
+    ensure_case_source(&app, case_number.as_deref(), &path)?;
+    let (data, total_size) = preview::read_range(&path, offset, length)
+        .map_err(|e| AppError::Io(e).to_string_message())?;
+    
+    Ok(FileChunk { offset, total_size, data })
+}
+
+/// Compares two files of the case; like previews, files outside the case's
+/// sources are refused
+#[tauri::command(async)]
+fn diff_files(
+    app: AppHandle,
+    path_a: String,
+    path_b: String,
+    case_number: Option<String>,
+) -> Result<FileDiff, String> {
+    let file_a = validate_file_path(&path_a)?;
+    let file_b = validate_file_path(&path_b)?;
+    ensure_case_source(&app, case_number.as_deref(), &file_a)?;
+    ensure_case_source(&app, case_number.as_deref(), &file_b)?;
+    
+    diff::diff_files(&file_a, &file_b)
+        .map_err(|e| AppError::DiffError(e.to_string()).to_string_message())
+}
+
+fn checkouts_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::CheckoutError(e.to_string()).to_string_message())
+}
+
+/// Stores a copy of the file if versioning is on for the case and its
+/// content changed since the last stored version
+fn capture_if_versioned(app: &AppHandle, case_number: Option<&str>, path: &Path) -> Result<Option<FileVersion>, String> {
+    let data_dir = versions_dir(app)?;
+    let enabled = versions::versioning_enabled(&data_dir, case_number)
+        .map_err(|e| AppError::VersionError(e.to_string()).to_string_message())?;
+    if !enabled {
+        return Ok(None);
+    }
+    let captured_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    versions::capture_version(&data_dir, case_number, path, &captured_at)
+        .map_err(|e| AppError::VersionError(e.to_string()).to_string_message())
+}
+
+/// Snapshots a file's hash before it is opened for editing in another
+/// application, and with versioning on stores a copy of its content. The
+/// frontend opens the file once this returns.
+#[tauri::command]
+fn checkout_file_for_edit(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<Checkout, String> {
+    let path = validate_file_path(&absolute_path)?;
+    ensure_case_source(&app, case_number.as_deref(), &path)?;
+    capture_if_versioned(&app, case_number.as_deref(), &path)?;
+    let checked_out_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    
+    checkouts::check_out(&checkouts_dir(&app)?, case_number.as_deref(), &path, &checked_out_at)
+        .map_err(|e| AppError::CheckoutError(e.to_string()).to_string_message())
+}
+
+/// Ends a checkout and reports whether the file changed. A change is recorded
+/// in the file's history so the reviewer can be asked to update its status,
+/// and with versioning on the new content is stored as a version.
+#[tauri::command]
+fn checkin_file(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<CheckinResult, String> {
+    let path = validate_file_path(&absolute_path)?;
+    let result = checkouts::check_in(&checkouts_dir(&app)?, case_number.as_deref(), &path)
+        .map_err(|e| AppError::CheckoutError(e.to_string()).to_string_message())?
+        .ok_or_else(|| AppError::NotCheckedOut(absolute_path.clone()).to_string_message())?;
+    
+    if result.changed {
+        capture_if_versioned(&app, case_number.as_deref(), &path)?;
+        let event = FileEvent::field_change(
+            &absolute_path,
+            FileEventKind::Modified,
+            "checkin",
+            "sha256",
+            &result.checkout.hash,
+            &result.new_hash,
+        );
+        record_file_events(&app, case_number.as_deref(), vec![event]);
+        notify_change(&app, FILES_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Updated, vec![absolute_path]);
+    }
+    Ok(result)
+}
+
+fn versions_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::VersionError(e.to_string()).to_string_message())
+}
+
+/// Turns versioning on or off for a case. With it on, files checked out
+/// for editing keep a stored copy of each version in the case's data
+/// directory.
+#[tauri::command]
+fn set_file_versioning(app: AppHandle, case_number: Option<String>, enabled: bool) -> Result<(), String> {
+    versions::set_versioning(&versions_dir(&app)?, case_number.as_deref(), enabled)
+        .map_err(|e| AppError::VersionError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn get_file_versioning(app: AppHandle, case_number: Option<String>) -> Result<bool, String> {
+    versions::versioning_enabled(&versions_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::VersionError(e.to_string()).to_string_message())
+}
+
+/// Stores the file's current content as a version, whether or not
+/// versioning is on. Returns `None` if it matches the latest version.
+#[tauri::command]
+fn capture_file_version(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<Option<FileVersion>, String> {
+    let path = validate_file_path(&absolute_path)?;
+    ensure_case_source(&app, case_number.as_deref(), &path)?;
+    let captured_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    
+    versions::capture_version(&versions_dir(&app)?, case_number.as_deref(), &path, &captured_at)
+        .map_err(|e| AppError::VersionError(e.to_string()).to_string_message())
+}
+
+/// Stored versions of a file, oldest first
+#[tauri::command]
+fn list_file_versions(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<Vec<FileVersion>, String> {
+    versions::list_versions(&versions_dir(&app)?, case_number.as_deref(), &absolute_path)
+        .map_err(|e| AppError::VersionError(e.to_string()).to_string_message())
+}
+
+fn stored_version(app: &AppHandle, case_number: Option<&str>, absolute_path: &str, hash: &str) -> Result<PathBuf, String> {
+    versions::version_copy(&versions_dir(app)?, case_number, absolute_path, hash)
+        .map_err(|e| AppError::VersionError(e.to_string()).to_string_message())?
+        .ok_or_else(|| AppError::VersionNotFound(hash.to_string()).to_string_message())
+}
+
+/// Writes a stored version of a file to `output_path`
+#[tauri::command]
+fn export_file_version(
+    app: AppHandle,
+    case_number: Option<String>,
+    absolute_path: String,
+    hash: String,
+    output_path: String,
+) -> Result<(), String> {
+    let copy = stored_version(&app, case_number.as_deref(), &absolute_path, &hash)?;
+    std::fs::copy(copy, &output_path)
+        .map(|_| ())
+        .map_err(|e| AppError::VersionError(e.to_string()).to_string_message())
+}
+
+/// Compares two stored versions of a file, or a stored version with the
+/// file as it is now when `hash_b` is omitted
+#[tauri::command]
+fn diff_file_versions(
+    app: AppHandle,
+    case_number: Option<String>,
+    absolute_path: String,
+    hash_a: String,
+    hash_b: Option<String>,
+) -> Result<FileDiff, String> {
+    let file_a = stored_version(&app, case_number.as_deref(), &absolute_path, &hash_a)?;
+    let file_b = match hash_b {
+        Some(hash_b) => stored_version(&app, case_number.as_deref(), &absolute_path, &hash_b)?,
+        None => {
+            let path = validate_file_path(&absolute_path)?;
+            ensure_case_source(&app, case_number.as_deref(), &path)?;
+            path
+        }
+    };
+    
+    diff::diff_files(&file_a, &file_b)
+        .map_err(|e| AppError::DiffError(e.to_string()).to_string_message())
+}
+
+/// Files checked out for editing in a case, oldest first
+#[tauri::command]
+fn list_checkouts(app: AppHandle, case_number: Option<String>) -> Result<Vec<Checkout>, String> {
+    checkouts::case_checkouts(&checkouts_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::CheckoutError(e.to_string()).to_string_message())
+}
+
+/// Zero-byte and sub-threshold files under a folder
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SmallFileReport {
+    pub threshold_bytes: u64,
+    pub files: Vec<SmallFile>,
+    /// How many of `files` are completely empty
+    pub zero_byte_count: usize,
+    pub total_files: usize,
+}
+
+/// Lists zero-byte and sub-threshold files without building an inventory
+#[tauri::command(async)]
+fn small_file_report(path: String, threshold_bytes: Option<u64>) -> Result<SmallFileReport, String> {
+    let root_path = validate_directory_path(&path)?;
+    let options = ScanOptions {
+        small_file_threshold_bytes: threshold_bytes.unwrap_or(0),
+        ..ScanOptions::default()
+    };
+    
+    let mut total_files = 0;
+    let outcome = scan_folder_streaming(&root_path, &options, DEFAULT_STREAM_BATCH_SIZE, |batch| {
+        total_files += batch.len();
+        Ok(())
+    })
+    .map_err(|e| AppError::ScanError(e.to_string()).to_string_message())?;
+    
+    Ok(SmallFileReport {
+        threshold_bytes: options.small_file_threshold_bytes,
+        zero_byte_count: outcome.small_files.iter().filter(|f| f.size_bytes == 0).count(),
+        files: outcome.small_files,
+        total_files,
+    })
+}
+
+#[tauri::command]
+fn diagnose_source_access(path: String) -> AccessDiagnosis {
+    diagnostics::diagnose_source_access(&PathBuf::from(path))
+}
+
+#[tauri::command]
+fn check_source_available(path: String) -> SourceStatus {
+    diagnostics::source_status(&PathBuf::from(path))
+}
+
+#[tauri::command]
+fn import_review_decisions(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    file_path: String,
+    dry_run: Option<bool>,
+    case_number: String,
+) -> Result<DecisionImportResult, String> {
+    validate_file_path(&file_path)?;
+    let dry_run = dry_run.unwrap_or(true);
+    let result = decisions::import_review_decisions(items, &file_path, dry_run)
+        .map_err(|e| AppError::ReadCsvError(e.to_string()).to_string_message())?;
+    if !dry_run {
+        let events = result
+            .changes
+            .iter()
+            .map(|change| {
+                FileEvent::field_change(
+                    &change.absolute_path,
+                    FileEventKind::FieldChanged,
+                    "decisions import",
+                    &change.field,
+                    &change.old_value,
+                    &change.new_value,
+                )
+            })
+            .collect();
+        record_file_events(&app, Some(&case_number), events);
+    }
+    Ok(result)
+}
+
+fn rules_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::RuleError(e.to_string()).to_string_message())
+}
+
+fn check_rule_targets(rules: &[AutomationRule]) -> Result<(), String> {
+    match rules.iter().find(|rule| !INVENTORY_FIELDS.contains(&rule.set_field.as_str())) {
+        Some(rule) => Err(AppError::UnknownField(rule.set_field.clone()).to_string_message()),
+        None => Ok(()),
+    }
+}
+
+/// Rejects exact-match filters on fields the inventory does not have
+fn check_filter_fields(filter: &HashMap<String, String>) -> Result<(), String> {
+    match filter.keys().find(|field| !INVENTORY_FIELDS.contains(&field.as_str())) {
+        Some(field) => Err(AppError::UnknownField(field.clone()).to_string_message()),
+        None => Ok(()),
+    }
+}
+
+/// Logs each change a rule made in the case's activity log and the file's
+/// history
+fn record_rule_actions(app: &AppHandle, case_number: Option<&str>, actions: &[RuleAction]) {
+    let events = actions
+        .iter()
+        .map(|action| {
+            FileEvent::field_change(
+                &action.absolute_path,
+                FileEventKind::FieldChanged,
+                &format!("rule: {}", action.rule),
+                &action.field,
+                &action.old_value,
+                &action.new_value,
+            )
+        })
+        .collect();
+    record_file_events(app, case_number, events);
+    for action in actions {
+        record_activity(app, "rule", case_number, &Ok::<_, String>(action), |action| {
+            format!(
+                "Rule \"{}\" set {} of {} from \"{}\" to \"{}\"",
+                action.rule, action.field, action.absolute_path, action.old_value, action.new_value
+            )
+        });
+    }
+}
+
+/// Runs the rules that apply to the case over newly ingested or synced items:
+/// its own, or the global rules when it has none. A rule store that cannot
+/// be read leaves the items as they were.
+fn apply_case_rules(app: &AppHandle, case_number: Option<&str>, items: Vec<InventoryItem>) -> Vec<InventoryItem> {
+    let rules = match rules_dir(app).and_then(|data_dir| {
+        configs::effective_rules(&data_dir, case_number).map_err(|e| AppError::RuleError(e.to_string()).to_string_message())
+    }) {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("Error running automation rules: {}", e);
+            return items;
+        }
+    };
+    if rules.is_empty() || items.is_empty() {
+        return items;
+    }
+    
+    let result = rules::apply_rules(items, &rules);
+    record_rule_actions(app, case_number, &result.actions);
+    result.items
+}
+
+/// Runs the case's stored rules over a newly ingested file
+fn apply_rules_to_ingested(app: &AppHandle, case_number: &str, result: Result<IngestedFile, String>) -> Result<IngestedFile, String> {
+    result.map(|mut ingested| {
+        ingested.item = apply_case_rules(app, Some(case_number), vec![ingested.item]).remove(0);
+        ingested
+    })
+}
+
+/// Applies automation rules in order: the given rules, or the rules that
+/// apply to the case when none are given. Every change is logged.
+#[tauri::command]
+fn apply_automation_rules(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    rules: Option<Vec<AutomationRule>>,
+    case_number: String,
+) -> Result<RuleRunResult, String> {
+    let rules = match rules {
+        Some(rules) => rules,
+        None => configs::effective_rules(&rules_dir(&app)?, Some(&case_number))
+            .map_err(|e| AppError::RuleError(e.to_string()).to_string_message())?,
+    };
+    check_rule_targets(&rules)?;
+    
+    let result = rules::apply_rules(items, &rules);
+    record_rule_actions(&app, Some(&case_number), &result.actions);
+    Ok(result)
+}
+
+/// Saves an automation rule, replacing one with the same name in the case.
+/// Stored rules run on every file a sync or ingest brings in.
+#[tauri::command]
+fn save_automation_rule(app: AppHandle, rule: AutomationRule) -> Result<(), String> {
+    check_rule_targets(std::slice::from_ref(&rule))?;
+    check_filter_fields(&rule.filter)?;
+    
+    let (case_number, name) = (rule.case_number.clone(), rule.name.clone());
+    rules::save_rule(&rules_dir(&app)?, rule)
+        .map_err(|e| AppError::RuleError(e.to_string()).to_string_message())?;
+    notify_change(&app, RULES_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Updated, vec![name]);
+    Ok(())
+}
+
+/// A case's stored automation rules, in the order they run
+#[tauri::command]
+fn list_automation_rules(app: AppHandle, case_number: Option<String>) -> Result<Vec<AutomationRule>, String> {
+    rules::case_rules(&rules_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::RuleError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn remove_automation_rule(app: AppHandle, case_number: Option<String>, name: String) -> Result<(), String> {
+    let removed = rules::remove_rule(&rules_dir(&app)?, case_number.as_deref(), &name)
+        .map_err(|e| AppError::RuleError(e.to_string()).to_string_message())?;
+    if removed {
+        notify_change(&app, RULES_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Deleted, vec![name]);
+        Ok(())
+    } else {
+        Err(AppError::RuleNotFound(name).to_string_message())
+    }
+}
+
+/// Refuses rules on unknown fields and format patterns that do not compile
+fn check_rule_fields(rules: &[FieldRule]) -> Result<(), String> {
+    for rule in rules {
+        if !INVENTORY_FIELDS.contains(&rule.field.as_str()) {
+            return Err(AppError::UnknownField(rule.field.clone()).to_string_message());
+        }
+        if let Some(pattern) = rule.pattern.as_deref().filter(|pattern| !pattern.is_empty()) {
+            validation::compile_pattern(pattern)
+                .map_err(|e| AppError::InvalidFieldPattern(pattern.to_string(), e).to_string_message())?;
+        }
+    }
+    Ok(())
+}
+
+fn field_rules_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::FieldRulesError(e.to_string()).to_string_message())
+}
+
+/// The case's saved field rules
+fn case_field_rules(app: &AppHandle, case_number: Option<&str>) -> Result<Vec<FieldRule>, String> {
+    validation::case_field_rules(&field_rules_dir(app)?, case_number)
+        .map_err(|e| AppError::FieldRulesError(e.to_string()).to_string_message())
+}
+
+/// Replaces the case's field rules, which are then enforced on every edit
+/// and before the case is finalized
+#[tauri::command]
+fn save_field_rules(app: AppHandle, case_number: Option<String>, rules: Vec<FieldRule>) -> Result<(), String> {
+    check_rule_fields(&rules)?;
+    validation::save_field_rules(&field_rules_dir(&app)?, case_number.as_deref(), rules)
+        .map_err(|e| AppError::FieldRulesError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn list_field_rules(app: AppHandle, case_number: Option<String>) -> Result<Vec<FieldRule>, String> {
+    case_field_rules(&app, case_number.as_deref())
+}
+
+/// Checks an edited value against the field's rules before it is saved: the
+/// given rules, or the case's saved rules when none are given. Blank values
+/// are accepted here; `required` is enforced at finalization.
+#[tauri::command]
+fn validate_field_edit(
+    app: AppHandle,
+    case_number: Option<String>,
+    field: String,
+    value: String,
+    rules: Option<Vec<FieldRule>>,
+) -> Result<(), String> {
+    let rules = match rules {
+        Some(rules) => rules,
+        None => case_field_rules(&app, case_number.as_deref())?,
+    };
+    check_rule_fields(&rules)?;
+    
+    match validation::check_edit(&rules, &field, &value) {
+        Some(problem) => Err(AppError::InvalidFieldValue(field, problem).to_string_message()),
+        None => Ok(()),
+    }
+}
+
+/// Lists the rows and fields that block finalizing the inventory, checked
+/// against the given rules or the case's saved rules
+#[tauri::command]
+fn validate_for_finalization(
+    app: AppHandle,
+    case_number: Option<String>,
+    items: Vec<InventoryItem>,
+    rules: Option<Vec<FieldRule>>,
+) -> Result<FinalizationCheck, String> {
+    let rules = match rules {
+        Some(rules) => rules,
+        None => case_field_rules(&app, case_number.as_deref())?,
+    };
+    check_rule_fields(&rules)?;
+    
+    Ok(validation::validate_for_finalization(&items, &rules))
+}
+
+/// Review hints for newly ingested or existing items: years far outside the
+/// case's range, one-off file types and files much larger than their folder's
+/// others. `first_year`/`last_year` set the case range instead of inferring it.
+#[tauri::command(async)]
+fn list_anomalies(
+    items: Vec<InventoryItem>,
+    first_year: Option<i32>,
+    last_year: Option<i32>,
+) -> Vec<Anomaly> {
+    let year_range = match (first_year, last_year) {
+        (Some(first), Some(last)) => Some((first.min(last), first.max(last))),
+        (Some(first), None) => Some((first, i32::MAX)),
+        (None, Some(last)) => Some((i32::MIN, last)),
+        (None, None) => None,
+    };
+    anomalies::find_anomalies(&items, year_range)
+}
+
+/// Flags files whose filesystem dates disagree with their document date, e.g.
+/// a 2015 statement created on disk after the litigation hold (`hold_date`,
+/// YYYY-MM-DD). With `output_path` the findings are also written to XLSX or
+/// CSV, chosen by extension.
+#[tauri::command(async)]
+fn check_date_consistency(
+    items: Vec<InventoryItem>,
+    hold_date: Option<String>,
+    max_gap_days: Option<i64>,
+    output_path: Option<String>,
+) -> Result<Vec<DateInconsistency>, String> {
+    let hold_date = hold_date
+        .map(|date| dates::parse_date(&date))
+        .transpose()
+        .map_err(|bad| AppError::InvalidDate(bad).to_string_message())?;
+    let issues = consistency::find_date_inconsistencies(&items, hold_date, max_gap_days);
+    
+    if let Some(output_path) = output_path {
+        if output_path.to_lowercase().ends_with(".csv") {
+            consistency::write_inconsistencies_csv(&issues, &output_path)
+                .map_err(|e| AppError::CsvError(e.to_string()).to_string_message())?;
+        } else {
+            consistency::write_inconsistencies_xlsx(&issues, &output_path)
+                .map_err(|e| AppError::XlsxError(e.to_string()).to_string_message())?;
+        }
+    }
+    
+    Ok(issues)
+}
+
+fn finalize_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::FinalizeError(e.to_string()).to_string_message())
+}
+
+fn finalize_error(e: Box<dyn std::error::Error>) -> String {
+    AppError::FinalizeError(e.to_string()).to_string_message()
+}
+
+/// Freezes a case at delivery: hashes every file and its inventory fields,
+/// stores the Merkle root with a timestamp, and from then on refuses commands
+/// that would change the case's inventory
+#[tauri::command(async)]
+fn finalize_case(
+    app: AppHandle,
+    case_number: String,
+    items: Vec<InventoryItem>,
+    workers: Option<usize>,
+) -> Result<FinalizedCase, String> {
+    let result = (|| {
+        let check = validation::validate_for_finalization(&items, &case_field_rules(&app, Some(&case_number))?);
+        if !check.ready {
+            return Err(AppError::FieldRulesNotMet(check.violations.len()).to_string_message());
+        }
+        let record = finalize::finalize_case(&case_number, &items, workers.unwrap_or_else(hashing::default_workers))
+            .map_err(|unreadable| {
+                AppError::FinalizeError(format!("cannot read {}", unreadable.join(", "))).to_string_message()
+            })?;
+        if !finalize::save_finalized(&finalize_dir(&app)?, record.clone()).map_err(finalize_error)? {
+            return Err(AppError::CaseFinalized(case_number.clone()).to_string_message());
+        }
+        Ok(record)
+    })();
+    record_activity(&app, "finalize", Some(&case_number), &result, |record| {
+        format!("Finalized {} files (root {})", record.files.len(), record.merkle_root)
+    });
+    result
+}
+
+/// Re-hashes the case's current inventory and reports whether anything has
+/// changed since it was finalized
+#[tauri::command(async)]
+fn verify_finalized_case(
+    app: AppHandle,
+    case_number: String,
+    items: Vec<InventoryItem>,
+    workers: Option<usize>,
+) -> Result<CaseVerification, String> {
+    let result = finalize::find_finalized(&finalize_dir(&app)?, &case_number)
+        .map_err(finalize_error)?
+        .ok_or_else(|| AppError::CaseNotFinalized(case_number.clone()).to_string_message())
+        .map(|record| finalize::verify_case(&record, &items, workers.unwrap_or_else(hashing::default_workers)));
+    record_activity(&app, "verify_finalized", Some(&case_number), &result, |verification| {
+        if verification.verified {
+            format!("Verified finalized case (root {})", verification.merkle_root)
+        } else {
+            format!(
+                "Finalized case changed: {} content, {} metadata, {} missing, {} added",
+                verification.modified_content.len(),
+                verification.modified_metadata.len(),
+                verification.missing.len(),
+                verification.added.len()
+            )
+        }
+    });
+    result
+}
+
+/// The stored snapshot for a case, or None when it has not been finalized
+#[tauri::command]
+fn get_finalized_case(app: AppHandle, case_number: String) -> Result<Option<FinalizedCase>, String> {
+    finalize::find_finalized(&finalize_dir(&app)?, &case_number).map_err(finalize_error)
+}
+
+/// Folder for timestamp tokens of finalized cases
+const TIMESTAMPS_DIR: &str = "timestamps";
+
+fn timestamp_error(message: String) -> String {
+    AppError::TimestampError(message).to_string_message()
+}
+
+/// Obtains an RFC 3161 token for `digest` from `tsa_url`, saves the TSA's
+/// response at `token_path` and returns the receipt
+fn obtain_timestamp(tsa_url: &str, digest: &[u8], token_path: &Path) -> Result<TimestampReceipt, String> {
+    let granted = timestamp::request_timestamp(tsa_url, digest).map_err(timestamp_error)?;
+    std::fs::write(token_path, &granted.response).map_err(|e| AppError::Io(e).to_string_message())?;
+    Ok(TimestampReceipt {
+        tsa_url: tsa_url.trim().to_string(),
+        digest: timestamp::to_hex(digest),
+        gen_time: granted.gen_time,
+        serial_number: granted.serial_number,
+        token_path: token_path.to_string_lossy().to_string(),
+    })
+}
+
+fn timestamp_case_root(app: &AppHandle, case_number: &str, tsa_url: &str) -> Result<FinalizedCase, String> {
+    let data_dir = finalize_dir(app)?;
+    let record = finalize::find_finalized(&data_dir, case_number)
+        .map_err(finalize_error)?
+        .ok_or_else(|| AppError::CaseNotFinalized(case_number.to_string()).to_string_message())?;
+    let digest = timestamp::from_hex(&record.merkle_root)
+        .ok_or_else(|| timestamp_error(format!("invalid Merkle root {}", record.merkle_root)))?;
+    
+    let token_dir = data_dir.join(TIMESTAMPS_DIR);
+    std::fs::create_dir_all(&token_dir).map_err(|e| AppError::Io(e).to_string_message())?;
+    let token_path = token_dir.join(format!("{}.tsr", export::sanitize_file_name(case_number.trim())));
+    let receipt = obtain_timestamp(tsa_url, &digest, &token_path)?;
+    finalize::set_timestamp(&data_dir, case_number, receipt)
+        .map_err(finalize_error)?
+        .ok_or_else(|| AppError::CaseNotFinalized(case_number.to_string()).to_string_message())
+}
+
+/// Timestamps a finalized case's Merkle root with a trusted timestamping
+/// authority (an `http://` RFC 3161 endpoint). The token is kept in the app
+/// data folder and its receipt stored with the case's snapshot.
+#[tauri::command(async)]
+fn timestamp_finalized_case(app: AppHandle, case_number: String, tsa_url: String) -> Result<FinalizedCase, String> {
+    let result = timestamp_case_root(&app, &case_number, &tsa_url);
+    record_activity(&app, "timestamp", Some(&case_number), &result, |record| match &record.timestamp {
+        Some(receipt) => format!("Timestamped case root {} at {}", receipt.digest, receipt.gen_time),
+        None => "Timestamped case root".to_string(),
+    });
+    result
+}
+
+/// Timestamps an export or manifest file's SHA-256 with a trusted
+/// timestamping authority and saves the token next to it as `<file>.tsr`
+#[tauri::command(async)]
+fn timestamp_file(
+    app: AppHandle,
+    file_path: String,
+    tsa_url: String,
+    case_number: Option<String>,
+) -> Result<TimestampReceipt, String> {
+    let result = validate_file_path(&file_path).and_then(|path| {
+        let hash = hashing::hash_file(&path, HashAlgorithm::Sha256).map_err(|e| AppError::Io(e).to_string_message())?;
+        let digest = timestamp::from_hex(&hash)
+            .ok_or_else(|| timestamp_error(format!("invalid SHA-256 {} for {}", hash, file_path)))?;
+        obtain_timestamp(&tsa_url, &digest, Path::new(&format!("{}.tsr", file_path)))
+    });
+    record_activity(&app, "timestamp", case_number.as_deref(), &result, |receipt| {
+        format!("Timestamped {} at {}", file_path, receipt.gen_time)
+    });
+    result
+}
+
+/// Pre-delivery quality gate: blank key fields, reused Bates stamps, missing
+/// source files and, optionally, identical files coded differently
+#[tauri::command(async)]
+fn generate_qc_report(
+    items: Vec<InventoryItem>,
+    key_fields: Option<Vec<String>>,
+    check_duplicates: Option<bool>,
+    workers: Option<usize>,
+) -> Result<QcReport, String> {
+    let key_fields = key_fields
+        .unwrap_or_else(|| qc::DEFAULT_QC_FIELDS.iter().map(|f| f.to_string()).collect());
+    if let Some(field) = key_fields.iter().find(|field| !INVENTORY_FIELDS.contains(&field.as_str())) {
+        return Err(AppError::UnknownField(field.clone()).to_string_message());
+    }
+    
+    Ok(qc::generate_qc_report(
+        &items,
+        &key_fields,
+        check_duplicates.unwrap_or(false),
+        workers.unwrap_or_else(hashing::default_workers),
+    ))
+}
+
+fn binders_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::BinderError(e.to_string()).to_string_message())
+}
+
+fn binder_error(e: Box<dyn std::error::Error>) -> String {
+    AppError::BinderError(e.to_string()).to_string_message()
+}
+
+#[tauri::command]
+fn list_binders(app: AppHandle, case_number: Option<String>) -> Result<Vec<Binder>, String> {
+    binders::list_binders(&binders_dir(&app)?, case_number.as_deref()).map_err(binder_error)
+}
+
+/// Adds files to a binder, creating it if needed. Files are the given paths
+/// plus, when `items` is passed, every item matching `filter` and `query`.
+#[tauri::command]
+fn add_to_binder(
+    app: AppHandle,
+    case_number: Option<String>,
+    name: String,
+    paths: Option<Vec<String>>,
+    items: Option<Vec<InventoryItem>>,
+    filter: Option<HashMap<String, String>>,
+    query: Option<String>,
+) -> Result<Binder, String> {
+    let mut paths = paths.unwrap_or_default();
+    if let Some(items) = items {
+        paths.extend(binders::matching_paths(&items, &filter.unwrap_or_default(), query.as_deref()));
+    }
+    
+    let binder = binders::add_to_binder(&binders_dir(&app)?, case_number.as_deref(), &name, &paths)
+        .map_err(binder_error)?;
+    notify_change(&app, BINDERS_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Updated, vec![binder.name.clone()]);
+    Ok(binder)
+}
+
+#[tauri::command]
+fn remove_from_binder(
+    app: AppHandle,
+    case_number: Option<String>,
+    name: String,
+    paths: Vec<String>,
+) -> Result<Binder, String> {
+    let binder = binders::remove_from_binder(&binders_dir(&app)?, case_number.as_deref(), &name, &paths)
+        .map_err(binder_error)?
+        .ok_or_else(|| AppError::BinderNotFound(name).to_string_message())?;
+    notify_change(&app, BINDERS_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Updated, vec![binder.name.clone()]);
+    Ok(binder)
+}
+
+#[tauri::command]
+fn delete_binder(app: AppHandle, case_number: Option<String>, name: String) -> Result<(), String> {
+    if binders::delete_binder(&binders_dir(&app)?, case_number.as_deref(), &name).map_err(binder_error)? {
+        notify_change(&app, BINDERS_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Deleted, vec![name]);
+        Ok(())
+    } else {
+        Err(AppError::BinderNotFound(name).to_string_message())
+    }
+}
+
+/// Narrows items to a binder's members, to scope search, export or review
+#[tauri::command]
+fn get_binder_items(
+    app: AppHandle,
+    case_number: Option<String>,
+    name: String,
+    items: Vec<InventoryItem>,
+) -> Result<Vec<InventoryItem>, String> {
+    let binder = binders::find_binder(&binders_dir(&app)?, case_number.as_deref(), &name)
+        .map_err(binder_error)?
+        .ok_or_else(|| AppError::BinderNotFound(name).to_string_message())?;
+    
+    Ok(binders::items_in_binder(items, &binder))
+}
+
+fn links_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::LinkError(e.to_string()).to_string_message())
+}
+
+/// The case's file links, for a report's "Related Documents" section
+fn report_links(app: &AppHandle, case_number: Option<&str>) -> Result<Vec<FileLink>, String> {
+    links::case_links(&links_dir(app)?, case_number)
+        .map_err(|e| AppError::ReportError(e.to_string()).to_string_message())
+}
+
+/// Links one file to another with a typed relationship, e.g. "signed
+/// version of". Linking the same files with the same relationship again
+/// replaces the note.
+#[tauri::command]
+fn link_files(
+    app: AppHandle,
+    case_number: Option<String>,
+    source: String,
+    target: String,
+    relationship: String,
+    note: Option<String>,
+) -> Result<FileLink, String> {
+    if source == target {
+        return Err(AppError::SelfLink(source).to_string_message());
+    }
+    let link = FileLink {
+        case_number: case_number.clone(),
+        source: source.clone(),
+        target,
+        relationship: relationship.trim().to_string(),
+        note: note.unwrap_or_default(),
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    links::add_link(&links_dir(&app)?, link.clone())
+        .map_err(|e| AppError::LinkError(e.to_string()).to_string_message())?;
+    record_link_events(&app, &link, FileEventKind::Linked);
+    notify_change(&app, LINKS_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Updated, vec![source]);
+    Ok(link)
+}
+
+#[tauri::command]
+fn unlink_files(
+    app: AppHandle,
+    case_number: Option<String>,
+    source: String,
+    target: String,
+    relationship: String,
+) -> Result<(), String> {
+    let removed = links::remove_link(&links_dir(&app)?, case_number.as_deref(), &source, &target, &relationship)
+        .map_err(|e| AppError::LinkError(e.to_string()).to_string_message())?;
+    if removed {
+        let link = FileLink {
+            case_number: case_number.clone(),
+            source: source.clone(),
+            target,
+            relationship,
+            note: String::new(),
+            created_at: String::new(),
+        };
+        record_link_events(&app, &link, FileEventKind::Unlinked);
+        notify_change(&app, LINKS_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Deleted, vec![source]);
+        Ok(())
+    } else {
+        Err(AppError::LinkNotFound(format!("{} -> {}", source, target)).to_string_message())
+    }
+}
+
+/// Everything recorded for one file in a case, oldest first: additions,
+/// removals, edits, rule and import changes, refreshes, pins and links
+#[tauri::command]
+fn get_file_history(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<Vec<FileEvent>, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::HistoryError(e.to_string()).to_string_message())?;
+    history::read_file_history(&data_dir, case_number.as_deref(), &absolute_path)
+        .map_err(|e| AppError::HistoryError(e.to_string()).to_string_message())
+}
+
+/// Records a field the user edited in the inventory table in the file's
+/// history. Edits are made in the frontend store, so it reports them here
+/// first; a value that breaks the case's field rules is refused.
+#[tauri::command]
+fn record_file_edit(
+    app: AppHandle,
+    case_number: Option<String>,
+    absolute_path: String,
+    field: String,
+    old_value: String,
+    new_value: String,
+) -> Result<(), String> {
+    if !INVENTORY_FIELDS.contains(&field.as_str()) {
+        return Err(AppError::UnknownField(field).to_string_message());
+    }
+    if let Some(problem) = validation::check_edit(&case_field_rules(&app, case_number.as_deref())?, &field, &new_value) {
+        return Err(AppError::InvalidFieldValue(field, problem).to_string_message());
+    }
+    let event = FileEvent::field_change(&absolute_path, FileEventKind::FieldChanged, "edit", &field, &old_value, &new_value);
+    record_file_events(&app, case_number.as_deref(), vec![event]);
+    Ok(())
+}
+
+/// A file whose field a bulk edit changes, with the value it had before
+#[derive(Debug, Deserialize)]
+struct BulkEditFile {
+    absolute_path: String,
+    old_value: String,
+}
+
+/// Records a bulk edit, one field set to one value across many files, in each
+/// file's history. Like `record_file_edit` it runs before the store applies
+/// the edit, and a value that breaks the case's field rules is refused.
+#[tauri::command]
+fn record_bulk_edit(
+    app: AppHandle,
+    case_number: Option<String>,
+    field: String,
+    new_value: String,
+    files: Vec<BulkEditFile>,
+) -> Result<(), String> {
+    if !INVENTORY_FIELDS.contains(&field.as_str()) {
+        return Err(AppError::UnknownField(field).to_string_message());
+    }
+    if let Some(problem) = validation::check_edit(&case_field_rules(&app, case_number.as_deref())?, &field, &new_value) {
+        return Err(AppError::InvalidFieldValue(field, problem).to_string_message());
+    }
+    let events = files
+        .iter()
+        .map(|file| FileEvent::field_change(&file.absolute_path, FileEventKind::FieldChanged, "bulk edit", &field, &file.old_value, &new_value))
+        .collect();
+    record_file_events(&app, case_number.as_deref(), events);
+    Ok(())
+}
+
+/// Records a link being made or removed in the history of both its files
+fn record_link_events(app: &AppHandle, link: &FileLink, kind: FileEventKind) {
+    let events = vec![
+        FileEvent::new(&link.source, kind, &format!("link: {} {}", link.relationship, link.target)),
+        FileEvent::new(&link.target, kind, &format!("link: {} of {}", link.relationship, link.source)),
+    ];
+    record_file_events(app, link.case_number.as_deref(), events);
+}
+
+/// Links made from a file (outbound) and to it (inbound) in a case
+#[tauri::command]
+fn get_file_links(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<FileLinks, String> {
+    links::links_for_file(&links_dir(&app)?, case_number.as_deref(), &absolute_path)
+        .map_err(|e| AppError::LinkError(e.to_string()).to_string_message())
+}
+
+/// Every file link in a case, oldest first
+#[tauri::command]
+fn list_case_links(app: AppHandle, case_number: Option<String>) -> Result<Vec<FileLink>, String> {
+    links::case_links(&links_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::LinkError(e.to_string()).to_string_message())
+}
+
+fn notes_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::NoteError(e.to_string()).to_string_message())
+}
+
+/// Adds a note on a file, or a reply to another note when `parent_id` is
+/// given. Replies belong to the parent's file, so `absolute_path` is then
+/// ignored. `@file:` references and Bates stamps in the text are resolved
+/// against `items` and saved with the note.
+#[tauri::command]
+fn add_note(
+    app: AppHandle,
+    case_number: Option<String>,
+    absolute_path: String,
+    parent_id: Option<u64>,
+    author: Option<String>,
+    content: String,
+    items: Option<Vec<InventoryItem>>,
+) -> Result<FileNote, String> {
+    let target = match parent_id {
+        Some(parent_id) => NoteTarget::Reply(parent_id),
+        None => NoteTarget::File(absolute_path),
+    };
+    let mentions = notes::resolve_mentions(&content, &items.unwrap_or_default());
+    let author = author.unwrap_or_default();
+    let note = notes::add_note(&notes_dir(&app)?, case_number.as_deref(), target, &author, &content, mentions)
+        .map_err(|e| AppError::NoteError(e.to_string()).to_string_message())?
+        .ok_or_else(|| AppError::NoteNotFound(parent_id.unwrap_or_default()).to_string_message())?;
+    
+    notify_change(&app, NOTES_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Created, vec![note.id.to_string()]);
+    Ok(note)
+}
+
+/// Notes in a case, oldest first, with their reply counts. With
+/// `absolute_path` only the notes on that file are listed.
+#[tauri::command]
+fn list_notes(app: AppHandle, case_number: Option<String>, absolute_path: Option<String>) -> Result<Vec<ListedNote>, String> {
+    notes::list_notes(&notes_dir(&app)?, case_number.as_deref(), absolute_path.as_deref())
+        .map_err(|e| AppError::NoteError(e.to_string()).to_string_message())
+}
+
+/// Notes in a case that mention a file, oldest first
+#[tauri::command]
+fn notes_mentioning_file(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<Vec<ListedNote>, String> {
+    notes::notes_mentioning(&notes_dir(&app)?, case_number.as_deref(), &absolute_path)
+        .map_err(|e| AppError::NoteError(e.to_string()).to_string_message())
+}
+
+/// Marks a note resolved, or open again with `resolved: false`
+#[tauri::command]
+fn resolve_note(app: AppHandle, case_number: Option<String>, id: u64, resolved: bool) -> Result<(), String> {
+    let found = notes::set_resolved(&notes_dir(&app)?, case_number.as_deref(), id, resolved)
+        .map_err(|e| AppError::NoteError(e.to_string()).to_string_message())?;
+    if !found {
+        return Err(AppError::NoteNotFound(id).to_string_message());
+    }
+    
+    notify_change(&app, NOTES_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Updated, vec![id.to_string()]);
+    Ok(())
+}
+
+/// Removes a note together with its replies
+#[tauri::command]
+fn remove_note(app: AppHandle, case_number: Option<String>, id: u64) -> Result<(), String> {
+    let removed = notes::remove_note(&notes_dir(&app)?, case_number.as_deref(), id)
+        .map_err(|e| AppError::NoteError(e.to_string()).to_string_message())?;
+    if removed.is_empty() {
+        return Err(AppError::NoteNotFound(id).to_string_message());
+    }
+    
+    let ids = removed.iter().map(|id| id.to_string()).collect();
+    notify_change(&app, NOTES_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Deleted, ids);
+    Ok(())
+}
+
+fn tools_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::ToolError(e.to_string()).to_string_message())
+}
+
+/// Adds a "send to tool" action, or replaces the one with the same id
+#[tauri::command]
+fn save_external_tool(app: AppHandle, tool: ExternalTool) -> Result<(), String> {
+    if tool.id.trim().is_empty() || tool.program.trim().is_empty() {
+        return Err(AppError::ToolError("a tool needs an id and a program".to_string()).to_string_message());
+    }
+    tools::save_tool(&tools_dir(&app)?, tool).map_err(|e| AppError::ToolError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn list_external_tools(app: AppHandle) -> Result<Vec<ExternalTool>, String> {
+    tools::list_tools(&tools_dir(&app)?).map_err(|e| AppError::ToolError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn remove_external_tool(app: AppHandle, id: String) -> Result<(), String> {
+    let removed = tools::remove_tool(&tools_dir(&app)?, &id)
+        .map_err(|e| AppError::ToolError(e.to_string()).to_string_message())?;
+    if !removed {
+        return Err(AppError::ToolNotFound(id).to_string_message());
+    }
+    Ok(())
+}
+
+/// Runs a stored tool on one of the case's files and keeps what it printed
+/// as a note on the file
+#[tauri::command(async)]
+fn run_external_tool(
+    app: AppHandle,
+    case_number: Option<String>,
+    absolute_path: String,
+    tool_id: String,
+    author: Option<String>,
+) -> Result<FileNote, String> {
+    let path = PathBuf::from(&absolute_path);
+    ensure_case_source(&app, case_number.as_deref(), &path)?;
+    let tool = tools::find_tool(&tools_dir(&app)?, &tool_id)
+        .map_err(|e| AppError::ToolError(e.to_string()).to_string_message())?
+        .ok_or_else(|| AppError::ToolNotFound(tool_id.clone()).to_string_message())?;
+    
+    let args = tools::expand_args(&tool, &path).map_err(|e| AppError::ToolError(e.to_string()).to_string_message())?;
+    let output = tools::run_tool(&tool, &args)
+        .map_err(|e| AppError::ToolError(format!("{}: {}", tool.program, e)).to_string_message())?;
+    let author = author.unwrap_or_else(|| tool.name.clone());
+    let content = output.note_text(&tool, &args);
+    let note = notes::add_note(&notes_dir(&app)?, case_number.as_deref(), NoteTarget::File(absolute_path), &author, &content, Vec::new())
+        .map_err(|e| AppError::NoteError(e.to_string()).to_string_message())?
+        .ok_or_else(|| AppError::NoteError("the note could not be added".to_string()).to_string_message())?;
+    
+    notify_change(&app, NOTES_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Created, vec![note.id.to_string()]);
+    Ok(note)
+}
+
+fn pins_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::PinError(e.to_string()).to_string_message())
+}
+
+/// Pins or unpins a file for a case; returns whether it is now pinned
+#[tauri::command]
+fn toggle_file_pinned(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<bool, String> {
+    let pinned = pins::toggle_pinned(&pins_dir(&app)?, case_number.as_deref(), &absolute_path)
+        .map_err(|e| AppError::PinError(e.to_string()).to_string_message())?;
+    let change = if pinned { ChangeKind::Created } else { ChangeKind::Deleted };
+    let kind = if pinned { FileEventKind::Pinned } else { FileEventKind::Unpinned };
+    record_file_events(&app, case_number.as_deref(), vec![FileEvent::new(&absolute_path, kind, "pin")]);
+    notify_change(&app, PINS_CHANGED_EVENT, case_number.as_deref(), change, vec![absolute_path]);
+    Ok(pinned)
+}
+
+/// Pinned files for a case. With `items`, returns only the pinned items
+/// among them (the pinned filter); otherwise returns every pinned path.
+#[tauri::command]
+fn get_pinned_files(
+    app: AppHandle,
+    case_number: Option<String>,
+    items: Option<Vec<InventoryItem>>,
+) -> Result<Vec<String>, String> {
+    let pinned = pins::pinned_paths(&pins_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::PinError(e.to_string()).to_string_message())?;
+    
+    Ok(match items {
+        Some(items) => items
+            .into_iter()
+            .map(|item| item.absolute_path)
+            .filter(|path| pinned.contains(path))
+            .collect(),
+        None => pinned.into_iter().collect(),
+    })
+}
+
+fn recents_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::RecentsError(e.to_string()).to_string_message())
+}
+
+/// Records that a file was opened for review, for the case's recents list
+/// and per-day review counts. Called alongside opening the file.
+#[tauri::command]
+fn record_file_opened(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<(), String> {
+    let opened_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    recents::record_file_opened(&recents_dir(&app)?, case_number.as_deref(), &absolute_path, &opened_at)
+        .map_err(|e| AppError::RecentsError(e.to_string()).to_string_message())
+}
+
+/// Files recently opened in a case, newest first, for "continue where I
+/// left off"
+#[tauri::command]
+fn get_recent_files(app: AppHandle, case_number: Option<String>, limit: Option<usize>) -> Result<Vec<RecentFile>, String> {
+    recents::recent_files(&recents_dir(&app)?, case_number.as_deref(), limit.unwrap_or(20))
+        .map_err(|e| AppError::RecentsError(e.to_string()).to_string_message())
+}
+
+/// Files opened per day in a case and the last file opened
+#[tauri::command]
+fn get_review_stats(app: AppHandle, case_number: Option<String>) -> Result<ReviewStats, String> {
+    recents::review_stats(&recents_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::RecentsError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn read_keyword_list(file_path: String) -> Result<Vec<String>, String> {
+    validate_file_path(&file_path)?;
+    
+    keywords::read_keyword_list(&file_path)
+        .map_err(|e| AppError::Io(e).to_string_message())
+}
+
+fn keyword_lists_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::KeywordListError(e.to_string()).to_string_message())
+}
+
+/// Saves a keyword list with its case, replacing one with the same name
+#[tauri::command]
+fn save_keyword_list(app: AppHandle, list: KeywordList) -> Result<(), String> {
+    keywords::save_keyword_list(&keyword_lists_dir(&app)?, list)
+        .map_err(|e| AppError::KeywordListError(e.to_string()).to_string_message())
+}
+
+/// Reads a keyword list file and saves it with the case under `name`
+#[tauri::command]
+fn import_keyword_list(
+    app: AppHandle,
+    case_number: Option<String>,
+    name: String,
+    file_path: String,
+) -> Result<KeywordList, String> {
+    validate_file_path(&file_path)?;
+    let terms = keywords::read_keyword_list(&file_path)
+        .map_err(|e| AppError::Io(e).to_string_message())?;
+    let list = KeywordList { name, case_number, terms };
+    
+    keywords::save_keyword_list(&keyword_lists_dir(&app)?, list.clone())
+        .map_err(|e| AppError::KeywordListError(e.to_string()).to_string_message())?;
+    Ok(list)
+}
+
+#[tauri::command]
+fn list_keyword_lists(app: AppHandle, case_number: Option<String>) -> Result<Vec<KeywordList>, String> {
+    keywords::case_keyword_lists(&keyword_lists_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::KeywordListError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn remove_keyword_list(app: AppHandle, case_number: Option<String>, name: String) -> Result<(), String> {
+    let removed = keywords::remove_keyword_list(&keyword_lists_dir(&app)?, case_number.as_deref(), &name)
+        .map_err(|e| AppError::KeywordListError(e.to_string()).to_string_message())?;
+    if removed {
+        Ok(())
+    } else {
+        Err(AppError::KeywordListNotFound(name).to_string_message())
+    }
+}
+
+/// Computes per-term and per-document hits for one of the case's saved
+/// keyword lists, optionally writing them to XLSX
+#[tauri::command(async)]
+fn run_keyword_report(
+    app: AppHandle,
+    case_number: Option<String>,
+    list_name: String,
+    items: Vec<InventoryItem>,
+    output_path: Option<String>,
+) -> Result<KeywordReport, String> {
+    let list = configs::find_keyword_list(&keyword_lists_dir(&app)?, case_number.as_deref(), &list_name)
+        .map_err(|e| AppError::KeywordListError(e.to_string()).to_string_message())?
+        .ok_or_else(|| AppError::KeywordListNotFound(list_name).to_string_message())?;
+    let report = keywords::run_keyword_report(&items, &list.terms);
+    
+    if let Some(output_path) = output_path {
+        keywords::write_keyword_report_xlsx(&report, &output_path)
+            .map_err(|e| AppError::XlsxError(e.to_string()).to_string_message())?;
+    }
+    
+    Ok(report)
+}
+
+/// Groups identical files and writes the groups, with primary/duplicate status
+/// and wasted space, to an XLSX or CSV report (format from the extension
+/// unless given)
+#[tauri::command(async)]
+fn export_duplicates_report(
+    items: Vec<InventoryItem>,
+    output_path: String,
+    format: Option<String>,
+    workers: Option<usize>,
+    path_case: Option<PathCase>,
+) -> Result<DuplicatesReport, String> {
+    let format = format.unwrap_or_else(|| {
+        Path::new(&output_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .unwrap_or_else(|| "xlsx".to_string())
+    });
+    if format != "xlsx" && format != "csv" {
+        return Err(AppError::UnsupportedFormat(format).to_string_message());
+    }
+    
+    let case_insensitive = items_case_insensitive(&items, path_case);
+    let report = duplicates::find_duplicates(&items, workers.unwrap_or_else(hashing::default_workers), case_insensitive);
+    if format == "csv" {
+        duplicates::write_duplicates_csv(&report, &output_path)
+            .map_err(|e| AppError::CsvError(e.to_string()).to_string_message())?;
+    } else {
+        duplicates::write_duplicates_xlsx(&report, &output_path)
+            .map_err(|e| AppError::XlsxError(e.to_string()).to_string_message())?;
+    }
+    
+    Ok(report)
+}
+
+/// Resolves how the items' paths compare, probing the volume of the first
+/// item when `path_case` is Auto or unset
+fn items_case_insensitive(items: &[InventoryItem], path_case: Option<PathCase>) -> bool {
+    let probe = items
+        .iter()
+        .map(|item| item.absolute_path.as_str())
+        .find(|path| !path.is_empty())
+        .unwrap_or_default();
+    path_case.unwrap_or_default().is_insensitive(Path::new(probe))
+}
+
+fn dedup_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::DedupError(e.to_string()).to_string_message())
+}
+
+fn dedup_error(e: Box<dyn std::error::Error>) -> String {
+    AppError::DedupError(e.to_string()).to_string_message()
+}
+
+/// Drops duplicates the case's dedup policy suppresses, unless
+/// `include_suppressed` asks for them. Views, counts, reports and exports all
+/// go through this. A policy that cannot be read is an error, rather than
+/// quietly putting suppressed copies back into a production.
+fn in_review_scope(
+    app: &AppHandle,
+    case_number: Option<&str>,
+    items: Vec<InventoryItem>,
+    include_suppressed: bool,
+) -> Result<Vec<InventoryItem>, String> {
+    if include_suppressed {
+        return Ok(items);
+    }
+    let policy = dedup::read_policy(&dedup_dir(app)?, case_number).map_err(dedup_error)?;
+    Ok(policy.in_review_scope(items))
+}
+
+/// Finds duplicates among the case's items and suppresses every copy but the
+/// primary from review scope. Reviewer choices from earlier runs are kept.
+#[tauri::command(async)]
+fn apply_dedup_policy(
+    app: AppHandle,
+    case_number: String,
+    items: Vec<InventoryItem>,
+    workers: Option<usize>,
+    path_case: Option<PathCase>,
+) -> Result<DedupPolicy, String> {
+    let case_insensitive = items_case_insensitive(&items, path_case);
+    let report = duplicates::find_duplicates(&items, workers.unwrap_or_else(hashing::default_workers), case_insensitive);
+    let policy = dedup::apply_policy(&dedup_dir(&app)?, Some(&case_number), &report, case_insensitive)
+        .map_err(dedup_error)?;
+    notify_change(
+        &app,
+        DEDUP_CHANGED_EVENT,
+        Some(&case_number),
+        ChangeKind::Updated,
+        policy.suppressed.iter().cloned().collect(),
+    );
+    Ok(policy)
+}
+
+#[tauri::command]
+fn get_dedup_policy(app: AppHandle, case_number: Option<String>) -> Result<DedupPolicy, String> {
+    dedup::read_policy(&dedup_dir(&app)?, case_number.as_deref()).map_err(dedup_error)
+}
+
+/// Makes a file the primary of its duplicate group, suppressing the others
+#[tauri::command]
+fn set_duplicate_primary(
+    app: AppHandle,
+    case_number: String,
+    hash: String,
+    absolute_path: String,
+) -> Result<DedupPolicy, String> {
+    let policy = dedup::set_primary(&dedup_dir(&app)?, Some(&case_number), &hash, &absolute_path)
+        .map_err(dedup_error)?
+        .ok_or_else(|| AppError::NotInDuplicateGroup(absolute_path).to_string_message())?;
+    let members = policy.groups.get(&hash).cloned().unwrap_or_default();
+    notify_change(&app, DEDUP_CHANGED_EVENT, Some(&case_number), ChangeKind::Updated, members);
+    Ok(policy)
+}
+
+/// Re-includes suppressed duplicates in review scope (`included: true`) or
+/// suppresses them again
+#[tauri::command]
+fn set_duplicates_included(
+    app: AppHandle,
+    case_number: String,
+    paths: Vec<String>,
+    included: bool,
+) -> Result<DedupPolicy, String> {
+    let policy = dedup::set_included(&dedup_dir(&app)?, Some(&case_number), &paths, included)
+        .map_err(dedup_error)?;
+    notify_change(&app, DEDUP_CHANGED_EVENT, Some(&case_number), ChangeKind::Updated, paths);
+    Ok(policy)
+}
+
+/// Removes the case's dedup policy so every file is back in review scope
+#[tauri::command]
+fn clear_dedup_policy(app: AppHandle, case_number: String) -> Result<(), String> {
+    let data_dir = dedup_dir(&app)?;
+    let previous = dedup::read_policy(&data_dir, Some(&case_number)).map_err(dedup_error)?;
+    dedup::clear_policy(&data_dir, Some(&case_number)).map_err(dedup_error)?;
+    notify_change(
+        &app,
+        DEDUP_CHANGED_EVENT,
+        Some(&case_number),
+        ChangeKind::Deleted,
+        previous.suppressed.into_iter().collect(),
+    );
+    Ok(())
+}
+
+/// Profiles the spreadsheets among `paths` (sheet names, sizes, header rows).
+/// With a query, only workbooks whose sheet names or headers contain it are
+/// returned, e.g. the one with a "Wire Transfers" sheet.
+#[tauri::command(async)]
+fn profile_spreadsheets(paths: Vec<String>, query: Option<String>) -> Vec<SpreadsheetProfile> {
+    let query = query.map(|q| q.trim().to_lowercase()).filter(|q| !q.is_empty());
+    
+    paths
+        .iter()
+        .filter(|path| profiling::is_spreadsheet(path))
+        .map(|path| profiling::profile_spreadsheet(path))
+        .filter(|profile| query.as_deref().is_none_or(|q| profiling::profile_matches(profile, q)))
+        .collect()
+}
+
+/// Lists tables and row counts of the database files (SQLite, recognized by
+/// header; Access files are reported as unsupported) among `paths`. Files are
+/// opened immutable so nothing is written beside them, and each profiled file
+/// is recorded in the activity log as evidence introspection.
+#[tauri::command(async)]
+fn profile_databases(
+    app: AppHandle,
+    paths: Vec<String>,
+    case_number: Option<String>,
+) -> Vec<DatabaseProfile> {
+    let profiles: Vec<DatabaseProfile> = paths
+        .iter()
+        .filter(|path| profiling::is_database(path))
+        .map(|path| profiling::profile_database(path))
+        .collect();
+    
+    if !profiles.is_empty() {
+        let result: Result<&[DatabaseProfile], String> = Ok(&profiles);
+        record_activity(&app, "evidence_introspection", case_number.as_deref(), &result, |profiles| {
+            let mut description = format!("Read-only profile of {} database files", profiles.len());
+            for profile in profiles.iter() {
+                match &profile.error {
+                    Some(e) => description.push_str(&format!("\n{} (failed: {})", profile.path, e)),
+                    None => description.push_str(&format!("\n{} ({} tables)", profile.path, profile.tables.len())),
+                }
+            }
+            description
+        });
+    }
+    profiles
+}
+
+/// Extracts page visits and downloads from the Chrome and Firefox history
+/// databases among `paths`, as dated events that reference their source file.
+/// Files are opened read-only and the run is logged as evidence introspection.
+#[tauri::command(async)]
+fn parse_browser_artifacts(
+    app: AppHandle,
+    paths: Vec<String>,
+    case_number: Option<String>,
+) -> Vec<BrowserArtifact> {
+    let artifacts: Vec<BrowserArtifact> = paths
+        .iter()
+        .filter_map(|path| artifacts::parse_browser_history(path))
+        .collect();
+    
+    if !artifacts.is_empty() {
+        let result: Result<&[BrowserArtifact], String> = Ok(&artifacts);
+        record_activity(&app, "evidence_introspection", case_number.as_deref(), &result, |artifacts| {
+            let mut description = format!("Parsed {} browser history files", artifacts.len());
+            for artifact in artifacts.iter() {
+                match &artifact.error {
+                    Some(e) => description.push_str(&format!("\n{} (failed: {})", artifact.path, e)),
+                    None => description.push_str(&format!("\n{} ({} events)", artifact.path, artifact.events.len())),
+                }
+            }
+            description
+        });
+    }
+    artifacts
+}
+
+/// Reads target path, target timestamps and volume details from the Windows
+/// shortcuts (.lnk) among `paths`, e.g. from a produced user profile's
+/// Recent folder
+#[tauri::command(async)]
+fn parse_shortcuts(paths: Vec<String>) -> Vec<ShortcutInfo> {
+    paths
+        .iter()
+        .filter_map(|path| artifacts::parse_shortcut(path))
+        .collect()
+}
+
+/// Hashes files on a bounded worker pool; runs off the main thread
+#[tauri::command(async)]
+fn hash_files(
+    paths: Vec<String>,
+    algorithm: Option<HashAlgorithm>,
+    workers: Option<usize>,
+) -> Vec<FileHash> {
+    hashing::hash_files_parallel(
+        &paths,
+        algorithm.unwrap_or_default(),
+        workers.unwrap_or_else(hashing::default_workers),
+    )
+}
+
+/// Hashes files like `hash_files`, but measures each source's read rate and
+/// drops to fewer workers on slow sources such as network shares
+#[tauri::command(async)]
+fn hash_files_tuned(
+    paths: Vec<String>,
+    algorithm: Option<HashAlgorithm>,
+    workers: Option<usize>,
+) -> HashReport {
+    hashing::hash_files_tuned(
+        &paths,
+        algorithm.unwrap_or_default(),
+        workers.unwrap_or_else(hashing::default_workers),
+    )
+}
+
+/// Checks which hashes (given directly and/or read from a production log or
+/// hash manifest) match files among `paths`, e.g. to confirm what opposing
+/// counsel produced is already in hand
+#[tauri::command(async)]
+fn lookup_hashes(
+    paths: Vec<String>,
+    hashes: Option<Vec<String>>,
+    manifest_path: Option<String>,
+    workers: Option<usize>,
+) -> Result<HashLookup, String> {
+    lookup_manifest_hashes(&paths, hashes.unwrap_or_default(), manifest_path.as_deref(), workers)
+}
+
+/// Reconciles an acquisition tool's hash manifest against the files in `folder_path`
+#[tauri::command(async)]
+fn reconcile_hash_manifest(
+    manifest_path: String,
+    folder_path: String,
+    workers: Option<usize>,
+    path_case: Option<PathCase>,
+) -> Result<ManifestReconciliation, String> {
+    reconcile_manifest_file(&manifest_path, &folder_path, workers, path_case.unwrap_or_default())
+}
+
+/// Computes deadlines and derived dates, optionally counting business days only
+#[tauri::command]
+fn compute_date_offsets(
+    offsets: Vec<DateOffset>,
+    holidays: Option<Vec<String>>,
+) -> Result<Vec<ComputedDate>, String> {
+    dates::compute_date_offsets(&offsets, &holidays.unwrap_or_default())
+        .map_err(|bad| AppError::InvalidDate(bad).to_string_message())
+}
+
+/// Statement period found in an item's file name
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemPeriod {
+    pub absolute_path: String,
+    pub file_name: String,
+    pub period: StatementPeriod,
+}
+
+/// Extracts structured statement periods (start/end dates) from item file
+/// names, trying the given period kinds in order; items without one are omitted
+#[tauri::command]
+fn extract_statement_periods(
+    items: Vec<InventoryItem>,
+    kinds: Option<Vec<PeriodKind>>,
+) -> Vec<ItemPeriod> {
+    let kinds = kinds.unwrap_or_else(|| DEFAULT_PERIOD_KINDS.to_vec());
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let period = mappings::extract_period(&item.file_name, &kinds)?;
+            Some(ItemPeriod {
+                absolute_path: item.absolute_path,
+                file_name: item.file_name,
+                period,
+            })
+        })
+        .collect()
+}
+
+/// Folder under app data holding each case's managed loose documents
+const LOOSE_DOCUMENTS_DIR: &str = "loose-documents";
+
+/// Returns (creating it if needed) the managed folder for documents dropped or
+/// pasted into a case. It lives under `base_dir` when given, otherwise under
+/// the app data directory, and can be scanned and synced like any source folder.
+fn loose_documents_folder(app: &AppHandle, case_number: &str, base_dir: Option<&str>) -> Result<PathBuf, String> {
+    let base = match base_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::ScanError(e.to_string()).to_string_message())?
+            .join(LOOSE_DOCUMENTS_DIR),
+    };
+    let folder = base.join(export::sanitize_file_name(case_number.trim()));
+    std::fs::create_dir_all(&folder).map_err(|e| AppError::Io(e).to_string_message())?;
+    Ok(folder)
+}
+
+#[tauri::command]
+fn get_loose_documents_folder(
+    app: AppHandle,
+    case_number: String,
+    base_dir: Option<String>,
+) -> Result<String, String> {
+    loose_documents_folder(&app, &case_number, base_dir.as_deref())
+        .map(|folder| folder.to_string_lossy().to_string())
+}
+
+/// Copies a document into the case's loose documents folder and ingests the copy
+#[tauri::command(async)]
+fn add_loose_document(
+    app: AppHandle,
+    case_number: String,
+    file_path: String,
+    base_dir: Option<String>,
+) -> Result<IngestedFile, String> {
+    let started = Instant::now();
+    let result = loose_documents_folder(&app, &case_number, base_dir.as_deref()).and_then(|folder| {
+        ingest_file(&file_path, Some(&folder.to_string_lossy()), None)
+    });
+    let result = apply_rules_to_ingested(&app, &case_number, result);
+    record_metric(&app, "ingest", Some(&case_number), started, &result, |_| 1);
+    record_activity(&app, "ingest", Some(&case_number), &result, |ingested| {
+        format!(
+            "Added {} to loose documents",
+            ingested.copied_to.as_deref().unwrap_or(&ingested.item.absolute_path)
+        )
+    });
+    grant_ingested(&app, Some(&case_number), &result);
+    notify_ingested(&app, Some(&case_number), &result);
+    result
+}
+
+/// Saves captured clipboard content (text or a PNG image read by the
+/// frontend) as a timestamped file in the case's loose documents folder and
+/// ingests it, optionally with a note on the new item
+#[tauri::command(async)]
+fn capture_clipboard_to_case(
+    app: AppHandle,
+    case_number: String,
+    text: Option<String>,
+    image_png: Option<Vec<u8>>,
+    note: Option<String>,
+) -> Result<IngestedFile, String> {
+    let result = loose_documents_folder(&app, &case_number, None).and_then(|folder| {
+        let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let (name, bytes) = match (image_png, text) {
+            (Some(image), _) => (format!("clipboard_{}.png", stamp), image),
+            (None, Some(text)) => (format!("clipboard_{}.txt", stamp), text.into_bytes()),
+            (None, None) => {
+                return Err(AppError::UnsupportedFormat("empty clipboard".to_string()).to_string_message())
+            }
+        };
+        let destination = export::resolve_export_path(&folder, &name, CollisionPolicy::Increment)
+            .map_err(|path| AppError::ExportExists(path).to_string_message())?;
+        std::fs::write(&destination, bytes).map_err(|e| AppError::Io(e).to_string_message())?;
+        
+        let mut ingested = ingest_file(&destination.to_string_lossy(), None, None)?;
+        ingested.copied_to = Some(destination.to_string_lossy().to_string());
+        if let Some(note) = note {
+            ingested.item.notes = note;
+        }
+        Ok(ingested)
+    });
+    let result = apply_rules_to_ingested(&app, &case_number, result);
+    record_activity(&app, "ingest", Some(&case_number), &result, |ingested| {
+        format!("Captured clipboard to {}", ingested.item.absolute_path)
+    });
+    grant_ingested(&app, Some(&case_number), &result);
+    notify_ingested(&app, Some(&case_number), &result);
+    result
+}
+
+/// Adds a single drag-and-dropped document without scanning a folder. With
+/// the case's `source_folder`, a file inside it gets the same folder path a
+/// scan would give it.
+#[tauri::command(async)]
+fn ingest_single_file(
+    app: AppHandle,
+    file_path: String,
+    copy_to: Option<String>,
+    case_number: String,
+    source_folder: Option<String>,
+) -> Result<IngestedFile, String> {
+    let started = Instant::now();
+    let result = ingest_file(&file_path, copy_to.as_deref(), source_folder.as_deref());
+    let result = apply_rules_to_ingested(&app, &case_number, result);
+    record_metric(&app, "ingest", Some(&case_number), started, &result, |_| 1);
+    record_activity(&app, "ingest", Some(&case_number), &result, |ingested| {
+        format!("Added {}", ingested.item.absolute_path)
+    });
+    grant_ingested(&app, Some(&case_number), &result);
+    notify_ingested(&app, Some(&case_number), &result);
+    result
+}
+
+/// Announces an added file and records it in the file's history
+fn notify_ingested(app: &AppHandle, case_number: Option<&str>, result: &Result<IngestedFile, String>) {
+    if let Ok(ingested) = result {
+        record_file_events(app, case_number, vec![FileEvent::new(&ingested.item.absolute_path, FileEventKind::Added, "ingest")]);
+        vault_if_enabled(app, case_number, &[&ingested.item.absolute_path]);
+        notify_change(app, FILES_CHANGED_EVENT, case_number, ChangeKind::Created, vec![ingested.item.absolute_path.clone()]);
+    }
+}
+
+/// Makes an added file, and its copy if one was made, previewable in the case
+fn grant_ingested(app: &AppHandle, case_number: Option<&str>, result: &Result<IngestedFile, String>) {
+    if let Ok(ingested) = result {
+        let mut paths = vec![ingested.item.absolute_path.as_str()];
+        paths.extend(ingested.copied_to.as_deref());
+        grant_sources(app, case_number, result, &paths);
+    }
+}
+
+/// Renames source files on disk to a pattern such as
+/// `{doc_year}_{document_type}_{bates_stamp}`. Defaults to a dry run; the
+/// returned items carry the new paths and names when applied.
+#[tauri::command(async)]
+fn rename_files(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    pattern: String,
+    dry_run: Option<bool>,
+    case_number: String,
+) -> Result<RenameReport, String> {
+    let dry_run = dry_run.unwrap_or(true);
+    let result = organize::rename_items(items, &pattern, dry_run)
+        .map_err(|placeholder| AppError::InvalidFilenamePattern(placeholder).to_string_message());
+    if !dry_run {
+        record_activity(&app, "rename", Some(&case_number), &result, |report| {
+            let mut description = format!("Renamed {} files", report.renamed.len());
+            for file in &report.renamed {
+                description.push_str(&format!("\n{} -> {}", file.old_path, file.new_name));
+            }
+            description
+        });
+        if let Ok(report) = &result {
+            let old_paths = report.renamed.iter().map(|file| file.old_path.clone()).collect();
+            let new_paths = report.renamed.iter().map(|file| file.new_path.clone()).collect();
+            notify_change(&app, FILES_CHANGED_EVENT, Some(&case_number), ChangeKind::Deleted, old_paths);
+            notify_change(&app, FILES_CHANGED_EVENT, Some(&case_number), ChangeKind::Created, new_paths);
+        }
+    }
+    result
+}
+
+/// Proposes a new folder layout (e.g. `{doc_year}/{document_type}` under a
+/// target root) without touching any files
+#[tauri::command(async)]
+fn plan_reorganization(
+    items: Vec<InventoryItem>,
+    rules: ReorganizationRules,
+) -> Result<ReorganizationPlan, String> {
+    organize::plan_reorganization(&items, &rules)
+        .map_err(|placeholder| AppError::InvalidFilenamePattern(placeholder).to_string_message())
+}
+
+/// Copies or moves files into the planned layout and returns the items
+/// re-pointed at their new locations
+#[tauri::command(async)]
+fn apply_reorganization(
+    app: AppHandle,
+    items: Vec<InventoryItem>,
+    rules: ReorganizationRules,
+    case_number: String,
+) -> Result<ReorganizationReport, String> {
+    let result = organize::apply_reorganization(items, &rules)
+        .map_err(|placeholder| AppError::InvalidFilenamePattern(placeholder).to_string_message());
+    grant_sources(&app, Some(&case_number), &result, &[&rules.target_root]);
+    record_activity(&app, "reorganize", Some(&case_number), &result, |report| {
+        format!(
+            "Reorganized {} files into {} ({} failed)",
+            report.moved.len(),
+            rules.target_root,
+            report.failed.len()
+        )
+    });
+    if let Ok(report) = &result {
+        // Copies leave the originals in place
+        if rules.mode == TransferMode::Move {
+            let old_paths = report.moved.iter().map(|planned| planned.source_path.clone()).collect();
+            notify_change(&app, FILES_CHANGED_EVENT, Some(&case_number), ChangeKind::Deleted, old_paths);
+        }
+        let new_paths = report.moved.iter().map(|planned| planned.target_path.clone()).collect();
+        notify_change(&app, FILES_CHANGED_EVENT, Some(&case_number), ChangeKind::Created, new_paths);
+    }
+    result
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub items: Vec<InventoryItem>,
+    pub case_number: Option<String>,
+    pub folder_path: Option<String>,
+}
+
+#[tauri::command]
+fn import_inventory(
+    file_path: String,
+    format: Option<String>,
+) -> Result<ImportResult, String> {
+    // Detect format from file extension if not provided
+    let detected_format = format.unwrap_or_else(|| {
+        let path = PathBuf::from(&file_path);
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .unwrap_or_else(|| "xlsx".to_string())
+    });
+    
+    let (rows, case_number, folder_path) = match detected_format.as_str() {
+        "xlsx" => read_xlsx(&file_path)
+            .map_err(|e| AppError::ReadXlsxError(e.to_string()).to_string_message())?,
+        "csv" => read_csv(&file_path)
+            .map_err(|e| AppError::ReadCsvError(e.to_string()).to_string_message())?,
+        "json" => read_json(&file_path)
+            .map_err(|e| AppError::ReadJsonError(e.to_string()).to_string_message())?,
+        _ => return Err(AppError::UnsupportedFormat(detected_format).to_string_message()),
+    };
+    
+    // Convert InventoryRow to InventoryItem (with empty absolute_path)
+    let items: Vec<InventoryItem> = rows
+        .into_iter()
+        .map(|row| InventoryItem {
+            date_rcvd: row.date_rcvd,
+            doc_year: row.doc_year,
+            doc_date_range: row.doc_date_range,
+            document_type: row.document_type,
+            document_description: row.document_description,
+            file_name: row.file_name,
+            folder_name: row.folder_name,
+            folder_path: row.folder_path,
+            // Inventories exported before categories existed derive it from the type
+            file_category: if row.file_category.is_empty() {
+                categories::category_for_type(&row.file_type).to_string()
+            } else {
+                row.file_category
+            },
+            file_type: row.file_type,
+            bates_stamp: row.bates_stamp,
+            notes: row.notes,
+            absolute_path: String::new(), // Not exported, so empty
+        })
+        .collect();
+    
+    Ok(ImportResult {
+        items,
+        case_number,
+        folder_path,
+    })
+}
+
+/// Syncs a folder without a case too (the main window's baseline sync);
+/// when a case number is given, a finalized case is refused. `options` are
+/// the case's scan options, so files a scan would skip or flag are skipped
+/// or flagged here as well. Unreadable files and folders go to the retry
+/// queue and their existing items are kept. The case's stored automation
+/// rules run on the files the sync brings in.
+#[tauri::command]
+fn sync_inventory(
+    app: AppHandle,
+    folder_path: String,
+    existing_items: Vec<InventoryItem>,
+    case_number: Option<String>,
+    path_case: Option<PathCase>,
+    force: Option<bool>,
+    options: Option<ScanOptions>,
+) -> Result<Vec<InventoryItem>, String> {
+    let case_number = case_number.filter(|case_number| !case_number.trim().is_empty());
+    let case_insensitive = path_case.unwrap_or_default().is_insensitive(Path::new(&folder_path));
+    let known: HashMap<String, String> = existing_items
+        .iter()
+        .map(|item| (path_key(&item.absolute_path, case_insensitive), item.absolute_path.clone()))
+        .collect();
+    let started = Instant::now();
+    let result = sync_inventory_items(
+        folder_path.clone(),
+        existing_items,
+        case_insensitive,
+        force.unwrap_or(false),
+        &options.unwrap_or_default(),
+    )
+    .map(|(items, failed)| {
+        if let Ok(data_dir) = retry_queue_dir(&app) {
+            let _ = retry_queue::record_attempts(&data_dir, case_number.as_deref(), &folder_path, &failed, &[]);
+        }
+        apply_synced_changes(&app, case_number.as_deref(), &known, case_insensitive, items)
+    });
+    grant_sources(&app, case_number.as_deref(), &result, &[&folder_path]);
+    record_metric(&app, "sync", case_number.as_deref(), started, &result, |items| items.len());
+    record_activity(&app, "sync", case_number.as_deref(), &result, |items| {
+        format!("Synced {} ({} files)", folder_path, items.len())
+    });
+    result
+}
+
+/// After a sync: runs the case's rules on the files it brought in, records
+/// and announces added and removed files, and checks watched searches.
+/// `known` maps path keys of the items before the sync to their paths.
+fn apply_synced_changes(
+    app: &AppHandle,
+    case_number: Option<&str>,
+    known: &HashMap<String, String>,
+    case_insensitive: bool,
+    mut items: Vec<InventoryItem>,
+) -> Vec<InventoryItem> {
+    // The case's stored rules triage the files the sync brought in
+    let positions: Vec<usize> = (0..items.len())
+        .filter(|i| !known.contains_key(&path_key(&items[*i].absolute_path, case_insensitive)))
+        .collect();
+    let added = positions
+        .iter()
+        .map(|i| FileEvent::new(&items[*i].absolute_path, FileEventKind::Added, "sync"))
+        .collect();
+    record_file_events(app, case_number, added);
+    let new_items: Vec<InventoryItem> = positions.iter().map(|i| items[*i].clone()).collect();
+    for (position, item) in positions.iter().zip(apply_case_rules(app, case_number, new_items)) {
+        items[*position] = item;
+    }
+    let new_items: Vec<InventoryItem> = positions.iter().map(|i| items[*i].clone()).collect();
+    let new_paths: Vec<&str> = new_items.iter().map(|item| item.absolute_path.as_str()).collect();
+    vault_if_enabled(app, case_number, &new_paths);
+    
+    let current: HashSet<String> = items.iter().map(|item| path_key(&item.absolute_path, case_insensitive)).collect();
+    let removed: Vec<String> = known
+        .iter()
+        .filter(|(key, _)| !current.contains(*key))
+        .map(|(_, path)| path.clone())
+        .collect();
+    let removed_events = removed.iter().map(|path| FileEvent::new(path, FileEventKind::Removed, "sync")).collect();
+    record_file_events(app, case_number, removed_events);
+    notify_change(app, FILES_CHANGED_EVENT, case_number, ChangeKind::Deleted, removed);
+    notify_change(
+        app,
+        FILES_CHANGED_EVENT,
+        case_number,
+        ChangeKind::Created,
+        new_items.iter().map(|item| item.absolute_path.clone()).collect(),
+    );
+    check_watches(app, case_number, &new_items);
+    items
+}
+
+/// How syncing one of a case's sources went
+#[derive(Debug, Serialize)]
+struct SourceSyncResult {
+    source: String,
+    /// Whether the source's new items were kept
+    synced: bool,
+    /// Items for the source after the sync, or before it if it failed
+    file_count: usize,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CaseSyncReport {
+    items: Vec<InventoryItem>,
+    sources: Vec<SourceSyncResult>,
+    /// Set when an atomic sync failed and every source was left as it was
+    rolled_back: bool,
+}
+
+/// Syncs every folder the case has scanned or synced. Each source is synced
+/// on its own: one that fails keeps its existing items while the others
+/// take their changes. With `atomic`, any failure leaves the whole
+/// inventory as it was. Items outside every source are kept.
+#[tauri::command(async)]
+fn sync_case_all_sources(
+    app: AppHandle,
+    case_number: String,
+    existing_items: Vec<InventoryItem>,
+    path_case: Option<PathCase>,
+    atomic: Option<bool>,
+    options: Option<ScanOptions>,
+) -> Result<CaseSyncReport, String> {
+    let case = Some(case_number.as_str());
+    let options = options.unwrap_or_default();
+    let folders: Vec<CaseSource> = access::case_sources(&access_dir(&app)?, case)
+        .map_err(|e| AppError::CaseSourcesError(e.to_string()).to_string_message())?
+        .into_iter()
+        .filter(|source| !Path::new(&source.path).is_file())
+        .collect();
+    // A folder inside another source is synced as part of that source
+    let sources: Vec<CaseSource> = folders
+        .iter()
+        .filter(|folder| {
+            !folders
+                .iter()
+                .any(|other| other.resolved != folder.resolved && Path::new(&folder.resolved).starts_with(&other.resolved))
+        })
+        .cloned()
+        .collect();
+    let path_case = path_case.unwrap_or_default();
+    let started = Instant::now();
+    
+    let mut remaining = existing_items.clone();
+    let mut items = Vec::new();
+    let mut synced_sources = Vec::new();
+    let mut results = Vec::new();
+    let mut failed_files = Vec::new();
+    for source in sources {
+        // Items are matched as picked or resolved, and spelled as picked so
+        // the sync finds them among the files it scans
+        let case_insensitive = path_case.is_insensitive(Path::new(&source.path));
+        let mut before = Vec::new();
+        let mut rest = Vec::new();
+        for mut item in remaining {
+            match source.rebase(&item.absolute_path, case_insensitive) {
+                Some(path) => {
+                    item.absolute_path = path;
+                    before.push(item);
+                }
+                None => rest.push(item),
+            }
+        }
+        remaining = rest;
+        match sync_inventory_items(source.path.clone(), before.clone(), case_insensitive, false, &options) {
+            Ok((synced, failed)) => {
+                results.push(SourceSyncResult { source: source.path.clone(), synced: true, file_count: synced.len(), error: None });
+                failed_files.push((source.path, failed));
+                let known: HashMap<String, String> = before
+                    .iter()
+                    .map(|item| (path_key(&item.absolute_path, case_insensitive), item.absolute_path.clone()))
+                    .collect();
+                synced_sources.push((known, case_insensitive, synced));
+            }
+            Err(e) => {
+                results.push(SourceSyncResult { source: source.path, synced: false, file_count: before.len(), error: Some(e) });
+                items.extend(before);
+            }
+        }
+    }
+    items.extend(remaining);
+    
+    let failures: Vec<&str> = results.iter().filter_map(|result| result.error.as_deref()).collect();
+    let rolled_back = atomic.unwrap_or(false) && !failures.is_empty();
+    let result = if rolled_back {
+        Err(format!("rolled back: {}", failures.join("; ")))
+    } else {
+        Ok(items.len() + synced_sources.iter().map(|(_, _, synced)| synced.len()).sum::<usize>())
+    };
+    record_metric(&app, "sync", case, started, &result, |count| *count);
+    record_activity(&app, "sync", case, &result, |count| {
+        format!("Synced {} of {} sources ({} files)", results.len() - failures.len(), results.len(), count)
+    });
+    if rolled_back {
+        return Ok(CaseSyncReport { items: existing_items, sources: results, rolled_back });
+    }
+    
+    if let Ok(data_dir) = retry_queue_dir(&app) {
+        for (source, failed) in &failed_files {
+            let _ = retry_queue::record_attempts(&data_dir, case, source, failed, &[]);
+        }
+    }
+    for (known, case_insensitive, synced) in synced_sources {
+        items.extend(apply_synced_changes(&app, case, &known, case_insensitive, synced));
+    }
+    Ok(CaseSyncReport { items, sources: results, rolled_back })
+}
+
+/// Event emitted with the new `WatchHit`s when a sync brings in watched files
+const WATCH_HITS_EVENT: &str = "watch-hits";
+
+/// Records and announces newly synced files that match a watched search.
+/// Like the activity log, failures are reported to stderr only.
+fn check_watches(app: &AppHandle, case_number: Option<&str>, new_items: &[InventoryItem]) {
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        return;
+    };
+    let result = configs::effective_watches(&data_dir, case_number)
+        .and_then(|watches| watches::record_watch_hits(&data_dir, case_number, &watches, new_items));
+    match result {
+        Ok(hits) if !hits.is_empty() => emit_to_case(app, WATCH_HITS_EVENT, case_number, hits),
+        Ok(_) => {}
+        Err(e) => eprintln!("Error checking watched searches: {}", e),
+    }
+}
+
+fn watches_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::WatchError(e.to_string()).to_string_message())
+}
+
+/// Saves a watched search, replacing one with the same name in the case
+#[tauri::command]
+fn save_watch(app: AppHandle, watch: Watch) -> Result<(), String> {
+    check_filter_fields(&watch.filter)?;
+    
+    let (case_number, name) = (watch.case_number.clone(), watch.name.clone());
+    watches::save_watch(&watches_dir(&app)?, watch)
+        .map_err(|e| AppError::WatchError(e.to_string()).to_string_message())?;
+    notify_change(&app, WATCHES_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Updated, vec![name]);
+    Ok(())
+}
+
+#[tauri::command]
+fn list_watches(app: AppHandle, case_number: Option<String>) -> Result<Vec<Watch>, String> {
+    let watches = watches::read_watches(&watches_dir(&app)?)
+        .map_err(|e| AppError::WatchError(e.to_string()).to_string_message())?;
+    
+    Ok(watches
+        .into_iter()
+        .filter(|watch| watch.case_number == case_number)
+        .collect())
+}
+
+#[tauri::command]
+fn remove_watch(app: AppHandle, case_number: Option<String>, name: String) -> Result<(), String> {
+    let removed = watches::remove_watch(&watches_dir(&app)?, case_number.as_deref(), &name)
+        .map_err(|e| AppError::WatchError(e.to_string()).to_string_message())?;
+    if removed {
+        notify_change(&app, WATCHES_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Deleted, vec![name]);
+        Ok(())
+    } else {
+        Err(AppError::WatchNotFound(name).to_string_message())
+    }
+}
+
+/// Files that matched a watched search when they arrived, newest first
+#[tauri::command]
+fn list_watch_hits(
+    app: AppHandle,
+    case_number: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<WatchHit>, String> {
+    watches::read_watch_hits(&watches_dir(&app)?, case_number.as_deref(), limit.unwrap_or(100))
+        .map_err(|e| AppError::WatchError(e.to_string()).to_string_message())
+}
+
+/// Opens a case in its own window, or focuses the window already showing it,
+/// so two matters can be compared side by side. Each window keeps its own
+/// frontend state and saved workspace preferences, and only receives change
+/// events for its case; the case number is handed to it before the page
+/// loads. Returns the window label.
+// Async: building a window from a synchronous command deadlocks on Windows
+#[tauri::command(async)]
+fn open_case_window(app: AppHandle, case_number: String) -> Result<String, String> {
+    let case_number = case_number.trim().to_string();
+    if case_number.is_empty() {
+        return Err(AppError::WindowError("a case number is required".to_string()).to_string_message());
+    }
+    
+    let label = case_window_label(&case_number);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        window
+            .set_focus()
+            .map_err(|e| AppError::WindowError(e.to_string()).to_string_message())?;
+        return Ok(label);
+    }
+    
+    let case_literal = serde_json::to_string(&case_number)
+        .map_err(|e| AppError::WindowError(e.to_string()).to_string_message())?;
+    tauri::WebviewWindowBuilder::new(&app, &label, tauri::WebviewUrl::App("index.html".into()))
+        .title(format!("Document Inventory Generator - {}", case_number))
+        .inner_size(1600.0, 1000.0)
+        .min_inner_size(1200.0, 700.0)
+        .center()
+        .initialization_script(format!("window.__CASE_NUMBER__ = {};", case_literal))
+        .build()
+        .map_err(|e| AppError::WindowError(e.to_string()).to_string_message())?;
+    Ok(label)
+}
+
+fn workspace_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::WorkspaceError(e.to_string()).to_string_message())
+}
+
+/// The calling window's saved view preferences, or null if it has none
+#[tauri::command]
+fn get_workspace_preferences(app: AppHandle, window: WebviewWindow) -> Result<Option<serde_json::Value>, String> {
+    workspace::read_preferences(&workspace_dir(&app)?, window.label())
+        .map_err(|e| AppError::WorkspaceError(e.to_string()).to_string_message())
+}
+
+/// Saves the calling window's view preferences; other windows keep theirs
+#[tauri::command]
+fn save_workspace_preferences(
+    app: AppHandle,
+    window: WebviewWindow,
+    preferences: serde_json::Value,
+) -> Result<(), String> {
+    workspace::save_preferences(&workspace_dir(&app)?, window.label(), preferences)
+        .map_err(|e| AppError::WorkspaceError(e.to_string()).to_string_message())
+}
+
+/// Lock state for the whole app; every window shares it
+static APP_LOCK: LockState = LockState::new();
+
+/// Commands that still run while the app is locked
+const LOCK_COMMANDS: &[&str] = &["get_lock_status", "unlock_app"];
+
+fn lock_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::LockError(e.to_string()).to_string_message())
+}
+
+fn read_lock_settings(app: &AppHandle) -> Result<lock::LockSettings, String> {
+    lock::read_settings(&lock_dir(app)?).map_err(|e| AppError::LockError(e.to_string()).to_string_message())
+}
+
+/// Starts the app locked when a passphrase is set
+fn load_app_lock(app: &AppHandle) {
+    match read_lock_settings(app) {
+        Ok(settings) => APP_LOCK.load(&settings),
+        Err(e) => eprintln!("Error reading app lock settings: {}", e),
+    }
+}
+
+/// A command that changes a case's files or the data kept about them, and
+/// so is refused once the case is finalized
+struct CaseWriteCommand {
+    command: &'static str,
+    /// Where the case number is in the arguments: a top-level `caseNumber`,
+    /// or the `case_number` of the object being saved
+    case_number_at: &'static [&'static str],
+    /// The command cannot run without a case
+    case_required: bool,
+    /// The command changes nothing unless `dryRun` is false
+    dry_run_by_default: bool,
+}
+
+const fn case_write(command: &'static str, case_required: bool) -> CaseWriteCommand {
+    CaseWriteCommand {
+        command,
+        case_number_at: &["caseNumber"],
+        case_required,
+        dry_run_by_default: false,
+    }
+}
+
+/// Every command that changes a case, checked in `gate_commands` so none can
+/// change a finalized case by leaving the check out
+const CASE_WRITE_COMMANDS: &[CaseWriteCommand] = &[
+    case_write("finalize_case", true),
+    case_write("reextract_fields", true),
+    case_write("reclassify_folder", true),
+    CaseWriteCommand { dry_run_by_default: true, ..case_write("import_review_decisions", true) },
+    case_write("apply_automation_rules", true),
+    case_write("apply_dedup_policy", true),
+    case_write("set_duplicate_primary", true),
+    case_write("set_duplicates_included", true),
+    case_write("clear_dedup_policy", true),
+    case_write("add_loose_document", true),
+    case_write("capture_clipboard_to_case", true),
+    case_write("ingest_single_file", true),
+    CaseWriteCommand { dry_run_by_default: true, ..case_write("rename_files", true) },
+    case_write("apply_reorganization", true),
+    case_write("sync_case_all_sources", true),
+    case_write("sync_inventory", false),
+    case_write("retry_failed_files", false),
+    case_write("add_case_source", false),
+    case_write("remove_case_source", false),
+    case_write("record_file_edit", false),
+    case_write("record_bulk_edit", false),
+    case_write("checkout_file_for_edit", false),
+    case_write("checkin_file", false),
+    case_write("capture_file_version", false),
+    case_write("add_to_binder", false),
+    case_write("remove_from_binder", false),
+    case_write("delete_binder", false),
+    case_write("link_files", false),
+    case_write("unlink_files", false),
+    case_write("add_note", false),
+    case_write("resolve_note", false),
+    case_write("remove_note", false),
+    case_write("run_external_tool", false),
+    case_write("toggle_file_pinned", false),
+    case_write("clear_privilege_designation", false),
+    CaseWriteCommand { case_number_at: &["designation", "case_number"], ..case_write("designate_privilege", false) },
+];
+
+/// Why a case-changing command may not run: its case is finalized, or it
+/// needs a case and was given none
+fn case_write_refusal(invoke: &Invoke) -> Option<String> {
+    let command = CASE_WRITE_COMMANDS.iter().find(|write| write.command == invoke.message.command())?;
+    let InvokeBody::Json(args) = invoke.message.payload() else {
+        return None;
+    };
+    let dry_run = args.get("dryRun").and_then(|dry_run| dry_run.as_bool()).unwrap_or(command.dry_run_by_default);
+    if dry_run {
+        return None;
+    }
+    let case_number = command
+        .case_number_at
+        .iter()
+        .try_fold(args, |value, key| value.get(key))
+        .and_then(|value| value.as_str())
+        .filter(|case_number| !case_number.trim().is_empty());
+    let Some(case_number) = case_number else {
+        return command.case_required.then(|| AppError::CaseNumberRequired.to_string_message());
+    };
+    let app = invoke.message.webview_ref().app_handle();
+    match finalize_dir(app).and_then(|dir| finalize::find_finalized(&dir, case_number).map_err(finalize_error)) {
+        Ok(Some(_)) => Some(AppError::CaseFinalized(case_number.to_string()).to_string_message()),
+        Ok(None) => None,
+        Err(e) => Some(e),
+    }
+}
+
+/// Refuses every command but the lock commands while the app is locked, and
+/// locks it once no command has run for the idle timeout. Commands that
+/// would change a finalized case are refused too.
+fn gate_commands<H>(handler: H) -> impl Fn(Invoke) -> bool + Send + Sync + 'static
+where
+    H: Fn(Invoke) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let allowed = LOCK_COMMANDS.contains(&invoke.message.command()) || !APP_LOCK.engaged(Instant::now());
+        if !allowed {
+            invoke.resolver.reject(AppError::AppLocked.to_string_message());
+            return true;
+        }
+        match case_write_refusal(&invoke) {
+            Some(refusal) => {
+                invoke.resolver.reject(refusal);
+                true
+            }
+            None => handler(invoke),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct LockStatus {
+    /// Whether a passphrase is set
+    enabled: bool,
+    locked: bool,
+    idle_timeout_minutes: Option<u64>,
+}
+
+#[tauri::command]
+fn get_lock_status(app: AppHandle) -> Result<LockStatus, String> {
+    let settings = read_lock_settings(&app)?;
+    Ok(LockStatus {
+        enabled: settings.passphrase_hash.is_some(),
+        locked: APP_LOCK.is_locked(),
+        idle_timeout_minutes: settings.idle_timeout_minutes,
+    })
+}
+
+#[tauri::command]
+fn unlock_app(app: AppHandle, passphrase: String) -> Result<(), String> {
+    let settings = read_lock_settings(&app)?;
+    match settings.passphrase_hash {
+        Some(hash) if !lock::verify_passphrase(&hash, &passphrase) => {
+            Err(AppError::WrongPassphrase.to_string_message())
+        }
+        _ => {
+            APP_LOCK.unlock(Instant::now());
+            Ok(())
+        }
+    }
+}
+
+/// Locks the app now, e.g. from a "Lock" button
+#[tauri::command]
+fn lock_app(app: AppHandle) -> Result<(), String> {
+    if read_lock_settings(&app)?.passphrase_hash.is_none() {
+        return Err(AppError::NoPassphrase.to_string_message());
+    }
+    APP_LOCK.lock();
+    Ok(())
+}
+
+/// Sets, changes or (with no `passphrase`) removes the app lock passphrase.
+/// Changing or removing one requires the current passphrase.
+#[tauri::command]
+fn set_app_passphrase(app: AppHandle, current_passphrase: Option<String>, passphrase: Option<String>) -> Result<(), String> {
+    let data_dir = lock_dir(&app)?;
+    let mut settings = read_lock_settings(&app)?;
+    if let Some(hash) = &settings.passphrase_hash {
+        if !lock::verify_passphrase(hash, current_passphrase.as_deref().unwrap_or_default()) {
+            return Err(AppError::WrongPassphrase.to_string_message());
+        }
+    }
+    settings.passphrase_hash = match passphrase {
+        Some(passphrase) if passphrase.is_empty() => {
+            return Err(AppError::LockError("the passphrase cannot be empty".to_string()).to_string_message());
+        }
+        Some(passphrase) => Some(lock::hash_passphrase(&passphrase).map_err(|e| AppError::LockError(e).to_string_message())?),
+        None => None,
+    };
+    
+    lock::save_settings(&data_dir, &settings).map_err(|e| AppError::LockError(e.to_string()).to_string_message())?;
+    if settings.passphrase_hash.is_none() {
+        APP_LOCK.unlock(Instant::now());
+    }
+    Ok(())
+}
+
+/// Minutes without a command before the app locks itself; none turns the
+/// idle lock off
+#[tauri::command]
+fn set_idle_lock_timeout(app: AppHandle, minutes: Option<u64>) -> Result<(), String> {
+    let data_dir = lock_dir(&app)?;
+    let mut settings = read_lock_settings(&app)?;
+    settings.idle_timeout_minutes = minutes.filter(|minutes| *minutes > 0);
+    lock::save_settings(&data_dir, &settings).map_err(|e| AppError::LockError(e.to_string()).to_string_message())?;
+    APP_LOCK.set_idle_timeout(settings.idle_timeout_minutes);
+    Ok(())
+}
+
+/// The case windows' current inventories, for the local API and scheduled
+/// exports
+static API_INVENTORIES: PublishedInventories = PublishedInventories::new();
+
+/// The local API server, while it runs
+static API_SERVER: Mutex<Option<ApiServer>> = Mutex::new(None);
+
+#[derive(Debug, Serialize)]
+struct ApiStatus {
+    running: bool,
+    port: Option<u16>,
+    /// Token clients send as `Authorization: Bearer <token>`; a new one is
+    /// made each time the API starts
+    token: Option<String>,
+}
+
+fn api_status(server: &Option<ApiServer>) -> ApiStatus {
+    ApiStatus {
+        running: server.is_some(),
+        port: server.as_ref().map(|server| server.port),
+        token: server.as_ref().map(|server| server.token.clone()),
+    }
+}
+
+/// Starts the read-only API on 127.0.0.1 for integrations. Port 0 or none
+/// picks a free port.
+#[tauri::command]
+fn start_api_server(app: AppHandle, port: Option<u16>) -> Result<ApiStatus, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::ApiError(e.to_string()).to_string_message())?;
+    let mut server = API_SERVER.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if server.is_some() {
+        return Err(AppError::ApiError("the API is already running".to_string()).to_string_message());
+    }
+    
+    let started = api::start_server(&API_INVENTORIES, &APP_LOCK, data_dir, port.unwrap_or(0))
+        .map_err(|e| AppError::ApiError(e.to_string()).to_string_message())?;
+    *server = Some(started);
+    Ok(api_status(&server))
+}
+
+#[tauri::command]
+fn stop_api_server() -> Result<(), String> {
+    let server = API_SERVER.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+    if let Some(server) = server {
+        server.stop();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_api_status() -> Result<ApiStatus, String> {
+    Ok(api_status(&API_SERVER.lock().unwrap_or_else(|poisoned| poisoned.into_inner())))
+}
+
+/// Makes a case's current inventory available to the local API and to
+/// scheduled exports. Returns the cases unloaded to stay within the limits.
+#[tauri::command]
+fn publish_case_inventory(case_number: String, items: Vec<InventoryItem>) -> Result<Vec<String>, String> {
+    let published_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    API_INVENTORIES
+        .publish(&case_number, items, &published_at)
+        .map_err(|e| AppError::ApiError(e).to_string_message())
+}
+
+/// Drops a case's published inventory, as when its window closes
+#[tauri::command]
+fn unpublish_case_inventory(case_number: String) -> bool {
+    API_INVENTORIES.unload(&case_number)
+}
+
+/// Caps how many cases' inventories are held at once and how many files one
+/// may publish. Returns the cases unloaded to meet the new caps.
+#[tauri::command]
+fn set_resource_limits(limits: InventoryLimits) -> Vec<String> {
+    API_INVENTORIES.set_limits(limits)
+}
+
+/// One case's published files and cached stores
+#[derive(Debug, Serialize)]
+struct CaseResourceUsage {
+    #[serde(flatten)]
+    published: PublishedUsage,
+    cached_stores: usize,
+    cached_store_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct ResourceUsage {
+    limits: InventoryLimits,
+    /// Published cases, most recently used first
+    cases: Vec<CaseResourceUsage>,
+    /// Every cached store, including the app-wide ones
+    cached_stores: usize,
+    cached_store_bytes: u64,
+}
+
+/// What the backend holds in memory per case, for diagnostics
+#[tauri::command]
+fn get_resource_usage(app: AppHandle) -> Result<ResourceUsage, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::ApiError(e.to_string()).to_string_message())?;
+    let cases = API_INVENTORIES
+        .usage()
+        .into_iter()
+        .map(|published| {
+            let (cached_stores, cached_store_bytes) = json_store::cached_under(&json_store::case_data_dir(&data_dir, &published.case_number));
+            CaseResourceUsage { published, cached_stores, cached_store_bytes }
+        })
+        .collect();
+    let (cached_stores, cached_store_bytes) = json_store::cached_under(&data_dir);
+    Ok(ResourceUsage {
+        limits: API_INVENTORIES.get_limits(),
+        cases,
+        cached_stores,
+        cached_store_bytes,
+    })
+}
+
+fn privilege_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::PrivilegeError(e.to_string()).to_string_message())
+}
+
+fn privilege_status_name(designation: Option<&PrivilegeDesignation>) -> String {
+    designation
+        .and_then(|designation| serde_json::to_value(designation.status).ok())
+        .and_then(|status| status.as_str().map(|status| status.to_string()))
+        .unwrap_or_default()
+}
+
+/// Marks a document privileged, redacted or produced. Empty date, author,
+/// recipients and description are filled from the document's email headers
+/// or PDF/Office properties. With `propagate_to_family`, the status and
+/// basis also go to the rest of its family (files joined by attachment or
+/// containment links), each keeping its own details. Returns the
+/// designation as stored.
+#[tauri::command]
+fn designate_privilege(
+    app: AppHandle,
+    mut designation: PrivilegeDesignation,
+    propagate_to_family: Option<bool>,
+) -> Result<PrivilegeDesignation, String> {
+    let case_number = designation.case_number.clone();
+    let path = PathBuf::from(&designation.absolute_path);
+    ensure_case_source(&app, case_number.as_deref(), &path)?;
+    let data_dir = privilege_dir(&app)?;
+    let mut existing = privilege::case_designations(&data_dir, case_number.as_deref())
+        .map_err(|e| AppError::PrivilegeError(e.to_string()).to_string_message())?;
+    let mut previous = |absolute_path: &str| {
+        existing
+            .iter()
+            .position(|existing| existing.absolute_path == absolute_path)
+            .map(|position| existing.swap_remove(position))
+    };
+    
+    privilege::seed_from_metadata(&mut designation);
+    let mut changes = vec![(previous(&designation.absolute_path), designation.clone())];
+    if propagate_to_family.unwrap_or(false) {
+        let links = links::case_links(&links_dir(&app)?, case_number.as_deref())
+            .map_err(|e| AppError::LinkError(e.to_string()).to_string_message())?;
+        for member in links::family_members(&links, &designation.absolute_path) {
+            let old = previous(&member);
+            let mut new = privilege::family_designation(&designation, old.clone(), &member);
+            privilege::seed_from_metadata(&mut new);
+            changes.push((old, new));
+        }
+    }
+    
+    let mut events = Vec::new();
+    for (old, new) in &changes {
+        privilege::designate(&data_dir, new.clone())
+            .map_err(|e| AppError::PrivilegeError(e.to_string()).to_string_message())?;
+        let (old_status, new_status) = (privilege_status_name(old.as_ref()), privilege_status_name(Some(new)));
+        if old_status != new_status {
+            events.push(FileEvent::field_change(
+                &new.absolute_path,
+                FileEventKind::FieldChanged,
+                "privilege review",
+                "privilege",
+                &old_status,
+                &new_status,
+            ));
+        }
+    }
+    record_file_events(&app, case_number.as_deref(), events);
+    let paths = changes.into_iter().map(|(_, new)| new.absolute_path).collect();
+    notify_change(&app, PRIVILEGE_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Updated, paths);
+    Ok(designation)
+}
+
+/// Document families whose members have differing privilege designations,
+/// such as a withheld email with a produced attachment, for review before a
+/// production goes out
+#[tauri::command]
+fn check_privilege_families(app: AppHandle, case_number: Option<String>) -> Result<Vec<Vec<FamilyMemberStatus>>, String> {
+    let links = links::case_links(&links_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::LinkError(e.to_string()).to_string_message())?;
+    let designations = privilege::case_designations(&privilege_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::PrivilegeError(e.to_string()).to_string_message())?;
+    Ok(privilege::family_conflicts(&designations, &links::families(&links)))
+}
+
+#[tauri::command]
+fn list_privilege_designations(app: AppHandle, case_number: Option<String>) -> Result<Vec<PrivilegeDesignation>, String> {
+    privilege::case_designations(&privilege_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::PrivilegeError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn clear_privilege_designation(app: AppHandle, case_number: Option<String>, absolute_path: String) -> Result<(), String> {
+    let data_dir = privilege_dir(&app)?;
+    let previous = privilege::case_designations(&data_dir, case_number.as_deref())
+        .map_err(|e| AppError::PrivilegeError(e.to_string()).to_string_message())?
+        .into_iter()
+        .find(|existing| existing.absolute_path == absolute_path);
+    let removed = privilege::clear_designation(&data_dir, case_number.as_deref(), &absolute_path)
+        .map_err(|e| AppError::PrivilegeError(e.to_string()).to_string_message())?;
+    if !removed {
+        return Err(AppError::PrivilegeNotFound(absolute_path).to_string_message());
+    }
+    
+    let old_status = privilege_status_name(previous.as_ref());
+    let event = FileEvent::field_change(&absolute_path, FileEventKind::FieldChanged, "privilege review", "privilege", &old_status, "");
+    record_file_events(&app, case_number.as_deref(), vec![event]);
+    notify_change(&app, PRIVILEGE_CHANGED_EVENT, case_number.as_deref(), ChangeKind::Deleted, vec![absolute_path]);
+    Ok(())
+}
+
+/// Writes the case's privilege log as CSV: every withheld or redacted
+/// document among `items`, in order, with date, author, recipients,
+/// description and privilege basis. Returns the number of entries.
+#[tauri::command]
+fn export_privilege_log(
+    app: AppHandle,
+    case_number: Option<String>,
+    items: Vec<InventoryItem>,
+    output_path: String,
+) -> Result<usize, String> {
+    let designations = privilege::case_designations(&privilege_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::PrivilegeError(e.to_string()).to_string_message())?;
+    let result = privilege::write_privilege_log(&designations, &items, &output_path)
+        .map_err(|e| AppError::CsvError(e.to_string()).to_string_message());
+    record_activity(&app, "export", case_number.as_deref(), &result, |entries| {
+        format!("Exported a privilege log of {} entries to {}", entries, output_path)
+    });
+    result
+}
+
+/// Event emitted with the `ExportRun` when a scheduled export fails
+const EXPORT_SCHEDULE_FAILED_EVENT: &str = "export-schedule-failed";
+
+/// How often the scheduler looks for due exports; under a minute so no
+/// scheduled minute is skipped
+const SCHEDULER_TICK: std::time::Duration = std::time::Duration::from_secs(20);
+
+fn schedules_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::ScheduleError(e.to_string()).to_string_message())
+}
+
+/// Exports the case's current inventory the way the schedule says, and
+/// records the run. Failures are also announced to the case's windows.
+fn run_export_schedule(app: &AppHandle, schedule: &ExportSchedule) -> ExportRun {
+    let case_number = Some(schedule.case_number.as_str());
+    let mut run = ExportRun {
+        schedule_id: schedule.id.clone(),
+        case_number: schedule.case_number.clone(),
+        started_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        output_path: None,
+        item_count: 0,
+        error: None,
+    };
+    let result = (|| {
+        let items = API_INVENTORIES.items(&schedule.case_number).ok_or_else(|| {
+            AppError::ScheduleError("the case has not been opened since the app started".to_string()).to_string_message()
+        })?;
+        let items = in_review_scope(app, case_number, items, schedule.options.include_suppressed)?;
+        run.item_count = items.len();
+        let output_path = resolve_export_filename(
+            schedule.directory.clone(),
+            schedule.pattern.clone(),
+            schedule.format.clone(),
+            Some(schedule.case_number.clone()),
+            Some(schedule.id.clone()),
+            Some(schedule.collision),
+        )?;
+        run.output_path = Some(output_path.clone());
+        export_items(items, &schedule.format, &output_path, case_number, None, &schedule.options)
+    })();
+    
+    run.error = result.as_ref().err().cloned();
+    if run.error.is_some() {
+        run.output_path = None;
+    }
+    record_activity(app, "scheduled_export", case_number, &result, |_| {
+        format!("Scheduled export {} wrote {} items to {}", schedule.id, run.item_count, run.output_path.as_deref().unwrap_or_default())
+    });
+    match schedules_dir(app) {
+        Ok(data_dir) => {
+            if let Err(e) = schedules::record_run(&data_dir, &run) {
+                eprintln!("Error recording export run: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Error recording export run: {}", e),
+    }
+    if run.error.is_some() {
+        emit_to_case(app, EXPORT_SCHEDULE_FAILED_EVENT, case_number, run.clone());
+    }
+    run
+}
+
+/// Runs due export schedules for as long as the app is open
+fn start_export_scheduler(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_minute = None;
+        loop {
+            let now = chrono::Local::now().naive_local();
+            let minute = now.format("%Y-%m-%d %H:%M").to_string();
+            if last_minute.as_ref() != Some(&minute) {
+                last_minute = Some(minute);
+                let schedules = schedules_dir(&app).and_then(|data_dir| {
+                    schedules::list_schedules(&data_dir, None).map_err(|e| e.to_string())
+                });
+                match schedules {
+                    Ok(schedules) => {
+                        for schedule in schedules::due_schedules(&schedules, &now) {
+                            run_export_schedule(&app, schedule);
+                        }
+                    }
+                    Err(e) => eprintln!("Error reading export schedules: {}", e),
+                }
+            }
+            std::thread::sleep(SCHEDULER_TICK);
+        }
+    });
+}
+
+/// Adds an export schedule, or replaces the one with the same id
+#[tauri::command]
+fn create_export_schedule(app: AppHandle, schedule: ExportSchedule) -> Result<(), String> {
+    if schedule.id.trim().is_empty() || schedule.case_number.trim().is_empty() {
+        return Err(AppError::ScheduleError("a schedule needs an id and a case".to_string()).to_string_message());
+    }
+    schedules::Cron::parse(&schedule.cron).map_err(|field| AppError::InvalidSchedule(field).to_string_message())?;
+    validate_directory_path(&schedule.directory)?;
+    
+    schedules::save_schedule(&schedules_dir(&app)?, schedule)
+        .map_err(|e| AppError::ScheduleError(e.to_string()).to_string_message())
+}
+
+#[tauri::command]
+fn list_export_schedules(app: AppHandle, case_number: Option<String>) -> Result<Vec<ExportSchedule>, String> {
+    schedules::list_schedules(&schedules_dir(&app)?, case_number.as_deref())
+        .map_err(|e| AppError::ScheduleError(e.to_string()).to_string_message())
+}
+
+/// Removes an export schedule together with its run history
+#[tauri::command]
+fn delete_export_schedule(app: AppHandle, id: String) -> Result<(), String> {
+    let removed = schedules::remove_schedule(&schedules_dir(&app)?, &id)
+        .map_err(|e| AppError::ScheduleError(e.to_string()).to_string_message())?;
+    if !removed {
+        return Err(AppError::ScheduleNotFound(id).to_string_message());
+    }
+    Ok(())
+}
+
+/// A case's scheduled export runs, newest first
+#[tauri::command]
+fn list_export_runs(app: AppHandle, case_number: String, schedule_id: Option<String>) -> Result<Vec<ExportRun>, String> {
+    schedules::list_runs(&schedules_dir(&app)?, &case_number, schedule_id.as_deref())
+        .map_err(|e| AppError::ScheduleError(e.to_string()).to_string_message())
+}
+
+/// Runs a schedule now, outside its schedule
+#[tauri::command(async)]
+fn run_export_schedule_now(app: AppHandle, id: String) -> Result<ExportRun, String> {
+    let schedule = schedules::list_schedules(&schedules_dir(&app)?, None)
+        .map_err(|e| AppError::ScheduleError(e.to_string()).to_string_message())?
+        .into_iter()
+        .find(|schedule| schedule.id == id)
+        .ok_or_else(|| AppError::ScheduleNotFound(id).to_string_message())?;
+    Ok(run_export_schedule(&app, &schedule))
+}
+
+/// With `force`, the offline checks are skipped so a folder that really was
+/// emptied can be synced down to nothing. New files go through the same type,
+/// small-file and ignore policies as a scan with `options`.
+fn sync_inventory_items(
+    folder_path: String,
+    existing_items: Vec<InventoryItem>,
+    case_insensitive: bool,
+    force: bool,
+    options: &ScanOptions,
+) -> Result<(Vec<InventoryItem>, Vec<FailedFile>), String> {
+    let root_path = PathBuf::from(&folder_path);
+    // New files get their timestamps in the case's time zone, like a scan
+    validate_scan_options(options)?;
+    
+    // Refuse to sync an offline source - scanning it would drop every item
+    let status = diagnostics::source_status(&root_path);
+    if let Some(reason) = status.reason.filter(|_| !force) {
+        return Err(AppError::SourceOffline(folder_path, reason).to_string_message());
+    }
+    
+    // Scan folder for current files
+    let outcome = scan_folder_with_options(&root_path, options)
+        .map_err(|e| AppError::ScanError(e.to_string()).to_string_message())?;
+    let files = outcome.files;
+    
+    // An empty scan of a previously populated folder almost always means the
+    // drive or share behind it went away, so keep the existing inventory
+    if files.is_empty() && !existing_items.is_empty() && !force {
+        return Err(AppError::SourceOffline(
+            folder_path,
+            "No files were found where the inventory previously had some.".to_string(),
+        )
+        .to_string_message());
+    }
+    
+    // Create a map of existing items by path key for quick lookup. On a
+    // case-insensitive volume a case-only rename is still the same file.
+    let mut existing_map: std::collections::HashMap<String, InventoryItem> = existing_items
+        .into_iter()
+        .map(|item| (path_key(&item.absolute_path, case_insensitive), item))
+        .collect();
+    
+    let mut updated_items = Vec::new();
+    let mut processed_paths = std::collections::HashSet::new();
+    
+    // Process new/updated files
+    for file_metadata in files {
+        let absolute_path = file_metadata.absolute_path.clone();
+        processed_paths.insert(absolute_path.clone());
+        
+        if let Some(mut existing_item) = existing_map.remove(&path_key(&absolute_path, case_insensitive)) {
+            // File still exists - keep it with existing user edits, under its
+            // current spelling
+            existing_item.absolute_path = absolute_path;
+            updated_items.push(existing_item);
+        } else {
+            // New file - create new item
+            updated_items.push(item_from_scan(file_metadata, options));
+        }
+    }
+    
+    // Files that were removed are not included (they're in existing_map but not in processed_paths)
+    // This is intentional - we only keep files that still exist. Files that
+    // could not be read, or sit in a folder that could not be listed, are
+    // kept as they were rather than treated as deleted.
+    updated_items.extend(existing_map.into_values().filter(|item| {
+        let path = Path::new(&item.absolute_path);
+        outcome.failed.iter().any(|failed| path.starts_with(&failed.path))
+    }));
+    
+    Ok((updated_items, outcome.failed))
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .register_asynchronous_uri_scheme_protocol(preview::PREVIEW_SCHEME, |ctx, request, responder| {
+            // File bytes are as sensitive as any command, so the lock
+            // applies here as it does in gate_commands
+            if APP_LOCK.engaged(Instant::now()) {
+                responder.respond(preview::error_response(tauri::http::StatusCode::FORBIDDEN, AppError::AppLocked.to_string_message()));
+                return;
+            }
+            let data_dir = access_dir(ctx.app_handle());
+            // Reads happen off the main thread so a slow disk never stalls the UI
+            tauri::async_runtime::spawn_blocking(move || {
+                responder.respond(match data_dir {
+                    Ok(data_dir) => preview::serve_file(&request, &data_dir),
+                    Err(e) => preview::error_response(tauri::http::StatusCode::INTERNAL_SERVER_ERROR, e),
+                });
+            });
+        })
+        .setup(|app| {
+            load_app_lock(app.handle());
+            start_export_scheduler(app.handle().clone());
+            let handle = app.handle().clone();
+            std::thread::spawn(move || {
+                if let Err(e) = health_check(&handle) {
+                    eprintln!("Error running health check: {}", e);
+                }
+            });
+            Ok(())
+        })
+        .invoke_handler(gate_commands(tauri::generate_handler![
+            count_directory_files,
+            scan_directory,
+            scan_directory_report,
+            scan_directory_streaming,
+            small_file_report,
+            retry_failed_files,
+            get_retry_queue,
+            export_inventory,
+            resolve_export_filename,
+            import_inventory,
+            sync_inventory,
+            save_watch,
+            list_watches,
+            remove_watch,
+            list_watch_hits,
+            save_automation_rule,
+            list_automation_rules,
+            remove_automation_rule,
+            save_report_template,
+            list_report_templates,
+            remove_report_template,
+            generate_report,
+            generate_saved_report,
+            export_print_view,
+            get_recent_activity,
+            get_performance_metrics,
+            get_file_categories,
+            categorize_items,
+            reextract_fields,
+            reclassify_folder,
+            get_field_facets,
+            run_case_query,
+            compute_term_frequencies,
+            cluster_documents,
+            profile_spreadsheets,
+            profile_databases,
+            parse_browser_artifacts,
+            parse_shortcuts,
+            get_date_histogram,
+            search_in_folder,
+            search_all,
+            diff_files,
+            checkout_file_for_edit,
+            checkin_file,
+            list_checkouts,
+            set_file_versioning,
+            get_file_versioning,
+            capture_file_version,
+            list_file_versions,
+            export_file_version,
+            diff_file_versions,
+            read_file_range,
+            diagnose_source_access,
+            check_source_available,
+            import_review_decisions,
+            apply_automation_rules,
+            validate_field_edit,
+            validate_for_finalization,
+            save_field_rules,
+            list_field_rules,
+            generate_qc_report,
+            list_anomalies,
+            check_date_consistency,
+            finalize_case,
+            verify_finalized_case,
+            get_finalized_case,
+            timestamp_finalized_case,
+            timestamp_file,
+            list_binders,
+            add_to_binder,
+            remove_from_binder,
+            delete_binder,
+            get_binder_items,
+            link_files,
+            unlink_files,
+            get_file_links,
+            list_case_links,
+            add_note,
+            list_notes,
+            notes_mentioning_file,
+            resolve_note,
+            remove_note,
+            get_file_history,
+            record_file_edit,
+            record_bulk_edit,
+            toggle_file_pinned,
+            get_pinned_files,
+            record_file_opened,
+            get_recent_files,
+            get_review_stats,
+            read_keyword_list,
+            save_keyword_list,
+            import_keyword_list,
+            list_keyword_lists,
+            remove_keyword_list,
+            run_keyword_report,
+            export_duplicates_report,
+            apply_dedup_policy,
+            get_dedup_policy,
+            set_duplicate_primary,
+            set_duplicates_included,
+            clear_dedup_policy,
+            hash_files,
+            hash_files_tuned,
+            reconcile_hash_manifest,
+            lookup_hashes,
+            ingest_single_file,
+            get_loose_documents_folder,
+            add_loose_document,
+            capture_clipboard_to_case,
+            rename_files,
+            plan_reorganization,
+            apply_reorganization,
+            compute_date_offsets,
+            extract_statement_periods,
+            open_case_window,
+            get_workspace_preferences,
+            save_workspace_preferences,
+            get_lock_status,
+            unlock_app,
+            lock_app,
+            set_app_passphrase,
+            set_idle_lock_timeout,
+            start_api_server,
+            stop_api_server,
+            get_api_status,
+            publish_case_inventory,
+            unpublish_case_inventory,
+            set_resource_limits,
+            get_resource_usage,
+            get_changes_since,
+            save_external_tool,
+            list_external_tools,
+            remove_external_tool,
+            run_external_tool,
+            create_export_schedule,
+            list_export_schedules,
+            delete_export_schedule,
+            list_export_runs,
+            run_export_schedule_now,
+            sync_case_all_sources,
+            export_case_bundle,
+            designate_privilege,
+            list_privilege_designations,
+            clear_privilege_designation,
+            export_privilege_log,
+            list_productions,
+            get_file_productions,
+            set_vault_enabled,
+            get_vault_enabled,
+            vault_case_files,
+            get_vault_report,
+            get_vault_copy,
+            set_store_slow_log,
+            check_privilege_families,
+            list_case_sources,
+            add_case_source,
+            remove_case_source,
+            run_health_check,
+            export_config,
+            import_config,
+            get_effective_config,
+            reset_case_config,
+        ]))
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
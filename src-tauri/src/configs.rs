@@ -0,0 +1,300 @@
+use crate::keywords::{self, KeywordList};
+use crate::report::{self, ReportTemplate, SavedReportTemplate};
+use crate::rules::{self, AutomationRule};
+use crate::watches::{self, Watch};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Version written into config files; files from a newer version are refused
+const CONFIG_VERSION: u32 = 1;
+
+/// The kinds of saved configuration that can be shared as a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigKind {
+    AutomationRules,
+    ReportTemplates,
+    KeywordLists,
+    Watches,
+}
+
+/// A case's saved configuration of one kind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "entries", rename_all = "snake_case")]
+pub enum ConfigEntries {
+    AutomationRules(Vec<AutomationRule>),
+    ReportTemplates(Vec<SavedReportTemplate>),
+    KeywordLists(Vec<KeywordList>),
+    Watches(Vec<Watch>),
+}
+
+impl ConfigEntries {
+    pub fn kind(&self) -> ConfigKind {
+        match self {
+            Self::AutomationRules(_) => ConfigKind::AutomationRules,
+            Self::ReportTemplates(_) => ConfigKind::ReportTemplates,
+            Self::KeywordLists(_) => ConfigKind::KeywordLists,
+            Self::Watches(_) => ConfigKind::Watches,
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        match self {
+            Self::AutomationRules(all) => all.iter().map(|rule| rule.name.clone()).collect(),
+            Self::ReportTemplates(all) => all.iter().map(|saved| saved.name.clone()).collect(),
+            Self::KeywordLists(all) => all.iter().map(|list| list.name.clone()).collect(),
+            Self::Watches(all) => all.iter().map(|watch| watch.name.clone()).collect(),
+        }
+    }
+
+    /// Moves every entry to `case_number`; `None` leaves them without a case,
+    /// as they are written to a file
+    fn set_case(&mut self, case_number: Option<&str>) {
+        let case_number = case_number.map(|s| s.to_string());
+        match self {
+            Self::AutomationRules(all) => all.iter_mut().for_each(|rule| rule.case_number = case_number.clone()),
+            Self::ReportTemplates(all) => all.iter_mut().for_each(|saved| saved.case_number = case_number.clone()),
+            Self::KeywordLists(all) => all.iter_mut().for_each(|list| list.case_number = case_number.clone()),
+            Self::Watches(all) => all.iter_mut().for_each(|watch| watch.case_number = case_number.clone()),
+        }
+    }
+}
+
+/// The configuration of one kind that applies to a case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveConfig {
+    /// Whether the case has none of its own, so the global set applies
+    pub inherited: bool,
+    #[serde(flatten)]
+    pub config: ConfigEntries,
+}
+
+/// A config file as written by `export_config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigFile {
+    pub version: u32,
+    pub exported_at: String,
+    #[serde(flatten)]
+    pub config: ConfigEntries,
+}
+
+/// A case's own saved configuration of one kind; `None` is the global set
+pub fn case_config(data_dir: &Path, kind: ConfigKind, case_number: Option<&str>) -> Result<ConfigEntries, Box<dyn std::error::Error>> {
+    Ok(match kind {
+        ConfigKind::AutomationRules => ConfigEntries::AutomationRules(rules::case_rules(data_dir, case_number)?),
+        ConfigKind::ReportTemplates => ConfigEntries::ReportTemplates(report::case_report_templates(data_dir, case_number)?),
+        ConfigKind::KeywordLists => ConfigEntries::KeywordLists(keywords::case_keyword_lists(data_dir, case_number)?),
+        ConfigKind::Watches => ConfigEntries::Watches(case_watches(data_dir, case_number)?),
+    })
+}
+
+/// A case's own entries, or the global ones when it has none of the kind.
+/// A case with any entries of its own uses only those, so removing a global
+/// entry from a case is done by giving it its own set.
+fn inherited<T>(
+    case_number: Option<&str>,
+    read: impl Fn(Option<&str>) -> Result<Vec<T>, Box<dyn std::error::Error>>,
+) -> Result<(Vec<T>, bool), Box<dyn std::error::Error>> {
+    let own = read(case_number)?;
+    if own.is_empty() && case_number.is_some() {
+        Ok((read(None)?, true))
+    } else {
+        Ok((own, false))
+    }
+}
+
+fn case_watches(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<Watch>, Box<dyn std::error::Error>> {
+    Ok(watches::read_watches(data_dir)?
+        .into_iter()
+        .filter(|watch| watch.case_number.as_deref() == case_number)
+        .collect())
+}
+
+/// The configuration of one kind that applies to a case, as ingestion,
+/// syncs, reports and exports see it
+pub fn effective_config(data_dir: &Path, kind: ConfigKind, case_number: Option<&str>) -> Result<EffectiveConfig, Box<dyn std::error::Error>> {
+    let (config, inherited) = match kind {
+        ConfigKind::AutomationRules => {
+            let (all, inherited) = inherited(case_number, |case| rules::case_rules(data_dir, case))?;
+            (ConfigEntries::AutomationRules(all), inherited)
+        }
+        ConfigKind::ReportTemplates => {
+            let (all, inherited) = inherited(case_number, |case| report::case_report_templates(data_dir, case))?;
+            (ConfigEntries::ReportTemplates(all), inherited)
+        }
+        ConfigKind::KeywordLists => {
+            let (all, inherited) = inherited(case_number, |case| keywords::case_keyword_lists(data_dir, case))?;
+            (ConfigEntries::KeywordLists(all), inherited)
+        }
+        ConfigKind::Watches => {
+            let (all, inherited) = inherited(case_number, |case| case_watches(data_dir, case))?;
+            (ConfigEntries::Watches(all), inherited)
+        }
+    };
+    Ok(EffectiveConfig { inherited, config })
+}
+
+/// The automation rules that run on a case's new files
+pub fn effective_rules(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<AutomationRule>, Box<dyn std::error::Error>> {
+    Ok(inherited(case_number, |case| rules::case_rules(data_dir, case))?.0)
+}
+
+/// The watched searches checked against a case's newly synced files
+pub fn effective_watches(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<Watch>, Box<dyn std::error::Error>> {
+    Ok(inherited(case_number, |case| case_watches(data_dir, case))?.0)
+}
+
+/// A report template that applies to the case, by name (case-insensitive)
+pub fn find_report_template(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    name: &str,
+) -> Result<Option<ReportTemplate>, Box<dyn std::error::Error>> {
+    let (_, inherited) = inherited(case_number, |case| report::case_report_templates(data_dir, case))?;
+    report::find_report_template(data_dir, if inherited { None } else { case_number }, name)
+}
+
+/// A keyword list that applies to the case, by name (case-insensitive)
+pub fn find_keyword_list(data_dir: &Path, case_number: Option<&str>, name: &str) -> Result<Option<KeywordList>, Box<dyn std::error::Error>> {
+    let (_, inherited) = inherited(case_number, |case| keywords::case_keyword_lists(data_dir, case))?;
+    keywords::find_keyword_list(data_dir, if inherited { None } else { case_number }, name)
+}
+
+/// Removes a case's own entries of one kind so the global set applies to it
+/// again. Returns the names removed.
+pub fn reset_to_global(data_dir: &Path, kind: ConfigKind, case_number: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let names = case_config(data_dir, kind, Some(case_number))?.names();
+    for name in &names {
+        match kind {
+            ConfigKind::AutomationRules => rules::remove_rule(data_dir, Some(case_number), name)?,
+            ConfigKind::ReportTemplates => report::remove_report_template(data_dir, Some(case_number), name)?,
+            ConfigKind::KeywordLists => keywords::remove_keyword_list(data_dir, Some(case_number), name)?,
+            ConfigKind::Watches => watches::remove_watch(data_dir, Some(case_number), name)?,
+        };
+    }
+    Ok(names)
+}
+
+/// Saves every entry with `case_number`, replacing entries of the same name
+/// there and keeping the rest. Returns the names saved.
+pub fn save_config(data_dir: &Path, case_number: Option<&str>, mut config: ConfigEntries) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    config.set_case(case_number);
+    let names = config.names();
+    match config {
+        ConfigEntries::AutomationRules(all) => all.into_iter().try_for_each(|rule| rules::save_rule(data_dir, rule))?,
+        ConfigEntries::ReportTemplates(all) => all.into_iter().try_for_each(|saved| report::save_report_template(data_dir, saved))?,
+        ConfigEntries::KeywordLists(all) => all.into_iter().try_for_each(|list| keywords::save_keyword_list(data_dir, list))?,
+        ConfigEntries::Watches(all) => all.into_iter().try_for_each(|watch| watches::save_watch(data_dir, watch))?,
+    }
+    Ok(names)
+}
+
+/// Writes a case's configuration of one kind to `path`, without its case
+/// number so it can be loaded into any case or install. Returns how many
+/// entries were written.
+pub fn export_config(
+    data_dir: &Path,
+    kind: ConfigKind,
+    case_number: Option<&str>,
+    path: &Path,
+    exported_at: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut config = case_config(data_dir, kind, case_number)?;
+    config.set_case(None);
+    let count = config.names().len();
+    let file = ConfigFile {
+        version: CONFIG_VERSION,
+        exported_at: exported_at.to_string(),
+        config,
+    };
+    fs::write(path, serde_json::to_string_pretty(&file)?)?;
+    Ok(count)
+}
+
+/// Reads a config file, refusing one of another kind or from a newer version
+pub fn read_config_file(path: &Path, kind: ConfigKind) -> Result<ConfigEntries, Box<dyn std::error::Error>> {
+    let file: ConfigFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+    if file.version > CONFIG_VERSION {
+        return Err(format!("{} was written by a newer version (config version {})", path.display(), file.version).into());
+    }
+    if file.config.kind() != kind {
+        return Err(format!("{} holds {:?}, not {:?}", path.display(), file.config.kind(), kind).into());
+    }
+    Ok(file.config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn watch(name: &str, case_number: Option<&str>) -> Watch {
+        Watch {
+            name: name.to_string(),
+            case_number: case_number.map(|s| s.to_string()),
+            query: Some("4417".to_string()),
+            filter: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn exported_config_loads_into_another_case() {
+        let dir = std::env::temp_dir().join(format!("configs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        watches::save_watch(&dir, watch("Account", Some("A-1"))).unwrap();
+        watches::save_watch(&dir, watch("Wire", Some("A-1"))).unwrap();
+        watches::save_watch(&dir, watch("Other case", Some("B-2"))).unwrap();
+        keywords::save_keyword_list(
+            &dir,
+            KeywordList {
+                name: "Agreed terms".to_string(),
+                case_number: Some("A-1".to_string()),
+                terms: vec!["merger".to_string()],
+            },
+        )
+        .unwrap();
+
+        let path = dir.join("watches.config.json");
+        assert_eq!(export_config(&dir, ConfigKind::Watches, Some("A-1"), &path, "2026-03-01 09:00:00").unwrap(), 2);
+        assert!(!fs::read_to_string(&path).unwrap().contains("A-1"));
+        assert!(read_config_file(&path, ConfigKind::KeywordLists).is_err());
+
+        // Loading replaces same-named entries and keeps the case's others
+        watches::save_watch(&dir, watch("Account", Some("C-3"))).unwrap();
+        watches::save_watch(&dir, watch("Local", Some("C-3"))).unwrap();
+        let config = read_config_file(&path, ConfigKind::Watches).unwrap();
+        assert_eq!(save_config(&dir, Some("C-3"), config).unwrap(), ["Account", "Wire"]);
+        let mut names = case_config(&dir, ConfigKind::Watches, Some("C-3")).unwrap().names();
+        names.sort();
+        assert_eq!(names, ["Account", "Local", "Wire"]);
+        assert_eq!(case_config(&dir, ConfigKind::Watches, Some("B-2")).unwrap().names(), ["Other case"]);
+
+        let newer = fs::read_to_string(&path).unwrap().replace("\"version\": 1", "\"version\": 2");
+        fs::write(&path, newer).unwrap();
+        assert!(read_config_file(&path, ConfigKind::Watches).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cases_without_their_own_config_inherit_the_global_set() {
+        let dir = std::env::temp_dir().join(format!("configs-inherit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        watches::save_watch(&dir, watch("Firm standard", None)).unwrap();
+        watches::save_watch(&dir, watch("Account", Some("A-1"))).unwrap();
+
+        let names = |watches: Vec<Watch>| watches.into_iter().map(|watch| watch.name).collect::<Vec<_>>();
+        assert_eq!(names(effective_watches(&dir, Some("A-1")).unwrap()), ["Account"]);
+        assert_eq!(names(effective_watches(&dir, Some("B-2")).unwrap()), ["Firm standard"]);
+        assert!(effective_config(&dir, ConfigKind::Watches, Some("B-2")).unwrap().inherited);
+        assert!(!effective_config(&dir, ConfigKind::Watches, Some("A-1")).unwrap().inherited);
+        assert!(effective_rules(&dir, Some("B-2")).unwrap().is_empty());
+
+        assert_eq!(reset_to_global(&dir, ConfigKind::Watches, "A-1").unwrap(), ["Account"]);
+        assert_eq!(names(effective_watches(&dir, Some("A-1")).unwrap()), ["Firm standard"]);
+        assert_eq!(names(effective_watches(&dir, None).unwrap()), ["Firm standard"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+use crate::InventoryItem;
+use chrono::{DateTime, Local, NaiveDate};
+use rust_xlsxwriter::{Format, FormatBorder, Workbook};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::SystemTime;
+
+const REPORT_HEADERS: &[&str] = &[
+    "File Name",
+    "Path",
+    "Issue",
+    "Document Period",
+    "Created",
+    "Modified",
+    "Detail",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InconsistencyKind {
+    /// Last modified before the period the document covers began
+    ModifiedBeforeDocument,
+    /// Dated before the hold but created on disk after it
+    CreatedAfterHold,
+    /// Dated before the hold but modified after it
+    ModifiedAfterHold,
+    /// Modified much later than the period the document covers
+    ModifiedLongAfterDocument,
+}
+
+impl InconsistencyKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::ModifiedBeforeDocument => "Modified before document date",
+            Self::CreatedAfterHold => "Created after hold",
+            Self::ModifiedAfterHold => "Modified after hold",
+            Self::ModifiedLongAfterDocument => "Modified long after document date",
+        }
+    }
+}
+
+/// A file whose filesystem dates disagree with its document date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DateInconsistency {
+    pub absolute_path: String,
+    pub file_name: String,
+    pub kind: InconsistencyKind,
+    pub doc_date_range: String,
+    /// YYYY-MM-DD, local time; empty when the filesystem does not record it
+    pub created: String,
+    pub modified: String,
+    pub detail: String,
+}
+
+/// Start and end of the period in a "01-Sep-25 to 30-Sep-25" date range
+fn document_period(range: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let (start, end) = range.split_once(" to ")?;
+    let parse = |value: &str| NaiveDate::parse_from_str(value.trim(), "%d-%b-%y").ok();
+    Some((parse(start)?, parse(end)?))
+}
+
+fn local_date(time: std::io::Result<SystemTime>) -> Option<NaiveDate> {
+    time.ok().map(|time| DateTime::<Local>::from(time).date_naive())
+}
+
+fn format_date(date: Option<NaiveDate>) -> String {
+    date.map(|date| date.format("%Y-%m-%d").to_string()).unwrap_or_default()
+}
+
+/// Compares each item's filesystem dates with the period in its document date
+/// range. Copying files resets creation dates on most systems, so the results
+/// are hints for review rather than findings.
+pub fn find_date_inconsistencies(
+    items: &[InventoryItem],
+    hold_date: Option<NaiveDate>,
+    max_gap_days: Option<i64>,
+) -> Vec<DateInconsistency> {
+    let mut found = Vec::new();
+    for item in items.iter().filter(|item| !item.absolute_path.is_empty()) {
+        let Some((start, end)) = document_period(&item.doc_date_range) else {
+            continue;
+        };
+        let Ok(metadata) = fs::metadata(&item.absolute_path) else {
+            continue;
+        };
+        let created = local_date(metadata.created());
+        let modified = local_date(metadata.modified());
+
+        let mut flag = |kind: InconsistencyKind, detail: String| {
+            found.push(DateInconsistency {
+                absolute_path: item.absolute_path.clone(),
+                file_name: item.file_name.clone(),
+                kind,
+                doc_date_range: item.doc_date_range.clone(),
+                created: format_date(created),
+                modified: format_date(modified),
+                detail,
+            });
+        };
+
+        if let Some(modified) = modified.filter(|modified| *modified < start) {
+            flag(
+                InconsistencyKind::ModifiedBeforeDocument,
+                format!("Modified {} days before the period starts", (start - modified).num_days()),
+            );
+        }
+        if let Some(hold) = hold_date.filter(|hold| end < *hold) {
+            if let Some(created) = created.filter(|created| *created > hold) {
+                flag(
+                    InconsistencyKind::CreatedAfterHold,
+                    format!("Created {} days after the hold", (created - hold).num_days()),
+                );
+            }
+            if let Some(modified) = modified.filter(|modified| *modified > hold) {
+                flag(
+                    InconsistencyKind::ModifiedAfterHold,
+                    format!("Modified {} days after the hold", (modified - hold).num_days()),
+                );
+            }
+        }
+        if let (Some(max_gap), Some(modified)) = (max_gap_days, modified) {
+            let gap = (modified - end).num_days();
+            if gap > max_gap {
+                flag(
+                    InconsistencyKind::ModifiedLongAfterDocument,
+                    format!("Modified {} days after the period ends", gap),
+                );
+            }
+        }
+    }
+    found
+}
+
+fn report_row(issue: &DateInconsistency) -> [&str; 7] {
+    [
+        &issue.file_name,
+        &issue.absolute_path,
+        issue.kind.label(),
+        &issue.doc_date_range,
+        &issue.created,
+        &issue.modified,
+        &issue.detail,
+    ]
+}
+
+pub fn write_inconsistencies_csv(issues: &[DateInconsistency], output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_path(output_path)?;
+    writer.write_record(REPORT_HEADERS)?;
+    for issue in issues {
+        writer.write_record(report_row(issue))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn write_inconsistencies_xlsx(issues: &[DateInconsistency], output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold().set_border(FormatBorder::Thin);
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Date Inconsistencies")?;
+    for (col, header) in REPORT_HEADERS.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *header, &header_format)?;
+    }
+    sheet.set_column_width(0, 30.0)?;
+    sheet.set_column_width(1, 60.0)?;
+    sheet.set_column_width(2, 30.0)?;
+    sheet.set_column_width(3, 22.0)?;
+    sheet.set_column_width(6, 40.0)?;
+    for (idx, issue) in issues.iter().enumerate() {
+        for (col, value) in report_row(issue).iter().enumerate() {
+            sheet.write_string(idx as u32 + 1, col as u16, *value)?;
+        }
+    }
+
+    workbook.save(output_path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn item(path: &std::path::Path, doc_date_range: &str) -> InventoryItem {
+        InventoryItem {
+            doc_year: 2020,
+            doc_date_range: doc_date_range.to_string(),
+            ..test_item(&path.to_string_lossy())
+        }
+    }
+
+    fn kinds(issues: &[DateInconsistency], file_name: &str) -> Vec<InconsistencyKind> {
+        issues.iter().filter(|issue| issue.file_name == file_name).map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn filesystem_dates_are_compared_with_the_document_period() {
+        let dir = std::env::temp_dir().join(format!("consistency-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let early = dir.join("early.pdf");
+        let recent = dir.join("recent.pdf");
+        let undated = dir.join("undated.pdf");
+        for path in [&early, &recent, &undated] {
+            fs::write(path, b"statement").unwrap();
+        }
+        // 2019-06-15, well before the statement period
+        fs::File::options()
+            .write(true)
+            .open(&early)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(1_560_600_000))
+            .unwrap();
+
+        let items = [
+            item(&early, "01-Sep-20 to 30-Sep-20"),
+            item(&recent, "01-Sep-20 to 30-Sep-20"),
+            item(&undated, ""),
+        ];
+        let hold = NaiveDate::from_ymd_opt(2021, 1, 1);
+
+        let issues = find_date_inconsistencies(&items, None, None);
+        assert_eq!(kinds(&issues, "early.pdf"), [InconsistencyKind::ModifiedBeforeDocument]);
+        assert_eq!(issues[0].modified, "2019-06-15");
+        assert!(kinds(&issues, "recent.pdf").is_empty());
+
+        let issues = find_date_inconsistencies(&items, hold, Some(365));
+        let recent_kinds = kinds(&issues, "recent.pdf");
+        assert!(recent_kinds.contains(&InconsistencyKind::ModifiedAfterHold));
+        assert!(recent_kinds.contains(&InconsistencyKind::ModifiedLongAfterDocument));
+        assert_eq!(
+            recent_kinds.contains(&InconsistencyKind::CreatedAfterHold),
+            fs::metadata(&recent).unwrap().created().is_ok()
+        );
+        assert!(kinds(&issues, "undated.pdf").is_empty());
+
+        let csv_path = dir.join("issues.csv");
+        write_inconsistencies_csv(&issues, csv_path.to_str().unwrap()).unwrap();
+        let csv = fs::read_to_string(&csv_path).unwrap();
+        assert!(csv.starts_with("File Name,Path,Issue,Document Period,Created,Modified,Detail"));
+        assert!(csv.contains("Modified before document date"));
+        assert_eq!(csv.lines().count(), issues.len() + 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
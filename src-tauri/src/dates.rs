@@ -0,0 +1,94 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// A deadline or derived date, e.g. "Answer due" = 30 days after service
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DateOffset {
+    pub label: String,
+    /// Start date as YYYY-MM-DD
+    pub base_date: String,
+    /// Days to add; negative values count backwards
+    pub days: i64,
+    /// Count only weekdays that are not holidays
+    #[serde(default)]
+    pub business_days: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComputedDate {
+    pub label: String,
+    pub base_date: String,
+    pub date: String,
+    pub weekday: String,
+    /// Calendar days from the base date to `date`
+    pub calendar_days: i64,
+    /// Business days from the base date to `date`
+    pub business_days: i64,
+}
+
+pub(crate) fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), DATE_FORMAT).map_err(|_| value.to_string())
+}
+
+/// Parses holiday dates; returns the first invalid value on error
+pub fn parse_holidays(holidays: &[String]) -> Result<HashSet<NaiveDate>, String> {
+    holidays.iter().map(|h| parse_date(h)).collect()
+}
+
+fn is_business_day(date: NaiveDate, holidays: &HashSet<NaiveDate>) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !holidays.contains(&date)
+}
+
+/// Moves `days` business days from `start`, skipping weekends and holidays
+fn add_business_days(start: NaiveDate, days: i64, holidays: &HashSet<NaiveDate>) -> NaiveDate {
+    let step = if days < 0 { -1 } else { 1 };
+    let mut date = start;
+    let mut remaining = days.abs();
+    while remaining > 0 {
+        date += Duration::days(step);
+        if is_business_day(date, holidays) {
+            remaining -= 1;
+        }
+    }
+    date
+}
+
+/// Business days after `start` up to and including `end` (negative if `end` is earlier)
+pub fn business_days_between(start: NaiveDate, end: NaiveDate, holidays: &HashSet<NaiveDate>) -> i64 {
+    let (from, to, sign) = if end >= start { (start, end, 1) } else { (end, start, -1) };
+    let count = from
+        .iter_days()
+        .skip(1)
+        .take_while(|date| *date <= to)
+        .filter(|date| is_business_day(*date, holidays))
+        .count() as i64;
+    sign * count
+}
+
+/// Computes each offset; returns the first invalid date string on error
+pub fn compute_date_offsets(offsets: &[DateOffset], holidays: &[String]) -> Result<Vec<ComputedDate>, String> {
+    let holidays = parse_holidays(holidays)?;
+
+    offsets
+        .iter()
+        .map(|offset| {
+            let base = parse_date(&offset.base_date)?;
+            let date = if offset.business_days {
+                add_business_days(base, offset.days, &holidays)
+            } else {
+                base + Duration::days(offset.days)
+            };
+            Ok(ComputedDate {
+                label: offset.label.clone(),
+                base_date: base.format(DATE_FORMAT).to_string(),
+                date: date.format(DATE_FORMAT).to_string(),
+                weekday: date.format("%A").to_string(),
+                calendar_days: (date - base).num_days(),
+                business_days: business_days_between(base, date, &holidays),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,201 @@
+use crate::{InventoryItem, INVENTORY_FIELDS};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+/// Export headers accepted as column names, alongside the raw field names
+const HEADER_FIELDS: &[(&str, &str)] = &[
+    ("Date Rcvd", "date_rcvd"),
+    ("Doc Year", "doc_year"),
+    ("Doc Date Range", "doc_date_range"),
+    ("Document Type", "document_type"),
+    ("Document Description", "document_description"),
+    ("Bates Stamp", "bates_stamp"),
+    ("Notes", "notes"),
+];
+
+/// A single field change that was (or, in a dry run, would be) applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionChange {
+    pub key: String,
+    pub absolute_path: String,
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionImportResult {
+    /// Items with the decisions applied (unchanged when dry_run is set)
+    pub items: Vec<InventoryItem>,
+    pub changes: Vec<DecisionChange>,
+    /// Keys that matched no item
+    pub unmatched_keys: Vec<String>,
+    /// Keys that matched more than one item and were not applied
+    pub ambiguous_keys: Vec<String>,
+    /// Column headers that don't correspond to an inventory field
+    pub ignored_columns: Vec<String>,
+    pub dry_run: bool,
+}
+
+fn field_for_header(header: &str) -> Option<&'static str> {
+    let header = header.trim();
+    HEADER_FIELDS
+        .iter()
+        .find(|(label, _)| label.eq_ignore_ascii_case(header))
+        .map(|(_, field)| *field)
+        .or_else(|| INVENTORY_FIELDS.iter().find(|field| **field == header).copied())
+}
+
+/// Applies reviewer decisions from a CSV whose first column identifies the
+/// document (Bates stamp, or file name when no Bates stamp matches) and whose
+/// remaining columns are inventory fields to set. Empty cells are skipped.
+pub fn import_review_decisions(
+    mut items: Vec<InventoryItem>,
+    path: &str,
+    dry_run: bool,
+) -> Result<DecisionImportResult, Box<dyn std::error::Error>> {
+    // A dry run applies changes to a working copy and hands back the originals
+    let original_items = if dry_run { Some(items.clone()) } else { None };
+    let mut rdr = csv::Reader::from_reader(BufReader::new(File::open(path)?));
+    let headers = rdr.headers()?.clone();
+
+    let mut columns = Vec::new();
+    let mut ignored_columns = Vec::new();
+    for (idx, header) in headers.iter().enumerate().skip(1) {
+        match field_for_header(header) {
+            Some(field) => columns.push((idx, field)),
+            None => ignored_columns.push(header.to_string()),
+        }
+    }
+
+    let mut by_bates: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+    for (idx, item) in items.iter().enumerate() {
+        if !item.bates_stamp.is_empty() {
+            by_bates.entry(item.bates_stamp.trim().to_lowercase()).or_default().push(idx);
+        }
+        by_name.entry(item.file_name.trim().to_lowercase()).or_default().push(idx);
+    }
+
+    let mut changes = Vec::new();
+    let mut unmatched_keys = Vec::new();
+    let mut ambiguous_keys = Vec::new();
+
+    for record in rdr.records() {
+        let record = record?;
+        let key = record.get(0).unwrap_or("").trim().to_string();
+        if key.is_empty() {
+            continue;
+        }
+
+        let lookup = key.to_lowercase();
+        let matches = by_bates.get(&lookup).or_else(|| by_name.get(&lookup));
+        let idx = match matches.map(|m| m.as_slice()) {
+            Some([idx]) => *idx,
+            Some(_) => {
+                ambiguous_keys.push(key);
+                continue;
+            }
+            None => {
+                unmatched_keys.push(key);
+                continue;
+            }
+        };
+
+        for (col, field) in &columns {
+            let new_value = record.get(*col).unwrap_or("").trim();
+            if new_value.is_empty() {
+                continue;
+            }
+            let item = &mut items[idx];
+            let old_value = item.field_value(field).unwrap_or_default();
+            if old_value == new_value {
+                continue;
+            }
+            if item.set_field_value(field, new_value) {
+                changes.push(DecisionChange {
+                    key: key.clone(),
+                    absolute_path: item.absolute_path.clone(),
+                    field: field.to_string(),
+                    old_value,
+                    new_value: new_value.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(DecisionImportResult {
+        items: original_items.unwrap_or(items),
+        changes,
+        unmatched_keys,
+        ambiguous_keys,
+        ignored_columns,
+        dry_run,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+    use std::fs;
+
+    fn item(file_name: &str, bates_stamp: &str) -> InventoryItem {
+        InventoryItem { bates_stamp: bates_stamp.to_string(), ..test_item(&format!("/c/{}", file_name)) }
+    }
+
+    fn write_csv(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("decisions-{}-{}.csv", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn items() -> Vec<InventoryItem> {
+        vec![
+            item("jan.pdf", "ABC-0001"),
+            item("feb.pdf", "ABC-0002"),
+            item("copy.pdf", ""),
+            item("copy.pdf", ""),
+        ]
+    }
+
+    #[test]
+    fn decisions_match_by_bates_then_file_name() {
+        let path = write_csv(
+            "apply",
+            "Key,Notes,Doc Year,Reviewer\n\
+             abc-0001,Privileged,,Kim\n\
+             FEB.PDF,,2023,Kim\n\
+             copy.pdf,Dup,,Kim\n\
+             ABC-9999,Missing,,Kim\n",
+        );
+        let result = import_review_decisions(items(), &path.to_string_lossy(), false).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.items[0].notes, "Privileged");
+        assert_eq!(result.items[1].doc_year, 2023);
+        let changed: Vec<(&str, &str)> = result
+            .changes
+            .iter()
+            .map(|change| (change.absolute_path.as_str(), change.field.as_str()))
+            .collect();
+        assert_eq!(changed, [("/c/jan.pdf", "notes"), ("/c/feb.pdf", "doc_year")]);
+        assert_eq!(result.ambiguous_keys, ["copy.pdf"]);
+        assert_eq!(result.unmatched_keys, ["ABC-9999"]);
+        assert_eq!(result.ignored_columns, ["Reviewer"]);
+    }
+
+    #[test]
+    fn dry_runs_report_changes_without_applying_them() {
+        let path = write_csv("dry", "Bates,notes\nABC-0002,Hot\nABC-0001,\n");
+        let result = import_review_decisions(items(), &path.to_string_lossy(), true).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(result.dry_run);
+        assert_eq!(result.changes.len(), 1);
+        assert_eq!(result.changes[0].new_value, "Hot");
+        assert!(result.items.iter().all(|item| item.notes.is_empty()));
+    }
+}
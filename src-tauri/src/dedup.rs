@@ -0,0 +1,288 @@
+use crate::duplicates::DuplicatesReport;
+use crate::json_store;
+use crate::paths::path_key;
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+const DEDUP_FILE: &str = "dedup.json";
+
+/// A case's duplicate culling state. Non-primary copies are suppressed from
+/// default views, counts and exports unless the reviewer re-includes them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DedupPolicy {
+    pub case_number: Option<String>,
+    /// Member paths of each duplicate group, by content hash
+    pub groups: BTreeMap<String, Vec<String>>,
+    /// Primary chosen by the reviewer, by content hash; otherwise the first
+    /// member in inventory order is primary
+    pub primaries: BTreeMap<String, String>,
+    /// Duplicates kept in review scope despite not being primary
+    pub included: BTreeSet<String>,
+    /// Derived from the above: copies excluded from review scope
+    pub suppressed: BTreeSet<String>,
+    /// Paths are matched ignoring case, as on the volume the policy was applied to
+    #[serde(default)]
+    pub case_insensitive: bool,
+}
+
+impl DedupPolicy {
+    /// Comparison keys of the suppressed copies, for filtering items
+    pub fn suppressed_keys(&self) -> HashSet<String> {
+        self.suppressed.iter().map(|path| path_key(path, self.case_insensitive)).collect()
+    }
+
+    /// The group member's stored spelling of `path`, if it is in any group
+    fn member_path(&self, path: &str) -> Option<&String> {
+        let key = path_key(path, self.case_insensitive);
+        self.groups
+            .values()
+            .flatten()
+            .find(|member| path_key(member, self.case_insensitive) == key)
+    }
+
+    /// The items outside the suppressed set: what views, counts, reports and
+    /// exports see
+    pub fn in_review_scope(&self, items: Vec<InventoryItem>) -> Vec<InventoryItem> {
+        if self.suppressed.is_empty() {
+            return items;
+        }
+        let suppressed = self.suppressed_keys();
+        items
+            .into_iter()
+            .filter(|item| !suppressed.contains(&path_key(&item.absolute_path, self.case_insensitive)))
+            .collect()
+    }
+
+    /// Paths from earlier runs may be spelled differently from the current
+    /// group members, so primaries and re-included files are matched by key
+    fn recompute(&mut self) {
+        let case_insensitive = self.case_insensitive;
+        let groups = &self.groups;
+        self.primaries = std::mem::take(&mut self.primaries)
+            .into_iter()
+            .filter_map(|(hash, primary)| {
+                let key = path_key(&primary, case_insensitive);
+                let member = groups.get(&hash)?.iter().find(|member| path_key(member, case_insensitive) == key)?;
+                Some((hash, member.clone()))
+            })
+            .collect();
+
+        let included: HashSet<String> = self.included.iter().map(|path| path_key(path, case_insensitive)).collect();
+        let mut suppressed = BTreeSet::new();
+        for (hash, members) in &self.groups {
+            let primary = self.primaries.get(hash).or_else(|| members.first()).map(|path| path_key(path, case_insensitive));
+            suppressed.extend(
+                members
+                    .iter()
+                    .filter(|path| {
+                        let key = path_key(path, case_insensitive);
+                        primary.as_ref() != Some(&key) && !included.contains(&key)
+                    })
+                    .cloned(),
+            );
+        }
+        self.suppressed = suppressed;
+    }
+}
+
+fn dedup_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(DEDUP_FILE)
+}
+
+fn policy_for(all: &[DedupPolicy], case_number: Option<&str>) -> DedupPolicy {
+    all.iter()
+        .find(|policy| policy.case_number.as_deref() == case_number)
+        .cloned()
+        .unwrap_or_else(|| DedupPolicy {
+            case_number: case_number.map(|s| s.to_string()),
+            ..Default::default()
+        })
+}
+
+/// The case's policy; an empty policy (nothing suppressed) if none was applied
+pub fn read_policy(data_dir: &Path, case_number: Option<&str>) -> Result<DedupPolicy, Box<dyn std::error::Error>> {
+    let all: Vec<DedupPolicy> = json_store::read(&dedup_path(data_dir))?;
+    Ok(policy_for(&all, case_number))
+}
+
+/// Reads the case's policy, lets `change` modify it, then recomputes and saves
+/// it. `change` returns false to abandon the update.
+fn update_policy(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    change: impl FnOnce(&mut DedupPolicy) -> bool,
+) -> Result<Option<DedupPolicy>, Box<dyn std::error::Error>> {
+    json_store::update(&dedup_path(data_dir), |all: &mut Vec<DedupPolicy>| {
+        let mut policy = policy_for(all, case_number);
+        if !change(&mut policy) {
+            return None;
+        }
+        policy.recompute();
+
+        all.retain(|existing| existing.case_number.as_deref() != case_number);
+        if !policy.groups.is_empty() {
+            all.push(policy.clone());
+        }
+        Some(policy)
+    })
+}
+
+/// Replaces the case's duplicate groups with a fresh analysis, keeping chosen
+/// primaries and re-included files that are still duplicates
+pub fn apply_policy(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    report: &DuplicatesReport,
+    case_insensitive: bool,
+) -> Result<DedupPolicy, Box<dyn std::error::Error>> {
+    let policy = update_policy(data_dir, case_number, |policy| {
+        policy.case_insensitive = case_insensitive;
+        policy.groups = report
+            .groups
+            .iter()
+            .map(|group| {
+                let members = group.members.iter().map(|member| member.absolute_path.clone()).collect();
+                (group.hash.clone(), members)
+            })
+            .collect();
+        let members: HashSet<String> =
+            policy.groups.values().flatten().map(|path| path_key(path, case_insensitive)).collect();
+        policy.included.retain(|path| members.contains(&path_key(path, case_insensitive)));
+        true
+    })?;
+    Ok(policy.unwrap_or_default())
+}
+
+/// Makes `path` the primary of its duplicate group. Returns None if the path
+/// is not in the group with that hash.
+pub fn set_primary(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    hash: &str,
+    path: &str,
+) -> Result<Option<DedupPolicy>, Box<dyn std::error::Error>> {
+    update_policy(data_dir, case_number, |policy| {
+        let key = path_key(path, policy.case_insensitive);
+        let Some(member) = policy
+            .groups
+            .get(hash)
+            .and_then(|members| members.iter().find(|member| path_key(member, policy.case_insensitive) == key))
+            .cloned()
+        else {
+            return false;
+        };
+        policy.primaries.insert(hash.to_string(), member);
+        true
+    })
+}
+
+/// Re-includes suppressed duplicates in review scope, or suppresses them again
+pub fn set_included(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    paths: &[String],
+    included: bool,
+) -> Result<DedupPolicy, Box<dyn std::error::Error>> {
+    let policy = update_policy(data_dir, case_number, |policy| {
+        for path in paths {
+            if included {
+                let path = policy.member_path(path).cloned().unwrap_or_else(|| path.clone());
+                policy.included.insert(path);
+            } else {
+                let key = path_key(path, policy.case_insensitive);
+                policy.included.retain(|existing| path_key(existing, policy.case_insensitive) != key);
+            }
+        }
+        true
+    })?;
+    Ok(policy.unwrap_or_default())
+}
+
+/// Drops the case's policy so no file is suppressed
+pub fn clear_policy(data_dir: &Path, case_number: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&dedup_path(data_dir), |all: &mut Vec<DedupPolicy>| {
+        all.retain(|policy| policy.case_number.as_deref() != case_number);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+    use crate::duplicates::{DuplicateGroup, DuplicateMember};
+
+    fn report(paths: &[&str]) -> DuplicatesReport {
+        let members = paths
+            .iter()
+            .enumerate()
+            .map(|(index, path)| DuplicateMember {
+                absolute_path: path.to_string(),
+                file_name: path.rsplit('/').next().unwrap_or_default().to_string(),
+                bates_stamp: String::new(),
+                primary: index == 0,
+            })
+            .collect();
+        DuplicatesReport {
+            groups: vec![DuplicateGroup { group: 1, hash: "h".to_string(), size: 3, members, wasted_bytes: 6 }],
+            duplicate_files: paths.len() - 1,
+            wasted_bytes: 6,
+        }
+    }
+
+    fn suppressed(policy: &DedupPolicy) -> Vec<&str> {
+        policy.suppressed.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn reviewer_choices_survive_a_rerun_with_other_spellings() {
+        let dir = std::env::temp_dir().join(format!("dedup-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let case = Some("A-1");
+
+        let policy = apply_policy(&dir, case, &report(&["/c/a.pdf", "/c/b.pdf", "/c/c.pdf"]), true).unwrap();
+        assert_eq!(suppressed(&policy), ["/c/b.pdf", "/c/c.pdf"]);
+
+        let policy = set_primary(&dir, case, "h", "/C/B.PDF").unwrap().unwrap();
+        assert_eq!(policy.primaries["h"], "/c/b.pdf");
+        assert_eq!(suppressed(&policy), ["/c/a.pdf", "/c/c.pdf"]);
+        assert!(set_primary(&dir, case, "h", "/c/d.pdf").unwrap().is_none());
+        assert!(set_primary(&dir, case, "other", "/c/a.pdf").unwrap().is_none());
+
+        let policy = set_included(&dir, case, &["/C/C.pdf".to_string()], true).unwrap();
+        assert_eq!(suppressed(&policy), ["/c/a.pdf"]);
+
+        // The volume now reports other spellings; the primary and the
+        // re-included copy are still recognised
+        let policy = apply_policy(&dir, case, &report(&["/C/A.pdf", "/C/B.pdf", "/C/C.PDF"]), true).unwrap();
+        assert_eq!(policy.primaries["h"], "/C/B.pdf");
+        assert_eq!(suppressed(&policy), ["/C/A.pdf"]);
+
+        let policy = set_included(&dir, case, &["/c/c.pdf".to_string()], false).unwrap();
+        assert_eq!(suppressed(&policy), ["/C/A.pdf", "/C/C.PDF"]);
+        assert!(read_policy(&dir, Some("B-2")).unwrap().suppressed.is_empty());
+
+        clear_policy(&dir, case).unwrap();
+        assert!(read_policy(&dir, case).unwrap().groups.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn suppressed_copies_are_left_out_of_review_scope() {
+        let mut policy = DedupPolicy {
+            groups: BTreeMap::from([("h".to_string(), vec!["/c/a.pdf".to_string(), "/c/b.pdf".to_string()])]),
+            case_insensitive: true,
+            ..Default::default()
+        };
+        policy.recompute();
+        let items = vec![test_item("/c/a.pdf"), test_item("/C/B.pdf"), test_item("/c/other.pdf")];
+        let in_scope: Vec<String> = policy.in_review_scope(items.clone()).into_iter().map(|item| item.absolute_path).collect();
+        assert_eq!(in_scope, ["/c/a.pdf", "/c/other.pdf"]);
+
+        // On a case-sensitive volume a differently spelled path is another file
+        policy.case_insensitive = false;
+        assert_eq!(policy.in_review_scope(items.clone()).len(), 3);
+        assert_eq!(DedupPolicy::default().in_review_scope(items).len(), 3);
+    }
+}
@@ -0,0 +1,351 @@
+use crate::scanner::is_cloud_placeholder;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Number of files opened to check content access and placeholder status
+const SAMPLE_FILE_LIMIT: usize = 25;
+
+/// Folder names used by the common sync clients
+const CLOUD_FOLDER_MARKERS: &[&str] = &["onedrive", "dropbox", "google drive", "googledrive", "cloudstorage", "icloud"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// One actionable result from an access check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessFinding {
+    pub severity: Severity,
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessDiagnosis {
+    pub path: String,
+    /// True when no error-level findings were recorded
+    pub readable: bool,
+    pub entries_listed: usize,
+    pub files_sampled: usize,
+    pub placeholders_sampled: usize,
+    pub findings: Vec<AccessFinding>,
+}
+
+impl AccessDiagnosis {
+    fn add(&mut self, severity: Severity, code: &str, message: String) {
+        if severity == Severity::Error {
+            self.readable = false;
+        }
+        self.findings.push(AccessFinding {
+            severity,
+            code: code.to_string(),
+            message,
+        });
+    }
+}
+
+/// macOS protects these locations with TCC; listing them fails with
+/// "Operation not permitted" unless the app has Full Disk Access
+fn is_tcc_protected(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    cfg!(target_os = "macos")
+        && ["/Desktop", "/Documents", "/Downloads", "/Library/Mail", "/Library/Messages", "/Volumes/"]
+            .iter()
+            .any(|marker| path.contains(marker))
+}
+
+fn permission_denied_message(path: &Path) -> String {
+    if is_tcc_protected(path) {
+        "macOS blocked access to this folder. Grant the app Full Disk Access in System Settings > Privacy & Security, then try again.".to_string()
+    } else {
+        "The current user does not have permission to read this folder. Check the folder's permissions or sharing settings.".to_string()
+    }
+}
+
+/// Checks whether a source folder can actually be scanned and explains why not
+pub fn diagnose_source_access(path: &Path) -> AccessDiagnosis {
+    let mut diagnosis = AccessDiagnosis {
+        path: path.to_string_lossy().to_string(),
+        readable: true,
+        entries_listed: 0,
+        files_sampled: 0,
+        placeholders_sampled: 0,
+        findings: Vec::new(),
+    };
+
+    if !path.exists() {
+        diagnosis.add(
+            Severity::Error,
+            "not_found",
+            "The path does not exist. If it is on a removable or network drive, make sure the drive is connected.".to_string(),
+        );
+        return diagnosis;
+    }
+
+    if !path.is_dir() {
+        diagnosis.add(Severity::Error, "not_a_directory", "The path is not a folder.".to_string());
+        return diagnosis;
+    }
+
+    let lower = diagnosis.path.to_lowercase();
+    if CLOUD_FOLDER_MARKERS.iter().any(|marker| lower.contains(marker)) {
+        diagnosis.add(
+            Severity::Info,
+            "cloud_folder",
+            "This folder appears to be synced by a cloud storage client. Files set to online-only are placeholders and may be skipped or downloaded on access.".to_string(),
+        );
+    }
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+            diagnosis.add(Severity::Error, "permission_denied", permission_denied_message(path));
+            return diagnosis;
+        }
+        Err(e) => {
+            diagnosis.add(Severity::Error, "read_failed", format!("The folder could not be listed: {}", e));
+            return diagnosis;
+        }
+    };
+
+    let mut unreadable = 0;
+    for entry in entries.flatten() {
+        diagnosis.entries_listed += 1;
+        let entry_path = entry.path();
+        if !entry_path.is_file() || diagnosis.files_sampled >= SAMPLE_FILE_LIMIT {
+            continue;
+        }
+        diagnosis.files_sampled += 1;
+
+        if let Ok(metadata) = fs::metadata(&entry_path) {
+            if is_cloud_placeholder(&metadata) {
+                // Opening a placeholder would trigger a download, so don't
+                diagnosis.placeholders_sampled += 1;
+                continue;
+            }
+        }
+        if fs::File::open(&entry_path).is_err() {
+            unreadable += 1;
+        }
+    }
+
+    if diagnosis.entries_listed == 0 {
+        diagnosis.add(
+            Severity::Warning,
+            "empty",
+            "The folder is empty at the top level. If you expected files here, check that the correct folder was selected and that any cloud sync has finished.".to_string(),
+        );
+    }
+
+    if diagnosis.placeholders_sampled > 0 {
+        diagnosis.add(
+            Severity::Warning,
+            "cloud_placeholders",
+            format!(
+                "{} of {} sampled files are online-only placeholders. Mark the folder as \"Always keep on this device\" to scan real content.",
+                diagnosis.placeholders_sampled, diagnosis.files_sampled
+            ),
+        );
+    }
+
+    if unreadable > 0 {
+        diagnosis.add(
+            Severity::Warning,
+            "unreadable_files",
+            format!(
+                "{} of {} sampled files could not be opened. They may be locked by another program or restricted by permissions.",
+                unreadable, diagnosis.files_sampled
+            ),
+        );
+    }
+
+    diagnosis
+}
+
+/// Whether a source folder can be synced right now
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceStatus {
+    pub available: bool,
+    /// Why the source is offline, when it is
+    pub reason: Option<String>,
+}
+
+/// For paths on removable-media mount points, returns the mount directory
+/// (e.g. `/Volumes/USB`, `/media/alice/USB`, `/mnt/evidence`)
+#[cfg(unix)]
+fn removable_mount_dir(path: &Path) -> Option<std::path::PathBuf> {
+    let components: Vec<_> = path.components().collect();
+    let depth = match components.get(1)?.as_os_str().to_str()? {
+        "Volumes" | "mnt" => 3,
+        "media" => 4,
+        "run" if components.get(2)?.as_os_str() == "media" => 5,
+        _ => return None,
+    };
+    if components.len() < depth {
+        return None;
+    }
+    Some(components[..depth].iter().collect())
+}
+
+/// Decodes the octal escapes (`\040` for a space) used in mount tables
+#[cfg(target_os = "linux")]
+fn unescape_mount_path(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4).and_then(|digits| {
+            std::str::from_utf8(digits).ok().and_then(|digits| u8::from_str_radix(digits, 8).ok())
+        });
+        match (bytes[i], octal) {
+            (b'\\', Some(byte)) => {
+                out.push(byte);
+                i += 4;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Whether `dir` is currently a mount point. Linux reads the mount table;
+/// elsewhere a mounted volume has a different device than its parent.
+#[cfg(unix)]
+fn is_mount_point(dir: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    #[cfg(target_os = "linux")]
+    if let Ok(info) = fs::read_to_string("/proc/self/mountinfo") {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        // Fields: mount ID, parent ID, major:minor, root, mount point, ...
+        return info
+            .lines()
+            .filter_map(|line| line.split(' ').nth(4))
+            .any(|mount_point| Path::new(&unescape_mount_path(mount_point)) == dir);
+    }
+    let (Some(parent), Ok(dir_meta)) = (dir.parent(), fs::metadata(dir)) else {
+        return false;
+    };
+    fs::metadata(parent)
+        .map(|parent_meta| parent_meta.dev() != dir_meta.dev())
+        .unwrap_or(false)
+}
+
+/// A removable-media directory is taken to be an unplugged drive only when it
+/// is gone, or is an empty directory that nothing is mounted on. Ordinary
+/// folders kept under /mnt, /media or /Volumes have content and are left alone.
+#[cfg(unix)]
+fn removable_drive_missing(path: &Path) -> bool {
+    let Some(mount_dir) = removable_mount_dir(path) else {
+        return false;
+    };
+    if !mount_dir.exists() {
+        return true;
+    }
+    if is_mount_point(&mount_dir) {
+        return false;
+    }
+    fs::read_dir(&mount_dir)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false)
+}
+
+/// Drive letters and UNC shares simply stop existing when disconnected,
+/// which the existence check below already covers
+#[cfg(not(unix))]
+fn removable_drive_missing(_path: &Path) -> bool {
+    false
+}
+
+/// Checks that a source folder is present and readable before syncing, so an
+/// unplugged drive is reported as offline rather than as "all files removed"
+pub fn source_status(path: &Path) -> SourceStatus {
+    let offline = |reason: &str| SourceStatus {
+        available: false,
+        reason: Some(reason.to_string()),
+    };
+
+    if removable_drive_missing(path) {
+        return offline("The drive holding this folder is not mounted.");
+    }
+
+    if !path.exists() {
+        return offline("The folder is not available. If it is on a removable or network drive, reconnect it.");
+    }
+    if !path.is_dir() {
+        return offline("The path is not a folder.");
+    }
+    if let Err(e) = fs::read_dir(path) {
+        return SourceStatus {
+            available: false,
+            reason: Some(format!("The folder could not be read: {}", e)),
+        };
+    }
+
+    SourceStatus {
+        available: true,
+        reason: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(diagnosis: &AccessDiagnosis) -> Vec<&str> {
+        diagnosis.findings.iter().map(|finding| finding.code.as_str()).collect()
+    }
+
+    #[test]
+    fn access_findings_explain_what_blocks_a_scan() {
+        let dir = std::env::temp_dir().join(format!("diagnostics-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let synced = dir.join("OneDrive - Firm");
+        fs::create_dir_all(synced.join("Bank")).unwrap();
+        for n in 0..SAMPLE_FILE_LIMIT + 5 {
+            fs::write(synced.join(format!("{}.pdf", n)), "x").unwrap();
+        }
+
+        let missing = diagnose_source_access(&dir.join("gone"));
+        assert!(!missing.readable);
+        assert_eq!(codes(&missing), ["not_found"]);
+        assert_eq!(codes(&diagnose_source_access(&synced.join("0.pdf"))), ["not_a_directory"]);
+
+        let empty = diagnose_source_access(&synced.join("Bank"));
+        assert!(empty.readable);
+        assert_eq!(codes(&empty), ["cloud_folder", "empty"]);
+
+        let diagnosis = diagnose_source_access(&synced);
+        assert!(diagnosis.readable);
+        assert_eq!(codes(&diagnosis), ["cloud_folder"]);
+        assert_eq!(diagnosis.entries_listed, SAMPLE_FILE_LIMIT + 6);
+        assert_eq!(diagnosis.files_sampled, SAMPLE_FILE_LIMIT);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unplugged_drives_are_told_apart_from_missing_folders() {
+        let mount_dir = |path: &str| removable_mount_dir(Path::new(path)).map(|dir| dir.to_string_lossy().to_string());
+        assert_eq!(mount_dir("/Volumes/USB/case/a.pdf").as_deref(), Some("/Volumes/USB"));
+        assert_eq!(mount_dir("/media/alice/USB/case").as_deref(), Some("/media/alice/USB"));
+        assert_eq!(mount_dir("/run/media/alice/USB").as_deref(), Some("/run/media/alice/USB"));
+        assert_eq!(mount_dir("/media/alice"), None);
+        assert_eq!(mount_dir("/home/alice/case"), None);
+
+        let status = source_status(Path::new("/mnt/not-plugged-in-drive/case"));
+        assert!(!status.available);
+        assert_eq!(status.reason.as_deref(), Some("The drive holding this folder is not mounted."));
+        assert!(source_status(&std::env::temp_dir()).available);
+        #[cfg(target_os = "linux")]
+        assert_eq!(unescape_mount_path("/media/USB\\040Drive\\0"), "/media/USB Drive\\0");
+    }
+}
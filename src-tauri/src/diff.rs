@@ -0,0 +1,618 @@
+use crate::scanner::FileMetadata;
+use calamine::{open_workbook_auto, Data, Reader};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Files with more lines than this on either side are compared by metadata only
+const MAX_DIFF_LINES: usize = 2000;
+
+/// Bytes inspected when deciding whether a file is text
+const TEXT_SNIFF_BYTES: usize = 8192;
+
+/// Text files larger than this are not loaded for a line diff
+const MAX_TEXT_BYTES: u64 = 4 * 1024 * 1024;
+
+/// PDFs and Office parts larger than this are not read for structure
+const MAX_STRUCTURE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Changed cells listed per sheet before the rest are only counted
+const MAX_CELL_DIFFERENCES: usize = 20;
+
+/// Document properties compared between Office files (docProps/core.xml)
+const CORE_PROPERTIES: &[&str] = &[
+    "dc:title",
+    "dc:creator",
+    "cp:lastModifiedBy",
+    "cp:revision",
+    "dcterms:created",
+    "dcterms:modified",
+];
+
+/// Info dictionary entries compared between PDFs
+const PDF_INFO_KEYS: &[&str] = &["Title", "Author", "Creator", "Producer", "CreationDate", "ModDate"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLineKind {
+    Same,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataDifference {
+    pub field: String,
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    /// True when both files have byte-identical content
+    pub identical: bool,
+    pub metadata: Vec<MetadataDifference>,
+    /// Document structure that differs: PDF pages, revisions and info
+    /// entries; Office parts and properties; spreadsheet sheets and cells
+    pub structure: Vec<MetadataDifference>,
+    /// Line diff of text files, or paragraph/slide diff of Word and
+    /// PowerPoint files, present only when small enough
+    pub text_diff: Option<Vec<DiffLine>>,
+}
+
+fn load_metadata(path: &Path) -> std::io::Result<FileMetadata> {
+    let parent = path.parent().unwrap_or(path);
+    FileMetadata::from_path(parent, path)
+}
+
+fn contents_identical(a: &Path, b: &Path) -> std::io::Result<bool> {
+    let mut reader_a = BufReader::new(File::open(a)?);
+    let mut reader_b = BufReader::new(File::open(b)?);
+    let mut buf_a = [0u8; 8192];
+    let mut buf_b = [0u8; 8192];
+
+    loop {
+        let read_a = reader_a.read(&mut buf_a)?;
+        if read_a == 0 {
+            return Ok(reader_b.read(&mut buf_b)? == 0);
+        }
+        // Fill the same number of bytes from b so chunks line up
+        if reader_b.read_exact(&mut buf_b[..read_a]).is_err() {
+            return Ok(false);
+        }
+        if buf_a[..read_a] != buf_b[..read_a] {
+            return Ok(false);
+        }
+    }
+}
+
+/// Reads a file as text if it is valid UTF-8 without NUL bytes in its first
+/// block. Only that block is read before deciding, so large binaries are
+/// rejected without loading them.
+fn read_text(path: &Path) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut bytes = Vec::new();
+    (&mut file).take(TEXT_SNIFF_BYTES as u64).read_to_end(&mut bytes).ok()?;
+    if bytes.contains(&0) || file.metadata().ok()?.len() > MAX_TEXT_BYTES {
+        return None;
+    }
+    file.read_to_end(&mut bytes).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Records each named value that differs between the two sides
+fn compare_values(
+    differences: &mut Vec<MetadataDifference>,
+    a: &[(String, String)],
+    b: &[(String, String)],
+) {
+    let lookup = |values: &[(String, String)], field: &str| {
+        values.iter().find(|(name, _)| name == field).map(|(_, value)| value.clone()).unwrap_or_default()
+    };
+    let fields: BTreeSet<&String> = a.iter().chain(b).map(|(field, _)| field).collect();
+    for field in fields {
+        let (value_a, value_b) = (lookup(a, field), lookup(b, field));
+        if value_a != value_b {
+            differences.push(MetadataDifference { field: field.clone(), a: value_a, b: value_b });
+        }
+    }
+}
+
+fn read_capped(path: &Path) -> Option<Vec<u8>> {
+    let file = File::open(path).ok()?;
+    if file.metadata().ok()?.len() > MAX_STRUCTURE_BYTES {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    BufReader::new(file).read_to_end(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+fn count_occurrences(haystack: &[u8], needle: &[u8]) -> usize {
+    haystack.windows(needle.len()).filter(|window| *window == needle).count()
+}
+
+/// Value of a literal string entry such as `/Title (Lease)`, with `\)`
+/// escapes kept as written
+fn pdf_info_value(bytes: &[u8], key: &str) -> Option<String> {
+    let marker = format!("/{}", key);
+    let start = bytes.windows(marker.len()).position(|window| window == marker.as_bytes())? + marker.len();
+    let rest = &bytes[start..];
+    let open = rest.iter().position(|byte| !byte.is_ascii_whitespace())?;
+    if rest[open] != b'(' {
+        return None;
+    }
+    let mut end = open + 1;
+    while end < rest.len() && !(rest[end] == b')' && rest[end - 1] != b'\\') {
+        end += 1;
+    }
+    Some(String::from_utf8_lossy(&rest[open + 1..end.min(rest.len())]).to_string())
+}
+
+/// Version, page count, revision count (one `%%EOF` per incremental save) and
+/// info entries. Objects inside compressed streams are not decoded, so a
+/// PDF that stores its pages or info there reports what is visible.
+fn pdf_structure(path: &Path) -> Option<Vec<(String, String)>> {
+    let bytes = read_capped(path)?;
+    let version = bytes
+        .strip_prefix(b"%PDF-")
+        .map(|rest| String::from_utf8_lossy(&rest[..rest.len().min(3)]).to_string())
+        .unwrap_or_default();
+    let pages = count_occurrences(&bytes, b"/Type /Page") + count_occurrences(&bytes, b"/Type/Page")
+        - count_occurrences(&bytes, b"/Type /Pages")
+        - count_occurrences(&bytes, b"/Type/Pages");
+    let mut values = vec![
+        ("pdf_version".to_string(), version),
+        ("pages".to_string(), pages.to_string()),
+        ("revisions".to_string(), count_occurrences(&bytes, b"%%EOF").to_string()),
+    ];
+    for key in PDF_INFO_KEYS {
+        if let Some(value) = pdf_info_value(&bytes, key) {
+            values.push((key.to_string(), value));
+        }
+    }
+    Some(values)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Text content of an XML fragment with tags removed
+fn xml_text(xml: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in xml.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    decode_entities(&text)
+}
+
+/// Content of the first `<tag ...>...</tag>` element
+fn xml_element(xml: &str, tag: &str) -> Option<String> {
+    let open = xml.find(&format!("<{}", tag))?;
+    let content = open + xml[open..].find('>')? + 1;
+    let close = content + xml[content..].find(&format!("</{}>", tag))?;
+    Some(xml_text(&xml[content..close]))
+}
+
+/// An Office Open XML package: part names, core properties and text lines
+/// (Word paragraphs, or one line per PowerPoint slide)
+struct OfficePackage {
+    parts: BTreeSet<String>,
+    properties: Vec<(String, String)>,
+    lines: Vec<String>,
+}
+
+fn read_part(archive: &mut zip::ZipArchive<File>, name: &str) -> Option<String> {
+    let part = archive.by_name(name).ok()?;
+    if part.size() > MAX_STRUCTURE_BYTES {
+        return None;
+    }
+    let mut xml = String::new();
+    part.take(MAX_STRUCTURE_BYTES).read_to_string(&mut xml).ok()?;
+    Some(xml)
+}
+
+/// Slide parts in presentation order: ppt/slides/slide1.xml, slide2.xml, ...
+fn slide_number(part: &str) -> Option<usize> {
+    part.strip_prefix("ppt/slides/slide")?.strip_suffix(".xml")?.parse().ok()
+}
+
+fn office_package(path: &Path) -> Option<OfficePackage> {
+    let mut archive = zip::ZipArchive::new(File::open(path).ok()?).ok()?;
+    let parts: BTreeSet<String> = archive.file_names().map(|name| name.to_string()).collect();
+
+    let mut properties = Vec::new();
+    if let Some(core) = read_part(&mut archive, "docProps/core.xml") {
+        for property in CORE_PROPERTIES {
+            if let Some(value) = xml_element(&core, property) {
+                properties.push((property.to_string(), value));
+            }
+        }
+    }
+
+    let mut lines = Vec::new();
+    if let Some(document) = read_part(&mut archive, "word/document.xml") {
+        lines.extend(
+            document
+                .split("</w:p>")
+                .map(xml_text)
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty()),
+        );
+    }
+    let mut slides: Vec<(usize, &String)> =
+        parts.iter().filter_map(|part| slide_number(part).map(|number| (number, part))).collect();
+    slides.sort();
+    for (number, part) in slides {
+        if let Some(xml) = read_part(&mut archive, part) {
+            let text: Vec<String> = xml.split("</a:p>").map(xml_text).filter(|t| !t.trim().is_empty()).collect();
+            lines.push(format!("Slide {}: {}", number, text.join(" / ")));
+        }
+    }
+
+    Some(OfficePackage { parts, properties, lines })
+}
+
+fn cell_name(row: usize, col: usize) -> String {
+    let mut letters = String::new();
+    let mut n = col + 1;
+    while n > 0 {
+        letters.insert(0, (b'A' + ((n - 1) % 26) as u8) as char);
+        n = (n - 1) / 26;
+    }
+    format!("{}{}", letters, row + 1)
+}
+
+/// Sheets added or removed, size changes and changed cells per common sheet
+fn spreadsheet_differences(a: &Path, b: &Path) -> Option<Vec<MetadataDifference>> {
+    let mut book_a = open_workbook_auto(a).ok()?;
+    let mut book_b = open_workbook_auto(b).ok()?;
+    let names_a = book_a.sheet_names().to_vec();
+    let names_b = book_b.sheet_names().to_vec();
+
+    let mut differences = Vec::new();
+    if names_a != names_b {
+        differences.push(MetadataDifference {
+            field: "sheets".to_string(),
+            a: names_a.join(", "),
+            b: names_b.join(", "),
+        });
+    }
+    for name in names_a.iter().filter(|name| names_b.contains(name)) {
+        let (Ok(range_a), Ok(range_b)) = (book_a.worksheet_range(name), book_b.worksheet_range(name)) else {
+            continue;
+        };
+        if range_a.get_size() != range_b.get_size() {
+            let size = |(rows, cols): (usize, usize)| format!("{} rows x {} columns", rows, cols);
+            differences.push(MetadataDifference {
+                field: format!("{} size", name),
+                a: size(range_a.get_size()),
+                b: size(range_b.get_size()),
+            });
+        }
+        // Compare over the union of both used areas, in absolute positions
+        let (Some(start_a), Some(end_a), Some(start_b), Some(end_b)) =
+            (range_a.start(), range_a.end(), range_b.start(), range_b.end())
+        else {
+            continue;
+        };
+        let origin = (start_a.0.min(start_b.0), start_a.1.min(start_b.1));
+        let rows = (end_a.0.max(end_b.0) - origin.0 + 1) as usize;
+        let cols = (end_a.1.max(end_b.1) - origin.1 + 1) as usize;
+        let cell = |range: &calamine::Range<Data>, row: usize, col: usize| {
+            range
+                .get_value((origin.0 + row as u32, origin.1 + col as u32))
+                .map(|value| value.to_string())
+                .unwrap_or_default()
+        };
+        let mut changed = 0;
+        for row in 0..rows {
+            for col in 0..cols {
+                let value_a = cell(&range_a, row, col);
+                let value_b = cell(&range_b, row, col);
+                if value_a == value_b {
+                    continue;
+                }
+                changed += 1;
+                if changed <= MAX_CELL_DIFFERENCES {
+                    differences.push(MetadataDifference {
+                        field: format!("{}!{}", name, cell_name(origin.0 as usize + row, origin.1 as usize + col)),
+                        a: value_a,
+                        b: value_b,
+                    });
+                }
+            }
+        }
+        if changed > MAX_CELL_DIFFERENCES {
+            differences.push(MetadataDifference {
+                field: format!("{} changed cells", name),
+                a: String::new(),
+                b: changed.to_string(),
+            });
+        }
+    }
+    Some(differences)
+}
+
+/// Title, author and creation date of a PDF (info dictionary) or Word,
+/// Excel or PowerPoint file (core properties), as far as they are set
+pub(crate) fn document_properties(path: &Path) -> Vec<(&'static str, String)> {
+    const NAMES: &[(&str, &str, &str)] = &[
+        ("title", "Title", "dc:title"),
+        ("author", "Author", "dc:creator"),
+        ("created", "CreationDate", "dcterms:created"),
+    ];
+    let found: Vec<(&'static str, String)> = match extension(path).as_str() {
+        "pdf" => match read_capped(path) {
+            Some(bytes) => NAMES
+                .iter()
+                .filter_map(|(name, key, _)| pdf_info_value(&bytes, key).map(|value| (*name, value)))
+                .collect(),
+            None => Vec::new(),
+        },
+        "docx" | "docm" | "dotx" | "xlsx" | "xlsm" | "pptx" | "pptm" => match office_package(path) {
+            Some(package) => NAMES
+                .iter()
+                .filter_map(|(name, _, property)| {
+                    let (_, value) = package.properties.iter().find(|(key, _)| key == property)?;
+                    Some((*name, value.clone()))
+                })
+                .collect(),
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+    found.into_iter().filter(|(_, value)| !value.trim().is_empty()).collect()
+}
+
+/// Structure differences and, for Word and PowerPoint, a text diff. Files of
+/// other types, or that cannot be parsed, have no structure to compare.
+fn structural_diff(a: &Path, b: &Path) -> (Vec<MetadataDifference>, Option<Vec<DiffLine>>) {
+    let mut differences = Vec::new();
+    match (extension(a).as_str(), extension(b).as_str()) {
+        ("pdf", "pdf") => {
+            if let (Some(values_a), Some(values_b)) = (pdf_structure(a), pdf_structure(b)) {
+                compare_values(&mut differences, &values_a, &values_b);
+            }
+        }
+        ("xlsx" | "xlsm" | "xlsb" | "xls" | "ods", "xlsx" | "xlsm" | "xlsb" | "xls" | "ods") => {
+            differences.extend(spreadsheet_differences(a, b).unwrap_or_default());
+        }
+        ("docx" | "docm" | "dotx" | "pptx" | "pptm", "docx" | "docm" | "dotx" | "pptx" | "pptm") => {
+            if let (Some(package_a), Some(package_b)) = (office_package(a), office_package(b)) {
+                compare_values(&mut differences, &package_a.properties, &package_b.properties);
+                let added: Vec<&String> = package_b.parts.difference(&package_a.parts).collect();
+                let removed: Vec<&String> = package_a.parts.difference(&package_b.parts).collect();
+                if !added.is_empty() || !removed.is_empty() {
+                    let join = |parts: Vec<&String>| parts.iter().map(|part| part.as_str()).collect::<Vec<_>>().join(", ");
+                    differences.push(MetadataDifference {
+                        field: "parts".to_string(),
+                        a: join(removed),
+                        b: join(added),
+                    });
+                }
+                if package_a.lines.len() <= MAX_DIFF_LINES && package_b.lines.len() <= MAX_DIFF_LINES {
+                    let lines_a: Vec<&str> = package_a.lines.iter().map(|line| line.as_str()).collect();
+                    let lines_b: Vec<&str> = package_b.lines.iter().map(|line| line.as_str()).collect();
+                    return (differences, Some(diff_lines(&lines_a, &lines_b)));
+                }
+            }
+        }
+        _ => {}
+    }
+    (differences, None)
+}
+
+/// Longest-common-subsequence line diff
+fn diff_lines(a: &[&str], b: &[&str]) -> Vec<DiffLine> {
+    let (n, m) = (a.len(), b.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            lines.push(DiffLine { kind: DiffLineKind::Same, text: a[i].to_string() });
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine { kind: DiffLineKind::Removed, text: a[i].to_string() });
+            i += 1;
+        } else {
+            lines.push(DiffLine { kind: DiffLineKind::Added, text: b[j].to_string() });
+            j += 1;
+        }
+    }
+    lines.extend(a[i..].iter().map(|l| DiffLine { kind: DiffLineKind::Removed, text: l.to_string() }));
+    lines.extend(b[j..].iter().map(|l| DiffLine { kind: DiffLineKind::Added, text: l.to_string() }));
+    lines
+}
+
+pub fn diff_files(path_a: &Path, path_b: &Path) -> std::io::Result<FileDiff> {
+    let meta_a = load_metadata(path_a)?;
+    let meta_b = load_metadata(path_b)?;
+
+    let mut metadata = Vec::new();
+    let fields = [
+        ("file_type", meta_a.file_type.clone(), meta_b.file_type.clone()),
+        ("size", meta_a.size_human.clone(), meta_b.size_human.clone()),
+        ("created", meta_a.created.clone(), meta_b.created.clone()),
+        ("modified", meta_a.modified.clone(), meta_b.modified.clone()),
+    ];
+    for (field, a, b) in fields {
+        if a != b {
+            metadata.push(MetadataDifference { field: field.to_string(), a, b });
+        }
+    }
+
+    let identical = meta_a.size_bytes == meta_b.size_bytes && contents_identical(path_a, path_b)?;
+
+    let (structure, structure_text) = if identical {
+        (Vec::new(), None)
+    } else {
+        structural_diff(path_a, path_b)
+    };
+    let text_diff = if identical {
+        None
+    } else if structure_text.is_some() {
+        structure_text
+    } else {
+        match (read_text(path_a), read_text(path_b)) {
+            (Some(text_a), Some(text_b)) => {
+                let lines_a: Vec<&str> = text_a.lines().collect();
+                let lines_b: Vec<&str> = text_b.lines().collect();
+                if lines_a.len() <= MAX_DIFF_LINES && lines_b.len() <= MAX_DIFF_LINES {
+                    Some(diff_lines(&lines_a, &lines_b))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    };
+
+    Ok(FileDiff {
+        identical,
+        metadata,
+        structure,
+        text_diff,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+
+    fn kinds(lines: &[DiffLine]) -> Vec<(DiffLineKind, &str)> {
+        lines.iter().map(|line| (line.kind.clone(), line.text.as_str())).collect()
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn docx(path: &Path, title: &str, paragraphs: &[&str]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("docProps/core.xml", options).unwrap();
+        write!(zip, "<cp:coreProperties><dc:title>{}</dc:title></cp:coreProperties>", title).unwrap();
+        zip.start_file("word/document.xml", options).unwrap();
+        for paragraph in paragraphs {
+            write!(zip, "<w:p><w:r><w:t>{}</w:t></w:r></w:p>", paragraph).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn lines_added_removed_and_moved_are_marked() {
+        use DiffLineKind::*;
+        let diff = diff_lines(&["intro", "moved", "terms", "signed"], &["intro", "terms", "signed", "moved", "annex"]);
+        assert_eq!(
+            kinds(&diff),
+            [(Same, "intro"), (Removed, "moved"), (Same, "terms"), (Same, "signed"), (Added, "moved"), (Added, "annex")]
+        );
+        assert!(diff_lines(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn renamed_copies_are_identical_and_edits_are_not() {
+        let dir = temp_dir("diff-text");
+        fs::write(dir.join("lease.txt"), "rent: 100\nterm: 12\n").unwrap();
+        fs::write(dir.join("lease copy.txt"), "rent: 100\nterm: 12\n").unwrap();
+        fs::write(dir.join("lease v2.txt"), "rent: 150\nterm: 12\n").unwrap();
+
+        let renamed = diff_files(&dir.join("lease.txt"), &dir.join("lease copy.txt")).unwrap();
+        assert!(renamed.identical);
+        assert!(renamed.text_diff.is_none() && renamed.structure.is_empty());
+        assert!(renamed.metadata.iter().all(|difference| difference.field != "size"));
+
+        // Same size, different bytes
+        let edited = diff_files(&dir.join("lease.txt"), &dir.join("lease v2.txt")).unwrap();
+        assert!(!edited.identical);
+        let text_diff = edited.text_diff.unwrap();
+        assert_eq!(
+            kinds(&text_diff),
+            [(DiffLineKind::Removed, "rent: 100"), (DiffLineKind::Added, "rent: 150"), (DiffLineKind::Same, "term: 12")]
+        );
+
+        // Binary content gets no line diff
+        fs::write(dir.join("a.bin"), [0u8, 1, 2]).unwrap();
+        fs::write(dir.join("b.bin"), [0u8, 1, 3]).unwrap();
+        let binary = diff_files(&dir.join("a.bin"), &dir.join("b.bin")).unwrap();
+        assert!(!binary.identical && binary.text_diff.is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn pdfs_and_word_files_are_compared_by_structure() {
+        let dir = temp_dir("diff-structure");
+        let pdf = "%PDF-1.4\n1 0 obj << /Type /Pages >> endobj\n2 0 obj << /Type /Page >> endobj\n\
+                   3 0 obj << /Title (Lease) >> endobj\n%%EOF\n";
+        fs::write(dir.join("a.pdf"), pdf).unwrap();
+        let revised = format!("{}4 0 obj << /Type /Page >> endobj\n5 0 obj << /Title (Lease \\) v2) >> endobj\n%%EOF\n", pdf);
+        fs::write(dir.join("b.pdf"), revised).unwrap();
+
+        let diff = diff_files(&dir.join("a.pdf"), &dir.join("b.pdf")).unwrap();
+        let structure: Vec<(&str, &str, &str)> =
+            diff.structure.iter().map(|d| (d.field.as_str(), d.a.as_str(), d.b.as_str())).collect();
+        assert_eq!(structure, [("pages", "1", "2"), ("revisions", "1", "2")]);
+
+        docx(&dir.join("a.docx"), "Lease", &["Parties", "Rent is 100"]);
+        docx(&dir.join("b.docx"), "Lease &amp; annex", &["Parties", "Rent is 150", "Annex"]);
+        let diff = diff_files(&dir.join("a.docx"), &dir.join("b.docx")).unwrap();
+        assert_eq!(diff.structure.len(), 1);
+        assert_eq!((diff.structure[0].a.as_str(), diff.structure[0].b.as_str()), ("Lease", "Lease & annex"));
+        let text_diff = diff.text_diff.unwrap();
+        assert_eq!(
+            kinds(&text_diff),
+            [
+                (DiffLineKind::Same, "Parties"),
+                (DiffLineKind::Removed, "Rent is 100"),
+                (DiffLineKind::Added, "Rent is 150"),
+                (DiffLineKind::Added, "Annex"),
+            ]
+        );
+        assert_eq!(document_properties(&dir.join("b.docx")), [("title", "Lease & annex".to_string())]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+use crate::hashing::{hash_files_parallel, HashAlgorithm};
+use crate::paths::path_key;
+use crate::InventoryItem;
+use rust_xlsxwriter::{Format, FormatBorder, Workbook};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+
+const REPORT_HEADERS: &[&str] = &[
+    "Group",
+    "Status",
+    "File Name",
+    "Path",
+    "Bates Stamp",
+    "Size (bytes)",
+    "Wasted (bytes)",
+    "SHA-256",
+];
+
+/// One copy of a duplicated file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateMember {
+    pub absolute_path: String,
+    pub file_name: String,
+    pub bates_stamp: String,
+    /// The copy kept for review; the first in inventory order
+    pub primary: bool,
+}
+
+/// Files with identical content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub group: usize,
+    pub hash: String,
+    pub size: u64,
+    pub members: Vec<DuplicateMember>,
+    /// Space taken by every copy but the primary
+    pub wasted_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicatesReport {
+    pub groups: Vec<DuplicateGroup>,
+    /// Non-primary copies across all groups
+    pub duplicate_files: usize,
+    pub wasted_bytes: u64,
+}
+
+/// Groups items by content. Only files sharing a size are hashed, since a
+/// unique size rules out a duplicate. An item listed twice under paths that
+/// differ only in case counts once on a case-insensitive volume.
+pub fn find_duplicates(items: &[InventoryItem], workers: usize, case_insensitive: bool) -> DuplicatesReport {
+    let mut listed = HashSet::new();
+    let sized: Vec<(&InventoryItem, u64)> = items
+        .iter()
+        .filter(|item| !item.absolute_path.is_empty())
+        .filter(|item| listed.insert(path_key(&item.absolute_path, case_insensitive)))
+        .filter_map(|item| {
+            let metadata = fs::metadata(&item.absolute_path).ok()?;
+            (metadata.is_file() && metadata.len() > 0).then_some((item, metadata.len()))
+        })
+        .collect();
+    let mut size_counts: HashMap<u64, usize> = HashMap::new();
+    for (_, size) in &sized {
+        *size_counts.entry(*size).or_insert(0) += 1;
+    }
+    let candidates: Vec<(&InventoryItem, u64)> = sized
+        .into_iter()
+        .filter(|(_, size)| size_counts[size] > 1)
+        .collect();
+
+    let paths: Vec<String> = candidates.iter().map(|(item, _)| item.absolute_path.clone()).collect();
+    let hashes = hash_files_parallel(&paths, HashAlgorithm::Sha256, workers);
+
+    // BTreeMap keyed by first appearance keeps groups in inventory order
+    let mut first_seen: HashMap<String, usize> = HashMap::new();
+    let mut groups: BTreeMap<usize, (String, u64, Vec<&InventoryItem>)> = BTreeMap::new();
+    for ((item, size), hashed) in candidates.iter().zip(hashes) {
+        let Some(hash) = hashed.hash else {
+            continue;
+        };
+        let order = first_seen.len();
+        let key = *first_seen.entry(hash.clone()).or_insert(order);
+        groups.entry(key).or_insert_with(|| (hash, *size, Vec::new())).2.push(item);
+    }
+
+    let groups: Vec<DuplicateGroup> = groups
+        .into_values()
+        .filter(|(_, _, members)| members.len() > 1)
+        .enumerate()
+        .map(|(index, (hash, size, members))| DuplicateGroup {
+            group: index + 1,
+            wasted_bytes: size * (members.len() as u64 - 1),
+            members: members
+                .iter()
+                .enumerate()
+                .map(|(position, item)| DuplicateMember {
+                    absolute_path: item.absolute_path.clone(),
+                    file_name: item.file_name.clone(),
+                    bates_stamp: item.bates_stamp.clone(),
+                    primary: position == 0,
+                })
+                .collect(),
+            hash,
+            size,
+        })
+        .collect();
+
+    DuplicatesReport {
+        duplicate_files: groups.iter().map(|group| group.members.len() - 1).sum(),
+        wasted_bytes: groups.iter().map(|group| group.wasted_bytes).sum(),
+        groups,
+    }
+}
+
+/// One report row per group member; wasted space is shown on duplicates only
+fn report_rows(report: &DuplicatesReport) -> Vec<[String; 8]> {
+    report
+        .groups
+        .iter()
+        .flat_map(|group| {
+            group.members.iter().map(move |member| {
+                [
+                    group.group.to_string(),
+                    if member.primary { "Primary" } else { "Duplicate" }.to_string(),
+                    member.file_name.clone(),
+                    member.absolute_path.clone(),
+                    member.bates_stamp.clone(),
+                    group.size.to_string(),
+                    if member.primary { 0 } else { group.size }.to_string(),
+                    group.hash.clone(),
+                ]
+            })
+        })
+        .collect()
+}
+
+pub fn write_duplicates_csv(report: &DuplicatesReport, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_path(output_path)?;
+    writer.write_record(REPORT_HEADERS)?;
+    for row in report_rows(report) {
+        writer.write_record(&row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes a "Summary" sheet and a "Duplicates" sheet with one row per copy
+pub fn write_duplicates_xlsx(report: &DuplicatesReport, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold().set_border(FormatBorder::Thin);
+
+    let summary = workbook.add_worksheet();
+    summary.set_name("Summary")?;
+    summary.set_column_width(0, 30.0)?;
+    summary.set_column_width(1, 15.0)?;
+    let totals = [
+        ("Duplicate groups", report.groups.len() as f64),
+        ("Duplicate files", report.duplicate_files as f64),
+        ("Wasted space (bytes)", report.wasted_bytes as f64),
+    ];
+    for (row, (label, value)) in totals.iter().enumerate() {
+        summary.write_string_with_format(row as u32, 0, *label, &header_format)?;
+        summary.write_number(row as u32, 1, *value)?;
+    }
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Duplicates")?;
+    for (col, header) in REPORT_HEADERS.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *header, &header_format)?;
+    }
+    sheet.set_column_width(2, 30.0)?;
+    sheet.set_column_width(3, 60.0)?;
+    sheet.set_column_width(7, 66.0)?;
+    for (idx, row) in report_rows(report).iter().enumerate() {
+        let row_num = idx as u32 + 1;
+        for (col, value) in row.iter().enumerate() {
+            // Group, size and wasted space are numeric
+            if matches!(col, 0 | 5 | 6) {
+                sheet.write_number(row_num, col as u16, value.parse::<f64>().unwrap_or(0.0))?;
+            } else {
+                sheet.write_string(row_num, col as u16, value)?;
+            }
+        }
+    }
+
+    workbook.save(output_path)?;
+    Ok(())
+}
@@ -14,9 +14,21 @@ pub enum AppError {
     #[error("Path is not a directory: {0}")]
     NotADirectory(String),
 
+    #[error("Path is not a file: {0}")]
+    NotAFile(String),
+
+    #[error("Source is offline: {0} ({1}) - existing inventory was kept")]
+    SourceOffline(String, String),
+
     #[error("Error scanning folder: {0}")]
     ScanError(String),
 
+    #[error("File is outside the source folder: {0}")]
+    OutsideSourceFolder(String),
+
+    #[error("Error accessing retry queue: {0}")]
+    RetryQueueError(String),
+
     #[error("Error generating XLSX: {0}")]
     XlsxError(String),
 
@@ -26,6 +38,9 @@ pub enum AppError {
     #[error("Error generating JSON: {0}")]
     JsonError(String),
 
+    #[error("Error generating SQLite database: {0}")]
+    SqliteError(String),
+
     #[error("Error reading XLSX: {0}")]
     ReadXlsxError(String),
 
@@ -35,6 +50,219 @@ pub enum AppError {
     #[error("Error reading JSON: {0}")]
     ReadJsonError(String),
 
+    #[error("Error generating report: {0}")]
+    ReportError(String),
+
+    #[error("Error accessing activity log: {0}")]
+    ActivityLogError(String),
+
+    #[error("Error accessing performance metrics: {0}")]
+    MetricsError(String),
+
+    #[error("Unknown inventory field: {0}")]
+    UnknownField(String),
+
+    #[error("Column group must list adjacent, known fields not used by another group: {0}")]
+    InvalidColumnGroup(String),
+
+    #[error("Field does not hold dates: {0}")]
+    NotADateField(String),
+
+    #[error("Error comparing files: {0}")]
+    DiffError(String),
+
+    #[error("Invalid date (expected YYYY-MM-DD): {0}")]
+    InvalidDate(String),
+
+    #[error("Invalid time zone (expected UTC or an offset like +05:30): {0}")]
+    InvalidTimeZone(String),
+
+    #[error("Error reading hash manifest: {0}")]
+    ManifestError(String),
+
+    #[error("Unknown placeholder in file name pattern: {0}")]
+    InvalidFilenamePattern(String),
+
+    #[error("Export file already exists: {0}")]
+    ExportExists(String),
+
+    #[error("Invalid value for {0}: {1}")]
+    InvalidFieldValue(String, String),
+
+    #[error("Invalid field format {0}: {1}")]
+    InvalidFieldPattern(String, String),
+
+    #[error("Error accessing field rules: {0}")]
+    FieldRulesError(String),
+
+    #[error("Case is not ready to finalize: {0} values break the field rules")]
+    FieldRulesNotMet(usize),
+
+    #[error("Error accessing binders: {0}")]
+    BinderError(String),
+
+    #[error("Binder not found: {0}")]
+    BinderNotFound(String),
+
+    #[error("Error accessing pinned files: {0}")]
+    PinError(String),
+
+    #[error("Error accessing recent files: {0}")]
+    RecentsError(String),
+
+    #[error("Error accessing watched searches: {0}")]
+    WatchError(String),
+
+    #[error("Watched search not found: {0}")]
+    WatchNotFound(String),
+
+    #[error("Error accessing automation rules: {0}")]
+    RuleError(String),
+
+    #[error("Automation rule not found: {0}")]
+    RuleNotFound(String),
+
+    #[error("Error accessing keyword lists: {0}")]
+    KeywordListError(String),
+
+    #[error("Keyword list not found: {0}")]
+    KeywordListNotFound(String),
+
+    #[error("Error accessing report templates: {0}")]
+    ReportTemplateError(String),
+
+    #[error("Report template not found: {0}")]
+    ReportTemplateNotFound(String),
+
+    #[error("Error accessing file links: {0}")]
+    LinkError(String),
+
+    #[error("File link not found: {0}")]
+    LinkNotFound(String),
+
+    #[error("A file cannot be linked to itself: {0}")]
+    SelfLink(String),
+
+    #[error("Error checking files out for editing: {0}")]
+    CheckoutError(String),
+
+    #[error("File is not checked out: {0}")]
+    NotCheckedOut(String),
+
+    #[error("Error accessing file versions: {0}")]
+    VersionError(String),
+
+    #[error("File version not found: {0}")]
+    VersionNotFound(String),
+
+    #[error("Error accessing file history: {0}")]
+    HistoryError(String),
+
+    #[error("Error searching case: {0}")]
+    SearchError(String),
+
+    #[error("Error accessing notes: {0}")]
+    NoteError(String),
+
+    #[error("Note not found: {0}")]
+    NoteNotFound(u64),
+
+    #[error("Similarity threshold must be between 0 and 1: {0}")]
+    InvalidSimilarityThreshold(f64),
+
+    #[error("Error accessing duplicate policy: {0}")]
+    DedupError(String),
+
+    #[error("File is not in that duplicate group: {0}")]
+    NotInDuplicateGroup(String),
+
+    #[error("Field cannot be re-extracted: {0}")]
+    NotReextractable(String),
+
+    #[error("Error opening case window: {0}")]
+    WindowError(String),
+
+    #[error("Error saving workspace preferences: {0}")]
+    WorkspaceError(String),
+
+    #[error("Error finalizing case: {0}")]
+    FinalizeError(String),
+
+    #[error("Case is finalized and cannot be changed: {0}")]
+    CaseFinalized(String),
+
+    #[error("A case number is required to change the inventory")]
+    CaseNumberRequired,
+
+    #[error("Case has not been finalized: {0}")]
+    CaseNotFinalized(String),
+
+    #[error("Error obtaining trusted timestamp: {0}")]
+    TimestampError(String),
+
+    #[error("Error accessing case sources: {0}")]
+    CaseSourcesError(String),
+
+    #[error("File is not part of the case: {0}")]
+    NotInCase(String),
+
+    #[error("Not a source of the case: {0}")]
+    NotCaseSource(String),
+
+    #[error("The app is locked; enter the passphrase to continue")]
+    AppLocked,
+
+    #[error("Error accessing app lock settings: {0}")]
+    LockError(String),
+
+    #[error("Incorrect passphrase")]
+    WrongPassphrase,
+
+    #[error("No app lock passphrase is set")]
+    NoPassphrase,
+
+    #[error("Error running the local API: {0}")]
+    ApiError(String),
+
+    #[error("Error running external tool: {0}")]
+    ToolError(String),
+
+    #[error("External tool not found: {0}")]
+    ToolNotFound(String),
+
+    #[error("Error accessing export schedules: {0}")]
+    ScheduleError(String),
+
+    #[error("Export schedule not found: {0}")]
+    ScheduleNotFound(String),
+
+    #[error("Invalid schedule field: {0}")]
+    InvalidSchedule(String),
+
+    #[error("Error writing case bundle: {0}")]
+    BundleError(String),
+
+    #[error("Error accessing privilege designations: {0}")]
+    PrivilegeError(String),
+
+    #[error("No privilege designation for: {0}")]
+    PrivilegeNotFound(String),
+
+    #[error("Error accessing productions: {0}")]
+    ProductionError(String),
+
+    #[error("Error accessing evidence vault: {0}")]
+    VaultError(String),
+
+    #[error("No vaulted copy of: {0}")]
+    NotInVault(String),
+
+    #[error("Error checking app data: {0}")]
+    HealthCheckError(String),
+
+    #[error("Error sharing config: {0}")]
+    ConfigError(String),
+
     #[error("Unsupported format: {0}")]
     UnsupportedFormat(String),
 }
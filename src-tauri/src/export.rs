@@ -1,9 +1,11 @@
 use rust_xlsxwriter::*;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{Write, BufReader};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Write, BufReader, BufWriter, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
 use serde_json;
 use calamine::{open_workbook, Reader, Xlsx, Data};
+use sha2::{Digest, Sha256};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct InventoryRow {
@@ -16,8 +18,184 @@ pub struct InventoryRow {
     pub folder_name: String,
     pub folder_path: String,
     pub file_type: String,
+    #[serde(default)]
+    pub file_category: String,
     pub bates_stamp: String,
     pub notes: String,
+    /// Source file location, used only to build XLSX links; never written out
+    #[serde(default, skip_serializing)]
+    pub absolute_path: String,
+    /// File size, read only for grouped XLSX subtotals; never written out
+    #[serde(default, skip_serializing)]
+    pub size_bytes: Option<u64>,
+}
+
+impl InventoryRow {
+    /// Reads a field by its serialized name, as `InventoryItem::field_value` does
+    pub fn field_value(&self, field: &str) -> Option<String> {
+        let value = match field {
+            "date_rcvd" => self.date_rcvd.clone(),
+            "doc_year" => self.doc_year.to_string(),
+            "doc_date_range" => self.doc_date_range.clone(),
+            "document_type" => self.document_type.clone(),
+            "document_description" => self.document_description.clone(),
+            "file_name" => self.file_name.clone(),
+            "folder_name" => self.folder_name.clone(),
+            "folder_path" => self.folder_path.clone(),
+            "file_type" => self.file_type.clone(),
+            "file_category" => self.file_category.clone(),
+            "bates_stamp" => self.bates_stamp.clone(),
+            "notes" => self.notes.clone(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Optional export behaviour passed through from the export dialog
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    /// Continue an interrupted CSV/JSON export from its last checkpoint
+    pub resume: bool,
+    /// Text fields to mask with a placeholder, recorded in every format.
+    /// Masking a path field also drops file links and the source folder.
+    pub redacted_fields: Vec<String>,
+    /// Column groups rendered as merged header cells (XLSX) or nested objects (JSON)
+    pub column_groups: Vec<ColumnGroup>,
+    /// Field to group XLSX rows by, with an outline group and a subtotal row
+    /// (document count and total file size) per value
+    pub group_by: Option<String>,
+    /// Add a "Summary" worksheet with overview counts to XLSX exports
+    pub include_summary: bool,
+    /// How the File Name column links to the source file in XLSX exports
+    pub link_mode: LinkMode,
+    /// Also export duplicates suppressed by the case's dedup policy
+    pub include_suppressed: bool,
+}
+
+/// Hyperlink style for the XLSX File Name column. Absolute links break when
+/// the spreadsheet is opened on another machine, so the target can be made
+/// relative to the delivery folder or rewritten to a network share.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum LinkMode {
+    /// Plain text, no hyperlinks
+    #[default]
+    Plain,
+    /// file:// link to the absolute path on this machine
+    Absolute,
+    /// Link relative to `root`, normally the folder the spreadsheet is delivered in
+    Relative { root: String },
+    /// Replace a local path prefix with a UNC share, e.g. `D:\Cases` -> `\\fileserver\cases`
+    Unc { local_prefix: String, unc_prefix: String },
+}
+
+impl LinkMode {
+    /// Link target for a source file, or None when no link should be written
+    pub fn link_for(&self, absolute_path: &str) -> Option<String> {
+        if absolute_path.is_empty() {
+            return None;
+        }
+        let target = match self {
+            LinkMode::Plain => return None,
+            LinkMode::Absolute => absolute_path.to_string(),
+            LinkMode::Relative { root } => relative_link(Path::new(root), Path::new(absolute_path))
+                .unwrap_or_else(|| absolute_path.to_string()),
+            LinkMode::Unc { local_prefix, unc_prefix } => {
+                let local = local_prefix.trim_end_matches(['/', '\\']);
+                match absolute_path.get(..local.len()) {
+                    Some(prefix) if !local.is_empty() && prefix.eq_ignore_ascii_case(local) => {
+                        let rest = &absolute_path[local.len()..];
+                        format!("{}{}", unc_prefix.trim_end_matches(['/', '\\']), rest.replace('/', "\\"))
+                    }
+                    _ => absolute_path.to_string(),
+                }
+            }
+        };
+        Some(format!("file:///{}", target.trim_start_matches('/')))
+    }
+}
+
+/// Path from `root` to `target` using `..` steps, in Excel's backslash form.
+/// None if they share no common prefix (e.g. different drives).
+fn relative_link(root: &Path, target: &Path) -> Option<String> {
+    let root: Vec<Component> = root.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = root.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return None;
+    }
+    
+    let mut parts: Vec<String> = vec!["..".to_string(); root.len() - common];
+    parts.extend(target[common..].iter().map(|c| c.as_os_str().to_string_lossy().to_string()));
+    Some(parts.join("\\"))
+}
+
+/// A named set of adjacent columns, e.g. "Document Info" or "Review"
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ColumnGroup {
+    pub name: String,
+    /// Field names as in INVENTORY_FIELDS; must be adjacent in the export
+    pub fields: Vec<String>,
+}
+
+/// Resolves each group to its first and last column index. Returns the group
+/// name if it is empty, names an unknown field, skips a column or overlaps
+/// another group.
+pub fn resolve_column_groups(groups: &[ColumnGroup]) -> Result<Vec<(u16, u16)>, String> {
+    let mut used = vec![false; crate::INVENTORY_FIELDS.len()];
+    let mut spans = Vec::new();
+    
+    for group in groups {
+        let mut columns = Vec::new();
+        for field in &group.fields {
+            let column = crate::INVENTORY_FIELDS
+                .iter()
+                .position(|f| f == field)
+                .ok_or_else(|| group.name.clone())?;
+            columns.push(column);
+        }
+        columns.sort_unstable();
+        columns.dedup();
+        
+        let (Some(&first), Some(&last)) = (columns.first(), columns.last()) else {
+            return Err(group.name.clone());
+        };
+        if last - first + 1 != columns.len() || columns.iter().any(|&c| used[c]) {
+            return Err(group.name.clone());
+        }
+        for &column in &columns {
+            used[column] = true;
+        }
+        spans.push((first as u16, last as u16));
+    }
+    
+    Ok(spans)
+}
+
+/// Column headings, in the same order as INVENTORY_FIELDS
+pub const EXPORT_HEADERS: &[&str] = &[
+    "Date Rcvd",
+    "Doc Year",
+    "Doc Date Range",
+    "Document Type",
+    "Document Description",
+    "File Name",
+    "Folder Name",
+    "Folder Path",
+    "File Type",
+    "File Category",
+    "Bates Stamp",
+    "Notes",
+];
+
+/// Column heading for a field name, e.g. "doc_year" -> "Doc Year"
+pub fn field_label(field: &str) -> Option<&'static str> {
+    crate::INVENTORY_FIELDS
+        .iter()
+        .position(|f| *f == field)
+        .map(|index| EXPORT_HEADERS[index])
 }
 
 pub fn generate_xlsx(
@@ -25,6 +203,7 @@ pub fn generate_xlsx(
     case_number: Option<&str>,
     folder_path: Option<&str>,
     output_path: &str,
+    options: &ExportOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut workbook = Workbook::new();
     let worksheet = workbook.add_worksheet();
@@ -39,8 +218,9 @@ pub fn generate_xlsx(
     worksheet.set_column_width(6, 20.0)?; // Folder Name
     worksheet.set_column_width(7, 40.0)?; // Folder Path
     worksheet.set_column_width(8, 10.0)?; // File Type
-    worksheet.set_column_width(9, 15.0)?; // Bates Stamp
-    worksheet.set_column_width(10, 30.0)?; // Notes
+    worksheet.set_column_width(9, 14.0)?; // File Category
+    worksheet.set_column_width(10, 15.0)?; // Bates Stamp
+    worksheet.set_column_width(11, 30.0)?; // Notes
     
     // Create header format (bold)
     let header_format = Format::new()
@@ -48,6 +228,7 @@ pub fn generate_xlsx(
         .set_border(FormatBorder::Thin);
     
     // Write metadata rows if case number or folder path provided
+    let redaction_note = redaction_note(&options.redacted_fields);
     let mut current_row = 0;
     if case_number.is_some() {
         // Create centered format for merged title cells
@@ -73,7 +254,10 @@ pub fn generate_xlsx(
         }
         current_row += 1;
         
-        // Empty row for spacing
+        // Spacing row, which also records any redacted fields
+        if let Some(note) = &redaction_note {
+            worksheet.write_string(current_row, 0, note)?;
+        }
         current_row += 1;
     } else if folder_path.is_some() {
         // If no case number but folder path exists, write folder path row
@@ -83,57 +267,430 @@ pub fn generate_xlsx(
         }
         current_row += 1;
         
-        // Empty row for spacing
+        // Spacing row, which also records any redacted fields
+        if let Some(note) = &redaction_note {
+            worksheet.write_string(current_row, 0, note)?;
+        }
+        current_row += 1;
+    } else if let Some(note) = &redaction_note {
+        worksheet.write_string(current_row, 0, note)?;
         current_row += 1;
     }
     
-    // Write headers
-    let headers = [
-        "Date Rcvd",
-        "Doc Year",
-        "Doc Date Range",
-        "Document Type",
-        "Document Description",
-        "File Name",
-        "Folder Name",
-        "Folder Path",
-        "File Type",
-        "Bates Stamp",
-        "Notes",
-    ];
+    // Write merged group headers above the column headers
+    if !options.column_groups.is_empty() {
+        let group_format = Format::new()
+            .set_bold()
+            .set_border(FormatBorder::Thin)
+            .set_align(FormatAlign::Center);
+        let spans = resolve_column_groups(&options.column_groups)?;
+        for (group, (first, last)) in options.column_groups.iter().zip(spans) {
+            if first == last {
+                worksheet.write_string_with_format(current_row, first, &group.name, &group_format)?;
+            } else {
+                worksheet.merge_range(current_row, first, current_row, last, &group.name, &group_format)?;
+            }
+        }
+        current_row += 1;
+    }
     
-    for (col, header) in headers.iter().enumerate() {
+    // Write headers
+    for (col, header) in EXPORT_HEADERS.iter().enumerate() {
         worksheet.write_string_with_format(current_row, col as u16, header.to_string(), &header_format)?;
     }
+    if options.group_by.is_some() {
+        worksheet.set_column_width(SIZE_TOTAL_COLUMN, 18.0)?;
+        worksheet.write_string_with_format(current_row, SIZE_TOTAL_COLUMN, "Total Size (bytes)", &header_format)?;
+    }
     current_row += 1;
     
     // Write data rows
+    match options.group_by.as_deref() {
+        None => {
+            for row in rows {
+                write_xlsx_row(worksheet, current_row, row, &options.link_mode)?;
+                current_row += 1;
+            }
+        }
+        Some(field) => {
+            write_grouped_rows(worksheet, current_row, rows, field, &options.link_mode)?;
+        }
+    }
+    
+    if options.include_summary {
+        write_summary_sheet(workbook.add_worksheet(), rows)?;
+    }
+    
+    // Save to a partial file first so a failed save never clobbers an existing export
+    workbook.save(partial_path(output_path))?;
+    finish_partial(output_path)?;
+    Ok(())
+}
+
+fn write_xlsx_row(
+    worksheet: &mut Worksheet,
+    current_row: u32,
+    row: &InventoryRow,
+    link_mode: &LinkMode,
+) -> Result<(), XlsxError> {
+    worksheet.write_string(current_row, 0, &row.date_rcvd)?;
+    worksheet.write_number(current_row, 1, row.doc_year as f64)?;
+    worksheet.write_string(current_row, 2, &row.doc_date_range)?;
+    worksheet.write_string(current_row, 3, &row.document_type)?;
+    worksheet.write_string(current_row, 4, &row.document_description)?;
+    match link_mode.link_for(&row.absolute_path) {
+        Some(link) => worksheet.write_url_with_text(current_row, 5, Url::new(link), &row.file_name)?,
+        None => worksheet.write_string(current_row, 5, &row.file_name)?,
+    };
+    worksheet.write_string(current_row, 6, &row.folder_name)?;
+    worksheet.write_string(current_row, 7, &row.folder_path)?;
+    worksheet.write_string(current_row, 8, &row.file_type)?;
+    worksheet.write_string(current_row, 9, &row.file_category)?;
+    worksheet.write_string(current_row, 10, &row.bates_stamp)?;
+    worksheet.write_string(current_row, 11, &row.notes)?;
+    Ok(())
+}
+
+/// Writes the overview tables attorneys ask for: documents by type and year
+/// (with totals) and documents by file type
+fn write_summary_sheet(worksheet: &mut Worksheet, rows: &[InventoryRow]) -> Result<(), XlsxError> {
+    worksheet.set_name("Summary")?;
+    worksheet.set_column_width(0, 30.0)?;
+    
+    let heading_format = Format::new().set_bold().set_font_size(12);
+    let header_format = Format::new()
+        .set_bold()
+        .set_border(FormatBorder::Thin);
+    let total_format = Format::new()
+        .set_bold()
+        .set_border_top(FormatBorder::Thin);
+    
+    let mut by_type_year: BTreeMap<&str, BTreeMap<i32, u32>> = BTreeMap::new();
+    let mut years: BTreeSet<i32> = BTreeSet::new();
+    let mut by_file_type: BTreeMap<&str, u32> = BTreeMap::new();
     for row in rows {
-        worksheet.write_string(current_row, 0, &row.date_rcvd)?;
-        worksheet.write_number(current_row, 1, row.doc_year as f64)?;
-        worksheet.write_string(current_row, 2, &row.doc_date_range)?;
-        worksheet.write_string(current_row, 3, &row.document_type)?;
-        worksheet.write_string(current_row, 4, &row.document_description)?;
-        worksheet.write_string(current_row, 5, &row.file_name)?;
-        worksheet.write_string(current_row, 6, &row.folder_name)?;
-        worksheet.write_string(current_row, 7, &row.folder_path)?;
-        worksheet.write_string(current_row, 8, &row.file_type)?;
-        worksheet.write_string(current_row, 9, &row.bates_stamp)?;
-        worksheet.write_string(current_row, 10, &row.notes)?;
+        *by_type_year
+            .entry(row.document_type.as_str())
+            .or_default()
+            .entry(row.doc_year)
+            .or_insert(0) += 1;
+        years.insert(row.doc_year);
+        *by_file_type.entry(row.file_type.as_str()).or_insert(0) += 1;
+    }
+    let years: Vec<i32> = years.into_iter().collect();
+    let total_col = years.len() as u16 + 1;
+    
+    // Document type x year
+    let mut current_row = 0;
+    worksheet.write_string_with_format(current_row, 0, "Documents by Type and Year", &heading_format)?;
+    current_row += 1;
+    worksheet.write_string_with_format(current_row, 0, "Document Type", &header_format)?;
+    for (index, year) in years.iter().enumerate() {
+        worksheet.write_number_with_format(current_row, index as u16 + 1, *year as f64, &header_format)?;
+    }
+    worksheet.write_string_with_format(current_row, total_col, "Total", &header_format)?;
+    current_row += 1;
+    
+    for (document_type, counts) in &by_type_year {
+        worksheet.write_string(current_row, 0, *document_type)?;
+        for (index, year) in years.iter().enumerate() {
+            if let Some(count) = counts.get(year) {
+                worksheet.write_number(current_row, index as u16 + 1, *count as f64)?;
+            }
+        }
+        worksheet.write_number(current_row, total_col, counts.values().sum::<u32>() as f64)?;
+        current_row += 1;
+    }
+    
+    worksheet.write_string_with_format(current_row, 0, "Total", &total_format)?;
+    for (index, year) in years.iter().enumerate() {
+        let count: u32 = by_type_year.values().filter_map(|counts| counts.get(year)).sum();
+        worksheet.write_number_with_format(current_row, index as u16 + 1, count as f64, &total_format)?;
+    }
+    worksheet.write_number_with_format(current_row, total_col, rows.len() as f64, &total_format)?;
+    current_row += 2;
+    
+    // File type
+    worksheet.write_string_with_format(current_row, 0, "Documents by File Type", &heading_format)?;
+    current_row += 1;
+    worksheet.write_string_with_format(current_row, 0, "File Type", &header_format)?;
+    worksheet.write_string_with_format(current_row, 1, "Count", &header_format)?;
+    current_row += 1;
+    for (file_type, count) in &by_file_type {
+        let label = if file_type.is_empty() { "(none)" } else { *file_type };
+        worksheet.write_string(current_row, 0, label)?;
+        worksheet.write_number(current_row, 1, *count as f64)?;
         current_row += 1;
     }
     
-    workbook.save(output_path)?;
     Ok(())
 }
 
-pub fn generate_csv(
+/// Prefixes of the subtotal rows written by grouped exports; skipped on import
+const SUBTOTAL_PREFIX: &str = "Subtotal: ";
+const GRAND_TOTAL_PREFIX: &str = "Grand Total: ";
+
+/// Column after the inventory columns holding each group's total file size
+const SIZE_TOTAL_COLUMN: u16 = 12;
+
+/// Reads each row's file size for the grouped subtotals. Files that can no
+/// longer be read are left without a size and count as zero.
+pub fn fill_file_sizes(rows: &mut [InventoryRow]) {
+    for row in rows.iter_mut().filter(|row| !row.absolute_path.is_empty()) {
+        row.size_bytes = fs::metadata(&row.absolute_path).ok().map(|metadata| metadata.len());
+    }
+}
+
+fn total_size<'a>(rows: impl Iterator<Item = &'a InventoryRow>) -> f64 {
+    rows.filter_map(|row| row.size_bytes).sum::<u64>() as f64
+}
+
+/// Writes rows sorted by `field`, each value's rows in a collapsible outline
+/// group followed by a subtotal row with the document count
+fn write_grouped_rows(
+    worksheet: &mut Worksheet,
+    mut current_row: u32,
+    rows: &[InventoryRow],
+    field: &str,
+    link_mode: &LinkMode,
+) -> Result<(), XlsxError> {
+    let subtotal_format = Format::new()
+        .set_bold()
+        .set_border_top(FormatBorder::Thin);
+    let size_format = subtotal_format.clone().set_num_format("#,##0");
+    
+    let mut keyed: Vec<(String, &InventoryRow)> = rows
+        .iter()
+        .map(|row| (row.field_value(field).unwrap_or_default(), row))
+        .collect();
+    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+    
+    let mut start = 0;
+    while start < keyed.len() {
+        let key = &keyed[start].0;
+        let end = keyed[start..]
+            .iter()
+            .position(|(k, _)| k != key)
+            .map_or(keyed.len(), |offset| start + offset);
+        
+        let first_row = current_row;
+        for (_, row) in &keyed[start..end] {
+            write_xlsx_row(worksheet, current_row, row, link_mode)?;
+            current_row += 1;
+        }
+        worksheet.group_rows(first_row, current_row - 1)?;
+        
+        let label = if key.is_empty() { "(blank)" } else { key.as_str() };
+        worksheet.write_string_with_format(
+            current_row,
+            0,
+            format!("{}{} ({} documents)", SUBTOTAL_PREFIX, label, end - start),
+            &subtotal_format,
+        )?;
+        worksheet.write_number_with_format(
+            current_row,
+            SIZE_TOTAL_COLUMN,
+            total_size(keyed[start..end].iter().map(|(_, row)| *row)),
+            &size_format,
+        )?;
+        current_row += 1;
+        start = end;
+    }
+    
+    worksheet.write_string_with_format(
+        current_row,
+        0,
+        format!("{}{} documents", GRAND_TOTAL_PREFIX, rows.len()),
+        &subtotal_format,
+    )?;
+    worksheet.write_number_with_format(current_row, SIZE_TOTAL_COLUMN, total_size(rows.iter()), &size_format)?;
+    Ok(())
+}
+
+/// Number of rows written between checkpoints in resumable exports
+const CHECKPOINT_INTERVAL: usize = 1000;
+
+/// Progress marker for an interrupted CSV/JSON export. Everything in the
+/// partial file up to `byte_offset` is complete and covers `rows_written` rows.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct ExportCheckpoint {
+    rows_written: usize,
+    byte_offset: u64,
+    /// Identifies the export the partial file belongs to; see `export_fingerprint`
+    #[serde(default)]
+    fingerprint: String,
+}
+
+/// What to do when a patterned export file name is already taken
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionPolicy {
+    /// Append `_2`, `_3`, ... before the extension until the name is free
+    #[default]
+    Increment,
+    /// Reuse the name; the export replaces the existing file
+    Overwrite,
+    /// Refuse to export
+    Fail,
+}
+
+/// Values substituted into an export file name pattern
+pub struct FilenameContext<'a> {
+    pub case_number: Option<&'a str>,
+    pub profile: Option<&'a str>,
+    pub format: &'a str,
+}
+
+/// Replaces characters that are not allowed in file names on Windows
+pub fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_control() || "<>:\"/\\|?*".contains(c) { '_' } else { c })
+        .collect()
+}
+
+/// Expands `{case_id}` / `{case_number}`, `{date}`, `{time}`, `{profile}` and
+/// `{format}` in a file name pattern, adding the format's extension if the
+/// pattern has none. Returns the offending placeholder if one is unknown.
+pub fn expand_filename_pattern(pattern: &str, context: &FilenameContext) -> Result<String, String> {
+    let now = chrono::Local::now();
+    let mut name = String::new();
+    let mut rest = pattern;
+    
+    while let Some(start) = rest.find('{') {
+        name.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| rest[start..].to_string())? + start;
+        let value = match &rest[start + 1..end] {
+            "case_id" | "case_number" => context.case_number.unwrap_or("").to_string(),
+            "date" => now.format("%Y-%m-%d").to_string(),
+            "time" => now.format("%H%M%S").to_string(),
+            "profile" => context.profile.unwrap_or("").to_string(),
+            "format" => context.format.to_string(),
+            _ => return Err(rest[start..=end].to_string()),
+        };
+        name.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    name.push_str(rest);
+    
+    // Drop separators left dangling by empty placeholders, e.g. "_{profile}"
+    let mut name = sanitize_file_name(name.trim_matches(|c: char| c == '_' || c == '-' || c.is_whitespace()));
+    let extension = format!(".{}", context.format);
+    if !name.to_lowercase().ends_with(&extension) {
+        name.push_str(&extension);
+    }
+    Ok(name)
+}
+
+/// Joins `file_name` onto `directory` and applies the collision policy.
+/// Returns the taken path as the error when the policy is `Fail`.
+pub fn resolve_export_path(directory: &Path, file_name: &str, policy: CollisionPolicy) -> Result<PathBuf, String> {
+    let path = directory.join(file_name);
+    if !path.exists() || policy == CollisionPolicy::Overwrite {
+        return Ok(path);
+    }
+    if policy == CollisionPolicy::Fail {
+        return Err(path.to_string_lossy().to_string());
+    }
+    
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (2u32..)
+        .map(|n| directory.join(format!("{}_{}{}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .ok_or_else(|| path.to_string_lossy().to_string())
+}
+
+/// Exports are written to `<output>.partial` and renamed into place once complete,
+/// so a crash never leaves a truncated file at the output path
+fn partial_path(output_path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.partial", output_path))
+}
+
+fn checkpoint_path(output_path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.checkpoint", output_path))
+}
+
+fn read_checkpoint(output_path: &str) -> Option<ExportCheckpoint> {
+    let contents = fs::read_to_string(checkpoint_path(output_path)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write_checkpoint(output_path: &str, checkpoint: &ExportCheckpoint) -> std::io::Result<()> {
+    fs::write(checkpoint_path(output_path), serde_json::to_string(checkpoint)?)
+}
+
+/// Hash of everything that determines an export's bytes: format, title
+/// fields, options and every row. A checkpoint is only resumed when this
+/// matches, so a partial file is never continued with different data.
+fn export_fingerprint(
+    format: &str,
     rows: &[InventoryRow],
     case_number: Option<&str>,
     folder_path: Option<&str>,
+    options: &ExportOptions,
+) -> Result<String, serde_json::Error> {
+    let mut hasher = Sha256::new();
+    hasher.update(format.as_bytes());
+    hasher.update(serde_json::to_vec(&(case_number, folder_path, rows.len()))?);
+    let options = ExportOptions { resume: false, ..options.clone() };
+    hasher.update(serde_json::to_vec(&options)?);
+    for row in rows {
+        hasher.update(serde_json::to_vec(row)?);
+    }
+    Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Opens the partial file for writing. When resuming from a checkpoint of the
+/// same export the file is truncated to the last checkpointed offset and the
+/// number of rows already written is returned; otherwise a fresh file is
+/// created and any stale checkpoint removed.
+fn open_partial(
     output_path: &str,
+    resume: bool,
+    total_rows: usize,
+    fingerprint: &str,
+) -> std::io::Result<(File, Option<usize>)> {
+    let temp_path = partial_path(output_path);
+    
+    if resume && temp_path.exists() {
+        if let Some(checkpoint) = read_checkpoint(output_path) {
+            if checkpoint.fingerprint == fingerprint && checkpoint.rows_written <= total_rows {
+                let mut file = OpenOptions::new().write(true).open(&temp_path)?;
+                file.set_len(checkpoint.byte_offset)?;
+                file.seek(SeekFrom::End(0))?;
+                return Ok((file, Some(checkpoint.rows_written)));
+            }
+        }
+    }
+    
+    let checkpoint = checkpoint_path(output_path);
+    if checkpoint.exists() {
+        fs::remove_file(checkpoint)?;
+    }
+    Ok((File::create(&temp_path)?, None))
+}
+
+/// Moves a completed partial file into place and removes its checkpoint
+fn finish_partial(output_path: &str) -> std::io::Result<()> {
+    fs::rename(partial_path(output_path), output_path)?;
+    let checkpoint = checkpoint_path(output_path);
+    if checkpoint.exists() {
+        fs::remove_file(checkpoint)?;
+    }
+    Ok(())
+}
+
+fn write_csv_preamble<W: Write>(
+    wtr: &mut csv::Writer<W>,
+    case_number: Option<&str>,
+    folder_path: Option<&str>,
+    redacted_fields: &[String],
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut wtr = csv::Writer::from_path(output_path)?;
+    let redaction_note = redaction_note(redacted_fields);
+    let mut spacing_row: Vec<&str> = vec![""; 12];
+    if let Some(note) = &redaction_note {
+        spacing_row[0] = note;
+    }
     
     // Write title row with case number and source folder row
     if case_number.is_some() {
@@ -149,8 +706,8 @@ pub fn generate_csv(
         let mut title_row: Vec<String> = vec![title_text];
         // Second cell empty (will be merged with first in spreadsheet apps)
         title_row.push(String::new());
-        // Pad with empty cells to match column structure (11 columns total)
-        while title_row.len() < 11 {
+        // Pad with empty cells to match column structure (12 columns total)
+        while title_row.len() < 12 {
             title_row.push(String::new());
         }
         let title_row_refs: Vec<&str> = title_row.iter().map(|s| s.as_str()).collect();
@@ -159,32 +716,37 @@ pub fn generate_csv(
         // Write folder path row if provided
         if let Some(folder) = folder_path {
             let mut folder_row: Vec<String> = vec![format!("Source Folder: {}", folder)];
-            // Pad with empty cells to match column structure (11 columns total)
-            while folder_row.len() < 11 {
+            // Pad with empty cells to match column structure (12 columns total)
+            while folder_row.len() < 12 {
                 folder_row.push(String::new());
             }
             let folder_row_refs: Vec<&str> = folder_row.iter().map(|s| s.as_str()).collect();
             wtr.write_record(&folder_row_refs)?;
         }
         
-        // Empty row for spacing (matching XLSX format)
-        let empty_row: Vec<&str> = vec![""; 11];
-        wtr.write_record(&empty_row)?;
+        // Spacing row, which also records any redacted fields (matching XLSX format)
+        wtr.write_record(&spacing_row)?;
     } else if folder_path.is_some() {
         // If no case number but folder path exists, write folder path row
         if let Some(folder) = folder_path {
             let mut folder_row: Vec<String> = vec![format!("Source Folder: {}", folder)];
-            // Pad with empty cells to match column structure (11 columns total)
-            while folder_row.len() < 11 {
+            // Pad with empty cells to match column structure (12 columns total)
+            while folder_row.len() < 12 {
                 folder_row.push(String::new());
             }
             let folder_row_refs: Vec<&str> = folder_row.iter().map(|s| s.as_str()).collect();
             wtr.write_record(&folder_row_refs)?;
         }
         
-        // Empty row for spacing
-        let empty_row: Vec<&str> = vec![""; 11];
-        wtr.write_record(&empty_row)?;
+        // Spacing row, which also records any redacted fields
+        wtr.write_record(&spacing_row)?;
+    } else if let Some(note) = &redaction_note {
+        // A "#" row is skipped as a comment when the CSV is imported again
+        let mut note_row: Vec<String> = vec![format!("# {}", note)];
+        while note_row.len() < 12 {
+            note_row.push(String::new());
+        }
+        wtr.write_record(&note_row)?;
     }
     
     // Write headers
@@ -198,12 +760,32 @@ pub fn generate_csv(
         "Folder Name",
         "Folder Path",
         "File Type",
+        "File Category",
         "Bates Stamp",
         "Notes",
     ])?;
     
-    // Write data rows
-    for row in rows {
+    Ok(())
+}
+
+pub fn generate_csv(
+    rows: &[InventoryRow],
+    case_number: Option<&str>,
+    folder_path: Option<&str>,
+    output_path: &str,
+    options: &ExportOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let fingerprint = export_fingerprint("csv", rows, case_number, folder_path, options)?;
+    let (file, resumed_rows) = open_partial(output_path, options.resume, rows.len(), &fingerprint)?;
+    let mut wtr = csv::Writer::from_writer(file);
+    
+    if resumed_rows.is_none() {
+        write_csv_preamble(&mut wtr, case_number, folder_path, &options.redacted_fields)?;
+    }
+    let start_row = resumed_rows.unwrap_or(0);
+    
+    // Write data rows, checkpointing periodically so an interrupted export can resume
+    for (index, row) in rows.iter().enumerate().skip(start_row) {
         wtr.write_record(&[
             &row.date_rcvd,
             &row.doc_year.to_string(),
@@ -214,12 +796,30 @@ pub fn generate_csv(
             &row.folder_name,
             &row.folder_path,
             &row.file_type,
+            &row.file_category,
             &row.bates_stamp,
             &row.notes,
         ])?;
+        
+        if (index + 1) % CHECKPOINT_INTERVAL == 0 {
+            wtr.flush()?;
+            // csv::Writer only exposes a shared reference; &File implements Seek
+            let mut file: &File = wtr.get_ref();
+            let byte_offset = file.stream_position()?;
+            write_checkpoint(
+                output_path,
+                &ExportCheckpoint {
+                    rows_written: index + 1,
+                    byte_offset,
+                    fingerprint: fingerprint.clone(),
+                },
+            )?;
+        }
     }
     
     wtr.flush()?;
+    drop(wtr);
+    finish_partial(output_path)?;
     Ok(())
 }
 
@@ -227,12 +827,100 @@ pub fn generate_csv(
 struct JsonMetadata {
     case_number: Option<String>,
     folder_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    redacted_fields: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    column_groups: Vec<ColumnGroup>,
 }
 
-#[derive(serde::Serialize)]
-struct JsonExport {
-    metadata: Option<JsonMetadata>,
-    items: Vec<InventoryRow>,
+/// Moves grouped fields of a serialized row into one nested object per group
+fn group_row_fields(
+    row: &InventoryRow,
+    groups: &[ColumnGroup],
+) -> Result<serde_json::Value, serde_json::Error> {
+    let mut value = serde_json::to_value(row)?;
+    if let serde_json::Value::Object(fields) = &mut value {
+        for group in groups {
+            let nested: serde_json::Map<String, serde_json::Value> = group
+                .fields
+                .iter()
+                .filter_map(|field| fields.remove(field).map(|v| (field.clone(), v)))
+                .collect();
+            fields.insert(group.name.clone(), serde_json::Value::Object(nested));
+        }
+    }
+    Ok(value)
+}
+
+/// Undoes `group_row_fields` so grouped exports can be imported again
+fn flatten_grouped_items(json_value: &mut serde_json::Value) {
+    let Some(items) = json_value.get_mut("items").and_then(|v| v.as_array_mut()) else {
+        return;
+    };
+    for item in items {
+        if let serde_json::Value::Object(fields) = item {
+            let group_names: Vec<String> = fields
+                .iter()
+                .filter(|(_, v)| v.is_object())
+                .map(|(k, _)| k.clone())
+                .collect();
+            for name in group_names {
+                if let Some(serde_json::Value::Object(nested)) = fields.remove(&name) {
+                    fields.extend(nested);
+                }
+            }
+        }
+    }
+}
+
+/// Placeholder written in place of redacted values
+pub const REDACTED_PLACEHOLDER: &str = "[REDACTED]";
+
+/// Fields that reveal where a file lives
+const PATH_FIELDS: &[&str] = &["file_name", "folder_name", "folder_path"];
+
+/// Whether redacting these fields must also hide file locations: hyperlinks
+/// and the source folder in export headers
+pub fn redacts_paths(fields: &[String]) -> bool {
+    fields.iter().any(|field| PATH_FIELDS.contains(&field.as_str()))
+}
+
+/// Line recording which fields an XLSX or CSV export masked
+fn redaction_note(fields: &[String]) -> Option<String> {
+    (!fields.is_empty()).then(|| format!("Redacted fields: {}", fields.join(", ")))
+}
+
+/// Masks the given fields in every row. Only text fields can be redacted;
+/// returns the offending name if a field is unknown or not maskable. Masking
+/// a path field also clears the row's source location so no link is written.
+pub fn redact_rows(rows: &mut [InventoryRow], fields: &[String]) -> Result<(), String> {
+    if redacts_paths(fields) {
+        for row in rows.iter_mut() {
+            row.absolute_path.clear();
+        }
+    }
+    for field in fields {
+        for row in rows.iter_mut() {
+            let value = match field.as_str() {
+                "date_rcvd" => &mut row.date_rcvd,
+                "doc_date_range" => &mut row.doc_date_range,
+                "document_type" => &mut row.document_type,
+                "document_description" => &mut row.document_description,
+                "file_name" => &mut row.file_name,
+                "folder_name" => &mut row.folder_name,
+                "folder_path" => &mut row.folder_path,
+                "file_type" => &mut row.file_type,
+                "file_category" => &mut row.file_category,
+                "bates_stamp" => &mut row.bates_stamp,
+                "notes" => &mut row.notes,
+                _ => return Err(field.clone()),
+            };
+            if !value.is_empty() {
+                *value = REDACTED_PLACEHOLDER.to_string();
+            }
+        }
+    }
+    Ok(())
 }
 
 pub fn generate_json(
@@ -240,21 +928,163 @@ pub fn generate_json(
     case_number: Option<&str>,
     folder_path: Option<&str>,
     output_path: &str,
+    options: &ExportOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let export = JsonExport {
-        metadata: if case_number.is_some() || folder_path.is_some() {
+    let redacted_fields = &options.redacted_fields;
+    let fingerprint = export_fingerprint("json", rows, case_number, folder_path, options)?;
+    let (file, resumed_rows) = open_partial(output_path, options.resume, rows.len(), &fingerprint)?;
+    let mut writer = BufWriter::new(file);
+    
+    // Items are streamed one at a time rather than serializing the whole export,
+    // which keeps memory flat and allows checkpointing between rows
+    if resumed_rows.is_none() {
+        let has_metadata = case_number.is_some()
+            || folder_path.is_some()
+            || !redacted_fields.is_empty()
+            || !options.column_groups.is_empty();
+        let metadata = if has_metadata {
             Some(JsonMetadata {
                 case_number: case_number.map(|s| s.to_string()),
                 folder_path: folder_path.map(|s| s.to_string()),
+                redacted_fields: redacted_fields.to_vec(),
+                column_groups: options.column_groups.clone(),
             })
         } else {
             None
-        },
-        items: rows.to_vec(),
-    };
-    let json = serde_json::to_string_pretty(&export)?;
-    let mut file = File::create(output_path)?;
-    file.write_all(json.as_bytes())?;
+        };
+        write!(writer, "{{\n  \"metadata\": {},\n  \"items\": [", serde_json::to_string(&metadata)?)?;
+    }
+    let start_row = resumed_rows.unwrap_or(0);
+    
+    for (index, row) in rows.iter().enumerate().skip(start_row) {
+        let separator = if index == 0 { "\n    " } else { ",\n    " };
+        let item = if options.column_groups.is_empty() {
+            serde_json::to_string(row)?
+        } else {
+            serde_json::to_string(&group_row_fields(row, &options.column_groups)?)?
+        };
+        write!(writer, "{}{}", separator, item)?;
+        
+        if (index + 1) % CHECKPOINT_INTERVAL == 0 {
+            writer.flush()?;
+            let byte_offset = writer.get_mut().stream_position()?;
+            write_checkpoint(
+                output_path,
+                &ExportCheckpoint {
+                    rows_written: index + 1,
+                    byte_offset,
+                    fingerprint: fingerprint.clone(),
+                },
+            )?;
+        }
+    }
+    
+    write!(writer, "\n  ]\n}}\n")?;
+    writer.flush()?;
+    drop(writer);
+    finish_partial(output_path)?;
+    Ok(())
+}
+
+/// SQLite's default SQLITE_MAX_VARIABLE_NUMBER on older builds
+const SQLITE_MAX_PARAMS: usize = 999;
+
+/// Bound parameters per row in the inventory INSERT
+const SQLITE_INVENTORY_COLUMNS: usize = 12;
+
+/// Writes the inventory to a standalone SQLite database so it can be queried
+/// with any SQL tool. Produces an `inventory` table and a key/value `metadata` table.
+pub fn generate_sqlite(
+    rows: &[InventoryRow],
+    case_number: Option<&str>,
+    folder_path: Option<&str>,
+    output_path: &str,
+    options: &ExportOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let redacted_fields = &options.redacted_fields;
+    let temp_path = partial_path(output_path);
+    if temp_path.exists() {
+        fs::remove_file(&temp_path)?;
+    }
+    
+    let mut conn = rusqlite::Connection::open(&temp_path)?;
+    conn.execute_batch(
+        "CREATE TABLE metadata (
+            key TEXT PRIMARY KEY,
+            value TEXT
+        );
+        CREATE TABLE inventory (
+            id INTEGER PRIMARY KEY,
+            date_rcvd TEXT,
+            doc_year INTEGER,
+            doc_date_range TEXT,
+            document_type TEXT,
+            document_description TEXT,
+            file_name TEXT,
+            folder_name TEXT,
+            folder_path TEXT,
+            file_type TEXT,
+            file_category TEXT,
+            bates_stamp TEXT,
+            notes TEXT
+        );
+        CREATE INDEX idx_inventory_document_type ON inventory(document_type);
+        CREATE INDEX idx_inventory_doc_year ON inventory(doc_year);
+        CREATE INDEX idx_inventory_file_category ON inventory(file_category);",
+    )?;
+    
+    let tx = conn.transaction()?;
+    {
+        let mut metadata_stmt = tx.prepare("INSERT INTO metadata (key, value) VALUES (?1, ?2)")?;
+        metadata_stmt.execute(rusqlite::params!["case_number", case_number])?;
+        metadata_stmt.execute(rusqlite::params!["folder_path", folder_path])?;
+        if !redacted_fields.is_empty() {
+            metadata_stmt.execute(rusqlite::params!["redacted_fields", redacted_fields.join(",")])?;
+        }
+        metadata_stmt.execute(rusqlite::params![
+            "exported_at",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+        ])?;
+        
+        // Insert in multi-row batches, keeping each statement under SQLite's
+        // default 999 bound-parameter limit
+        let rows_per_insert = SQLITE_MAX_PARAMS / SQLITE_INVENTORY_COLUMNS;
+        for chunk in rows.chunks(rows_per_insert) {
+            let placeholders = vec!["(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"; chunk.len()].join(", ");
+            let sql = format!(
+                "INSERT INTO inventory (date_rcvd, doc_year, doc_date_range, document_type, document_description,
+                    file_name, folder_name, folder_path, file_type, file_category, bates_stamp, notes)
+                 VALUES {}",
+                placeholders
+            );
+            
+            let mut params: Vec<&dyn rusqlite::ToSql> = Vec::with_capacity(chunk.len() * SQLITE_INVENTORY_COLUMNS);
+            for row in chunk {
+                let values: [&dyn rusqlite::ToSql; SQLITE_INVENTORY_COLUMNS] = [
+                    &row.date_rcvd,
+                    &row.doc_year,
+                    &row.doc_date_range,
+                    &row.document_type,
+                    &row.document_description,
+                    &row.file_name,
+                    &row.folder_name,
+                    &row.folder_path,
+                    &row.file_type,
+                    &row.file_category,
+                    &row.bates_stamp,
+                    &row.notes,
+                ];
+                params.extend_from_slice(&values);
+            }
+            
+            // Full chunks share the same SQL, so the cached statement is reused
+            tx.prepare_cached(&sql)?.execute(params.as_slice())?;
+        }
+    }
+    tx.commit()?;
+    conn.close().map_err(|(_, e)| e)?;
+    
+    finish_partial(output_path)?;
     Ok(())
 }
 
@@ -302,6 +1132,17 @@ pub fn read_xlsx(
         }
     }
     
+    // Skip a redaction note and a group header row written above the column headers
+    let is_header_row = |index: usize| {
+        rows.get(index).is_some_and(|row| {
+            row.iter().any(|cell| matches!(cell, Data::String(s) if s == "File Name"))
+        })
+    };
+    if let Some(skip) = (0..3).find(|skip| is_header_row(header_row_index + skip)) {
+        header_row_index += skip;
+        data_start_row += skip;
+    }
+    
     // Find header row
     let headers: Vec<String> = rows
         .get(header_row_index)
@@ -335,6 +1176,12 @@ pub fn read_xlsx(
         if row.is_empty() {
             continue;
         }
+        // Subtotal rows from grouped exports are not inventory rows
+        if let Some(Data::String(first)) = row.first() {
+            if first.starts_with(SUBTOTAL_PREFIX) || first.starts_with(GRAND_TOTAL_PREFIX) {
+                continue;
+            }
+        }
         
         let get_cell_value = |col_name: &str| -> String {
             header_map
@@ -369,8 +1216,11 @@ pub fn read_xlsx(
             folder_name: get_cell_value("Folder Name"),
             folder_path: get_cell_value("Folder Path"),
             file_type: get_cell_value("File Type"),
+            file_category: get_cell_value("File Category"),
             bates_stamp: get_cell_value("Bates Stamp"),
             notes: get_cell_value("Notes"),
+            absolute_path: String::new(),
+            size_bytes: None,
         });
     }
     
@@ -381,72 +1231,35 @@ pub fn read_csv(
     file_path: &str,
 ) -> Result<(Vec<InventoryRow>, Option<String>, Option<String>), Box<dyn std::error::Error>> {
     let file = File::open(file_path)?;
-    let mut rdr = csv::Reader::from_reader(BufReader::new(file));
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(BufReader::new(file));
+    let records = rdr.records().collect::<Result<Vec<_>, _>>()?;
+    
+    // Title, source folder, redaction note and comment rows come before the
+    // headers; the header row is the first one naming the File Name column
+    let header_index = records
+        .iter()
+        .position(|record| record.iter().any(|field| field.trim() == "File Name"))
+        .unwrap_or(0);
     
     let mut case_number: Option<String> = None;
     let mut folder_path: Option<String> = None;
-    let mut skip_rows = 0;
-    
-    // Read first record to check if it's the title row
-    let mut records = rdr.records();
-    if let Some(Ok(first_record)) = records.next() {
-        let first_field = first_record.get(0).unwrap_or("");
-        if first_field == "Document Inventory" {
-            // Parse case number from first row
-            if let Some(case_field) = first_record.get(1) {
-                if case_field.starts_with("Case No. ") {
-                    case_number = Some(case_field.replace("Case No. ", "").trim().to_string());
-                }
-            }
-            skip_rows = 1;
-            
-            // Check second row for folder path
-            if let Some(Ok(second_record)) = records.next() {
-                if let Some(folder_field) = second_record.get(0) {
-                    if folder_field.starts_with("Source Folder: ") {
-                        folder_path = Some(folder_field.replace("Source Folder: ", "").trim().to_string());
-                    }
-                }
-                skip_rows = 2;
-                
-                // Check third row (should be empty)
-                if let Some(Ok(_third_record)) = records.next() {
-                    skip_rows = 3;
-                }
-            }
-        } else if first_field.starts_with("#") {
-            // Old format - metadata comment row
-            skip_rows = 1;
-            for field in first_record.iter() {
-                if field.starts_with("Case No. ") {
-                    case_number = Some(field.replace("Case No. ", "").trim().to_string());
-                } else if field.starts_with("Source Folder: ") {
-                    folder_path = Some(field.replace("Source Folder: ", "").trim().to_string());
-                }
-            }
+    for field in records[..header_index].iter().flat_map(|record| record.iter()) {
+        let field = field.trim_start_matches('#').trim();
+        if let Some(index) = field.find("Case No. ") {
+            case_number = Some(field[index + "Case No. ".len()..].trim().to_string());
+        } else if let Some(folder) = field.strip_prefix("Source Folder: ") {
+            folder_path = Some(folder.trim().to_string());
         }
     }
-    
-    // Re-read file from start
-    // If we have title rows, they come BEFORE headers in CSV
-    let file = File::open(file_path)?;
-    let mut rdr = csv::Reader::from_reader(BufReader::new(file));
-    
-    let headers: Vec<String> = if skip_rows > 0 {
-        // Title rows come before headers - skip them, then read headers
-        let file = File::open(file_path)?;
-        let mut temp_rdr = csv::Reader::from_reader(BufReader::new(file));
-        // Skip title rows
-        for _ in 0..skip_rows {
-            let mut record = csv::StringRecord::new();
-            temp_rdr.read_record(&mut record)?;
-        }
-        // Now read headers
-        temp_rdr.headers()?.iter().map(|s| s.to_string()).collect()
-    } else {
-        // Normal case - headers are first
-        rdr.headers()?.iter().map(|s| s.to_string()).collect()
-    };
+    let headers: Vec<String> = records
+        .get(header_index)
+        .ok_or("No header row found")?
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
     
     // Create a mapping from header name to column index
     let header_map: HashMap<String, usize> = headers
@@ -458,9 +1271,7 @@ pub fn read_csv(
     let mut rows = Vec::new();
     
     // Read data rows
-    for result in rdr.records() {
-        let record = result?;
-        
+    for record in &records[header_index + 1..] {
         let get_field = |col_name: &str| -> String {
             header_map
                 .get(col_name)
@@ -482,8 +1293,11 @@ pub fn read_csv(
             folder_name: get_field("Folder Name"),
             folder_path: get_field("Folder Path"),
             file_type: get_field("File Type"),
+            file_category: get_field("File Category"),
             bates_stamp: get_field("Bates Stamp"),
             notes: get_field("Notes"),
+            absolute_path: String::new(),
+            size_bytes: None,
         });
     }
     
@@ -502,9 +1316,10 @@ pub fn read_json(
     // Try to parse as new format with metadata first
     let file = File::open(file_path)?;
     let reader = BufReader::new(file);
-    let json_value: serde_json::Value = serde_json::from_reader(reader)?;
+    let mut json_value: serde_json::Value = serde_json::from_reader(reader)?;
     
     if json_value.get("metadata").is_some() {
+        flatten_grouped_items(&mut json_value);
         // New format with metadata
         let import: JsonImport = serde_json::from_value(json_value)?;
         let rows = import.items.unwrap_or_default();
@@ -518,3 +1333,129 @@ pub fn read_json(
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(file_name: &str) -> InventoryRow {
+        InventoryRow {
+            date_rcvd: String::new(),
+            doc_year: 2024,
+            doc_date_range: String::new(),
+            document_type: "Statement".to_string(),
+            document_description: String::new(),
+            file_name: file_name.to_string(),
+            folder_name: "Bank".to_string(),
+            folder_path: "Bank".to_string(),
+            file_type: "PDF".to_string(),
+            file_category: String::new(),
+            bates_stamp: String::new(),
+            notes: String::new(),
+            absolute_path: format!("/case/Bank/{}", file_name),
+            size_bytes: None,
+        }
+    }
+
+    fn rows(count: usize) -> Vec<InventoryRow> {
+        (0..count).map(|i| row(&format!("doc_{:04}.pdf", i))).collect()
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("export-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Leaves `<output>.partial` as an interrupted export would: the first
+    /// `rows_written` rows of `complete` followed by a half-written line
+    fn interrupt(output: &str, complete: &str, rows_written: usize, fingerprint: &str) {
+        // One header line, then one line per row
+        let byte_offset: usize = complete.split_inclusive('\n').take(1 + rows_written).map(str::len).sum();
+        fs::write(partial_path(output), format!("{}half a ro", &complete[..byte_offset])).unwrap();
+        write_checkpoint(
+            output,
+            &ExportCheckpoint {
+                rows_written,
+                byte_offset: byte_offset as u64,
+                fingerprint: fingerprint.to_string(),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn resumed_csv_export_matches_an_uninterrupted_one() {
+        let dir = scratch_dir("resume");
+        let rows = rows(CHECKPOINT_INTERVAL + 10);
+        let fresh = dir.join("fresh.csv").to_string_lossy().to_string();
+        generate_csv(&rows, None, None, &fresh, &ExportOptions::default()).unwrap();
+        let complete = fs::read_to_string(&fresh).unwrap();
+
+        let resumed = dir.join("resumed.csv").to_string_lossy().to_string();
+        let options = ExportOptions { resume: true, ..ExportOptions::default() };
+        let fingerprint = export_fingerprint("csv", &rows, None, None, &options).unwrap();
+        interrupt(&resumed, &complete, CHECKPOINT_INTERVAL, &fingerprint);
+        generate_csv(&rows, None, None, &resumed, &options).unwrap();
+
+        assert_eq!(fs::read_to_string(&resumed).unwrap(), complete);
+        assert!(!partial_path(&resumed).exists());
+        assert!(!checkpoint_path(&resumed).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn checkpoint_of_a_different_export_is_not_resumed() {
+        let dir = scratch_dir("mismatch");
+        let old_rows = rows(CHECKPOINT_INTERVAL + 10);
+        let output = dir.join("out.csv").to_string_lossy().to_string();
+        generate_csv(&old_rows, None, None, &output, &ExportOptions::default()).unwrap();
+        let old_complete = fs::read_to_string(&output).unwrap();
+
+        // The rows changed since the interrupted export was started
+        let mut new_rows = old_rows.clone();
+        new_rows[0].notes = "edited".to_string();
+        let options = ExportOptions { resume: true, ..ExportOptions::default() };
+        let old_fingerprint = export_fingerprint("csv", &old_rows, None, None, &options).unwrap();
+        assert_ne!(old_fingerprint, export_fingerprint("csv", &new_rows, None, None, &options).unwrap());
+        interrupt(&output, &old_complete, CHECKPOINT_INTERVAL, &old_fingerprint);
+        generate_csv(&new_rows, None, None, &output, &options).unwrap();
+
+        let written = fs::read_to_string(&output).unwrap();
+        assert_eq!(written.lines().count(), 1 + new_rows.len());
+        assert!(written.lines().nth(1).unwrap().ends_with(",edited"));
+        assert!(!written.contains("half a ro"));
+        assert!(!checkpoint_path(&output).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn redacting_a_path_field_also_clears_the_source_location() {
+        let mut redacted = rows(2);
+        redact_rows(&mut redacted, &["folder_path".to_string()]).unwrap();
+        assert!(redacted.iter().all(|row| row.absolute_path.is_empty()));
+        assert!(redacted.iter().all(|row| row.folder_path == REDACTED_PLACEHOLDER));
+        assert_eq!(redacted[0].file_name, "doc_0000.pdf");
+
+        let mut notes_only = rows(2);
+        notes_only[0].notes = "privileged".to_string();
+        redact_rows(&mut notes_only, &["notes".to_string()]).unwrap();
+        assert_eq!(notes_only[0].absolute_path, "/case/Bank/doc_0000.pdf");
+        assert_eq!(notes_only[0].notes, REDACTED_PLACEHOLDER);
+        // Empty values stay empty rather than implying there was something
+        assert_eq!(notes_only[1].notes, "");
+
+        assert_eq!(redact_rows(&mut rows(1), &["doc_year".to_string()]), Err("doc_year".to_string()));
+    }
+
+    #[test]
+    fn fingerprint_ignores_the_resume_flag_only() {
+        let rows = rows(3);
+        let fresh = export_fingerprint("csv", &rows, Some("A-1"), None, &ExportOptions::default()).unwrap();
+        let resumed = ExportOptions { resume: true, ..ExportOptions::default() };
+        assert_eq!(fresh, export_fingerprint("csv", &rows, Some("A-1"), None, &resumed).unwrap());
+        assert_ne!(fresh, export_fingerprint("json", &rows, Some("A-1"), None, &resumed).unwrap());
+        assert_ne!(fresh, export_fingerprint("csv", &rows, Some("A-2"), None, &resumed).unwrap());
+    }
+}
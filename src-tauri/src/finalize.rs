@@ -0,0 +1,353 @@
+use crate::hashing::{hash_files_parallel, HashAlgorithm};
+use crate::json_store;
+use crate::timestamp::{to_hex, TimestampReceipt};
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+const FINALIZED_FILE: &str = "finalized_cases.json";
+
+/// Domain prefixes keep a leaf hash from ever equalling an interior node hash
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Inventory fields covered by each version of the metadata hash, in hash
+/// order. A field added to `InventoryItem` later only joins the hash in a new
+/// version, so cases finalized under an older version still verify.
+const METADATA_HASH_FIELDS: &[&[&str]] = &[&[
+    "date_rcvd",
+    "doc_year",
+    "doc_date_range",
+    "document_type",
+    "document_description",
+    "file_name",
+    "folder_name",
+    "folder_path",
+    "file_type",
+    "file_category",
+    "bates_stamp",
+    "notes",
+]];
+
+/// Metadata hash version used for new snapshots
+pub const METADATA_HASH_VERSION: u32 = 1;
+
+fn first_metadata_hash_version() -> u32 {
+    1
+}
+
+/// One file as it stood when its case was finalized
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalizedFile {
+    pub absolute_path: String,
+    /// SHA-256 of the file content
+    pub content_hash: String,
+    /// SHA-256 of the item's inventory fields
+    pub metadata_hash: String,
+}
+
+/// A delivered case snapshot. The Merkle root over every file's content and
+/// metadata proves later that nothing in the inventory has changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalizedCase {
+    pub case_number: String,
+    pub finalized_at: String,
+    /// SHA-256 Merkle root over the files, sorted by path
+    pub merkle_root: String,
+    pub files: Vec<FinalizedFile>,
+    /// RFC 3161 timestamp of the Merkle root, when one was obtained
+    #[serde(default)]
+    pub timestamp: Option<TimestampReceipt>,
+    /// Which field list the metadata hashes cover, see `METADATA_HASH_FIELDS`
+    #[serde(default = "first_metadata_hash_version")]
+    pub metadata_version: u32,
+}
+
+/// Result of checking a case's current inventory against its finalized snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseVerification {
+    pub case_number: String,
+    pub finalized_at: String,
+    pub merkle_root: String,
+    pub current_root: String,
+    /// True when the roots match, i.e. nothing changed since finalization
+    pub verified: bool,
+    /// Files whose content no longer matches
+    pub modified_content: Vec<String>,
+    /// Files whose inventory fields were edited
+    pub modified_metadata: Vec<String>,
+    /// Finalized files missing from the inventory or no longer readable
+    pub missing: Vec<String>,
+    /// Inventory files that were not part of the finalized snapshot
+    pub added: Vec<String>,
+}
+
+fn finalized_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(FINALIZED_FILE)
+}
+
+/// Hash of the item's fields under a hash version, so any edit to one of
+/// them changes it. Each field is written as name and value, NUL-terminated,
+/// so no two rows hash alike. An unknown version (a record from a newer
+/// release) covers no fields and never verifies.
+fn metadata_hash(item: &InventoryItem, version: u32) -> String {
+    let fields = version
+        .checked_sub(1)
+        .and_then(|index| METADATA_HASH_FIELDS.get(index as usize))
+        .copied()
+        .unwrap_or_default();
+    let mut hasher = Sha256::new();
+    for field in fields {
+        hasher.update(field.as_bytes());
+        hasher.update([0]);
+        hasher.update(item.field_value(field).unwrap_or_default().as_bytes());
+        hasher.update([0]);
+    }
+    to_hex(&hasher.finalize())
+}
+
+fn leaf_hash(file: &FinalizedFile) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    for part in [&file.absolute_path, &file.content_hash, &file.metadata_hash] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.finalize().to_vec()
+}
+
+/// Merkle root over files sorted by path. An odd node at any level is carried
+/// up unchanged; an empty case hashes to SHA-256 of nothing.
+pub fn merkle_root(files: &[FinalizedFile]) -> String {
+    let mut sorted: Vec<&FinalizedFile> = files.iter().collect();
+    sorted.sort_by(|a, b| a.absolute_path.cmp(&b.absolute_path));
+    let mut level: Vec<Vec<u8>> = sorted.into_iter().map(leaf_hash).collect();
+    if level.is_empty() {
+        return to_hex(&Sha256::digest([]));
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    let mut hasher = Sha256::new();
+                    hasher.update([NODE_PREFIX]);
+                    hasher.update(left);
+                    hasher.update(right);
+                    hasher.finalize().to_vec()
+                }
+                [single] => single.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    to_hex(&level[0])
+}
+
+/// Hashes every item's file and fields. Items without a path are skipped and
+/// repeated paths count once. Unreadable files are returned separately.
+fn snapshot_files(items: &[InventoryItem], version: u32, workers: usize) -> (Vec<FinalizedFile>, Vec<String>) {
+    let by_path: BTreeMap<&str, &InventoryItem> = items
+        .iter()
+        .filter(|item| !item.absolute_path.is_empty())
+        .map(|item| (item.absolute_path.as_str(), item))
+        .collect();
+    let paths: Vec<String> = by_path.keys().map(|path| path.to_string()).collect();
+
+    let mut files = Vec::new();
+    let mut unreadable = Vec::new();
+    for hashed in hash_files_parallel(&paths, HashAlgorithm::Sha256, workers) {
+        match hashed.hash {
+            Some(content_hash) => files.push(FinalizedFile {
+                metadata_hash: metadata_hash(by_path[hashed.path.as_str()], version),
+                absolute_path: hashed.path,
+                content_hash,
+            }),
+            None => unreadable.push(hashed.path),
+        }
+    }
+    (files, unreadable)
+}
+
+/// Builds the snapshot for a case; fails with the unreadable paths when any
+/// file cannot be hashed, since the digest would not cover them
+pub fn finalize_case(case_number: &str, items: &[InventoryItem], workers: usize) -> Result<FinalizedCase, Vec<String>> {
+    let (files, unreadable) = snapshot_files(items, METADATA_HASH_VERSION, workers);
+    if !unreadable.is_empty() {
+        return Err(unreadable);
+    }
+    Ok(FinalizedCase {
+        case_number: case_number.to_string(),
+        finalized_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        merkle_root: merkle_root(&files),
+        files,
+        timestamp: None,
+        metadata_version: METADATA_HASH_VERSION,
+    })
+}
+
+/// Re-hashes the current inventory and compares it with the snapshot
+pub fn verify_case(record: &FinalizedCase, items: &[InventoryItem], workers: usize) -> CaseVerification {
+    let (files, unreadable) = snapshot_files(items, record.metadata_version, workers);
+    let current: BTreeMap<&str, &FinalizedFile> = files.iter().map(|file| (file.absolute_path.as_str(), file)).collect();
+    let finalized: BTreeSet<&str> = record.files.iter().map(|file| file.absolute_path.as_str()).collect();
+
+    let mut verification = CaseVerification {
+        case_number: record.case_number.clone(),
+        finalized_at: record.finalized_at.clone(),
+        merkle_root: record.merkle_root.clone(),
+        current_root: merkle_root(&files),
+        verified: false,
+        modified_content: Vec::new(),
+        modified_metadata: Vec::new(),
+        missing: Vec::new(),
+        added: files
+            .iter()
+            .map(|file| file.absolute_path.clone())
+            .chain(unreadable.iter().cloned())
+            .filter(|path| !finalized.contains(path.as_str()))
+            .collect(),
+    };
+    for file in &record.files {
+        match current.get(file.absolute_path.as_str()) {
+            None => verification.missing.push(file.absolute_path.clone()),
+            Some(now) => {
+                if now.content_hash != file.content_hash {
+                    verification.modified_content.push(file.absolute_path.clone());
+                }
+                if now.metadata_hash != file.metadata_hash {
+                    verification.modified_metadata.push(file.absolute_path.clone());
+                }
+            }
+        }
+    }
+    verification.added.sort();
+    // Recomputing the stored root catches a record edited without updating it
+    verification.verified = verification.current_root == record.merkle_root
+        && merkle_root(&record.files) == record.merkle_root;
+    verification
+}
+
+/// Reads all finalized cases; a missing file means none have been finalized
+pub fn read_finalized(data_dir: &Path) -> Result<Vec<FinalizedCase>, Box<dyn std::error::Error>> {
+    json_store::read(&finalized_path(data_dir))
+}
+
+pub fn find_finalized(data_dir: &Path, case_number: &str) -> Result<Option<FinalizedCase>, Box<dyn std::error::Error>> {
+    Ok(read_finalized(data_dir)?.into_iter().find(|record| record.case_number == case_number))
+}
+
+/// Stores a snapshot; returns false, storing nothing, if the case was
+/// finalized in the meantime
+pub fn save_finalized(data_dir: &Path, record: FinalizedCase) -> Result<bool, Box<dyn std::error::Error>> {
+    json_store::update(&finalized_path(data_dir), |records: &mut Vec<FinalizedCase>| {
+        if records.iter().any(|existing| existing.case_number == record.case_number) {
+            return false;
+        }
+        records.push(record);
+        true
+    })
+}
+
+/// Attaches a timestamp to a case's snapshot; returns None when the case has
+/// not been finalized. The root and file hashes are left untouched.
+pub fn set_timestamp(
+    data_dir: &Path,
+    case_number: &str,
+    receipt: TimestampReceipt,
+) -> Result<Option<FinalizedCase>, Box<dyn std::error::Error>> {
+    json_store::update(&finalized_path(data_dir), |records: &mut Vec<FinalizedCase>| {
+        let record = records.iter_mut().find(|record| record.case_number == case_number)?;
+        record.timestamp = Some(receipt);
+        Some(record.clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content_hash: &str) -> FinalizedFile {
+        FinalizedFile {
+            absolute_path: path.to_string(),
+            content_hash: content_hash.to_string(),
+            metadata_hash: "m".to_string(),
+        }
+    }
+
+    fn node(left: &[u8], right: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update([NODE_PREFIX]);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().to_vec()
+    }
+
+    #[test]
+    fn finalized_cases_are_keyed_by_the_exact_case_number() {
+        let dir = std::env::temp_dir().join(format!("finalized-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let record = finalize_case("A-1", &[], 1).unwrap();
+        assert!(save_finalized(&dir, record.clone()).unwrap());
+        assert!(!save_finalized(&dir, record).unwrap());
+        assert!(find_finalized(&dir, "A-1").unwrap().is_some());
+        assert!(find_finalized(&dir, "a-1").unwrap().is_none());
+        assert!(find_finalized(&dir, " A-1").unwrap().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn merkle_root_of_nothing_is_the_empty_hash() {
+        assert_eq!(
+            merkle_root(&[]),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn merkle_root_shapes() {
+        let [a, b, c] = [file("/a", "1"), file("/b", "2"), file("/c", "3")];
+        let (la, lb, lc) = (leaf_hash(&a), leaf_hash(&b), leaf_hash(&c));
+        let cases: Vec<(&str, Vec<FinalizedFile>, Vec<u8>)> = vec![
+            ("one file", vec![a.clone()], la.clone()),
+            ("two files", vec![a.clone(), b.clone()], node(&la, &lb)),
+            // The odd leaf is carried up and joined at the next level
+            ("odd count", vec![a.clone(), b.clone(), c.clone()], node(&node(&la, &lb), &lc)),
+            ("unsorted", vec![c.clone(), a.clone(), b.clone()], node(&node(&la, &lb), &lc)),
+        ];
+        for (name, files, expected) in cases {
+            assert_eq!(merkle_root(&files), to_hex(&expected), "{}", name);
+        }
+    }
+
+    #[test]
+    fn merkle_root_changes_with_any_field() {
+        let files = vec![file("/a", "1"), file("/b", "2"), file("/c", "3")];
+        let root = merkle_root(&files);
+        let changes: [fn(&mut FinalizedFile); 3] = [
+            |f| f.absolute_path.push('x'),
+            |f| f.content_hash.push('x'),
+            |f| f.metadata_hash.push('x'),
+        ];
+        for index in 0..files.len() {
+            for change in changes {
+                let mut changed = files.clone();
+                change(&mut changed[index]);
+                assert_ne!(merkle_root(&changed), root);
+            }
+        }
+        let mut extra = files.clone();
+        extra.push(file("/d", "4"));
+        assert_ne!(merkle_root(&extra), root);
+        assert_ne!(merkle_root(&files[..2]), root);
+    }
+
+    #[test]
+    fn leaf_fields_are_separated() {
+        // Moving a byte between fields must not produce the same leaf
+        assert_ne!(leaf_hash(&file("/ab", "c")), leaf_hash(&file("/a", "bc")));
+    }
+}
@@ -0,0 +1,428 @@
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::collections::HashMap;
+use std::path::{Component, Path};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+/// Read size for hashing. Large sequential reads keep spinning disks streaming
+/// instead of seeking between small chunks.
+const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Upper bound on concurrent hashing workers. Beyond this, parallel reads
+/// compete for the same disk and throughput drops on HDDs and network shares.
+const MAX_HASH_WORKERS: usize = 8;
+
+/// Files hashed one at a time per source to measure its throughput
+const PROBE_FILES: usize = 4;
+
+/// Single-stream read rate below which a source is treated as slow (typically
+/// a network share), where extra parallel reads only add contention
+const SLOW_SOURCE_BYTES_PER_SECOND: f64 = 20.0 * 1024.0 * 1024.0;
+
+/// Probes reading less than this are judged by per-file latency instead of
+/// byte rate, since opening tiny files dominates their timing
+const PROBE_MIN_BYTES: u64 = 1024 * 1024;
+
+/// Per-file time above which a source of small files is treated as slow
+const SLOW_SOURCE_FILE_MS: f64 = 20.0;
+
+/// Workers used for the remaining files of a slow source
+const SLOW_SOURCE_WORKERS: usize = 2;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    #[default]
+    Sha256,
+}
+
+impl HashAlgorithm {
+    /// Guesses the algorithm from the length of a hex digest
+    pub fn from_hex_len(len: usize) -> Option<Self> {
+        match len {
+            32 => Some(Self::Md5),
+            40 => Some(Self::Sha1),
+            64 => Some(Self::Sha256),
+            _ => None,
+        }
+    }
+}
+
+/// Hash (or the error that prevented hashing) for one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHash {
+    pub path: String,
+    pub hash: Option<String>,
+    pub error: Option<String>,
+}
+
+fn digest_reader<D: Digest, R: Read>(mut reader: R) -> std::io::Result<String> {
+    let mut hasher = D::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Hashes a file's content as lowercase hex
+pub fn hash_file(path: &Path, algorithm: HashAlgorithm) -> std::io::Result<String> {
+    let file = File::open(path)?;
+    match algorithm {
+        HashAlgorithm::Md5 => digest_reader::<Md5, _>(file),
+        HashAlgorithm::Sha1 => digest_reader::<Sha1, _>(file),
+        HashAlgorithm::Sha256 => digest_reader::<Sha256, _>(file),
+    }
+}
+
+/// Passes reads through while hashing them
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Copies `reader` into `writer`, returning the SHA-256 of what was copied
+pub fn copy_hashed<R: Read, W: Write>(reader: R, writer: &mut W) -> io::Result<String> {
+    let mut reader = HashingReader { inner: reader, hasher: Sha256::new() };
+    io::copy(&mut reader, writer)?;
+    Ok(reader.hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// The error for a copy whose content does not match its source
+pub fn verification_error(copy: &str, expected: &str, found: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Copy verification failed for {}: expected SHA-256 {}, found {}", copy, expected, found),
+    )
+}
+
+/// Copies a file, hashing the source as it streams, then reads the copy back
+/// and fails if its hash differs, so a flaky drive cannot corrupt it
+/// silently. The copy is removed on any failure. Returns the SHA-256.
+pub fn copy_verified(source: &Path, target: &Path) -> io::Result<String> {
+    copy_verified_to(source, target, File::options().write(true).create(true).truncate(true))
+}
+
+/// Like `copy_verified`, but fails with `AlreadyExists` rather than replace
+/// a file that is already at `target`
+pub fn copy_verified_new(source: &Path, target: &Path) -> io::Result<String> {
+    copy_verified_to(source, target, File::options().write(true).create_new(true))
+}
+
+fn copy_verified_to(source: &Path, target: &Path, options: &fs::OpenOptions) -> io::Result<String> {
+    let mut output = options.open(target)?;
+    let result = (|| {
+        let source_hash = copy_hashed(File::open(source)?, &mut output)?;
+        output.sync_all()?;
+        let copy_hash = hash_file(target, HashAlgorithm::Sha256)?;
+        if copy_hash != source_hash {
+            return Err(verification_error(&target.to_string_lossy(), &source_hash, &copy_hash));
+        }
+        fs::set_permissions(target, fs::metadata(source)?.permissions())?;
+        Ok(source_hash)
+    })();
+    if result.is_err() {
+        drop(output);
+        let _ = fs::remove_file(target);
+    }
+    result
+}
+
+/// Default worker count: available cores, capped at MAX_HASH_WORKERS
+pub fn default_workers() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(MAX_HASH_WORKERS)
+}
+
+/// Hashes files on a bounded pool of worker threads. Results are returned in
+/// the same order as `paths`.
+pub fn hash_files_parallel(paths: &[String], algorithm: HashAlgorithm, workers: usize) -> Vec<FileHash> {
+    let workers = workers.clamp(1, MAX_HASH_WORKERS).min(paths.len().max(1));
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<FileHash>>> = Mutex::new(vec![None; paths.len()]);
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                let result = match hash_file(Path::new(path), algorithm) {
+                    Ok(hash) => FileHash {
+                        path: path.clone(),
+                        hash: Some(hash),
+                        error: None,
+                    },
+                    Err(e) => FileHash {
+                        path: path.clone(),
+                        hash: None,
+                        error: Some(e.to_string()),
+                    },
+                };
+                results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Measured read rate for one source (drive, share or mount) during hashing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceThroughput {
+    pub source: String,
+    pub files: usize,
+    pub bytes: u64,
+    pub elapsed_ms: u64,
+    pub bytes_per_second: f64,
+    /// Workers used after the probe
+    pub workers: usize,
+    /// True when the probe rate was below the slow-source threshold
+    pub slow: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashReport {
+    /// Hashes in the same order as the requested paths
+    pub hashes: Vec<FileHash>,
+    pub sources: Vec<SourceThroughput>,
+}
+
+/// Identifies the volume a path lives on: the drive or UNC share on Windows,
+/// otherwise the first two folders (e.g. `/mnt/evidence`, `/Volumes/Drive`)
+fn source_key(path: &Path) -> String {
+    let mut key = std::path::PathBuf::new();
+    let mut folders = 0;
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => key.push(component),
+            Component::Normal(_) if folders < 2 => {
+                // The prefix alone identifies a Windows drive or share
+                if key.components().any(|c| matches!(c, Component::Prefix(_))) {
+                    break;
+                }
+                key.push(component);
+                folders += 1;
+            }
+            _ => break,
+        }
+    }
+    key.to_string_lossy().to_string()
+}
+
+fn total_bytes(paths: &[String]) -> u64 {
+    paths
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn rate(bytes: u64, elapsed_ms: u64) -> f64 {
+    bytes as f64 * 1000.0 / elapsed_ms.max(1) as f64
+}
+
+/// Hashes files source by source. Each source's first few files are hashed
+/// one at a time to measure its read rate; slow sources continue with
+/// `SLOW_SOURCE_WORKERS` while fast local disks use the full `workers`.
+pub fn hash_files_tuned(paths: &[String], algorithm: HashAlgorithm, workers: usize) -> HashReport {
+    let mut order: Vec<String> = Vec::new();
+    let mut by_source: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, path) in paths.iter().enumerate() {
+        let key = source_key(Path::new(path));
+        if !by_source.contains_key(&key) {
+            order.push(key.clone());
+        }
+        by_source.entry(key).or_default().push(index);
+    }
+
+    let mut hashes: Vec<Option<FileHash>> = vec![None; paths.len()];
+    let mut sources = Vec::new();
+    for source in order {
+        let indices = &by_source[&source];
+        let source_paths: Vec<String> = indices.iter().map(|&i| paths[i].clone()).collect();
+        let (probe, rest) = source_paths.split_at(PROBE_FILES.min(source_paths.len()));
+
+        let started = Instant::now();
+        let mut results = hash_files_parallel(probe, algorithm, 1);
+        let probe_ms = started.elapsed().as_millis() as u64;
+        let probe_bytes = total_bytes(probe);
+        let slow = if probe_bytes >= PROBE_MIN_BYTES {
+            rate(probe_bytes, probe_ms) < SLOW_SOURCE_BYTES_PER_SECOND
+        } else {
+            probe_ms as f64 / probe.len().max(1) as f64 > SLOW_SOURCE_FILE_MS
+        };
+        let source_workers = if slow { SLOW_SOURCE_WORKERS.min(workers.max(1)) } else { workers };
+
+        results.extend(hash_files_parallel(rest, algorithm, source_workers));
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        let bytes = probe_bytes + total_bytes(rest);
+        sources.push(SourceThroughput {
+            source,
+            files: indices.len(),
+            bytes,
+            elapsed_ms,
+            bytes_per_second: rate(bytes, elapsed_ms),
+            workers: source_workers,
+            slow,
+        });
+        for (&index, result) in indices.iter().zip(results) {
+            hashes[index] = Some(result);
+        }
+    }
+
+    HashReport {
+        hashes: hashes.into_iter().flatten().collect(),
+        sources,
+    }
+}
+
+/// Files in hand whose content has a looked-up hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashMatch {
+    pub hash: String,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashLookup {
+    /// Looked-up hashes with at least one matching file, in lookup order
+    pub found: Vec<HashMatch>,
+    /// Hashes no file matched
+    pub missing: Vec<String>,
+    /// Values that are not MD5, SHA-1 or SHA-256 hex digests
+    pub invalid: Vec<String>,
+    /// Files that could not be hashed, so a hash reported missing may still be in hand
+    pub unreadable: Vec<FileHash>,
+}
+
+/// Looks up hashes (e.g. from a production log) among `paths`. Each hash's
+/// algorithm is inferred from its length and files are hashed only with the
+/// algorithms actually needed.
+pub fn lookup_hashes(paths: &[String], hashes: &[String], workers: usize) -> HashLookup {
+    let mut wanted: Vec<String> = Vec::new();
+    let mut invalid = Vec::new();
+    for hash in hashes {
+        let hash = hash.trim().to_lowercase();
+        if hash.is_empty() || wanted.contains(&hash) {
+            continue;
+        }
+        if HashAlgorithm::from_hex_len(hash.len()).is_some() && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            wanted.push(hash);
+        } else {
+            invalid.push(hash);
+        }
+    }
+
+    let mut algorithms: Vec<HashAlgorithm> = wanted
+        .iter()
+        .filter_map(|hash| HashAlgorithm::from_hex_len(hash.len()))
+        .collect();
+    algorithms.sort_by_key(|algorithm| *algorithm as u8);
+    algorithms.dedup();
+
+    let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+    let mut unreadable: Vec<FileHash> = Vec::new();
+    for algorithm in algorithms {
+        for hashed in hash_files_parallel(paths, algorithm, workers) {
+            match hashed.hash {
+                Some(hash) => by_hash.entry(hash).or_default().push(hashed.path),
+                // A file that fails once will fail for every algorithm
+                None if !unreadable.iter().any(|failed| failed.path == hashed.path) => unreadable.push(hashed),
+                None => {}
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    let mut missing = Vec::new();
+    for hash in wanted {
+        match by_hash.remove(&hash) {
+            Some(paths) => found.push(HashMatch { hash, paths }),
+            None => missing.push(hash),
+        }
+    }
+    HashLookup {
+        found,
+        missing,
+        invalid,
+        unreadable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_hashes_keep_the_request_order() {
+        let dir = std::env::temp_dir().join(format!("hashing-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut paths: Vec<String> = (0..40)
+            .map(|n| {
+                let path = dir.join(format!("{}.txt", n));
+                fs::write(&path, if n % 2 == 0 { "abc" } else { "" }).unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+        paths.insert(3, dir.join("missing.txt").to_string_lossy().to_string());
+
+        let hashes = hash_files_parallel(&paths, HashAlgorithm::Sha256, 4);
+        assert_eq!(hashes.iter().map(|hash| &hash.path).collect::<Vec<_>>(), paths.iter().collect::<Vec<_>>());
+        assert_eq!(hashes[0].hash.as_deref(), Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"));
+        assert_eq!(hashes[1].hash.as_deref(), Some("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"));
+        assert!(hashes[3].hash.is_none() && hashes[3].error.is_some());
+        assert_eq!(hash_files_parallel(&paths[..1], HashAlgorithm::Md5, 0)[0].hash.as_deref(), Some("900150983cd24fb0d6963f7d28e17f72"));
+        assert!(hash_files_parallel(&[], HashAlgorithm::Sha1, 8).is_empty());
+
+        let report = hash_files_tuned(&paths, HashAlgorithm::Sha1, 4);
+        assert_eq!(report.hashes.len(), paths.len());
+        assert!(report.hashes.iter().zip(&paths).all(|(hash, path)| &hash.path == path));
+        assert_eq!(report.hashes[0].hash.as_deref(), Some("a9993e364706816aba3e25717850c26c9cd0d89d"));
+        assert_eq!(report.sources.len(), 1);
+        assert_eq!(report.sources[0].files, paths.len());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sources_are_the_first_two_folders() {
+        assert_eq!(source_key(Path::new("/mnt/evidence/case/a.pdf")), "/mnt/evidence");
+        assert_eq!(source_key(Path::new("/Volumes/Drive/a.pdf")), "/Volumes/Drive");
+        assert_eq!(source_key(Path::new("/mnt/../a.pdf")), "/mnt");
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{json_store, vault};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Temporary files older than this were left by a write that was cut off,
+/// such as by a crash or power loss
+const STALE_PARTIAL_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// What a health check of the app's data found and repaired
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HealthReport {
+    pub checked_at: String,
+    /// JSON stores that were read back
+    pub stores_checked: usize,
+    /// Stores that no longer parse. They are left in place so they can be
+    /// restored from a backup; commands using them fail until then.
+    pub unreadable_stores: Vec<String>,
+    /// Temporary files of interrupted writes, removed
+    pub removed_partials: Vec<String>,
+    /// Vault entries whose stored copy was gone, removed from the index
+    pub dropped_vault_entries: usize,
+    /// Set when the app data directory was missing and was created
+    pub created_data_dir: bool,
+}
+
+impl HealthReport {
+    /// Whether anything was found or repaired
+    pub fn has_findings(&self) -> bool {
+        !self.unreadable_stores.is_empty()
+            || !self.removed_partials.is_empty()
+            || self.dropped_vault_entries > 0
+            || self.created_data_dir
+    }
+}
+
+fn is_stale(path: &Path, now: SystemTime) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| now.duration_since(modified).is_ok_and(|age| age >= STALE_PARTIAL_AGE))
+}
+
+fn check_dir(dir: &Path, now: SystemTime, report: &mut HealthReport) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            check_dir(&path, now, report)?;
+        } else if json_store::is_partial(&path) {
+            if is_stale(&path, now) && json_store::remove_partial(&path).is_ok() {
+                report.removed_partials.push(path.to_string_lossy().to_string());
+            }
+        } else if path.extension().is_some_and(|extension| extension == "json") {
+            report.stores_checked += 1;
+            if json_store::check_readable(&path).is_err() {
+                report.unreadable_stores.push(path.to_string_lossy().to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every JSON store under the data directory, the cases' own
+/// included, still parses, and makes the repairs that lose nothing:
+/// creating a missing data directory, removing stale temporary files and
+/// dropping vault entries whose copy is gone
+pub fn run_health_check(data_dir: &Path, checked_at: &str) -> Result<HealthReport, Box<dyn std::error::Error>> {
+    let mut report = HealthReport {
+        checked_at: checked_at.to_string(),
+        ..HealthReport::default()
+    };
+    if !data_dir.exists() {
+        fs::create_dir_all(data_dir)?;
+        report.created_data_dir = true;
+    }
+    check_dir(data_dir, SystemTime::now(), &mut report)?;
+    if !report.unreadable_stores.iter().any(|store| store.ends_with("vault_index.json")) {
+        report.dropped_vault_entries = vault::drop_missing_copies(data_dir)?;
+    }
+    report.unreadable_stores.sort();
+    report.removed_partials.sort();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    #[test]
+    fn stale_partials_are_removed_and_broken_stores_reported() {
+        let dir = std::env::temp_dir().join(format!("health-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let report = run_health_check(&dir, "2026-03-01 09:00:00").unwrap();
+        assert!(report.created_data_dir);
+
+        let case_dir = json_store::case_data_dir(&dir, "A-1");
+        fs::create_dir_all(&case_dir).unwrap();
+        fs::write(dir.join("pins.json"), "[]").unwrap();
+        fs::write(case_dir.join("notes.json"), "[{\"truncated\":").unwrap();
+        let stale = dir.join("pins.json.1-0.partial");
+        fs::write(&stale, "[").unwrap();
+        let an_hour_ago = SystemTime::now() - STALE_PARTIAL_AGE - Duration::from_secs(1);
+        File::options().write(true).open(&stale).unwrap().set_modified(an_hour_ago).unwrap();
+        let fresh = dir.join("pins.json.1-1.partial");
+        fs::write(&fresh, "[").unwrap();
+
+        let report = run_health_check(&dir, "2026-03-01 09:00:00").unwrap();
+        assert!(!report.created_data_dir);
+        assert_eq!(report.stores_checked, 2);
+        assert_eq!(report.unreadable_stores, [case_dir.join("notes.json").to_string_lossy().to_string()]);
+        assert_eq!(report.removed_partials, [stale.to_string_lossy().to_string()]);
+        assert!(fresh.exists());
+        assert!(report.has_findings());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const HISTORY_FILE: &str = "file_history.jsonl";
+
+/// What happened to a file
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileEventKind {
+    Added,
+    Removed,
+    /// A field was edited by hand, by a rule or by an import
+    FieldChanged,
+    /// A field was recomputed from the file
+    Refreshed,
+    /// The file's content changed while it was checked out for editing
+    Modified,
+    Pinned,
+    Unpinned,
+    Linked,
+    Unlinked,
+}
+
+/// One entry in a file's history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEvent {
+    pub timestamp: String,
+    pub absolute_path: String,
+    pub kind: FileEventKind,
+    /// What made the change, e.g. "edit", "sync" or "rule: Bank statements"
+    pub source: String,
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde(default)]
+    pub old_value: Option<String>,
+    #[serde(default)]
+    pub new_value: Option<String>,
+}
+
+impl FileEvent {
+    pub fn new(absolute_path: &str, kind: FileEventKind, source: &str) -> Self {
+        Self {
+            timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            absolute_path: absolute_path.to_string(),
+            kind,
+            source: source.to_string(),
+            field: None,
+            old_value: None,
+            new_value: None,
+        }
+    }
+
+    /// A field going from `old_value` to `new_value`
+    pub fn field_change(
+        absolute_path: &str,
+        kind: FileEventKind,
+        source: &str,
+        field: &str,
+        old_value: &str,
+        new_value: &str,
+    ) -> Self {
+        Self {
+            field: Some(field.to_string()),
+            old_value: Some(old_value.to_string()),
+            new_value: Some(new_value.to_string()),
+            ..Self::new(absolute_path, kind, source)
+        }
+    }
+}
+
+/// A case's history lives in its data directory; files used without a case
+/// share one log in `data_dir`
+fn history_path(data_dir: &Path, case_number: Option<&str>) -> PathBuf {
+    match case_number.filter(|c| !c.trim().is_empty()) {
+        Some(case_number) => json_store::case_data_dir(data_dir, case_number).join(HISTORY_FILE),
+        None => data_dir.join(HISTORY_FILE),
+    }
+}
+
+/// Appends events to the case's file history
+pub fn append_events(data_dir: &Path, case_number: Option<&str>, events: &[FileEvent]) -> std::io::Result<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    let path = history_path(data_dir, case_number);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for event in events {
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+    }
+    Ok(())
+}
+
+/// Everything recorded for one file in a case, oldest first
+pub fn read_file_history(data_dir: &Path, case_number: Option<&str>, absolute_path: &str) -> std::io::Result<Vec<FileEvent>> {
+    Ok(json_store::read_jsonl::<FileEvent>(&history_path(data_dir, case_number))?
+        .into_iter()
+        .filter(|event| event.absolute_path == absolute_path)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_is_kept_per_case_and_per_file() {
+        let dir = std::env::temp_dir().join(format!("file-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        append_events(
+            &dir,
+            Some("A-1"),
+            &[
+                FileEvent::new("/c/a.pdf", FileEventKind::Added, "sync"),
+                FileEvent::field_change("/c/a.pdf", FileEventKind::FieldChanged, "edit", "notes", "", "Check"),
+                FileEvent::new("/c/b.pdf", FileEventKind::Added, "sync"),
+            ],
+        )
+        .unwrap();
+        append_events(&dir, Some("B-2"), &[FileEvent::new("/c/a.pdf", FileEventKind::Pinned, "pin")]).unwrap();
+
+        let history = read_file_history(&dir, Some("A-1"), "/c/a.pdf").unwrap();
+        let kinds: Vec<FileEventKind> = history.iter().map(|event| event.kind).collect();
+        assert_eq!(kinds, [FileEventKind::Added, FileEventKind::FieldChanged]);
+        assert_eq!(history[1].new_value.as_deref(), Some("Check"));
+        assert_eq!(read_file_history(&dir, Some("B-2"), "/c/a.pdf").unwrap().len(), 1);
+        assert!(read_file_history(&dir, None, "/c/a.pdf").unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,224 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Instant, SystemTime};
+
+/// Serializes every read-modify-write of a store. Commands run on worker
+/// threads and several windows can edit the same case, so updates made
+/// at the same time would otherwise overwrite each other.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Numbers temporary files so two writers never share one
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A store as last parsed, with the file's modified time and length then
+struct CachedStore {
+    modified: SystemTime,
+    len: u64,
+    value: Box<dyn Any + Send>,
+}
+
+/// Parsed stores by path. Some stores, such as the case access list, are
+/// read on every command; one whose file has not changed is not parsed
+/// again. Guarded by STORE_LOCK.
+static CACHE: Mutex<BTreeMap<PathBuf, CachedStore>> = Mutex::new(BTreeMap::new());
+
+/// Reads and writes slower than this many milliseconds are logged to stderr
+/// with their timings; 0 turns the log off
+static SLOW_LOG_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Logs store operations slower than `threshold_ms`, or stops logging them
+/// when `None`, to find stores that have grown large enough to slow a case
+pub fn set_slow_log(threshold_ms: Option<u64>) {
+    SLOW_LOG_MS.store(threshold_ms.unwrap_or(0), Ordering::Relaxed);
+}
+
+fn log_if_slow(operation: &str, path: &Path, started: Instant, detail: impl FnOnce() -> String) {
+    let threshold = SLOW_LOG_MS.load(Ordering::Relaxed);
+    let elapsed = started.elapsed().as_millis();
+    if threshold > 0 && elapsed >= u128::from(threshold) {
+        eprintln!("Slow store {} of {}: {} ms ({})", operation, path.display(), elapsed, detail());
+    }
+}
+
+fn cache() -> MutexGuard<'static, BTreeMap<PathBuf, CachedStore>> {
+    CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn lock() -> MutexGuard<'static, ()> {
+    // A panic mid-update never leaves the file half written, so the
+    // poisoned guard is still safe to use
+    STORE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn read_unlocked<T: DeserializeOwned + Default>(path: &Path) -> Result<T, Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let started = Instant::now();
+    let text = fs::read_to_string(path)?;
+    let read_ms = started.elapsed().as_millis();
+    let value = serde_json::from_str(&text)?;
+    log_if_slow("read", path, started, || format!("{} bytes, {} ms reading", text.len(), read_ms));
+    Ok(value)
+}
+
+/// Writes to a uniquely named temporary file beside `path` and renames it
+/// into place, so a crash never leaves a truncated store
+fn write_unlocked<T: Serialize>(path: &Path, value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    let directory = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(directory)?;
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let temp_path: PathBuf = directory.join(format!(
+        "{}.{}-{}.partial",
+        file_name,
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let started = Instant::now();
+    let text = serde_json::to_string_pretty(value)?;
+    let serialize_ms = started.elapsed().as_millis();
+    cache().remove(path);
+    let result = fs::write(&temp_path, &text).and_then(|_| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    log_if_slow("write", path, started, || format!("{} bytes, {} ms serializing", text.len(), serialize_ms));
+    Ok(result?)
+}
+
+/// Modified time and length of a store's file, which identify the version
+/// a cached value was parsed from
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Reads a JSON store; a missing file reads as the empty value. A store
+/// whose file is unchanged since the last read comes from the cache.
+pub fn read<T: DeserializeOwned + Default + Clone + Send + 'static>(path: &Path) -> Result<T, Box<dyn std::error::Error>> {
+    let _guard = lock();
+    let Some((modified, len)) = file_stamp(path) else {
+        return read_unlocked(path);
+    };
+    if let Some(cached) = cache().get(path).filter(|cached| cached.modified == modified && cached.len == len) {
+        if let Some(value) = cached.value.downcast_ref::<T>() {
+            return Ok(value.clone());
+        }
+    }
+    let value: T = read_unlocked(path)?;
+    cache().insert(path.to_path_buf(), CachedStore { modified, len, value: Box::new(value.clone()) });
+    Ok(value)
+}
+
+/// Parsed stores held in the cache from files under `directory`, and the
+/// total size of those files
+pub fn cached_under(directory: &Path) -> (usize, u64) {
+    let _guard = lock();
+    cache()
+        .iter()
+        .filter(|(path, _)| path.starts_with(directory))
+        .fold((0, 0), |(count, bytes), (_, cached)| (count + 1, bytes + cached.len))
+}
+
+/// Reads a JSON store, applies `change` and writes the result back, all
+/// under the store lock. Returns whatever `change` returns.
+pub fn update<T, R>(path: &Path, change: impl FnOnce(&mut T) -> R) -> Result<R, Box<dyn std::error::Error>>
+where
+    T: DeserializeOwned + Serialize + Default,
+{
+    let _guard = lock();
+    let mut value = read_unlocked(path)?;
+    let result = change(&mut value);
+    write_unlocked(path, &value)?;
+    Ok(result)
+}
+
+/// Whether a store's file still parses as JSON, checked under the store lock
+pub fn check_readable(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = lock();
+    read_unlocked::<serde_json::Value>(path).map(|_| ())
+}
+
+/// Whether a file is a temporary file left by a write that never finished
+pub fn is_partial(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "partial")
+}
+
+/// Removes a temporary file left by an interrupted write. Writes hold the
+/// store lock until their temporary file is renamed, so none is in use
+/// while it is held.
+pub fn remove_partial(path: &Path) -> std::io::Result<()> {
+    let _guard = lock();
+    fs::remove_file(path)
+}
+
+/// Directory holding one case's own files under the app data directory. The
+/// folder is named for the hex-encoded case number, as case windows are
+/// labelled, so case numbers differing only in letter case or in characters
+/// a folder name cannot hold never share one, and none can leave `cases`.
+pub fn case_data_dir(data_dir: &Path, case_number: &str) -> PathBuf {
+    let name: String = case_number.trim().bytes().map(|b| format!("{:02x}", b)).collect();
+    data_dir.join("cases").join(if name.is_empty() { "_".to_string() } else { name })
+}
+
+/// Every record of an append-only JSON Lines log, oldest first; none if it
+/// does not exist. Lines that were partially written, hand-edited or are not
+/// valid UTF-8 are skipped, and the records after them are still read.
+pub fn read_jsonl<T: DeserializeOwned>(path: &Path) -> std::io::Result<Vec<T>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let mut records = Vec::new();
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        if let Ok(record) = serde_json::from_slice(&line) {
+            records.push(record);
+        }
+        line.clear();
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_reads_follow_updates_and_outside_edits() {
+        let dir = std::env::temp_dir().join(format!("json-store-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("store.json");
+
+        assert!(read::<Vec<u32>>(&path).unwrap().is_empty());
+        update(&path, |all: &mut Vec<u32>| all.push(1)).unwrap();
+        assert_eq!(read::<Vec<u32>>(&path).unwrap(), [1]);
+        update(&path, |all: &mut Vec<u32>| all.push(2)).unwrap();
+        assert_eq!(read::<Vec<u32>>(&path).unwrap(), [1, 2]);
+
+        // An edit made outside the store changes the file's length
+        fs::write(&path, "[1, 2, 3]").unwrap();
+        assert_eq!(read::<Vec<u32>>(&path).unwrap(), [1, 2, 3]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn jsonl_reads_skip_bad_lines_and_keep_going() {
+        let dir = std::env::temp_dir().join(format!("json-store-jsonl-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("log.jsonl");
+
+        assert!(read_jsonl::<u32>(&path).unwrap().is_empty());
+        fs::write(&path, b"1\n{\"half\n\xff\xfe\n2\r\n\n3").unwrap();
+        assert_eq!(read_jsonl::<u32>(&path).unwrap(), [1, 2, 3]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
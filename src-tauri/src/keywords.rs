@@ -0,0 +1,219 @@
+use crate::json_store;
+use crate::search::item_matches;
+use crate::InventoryItem;
+use rust_xlsxwriter::{Format, FormatBorder, Workbook};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const KEYWORD_LISTS_FILE: &str = "keyword_lists.json";
+
+/// A named list of search terms kept with a case, e.g. the terms agreed
+/// with opposing counsel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeywordList {
+    pub name: String,
+    pub case_number: Option<String>,
+    pub terms: Vec<String>,
+}
+
+/// Hit count for one search term
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TermHits {
+    pub term: String,
+    pub documents: usize,
+}
+
+/// Which terms hit a given document; `hits[i]` corresponds to `terms[i]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentHits {
+    pub absolute_path: String,
+    pub file_name: String,
+    pub bates_stamp: String,
+    pub hits: Vec<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeywordReport {
+    pub terms: Vec<TermHits>,
+    /// Documents hit by at least one term
+    pub documents: Vec<DocumentHits>,
+    /// Documents hit by any term (deduplicated across terms)
+    pub unique_documents: usize,
+}
+
+/// Parses a keyword list: one term per line, blank lines and `#` comments ignored
+pub fn parse_keyword_list(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Reads a keyword list file, see `parse_keyword_list`
+pub fn read_keyword_list(path: &str) -> std::io::Result<Vec<String>> {
+    Ok(parse_keyword_list(&fs::read_to_string(path)?))
+}
+
+fn lists_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(KEYWORD_LISTS_FILE)
+}
+
+fn same_list(list: &KeywordList, case_number: Option<&str>, name: &str) -> bool {
+    list.case_number.as_deref() == case_number && list.name.eq_ignore_ascii_case(name.trim())
+}
+
+/// A case's saved keyword lists
+pub fn case_keyword_lists(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<KeywordList>, Box<dyn std::error::Error>> {
+    let lists: Vec<KeywordList> = json_store::read(&lists_path(data_dir))?;
+    Ok(lists.into_iter().filter(|list| list.case_number.as_deref() == case_number).collect())
+}
+
+/// One of a case's keyword lists by name (case-insensitive)
+pub fn find_keyword_list(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    name: &str,
+) -> Result<Option<KeywordList>, Box<dyn std::error::Error>> {
+    Ok(case_keyword_lists(data_dir, case_number)?.into_iter().find(|list| same_list(list, case_number, name)))
+}
+
+/// Saves a keyword list, replacing any list with the same name in the case
+pub fn save_keyword_list(data_dir: &Path, list: KeywordList) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&lists_path(data_dir), |lists: &mut Vec<KeywordList>| {
+        lists.retain(|existing| !same_list(existing, list.case_number.as_deref(), &list.name));
+        lists.push(list);
+    })
+}
+
+/// Removes a keyword list; returns whether it existed
+pub fn remove_keyword_list(data_dir: &Path, case_number: Option<&str>, name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    json_store::update(&lists_path(data_dir), |lists: &mut Vec<KeywordList>| {
+        let before = lists.len();
+        lists.retain(|list| !same_list(list, case_number, name));
+        lists.len() != before
+    })
+}
+
+/// Runs each term against every item's fields (case-insensitive substring)
+pub fn run_keyword_report(items: &[InventoryItem], terms: &[String]) -> KeywordReport {
+    let terms_lower: Vec<String> = terms.iter().map(|t| t.trim().to_lowercase()).collect();
+    let mut counts = vec![0usize; terms.len()];
+    let mut documents = Vec::new();
+
+    for item in items {
+        let hits: Vec<bool> = terms_lower.iter().map(|term| item_matches(item, term)).collect();
+        if !hits.iter().any(|hit| *hit) {
+            continue;
+        }
+        for (count, hit) in counts.iter_mut().zip(&hits) {
+            if *hit {
+                *count += 1;
+            }
+        }
+        documents.push(DocumentHits {
+            absolute_path: item.absolute_path.clone(),
+            file_name: item.file_name.clone(),
+            bates_stamp: item.bates_stamp.clone(),
+            hits,
+        });
+    }
+
+    KeywordReport {
+        terms: terms
+            .iter()
+            .zip(counts)
+            .map(|(term, documents)| TermHits {
+                term: term.clone(),
+                documents,
+            })
+            .collect(),
+        unique_documents: documents.len(),
+        documents,
+    }
+}
+
+/// Writes a "Term Summary" sheet and a per-document "Hit Matrix" sheet
+pub fn write_keyword_report_xlsx(
+    report: &KeywordReport,
+    output_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold().set_border(FormatBorder::Thin);
+
+    let summary = workbook.add_worksheet();
+    summary.set_name("Term Summary")?;
+    summary.set_column_width(0, 30.0)?;
+    summary.set_column_width(1, 15.0)?;
+    summary.write_string_with_format(0, 0, "Term", &header_format)?;
+    summary.write_string_with_format(0, 1, "Documents", &header_format)?;
+    let mut row = 1;
+    for term in &report.terms {
+        summary.write_string(row, 0, &term.term)?;
+        summary.write_number(row, 1, term.documents as f64)?;
+        row += 1;
+    }
+    summary.write_string_with_format(row, 0, "Unique documents", &header_format)?;
+    summary.write_number(row, 1, report.unique_documents as f64)?;
+
+    let matrix = workbook.add_worksheet();
+    matrix.set_name("Hit Matrix")?;
+    matrix.set_column_width(0, 30.0)?;
+    matrix.set_column_width(1, 15.0)?;
+    matrix.write_string_with_format(0, 0, "File Name", &header_format)?;
+    matrix.write_string_with_format(0, 1, "Bates Stamp", &header_format)?;
+    for (col, term) in report.terms.iter().enumerate() {
+        matrix.write_string_with_format(0, col as u16 + 2, &term.term, &header_format)?;
+    }
+    for (idx, document) in report.documents.iter().enumerate() {
+        let row = idx as u32 + 1;
+        matrix.write_string(row, 0, &document.file_name)?;
+        matrix.write_string(row, 1, &document.bates_stamp)?;
+        for (col, hit) in document.hits.iter().enumerate() {
+            if *hit {
+                matrix.write_string(row, col as u16 + 2, "X")?;
+            }
+        }
+    }
+
+    workbook.save(output_path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(name: &str, case_number: &str, terms: &[&str]) -> KeywordList {
+        KeywordList {
+            name: name.to_string(),
+            case_number: Some(case_number.to_string()),
+            terms: terms.iter().map(|term| term.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn parsing_skips_blank_lines_and_comments() {
+        assert_eq!(parse_keyword_list("# agreed terms\n  wire transfer \n\nACH\r\n#draft\n"), ["wire transfer", "ACH"]);
+    }
+
+    #[test]
+    fn lists_are_saved_and_found_per_case_by_name() {
+        let dir = std::env::temp_dir().join(format!("keyword-lists-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        save_keyword_list(&dir, list("Agreed", "A-1", &["wire"])).unwrap();
+        save_keyword_list(&dir, list("Agreed", "B-2", &["ach"])).unwrap();
+        save_keyword_list(&dir, list("agreed", "A-1", &["wire", "swift"])).unwrap();
+
+        let found = find_keyword_list(&dir, Some("A-1"), " AGREED ").unwrap().unwrap();
+        assert_eq!(found.terms, ["wire", "swift"]);
+        assert_eq!(case_keyword_lists(&dir, Some("A-1")).unwrap().len(), 1);
+        assert!(find_keyword_list(&dir, Some("C-3"), "Agreed").unwrap().is_none());
+        assert!(remove_keyword_list(&dir, Some("B-2"), "agreed").unwrap());
+        assert!(case_keyword_lists(&dir, Some("B-2")).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,16 +1,88 @@
+// Without the desktop feature only the scanning, export and hashing entry
+// points used by the CLI are reachable
+#![cfg_attr(not(feature = "desktop"), allow(dead_code, unused_imports))]
+
 mod scanner;
 mod mappings;
 mod export;
 mod error;
+mod report;
+mod activity;
+mod stats;
+mod search;
+mod diff;
+mod diagnostics;
+mod decisions;
+mod rules;
+mod keywords;
+mod hashing;
+mod manifest;
+mod dates;
+mod organize;
+#[cfg(feature = "desktop")]
+mod preview;
+mod metrics;
+mod categories;
+mod validation;
+mod qc;
+mod json_store;
+mod binders;
+mod pins;
+mod watches;
+mod terms;
+mod clusters;
+mod profiling;
+mod artifacts;
+mod duplicates;
+mod dedup;
+mod reextract;
+mod anomalies;
+mod consistency;
+mod finalize;
+mod timestamp;
+mod paths;
+mod retry_queue;
+mod access;
+mod workspace;
+mod lock;
+mod links;
+mod query;
+mod history;
+mod checkouts;
+mod versions;
+mod notes;
+mod recents;
+mod api;
+mod tools;
+mod schedules;
+mod bundle;
+mod privilege;
+mod productions;
+mod vault;
+mod health;
+mod configs;
+mod changes;
+#[cfg(feature = "desktop")]
+mod commands;
 
-use scanner::{scan_folder, count_files};
+use scanner::{parse_utc_offset, scan_folder_with_options, read_metadata_with_retry, FileMetadata, FailedFile, ScanOptions, SmallFile, SmallFilePolicy, ExtensionStats, TypePolicy};
 use mappings::process_file_metadata;
-use export::{InventoryRow, generate_xlsx, generate_csv, generate_json, read_xlsx, read_csv, read_json};
+pub use export::ExportOptions;
+use export::CollisionPolicy;
+pub use report::ReportTemplate;
+pub use links::FileLink;
+use export::{InventoryRow, generate_xlsx, generate_csv, generate_json, generate_sqlite};
 use error::AppError;
+use hashing::{HashAlgorithm, HashLookup};
+use manifest::ManifestReconciliation;
+use paths::PathCase;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "desktop")]
+pub use commands::run;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct InventoryItem {
     pub date_rcvd: String,
     pub doc_year: i32,
@@ -21,76 +93,199 @@ pub struct InventoryItem {
     pub folder_name: String,
     pub folder_path: String,
     pub file_type: String,
+    /// Broad category such as "Documents" or "Email", see `categories`
+    #[serde(default)]
+    pub file_category: String,
     pub bates_stamp: String,
     pub notes: String,
     // Internal fields for tracking
     pub absolute_path: String,
 }
 
-#[tauri::command]
-fn count_directory_files(path: String) -> Result<usize, String> {
-    let root_path = PathBuf::from(&path);
-    
-    if !root_path.exists() {
-        return Err(AppError::PathNotFound(path).to_string_message());
+/// Serialized names of the user-facing inventory fields
+pub const INVENTORY_FIELDS: &[&str] = &[
+    "date_rcvd",
+    "doc_year",
+    "doc_date_range",
+    "document_type",
+    "document_description",
+    "file_name",
+    "folder_name",
+    "folder_path",
+    "file_type",
+    "file_category",
+    "bates_stamp",
+    "notes",
+];
+
+impl InventoryItem {
+    /// Looks up a user-facing field by its serialized name
+    pub fn field_value(&self, field: &str) -> Option<String> {
+        let value = match field {
+            "date_rcvd" => self.date_rcvd.clone(),
+            "doc_year" => self.doc_year.to_string(),
+            "doc_date_range" => self.doc_date_range.clone(),
+            "document_type" => self.document_type.clone(),
+            "document_description" => self.document_description.clone(),
+            "file_name" => self.file_name.clone(),
+            "folder_name" => self.folder_name.clone(),
+            "folder_path" => self.folder_path.clone(),
+            "file_type" => self.file_type.clone(),
+            "file_category" => self.file_category.clone(),
+            "bates_stamp" => self.bates_stamp.clone(),
+            "notes" => self.notes.clone(),
+            _ => return None,
+        };
+        Some(value)
     }
     
-    if !root_path.is_dir() {
-        return Err(AppError::NotADirectory(path).to_string_message());
+    /// Sets a user-facing field by its serialized name. Returns false if the
+    /// field is unknown or the value doesn't parse (doc_year must be a number).
+    pub fn set_field_value(&mut self, field: &str, value: &str) -> bool {
+        let target = match field {
+            "doc_year" => {
+                return match value.trim().parse::<i32>() {
+                    Ok(year) => {
+                        self.doc_year = year;
+                        true
+                    }
+                    Err(_) => false,
+                };
+            }
+            "date_rcvd" => &mut self.date_rcvd,
+            "doc_date_range" => &mut self.doc_date_range,
+            "document_type" => &mut self.document_type,
+            "document_description" => &mut self.document_description,
+            "file_name" => &mut self.file_name,
+            "folder_name" => &mut self.folder_name,
+            "folder_path" => &mut self.folder_path,
+            "file_type" => &mut self.file_type,
+            "file_category" => &mut self.file_category,
+            "bates_stamp" => &mut self.bates_stamp,
+            "notes" => &mut self.notes,
+            _ => return false,
+        };
+        *target = value.to_string();
+        true
+    }
+}
+
+/// A 2024 PDF at `absolute_path` for tests, named for the path's last
+/// component; tests set the fields they care about over it
+#[cfg(test)]
+pub(crate) fn test_item(absolute_path: &str) -> InventoryItem {
+    InventoryItem {
+        doc_year: 2024,
+        file_name: absolute_path.rsplit(['/', '\\']).next().unwrap_or_default().to_string(),
+        file_type: "PDF".to_string(),
+        absolute_path: absolute_path.to_string(),
+        ..Default::default()
     }
-    
-    count_files(&root_path)
-        .map_err(|e| AppError::ScanError(e.to_string()).to_string_message())
 }
 
-#[tauri::command]
-fn scan_directory(path: String) -> Result<Vec<InventoryItem>, String> {
-    let root_path = PathBuf::from(&path);
+/// Validates that a command argument points at an existing directory
+fn validate_directory_path(path: &str) -> Result<PathBuf, String> {
+    let root_path = PathBuf::from(path);
     
     if !root_path.exists() {
-        return Err(AppError::PathNotFound(path).to_string_message());
+        return Err(AppError::PathNotFound(path.to_string()).to_string_message());
     }
     
     if !root_path.is_dir() {
-        return Err(AppError::NotADirectory(path).to_string_message());
+        return Err(AppError::NotADirectory(path.to_string()).to_string_message());
     }
     
-    let files = scan_folder(&root_path)
-        .map_err(|e| AppError::ScanError(e.to_string()).to_string_message())?;
+    Ok(root_path)
+}
+
+/// Rejects scan options that cannot be applied, returning the time zone label
+/// timestamps will be rendered in
+fn validate_scan_options(options: &ScanOptions) -> Result<String, String> {
+    match options.time_zone.as_deref() {
+        None => Ok("local".to_string()),
+        Some(zone) => match parse_utc_offset(zone) {
+            Some(offset) => Ok(offset.to_string()),
+            None => Err(AppError::InvalidTimeZone(zone.to_string()).to_string_message()),
+        },
+    }
+}
+
+/// Builds a fresh inventory item from scanned metadata, applying document mappings
+fn item_from_metadata(file_metadata: FileMetadata) -> InventoryItem {
+    let doc_info = process_file_metadata(&file_metadata);
+    let file_category = categories::categorize(Path::new(&file_metadata.absolute_path), &file_metadata.file_type);
     
-    let mut items = Vec::new();
-    
-    for file_metadata in files {
-        let doc_info = process_file_metadata(&file_metadata);
-        
-        items.push(InventoryItem {
-            date_rcvd: String::new(),
-            doc_year: file_metadata.created_year,
-            doc_date_range: doc_info.doc_date_range,
-            document_type: doc_info.document_type,
-            document_description: doc_info.document_description,
-            file_name: file_metadata.file_name,
-            folder_name: file_metadata.folder_name,
-            folder_path: file_metadata.folder_path,
-            file_type: file_metadata.file_type,
-            bates_stamp: String::new(),
-            notes: String::new(),
-            absolute_path: file_metadata.absolute_path,
+    InventoryItem {
+        date_rcvd: String::new(),
+        doc_year: file_metadata.created_year,
+        doc_date_range: doc_info.doc_date_range,
+        document_type: doc_info.document_type,
+        document_description: doc_info.document_description,
+        file_name: file_metadata.file_name,
+        folder_name: file_metadata.folder_name,
+        folder_path: file_metadata.folder_path,
+        file_type: file_metadata.file_type,
+        file_category: file_category.to_string(),
+        bates_stamp: String::new(),
+        notes: String::new(),
+        absolute_path: file_metadata.absolute_path,
+    }
+}
+
+/// Builds an item from a scan, noting small files and flagged types when the
+/// scan options ask for it
+fn item_from_scan(file_metadata: FileMetadata, options: &ScanOptions) -> InventoryItem {
+    let mut flags = Vec::new();
+    if options.type_policy(&file_metadata.file_type) == TypePolicy::Flag {
+        flags.push(format!("Flagged file type ({})", file_metadata.file_type));
+    }
+    if options.small_file_policy == SmallFilePolicy::Flag
+        && options.is_small_file(file_metadata.size_bytes)
+    {
+        flags.push(if file_metadata.size_bytes == 0 {
+            "Zero-byte file".to_string()
+        } else {
+            format!("Small file ({} bytes)", file_metadata.size_bytes)
         });
     }
     
-    Ok(items)
+    let mut item = item_from_metadata(file_metadata);
+    item.notes = flags.join("; ");
+    item
 }
 
-#[tauri::command]
-fn export_inventory(
-    items: Vec<InventoryItem>,
-    format: String,
-    output_path: String,
-    case_number: Option<String>,
-    folder_path: Option<String>,
-) -> Result<(), String> {
-    let rows: Vec<InventoryRow> = items
+/// Scans a directory into inventory items (shared by the scan command and the CLI)
+pub fn scan_directory_items(path: String) -> Result<Vec<InventoryItem>, String> {
+    Ok(scan_directory_with_report(path, &ScanOptions::default())?.items)
+}
+
+/// Scans a directory, reporting unreadable files alongside the items
+pub fn scan_directory_with_report(path: String, options: &ScanOptions) -> Result<ScanReport, String> {
+    let root_path = validate_directory_path(&path)?;
+    let time_zone = validate_scan_options(options)?;
+    
+    let outcome = scan_folder_with_options(&root_path, options)
+        .map_err(|e| AppError::ScanError(e.to_string()).to_string_message())?;
+    
+    Ok(ScanReport {
+        items: outcome
+            .files
+            .into_iter()
+            .map(|file_metadata| item_from_scan(file_metadata, options))
+            .collect(),
+        failed_files: outcome.failed,
+        placeholders: outcome.placeholders,
+        ignored_files: outcome.ignored_files,
+        ignored_folders: outcome.ignored_folders,
+        small_files: outcome.small_files,
+        extension_stats: sorted_extension_stats(outcome.extension_stats),
+        time_zone,
+    })
+}
+
+/// Converts inventory items to export rows (the source path is kept only for XLSX links)
+fn items_to_rows(items: Vec<InventoryItem>) -> Vec<InventoryRow> {
+    items
         .into_iter()
         .map(|item| InventoryRow {
             date_rcvd: item.date_rcvd,
@@ -102,148 +297,283 @@ fn export_inventory(
             folder_name: item.folder_name,
             folder_path: item.folder_path,
             file_type: item.file_type,
+            file_category: item.file_category,
             bates_stamp: item.bates_stamp,
             notes: item.notes,
+            absolute_path: item.absolute_path,
+            size_bytes: None,
         })
-        .collect();
+        .collect()
+}
+
+/// Writes items in the given format (shared by the export command and the CLI)
+pub fn export_items(
+    items: Vec<InventoryItem>,
+    format: &str,
+    output_path: &str,
+    case_number: Option<&str>,
+    folder_path: Option<&str>,
+    options: &ExportOptions,
+) -> Result<(), String> {
+    let mut rows = items_to_rows(items);
+    // Sizes are read before redaction may clear the source paths
+    if format == "xlsx" && options.group_by.is_some() {
+        export::fill_file_sizes(&mut rows);
+    }
+    export::redact_rows(&mut rows, &options.redacted_fields)
+        .map_err(|field| AppError::UnknownField(field).to_string_message())?;
+    // A redacted path must not reappear as the source folder in the header
+    let folder_path = if export::redacts_paths(&options.redacted_fields) { None } else { folder_path };
+    export::resolve_column_groups(&options.column_groups)
+        .map_err(|group| AppError::InvalidColumnGroup(group).to_string_message())?;
+    if let Some(field) = options.group_by.as_deref() {
+        if !INVENTORY_FIELDS.contains(&field) {
+            return Err(AppError::UnknownField(field.to_string()).to_string_message());
+        }
+    }
     
-    match format.as_str() {
-        "xlsx" => generate_xlsx(&rows, case_number.as_deref(), folder_path.as_deref(), &output_path)
+    match format {
+        "xlsx" => generate_xlsx(&rows, case_number, folder_path, output_path, options)
             .map_err(|e| AppError::XlsxError(e.to_string()).to_string_message()),
-        "csv" => generate_csv(&rows, case_number.as_deref(), folder_path.as_deref(), &output_path)
+        "csv" => generate_csv(&rows, case_number, folder_path, output_path, options)
             .map_err(|e| AppError::CsvError(e.to_string()).to_string_message()),
-        "json" => generate_json(&rows, case_number.as_deref(), folder_path.as_deref(), &output_path)
+        "json" => generate_json(&rows, case_number, folder_path, output_path, options)
             .map_err(|e| AppError::JsonError(e.to_string()).to_string_message()),
-        _ => Err(AppError::UnsupportedFormat(format).to_string_message()),
+        "sqlite" => generate_sqlite(&rows, case_number, folder_path, output_path, options)
+            .map_err(|e| AppError::SqliteError(e.to_string()).to_string_message()),
+        _ => Err(AppError::UnsupportedFormat(format.to_string()).to_string_message()),
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ImportResult {
-    pub items: Vec<InventoryItem>,
-    pub case_number: Option<String>,
-    pub folder_path: Option<String>,
-}
-
-#[tauri::command]
-fn import_inventory(
-    file_path: String,
-    format: Option<String>,
-) -> Result<ImportResult, String> {
-    // Detect format from file extension if not provided
-    let detected_format = format.unwrap_or_else(|| {
-        let path = PathBuf::from(&file_path);
-        path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| ext.to_lowercase())
-            .unwrap_or_else(|| "xlsx".to_string())
-    });
-    
-    let (rows, case_number, folder_path) = match detected_format.as_str() {
-        "xlsx" => read_xlsx(&file_path)
-            .map_err(|e| AppError::ReadXlsxError(e.to_string()).to_string_message())?,
-        "csv" => read_csv(&file_path)
-            .map_err(|e| AppError::ReadCsvError(e.to_string()).to_string_message())?,
-        "json" => read_json(&file_path)
-            .map_err(|e| AppError::ReadJsonError(e.to_string()).to_string_message())?,
-        _ => return Err(AppError::UnsupportedFormat(detected_format).to_string_message()),
-    };
-    
-    // Convert InventoryRow to InventoryItem (with empty absolute_path)
-    let items: Vec<InventoryItem> = rows
-        .into_iter()
-        .map(|row| InventoryItem {
-            date_rcvd: row.date_rcvd,
-            doc_year: row.doc_year,
-            doc_date_range: row.doc_date_range,
-            document_type: row.document_type,
-            document_description: row.document_description,
-            file_name: row.file_name,
-            folder_name: row.folder_name,
-            folder_path: row.folder_path,
-            file_type: row.file_type,
-            bates_stamp: row.bates_stamp,
-            notes: row.notes,
-            absolute_path: String::new(), // Not exported, so empty
-        })
-        .collect();
+/// Writes an HTML report for the items (shared by the report command and the CLI)
+pub fn generate_report_file(
+    items: Vec<InventoryItem>,
+    template: &ReportTemplate,
+    output_path: &str,
+    case_number: Option<&str>,
+    folder_path: Option<&str>,
+    links: &[FileLink],
+) -> Result<(), String> {
+    let rows = items_to_rows(items);
     
-    Ok(ImportResult {
-        items,
-        case_number,
-        folder_path,
-    })
+    report::generate_report(&rows, template, case_number, folder_path, links, output_path)
+        .map_err(|e| AppError::ReportError(e.to_string()).to_string_message())
 }
 
-#[tauri::command]
-fn sync_inventory(
-    folder_path: String,
-    existing_items: Vec<InventoryItem>,
-) -> Result<Vec<InventoryItem>, String> {
-    let root_path = PathBuf::from(&folder_path);
+/// Validates that a command argument points at an existing regular file
+fn validate_file_path(path: &str) -> Result<PathBuf, String> {
+    let file_path = PathBuf::from(path);
     
-    if !root_path.exists() {
-        return Err(AppError::PathNotFound(folder_path).to_string_message());
+    if !file_path.exists() {
+        return Err(AppError::PathNotFound(path.to_string()).to_string_message());
     }
     
-    if !root_path.is_dir() {
-        return Err(AppError::NotADirectory(folder_path).to_string_message());
+    if !file_path.is_file() {
+        return Err(AppError::NotAFile(path.to_string()).to_string_message());
     }
     
-    // Scan folder for current files
-    let files = scan_folder(&root_path)
+    Ok(file_path)
+}
+
+/// Result of a detailed scan: items plus files that could not be read
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanReport {
+    pub items: Vec<InventoryItem>,
+    pub failed_files: Vec<FailedFile>,
+    /// Online-only cloud placeholders encountered (see ScanOptions::placeholder_policy)
+    pub placeholders: Vec<String>,
+    /// Noise files skipped by the ignore list (see ScanOptions::ignore_patterns)
+    pub ignored_files: usize,
+    /// Folders skipped by the ignore list, not counting their contents
+    pub ignored_folders: usize,
+    /// Zero-byte and sub-threshold files (see ScanOptions::small_file_policy)
+    pub small_files: Vec<SmallFile>,
+    /// Counts and sizes per file type, most common first
+    pub extension_stats: Vec<ExtensionStats>,
+    /// Zone the items' timestamps were rendered in: "local" or a UTC offset
+    pub time_zone: String,
+}
+
+fn sorted_extension_stats(stats: std::collections::BTreeMap<String, ExtensionStats>) -> Vec<ExtensionStats> {
+    let mut stats: Vec<ExtensionStats> = stats.into_values().collect();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.file_type.cmp(&b.file_type)));
+    stats
+}
+
+/// A one-off document added outside any scanned folder
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IngestedFile {
+    pub item: InventoryItem,
+    /// SHA-256 of the file content
+    pub sha256: String,
+    /// Where the file was copied to, if a copy was requested
+    pub copied_to: Option<String>,
+}
+
+/// Copies `source` into `directory`, renaming with `_2`, `_3`, ... on collision
+fn copy_into(source: &Path, directory: &Path) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(directory).map_err(|e| AppError::Io(e).to_string_message())?;
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| AppError::NotAFile(source.to_string_lossy().to_string()).to_string_message())?;
+    let destination = export::resolve_export_path(directory, &file_name, CollisionPolicy::Increment)
+        .map_err(|path| AppError::ExportExists(path).to_string_message())?;
+    hashing::copy_verified(source, &destination).map_err(|e| AppError::Io(e).to_string_message())?;
+    Ok(destination)
+}
+
+/// Ingests a single dropped file: reads its metadata, applies the document
+/// mappings and hashes it. With `copy_to`, the file is first copied into that
+/// folder and the copy is inventoried; otherwise the original is referenced.
+/// The item's folder path is relative to `source_folder` (the case's scanned
+/// folder) when the file lies inside it, as a scan would record it, and is
+/// otherwise the full path of the folder holding the file.
+/// Shared by the ingest commands and the CLI.
+pub fn ingest_file(file_path: &str, copy_to: Option<&str>, source_folder: Option<&str>) -> Result<IngestedFile, String> {
+    let source = validate_file_path(file_path)?;
+    let (path, copied_to) = match copy_to {
+        Some(directory) => {
+            let destination = copy_into(&source, Path::new(directory))?;
+            let copied = destination.to_string_lossy().to_string();
+            (destination, Some(copied))
+        }
+        None => (source, None),
+    };
+    
+    let parent = path.parent().unwrap_or(&path).to_path_buf();
+    let root = source_folder.map(PathBuf::from).filter(|root| parent.starts_with(root));
+    let mut file_metadata = read_metadata_with_retry(root.as_ref().unwrap_or(&parent), &path, &ScanOptions::default())
         .map_err(|e| AppError::ScanError(e.to_string()).to_string_message())?;
+    if root.is_none() {
+        file_metadata.folder_path = parent.to_string_lossy().replace('\\', "/");
+    }
+    let sha256 = hashing::hash_file(&path, HashAlgorithm::Sha256)
+        .map_err(|e| AppError::Io(e).to_string_message())?;
     
-    // Create a map of existing items by absolute_path for quick lookup
-    let mut existing_map: std::collections::HashMap<String, InventoryItem> = existing_items
-        .into_iter()
-        .map(|item| (item.absolute_path.clone(), item))
-        .collect();
-    
-    let mut updated_items = Vec::new();
-    let mut processed_paths = std::collections::HashSet::new();
-    
-    // Process new/updated files
-    for file_metadata in files {
-        let absolute_path = file_metadata.absolute_path.clone();
-        processed_paths.insert(absolute_path.clone());
-        
-        if let Some(existing_item) = existing_map.remove(&absolute_path) {
-            // File still exists - keep it with existing user edits
-            updated_items.push(existing_item);
-        } else {
-            // New file - create new item
-            let doc_info = process_file_metadata(&file_metadata);
-            
-            updated_items.push(InventoryItem {
-                date_rcvd: String::new(),
-                doc_year: file_metadata.created_year,
-                doc_date_range: doc_info.doc_date_range,
-                document_type: doc_info.document_type,
-                document_description: doc_info.document_description,
-                file_name: file_metadata.file_name,
-                folder_name: file_metadata.folder_name,
-                folder_path: file_metadata.folder_path,
-                file_type: file_metadata.file_type,
-                bates_stamp: String::new(),
-                notes: String::new(),
-                absolute_path: file_metadata.absolute_path,
-            });
-        }
+    Ok(IngestedFile {
+        item: item_from_metadata(file_metadata),
+        sha256,
+        copied_to,
+    })
+}
+
+/// Checks which hashes (given directly and/or read from a production log or
+/// hash manifest) match files among `paths` (shared by the lookup command and the CLI)
+pub fn lookup_manifest_hashes(
+    paths: &[String],
+    mut hashes: Vec<String>,
+    manifest_path: Option<&str>,
+    workers: Option<usize>,
+) -> Result<HashLookup, String> {
+    if let Some(manifest_path) = manifest_path {
+        validate_file_path(manifest_path)?;
+        let entries = manifest::read_manifest(manifest_path)
+            .map_err(|e| AppError::ManifestError(e.to_string()).to_string_message())?;
+        hashes.extend(entries.into_iter().map(|entry| entry.hash));
     }
     
-    // Files that were removed are not included (they're in existing_map but not in processed_paths)
-    // This is intentional - we only keep files that still exist
+    Ok(hashing::lookup_hashes(paths, &hashes, workers.unwrap_or_else(hashing::default_workers)))
+}
+
+/// Reconciles a hash manifest against the files in `folder_path` (shared by
+/// the reconcile command and the CLI)
+pub fn reconcile_manifest_file(
+    manifest_path: &str,
+    folder_path: &str,
+    workers: Option<usize>,
+    path_case: PathCase,
+) -> Result<ManifestReconciliation, String> {
+    validate_file_path(manifest_path)?;
+    let root_path = validate_directory_path(folder_path)?;
+    
+    let entries = manifest::read_manifest(manifest_path)
+        .map_err(|e| AppError::ManifestError(e.to_string()).to_string_message())?;
     
-    Ok(updated_items)
+    manifest::reconcile_manifest(&entries, &root_path, workers.unwrap_or_else(hashing::default_workers), path_case)
+        .map_err(|e| AppError::ScanError(e.to_string()).to_string_message())
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_dialog::init())
-        .invoke_handler(tauri::generate_handler![count_directory_files, scan_directory, export_inventory, import_inventory, sync_inventory])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(file_name: &str) -> InventoryItem {
+        InventoryItem {
+            document_type: "Statement".to_string(),
+            folder_name: "Bank".to_string(),
+            folder_path: "Bank".to_string(),
+            ..test_item(&format!("/clients/smith/Bank/{}", file_name))
+        }
+    }
+
+    fn metadata(file_name: &str, file_type: &str, size_bytes: u64) -> FileMetadata {
+        FileMetadata {
+            file_name: file_name.to_string(),
+            folder_name: "Bank".to_string(),
+            folder_path: "Bank".to_string(),
+            absolute_path: format!("/clients/smith/Bank/{}.{}", file_name, file_type.to_lowercase()),
+            file_type: file_type.to_string(),
+            size_bytes,
+            size_human: String::new(),
+            created: String::new(),
+            modified: String::new(),
+            created_year: 2024,
+            is_placeholder: false,
+        }
+    }
+
+    #[test]
+    fn flagged_small_files_are_noted() {
+        let options = ScanOptions {
+            small_file_policy: SmallFilePolicy::Flag,
+            small_file_threshold_bytes: 10,
+            ..ScanOptions::default()
+        };
+        assert_eq!(item_from_scan(metadata("empty", "PDF", 0), &options).notes, "Zero-byte file");
+        assert_eq!(item_from_scan(metadata("tiny", "PDF", 4), &options).notes, "Small file (4 bytes)");
+        assert_eq!(item_from_scan(metadata("full", "PDF", 11), &options).notes, "");
+
+        let included = ScanOptions { small_file_policy: SmallFilePolicy::Include, ..options };
+        assert_eq!(item_from_scan(metadata("empty", "PDF", 0), &included).notes, "");
+    }
+
+    #[test]
+    fn flagged_types_are_noted_alongside_small_files() {
+        let options = ScanOptions {
+            small_file_policy: SmallFilePolicy::Flag,
+            type_policies: std::collections::HashMap::from([("db".to_string(), TypePolicy::Flag)]),
+            ..ScanOptions::default()
+        };
+        assert_eq!(item_from_scan(metadata("ledger", "DB", 2048), &options).notes, "Flagged file type (DB)");
+        assert_eq!(
+            item_from_scan(metadata("ledger", "DB", 0), &options).notes,
+            "Flagged file type (DB); Zero-byte file"
+        );
+    }
+
+    #[test]
+    fn redacted_paths_drop_the_source_folder_from_the_header() {
+        let dir = std::env::temp_dir().join(format!("export-items-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let export = |name: &str, redacted_fields: &[&str]| {
+            let output = dir.join(name).to_string_lossy().to_string();
+            let options = ExportOptions {
+                redacted_fields: redacted_fields.iter().map(|field| field.to_string()).collect(),
+                ..ExportOptions::default()
+            };
+            export_items(vec![item("jan.pdf")], "csv", &output, Some("A-1"), Some("/clients/smith"), &options).unwrap();
+            std::fs::read_to_string(output).unwrap()
+        };
+
+        let redacted = export("redacted.csv", &["file_name"]);
+        assert!(!redacted.contains("/clients/smith"));
+        assert!(!redacted.contains("jan.pdf"));
+        assert!(redacted.contains("Redacted fields: file_name"));
+
+        let notes_only = export("notes.csv", &["notes"]);
+        assert!(notes_only.contains("Source Folder: /clients/smith"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
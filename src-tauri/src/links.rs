@@ -0,0 +1,199 @@
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+const LINKS_FILE: &str = "links.json";
+
+/// Relationships that make two files one document family, such as an email
+/// and its attachments or an archive and its entries
+const FAMILY_RELATIONSHIPS: &[&str] = &[
+    "attachment",
+    "attachment of",
+    "attached to",
+    "contained in",
+    "entry of",
+    "parent of",
+    "child of",
+    "family member",
+];
+
+/// An analyst's link from one file to another, e.g. "signed version of"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileLink {
+    pub case_number: Option<String>,
+    /// Absolute path of the file the link is made from
+    pub source: String,
+    /// Absolute path of the file it points to
+    pub target: String,
+    pub relationship: String,
+    #[serde(default)]
+    pub note: String,
+    pub created_at: String,
+}
+
+/// Links made from a file and links pointing to it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileLinks {
+    pub outbound: Vec<FileLink>,
+    pub inbound: Vec<FileLink>,
+}
+
+fn links_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(LINKS_FILE)
+}
+
+fn same_link(link: &FileLink, case_number: Option<&str>, source: &str, target: &str, relationship: &str) -> bool {
+    link.case_number.as_deref() == case_number
+        && link.source == source
+        && link.target == target
+        && link.relationship.eq_ignore_ascii_case(relationship.trim())
+}
+
+/// Every link in a case, oldest first
+pub fn case_links(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<FileLink>, Box<dyn std::error::Error>> {
+    let links: Vec<FileLink> = json_store::read(&links_path(data_dir))?;
+    Ok(links.into_iter().filter(|link| link.case_number.as_deref() == case_number).collect())
+}
+
+/// The case's links from and to one file
+pub fn links_for_file(data_dir: &Path, case_number: Option<&str>, path: &str) -> Result<FileLinks, Box<dyn std::error::Error>> {
+    let (outbound, inbound) = case_links(data_dir, case_number)?
+        .into_iter()
+        .filter(|link| link.source == path || link.target == path)
+        .partition(|link| link.source == path);
+    Ok(FileLinks { outbound, inbound })
+}
+
+/// Adds a link, replacing the note of an existing link with the same files
+/// and relationship
+pub fn add_link(data_dir: &Path, link: FileLink) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&links_path(data_dir), |links: &mut Vec<FileLink>| {
+        let existing = links.iter_mut().find(|existing| {
+            same_link(existing, link.case_number.as_deref(), &link.source, &link.target, &link.relationship)
+        });
+        match existing {
+            Some(existing) => existing.note = link.note,
+            None => links.push(link),
+        }
+    })
+}
+
+/// Removes a link; returns whether it existed
+pub fn remove_link(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    source: &str,
+    target: &str,
+    relationship: &str,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    json_store::update(&links_path(data_dir), |links: &mut Vec<FileLink>| {
+        let before = links.len();
+        links.retain(|link| !same_link(link, case_number, source, target, relationship));
+        links.len() != before
+    })
+}
+
+pub fn is_family_relationship(relationship: &str) -> bool {
+    FAMILY_RELATIONSHIPS
+        .iter()
+        .any(|family| family.eq_ignore_ascii_case(relationship.trim()))
+}
+
+/// Document families made by family links: groups of two or more files
+/// connected through them, each sorted by path
+pub fn families(links: &[FileLink]) -> Vec<Vec<String>> {
+    let mut neighbours: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for link in links.iter().filter(|link| is_family_relationship(&link.relationship)) {
+        neighbours.entry(&link.source).or_default().push(&link.target);
+        neighbours.entry(&link.target).or_default().push(&link.source);
+    }
+    let mut seen: BTreeSet<&str> = BTreeSet::new();
+    let mut families = Vec::new();
+    for start in neighbours.keys() {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut family = BTreeSet::from([*start]);
+        let mut pending = vec![*start];
+        while let Some(path) = pending.pop() {
+            for next in &neighbours[path] {
+                if seen.insert(next) {
+                    family.insert(*next);
+                    pending.push(next);
+                }
+            }
+        }
+        families.push(family.into_iter().map(|path| path.to_string()).collect());
+    }
+    families
+}
+
+/// The other members of a file's family; empty if it has none
+pub fn family_members(links: &[FileLink], path: &str) -> Vec<String> {
+    families(links)
+        .into_iter()
+        .find(|family| family.iter().any(|member| member == path))
+        .map(|family| family.into_iter().filter(|member| member != path).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(source: &str, target: &str, relationship: &str, note: &str) -> FileLink {
+        FileLink {
+            case_number: Some("A-1".to_string()),
+            source: source.to_string(),
+            target: target.to_string(),
+            relationship: relationship.to_string(),
+            note: note.to_string(),
+            created_at: "2026-01-01 09:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn links_are_listed_both_ways_and_replaced_by_relationship() {
+        let dir = std::env::temp_dir().join(format!("links-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        add_link(&dir, link("/c/signed.pdf", "/c/draft.pdf", "signed version of", "")).unwrap();
+        add_link(&dir, link("/c/email.msg", "/c/signed.pdf", "attachment", "")).unwrap();
+        add_link(&dir, link("/c/signed.pdf", "/c/draft.pdf", "Signed Version Of", "wet ink")).unwrap();
+        let mut other_case = link("/c/signed.pdf", "/c/x.pdf", "copy of", "");
+        other_case.case_number = Some("B-2".to_string());
+        add_link(&dir, other_case).unwrap();
+
+        let links = links_for_file(&dir, Some("A-1"), "/c/signed.pdf").unwrap();
+        assert_eq!(links.outbound.len(), 1);
+        assert_eq!(links.outbound[0].note, "wet ink");
+        assert_eq!(links.inbound.len(), 1);
+        assert_eq!(links.inbound[0].source, "/c/email.msg");
+
+        assert!(remove_link(&dir, Some("A-1"), "/c/email.msg", "/c/signed.pdf", "attachment").unwrap());
+        assert!(!remove_link(&dir, Some("A-1"), "/c/email.msg", "/c/signed.pdf", "attachment").unwrap());
+        assert_eq!(case_links(&dir, Some("A-1")).unwrap().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn family_links_group_files_transitively() {
+        let links = [
+            link("/c/email.msg", "/c/invoice.pdf", "attachment", ""),
+            link("/c/archive.zip", "/c/email.msg", "Attachment Of", ""),
+            link("/c/ledger.xlsx", "/c/archive.zip", "contained in", ""),
+            link("/c/signed.pdf", "/c/invoice.pdf", "signed version of", ""),
+            link("/c/a.msg", "/c/b.pdf", "attachment", ""),
+        ];
+        assert_eq!(
+            families(&links),
+            [
+                vec!["/c/a.msg", "/c/b.pdf"],
+                vec!["/c/archive.zip", "/c/email.msg", "/c/invoice.pdf", "/c/ledger.xlsx"],
+            ]
+        );
+        assert_eq!(family_members(&links, "/c/b.pdf"), ["/c/a.msg"]);
+        assert!(family_members(&links, "/c/signed.pdf").is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+use crate::json_store;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const LOCK_FILE: &str = "app_lock.json";
+
+/// App lock configuration, kept in the app data directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LockSettings {
+    /// Argon2 hash of the passphrase in PHC format; the app has no lock
+    /// while this is unset
+    #[serde(default)]
+    pub passphrase_hash: Option<String>,
+    /// Minutes without a command before the app locks itself
+    #[serde(default)]
+    pub idle_timeout_minutes: Option<u64>,
+}
+
+fn lock_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(LOCK_FILE)
+}
+
+pub fn read_settings(data_dir: &Path) -> Result<LockSettings, Box<dyn std::error::Error>> {
+    json_store::read(&lock_path(data_dir))
+}
+
+pub fn save_settings(data_dir: &Path, settings: &LockSettings) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&lock_path(data_dir), |stored: &mut LockSettings| *stored = settings.clone())
+}
+
+/// Hashes a passphrase with Argon2 and a random salt
+pub fn hash_passphrase(passphrase: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the passphrase matches a stored hash. A hash that cannot be
+/// parsed matches nothing.
+pub fn verify_passphrase(hash: &str, passphrase: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(passphrase.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
+
+/// Whether the app is locked, and when a command last ran for the idle
+/// auto-lock. One instance lives for the whole process.
+pub struct LockState {
+    locked: AtomicBool,
+    /// Idle timeout in seconds; 0 when the app never locks itself
+    idle_timeout_secs: AtomicU64,
+    last_activity: Mutex<Option<Instant>>,
+}
+
+impl LockState {
+    pub const fn new() -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            idle_timeout_secs: AtomicU64::new(0),
+            last_activity: Mutex::new(None),
+        }
+    }
+
+    /// Applies stored settings; with a passphrase set the app starts locked
+    pub fn load(&self, settings: &LockSettings) {
+        self.set_idle_timeout(settings.idle_timeout_minutes);
+        self.locked.store(settings.passphrase_hash.is_some(), Ordering::SeqCst);
+    }
+
+    pub fn set_idle_timeout(&self, minutes: Option<u64>) {
+        let secs = minutes.unwrap_or(0).saturating_mul(60);
+        self.idle_timeout_secs.store(secs, Ordering::SeqCst);
+    }
+
+    pub fn lock(&self) {
+        self.locked.store(true, Ordering::SeqCst);
+    }
+
+    pub fn unlock(&self, now: Instant) {
+        *self.last_activity.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(now);
+        self.locked.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    /// Locks the app if it has been idle longer than the timeout; returns
+    /// whether it is locked
+    fn lock_if_idle(&self, last_activity: &Option<Instant>, now: Instant) -> bool {
+        if self.is_locked() {
+            return true;
+        }
+        let timeout = Duration::from_secs(self.idle_timeout_secs.load(Ordering::SeqCst));
+        let idle = last_activity.is_some_and(|last| !timeout.is_zero() && now.duration_since(last) >= timeout);
+        if idle {
+            self.lock();
+        }
+        idle
+    }
+
+    /// Called before each gated command. Locks the app if it has been idle
+    /// longer than the timeout; otherwise records the activity. Returns
+    /// whether the command must be refused.
+    pub fn engaged(&self, now: Instant) -> bool {
+        let mut last_activity = self.last_activity.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if self.lock_if_idle(&last_activity, now) {
+            return true;
+        }
+        *last_activity = Some(now);
+        false
+    }
+
+    /// Like `engaged`, for requests from outside the app such as the local
+    /// API. They are not activity, so an integration polling the API cannot
+    /// keep the app from locking itself.
+    pub fn refuses_outside_request(&self, now: Instant) -> bool {
+        let last_activity = self.last_activity.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.lock_if_idle(&last_activity, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passphrases_are_verified_against_their_hash() {
+        let hash = hash_passphrase("correct horse").unwrap();
+        assert!(hash.starts_with("$argon2"));
+        assert!(verify_passphrase(&hash, "correct horse"));
+        assert!(!verify_passphrase(&hash, "Correct horse"));
+        assert!(!verify_passphrase("not a hash", "correct horse"));
+    }
+
+    #[test]
+    fn idle_time_past_the_timeout_locks_the_app() {
+        let state = LockState::new();
+        state.load(&LockSettings {
+            passphrase_hash: Some("hash".to_string()),
+            idle_timeout_minutes: Some(5),
+        });
+        let start = Instant::now();
+        assert!(state.engaged(start));
+
+        state.unlock(start);
+        assert!(!state.engaged(start + Duration::from_secs(4 * 60)));
+        // Activity restarts the idle clock
+        assert!(!state.engaged(start + Duration::from_secs(8 * 60)));
+        assert!(state.engaged(start + Duration::from_secs(14 * 60)));
+        assert!(state.is_locked());
+
+        state.set_idle_timeout(None);
+        state.unlock(start);
+        assert!(!state.engaged(start + Duration::from_secs(24 * 60 * 60)));
+    }
+
+    #[test]
+    fn outside_requests_do_not_keep_the_app_unlocked() {
+        let state = LockState::new();
+        state.set_idle_timeout(Some(5));
+        let start = Instant::now();
+        state.unlock(start);
+
+        assert!(!state.refuses_outside_request(start + Duration::from_secs(4 * 60)));
+        assert!(state.refuses_outside_request(start + Duration::from_secs(6 * 60)));
+        assert!(state.is_locked());
+    }
+}
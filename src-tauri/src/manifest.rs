@@ -0,0 +1,313 @@
+use crate::hashing::{hash_files_parallel, HashAlgorithm};
+use crate::paths::{path_key, PathCase};
+use crate::scanner::{scan_folder_with_options, FailedFile, ScanOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// One line of an externally produced hash manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size_bytes: Option<u64>,
+    /// Lowercase hex digest; the algorithm is inferred from its length
+    pub hash: String,
+}
+
+/// A file listed in the manifest whose size or hash does not match the copy on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestMismatch {
+    pub path: String,
+    pub expected_hash: String,
+    pub actual_hash: Option<String>,
+    pub expected_size: Option<u64>,
+    pub actual_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestReconciliation {
+    pub manifest_entries: usize,
+    /// Files present with the expected size and hash
+    pub matched: usize,
+    /// Files present but with a different size or hash
+    pub different: Vec<ManifestMismatch>,
+    /// Manifest paths with no file on disk
+    pub missing: Vec<String>,
+    /// Files on disk that the manifest does not list
+    pub extra: Vec<String>,
+    /// Files that could not be read or hashed
+    pub unreadable: Vec<FailedFile>,
+}
+
+fn is_hex_digest(value: &str) -> bool {
+    HashAlgorithm::from_hex_len(value.len()).is_some() && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Normalizes a manifest or scanned path for comparison: no leading `./` or
+/// `/`, and lowercase only when the scanned volume ignores case
+fn normalize_path(path: &str, case_insensitive: bool) -> String {
+    path_key(path, case_insensitive)
+        .trim_start_matches("./")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+/// Manifests are usually written on the acquisition machine, often Windows,
+/// so even their relative paths may use backslashes
+fn normalize_manifest_path(path: &str, case_insensitive: bool) -> String {
+    normalize_path(&path.replace('\\', "/"), case_insensitive)
+}
+
+/// Parses `md5sum` / `sha256sum` output: `<hash>  <path>` or `<hash> *<path>`
+fn parse_sum_lines(contents: &str) -> Option<Vec<ManifestEntry>> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (hash, path) = line.split_once(char::is_whitespace)?;
+            if !is_hex_digest(hash) {
+                return None;
+            }
+            let path = path.trim_start().trim_start_matches('*');
+            Some(ManifestEntry {
+                path: path.to_string(),
+                size_bytes: None,
+                hash: hash.to_lowercase(),
+            })
+        })
+        .collect()
+}
+
+/// Parses a delimited manifest with a header row. Columns are found by name:
+/// anything containing "path" or "name" for the file, "size" or "bytes" for the
+/// size, and "md5", "sha" or "hash" for the digest.
+fn parse_delimited(contents: &str) -> Result<Vec<ManifestEntry>, Box<dyn std::error::Error>> {
+    let first_line = contents.lines().next().unwrap_or("");
+    let delimiter = if first_line.contains('\t') { b'\t' } else { b',' };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_lowercase()).collect();
+    let find = |keys: &[&str]| headers.iter().position(|h| keys.iter().any(|k| h.contains(k)));
+    let path_col = find(&["path", "name"]).ok_or("Manifest has no path column")?;
+    let hash_col = find(&["sha256", "sha-256", "sha1", "sha-1", "md5", "hash"]).ok_or("Manifest has no hash column")?;
+    let size_col = find(&["size", "bytes"]);
+
+    let mut entries = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let path = record.get(path_col).unwrap_or("").trim();
+        let hash = record.get(hash_col).unwrap_or("").trim();
+        if path.is_empty() || hash.is_empty() {
+            continue;
+        }
+        entries.push(ManifestEntry {
+            path: path.to_string(),
+            size_bytes: size_col
+                .and_then(|col| record.get(col))
+                .and_then(|size| size.trim().replace(',', "").parse().ok()),
+            hash: hash.to_lowercase(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Reads a hash manifest in `*sum` format or as CSV/TSV with a header row
+pub fn read_manifest(path: &str) -> Result<Vec<ManifestEntry>, Box<dyn std::error::Error>> {
+    let contents = fs::read_to_string(path)?;
+    match parse_sum_lines(&contents) {
+        Some(entries) if !entries.is_empty() => Ok(entries),
+        _ => parse_delimited(&contents),
+    }
+}
+
+/// Finds the scanned file a manifest path refers to. Manifest paths are often
+/// absolute paths from the acquisition machine, so leading components are
+/// dropped until the remainder matches a path relative to the scanned root.
+fn find_scanned(manifest_path: &str, scanned: &HashMap<String, usize>, case_insensitive: bool) -> Option<usize> {
+    let normalized = normalize_manifest_path(manifest_path, case_insensitive);
+    let mut candidate = normalized.as_str();
+    loop {
+        if let Some(index) = scanned.get(candidate) {
+            return Some(*index);
+        }
+        candidate = candidate.split_once('/')?.1;
+    }
+}
+
+/// Scans `root` and reconciles it against the manifest, hashing every file the
+/// manifest lists with the algorithm implied by its digest length
+pub fn reconcile_manifest(
+    entries: &[ManifestEntry],
+    root: &Path,
+    workers: usize,
+    path_case: PathCase,
+) -> std::io::Result<ManifestReconciliation> {
+    let case_insensitive = path_case.is_insensitive(root);
+    // The manifest may list system files, so nothing is ignored here
+    let options = ScanOptions {
+        use_default_ignores: false,
+        ..ScanOptions::default()
+    };
+    let outcome = scan_folder_with_options(root, &options)?;
+
+    let scanned: HashMap<String, usize> = outcome
+        .files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| {
+            let relative = Path::new(&file.absolute_path)
+                .strip_prefix(root)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|_| file.absolute_path.clone());
+            (normalize_path(&relative, case_insensitive), index)
+        })
+        .collect();
+
+    let mut result = ManifestReconciliation {
+        manifest_entries: entries.len(),
+        matched: 0,
+        different: Vec::new(),
+        missing: Vec::new(),
+        extra: Vec::new(),
+        unreadable: outcome.failed,
+    };
+
+    let mut listed = vec![false; outcome.files.len()];
+    let mut to_hash: HashMap<HashAlgorithm, Vec<(usize, &ManifestEntry)>> = HashMap::new();
+    for entry in entries {
+        let Some(index) = find_scanned(&entry.path, &scanned, case_insensitive) else {
+            result.missing.push(entry.path.clone());
+            continue;
+        };
+        listed[index] = true;
+        let file = &outcome.files[index];
+
+        if entry.size_bytes.is_some_and(|size| size != file.size_bytes) {
+            result.different.push(ManifestMismatch {
+                path: file.absolute_path.clone(),
+                expected_hash: entry.hash.clone(),
+                actual_hash: None,
+                expected_size: entry.size_bytes,
+                actual_size: file.size_bytes,
+            });
+            continue;
+        }
+        match HashAlgorithm::from_hex_len(entry.hash.len()) {
+            Some(algorithm) => to_hash.entry(algorithm).or_default().push((index, entry)),
+            None => result.unreadable.push(FailedFile {
+                path: file.absolute_path.clone(),
+                error: format!("Unrecognized hash in manifest: {}", entry.hash),
+            }),
+        }
+    }
+
+    for (algorithm, pending) in to_hash {
+        let paths: Vec<String> = pending
+            .iter()
+            .map(|(index, _)| outcome.files[*index].absolute_path.clone())
+            .collect();
+        let hashes = hash_files_parallel(&paths, algorithm, workers);
+
+        for ((index, entry), hashed) in pending.into_iter().zip(hashes) {
+            match hashed.hash {
+                Some(hash) if hash == entry.hash => result.matched += 1,
+                Some(hash) => result.different.push(ManifestMismatch {
+                    path: hashed.path,
+                    expected_hash: entry.hash.clone(),
+                    actual_hash: Some(hash),
+                    expected_size: entry.size_bytes,
+                    actual_size: outcome.files[index].size_bytes,
+                }),
+                None => result.unreadable.push(FailedFile {
+                    path: hashed.path,
+                    error: hashed.error.unwrap_or_default(),
+                }),
+            }
+        }
+    }
+
+    result.extra = outcome
+        .files
+        .iter()
+        .zip(&listed)
+        .filter(|(_, listed)| !**listed)
+        .map(|(file, _)| file.absolute_path.clone())
+        .collect();
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    const ABC_MD5: &str = "900150983cd24fb0d6963f7d28e17f72";
+
+    #[test]
+    fn sum_files_and_delimited_manifests_are_read() {
+        let sums = format!("# sha256sum\n{}  Bank/a.txt\n{} *Bank/b c.txt\n\n", ABC_SHA256.to_uppercase(), ABC_MD5);
+        let entries = parse_sum_lines(&sums).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!((entries[0].path.as_str(), entries[0].hash.as_str()), ("Bank/a.txt", ABC_SHA256));
+        assert_eq!(entries[1].path, "Bank/b c.txt");
+        assert!(parse_sum_lines("File Name,MD5\n").is_none());
+
+        let csv = format!("File Path\tSize (bytes)\tMD5 Hash\nBank/a.txt\t1,024\t{}\n\t3\t{}\n", ABC_MD5, ABC_MD5);
+        let entries = parse_delimited(&csv).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].size_bytes, Some(1024));
+        assert!(parse_delimited("File Name,Size\na.txt,3\n").is_err());
+    }
+
+    #[test]
+    fn folders_are_reconciled_against_a_manifest() {
+        let dir = std::env::temp_dir().join(format!("manifest-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("Bank")).unwrap();
+        for (name, content) in [("Bank/a.txt", "abc"), ("Bank/b.txt", "abd"), ("Bank/c.txt", "abc"), ("extra.txt", "x")] {
+            fs::write(dir.join(name), content).unwrap();
+        }
+        let entry = |path: &str, size_bytes: Option<u64>, hash: &str| ManifestEntry {
+            path: path.to_string(),
+            size_bytes,
+            hash: hash.to_string(),
+        };
+        let entries = [
+            // Absolute and relative paths from a Windows acquisition machine
+            entry("C:\\Evidence\\Bank\\a.txt", Some(3), ABC_SHA256),
+            entry("Bank\\b.txt", None, ABC_MD5),
+            entry("./Bank/c.txt", Some(4), ABC_SHA256),
+            entry("Bank/missing.txt", None, ABC_SHA256),
+        ];
+
+        let result = reconcile_manifest(&entries, &dir, 2, PathCase::Sensitive).unwrap();
+        assert_eq!(result.manifest_entries, 4);
+        assert_eq!(result.matched, 1);
+        assert_eq!(result.missing, ["Bank/missing.txt"]);
+        assert_eq!(result.extra, [dir.join("extra.txt").to_string_lossy()]);
+        assert!(result.unreadable.is_empty());
+
+        let mut different: Vec<(&str, Option<&str>, u64)> = result
+            .different
+            .iter()
+            .map(|mismatch| (mismatch.path.as_str(), mismatch.actual_hash.as_deref(), mismatch.actual_size))
+            .collect();
+        different.sort();
+        let (b, c) = (dir.join("Bank/b.txt"), dir.join("Bank/c.txt"));
+        assert_eq!(
+            different,
+            [(b.to_str().unwrap(), Some("4911e516e5aa21d327512e0c8b197616"), 3), (c.to_str().unwrap(), None, 3)]
+        );
+
+        // Only a case-insensitive volume lets differently cased paths match
+        let upper = [entry("BANK/A.TXT", None, ABC_SHA256)];
+        assert_eq!(reconcile_manifest(&upper, &dir, 1, PathCase::Sensitive).unwrap().missing.len(), 1);
+        assert_eq!(reconcile_manifest(&upper, &dir, 1, PathCase::Insensitive).unwrap().matched, 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,4 +1,6 @@
 use crate::scanner::FileMetadata;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct DocumentInfo {
@@ -97,48 +99,216 @@ fn extract_month_year(file_name: &str) -> String {
     "".to_string()
 }
 
-pub fn extract_date_range(file_name: &str) -> String {
-    // Try to find patterns like "Sep 25", "Sep25", "September 25", etc.
+/// Kinds of statement period recognised in file names
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PeriodKind {
+    /// Two explicit dates, e.g. pay stubs "2024-01-01 to 2024-01-15" or "20240101-20240115"
+    DateRange,
+    /// "Q3 2024", "2024Q3", "3Q24"
+    Quarter,
+    /// "Sep 25", "Sep2025" (the original statement pattern)
+    Month,
+    /// "FY2023", "2023 annual", "year end 2023"
+    Annual,
+}
+
+/// Patterns tried by default, most specific first
+pub const DEFAULT_PERIOD_KINDS: &[PeriodKind] = &[
+    PeriodKind::DateRange,
+    PeriodKind::Quarter,
+    PeriodKind::Month,
+    PeriodKind::Annual,
+];
+
+/// A statement period extracted from a file name
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StatementPeriod {
+    pub kind: PeriodKind,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+/// Splits a file name into lowercase alphanumeric tokens, also breaking at
+/// letter/digit boundaries so "2024Q3" becomes ["2024", "q", "3"]
+fn tokenize(file_name: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    for c in file_name.to_lowercase().chars() {
+        if !c.is_alphanumeric() {
+            previous = None;
+            continue;
+        }
+        let same_class = previous.is_some_and(|p| p.is_ascii_digit() == c.is_ascii_digit());
+        match tokens.last_mut() {
+            Some(last) if same_class => last.push(c),
+            _ => tokens.push(c.to_string()),
+        }
+        previous = Some(c);
+    }
+    tokens
+}
+
+/// Parses a 2 or 4 digit year token; two-digit years are taken as 20xx
+fn parse_year(token: &str) -> Option<i32> {
+    if !token.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match token.len() {
+        2 => token.parse::<i32>().ok().map(|y| 2000 + y),
+        4 => token.parse().ok(),
+        _ => None,
+    }
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()
+}
+
+fn month_period(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    Some((NaiveDate::from_ymd_opt(year, month, 1)?, last_day_of_month(year, month)?))
+}
+
+/// Reads a date from tokens at `i`: "20240115" or "2024", "01", "15".
+/// Returns the date and the number of tokens consumed.
+fn date_at(tokens: &[String], i: usize) -> Option<(NaiveDate, usize)> {
+    let token = tokens.get(i)?;
+    if token.len() == 8 && token.chars().all(|c| c.is_ascii_digit()) {
+        let date = NaiveDate::parse_from_str(token, "%Y%m%d").ok()?;
+        return Some((date, 1));
+    }
+    if token.len() == 4 {
+        let (month, day) = (tokens.get(i + 1)?, tokens.get(i + 2)?);
+        let date = NaiveDate::from_ymd_opt(token.parse().ok()?, month.parse().ok()?, day.parse().ok()?)?;
+        return Some((date, 3));
+    }
+    None
+}
+
+fn find_date_range(tokens: &[String]) -> Option<(NaiveDate, NaiveDate)> {
+    let mut i = 0;
+    while i < tokens.len() {
+        if let Some((start, used)) = date_at(tokens, i) {
+            // Allow a connecting word such as "to" or "thru" between the dates
+            let next = i + used;
+            let end = date_at(tokens, next).or_else(|| date_at(tokens, next + 1));
+            if let Some((end, _)) = end.filter(|(end, _)| *end >= start) {
+                return Some((start, end));
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+fn find_quarter(tokens: &[String]) -> Option<(NaiveDate, NaiveDate)> {
+    let quarter_number = |token: &str| match token {
+        "1" | "2" | "3" | "4" => token.parse::<u32>().ok(),
+        _ => None,
+    };
+    for (i, token) in tokens.iter().enumerate() {
+        if token != "q" {
+            continue;
+        }
+        let before = i.checked_sub(1).and_then(|j| tokens.get(j));
+        let after = tokens.get(i + 1);
+        // "q3 2024" / "2024 q3" / "3q24"
+        let found = match (after.and_then(|t| quarter_number(t)), before.and_then(|t| quarter_number(t))) {
+            (Some(quarter), _) => tokens
+                .get(i + 2)
+                .and_then(|t| parse_year(t))
+                .or_else(|| before.filter(|t| t.len() == 4).and_then(|t| parse_year(t)))
+                .map(|year| (quarter, year)),
+            (None, Some(quarter)) => after.and_then(|t| parse_year(t)).map(|year| (quarter, year)),
+            (None, None) => None,
+        };
+        if let Some((quarter, year)) = found {
+            let first_month = (quarter - 1) * 3 + 1;
+            let start = NaiveDate::from_ymd_opt(year, first_month, 1)?;
+            return Some((start, last_day_of_month(year, first_month + 2)?));
+        }
+    }
+    None
+}
+
+/// The original month pattern: a month name followed (after any separators
+/// or words) by a 2 or 4 digit year
+fn find_month(file_name: &str) -> Option<(NaiveDate, NaiveDate)> {
     let months = [
-        ("jan", "Jan", 31), ("feb", "Feb", 28), ("mar", "Mar", 31),
-        ("apr", "Apr", 30), ("may", "May", 31), ("jun", "Jun", 30),
-        ("jul", "Jul", 31), ("aug", "Aug", 31), ("sep", "Sep", 30),
-        ("oct", "Oct", 31), ("nov", "Nov", 30), ("dec", "Dec", 31),
+        "jan", "feb", "mar", "apr", "may", "jun",
+        "jul", "aug", "sep", "oct", "nov", "dec",
     ];
     
     let name_lower = file_name.to_lowercase();
     
-    for (month_lower, month_short, days_in_month) in months.iter() {
+    for (index, month_lower) in months.iter().enumerate() {
         if let Some(pos) = name_lower.find(month_lower) {
-            // Look for year pattern after month (e.g., "25", "2025")
             let after_month = &name_lower[pos + month_lower.len()..];
             let trimmed = after_month.trim_start_matches(|c: char| !c.is_alphanumeric());
             
-            // Try to extract 2 or 4 digit year
-            if let Some(year_start) = trimmed.chars().position(|c| c.is_ascii_digit()) {
-                let year_part = &trimmed[year_start..];
-                let year = year_part
+            if let Some(year_start) = trimmed.find(|c: char| c.is_ascii_digit()) {
+                let year = trimmed[year_start..]
                     .chars()
                     .take_while(|c| c.is_ascii_digit())
                     .collect::<String>();
                 
-                if year.len() == 2 || year.len() == 4 {
-                    // Format year as 2 digits
-                    let year_short = if year.len() == 4 {
-                        &year[2..]
-                    } else {
-                        &year
-                    };
-                    
-                    // Format as date range: "01-Sep-25 to 30-Sep-25"
-                    return format!("01-{}-{} to {}-{}-{}", month_short, year_short, days_in_month, month_short, year_short);
+                if let Some(year) = parse_year(&year) {
+                    return month_period(year, index as u32 + 1);
                 }
             }
         }
     }
     
-    // Fallback: return empty string if no date found
-    "".to_string()
+    None
+}
+
+fn find_annual(tokens: &[String]) -> Option<(NaiveDate, NaiveDate)> {
+    for (i, token) in tokens.iter().enumerate() {
+        let marker_len = match token.as_str() {
+            "fy" | "ye" | "annual" | "yearend" => 1,
+            "year" if tokens.get(i + 1).is_some_and(|t| t == "end") => 2,
+            _ => continue,
+        };
+        let before = i.checked_sub(1).and_then(|j| tokens.get(j));
+        let year = tokens
+            .get(i + marker_len)
+            .and_then(|t| parse_year(t))
+            .or_else(|| before.filter(|t| t.len() == 4).and_then(|t| parse_year(t)));
+        if let Some(year) = year {
+            return Some((NaiveDate::from_ymd_opt(year, 1, 1)?, NaiveDate::from_ymd_opt(year, 12, 31)?));
+        }
+    }
+    None
+}
+
+/// Extracts the first statement period matching one of `kinds`, tried in order
+pub fn extract_period(file_name: &str, kinds: &[PeriodKind]) -> Option<StatementPeriod> {
+    let tokens = tokenize(file_name);
+    kinds.iter().find_map(|kind| {
+        let (start, end) = match kind {
+            PeriodKind::DateRange => find_date_range(&tokens),
+            PeriodKind::Quarter => find_quarter(&tokens),
+            PeriodKind::Month => find_month(file_name),
+            PeriodKind::Annual => find_annual(&tokens),
+        }?;
+        Some(StatementPeriod { kind: *kind, start, end })
+    })
+}
+
+/// Formats a period the way the inventory shows it: "01-Sep-25 to 30-Sep-25"
+pub fn format_date_range(period: &StatementPeriod) -> String {
+    format!(
+        "{} to {}",
+        period.start.format("%d-%b-%y"),
+        period.end.format("%d-%b-%y")
+    )
+}
+
+pub fn extract_date_range(file_name: &str) -> String {
+    extract_period(file_name, DEFAULT_PERIOD_KINDS)
+        .map(|period| format_date_range(&period))
+        .unwrap_or_default()
 }
 
 pub fn process_file_metadata(metadata: &FileMetadata) -> DocumentInfo {
@@ -156,3 +326,60 @@ pub fn process_file_metadata(metadata: &FileMetadata) -> DocumentInfo {
         doc_date_range,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period(file_name: &str, kinds: &[PeriodKind]) -> Option<(PeriodKind, String, String)> {
+        extract_period(file_name, kinds).map(|period| (period.kind, period.start.to_string(), period.end.to_string()))
+    }
+
+    fn expected(kind: PeriodKind, start: &str, end: &str) -> Option<(PeriodKind, String, String)> {
+        Some((kind, start.to_string(), end.to_string()))
+    }
+
+    #[test]
+    fn quarters_are_read_in_either_order() {
+        let q3 = expected(PeriodKind::Quarter, "2024-07-01", "2024-09-30");
+        assert_eq!(period("Statement Q3 2024", DEFAULT_PERIOD_KINDS), q3);
+        assert_eq!(period("Statement_2024Q3", DEFAULT_PERIOD_KINDS), q3);
+        assert_eq!(period("3Q24 statement", DEFAULT_PERIOD_KINDS), q3);
+        assert_eq!(
+            period("Report Q4 23", DEFAULT_PERIOD_KINDS),
+            expected(PeriodKind::Quarter, "2023-10-01", "2023-12-31")
+        );
+        assert_eq!(period("Q5 2024 notes", &[PeriodKind::Quarter]), None);
+    }
+
+    #[test]
+    fn annual_markers_cover_the_calendar_year() {
+        let year = expected(PeriodKind::Annual, "2023-01-01", "2023-12-31");
+        assert_eq!(period("FY2023 Tax Return", DEFAULT_PERIOD_KINDS), year);
+        assert_eq!(period("2023 annual summary", DEFAULT_PERIOD_KINDS), year);
+        assert_eq!(period("Year End 2023", DEFAULT_PERIOD_KINDS), year);
+        assert_eq!(period("ye 23", DEFAULT_PERIOD_KINDS), year);
+        assert_eq!(period("Annual report", DEFAULT_PERIOD_KINDS), None);
+    }
+
+    #[test]
+    fn date_ranges_need_an_end_on_or_after_the_start() {
+        let pay_period = expected(PeriodKind::DateRange, "2024-01-01", "2024-01-15");
+        assert_eq!(period("Paystub 2024-01-01 to 2024-01-15", DEFAULT_PERIOD_KINDS), pay_period);
+        assert_eq!(period("Paystub 20240101-20240115", DEFAULT_PERIOD_KINDS), pay_period);
+        assert_eq!(period("Paystub 20240115-20240101", &[PeriodKind::DateRange]), None);
+        assert_eq!(period("Paystub 2024-02-30 to 2024-03-15", &[PeriodKind::DateRange]), None);
+    }
+
+    #[test]
+    fn kinds_are_tried_in_the_order_given() {
+        let name = "FY2023 statement Sep 23";
+        assert_eq!(period(name, DEFAULT_PERIOD_KINDS), expected(PeriodKind::Month, "2023-09-01", "2023-09-30"));
+        assert_eq!(
+            period(name, &[PeriodKind::Annual, PeriodKind::Month]),
+            expected(PeriodKind::Annual, "2023-01-01", "2023-12-31")
+        );
+        assert_eq!(period(name, &[PeriodKind::Quarter]), None);
+        assert_eq!(extract_date_range("Statement Q3 2024"), "01-Jul-24 to 30-Sep-24");
+    }
+}
@@ -0,0 +1,108 @@
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const METRICS_FILE: &str = "metrics.jsonl";
+
+/// One timed run of an operation, stored locally and never sent anywhere
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub timestamp: String,
+    /// Operation that was timed: "scan", "ingest", "query", "export", ...
+    pub operation: String,
+    pub case_number: Option<String>,
+    pub duration_ms: u64,
+    /// Files or items processed, used for throughput
+    pub items: usize,
+    pub success: bool,
+}
+
+impl MetricSample {
+    pub fn new(operation: &str, case_number: Option<&str>, duration_ms: u64, items: usize, success: bool) -> Self {
+        Self {
+            timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            operation: operation.to_string(),
+            case_number: case_number.map(|s| s.to_string()),
+            duration_ms,
+            items,
+            success,
+        }
+    }
+}
+
+/// Aggregated timings for one operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationMetrics {
+    pub operation: String,
+    pub runs: usize,
+    pub failures: usize,
+    pub total_ms: u64,
+    pub average_ms: u64,
+    pub max_ms: u64,
+    /// Duration of the most recent run
+    pub last_ms: u64,
+    pub last_run: String,
+    /// Items processed per second across successful runs
+    pub items_per_second: f64,
+}
+
+fn metrics_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(METRICS_FILE)
+}
+
+/// Appends a sample to the metrics file in `data_dir`, creating the directory if needed
+pub fn append_sample(data_dir: &Path, sample: &MetricSample) -> std::io::Result<()> {
+    fs::create_dir_all(data_dir)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(metrics_path(data_dir))?;
+    let line = serde_json::to_string(sample)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Summarizes stored samples per operation, optionally limited to one case
+pub fn read_metrics(data_dir: &Path, case_number: Option<&str>) -> std::io::Result<Vec<OperationMetrics>> {
+    let path = metrics_path(data_dir);
+    let samples: Vec<MetricSample> = json_store::read_jsonl::<MetricSample>(&path)?
+        .into_iter()
+        .filter(|sample| case_number.is_none_or(|c| sample.case_number.as_deref() == Some(c)))
+        .collect();
+
+    Ok(summarize(&samples))
+}
+
+/// Groups samples by operation; samples are expected oldest first
+pub fn summarize(samples: &[MetricSample]) -> Vec<OperationMetrics> {
+    let mut by_operation: BTreeMap<&str, Vec<&MetricSample>> = BTreeMap::new();
+    for sample in samples {
+        by_operation.entry(&sample.operation).or_default().push(sample);
+    }
+
+    by_operation
+        .into_iter()
+        .map(|(operation, runs)| {
+            let total_ms: u64 = runs.iter().map(|s| s.duration_ms).sum();
+            let (ok_items, ok_ms) = runs
+                .iter()
+                .filter(|s| s.success)
+                .fold((0usize, 0u64), |(items, ms), s| (items + s.items, ms + s.duration_ms));
+            let last = runs[runs.len() - 1];
+            OperationMetrics {
+                operation: operation.to_string(),
+                runs: runs.len(),
+                failures: runs.iter().filter(|s| !s.success).count(),
+                total_ms,
+                average_ms: total_ms / runs.len() as u64,
+                max_ms: runs.iter().map(|s| s.duration_ms).max().unwrap_or(0),
+                last_ms: last.duration_ms,
+                last_run: last.timestamp.clone(),
+                items_per_second: if ok_ms == 0 { 0.0 } else { ok_items as f64 * 1000.0 / ok_ms as f64 },
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,247 @@
+use crate::{json_store, InventoryItem};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const NOTES_FILE: &str = "notes.json";
+
+/// A discussion note on a file. Unlike the inventory's `notes` column, notes
+/// are kept per case and can be replied to and resolved.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileNote {
+    pub id: u64,
+    pub case_number: Option<String>,
+    /// Absolute path of the file the note is about
+    pub absolute_path: String,
+    /// Note this one replies to; replies belong to the parent's file
+    #[serde(default)]
+    pub parent_id: Option<u64>,
+    #[serde(default)]
+    pub author: String,
+    pub content: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub resolved: bool,
+    /// Absolute paths of the files the note mentions, resolved when it was
+    /// saved
+    #[serde(default)]
+    pub mentions: Vec<String>,
+}
+
+/// A note as listed, with the number of direct replies to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListedNote {
+    #[serde(flatten)]
+    pub note: FileNote,
+    pub reply_count: usize,
+}
+
+/// What a new note is about; replies take their file from the parent
+#[derive(Debug, Clone)]
+pub enum NoteTarget {
+    File(String),
+    Reply(u64),
+}
+
+fn notes_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(NOTES_FILE)
+}
+
+/// Files a note's text refers to: `@file:` followed by a file name or
+/// absolute path, or a word matching a file's Bates stamp. References that
+/// match no item are dropped.
+pub fn resolve_mentions(content: &str, items: &[InventoryItem]) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+    for word in content.split_whitespace() {
+        let word = word.trim_end_matches(['.', ',', ';', ':', ')', '?', '!']);
+        let matched = match word.strip_prefix("@file:") {
+            Some(name) => items
+                .iter()
+                .find(|item| item.absolute_path == name || item.file_name.eq_ignore_ascii_case(name)),
+            None => items
+                .iter()
+                .find(|item| !item.bates_stamp.is_empty() && item.bates_stamp.eq_ignore_ascii_case(word)),
+        };
+        if let Some(item) = matched {
+            if !mentions.contains(&item.absolute_path) {
+                mentions.push(item.absolute_path.clone());
+            }
+        }
+    }
+    mentions
+}
+
+/// The case's notes that pass `keep`, oldest first, with reply counts
+fn listed_notes(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    keep: impl Fn(&FileNote) -> bool,
+) -> Result<Vec<ListedNote>, Box<dyn std::error::Error>> {
+    let notes: Vec<FileNote> = json_store::read(&notes_path(data_dir))?;
+    let case_notes: Vec<FileNote> = notes
+        .into_iter()
+        .filter(|note| note.case_number.as_deref() == case_number)
+        .collect();
+    Ok(case_notes
+        .iter()
+        .filter(|note| keep(note))
+        .map(|note| ListedNote {
+            reply_count: case_notes.iter().filter(|reply| reply.parent_id == Some(note.id)).count(),
+            note: note.clone(),
+        })
+        .collect())
+}
+
+/// Notes in a case, oldest first, optionally only those on one file
+pub fn list_notes(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    absolute_path: Option<&str>,
+) -> Result<Vec<ListedNote>, Box<dyn std::error::Error>> {
+    listed_notes(data_dir, case_number, |note| {
+        absolute_path.is_none_or(|path| note.absolute_path == path)
+    })
+}
+
+/// Notes in a case that mention a file, oldest first
+pub fn notes_mentioning(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    absolute_path: &str,
+) -> Result<Vec<ListedNote>, Box<dyn std::error::Error>> {
+    listed_notes(data_dir, case_number, |note| note.mentions.iter().any(|path| path == absolute_path))
+}
+
+/// Adds a note with its resolved mentions. Returns `None` if the note
+/// replies to one that is not in the case.
+pub fn add_note(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    target: NoteTarget,
+    author: &str,
+    content: &str,
+    mentions: Vec<String>,
+) -> Result<Option<FileNote>, Box<dyn std::error::Error>> {
+    json_store::update(&notes_path(data_dir), |notes: &mut Vec<FileNote>| {
+        let (absolute_path, parent_id) = match target {
+            NoteTarget::File(path) => (path, None),
+            NoteTarget::Reply(parent_id) => {
+                let parent = notes
+                    .iter()
+                    .find(|note| note.id == parent_id && note.case_number.as_deref() == case_number)?;
+                (parent.absolute_path.clone(), Some(parent_id))
+            }
+        };
+        let note = FileNote {
+            id: notes.iter().map(|note| note.id).max().unwrap_or(0) + 1,
+            case_number: case_number.map(|s| s.to_string()),
+            absolute_path,
+            parent_id,
+            author: author.trim().to_string(),
+            content: content.trim().to_string(),
+            created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            resolved: false,
+            mentions,
+        };
+        notes.push(note.clone());
+        Some(note)
+    })
+}
+
+/// Marks a note resolved or open again; returns whether it exists
+pub fn set_resolved(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    id: u64,
+    resolved: bool,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    json_store::update(&notes_path(data_dir), |notes: &mut Vec<FileNote>| {
+        match notes.iter_mut().find(|note| note.id == id && note.case_number.as_deref() == case_number) {
+            Some(note) => {
+                note.resolved = resolved;
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// Removes a note and every reply under it. Returns the removed ids, empty
+/// if the note does not exist.
+pub fn remove_note(data_dir: &Path, case_number: Option<&str>, id: u64) -> Result<Vec<u64>, Box<dyn std::error::Error>> {
+    json_store::update(&notes_path(data_dir), |notes: &mut Vec<FileNote>| {
+        let mut removed = Vec::new();
+        if notes.iter().any(|note| note.id == id && note.case_number.as_deref() == case_number) {
+            removed.push(id);
+        }
+        // Replies are always added after their parent, so one pass in order
+        // collects the whole thread
+        for note in notes.iter() {
+            if note.parent_id.is_some_and(|parent| removed.contains(&parent)) {
+                removed.push(note.id);
+            }
+        }
+        notes.retain(|note| !removed.contains(&note.id));
+        removed
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+
+    #[test]
+    fn replies_are_counted_resolved_and_removed_with_their_thread() {
+        let dir = std::env::temp_dir().join(format!("notes-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let case = Some("A-1");
+        let note = |target, author, content| add_note(&dir, case, target, author, content, Vec::new()).unwrap();
+        let first = note(NoteTarget::File("/c/a.pdf".to_string()), "Ann", "Missing page 3?").unwrap();
+        let reply = note(NoteTarget::Reply(first.id), "Bo", "Yes, requested").unwrap();
+        note(NoteTarget::Reply(reply.id), "Ann", "Thanks").unwrap();
+        note(NoteTarget::File("/c/b.pdf".to_string()), "Bo", "Duplicate").unwrap();
+        let other_case = add_note(&dir, Some("B-2"), NoteTarget::Reply(first.id), "Cy", "Wrong case", Vec::new());
+        assert!(other_case.unwrap().is_none());
+
+        assert_eq!(reply.absolute_path, "/c/a.pdf");
+        let on_a = list_notes(&dir, case, Some("/c/a.pdf")).unwrap();
+        let counts: Vec<usize> = on_a.iter().map(|listed| listed.reply_count).collect();
+        assert_eq!(counts, [1, 1, 0]);
+
+        assert!(set_resolved(&dir, case, first.id, true).unwrap());
+        assert!(!set_resolved(&dir, Some("B-2"), first.id, true).unwrap());
+        assert!(list_notes(&dir, case, None).unwrap()[0].note.resolved);
+
+        assert_eq!(remove_note(&dir, case, first.id).unwrap().len(), 3);
+        assert_eq!(list_notes(&dir, case, None).unwrap().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn item(file_name: &str, bates_stamp: &str) -> InventoryItem {
+        InventoryItem {
+            folder_name: "Bank".to_string(),
+            folder_path: "Bank".to_string(),
+            bates_stamp: bates_stamp.to_string(),
+            ..test_item(&format!("/c/Bank/{}", file_name))
+        }
+    }
+
+    #[test]
+    fn mentions_resolve_file_references_and_bates_stamps() {
+        let items = vec![item("jan.pdf", "ABC000101"), item("feb.pdf", "ABC000102"), item("mar.pdf", "")];
+        let mentions = resolve_mentions(
+            "See @file:FEB.pdf and abc000101, also @file:/c/Bank/mar.pdf. Not @file:apr.pdf or ABC000999. Again ABC000102",
+            &items,
+        );
+        assert_eq!(mentions, ["/c/Bank/feb.pdf", "/c/Bank/jan.pdf", "/c/Bank/mar.pdf"]);
+
+        let dir = std::env::temp_dir().join(format!("note-mentions-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let target = NoteTarget::File("/c/Bank/jan.pdf".to_string());
+        add_note(&dir, Some("A-1"), target, "", "Compare", mentions).unwrap().unwrap();
+        assert_eq!(notes_mentioning(&dir, Some("A-1"), "/c/Bank/mar.pdf").unwrap().len(), 1);
+        assert!(notes_mentioning(&dir, Some("A-1"), "/c/Bank/apr.pdf").unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,488 @@
+use crate::export::sanitize_file_name;
+use crate::hashing::copy_verified_new;
+use crate::scanner::FailedFile;
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::fs::{self, File, FileTimes};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// A file renamed on disk, with the name it had before
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenamedFile {
+    pub old_path: String,
+    pub old_name: String,
+    pub new_path: String,
+    pub new_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameReport {
+    /// Every input item; renamed ones carry their new path and file name
+    pub items: Vec<InventoryItem>,
+    pub renamed: Vec<RenamedFile>,
+    pub failed: Vec<FailedFile>,
+    /// True when nothing was renamed on disk
+    pub dry_run: bool,
+}
+
+/// Expands `{field}` placeholders for any inventory field, `{original}` (the
+/// current name without extension) and `{n}` (1-based position, zero-padded).
+/// Returns the offending placeholder if one is unknown.
+fn expand_placeholders(pattern: &str, item: &InventoryItem, position: usize) -> Result<String, String> {
+    let mut expanded = String::new();
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| rest[start..].to_string())? + start;
+        let value = match &rest[start + 1..end] {
+            "original" => Path::new(&item.file_name)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            "n" => format!("{:04}", position),
+            field => item.field_value(field).ok_or_else(|| rest[start..=end].to_string())?,
+        };
+        expanded.push_str(value.trim());
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Drops separators left dangling by empty fields, e.g. "{doc_year}_{bates_stamp}"
+fn clean_segment(segment: &str) -> String {
+    sanitize_file_name(segment.trim_matches(|c: char| c == '_' || c == '-' || c.is_whitespace()))
+}
+
+/// Expands a rename pattern (see `expand_placeholders`) into a new file name,
+/// keeping the file's extension
+pub fn expand_rename_pattern(pattern: &str, item: &InventoryItem, position: usize) -> Result<String, String> {
+    let current = Path::new(&item.file_name);
+    let mut name = clean_segment(&expand_placeholders(pattern, item, position)?);
+    if name.is_empty() {
+        name = current.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    }
+    if let Some(extension) = current.extension() {
+        name.push('.');
+        name.push_str(&extension.to_string_lossy());
+    }
+    Ok(name)
+}
+
+/// Picks `directory/name`, appending `_2`, `_3`, ... while the name belongs to
+/// another file or was already claimed earlier in the batch
+fn free_target(directory: &Path, name: &str, source: &Path, claimed: &HashSet<PathBuf>) -> PathBuf {
+    let taken = |path: &Path| claimed.contains(path) || (path != source && path.exists());
+    let path = directory.join(name);
+    if !taken(&path) {
+        return path;
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (2u32..)
+        .map(|n| directory.join(format!("{}_{}{}", stem, n, extension)))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or(path)
+}
+
+/// Renames each item's file in place according to `pattern`. Content is never
+/// touched, so hashes stay valid, and notes are left alone; the old name is
+/// kept in the report's `RenamedFile` entries.
+/// With `dry_run`, reports the planned names without renaming anything.
+/// Returns the offending placeholder if the pattern is invalid.
+pub fn rename_items(mut items: Vec<InventoryItem>, pattern: &str, dry_run: bool) -> Result<RenameReport, String> {
+    let mut renamed = Vec::new();
+    let mut failed = Vec::new();
+    let mut claimed = HashSet::new();
+
+    for (index, item) in items.iter_mut().enumerate() {
+        let new_name = expand_rename_pattern(pattern, item, index + 1)?;
+        let source = PathBuf::from(&item.absolute_path);
+        let Some(directory) = source.parent() else {
+            failed.push(FailedFile {
+                path: item.absolute_path.clone(),
+                error: "File has no parent folder".to_string(),
+            });
+            continue;
+        };
+        if !source.is_file() {
+            failed.push(FailedFile {
+                path: item.absolute_path.clone(),
+                error: "File not found".to_string(),
+            });
+            continue;
+        }
+
+        let target = free_target(directory, &new_name, &source, &claimed);
+        if target == source {
+            claimed.insert(target);
+            continue;
+        }
+        if !dry_run {
+            if let Err(e) = move_no_clobber(&source, &target) {
+                failed.push(FailedFile {
+                    path: item.absolute_path.clone(),
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        }
+
+        let file = RenamedFile {
+            old_path: item.absolute_path.clone(),
+            old_name: item.file_name.clone(),
+            new_path: target.to_string_lossy().to_string(),
+            new_name: target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        };
+        item.absolute_path = file.new_path.clone();
+        item.file_name = file.new_name.clone();
+        claimed.insert(target);
+        renamed.push(file);
+    }
+
+    Ok(RenameReport {
+        items,
+        renamed,
+        failed,
+        dry_run,
+    })
+}
+
+/// Folder used for a level whose pattern fields are all empty
+const UNSORTED_FOLDER: &str = "Unsorted";
+
+/// Whether reorganizing copies files into a clean working copy or moves the originals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferMode {
+    #[default]
+    Copy,
+    Move,
+}
+
+/// Where and how to lay out files, e.g. `{doc_year}/{document_type}` under a new root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorganizationRules {
+    pub target_root: String,
+    /// Folder path pattern; `/` separates folder levels and each level may use
+    /// the same placeholders as rename patterns
+    pub folder_pattern: String,
+    #[serde(default)]
+    pub mode: TransferMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedMove {
+    pub source_path: String,
+    pub target_path: String,
+    /// Folder under the target root, with `/` separators
+    pub target_folder: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorganizationPlan {
+    pub moves: Vec<PlannedMove>,
+    /// Distinct folders the plan creates, sorted
+    pub folders: Vec<String>,
+    /// Items that cannot be placed, e.g. because the source file is missing
+    pub skipped: Vec<FailedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorganizationReport {
+    /// Every input item; placed ones point at their new location
+    pub items: Vec<InventoryItem>,
+    pub moved: Vec<PlannedMove>,
+    pub failed: Vec<FailedFile>,
+}
+
+/// Expands the folder pattern for one item into folder levels. Levels are split
+/// before expansion so field values containing slashes stay in one folder, and
+/// a level whose fields are all empty becomes "Unsorted" to keep the depth even.
+fn folder_levels(pattern: &str, item: &InventoryItem, position: usize) -> Result<Vec<String>, String> {
+    let mut levels = Vec::new();
+    for level in pattern.split(['/', '\\']).filter(|level| !level.trim().is_empty()) {
+        let level = clean_segment(&expand_placeholders(level, item, position)?);
+        if level.is_empty() || level == "." || level == ".." {
+            levels.push(UNSORTED_FOLDER.to_string());
+        } else {
+            levels.push(level);
+        }
+    }
+    if levels.is_empty() {
+        levels.push(UNSORTED_FOLDER.to_string());
+    }
+    Ok(levels)
+}
+
+/// Proposes a target path for every item without touching the disk.
+/// Returns the offending placeholder if the pattern is invalid.
+pub fn plan_reorganization(items: &[InventoryItem], rules: &ReorganizationRules) -> Result<ReorganizationPlan, String> {
+    let root = Path::new(&rules.target_root);
+    let mut moves = Vec::new();
+    let mut skipped = Vec::new();
+    let mut folders = BTreeSet::new();
+    let mut claimed = HashSet::new();
+
+    for (index, item) in items.iter().enumerate() {
+        let levels = folder_levels(&rules.folder_pattern, item, index + 1)?;
+        let source = PathBuf::from(&item.absolute_path);
+        if !source.is_file() {
+            skipped.push(FailedFile {
+                path: item.absolute_path.clone(),
+                error: "File not found".to_string(),
+            });
+            continue;
+        }
+
+        let directory = levels.iter().fold(root.to_path_buf(), |path, level| path.join(level));
+        let target = free_target(&directory, &item.file_name, &source, &claimed);
+        let target_folder = levels.join("/");
+        folders.insert(target_folder.clone());
+        claimed.insert(target.clone());
+        moves.push(PlannedMove {
+            source_path: item.absolute_path.clone(),
+            target_path: target.to_string_lossy().to_string(),
+            target_folder,
+        });
+    }
+
+    Ok(ReorganizationPlan {
+        moves,
+        folders: folders.into_iter().collect(),
+        skipped,
+    })
+}
+
+/// Copies a file to a new `target`, checking the copy against the source's
+/// hash, and carries over its modified and accessed times, which a copy
+/// leaves at the time of the copy. Fails rather than replace an existing file.
+fn copy_with_times(source: &Path, target: &Path) -> io::Result<()> {
+    let metadata = fs::metadata(source)?;
+    copy_verified_new(source, target)?;
+    let mut times = FileTimes::new();
+    if let Ok(modified) = metadata.modified() {
+        times = times.set_modified(modified);
+    }
+    if let Ok(accessed) = metadata.accessed() {
+        times = times.set_accessed(accessed);
+    }
+    File::options().write(true).open(target)?.set_times(times)
+}
+
+/// Moves a file to `target` without ever replacing a file already there,
+/// even one that appeared after the name was chosen. The target is linked
+/// to the source, which fails if the name is taken, and the source is
+/// unlinked afterwards. Where links cannot be made (across volumes, or on
+/// a volume without them) the file is copied to a newly created target
+/// instead. If the source cannot be removed the target is removed again,
+/// so the file is never left under both names.
+fn move_no_clobber(source: &Path, target: &Path) -> io::Result<()> {
+    match fs::hard_link(source, target) {
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => return Err(e),
+        Err(_) => copy_with_times(source, target)?,
+        Ok(()) => {}
+    }
+    if let Err(e) = fs::remove_file(source) {
+        let _ = fs::remove_file(target);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Copies or moves a file to its planned target. Neither replaces a file
+/// that has appeared at the target since the plan was made.
+fn transfer(source: &Path, target: &Path, mode: TransferMode) -> io::Result<()> {
+    // The file is already where the plan puts it
+    if source == target {
+        return Ok(());
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match mode {
+        TransferMode::Copy => copy_with_times(source, target),
+        TransferMode::Move => move_no_clobber(source, target),
+    }
+}
+
+/// Plans and carries out a reorganization, returning the items re-pointed at
+/// their new locations. Content is copied byte for byte, so hashes stay valid.
+pub fn apply_reorganization(
+    items: Vec<InventoryItem>,
+    rules: &ReorganizationRules,
+) -> Result<ReorganizationReport, String> {
+    let plan = plan_reorganization(&items, rules)?;
+    Ok(carry_out(items, plan, rules.mode))
+}
+
+/// Transfers the planned files. A file that cannot be transferred is left
+/// where it was, and its item keeps pointing at it.
+fn carry_out(mut items: Vec<InventoryItem>, plan: ReorganizationPlan, mode: TransferMode) -> ReorganizationReport {
+    let mut failed = plan.skipped;
+    let mut moved = Vec::new();
+
+    for planned in plan.moves {
+        if let Err(e) = transfer(Path::new(&planned.source_path), Path::new(&planned.target_path), mode) {
+            failed.push(FailedFile {
+                path: planned.source_path.clone(),
+                error: e.to_string(),
+            });
+            continue;
+        }
+        if let Some(item) = items.iter_mut().find(|item| item.absolute_path == planned.source_path) {
+            let target = Path::new(&planned.target_path);
+            item.absolute_path = planned.target_path.clone();
+            item.file_name = target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            item.folder_name = target
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            item.folder_path = planned.target_folder.clone();
+        }
+        moved.push(planned);
+    }
+
+    ReorganizationReport { items, moved, failed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn item(path: &Path, document_type: &str, bates_stamp: &str) -> InventoryItem {
+        fs::write(path, path.to_string_lossy().as_bytes()).unwrap();
+        InventoryItem {
+            document_type: document_type.to_string(),
+            bates_stamp: bates_stamp.to_string(),
+            ..test_item(&path.to_string_lossy())
+        }
+    }
+
+    fn read(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn renames_step_around_existing_and_claimed_names() {
+        let dir = temp_dir("rename-collisions");
+        fs::write(dir.join("2024_Statement.pdf"), "someone else's").unwrap();
+        let items = vec![
+            item(&dir.join("scan1.pdf"), "Statement", ""),
+            item(&dir.join("scan2.pdf"), "Statement", ""),
+            item(&dir.join("2024_Letter.pdf"), "Letter", ""),
+        ];
+
+        let planned = rename_items(items.clone(), "{doc_year}_{document_type}", true).unwrap();
+        assert!(dir.join("scan1.pdf").exists());
+        let report = rename_items(items, "{doc_year}_{document_type}", false).unwrap();
+        assert_eq!(
+            planned.renamed.iter().map(|file| &file.new_name).collect::<Vec<_>>(),
+            report.renamed.iter().map(|file| &file.new_name).collect::<Vec<_>>()
+        );
+
+        let names: Vec<&str> = report.items.iter().map(|item| item.file_name.as_str()).collect();
+        assert_eq!(names, ["2024_Statement_2.pdf", "2024_Statement_3.pdf", "2024_Letter.pdf"]);
+        assert_eq!(report.renamed.len(), 2);
+        assert_eq!(read(&dir.join("2024_Statement.pdf")), "someone else's");
+        assert_eq!(read(&dir.join("2024_Statement_3.pdf")), dir.join("scan2.pdf").to_string_lossy());
+        assert!(!dir.join("scan1.pdf").exists() && !dir.join("scan2.pdf").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn moves_never_replace_a_file_that_appeared_at_the_target() {
+        let dir = temp_dir("move-no-clobber");
+        let source = dir.join("scan.pdf");
+        fs::write(&source, "scan").unwrap();
+        // Created after the target name was chosen
+        fs::write(dir.join("Statement.pdf"), "newer").unwrap();
+
+        let e = move_no_clobber(&source, &dir.join("Statement.pdf")).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::AlreadyExists);
+        assert_eq!((read(&source), read(&dir.join("Statement.pdf"))), ("scan".to_string(), "newer".to_string()));
+        // The exclusive copy used where links cannot be made refuses as well
+        assert_eq!(copy_with_times(&source, &dir.join("Statement.pdf")).unwrap_err().kind(), ErrorKind::AlreadyExists);
+        assert_eq!(read(&dir.join("Statement.pdf")), "newer");
+
+        move_no_clobber(&source, &dir.join("Statement_2.pdf")).unwrap();
+        assert!(!source.exists());
+        assert_eq!(read(&dir.join("Statement_2.pdf")), "scan");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reorganizing_skips_targets_taken_after_planning() {
+        let dir = temp_dir("reorganize-collisions");
+        fs::create_dir_all(dir.join("in")).unwrap();
+        for mode in [TransferMode::Copy, TransferMode::Move] {
+            let out = dir.join(format!("{:?}", mode));
+            let items = vec![
+                item(&dir.join("in/a.pdf"), "Statement", ""),
+                item(&dir.join("in/b.pdf"), "Letter", ""),
+                item(&dir.join("in/missing.pdf"), "Letter", ""),
+            ];
+            fs::remove_file(dir.join("in/missing.pdf")).unwrap();
+            let rules = ReorganizationRules {
+                target_root: out.to_string_lossy().to_string(),
+                folder_pattern: "{doc_year}/{document_type}".to_string(),
+                mode,
+            };
+
+            let plan = plan_reorganization(&items, &rules).unwrap();
+            assert_eq!(plan.folders, ["2024/Letter", "2024/Statement"]);
+            assert_eq!(plan.skipped.len(), 1);
+            // Another process writes to one planned target before it is applied
+            fs::create_dir_all(out.join("2024/Letter")).unwrap();
+            fs::write(out.join("2024/Letter/b.pdf"), "theirs").unwrap();
+
+            let report = carry_out(items, plan, mode);
+            assert_eq!(report.moved.len(), 1);
+            assert_eq!(report.failed.len(), 2);
+            assert_eq!(read(&out.join("2024/Letter/b.pdf")), "theirs");
+            assert_eq!(report.items[1].absolute_path, dir.join("in/b.pdf").to_string_lossy());
+            assert!(dir.join("in/b.pdf").exists());
+
+            let placed = &report.items[0];
+            assert_eq!((placed.folder_name.as_str(), placed.folder_path.as_str()), ("Statement", "2024/Statement"));
+            assert!(Path::new(&placed.absolute_path).exists());
+            assert_eq!(dir.join("in/a.pdf").exists(), mode == TransferMode::Copy);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_move_whose_source_stays_put_is_undone() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = temp_dir("move-rollback");
+        fs::create_dir_all(dir.join("locked")).unwrap();
+        fs::create_dir_all(dir.join("out")).unwrap();
+        let source = dir.join("locked/scan.pdf");
+        fs::write(&source, "scan").unwrap();
+        fs::set_permissions(dir.join("locked"), fs::Permissions::from_mode(0o555)).unwrap();
+
+        // Superusers can remove files from read-only folders regardless
+        let enforced = fs::write(dir.join("locked/probe"), "").is_err();
+        let result = move_no_clobber(&source, &dir.join("out/scan.pdf"));
+        if enforced {
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::PermissionDenied);
+            assert_eq!(read(&source), "scan");
+            assert!(!dir.join("out/scan.pdf").exists());
+        }
+        fs::set_permissions(dir.join("locked"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
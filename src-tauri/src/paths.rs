@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, Metadata};
+use std::path::Path;
+
+/// How paths are compared when matching files across scans, manifests and
+/// saved policies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathCase {
+    /// Probe the volume the files live on
+    #[default]
+    Auto,
+    /// Paths differing only in case are different files (Linux, case-sensitive APFS)
+    Sensitive,
+    /// Paths differing only in case are the same file (Windows, default APFS)
+    Insensitive,
+}
+
+impl PathCase {
+    /// Whether paths under `probe` should be compared case-insensitively
+    pub fn is_insensitive(self, probe: &Path) -> bool {
+        match self {
+            Self::Auto => is_case_insensitive_volume(probe),
+            Self::Sensitive => false,
+            Self::Insensitive => true,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn same_file(a: &Metadata, b: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn same_file(a: &Metadata, b: &Metadata) -> bool {
+    a.len() == b.len() && a.modified().ok() == b.modified().ok() && a.is_dir() == b.is_dir()
+}
+
+fn swap_case(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_lowercase() {
+                c.to_uppercase().to_string()
+            } else {
+                c.to_lowercase().to_string()
+            }
+        })
+        .collect()
+}
+
+/// Looks up the nearest existing ancestor of `path` with a cased name under
+/// its case-swapped spelling: if that resolves to the same file, the volume
+/// ignores case. Falls back to the platform default when nothing can be probed.
+pub fn is_case_insensitive_volume(path: &Path) -> bool {
+    for candidate in path.ancestors() {
+        let Some(name) = candidate.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let swapped = swap_case(name);
+        if swapped == name {
+            continue;
+        }
+        let Ok(original) = fs::metadata(candidate) else {
+            continue;
+        };
+        return fs::metadata(candidate.with_file_name(swapped)).is_ok_and(|other| same_file(&original, &other));
+    }
+    cfg!(any(windows, target_os = "macos"))
+}
+
+/// Windows paths keep their backslashes through manifests and saved state
+/// even on other platforms: a drive (`C:\`) or UNC (`\\server`) prefix
+fn is_windows_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with("\\\\") || (bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\')
+}
+
+/// Comparison key for a path: backslashes become forward slashes in Windows
+/// paths (elsewhere a backslash is part of the file name), and case is
+/// folded only for case-insensitive volumes so distinct files never merge
+pub fn path_key(path: &str, case_insensitive: bool) -> String {
+    let path = if cfg!(windows) || is_windows_path(path) {
+        path.replace('\\', "/")
+    } else {
+        path.to_string()
+    };
+    if case_insensitive {
+        path.to_lowercase()
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn backslashes_are_separators_only_in_windows_paths() {
+        assert_eq!(path_key("C:\\Docs\\Jan.pdf", false), "C:/Docs/Jan.pdf");
+        assert_eq!(path_key("c:\\Docs\\Jan.pdf", true), "c:/docs/jan.pdf");
+        assert_eq!(path_key("\\\\server\\share\\Jan.pdf", false), "//server/share/Jan.pdf");
+        assert_eq!(path_key("/c/Bank\\Jan.pdf", false), "/c/Bank\\Jan.pdf");
+        assert_eq!(path_key("/c/Bank\\Jan.pdf", true), "/c/bank\\jan.pdf");
+        assert_ne!(path_key("/c/a\\b.pdf", true), path_key("/c/a/b.pdf", true));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn volumes_are_probed_through_a_case_swapped_name() {
+        let dir = std::env::temp_dir().join(format!("path-case-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("Sensitive")).unwrap();
+        fs::create_dir_all(dir.join("sENSITIVE")).unwrap();
+        // A swapped spelling that resolves to the same directory is what an
+        // insensitive volume looks like
+        fs::create_dir_all(dir.join("Folded")).unwrap();
+        std::os::unix::fs::symlink(dir.join("Folded"), dir.join("fOLDED")).unwrap();
+
+        assert!(!is_case_insensitive_volume(&dir.join("Sensitive").join("missing.pdf")));
+        assert!(is_case_insensitive_volume(&dir.join("Folded").join("missing.pdf")));
+        assert!(PathCase::Auto.is_insensitive(&dir.join("Folded")));
+        assert!(!PathCase::Sensitive.is_insensitive(&dir.join("Folded")));
+        assert!(PathCase::Insensitive.is_insensitive(&dir.join("Sensitive")));
+        // Nothing with a cased name to probe: the platform default
+        assert_eq!(is_case_insensitive_volume(Path::new("/0/1")), cfg!(target_os = "macos"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
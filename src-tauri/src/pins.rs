@@ -0,0 +1,53 @@
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+const PINS_FILE: &str = "pins.json";
+
+/// Pinned files for one case, by absolute path
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CasePins {
+    case_number: Option<String>,
+    paths: BTreeSet<String>,
+}
+
+fn pins_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(PINS_FILE)
+}
+
+/// Pinned paths for a case, sorted
+pub fn pinned_paths(data_dir: &Path, case_number: Option<&str>) -> Result<BTreeSet<String>, Box<dyn std::error::Error>> {
+    Ok(json_store::read::<Vec<CasePins>>(&pins_path(data_dir))?
+        .into_iter()
+        .find(|pins| pins.case_number.as_deref() == case_number)
+        .map(|pins| pins.paths)
+        .unwrap_or_default())
+}
+
+/// Pins the file if it is not pinned, otherwise unpins it. Returns whether the
+/// file is pinned afterwards.
+pub fn toggle_pinned(data_dir: &Path, case_number: Option<&str>, path: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    json_store::update(&pins_path(data_dir), |all: &mut Vec<CasePins>| {
+        let index = match all.iter().position(|pins| pins.case_number.as_deref() == case_number) {
+            Some(index) => index,
+            None => {
+                all.push(CasePins {
+                    case_number: case_number.map(|s| s.to_string()),
+                    paths: BTreeSet::new(),
+                });
+                all.len() - 1
+            }
+        };
+
+        let paths = &mut all[index].paths;
+        let pinned = if paths.remove(path) {
+            false
+        } else {
+            paths.insert(path.to_string());
+            true
+        };
+        all.retain(|pins| !pins.paths.is_empty());
+        pinned
+    })
+}
@@ -0,0 +1,245 @@
+use crate::access;
+use crate::vault;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use tauri::http::{header, Request, Response, StatusCode};
+
+/// URI scheme that streams files to the webview, e.g.
+/// `casefile://localhost/<encoded path>?case=<encoded case number>`
+pub const PREVIEW_SCHEME: &str = "casefile";
+
+/// Largest slice returned by one ranged read, so previews of large files are
+/// paged instead of loaded whole
+pub const MAX_READ_CHUNK_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Reads up to `length` bytes (capped at `MAX_READ_CHUNK_BYTES`) from `offset`,
+/// returning the bytes and the file's total size. Reading at or past the end
+/// returns no bytes.
+pub fn read_range(path: &Path, offset: u64, length: u64) -> std::io::Result<(Vec<u8>, u64)> {
+    let mut file = File::open(path)?;
+    let total_size = file.metadata()?.len();
+    let length = length.min(MAX_READ_CHUNK_BYTES).min(total_size.saturating_sub(offset));
+    let mut data = Vec::with_capacity(length as usize);
+    file.seek(SeekFrom::Start(offset))?;
+    file.take(length).read_to_end(&mut data)?;
+    Ok((data, total_size))
+}
+
+/// Decodes `%XX` escapes; the frontend builds URLs with `encodeURIComponent`
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Parses a single `bytes=start-end`, `bytes=start-` or `bytes=-suffix` range
+/// into an offset and length within a file of `total_size` bytes
+fn parse_range(value: &str, total_size: u64) -> Option<(u64, u64)> {
+    let (start, end) = value.trim().strip_prefix("bytes=")?.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (total_size.saturating_sub(suffix), total_size.checked_sub(1)?)
+        }
+        (start, "") => (start.parse().ok()?, total_size.checked_sub(1)?),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(total_size.checked_sub(1)?)),
+    };
+    if start > end {
+        return None;
+    }
+    Some((start, end - start + 1))
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "tif" | "tiff" => "image/tiff",
+        "mp4" | "m4v" => "video/mp4",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "wav" => "audio/wav",
+        "txt" | "log" | "csv" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// The `case` query parameter, if any
+fn case_parameter(request: &Request<Vec<u8>>) -> Option<String> {
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("case="))
+        .map(percent_decode)
+}
+
+pub fn error_response(status: StatusCode, message: String) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(message.into_bytes())
+        .unwrap_or_default()
+}
+
+/// Serves a `casefile://` request. The request path is the percent-encoded
+/// absolute file path and the `case` parameter names the case it belongs to;
+/// files outside that case's sources (see `access`) are refused with 403.
+/// A file that is gone is served from the case's vault when it has a copy
+/// there. No response carries more than `MAX_READ_CHUNK_BYTES`: a larger file
+/// requested without a Range header is answered 206 with its first chunk, and
+/// media elements request the rest as they play.
+pub fn serve_file(request: &Request<Vec<u8>>, data_dir: &Path) -> Response<Vec<u8>> {
+    let encoded = request.uri().path().trim_start_matches('/');
+    let path_string = percent_decode(encoded);
+    let path = Path::new(&path_string);
+    let case_number = case_parameter(request);
+    match access::is_allowed(data_dir, case_number.as_deref(), path) {
+        Ok(true) => {}
+        Ok(false) => {
+            return error_response(StatusCode::FORBIDDEN, format!("File is not part of the case: {}", path_string))
+        }
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+    let source = if path.is_file() {
+        path.to_path_buf()
+    } else {
+        match vault::vault_copy(data_dir, case_number.as_deref(), &path_string) {
+            Ok(Some(copy)) if copy.is_file() => copy,
+            Ok(_) => return error_response(StatusCode::NOT_FOUND, format!("Path is not a file: {}", path_string)),
+            Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        }
+    };
+
+    let total_size = match source.metadata() {
+        Ok(metadata) => metadata.len(),
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    let (offset, length, status) = match range {
+        Some(range) => match parse_range(range, total_size) {
+            Some((offset, length)) => (offset, length.min(MAX_READ_CHUNK_BYTES), StatusCode::PARTIAL_CONTENT),
+            None => {
+                return Response::builder()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(header::CONTENT_RANGE, format!("bytes */{}", total_size))
+                    .body(Vec::new())
+                    .unwrap_or_default()
+            }
+        },
+        None if total_size <= MAX_READ_CHUNK_BYTES => (0, total_size, StatusCode::OK),
+        None => (0, MAX_READ_CHUNK_BYTES, StatusCode::PARTIAL_CONTENT),
+    };
+
+    let data = match read_range(&source, offset, length) {
+        Ok((data, _)) => data,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+
+    let mut response = Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type(path))
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CONTENT_LENGTH, data.len());
+    if status == StatusCode::PARTIAL_CONTENT {
+        let end = offset + (data.len() as u64).max(1) - 1;
+        response = response.header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", offset, end, total_size));
+    }
+    response.body(data).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (Range header, file size, expected start and length)
+    type RangeCase = (&'static str, u64, Option<(u64, u64)>);
+
+    #[test]
+    fn parse_range_table() {
+        let cases: &[RangeCase] = &[
+            ("bytes=0-99", 1000, Some((0, 100))),
+            ("bytes=500-", 1000, Some((500, 500))),
+            ("bytes=-100", 1000, Some((900, 100))),
+            (" bytes=10-10 ", 1000, Some((10, 1))),
+            ("bytes=900-5000", 1000, Some((900, 100))),
+            ("bytes=-5000", 1000, Some((0, 1000))),
+            ("bytes=5-2", 1000, None),
+            ("bytes=1000-", 1000, None),
+            ("bytes=a-10", 1000, None),
+            ("bytes=0-z", 1000, None),
+            ("bytes=--1", 1000, None),
+            ("bytes=-", 1000, None),
+            ("bytes=10", 1000, None),
+            ("0-99", 1000, None),
+            ("items=0-99", 1000, None),
+            ("", 1000, None),
+            ("bytes=0-0", 0, None),
+            ("bytes=-10", 0, None),
+            ("bytes=0-", 0, None),
+            ("bytes=18446744073709551615-", 1000, None),
+            ("bytes=0-18446744073709551616", 1000, None),
+        ];
+        for (value, total_size, expected) in cases {
+            assert_eq!(parse_range(value, *total_size), *expected, "{:?} of {}", value, total_size);
+        }
+    }
+
+    #[test]
+    fn missing_files_are_served_from_the_vault() {
+        let dir = std::env::temp_dir().join(format!("preview-vault-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let source = dir.join("source");
+        std::fs::create_dir_all(&source).unwrap();
+        let file = source.join("scan.pdf");
+        std::fs::write(&file, "%PDF-1.4").unwrap();
+        let case = Some("A-1");
+        access::grant(&dir, case, &[&source.to_string_lossy()]).unwrap();
+        vault::store_file(&dir, case, &file, "2026-03-01 09:00:00").unwrap();
+        let request = |case: &str| {
+            let encoded = file.to_string_lossy().replace('/', "%2F");
+            Request::builder()
+                .uri(format!("casefile://localhost/{}?case={}", encoded, case))
+                .body(Vec::new())
+                .unwrap()
+        };
+
+        std::fs::remove_dir_all(&source).unwrap();
+        let response = serve_file(&request("A-1"), &dir);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+        assert_eq!(response.body(), b"%PDF-1.4");
+        // Another case neither has the file as a source nor in its vault
+        assert_eq!(serve_file(&request("B-2"), &dir).status(), StatusCode::FORBIDDEN);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
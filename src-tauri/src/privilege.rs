@@ -0,0 +1,373 @@
+use crate::diff::document_properties;
+use crate::json_store;
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+const PRIVILEGE_FILE: &str = "privilege.json";
+
+/// Header lines read from an email before giving up on finding the blank
+/// line that ends them
+const MAX_EMAIL_HEADER_LINES: usize = 500;
+
+/// Where a document stands in privilege review
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivilegeStatus {
+    /// Withheld in full
+    Privileged,
+    /// Produced with privileged parts redacted
+    Redacted,
+    /// Produced without redaction; not listed on the log
+    Produced,
+}
+
+impl PrivilegeStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Privileged => "Withheld",
+            Self::Redacted => "Redacted",
+            Self::Produced => "Produced",
+        }
+    }
+}
+
+/// A reviewer's privilege call on one document, with the details the
+/// privilege log needs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PrivilegeDesignation {
+    pub case_number: Option<String>,
+    pub absolute_path: String,
+    pub status: PrivilegeStatus,
+    #[serde(default)]
+    pub date: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub recipients: String,
+    #[serde(default)]
+    pub description: String,
+    /// e.g. "Attorney-Client Privilege" or "Work Product"
+    #[serde(default)]
+    pub basis: String,
+}
+
+fn privilege_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(PRIVILEGE_FILE)
+}
+
+/// A case's designations, in the order they were first made
+pub fn case_designations(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<PrivilegeDesignation>, Box<dyn std::error::Error>> {
+    let all: Vec<PrivilegeDesignation> = json_store::read(&privilege_path(data_dir))?;
+    Ok(all.into_iter().filter(|designation| designation.case_number.as_deref() == case_number).collect())
+}
+
+/// Records a designation, replacing the file's previous one
+pub fn designate(data_dir: &Path, designation: PrivilegeDesignation) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&privilege_path(data_dir), |all: &mut Vec<PrivilegeDesignation>| {
+        let existing = all.iter_mut().find(|existing| {
+            existing.case_number == designation.case_number && existing.absolute_path == designation.absolute_path
+        });
+        match existing {
+            Some(existing) => *existing = designation,
+            None => all.push(designation),
+        }
+    })
+}
+
+pub fn clear_designation(data_dir: &Path, case_number: Option<&str>, absolute_path: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    json_store::update(&privilege_path(data_dir), |all: &mut Vec<PrivilegeDesignation>| {
+        let before = all.len();
+        all.retain(|designation| designation.case_number.as_deref() != case_number || designation.absolute_path != absolute_path);
+        all.len() != before
+    })
+}
+
+/// A family member's privilege status; `None` when it has no designation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FamilyMemberStatus {
+    pub absolute_path: String,
+    pub status: Option<PrivilegeStatus>,
+}
+
+/// Families whose members do not all share one privilege status, e.g. an
+/// email withheld while its attachment is produced. A family nobody has
+/// designated is consistent.
+pub fn family_conflicts(designations: &[PrivilegeDesignation], families: &[Vec<String>]) -> Vec<Vec<FamilyMemberStatus>> {
+    let by_path: HashMap<&str, PrivilegeStatus> = designations
+        .iter()
+        .map(|designation| (designation.absolute_path.as_str(), designation.status))
+        .collect();
+    families
+        .iter()
+        .map(|family| {
+            family
+                .iter()
+                .map(|path| FamilyMemberStatus {
+                    absolute_path: path.clone(),
+                    status: by_path.get(path.as_str()).copied(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|members| members.iter().any(|member| member.status != members[0].status))
+        .collect()
+}
+
+/// The designation a family member takes from `designation`: its own
+/// details are kept if it has them, and the status and basis are shared
+pub fn family_designation(
+    designation: &PrivilegeDesignation,
+    existing: Option<PrivilegeDesignation>,
+    absolute_path: &str,
+) -> PrivilegeDesignation {
+    let mut member = existing.unwrap_or_else(|| PrivilegeDesignation {
+        case_number: designation.case_number.clone(),
+        absolute_path: absolute_path.to_string(),
+        status: designation.status,
+        date: String::new(),
+        author: String::new(),
+        recipients: String::new(),
+        description: String::new(),
+        basis: String::new(),
+    });
+    member.status = designation.status;
+    member.basis = designation.basis.clone();
+    member
+}
+
+/// From, To, Cc, Date and Subject of an .eml file, with folded header lines
+/// joined
+fn email_headers(path: &Path) -> std::io::Result<HashMap<String, String>> {
+    let mut headers: HashMap<String, String> = HashMap::new();
+    let mut last: Option<String> = None;
+    for line in BufReader::new(File::open(path)?).lines().take(MAX_EMAIL_HEADER_LINES) {
+        let line = line?;
+        if line.trim().is_empty() {
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            if let Some(value) = last.as_ref().and_then(|name| headers.get_mut(name)) {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        last = None;
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim().to_lowercase();
+            if ["from", "to", "cc", "date", "subject"].contains(&name.as_str()) {
+                headers.entry(name.clone()).or_insert_with(|| value.trim().to_string());
+                last = Some(name);
+            }
+        }
+    }
+    Ok(headers)
+}
+
+/// Fills the designation's empty date, author, recipients and description
+/// from the document: an email's headers, or a PDF's or Office file's
+/// properties. Fields the reviewer already filled are kept.
+pub fn seed_from_metadata(designation: &mut PrivilegeDesignation) {
+    let path = Path::new(&designation.absolute_path);
+    let is_email = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("eml"));
+    let found: HashMap<String, String> = if is_email {
+        let headers = email_headers(path).unwrap_or_default();
+        let recipients = ["to", "cc"]
+            .iter()
+            .filter_map(|name| headers.get(*name))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("; ");
+        [
+            ("date", headers.get("date").cloned().unwrap_or_default()),
+            ("author", headers.get("from").cloned().unwrap_or_default()),
+            ("recipients", recipients),
+            ("description", headers.get("subject").map(|subject| format!("Email: {}", subject)).unwrap_or_default()),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+    } else {
+        document_properties(path)
+            .into_iter()
+            .map(|(name, value)| match name {
+                "created" => ("date".to_string(), value),
+                "title" => ("description".to_string(), value),
+                _ => (name.to_string(), value),
+            })
+            .collect()
+    };
+
+    for (field, value) in [
+        (&mut designation.date, "date"),
+        (&mut designation.author, "author"),
+        (&mut designation.recipients, "recipients"),
+        (&mut designation.description, "description"),
+    ] {
+        if field.trim().is_empty() {
+            if let Some(seeded) = found.get(value).filter(|seeded| !seeded.trim().is_empty()) {
+                *field = seeded.clone();
+            }
+        }
+    }
+}
+
+/// Writes the privilege log as CSV: one row per withheld or redacted
+/// document, in inventory order, with its Bates number (or file name when it
+/// has none). Produced documents are left off.
+pub fn write_privilege_log(
+    designations: &[PrivilegeDesignation],
+    items: &[InventoryItem],
+    output_path: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let by_path: HashMap<&str, &PrivilegeDesignation> = designations
+        .iter()
+        .filter(|designation| designation.status != PrivilegeStatus::Produced)
+        .map(|designation| (designation.absolute_path.as_str(), designation))
+        .collect();
+    let mut writer = csv::Writer::from_path(output_path)?;
+    writer.write_record([
+        "Entry",
+        "Bates / Document ID",
+        "Date",
+        "Document Type",
+        "Author",
+        "Recipients",
+        "Description",
+        "Privilege Basis",
+        "Treatment",
+    ])?;
+    let mut entries = 0;
+    for item in items {
+        let Some(designation) = by_path.get(item.absolute_path.as_str()) else {
+            continue;
+        };
+        entries += 1;
+        let document_id = if item.bates_stamp.is_empty() { &item.file_name } else { &item.bates_stamp };
+        writer.write_record([
+            entries.to_string().as_str(),
+            document_id,
+            &designation.date,
+            &item.document_type,
+            &designation.author,
+            &designation.recipients,
+            &designation.description,
+            &designation.basis,
+            designation.status.label(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+    use std::fs;
+
+    fn designation(absolute_path: &str, status: PrivilegeStatus) -> PrivilegeDesignation {
+        PrivilegeDesignation {
+            case_number: Some("A-1".to_string()),
+            absolute_path: absolute_path.to_string(),
+            status,
+            date: String::new(),
+            author: String::new(),
+            recipients: String::new(),
+            description: String::new(),
+            basis: "Attorney-Client Privilege".to_string(),
+        }
+    }
+
+    fn item(file_name: &str, bates_stamp: &str) -> InventoryItem {
+        InventoryItem {
+            document_type: "Email".to_string(),
+            file_type: "EML".to_string(),
+            bates_stamp: bates_stamp.to_string(),
+            ..test_item(&format!("/c/{}", file_name))
+        }
+    }
+
+    #[test]
+    fn email_headers_seed_empty_fields_only() {
+        let dir = std::env::temp_dir().join(format!("privilege-seed-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let email = dir.join("advice.eml");
+        fs::write(
+            &email,
+            "From: Pat Counsel <pat@firm.example>\r\nTo: Client <c@co.example>,\r\n Other <o@co.example>\r\n\
+             Cc: GC <gc@co.example>\r\nDate: Mon, 2 Mar 2026 09:00:00 +0000\r\nSubject: Re: settlement\r\n\r\n\
+             To: not a header\r\n",
+        )
+        .unwrap();
+
+        let mut seeded = designation(&email.to_string_lossy(), PrivilegeStatus::Privileged);
+        seeded.description = "Legal advice re settlement".to_string();
+        seed_from_metadata(&mut seeded);
+        assert_eq!(seeded.author, "Pat Counsel <pat@firm.example>");
+        assert_eq!(seeded.recipients, "Client <c@co.example>, Other <o@co.example>; GC <gc@co.example>");
+        assert_eq!(seeded.date, "Mon, 2 Mar 2026 09:00:00 +0000");
+        assert_eq!(seeded.description, "Legal advice re settlement");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_log_lists_withheld_and_redacted_documents() {
+        let dir = std::env::temp_dir().join(format!("privilege-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        designate(&dir, designation("/c/b.eml", PrivilegeStatus::Privileged)).unwrap();
+        designate(&dir, designation("/c/a.eml", PrivilegeStatus::Produced)).unwrap();
+        designate(&dir, designation("/c/a.eml", PrivilegeStatus::Redacted)).unwrap();
+        designate(&dir, designation("/c/c.eml", PrivilegeStatus::Produced)).unwrap();
+        let designations = case_designations(&dir, Some("A-1")).unwrap();
+        assert_eq!(designations.len(), 3);
+        assert!(case_designations(&dir, None).unwrap().is_empty());
+
+        let items = [item("a.eml", "ABC-0001"), item("b.eml", ""), item("c.eml", "ABC-0003")];
+        let output = dir.join("log.csv");
+        assert_eq!(write_privilege_log(&designations, &items, &output.to_string_lossy()).unwrap(), 2);
+        let log = fs::read_to_string(&output).unwrap();
+        let rows: Vec<&str> = log.lines().skip(1).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("1,ABC-0001,") && rows[0].ends_with(",Redacted"));
+        assert!(rows[1].starts_with("2,b.eml,") && rows[1].ends_with(",Withheld"));
+
+        assert!(clear_designation(&dir, Some("A-1"), "/c/a.eml").unwrap());
+        assert!(!clear_designation(&dir, Some("A-1"), "/c/a.eml").unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn families_conflict_when_members_differ() {
+        let designations = [
+            designation("/c/email.eml", PrivilegeStatus::Privileged),
+            designation("/c/invoice.pdf", PrivilegeStatus::Produced),
+            designation("/c/memo.eml", PrivilegeStatus::Redacted),
+            designation("/c/memo.pdf", PrivilegeStatus::Redacted),
+        ];
+        let families = [
+            vec!["/c/email.eml".to_string(), "/c/invoice.pdf".to_string()],
+            vec!["/c/memo.eml".to_string(), "/c/memo.pdf".to_string()],
+            vec!["/c/a.eml".to_string(), "/c/b.pdf".to_string()],
+            vec!["/c/c.eml".to_string(), "/c/memo.pdf".to_string()],
+        ];
+        let conflicts = family_conflicts(&designations, &families);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0][1].status, Some(PrivilegeStatus::Produced));
+        assert_eq!(conflicts[1][0], FamilyMemberStatus { absolute_path: "/c/c.eml".to_string(), status: None });
+
+        let mut existing = designation("/c/invoice.pdf", PrivilegeStatus::Produced);
+        existing.author = "Vendor".to_string();
+        existing.basis = String::new();
+        let member = family_designation(&designations[0], Some(existing), "/c/invoice.pdf");
+        assert_eq!((member.status, member.author.as_str()), (PrivilegeStatus::Privileged, "Vendor"));
+        assert_eq!(member.basis, "Attorney-Client Privilege");
+        assert_eq!(family_designation(&designations[0], None, "/c/new.pdf").absolute_path, "/c/new.pdf");
+    }
+}
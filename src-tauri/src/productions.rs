@@ -0,0 +1,150 @@
+use crate::json_store;
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const PRODUCTIONS_FILE: &str = "productions.json";
+
+/// A production set that was built and delivered
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Production {
+    pub case_number: Option<String>,
+    pub name: String,
+    pub created_at: String,
+    /// How the produced documents were chosen, as described by the reviewer
+    #[serde(default)]
+    pub filter: String,
+    /// Lowest and highest Bates stamp among the produced documents
+    pub bates_first: Option<String>,
+    pub bates_last: Option<String>,
+    pub file_count: usize,
+    pub output_path: String,
+    /// SHA-256 of the delivered archive
+    pub manifest_hash: String,
+    /// Whether every document was read back from the archive and matched
+    /// its source
+    #[serde(default)]
+    pub verified: bool,
+    /// Absolute paths of the produced documents
+    pub files: Vec<String>,
+}
+
+impl Production {
+    /// A production of `items`, with its Bates range taken from their stamps
+    pub fn new(case_number: Option<&str>, name: &str, filter: &str, items: &[&InventoryItem], output_path: &str, manifest_hash: &str) -> Self {
+        let mut stamps: Vec<&str> = items
+            .iter()
+            .map(|item| item.bates_stamp.trim())
+            .filter(|stamp| !stamp.is_empty())
+            .collect();
+        stamps.sort_unstable();
+        Self {
+            case_number: case_number.map(|s| s.to_string()),
+            name: name.trim().to_string(),
+            created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            filter: filter.to_string(),
+            bates_first: stamps.first().map(|stamp| stamp.to_string()),
+            bates_last: stamps.last().map(|stamp| stamp.to_string()),
+            file_count: items.len(),
+            output_path: output_path.to_string(),
+            manifest_hash: manifest_hash.to_string(),
+            verified: false,
+            files: items.iter().map(|item| item.absolute_path.clone()).collect(),
+        }
+    }
+}
+
+/// A production without its file list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProductionSummary {
+    pub name: String,
+    pub created_at: String,
+    pub filter: String,
+    pub bates_first: Option<String>,
+    pub bates_last: Option<String>,
+    pub file_count: usize,
+    pub output_path: String,
+    pub manifest_hash: String,
+    pub verified: bool,
+}
+
+impl From<Production> for ProductionSummary {
+    fn from(production: Production) -> Self {
+        Self {
+            name: production.name,
+            created_at: production.created_at,
+            filter: production.filter,
+            bates_first: production.bates_first,
+            bates_last: production.bates_last,
+            file_count: production.file_count,
+            output_path: production.output_path,
+            manifest_hash: production.manifest_hash,
+            verified: production.verified,
+        }
+    }
+}
+
+fn productions_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(PRODUCTIONS_FILE)
+}
+
+pub fn record_production(data_dir: &Path, production: Production) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&productions_path(data_dir), |all: &mut Vec<Production>| all.push(production))
+}
+
+fn case_productions(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<Production>, Box<dyn std::error::Error>> {
+    let all: Vec<Production> = json_store::read(&productions_path(data_dir))?;
+    Ok(all.into_iter().filter(|production| production.case_number.as_deref() == case_number).collect())
+}
+
+/// A case's productions, oldest first
+pub fn list_productions(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<ProductionSummary>, Box<dyn std::error::Error>> {
+    Ok(case_productions(data_dir, case_number)?.into_iter().map(ProductionSummary::from).collect())
+}
+
+/// The productions a file went out in, oldest first; empty if it has not
+/// been produced
+pub fn file_productions(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    absolute_path: &str,
+) -> Result<Vec<ProductionSummary>, Box<dyn std::error::Error>> {
+    Ok(case_productions(data_dir, case_number)?
+        .into_iter()
+        .filter(|production| production.files.iter().any(|file| file == absolute_path))
+        .map(ProductionSummary::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+    use std::fs;
+
+    fn item(file_name: &str, bates_stamp: &str) -> InventoryItem {
+        InventoryItem { bates_stamp: bates_stamp.to_string(), ..test_item(&format!("/c/{}", file_name)) }
+    }
+
+    #[test]
+    fn productions_answer_whether_a_file_was_produced() {
+        let dir = std::env::temp_dir().join(format!("productions-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let (a, b, c) = (item("a.pdf", "ABC-0007"), item("b.pdf", ""), item("c.pdf", "ABC-0003"));
+
+        let first = Production::new(Some("A-1"), " Vol 1 ", "Bank statements", &[&a, &b, &c], "/out/vol1.zip", "ff00");
+        assert_eq!(first.name, "Vol 1");
+        assert_eq!(first.bates_first.as_deref(), Some("ABC-0003"));
+        assert_eq!(first.bates_last.as_deref(), Some("ABC-0007"));
+        record_production(&dir, first).unwrap();
+        record_production(&dir, Production::new(Some("A-1"), "Vol 2", "", &[&a], "/out/vol2.zip", "ee11")).unwrap();
+        record_production(&dir, Production::new(Some("B-2"), "Vol 1", "", &[&c], "/out/b.zip", "dd22")).unwrap();
+
+        let names = |summaries: Vec<ProductionSummary>| summaries.into_iter().map(|s| s.name).collect::<Vec<_>>();
+        assert_eq!(names(list_productions(&dir, Some("A-1")).unwrap()), ["Vol 1", "Vol 2"]);
+        assert_eq!(names(file_productions(&dir, Some("A-1"), "/c/a.pdf").unwrap()), ["Vol 1", "Vol 2"]);
+        assert_eq!(names(file_productions(&dir, Some("A-1"), "/c/c.pdf").unwrap()), ["Vol 1"]);
+        assert!(file_productions(&dir, Some("A-1"), "/c/d.pdf").unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
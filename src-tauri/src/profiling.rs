@@ -0,0 +1,321 @@
+use calamine::{open_workbook_auto, Data, Reader};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Extensions calamine can open
+const WORKBOOK_EXTENSIONS: &[&str] = &["xlsx", "xlsm", "xlsb", "xls", "ods"];
+
+/// Header cells kept per sheet; wide exports can have hundreds of columns
+const MAX_HEADER_CELLS: usize = 50;
+
+/// Shape and header row of one sheet (a CSV file counts as one sheet)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetProfile {
+    pub name: String,
+    pub rows: usize,
+    pub columns: usize,
+    /// First non-empty row, taken as the header
+    pub headers: Vec<String>,
+}
+
+/// Sheet profiles for one spreadsheet, or the error that prevented reading it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpreadsheetProfile {
+    pub path: String,
+    pub sheets: Vec<SheetProfile>,
+    pub error: Option<String>,
+}
+
+fn extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Whether the file is a workbook or CSV that can be profiled
+pub fn is_spreadsheet(path: &str) -> bool {
+    let ext = extension(path);
+    ext == "csv" || WORKBOOK_EXTENSIONS.contains(&ext.as_str())
+}
+
+fn profile_workbook(path: &str) -> Result<Vec<SheetProfile>, Box<dyn std::error::Error>> {
+    let mut workbook = open_workbook_auto(path)?;
+    let mut sheets = Vec::new();
+    for name in workbook.sheet_names() {
+        let range = workbook.worksheet_range(&name)?;
+        let (rows, columns) = range.get_size();
+        let headers = range
+            .rows()
+            .find(|row| row.iter().any(|cell| !matches!(cell, Data::Empty)))
+            .map(|row| {
+                row.iter()
+                    .take(MAX_HEADER_CELLS)
+                    .map(|cell| cell.to_string().trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        sheets.push(SheetProfile { name, rows, columns, headers });
+    }
+    Ok(sheets)
+}
+
+fn profile_csv(path: &str) -> Result<Vec<SheetProfile>, Box<dyn std::error::Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(BufReader::new(File::open(path)?));
+
+    let mut headers = Vec::new();
+    let mut rows = 0;
+    let mut columns = 0;
+    for record in reader.records() {
+        let record = record?;
+        if headers.is_empty() && record.iter().any(|cell| !cell.trim().is_empty()) {
+            headers = record.iter().take(MAX_HEADER_CELLS).map(|cell| cell.trim().to_string()).collect();
+        }
+        rows += 1;
+        columns = columns.max(record.len());
+    }
+
+    let name = Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(vec![SheetProfile { name, rows, columns, headers }])
+}
+
+/// Reads sheet names, sizes and header rows without loading the file into
+/// the inventory
+pub fn profile_spreadsheet(path: &str) -> SpreadsheetProfile {
+    let result = if extension(path) == "csv" {
+        profile_csv(path)
+    } else {
+        profile_workbook(path)
+    };
+    match result {
+        Ok(sheets) => SpreadsheetProfile {
+            path: path.to_string(),
+            sheets,
+            error: None,
+        },
+        Err(e) => SpreadsheetProfile {
+            path: path.to_string(),
+            sheets: Vec::new(),
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// Case-insensitive match against sheet names and header cells
+pub fn profile_matches(profile: &SpreadsheetProfile, query: &str) -> bool {
+    profile.sheets.iter().any(|sheet| {
+        sheet.name.to_lowercase().contains(query)
+            || sheet.headers.iter().any(|header| header.to_lowercase().contains(query))
+    })
+}
+
+/// Every SQLite database file starts with this header
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Extensions of database containers that are recognized but cannot be read
+/// without a driver (Microsoft Access)
+const UNSUPPORTED_DATABASE_EXTENSIONS: &[&str] = &["mdb", "accdb"];
+
+/// Name, row count and columns of one table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableProfile {
+    pub name: String,
+    /// None when the table could not be counted (e.g. a virtual table whose
+    /// module is unavailable)
+    pub rows: Option<u64>,
+    pub columns: Vec<String>,
+}
+
+/// Tables of one database file, or the error that prevented reading it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseProfile {
+    pub path: String,
+    pub format: String,
+    pub tables: Vec<TableProfile>,
+    pub error: Option<String>,
+}
+
+pub(crate) fn has_sqlite_header(path: &str) -> bool {
+    let mut header = [0u8; 16];
+    File::open(path)
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut header))
+        .is_ok()
+        && header == SQLITE_HEADER
+}
+
+/// Whether the file is a database container worth profiling. SQLite files are
+/// recognized by header since apps use many extensions (.db, .sqlite, none).
+pub fn is_database(path: &str) -> bool {
+    UNSUPPORTED_DATABASE_EXTENSIONS.contains(&extension(path).as_str()) || has_sqlite_header(path)
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Opens the file as immutable so SQLite never writes a journal, WAL or lock
+/// file next to the evidence
+pub(crate) fn open_read_only(path: &str) -> rusqlite::Result<rusqlite::Connection> {
+    use rusqlite::OpenFlags;
+    let mut uri = String::from("file:");
+    for c in path.replace('\\', "/").chars() {
+        match c {
+            '?' | '#' | '%' => uri.push_str(&format!("%{:02X}", c as u32)),
+            _ => uri.push(c),
+        }
+    }
+    uri.push_str("?immutable=1");
+    rusqlite::Connection::open_with_flags(
+        uri,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+}
+
+fn profile_sqlite(path: &str) -> rusqlite::Result<Vec<TableProfile>> {
+    let conn = open_read_only(path)?;
+    let names: Vec<String> = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut tables = Vec::new();
+    for name in names {
+        let quoted = quote_identifier(&name);
+        let columns = conn
+            .prepare(&format!("PRAGMA table_info({})", quoted))
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| row.get::<_, String>(1))?
+                    .collect::<rusqlite::Result<Vec<String>>>()
+            })
+            .unwrap_or_default();
+        let rows = conn
+            .query_row(&format!("SELECT COUNT(*) FROM {}", quoted), [], |row| row.get::<_, i64>(0))
+            .ok()
+            .map(|count| count as u64);
+        tables.push(TableProfile { name, rows, columns });
+    }
+    Ok(tables)
+}
+
+/// Lists tables and row counts of a database file without modifying it
+pub fn profile_database(path: &str) -> DatabaseProfile {
+    let ext = extension(path);
+    if UNSUPPORTED_DATABASE_EXTENSIONS.contains(&ext.as_str()) {
+        return DatabaseProfile {
+            path: path.to_string(),
+            format: "Access".to_string(),
+            tables: Vec::new(),
+            error: Some("Access databases cannot be profiled; open a copy in Access".to_string()),
+        };
+    }
+
+    let (tables, error) = match profile_sqlite(path) {
+        Ok(tables) => (tables, None),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+    DatabaseProfile {
+        path: path.to_string(),
+        format: "SQLite".to_string(),
+        tables,
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn spreadsheets_report_sheets_sizes_and_headers() {
+        let dir = temp_dir("profile-sheets");
+        let csv = dir.join("Ledger.CSV");
+        fs::write(&csv, ",,\n Date ,Payee,Amount\n2024-01-02,Acme,10\n2024-01-03,Acme,12,late\n").unwrap();
+        let xlsx = dir.join("book.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet().set_name("Wires").unwrap();
+        sheet.write_string(1, 0, "Account").unwrap();
+        sheet.write_string(1, 1, "Beneficiary").unwrap();
+        sheet.write_number(2, 0, 1234.0).unwrap();
+        workbook.add_worksheet().set_name("Empty").unwrap();
+        workbook.save(&xlsx).unwrap();
+
+        let (csv, xlsx) = (csv.to_string_lossy().to_string(), xlsx.to_string_lossy().to_string());
+        assert!(is_spreadsheet(&csv) && is_spreadsheet(&xlsx) && !is_spreadsheet("notes.txt"));
+
+        let profile = profile_spreadsheet(&csv);
+        let sheet = &profile.sheets[0];
+        assert_eq!((sheet.name.as_str(), sheet.rows, sheet.columns), ("Ledger", 4, 4));
+        assert_eq!(sheet.headers, ["Date", "Payee", "Amount"]);
+
+        let profile = profile_spreadsheet(&xlsx);
+        assert_eq!(profile.error, None);
+        let sheets: Vec<(&str, Vec<String>)> =
+            profile.sheets.iter().map(|sheet| (sheet.name.as_str(), sheet.headers.clone())).collect();
+        assert_eq!(sheets, [("Wires", vec!["Account".to_string(), "Beneficiary".to_string()]), ("Empty", vec![])]);
+        assert!(profile_matches(&profile, "benef") && profile_matches(&profile, "empty"));
+        assert!(!profile_matches(&profile, "payee"));
+
+        fs::write(dir.join("broken.xlsx"), "not a workbook").unwrap();
+        let broken = profile_spreadsheet(&dir.join("broken.xlsx").to_string_lossy());
+        assert!(broken.sheets.is_empty() && broken.error.is_some());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn databases_are_profiled_without_being_touched() {
+        let dir = temp_dir("profile-database");
+        // Apps often give SQLite files their own extensions, or none
+        let path = dir.join("Messages #1");
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE \"chat \"\"log\"\"\" (id INTEGER, body TEXT);
+             INSERT INTO \"chat \"\"log\"\"\" VALUES (1, 'hi'), (2, 'bye');
+             CREATE TABLE contacts (name TEXT);",
+        )
+        .unwrap();
+        drop(conn);
+        let before: Vec<_> = fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+
+        let path = path.to_string_lossy().to_string();
+        assert!(is_database(&path));
+        let profile = profile_database(&path);
+        assert_eq!((profile.format.as_str(), profile.error.as_deref()), ("SQLite", None));
+        let tables: Vec<(&str, Option<u64>, &[String])> =
+            profile.tables.iter().map(|table| (table.name.as_str(), table.rows, table.columns.as_slice())).collect();
+        assert_eq!(
+            tables,
+            [
+                ("chat \"log\"", Some(2), &["id".to_string(), "body".to_string()][..]),
+                ("contacts", Some(0), &["name".to_string()][..]),
+            ]
+        );
+        let after: Vec<_> = fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(before, after);
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), modified);
+
+        fs::write(dir.join("notes.db"), "plain text").unwrap();
+        assert!(!is_database(&dir.join("notes.db").to_string_lossy()));
+        assert!(is_database("/c/Case.ACCDB"));
+        let access = profile_database("/c/Case.ACCDB");
+        assert_eq!(access.format, "Access");
+        assert!(access.error.is_some());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
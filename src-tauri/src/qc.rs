@@ -0,0 +1,145 @@
+use crate::hashing::{hash_files_parallel, HashAlgorithm};
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Fields checked for blanks when the caller does not name any
+pub const DEFAULT_QC_FIELDS: &[&str] = &["document_type", "document_description", "doc_date_range", "bates_stamp"];
+
+/// Fields that should agree between copies of the same file
+const DUPLICATE_CONSISTENCY_FIELDS: &[&str] = &["document_type", "document_description", "doc_date_range"];
+
+/// An item with one or more key fields left blank
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmptyFields {
+    pub absolute_path: String,
+    pub file_name: String,
+    pub fields: Vec<String>,
+}
+
+/// A Bates stamp assigned to more than one item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateBates {
+    pub bates_stamp: String,
+    pub paths: Vec<String>,
+}
+
+/// Identical files whose coding disagrees on a field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InconsistentDuplicates {
+    pub hash: String,
+    pub field: String,
+    pub paths: Vec<String>,
+    /// Distinct values found, sorted
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QcReport {
+    pub total_items: usize,
+    pub empty_fields: Vec<EmptyFields>,
+    pub duplicate_bates: Vec<DuplicateBates>,
+    /// Only filled when the duplicate check was requested
+    pub inconsistent_duplicates: Vec<InconsistentDuplicates>,
+    /// Items whose source file no longer exists
+    pub missing_files: Vec<String>,
+    /// True when no check found a problem
+    pub passed: bool,
+}
+
+/// Runs the pre-delivery checks. Hashing every file is slow on large
+/// productions, so the duplicate consistency check is opt-in.
+pub fn generate_qc_report(
+    items: &[InventoryItem],
+    key_fields: &[String],
+    check_duplicates: bool,
+    workers: usize,
+) -> QcReport {
+    let empty_fields: Vec<EmptyFields> = items
+        .iter()
+        .filter_map(|item| {
+            let fields: Vec<String> = key_fields
+                .iter()
+                .filter(|field| item.field_value(field).is_some_and(|value| value.trim().is_empty()))
+                .cloned()
+                .collect();
+            (!fields.is_empty()).then(|| EmptyFields {
+                absolute_path: item.absolute_path.clone(),
+                file_name: item.file_name.clone(),
+                fields,
+            })
+        })
+        .collect();
+
+    let mut by_bates: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for item in items.iter().filter(|item| !item.bates_stamp.trim().is_empty()) {
+        by_bates.entry(item.bates_stamp.trim()).or_default().push(item.absolute_path.clone());
+    }
+    let duplicate_bates: Vec<DuplicateBates> = by_bates
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(bates_stamp, paths)| DuplicateBates {
+            bates_stamp: bates_stamp.to_string(),
+            paths,
+        })
+        .collect();
+
+    // Imported inventories have no paths, so only known paths can be missing
+    let (present, missing): (Vec<&InventoryItem>, Vec<&InventoryItem>) = items
+        .iter()
+        .filter(|item| !item.absolute_path.is_empty())
+        .partition(|item| Path::new(&item.absolute_path).is_file());
+    let missing_files: Vec<String> = missing.iter().map(|item| item.absolute_path.clone()).collect();
+
+    let inconsistent_duplicates = if check_duplicates {
+        find_inconsistent_duplicates(&present, workers)
+    } else {
+        Vec::new()
+    };
+
+    let passed = empty_fields.is_empty()
+        && duplicate_bates.is_empty()
+        && inconsistent_duplicates.is_empty()
+        && missing_files.is_empty();
+    QcReport {
+        total_items: items.len(),
+        empty_fields,
+        duplicate_bates,
+        inconsistent_duplicates,
+        missing_files,
+        passed,
+    }
+}
+
+fn find_inconsistent_duplicates(items: &[&InventoryItem], workers: usize) -> Vec<InconsistentDuplicates> {
+    let paths: Vec<String> = items.iter().map(|item| item.absolute_path.clone()).collect();
+    let hashes = hash_files_parallel(&paths, HashAlgorithm::Sha256, workers);
+
+    let mut by_hash: BTreeMap<String, Vec<&InventoryItem>> = BTreeMap::new();
+    for (item, hashed) in items.iter().zip(hashes) {
+        if let Some(hash) = hashed.hash {
+            by_hash.entry(hash).or_default().push(item);
+        }
+    }
+
+    let mut inconsistent = Vec::new();
+    for (hash, group) in by_hash.iter().filter(|(_, group)| group.len() > 1) {
+        for field in DUPLICATE_CONSISTENCY_FIELDS {
+            let values: BTreeSet<String> = group
+                .iter()
+                .filter_map(|item| item.field_value(field))
+                .map(|value| value.trim().to_string())
+                .collect();
+            if values.len() > 1 {
+                inconsistent.push(InconsistentDuplicates {
+                    hash: hash.clone(),
+                    field: field.to_string(),
+                    paths: group.iter().map(|item| item.absolute_path.clone()).collect(),
+                    values: values.into_iter().collect(),
+                });
+            }
+        }
+    }
+    inconsistent
+}
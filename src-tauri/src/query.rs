@@ -0,0 +1,256 @@
+use crate::{InventoryItem, INVENTORY_FIELDS};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Comparison applied by a query condition. Ordering comparisons are numeric
+/// when both sides are numbers and textual otherwise.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionOp {
+    Equals,
+    NotEquals,
+    /// Case-insensitive substring match
+    Contains,
+    GreaterThan,
+    LessThan,
+    IsEmpty,
+    IsNotEmpty,
+}
+
+/// `field op value`; `value` is unused by the emptiness checks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryCondition {
+    pub field: String,
+    pub op: ConditionOp,
+    #[serde(default)]
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateFunction {
+    /// Number of items in the group; needs no field
+    Count,
+    CountDistinct,
+    Min,
+    Max,
+    /// Sum of the field's numeric values; other values are skipped
+    Sum,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryAggregate {
+    pub function: AggregateFunction,
+    #[serde(default)]
+    pub field: String,
+}
+
+/// A structured query over inventory items. With `group_by` or `aggregates`
+/// the result has one row per group; otherwise one row per item with the
+/// `select`ed fields (every field when empty). All conditions must hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaseQuery {
+    #[serde(default)]
+    pub select: Vec<String>,
+    #[serde(default)]
+    pub conditions: Vec<QueryCondition>,
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    #[serde(default)]
+    pub aggregates: Vec<QueryAggregate>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Query output as a table, e.g. for a pivot view
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+fn condition_holds(item: &InventoryItem, condition: &QueryCondition) -> bool {
+    let Some(value) = item.field_value(&condition.field) else {
+        return false;
+    };
+    match condition.op {
+        ConditionOp::Equals => value == condition.value,
+        ConditionOp::NotEquals => value != condition.value,
+        ConditionOp::Contains => value.to_lowercase().contains(&condition.value.to_lowercase()),
+        ConditionOp::GreaterThan => compare_values(&value, &condition.value) == Ordering::Greater,
+        ConditionOp::LessThan => compare_values(&value, &condition.value) == Ordering::Less,
+        ConditionOp::IsEmpty => value.trim().is_empty(),
+        ConditionOp::IsNotEmpty => !value.trim().is_empty(),
+    }
+}
+
+fn aggregate_label(aggregate: &QueryAggregate) -> String {
+    match aggregate.function {
+        AggregateFunction::Count => "count".to_string(),
+        AggregateFunction::CountDistinct => format!("count_distinct({})", aggregate.field),
+        AggregateFunction::Min => format!("min({})", aggregate.field),
+        AggregateFunction::Max => format!("max({})", aggregate.field),
+        AggregateFunction::Sum => format!("sum({})", aggregate.field),
+    }
+}
+
+fn aggregate_value(aggregate: &QueryAggregate, group: &[&InventoryItem]) -> String {
+    let values = || group.iter().filter_map(|item| item.field_value(&aggregate.field));
+    match aggregate.function {
+        AggregateFunction::Count => group.len().to_string(),
+        AggregateFunction::CountDistinct => values().collect::<BTreeSet<_>>().len().to_string(),
+        AggregateFunction::Min => values().min_by(|a, b| compare_values(a, b)).unwrap_or_default(),
+        AggregateFunction::Max => values().max_by(|a, b| compare_values(a, b)).unwrap_or_default(),
+        AggregateFunction::Sum => values()
+            .filter_map(|value| value.trim().parse::<f64>().ok())
+            .sum::<f64>()
+            .to_string(),
+    }
+}
+
+/// Checks every field the query names. Returns the first unknown one.
+fn check_fields(query: &CaseQuery) -> Result<(), String> {
+    let fields = query
+        .select
+        .iter()
+        .chain(query.conditions.iter().map(|condition| &condition.field))
+        .chain(&query.group_by)
+        .chain(
+            query
+                .aggregates
+                .iter()
+                .filter(|aggregate| aggregate.function != AggregateFunction::Count)
+                .map(|aggregate| &aggregate.field),
+        );
+    for field in fields {
+        if !INVENTORY_FIELDS.contains(&field.as_str()) {
+            return Err(field.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Runs a query over the items. Returns the offending name if a field is
+/// unknown.
+pub fn run_query(items: &[InventoryItem], query: &CaseQuery) -> Result<QueryResult, String> {
+    check_fields(query)?;
+    let matching = items
+        .iter()
+        .filter(|item| query.conditions.iter().all(|condition| condition_holds(item, condition)));
+    let limit = query.limit.unwrap_or(usize::MAX);
+
+    if query.group_by.is_empty() && query.aggregates.is_empty() {
+        let columns: Vec<String> = if query.select.is_empty() {
+            INVENTORY_FIELDS.iter().map(|field| field.to_string()).collect()
+        } else {
+            query.select.clone()
+        };
+        let rows = matching
+            .take(limit)
+            .map(|item| columns.iter().map(|field| item.field_value(field).unwrap_or_default()).collect())
+            .collect();
+        return Ok(QueryResult { columns, rows });
+    }
+
+    let mut groups: BTreeMap<Vec<String>, Vec<&InventoryItem>> = BTreeMap::new();
+    for item in matching {
+        let key = query.group_by.iter().map(|field| item.field_value(field).unwrap_or_default()).collect();
+        groups.entry(key).or_default().push(item);
+    }
+    let columns = query
+        .group_by
+        .iter()
+        .cloned()
+        .chain(query.aggregates.iter().map(aggregate_label))
+        .collect();
+    let rows = groups
+        .into_iter()
+        .take(limit)
+        .map(|(mut row, group)| {
+            row.extend(query.aggregates.iter().map(|aggregate| aggregate_value(aggregate, &group)));
+            row
+        })
+        .collect();
+    Ok(QueryResult { columns, rows })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+
+    fn item(document_type: &str, doc_year: i32, file_name: &str) -> InventoryItem {
+        InventoryItem {
+            doc_year,
+            document_type: document_type.to_string(),
+            folder_name: "Bank".to_string(),
+            folder_path: "Bank".to_string(),
+            ..test_item(&format!("/case/Bank/{}", file_name))
+        }
+    }
+
+    fn items() -> Vec<InventoryItem> {
+        vec![
+            item("Statement", 2023, "jan.pdf"),
+            item("Statement", 2024, "feb.pdf"),
+            item("Statement", 2024, "mar.pdf"),
+            item("Letter", 2022, "letter.pdf"),
+        ]
+    }
+
+    #[test]
+    fn groups_and_aggregates_matching_items() {
+        let query = CaseQuery {
+            conditions: vec![QueryCondition {
+                field: "doc_year".to_string(),
+                op: ConditionOp::GreaterThan,
+                value: "2022".to_string(),
+            }],
+            group_by: vec!["document_type".to_string()],
+            aggregates: vec![
+                QueryAggregate { function: AggregateFunction::Count, field: String::new() },
+                QueryAggregate { function: AggregateFunction::CountDistinct, field: "doc_year".to_string() },
+                QueryAggregate { function: AggregateFunction::Min, field: "doc_year".to_string() },
+            ],
+            ..CaseQuery::default()
+        };
+
+        let result = run_query(&items(), &query).unwrap();
+        assert_eq!(result.columns, ["document_type", "count", "count_distinct(doc_year)", "min(doc_year)"]);
+        assert_eq!(result.rows, [["Statement", "3", "2", "2023"]]);
+    }
+
+    #[test]
+    fn selects_fields_of_matching_items_up_to_the_limit() {
+        let query = CaseQuery {
+            select: vec!["file_name".to_string(), "doc_year".to_string()],
+            conditions: vec![QueryCondition {
+                field: "file_name".to_string(),
+                op: ConditionOp::Contains,
+                value: "A".to_string(),
+            }],
+            limit: Some(2),
+            ..CaseQuery::default()
+        };
+
+        let result = run_query(&items(), &query).unwrap();
+        assert_eq!(result.rows, [["jan.pdf", "2023"], ["mar.pdf", "2024"]]);
+    }
+
+    #[test]
+    fn unknown_fields_are_refused() {
+        let query = CaseQuery {
+            group_by: vec!["custodian".to_string()],
+            ..CaseQuery::default()
+        };
+        assert_eq!(run_query(&items(), &query), Err("custodian".to_string()));
+    }
+}
@@ -0,0 +1,138 @@
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const RECENTS_FILE: &str = "recents.json";
+
+/// Most recently opened files kept per case
+const MAX_RECENT_FILES: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecentFile {
+    pub absolute_path: String,
+    pub opened_at: String,
+}
+
+/// Files opened on one day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DayCount {
+    /// `YYYY-MM-DD`, local time
+    pub date: String,
+    pub files_opened: usize,
+}
+
+/// Review activity for a case, for productivity views
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewStats {
+    pub total_opened: usize,
+    /// Days with at least one file opened, oldest first
+    pub days: Vec<DayCount>,
+    pub last_opened: Option<RecentFile>,
+}
+
+/// Recently opened files for one case, newest first, and opens per day
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CaseRecents {
+    case_number: Option<String>,
+    files: Vec<RecentFile>,
+    daily: BTreeMap<String, usize>,
+}
+
+fn recents_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(RECENTS_FILE)
+}
+
+fn case_recents(data_dir: &Path, case_number: Option<&str>) -> Result<CaseRecents, Box<dyn std::error::Error>> {
+    Ok(json_store::read::<Vec<CaseRecents>>(&recents_path(data_dir))?
+        .into_iter()
+        .find(|recents| recents.case_number.as_deref() == case_number)
+        .unwrap_or_default())
+}
+
+/// Moves the file to the front of the case's recents and counts it against
+/// the day of `opened_at` (`YYYY-MM-DD HH:MM:SS`)
+pub fn record_file_opened(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    path: &str,
+    opened_at: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&recents_path(data_dir), |all: &mut Vec<CaseRecents>| {
+        let index = match all.iter().position(|recents| recents.case_number.as_deref() == case_number) {
+            Some(index) => index,
+            None => {
+                all.push(CaseRecents {
+                    case_number: case_number.map(|s| s.to_string()),
+                    ..CaseRecents::default()
+                });
+                all.len() - 1
+            }
+        };
+
+        let recents = &mut all[index];
+        recents.files.retain(|file| file.absolute_path != path);
+        recents.files.insert(
+            0,
+            RecentFile {
+                absolute_path: path.to_string(),
+                opened_at: opened_at.to_string(),
+            },
+        );
+        recents.files.truncate(MAX_RECENT_FILES);
+        let day = opened_at.get(..10).unwrap_or(opened_at).to_string();
+        *recents.daily.entry(day).or_insert(0) += 1;
+    })
+}
+
+/// Recently opened files in a case, newest first
+pub fn recent_files(data_dir: &Path, case_number: Option<&str>, limit: usize) -> Result<Vec<RecentFile>, Box<dyn std::error::Error>> {
+    let mut files = case_recents(data_dir, case_number)?.files;
+    files.truncate(limit);
+    Ok(files)
+}
+
+pub fn review_stats(data_dir: &Path, case_number: Option<&str>) -> Result<ReviewStats, Box<dyn std::error::Error>> {
+    let recents = case_recents(data_dir, case_number)?;
+    Ok(ReviewStats {
+        total_opened: recents.daily.values().sum(),
+        days: recents
+            .daily
+            .into_iter()
+            .map(|(date, files_opened)| DayCount { date, files_opened })
+            .collect(),
+        last_opened: recents.files.into_iter().next(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reopened_files_move_to_the_front_and_count_per_day() {
+        let dir = std::env::temp_dir().join(format!("recents-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let case = Some("A-1");
+        record_file_opened(&dir, case, "/c/a.pdf", "2026-03-01 09:00:00").unwrap();
+        record_file_opened(&dir, case, "/c/b.pdf", "2026-03-01 09:05:00").unwrap();
+        record_file_opened(&dir, case, "/c/a.pdf", "2026-03-02 10:00:00").unwrap();
+        record_file_opened(&dir, Some("B-2"), "/c/x.pdf", "2026-03-02 11:00:00").unwrap();
+
+        let paths: Vec<String> = recent_files(&dir, case, 10)
+            .unwrap()
+            .into_iter()
+            .map(|file| file.absolute_path)
+            .collect();
+        assert_eq!(paths, ["/c/a.pdf", "/c/b.pdf"]);
+        assert_eq!(recent_files(&dir, case, 1).unwrap().len(), 1);
+
+        let stats = review_stats(&dir, case).unwrap();
+        assert_eq!(stats.total_opened, 3);
+        let days: Vec<(&str, usize)> = stats.days.iter().map(|day| (day.date.as_str(), day.files_opened)).collect();
+        assert_eq!(days, [("2026-03-01", 2), ("2026-03-02", 1)]);
+        assert_eq!(stats.last_opened.unwrap().opened_at, "2026-03-02 10:00:00");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+use crate::categories::categorize;
+use crate::mappings::{derive_document_type, extract_date_range, generate_document_description};
+use crate::scanner::{FailedFile, FileMetadata};
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Fields derived from the file that can be recomputed without a rescan, in
+/// the order they are applied (the description depends on the type)
+pub const REEXTRACTABLE_FIELDS: &[&str] = &[
+    "document_type",
+    "document_description",
+    "doc_date_range",
+    "doc_year",
+    "file_category",
+];
+
+/// A field whose value changed when it was re-extracted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub absolute_path: String,
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReextractReport {
+    /// Every input item, with the selected fields recomputed
+    pub items: Vec<InventoryItem>,
+    pub changes: Vec<FieldChange>,
+    /// Files whose metadata could not be read (doc_year only)
+    pub failed: Vec<FailedFile>,
+}
+
+/// Recomputed value of one field, or Err when the file could not be read
+fn extract_field(item: &InventoryItem, field: &str) -> Result<String, String> {
+    let value = match field {
+        "document_type" => derive_document_type(&item.file_name),
+        "document_description" => generate_document_description(&item.file_name, &item.document_type, &item.file_type),
+        "doc_date_range" => extract_date_range(&item.file_name),
+        "doc_year" => {
+            let path = Path::new(&item.absolute_path);
+            let root = path.parent().unwrap_or(path);
+            FileMetadata::from_path(root, path)
+                .map_err(|e| e.to_string())?
+                .created_year
+                .to_string()
+        }
+        "file_category" => categorize(Path::new(&item.absolute_path), &item.file_type).to_string(),
+        _ => return Err(format!("cannot re-extract {}", field)),
+    };
+    Ok(value)
+}
+
+/// Recomputes only `fields` for the items selected by `selected` (by absolute
+/// path; all items when None), leaving every other field untouched
+pub fn reextract_fields(
+    mut items: Vec<InventoryItem>,
+    selected: impl Fn(&InventoryItem) -> bool,
+    fields: &[String],
+) -> ReextractReport {
+    let fields: HashSet<&str> = fields.iter().map(|field| field.as_str()).collect();
+    let mut changes = Vec::new();
+    let mut failed = Vec::new();
+
+    for item in items.iter_mut().filter(|item| selected(item)) {
+        for field in REEXTRACTABLE_FIELDS.iter().filter(|field| fields.contains(*field)) {
+            let new_value = match extract_field(item, field) {
+                Ok(value) => value,
+                Err(error) => {
+                    failed.push(FailedFile {
+                        path: item.absolute_path.clone(),
+                        error,
+                    });
+                    continue;
+                }
+            };
+            let old_value = item.field_value(field).unwrap_or_default();
+            if old_value != new_value && item.set_field_value(field, &new_value) {
+                changes.push(FieldChange {
+                    absolute_path: item.absolute_path.clone(),
+                    field: field.to_string(),
+                    old_value,
+                    new_value,
+                });
+            }
+        }
+    }
+
+    ReextractReport { items, changes, failed }
+}
@@ -0,0 +1,362 @@
+use crate::dates::{compute_date_offsets, DateOffset};
+use crate::export::{field_label, InventoryRow};
+use crate::json_store;
+use crate::links::FileLink;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const REPORT_TEMPLATES_FILE: &str = "report_templates.json";
+
+/// Sections that can be included in a generated report, rendered in the order given
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReportSection {
+    /// Totals, case number and source folder
+    CaseSummary,
+    /// Document counts per document type
+    DocumentTypes,
+    /// Document counts per year, oldest first
+    Chronology,
+    /// Document counts per folder
+    FolderSummary,
+    /// Inventory rows, optionally limited to one document type and/or year
+    InventoryExcerpt {
+        document_type: Option<String>,
+        doc_year: Option<i32>,
+    },
+    /// Deadlines and other dates derived from a base date, e.g. 30 days after service
+    DateOffsets {
+        offsets: Vec<DateOffset>,
+        /// Holidays (YYYY-MM-DD) skipped by business-day offsets
+        #[serde(default)]
+        holidays: Vec<String>,
+    },
+    /// Links analysts made between files, with their relationship and note
+    FileLinks,
+}
+
+/// A report template: a title plus the sections to render
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportTemplate {
+    pub title: String,
+    pub sections: Vec<ReportSection>,
+}
+
+impl Default for ReportTemplate {
+    fn default() -> Self {
+        Self {
+            title: "Document Inventory Report".to_string(),
+            sections: vec![
+                ReportSection::CaseSummary,
+                ReportSection::DocumentTypes,
+                ReportSection::Chronology,
+                ReportSection::FolderSummary,
+            ],
+        }
+    }
+}
+
+/// A report template saved with a case under a name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedReportTemplate {
+    pub name: String,
+    pub case_number: Option<String>,
+    pub template: ReportTemplate,
+}
+
+fn templates_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(REPORT_TEMPLATES_FILE)
+}
+
+fn same_template(saved: &SavedReportTemplate, case_number: Option<&str>, name: &str) -> bool {
+    saved.case_number.as_deref() == case_number && saved.name.eq_ignore_ascii_case(name.trim())
+}
+
+/// A case's saved report templates
+pub fn case_report_templates(
+    data_dir: &Path,
+    case_number: Option<&str>,
+) -> Result<Vec<SavedReportTemplate>, Box<dyn std::error::Error>> {
+    let templates: Vec<SavedReportTemplate> = json_store::read(&templates_path(data_dir))?;
+    Ok(templates.into_iter().filter(|saved| saved.case_number.as_deref() == case_number).collect())
+}
+
+/// One of a case's saved templates by name (case-insensitive)
+pub fn find_report_template(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    name: &str,
+) -> Result<Option<ReportTemplate>, Box<dyn std::error::Error>> {
+    Ok(case_report_templates(data_dir, case_number)?
+        .into_iter()
+        .find(|saved| same_template(saved, case_number, name))
+        .map(|saved| saved.template))
+}
+
+/// Saves a template, replacing any template with the same name in the case
+pub fn save_report_template(data_dir: &Path, saved: SavedReportTemplate) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&templates_path(data_dir), |templates: &mut Vec<SavedReportTemplate>| {
+        templates.retain(|existing| !same_template(existing, saved.case_number.as_deref(), &saved.name));
+        templates.push(saved);
+    })
+}
+
+/// Removes a saved template; returns whether it existed
+pub fn remove_report_template(data_dir: &Path, case_number: Option<&str>, name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    json_store::update(&templates_path(data_dir), |templates: &mut Vec<SavedReportTemplate>| {
+        let before = templates.len();
+        templates.retain(|saved| !same_template(saved, case_number, name));
+        templates.len() != before
+    })
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_count_table(html: &mut String, heading: &str, label: &str, counts: &BTreeMap<String, usize>) {
+    html.push_str(&format!("<h2>{}</h2>\n<table>\n", escape_html(heading)));
+    html.push_str(&format!("<tr><th>{}</th><th>Count</th></tr>\n", escape_html(label)));
+    for (key, count) in counts {
+        html.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", escape_html(key), count));
+    }
+    html.push_str("</table>\n");
+}
+
+fn count_by<F>(rows: &[InventoryRow], key: F) -> BTreeMap<String, usize>
+where
+    F: Fn(&InventoryRow) -> String,
+{
+    let mut counts = BTreeMap::new();
+    for row in rows {
+        *counts.entry(key(row)).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Renders the report as a standalone HTML document. `links` are the case's
+/// file links, listed by a `FileLinks` section.
+pub fn render_html(
+    rows: &[InventoryRow],
+    template: &ReportTemplate,
+    case_number: Option<&str>,
+    folder_path: Option<&str>,
+    links: &[FileLink],
+) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape_html(&template.title)));
+    html.push_str("<style>body{font-family:sans-serif;margin:2em}table{border-collapse:collapse;margin-bottom:1.5em}th,td{border:1px solid #999;padding:4px 8px;text-align:left}th{background:#eee}</style>\n");
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", escape_html(&template.title)));
+
+    for section in &template.sections {
+        match section {
+            ReportSection::CaseSummary => {
+                html.push_str("<h2>Case Summary</h2>\n<ul>\n");
+                if let Some(case_no) = case_number {
+                    html.push_str(&format!("<li>Case No.: {}</li>\n", escape_html(case_no)));
+                }
+                if let Some(folder) = folder_path {
+                    html.push_str(&format!("<li>Source Folder: {}</li>\n", escape_html(folder)));
+                }
+                html.push_str(&format!("<li>Total Documents: {}</li>\n", rows.len()));
+                html.push_str(&format!(
+                    "<li>Generated: {}</li>\n",
+                    chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+                ));
+                html.push_str("</ul>\n");
+            }
+            ReportSection::DocumentTypes => {
+                let counts = count_by(rows, |row| row.document_type.clone());
+                write_count_table(&mut html, "Documents by Type", "Document Type", &counts);
+            }
+            ReportSection::Chronology => {
+                let counts = count_by(rows, |row| row.doc_year.to_string());
+                write_count_table(&mut html, "Documents by Year", "Doc Year", &counts);
+            }
+            ReportSection::FolderSummary => {
+                let counts = count_by(rows, |row| row.folder_path.clone());
+                write_count_table(&mut html, "Documents by Folder", "Folder Path", &counts);
+            }
+            ReportSection::InventoryExcerpt { document_type, doc_year } => {
+                html.push_str("<h2>Inventory</h2>\n<table>\n");
+                html.push_str("<tr><th>Doc Year</th><th>Doc Date Range</th><th>Document Type</th><th>Document Description</th><th>File Name</th><th>Folder Path</th><th>Bates Stamp</th></tr>\n");
+                let excerpt = rows.iter().filter(|row| {
+                    document_type.as_ref().is_none_or(|t| &row.document_type == t)
+                        && doc_year.is_none_or(|y| row.doc_year == y)
+                });
+                for row in excerpt {
+                    html.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                        row.doc_year,
+                        escape_html(&row.doc_date_range),
+                        escape_html(&row.document_type),
+                        escape_html(&row.document_description),
+                        escape_html(&row.file_name),
+                        escape_html(&row.folder_path),
+                        escape_html(&row.bates_stamp),
+                    ));
+                }
+                html.push_str("</table>\n");
+            }
+            ReportSection::DateOffsets { offsets, holidays } => {
+                html.push_str("<h2>Derived Dates</h2>\n");
+                match compute_date_offsets(offsets, holidays) {
+                    Ok(dates) => {
+                        html.push_str("<table>\n<tr><th>Event</th><th>From</th><th>Date</th><th>Day</th><th>Calendar Days</th><th>Business Days</th></tr>\n");
+                        for date in dates {
+                            html.push_str(&format!(
+                                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                                escape_html(&date.label),
+                                date.base_date,
+                                date.date,
+                                date.weekday,
+                                date.calendar_days,
+                                date.business_days,
+                            ));
+                        }
+                        html.push_str("</table>\n");
+                    }
+                    Err(bad) => {
+                        html.push_str(&format!("<p>Invalid date: {}</p>\n", escape_html(&bad)));
+                    }
+                }
+            }
+            ReportSection::FileLinks => {
+                html.push_str("<h2>Related Documents</h2>\n<table>\n");
+                html.push_str("<tr><th>File</th><th>Relationship</th><th>Related File</th><th>Note</th></tr>\n");
+                for link in links {
+                    html.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                        escape_html(&link.source),
+                        escape_html(&link.relationship),
+                        escape_html(&link.target),
+                        escape_html(&link.note),
+                    ));
+                }
+                html.push_str("</table>\n");
+            }
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Renders the given columns of the rows as a print-ready table: landscape
+/// pages, the header row repeated on every page and page numbers in the
+/// footer. Open in a browser and print to PDF. Returns the unknown field name
+/// if a column is not an inventory field.
+pub fn render_print_table(
+    rows: &[InventoryRow],
+    columns: &[String],
+    title: &str,
+) -> Result<String, String> {
+    let labels = columns
+        .iter()
+        .map(|field| field_label(field).ok_or_else(|| field.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape_html(title)));
+    html.push_str("<style>@page{size:landscape;margin:12mm;@bottom-right{content:\"Page \" counter(page) \" of \" counter(pages);font:9pt sans-serif}}body{font-family:sans-serif;font-size:9pt}table{border-collapse:collapse;width:100%}thead{display:table-header-group}tr{page-break-inside:avoid}th,td{border:1px solid #999;padding:2px 4px;text-align:left;vertical-align:top}th{background:#eee}</style>\n");
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n<table>\n<thead><tr>", escape_html(title)));
+    for label in &labels {
+        html.push_str(&format!("<th>{}</th>", escape_html(label)));
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    
+    for row in rows {
+        html.push_str("<tr>");
+        for field in columns {
+            let value = row.field_value(field).unwrap_or_default();
+            html.push_str(&format!("<td>{}</td>", escape_html(&value)));
+        }
+        html.push_str("</tr>\n");
+    }
+    
+    html.push_str(&format!(
+        "</tbody>\n</table>\n<p>{} documents</p>\n</body>\n</html>\n",
+        rows.len()
+    ));
+    Ok(html)
+}
+
+pub fn generate_report(
+    rows: &[InventoryRow],
+    template: &ReportTemplate,
+    case_number: Option<&str>,
+    folder_path: Option<&str>,
+    links: &[FileLink],
+    output_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let html = render_html(rows, template, case_number, folder_path, links);
+    let mut file = File::create(output_path)?;
+    file.write_all(html.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(name: &str, case_number: &str, title: &str) -> SavedReportTemplate {
+        SavedReportTemplate {
+            name: name.to_string(),
+            case_number: Some(case_number.to_string()),
+            template: ReportTemplate {
+                title: title.to_string(),
+                sections: vec![ReportSection::CaseSummary],
+            },
+        }
+    }
+
+    #[test]
+    fn file_links_section_lists_the_case_links() {
+        let template = ReportTemplate {
+            title: "Links".to_string(),
+            sections: vec![ReportSection::FileLinks],
+        };
+        let links = [FileLink {
+            case_number: Some("A-1".to_string()),
+            source: "/c/signed.pdf".to_string(),
+            target: "/c/draft.pdf".to_string(),
+            relationship: "signed version of".to_string(),
+            note: "<wet ink>".to_string(),
+            created_at: "2026-01-01 09:00:00".to_string(),
+        }];
+
+        let html = render_html(&[], &template, Some("A-1"), None, &links);
+        assert!(html.contains("<h2>Related Documents</h2>"));
+        assert!(html.contains("<td>/c/signed.pdf</td><td>signed version of</td><td>/c/draft.pdf</td><td>&lt;wet ink&gt;</td>"));
+    }
+
+    #[test]
+    fn templates_are_saved_and_found_per_case_by_name() {
+        let dir = std::env::temp_dir().join(format!("report-templates-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        save_report_template(&dir, saved("Monthly", "A-1", "First")).unwrap();
+        save_report_template(&dir, saved("Monthly", "B-2", "Other case")).unwrap();
+        save_report_template(&dir, saved("MONTHLY", "A-1", "Replaced")).unwrap();
+
+        let template = find_report_template(&dir, Some("A-1"), "monthly").unwrap().unwrap();
+        assert_eq!(template.title, "Replaced");
+        assert_eq!(template.sections, [ReportSection::CaseSummary]);
+        assert_eq!(case_report_templates(&dir, Some("A-1")).unwrap().len(), 1);
+        assert!(remove_report_template(&dir, Some("A-1"), "Monthly").unwrap());
+        assert!(find_report_template(&dir, Some("A-1"), "Monthly").unwrap().is_none());
+        assert!(find_report_template(&dir, Some("B-2"), "Monthly").unwrap().is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
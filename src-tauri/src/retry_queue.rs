@@ -0,0 +1,73 @@
+use crate::json_store;
+use crate::scanner::FailedFile;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const RETRY_QUEUE_FILE: &str = "retry_queue.json";
+
+/// A file that could not be read, kept so it can be retried once whatever
+/// locked it (Outlook, Excel) lets go
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedFile {
+    pub case_number: Option<String>,
+    /// Source folder the file was scanned from
+    pub folder_path: String,
+    pub path: String,
+    /// Error from the most recent attempt
+    pub error: String,
+    /// Scans and retries that have failed on this file
+    pub attempts: u32,
+    pub last_attempt: String,
+}
+
+fn queue_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(RETRY_QUEUE_FILE)
+}
+
+fn is_entry(entry: &QueuedFile, case_number: Option<&str>, path: &str) -> bool {
+    entry.case_number.as_deref() == case_number && entry.path == path
+}
+
+/// Files waiting to be retried for a case, oldest first
+pub fn queued_files(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<QueuedFile>, Box<dyn std::error::Error>> {
+    Ok(json_store::read::<Vec<QueuedFile>>(&queue_path(data_dir))?
+        .into_iter()
+        .filter(|entry| entry.case_number.as_deref() == case_number)
+        .collect())
+}
+
+/// Records the outcome of a scan or retry under `folder_path`: failed files
+/// are queued (or have their attempt count raised) and recovered files leave
+/// the queue
+pub fn record_attempts(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    folder_path: &str,
+    failed: &[FailedFile],
+    recovered: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    if failed.is_empty() && recovered.is_empty() {
+        return Ok(());
+    }
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    json_store::update(&queue_path(data_dir), |queue: &mut Vec<QueuedFile>| {
+        queue.retain(|entry| !recovered.iter().any(|path| is_entry(entry, case_number, path)));
+        for file in failed {
+            match queue.iter_mut().find(|entry| is_entry(entry, case_number, &file.path)) {
+                Some(entry) => {
+                    entry.error = file.error.clone();
+                    entry.attempts += 1;
+                    entry.last_attempt = now.clone();
+                }
+                None => queue.push(QueuedFile {
+                    case_number: case_number.map(|s| s.to_string()),
+                    folder_path: folder_path.to_string(),
+                    path: file.path.clone(),
+                    error: file.error.clone(),
+                    attempts: 1,
+                    last_attempt: now.clone(),
+                }),
+            }
+        }
+    })
+}
@@ -0,0 +1,203 @@
+use crate::json_store;
+use crate::search::item_matches;
+use crate::stats::matches_filter;
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const RULES_FILE: &str = "automation_rules.json";
+
+/// "When an item matches, set field to value". An item matches when it
+/// contains the query (if any) and satisfies every exact-match filter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationRule {
+    pub name: String,
+    #[serde(default)]
+    pub case_number: Option<String>,
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub filter: HashMap<String, String>,
+    pub set_field: String,
+    pub set_value: String,
+    /// Only fill the field when it is currently empty, preserving manual edits
+    #[serde(default)]
+    pub only_if_empty: bool,
+}
+
+/// A change made automatically by a rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleAction {
+    pub rule: String,
+    pub absolute_path: String,
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleRunResult {
+    pub items: Vec<InventoryItem>,
+    pub actions: Vec<RuleAction>,
+}
+
+fn rules_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(RULES_FILE)
+}
+
+fn same_rule(rule: &AutomationRule, case_number: Option<&str>, name: &str) -> bool {
+    rule.case_number.as_deref() == case_number && rule.name.eq_ignore_ascii_case(name.trim())
+}
+
+/// A case's stored rules, in the order they run
+pub fn case_rules(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<AutomationRule>, Box<dyn std::error::Error>> {
+    let rules: Vec<AutomationRule> = json_store::read(&rules_path(data_dir))?;
+    Ok(rules.into_iter().filter(|rule| rule.case_number.as_deref() == case_number).collect())
+}
+
+/// Adds a rule after the case's others, or replaces the rule with the same
+/// name in the case where it stands
+pub fn save_rule(data_dir: &Path, rule: AutomationRule) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&rules_path(data_dir), |rules: &mut Vec<AutomationRule>| {
+        match rules.iter_mut().find(|existing| same_rule(existing, rule.case_number.as_deref(), &rule.name)) {
+            Some(existing) => *existing = rule,
+            None => rules.push(rule),
+        }
+    })
+}
+
+/// Removes a rule; returns whether it existed
+pub fn remove_rule(data_dir: &Path, case_number: Option<&str>, name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    json_store::update(&rules_path(data_dir), |rules: &mut Vec<AutomationRule>| {
+        let before = rules.len();
+        rules.retain(|rule| !same_rule(rule, case_number, name));
+        rules.len() != before
+    })
+}
+
+/// Evaluates rules in order against every item; later rules see earlier changes
+pub fn apply_rules(mut items: Vec<InventoryItem>, rules: &[AutomationRule]) -> RuleRunResult {
+    let mut actions = Vec::new();
+
+    for rule in rules {
+        let query = rule.query.as_deref().map(|q| q.trim().to_lowercase()).filter(|q| !q.is_empty());
+
+        for item in items.iter_mut() {
+            if !matches_filter(item, &rule.filter) {
+                continue;
+            }
+            if let Some(query) = &query {
+                if !item_matches(item, query) {
+                    continue;
+                }
+            }
+
+            let Some(old_value) = item.field_value(&rule.set_field) else {
+                continue;
+            };
+            if old_value == rule.set_value || (rule.only_if_empty && !old_value.is_empty()) {
+                continue;
+            }
+            if item.set_field_value(&rule.set_field, &rule.set_value) {
+                actions.push(RuleAction {
+                    rule: rule.name.clone(),
+                    absolute_path: item.absolute_path.clone(),
+                    field: rule.set_field.clone(),
+                    old_value,
+                    new_value: rule.set_value.clone(),
+                });
+            }
+        }
+    }
+
+    RuleRunResult { items, actions }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+    use std::fs;
+
+    fn item(file_name: &str, document_type: &str, notes: &str) -> InventoryItem {
+        InventoryItem {
+            document_type: document_type.to_string(),
+            folder_name: "Bank".to_string(),
+            folder_path: "Bank".to_string(),
+            notes: notes.to_string(),
+            ..test_item(&format!("/case/Bank/{}", file_name))
+        }
+    }
+
+    fn rule(name: &str, query: Option<&str>, filter: &[(&str, &str)], set_field: &str, set_value: &str) -> AutomationRule {
+        AutomationRule {
+            name: name.to_string(),
+            case_number: Some("A-1".to_string()),
+            query: query.map(str::to_string),
+            filter: filter.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            set_field: set_field.to_string(),
+            set_value: set_value.to_string(),
+            only_if_empty: false,
+        }
+    }
+
+    #[test]
+    fn rules_set_fields_on_matching_items_and_log_each_change() {
+        let items = vec![item("chase_jan.pdf", "", ""), item("letter.pdf", "", "")];
+        let rules = [rule("chase", Some("CHASE"), &[], "document_type", "Bank Statement")];
+
+        let result = apply_rules(items, &rules);
+        assert_eq!(result.items[0].document_type, "Bank Statement");
+        assert_eq!(result.items[1].document_type, "");
+        assert_eq!(result.actions.len(), 1);
+        assert_eq!(result.actions[0].absolute_path, "/case/Bank/chase_jan.pdf");
+        assert_eq!(result.actions[0].old_value, "");
+    }
+
+    #[test]
+    fn later_rules_see_earlier_changes() {
+        let rules = [
+            rule("type", Some("chase"), &[], "document_type", "Bank Statement"),
+            rule("note", None, &[("document_type", "Bank Statement")], "notes", "Review"),
+        ];
+
+        let result = apply_rules(vec![item("chase_jan.pdf", "", "")], &rules);
+        assert_eq!(result.items[0].notes, "Review");
+        assert_eq!(result.actions.len(), 2);
+    }
+
+    #[test]
+    fn only_if_empty_keeps_manual_edits_and_unchanged_values_are_not_logged() {
+        let mut fill = rule("fill", None, &[], "notes", "Auto");
+        fill.only_if_empty = true;
+        let items = vec![item("a.pdf", "", "Manual"), item("b.pdf", "", "Auto"), item("c.pdf", "", "")];
+
+        let result = apply_rules(items, &[fill]);
+        let notes: Vec<&str> = result.items.iter().map(|item| item.notes.as_str()).collect();
+        assert_eq!(notes, ["Manual", "Auto", "Auto"]);
+        assert_eq!(result.actions.len(), 1);
+        assert_eq!(result.actions[0].absolute_path, "/case/Bank/c.pdf");
+    }
+
+    #[test]
+    fn rules_are_stored_per_case_in_order() {
+        let dir = std::env::temp_dir().join(format!("rules-store-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        save_rule(&dir, rule("first", None, &[], "notes", "1")).unwrap();
+        save_rule(&dir, rule("second", None, &[], "notes", "2")).unwrap();
+        let mut other = rule("first", None, &[], "notes", "other");
+        other.case_number = Some("B-2".to_string());
+        save_rule(&dir, other).unwrap();
+        save_rule(&dir, rule("FIRST", None, &[], "notes", "replaced")).unwrap();
+
+        let rules = case_rules(&dir, Some("A-1")).unwrap();
+        let values: Vec<&str> = rules.iter().map(|rule| rule.set_value.as_str()).collect();
+        assert_eq!(values, ["replaced", "2"]);
+        assert!(remove_rule(&dir, Some("A-1"), "second").unwrap());
+        assert!(!remove_rule(&dir, Some("A-1"), "second").unwrap());
+        assert_eq!(case_rules(&dir, Some("B-2")).unwrap().len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::fs;
-use chrono::{Local, TimeZone, Datelike};
+use std::io::{ErrorKind, Read};
+use std::thread;
+use std::time::Duration;
+use chrono::{DateTime, Datelike, FixedOffset, Local, TimeZone};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileMetadata {
@@ -15,10 +19,22 @@ pub struct FileMetadata {
     pub created: String,
     pub modified: String,
     pub created_year: i32,
+    /// Online-only cloud placeholder with no local content
+    pub is_placeholder: bool,
 }
 
 impl FileMetadata {
     pub fn from_path(root_path: &Path, file_path: &Path) -> std::io::Result<Self> {
+        Self::from_path_in_zone(root_path, file_path, None)
+    }
+    
+    /// Reads metadata with timestamps rendered at a fixed UTC offset, or in
+    /// the machine's local zone when `offset` is None
+    pub fn from_path_in_zone(
+        root_path: &Path,
+        file_path: &Path,
+        offset: Option<FixedOffset>,
+    ) -> std::io::Result<Self> {
         let metadata = fs::metadata(file_path)?;
         
         // Get file name without extension
@@ -55,38 +71,25 @@ impl FileMetadata {
         let size_human = format_size(size_bytes);
         
         // Get file dates
-        let created = metadata
-            .created()
-            .ok()
-            .and_then(|t| {
-                let duration = t.duration_since(std::time::UNIX_EPOCH).ok()?;
-                Local.timestamp_opt(duration.as_secs() as i64, 0).single()
-            })
+        let created_at = metadata.created().ok().and_then(|t| to_datetime(t, offset));
+        let created = created_at
             .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
             .unwrap_or_else(|| "".to_string());
         
         let modified = metadata
             .modified()
             .ok()
-            .and_then(|t| {
-                let duration = t.duration_since(std::time::UNIX_EPOCH).ok()?;
-                Local.timestamp_opt(duration.as_secs() as i64, 0).single()
-            })
+            .and_then(|t| to_datetime(t, offset))
             .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
             .unwrap_or_else(|| "".to_string());
         
         // Extract year from creation date
-        let created_year = metadata
-            .created()
-            .ok()
-            .and_then(|t| {
-                let duration = t.duration_since(std::time::UNIX_EPOCH).ok()?;
-                Local.timestamp_opt(duration.as_secs() as i64, 0).single()
-            })
+        let created_year = created_at
             .map(|dt| dt.year())
             .unwrap_or_else(|| chrono::Local::now().year());
         
         let absolute_path = file_path.to_string_lossy().to_string();
+        let is_placeholder = is_cloud_placeholder(&metadata);
         
         Ok(Self {
             file_name: file_stem,
@@ -99,10 +102,79 @@ impl FileMetadata {
             created,
             modified,
             created_year,
+            is_placeholder,
         })
     }
 }
 
+/// Converts a file timestamp to a date-time at `offset`, or in local time
+fn to_datetime(time: std::time::SystemTime, offset: Option<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+    let duration = time.duration_since(std::time::UNIX_EPOCH).ok()?;
+    let seconds = duration.as_secs() as i64;
+    match offset {
+        Some(offset) => offset.timestamp_opt(seconds, 0).single(),
+        None => Local.timestamp_opt(seconds, 0).single().map(|dt| dt.fixed_offset()),
+    }
+}
+
+/// Parses a fixed UTC offset: "UTC", "Z", "+05:30", "-0800" or "+02"
+pub fn parse_utc_offset(value: &str) -> Option<FixedOffset> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("utc") || value.eq_ignore_ascii_case("z") {
+        return FixedOffset::east_opt(0);
+    }
+    
+    let (sign, rest) = match value.chars().next()? {
+        '+' => (1, &value[1..]),
+        '-' => (-1, &value[1..]),
+        _ => return None,
+    };
+    let digits = rest.replace(':', "");
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (digits[..2].parse::<i32>().ok()?, digits[2..].parse::<i32>().ok()?),
+        _ => return None,
+    };
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Detects online-only cloud placeholders (OneDrive Files On-Demand, Dropbox
+/// and Google Drive streaming). These report a size but have no local content,
+/// and reading them either fails or triggers a download.
+#[cfg(windows)]
+pub fn is_cloud_placeholder(metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x40000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x400000;
+    
+    metadata.file_attributes()
+        & (FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+        != 0
+}
+
+/// On macOS, File Provider placeholders (iCloud Drive, OneDrive, Dropbox)
+/// are marked dataless by the kernel
+#[cfg(target_os = "macos")]
+pub fn is_cloud_placeholder(metadata: &fs::Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+    const SF_DATALESS: u32 = 0x4000_0000;
+    metadata.st_flags() & SF_DATALESS != 0
+}
+
+/// Elsewhere there is no reliable marker: a size with no allocated blocks
+/// also describes sparse files, so files are never reported as placeholders
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn is_cloud_placeholder(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
 fn format_size(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     let mut size = bytes as f64;
@@ -116,18 +188,25 @@ fn format_size(bytes: u64) -> String {
     format!("{:.2} {}", size, UNITS[unit_index])
 }
 
-/// Fast file count - only counts files without reading metadata
-pub fn count_files(root_path: &Path) -> std::io::Result<usize> {
+/// Fast file count - only counts files without reading metadata. Files and
+/// folders the scan would ignore are left out, and unreadable subfolders are
+/// skipped as the scan skips them, so the count matches what a scan finds.
+pub fn count_files(root_path: &Path, options: &ScanOptions) -> std::io::Result<usize> {
     let mut count = 0;
     
-    fn walk_dir_count(dir: &Path, count: &mut usize) -> std::io::Result<()> {
+    fn walk_dir_count(dir: &Path, options: &ScanOptions, count: &mut usize) -> std::io::Result<()> {
         if dir.is_dir() {
             for entry in fs::read_dir(dir)? {
-                let entry = entry?;
+                let Ok(entry) = entry else {
+                    break;
+                };
                 let path = entry.path();
+                if options.is_ignored(&entry.file_name().to_string_lossy()) {
+                    continue;
+                }
                 
                 if path.is_dir() {
-                    walk_dir_count(&path, count)?;
+                    let _ = walk_dir_count(&path, options, count);
                 } else if path.is_file() {
                     *count += 1;
                 }
@@ -136,25 +215,376 @@ pub fn count_files(root_path: &Path) -> std::io::Result<usize> {
         Ok(())
     }
     
-    walk_dir_count(root_path, &mut count)?;
+    walk_dir_count(root_path, options, &mut count)?;
     Ok(count)
 }
 
-pub fn scan_folder(root_path: &Path) -> std::io::Result<Vec<FileMetadata>> {
+/// Options controlling how a folder is scanned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    /// Extra attempts for files that fail with a transient error (e.g. locked by another process)
+    pub max_retries: u32,
+    /// Delay before the first retry in milliseconds; doubled after each attempt
+    pub retry_delay_ms: u64,
+    /// What to do with online-only cloud placeholders (detected on Windows and macOS)
+    pub placeholder_policy: PlaceholderPolicy,
+    /// Skip the built-in system noise names in `DEFAULT_IGNORE_PATTERNS`
+    pub use_default_ignores: bool,
+    /// Extra file or folder names to skip; `*` matches any run of characters
+    pub ignore_patterns: Vec<String>,
+    /// What to do with files at or below `small_file_threshold_bytes`
+    pub small_file_policy: SmallFilePolicy,
+    /// Size in bytes at or below which a file counts as small (0 = only empty files)
+    pub small_file_threshold_bytes: u64,
+    /// UTC offset for created/modified timestamps ("UTC", "+05:30", ...);
+    /// None uses this machine's local time zone
+    pub time_zone: Option<String>,
+    /// Per-extension handling keyed by file type without the dot (e.g. "EXE");
+    /// types not listed are included
+    pub type_policies: HashMap<String, TypePolicy>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TypePolicy {
+    #[default]
+    Include,
+    /// Inventory the file with a note so reviewers can spot it (e.g. databases)
+    Flag,
+    /// Leave files of this type out of the inventory (e.g. executables)
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmallFilePolicy {
+    /// Inventory small files like any other
+    #[default]
+    Include,
+    /// Inventory small files with a note so reviewers can spot them
+    Flag,
+    /// Leave small files out of the inventory
+    Ignore,
+}
+
+/// System noise skipped by default: thumbnail caches, Finder metadata, Office
+/// lock files, dependency folders and recycle bins
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
+    "Thumbs.db",
+    "ehthumbs.db",
+    ".DS_Store",
+    "._*",
+    "desktop.ini",
+    "~$*",
+    "node_modules",
+    "$RECYCLE.BIN",
+    "RECYCLER",
+    ".Trashes",
+    ".Trash-*",
+    "System Volume Information",
+];
+
+/// Case-insensitive name match supporting `*` wildcards
+fn matches_ignore_pattern(name: &str, pattern: &str) -> bool {
+    let name = name.to_lowercase();
+    let pattern = pattern.to_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    if !name.starts_with(first) {
+        return false;
+    }
+    
+    let mut rest = &name[first.len()..];
+    let remaining: Vec<&str> = parts.collect();
+    let Some((last, middle)) = remaining.split_last() else {
+        // No wildcard: the whole name must match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+impl ScanOptions {
+    /// Whether a file or folder with this name should be left out of the scan
+    pub fn is_ignored(&self, name: &str) -> bool {
+        let defaults: &[&str] = if self.use_default_ignores {
+            DEFAULT_IGNORE_PATTERNS
+        } else {
+            &[]
+        };
+        defaults
+            .iter()
+            .copied()
+            .chain(self.ignore_patterns.iter().map(String::as_str))
+            .any(|pattern| matches_ignore_pattern(name, pattern))
+    }
+    
+    /// Policy for a file type; keys are matched case-insensitively
+    pub fn type_policy(&self, file_type: &str) -> TypePolicy {
+        self.type_policies
+            .iter()
+            .find(|(key, _)| key.trim_start_matches('.').eq_ignore_ascii_case(file_type))
+            .map(|(_, policy)| *policy)
+            .unwrap_or_default()
+    }
+    
+    /// Whether a file of this size falls under the small-file policy
+    pub fn is_small_file(&self, size_bytes: u64) -> bool {
+        size_bytes <= self.small_file_threshold_bytes
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaceholderPolicy {
+    /// Keep placeholders in the inventory (metadata is available without downloading)
+    #[default]
+    Include,
+    /// Leave placeholders out of the inventory
+    Skip,
+    /// Open each placeholder to make the sync client download it, then include it
+    Hydrate,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            retry_delay_ms: 250,
+            placeholder_policy: PlaceholderPolicy::Include,
+            use_default_ignores: true,
+            ignore_patterns: Vec::new(),
+            small_file_policy: SmallFilePolicy::Include,
+            small_file_threshold_bytes: 0,
+            time_zone: None,
+            type_policies: HashMap::new(),
+        }
+    }
+}
+
+/// A file that could not be read during a scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedFile {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ScanOutcome {
+    pub files: Vec<FileMetadata>,
+    pub failed: Vec<FailedFile>,
+    /// Absolute paths of placeholders found, whether included, skipped or hydrated
+    pub placeholders: Vec<String>,
+    /// Files skipped because their name matched an ignore pattern
+    pub ignored_files: usize,
+    /// Folders skipped (with everything inside them) because their name matched an ignore pattern
+    pub ignored_folders: usize,
+    /// Files at or below the small-file threshold, whether included, flagged or ignored
+    pub small_files: Vec<SmallFile>,
+    /// Counts and sizes per file type, including files skipped by a type policy
+    pub extension_stats: BTreeMap<String, ExtensionStats>,
+}
+
+/// Files of one type seen during a scan
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtensionStats {
+    pub file_type: String,
+    pub count: usize,
+    pub total_bytes: u64,
+    /// Files of this type left out by its type policy
+    pub skipped: usize,
+}
+
+/// A zero-byte or sub-threshold file found during a scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmallFile {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// Reads the first byte of a placeholder, which makes the sync client fetch it
+fn hydrate_placeholder(path: &Path) -> std::io::Result<()> {
+    let mut buf = [0u8; 1];
+    let mut file = fs::File::open(path)?;
+    file.read_exact(&mut buf)?;
+    Ok(())
+}
+
+/// Errors worth retrying: interrupted reads, timeouts, and Windows sharing/lock
+/// violations raised while another process (Outlook, Excel) holds the file
+fn is_transient_error(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        ErrorKind::Interrupted | ErrorKind::WouldBlock | ErrorKind::TimedOut
+    ) || (cfg!(windows) && matches!(e.raw_os_error(), Some(32) | Some(33)))
+}
+
+/// Runs `read`, retrying transient failures with exponential backoff
+fn retry_transient<T>(options: &ScanOptions, mut read: impl FnMut() -> std::io::Result<T>) -> std::io::Result<T> {
+    let mut delay = Duration::from_millis(options.retry_delay_ms);
+    let mut attempt = 0;
+    loop {
+        match read() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < options.max_retries && is_transient_error(&e) => {
+                attempt += 1;
+                thread::sleep(delay);
+                delay *= 2;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Opens the file and reads from it. Metadata can be read while another
+/// process holds the file locked, so this is what catches the lock.
+fn check_readable(path: &Path) -> std::io::Result<()> {
+    let mut buf = [0u8; 1];
+    fs::File::open(path)?.read(&mut buf).map(|_| ())
+}
+
+/// Reads file metadata and checks the file can be opened and read, retrying
+/// transient failures of either with exponential backoff. Placeholders are
+/// not read, which would download them.
+pub fn read_metadata_with_retry(
+    root_path: &Path,
+    file_path: &Path,
+    options: &ScanOptions,
+) -> std::io::Result<FileMetadata> {
+    let offset = options.time_zone.as_deref().and_then(parse_utc_offset);
+    retry_transient(options, || {
+        let metadata = FileMetadata::from_path_in_zone(root_path, file_path, offset)?;
+        if !metadata.is_placeholder {
+            check_readable(file_path)?;
+        }
+        Ok(metadata)
+    })
+}
+
+/// Scans a folder tree, collecting unreadable files instead of aborting
+pub fn scan_folder_with_options(
+    root_path: &Path,
+    options: &ScanOptions,
+) -> std::io::Result<ScanOutcome> {
     let mut files = Vec::new();
+    let mut outcome = scan_folder_streaming(root_path, options, usize::MAX, |batch| {
+        files.extend(batch);
+        Ok(())
+    })?;
+    outcome.files = files;
+    Ok(outcome)
+}
+
+/// Walks a folder tree, handing files to a sink in batches as they are read
+struct StreamingScan<'a, F> {
+    root: &'a Path,
+    options: &'a ScanOptions,
+    batch_size: usize,
+    pending: Vec<FileMetadata>,
+    outcome: ScanOutcome,
+    on_batch: F,
+}
+
+impl<F> StreamingScan<'_, F>
+where
+    F: FnMut(Vec<FileMetadata>) -> std::io::Result<()>,
+{
+    fn push(&mut self, metadata: FileMetadata) -> std::io::Result<()> {
+        self.pending.push(metadata);
+        if self.pending.len() >= self.batch_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+    
+    /// Records a file in the per-type stats and applies the type and small-file policies
+    fn accept(&mut self, metadata: FileMetadata) -> std::io::Result<()> {
+        let stats = self
+            .outcome
+            .extension_stats
+            .entry(metadata.file_type.clone())
+            .or_insert_with(|| ExtensionStats {
+                file_type: metadata.file_type.clone(),
+                ..ExtensionStats::default()
+            });
+        stats.count += 1;
+        stats.total_bytes += metadata.size_bytes;
+        if self.options.type_policy(&metadata.file_type) == TypePolicy::Skip {
+            stats.skipped += 1;
+            return Ok(());
+        }
+        
+        if !self.options.is_small_file(metadata.size_bytes) {
+            return self.push(metadata);
+        }
+        
+        self.outcome.small_files.push(SmallFile {
+            path: metadata.absolute_path.clone(),
+            size_bytes: metadata.size_bytes,
+        });
+        match self.options.small_file_policy {
+            SmallFilePolicy::Include | SmallFilePolicy::Flag => self.push(metadata),
+            SmallFilePolicy::Ignore => Ok(()),
+        }
+    }
     
-    fn walk_dir(dir: &Path, root: &Path, files: &mut Vec<FileMetadata>) -> std::io::Result<()> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.pending);
+        (self.on_batch)(batch)
+    }
+    
+    /// Records a folder that could not be listed, so it can be retried like
+    /// an unreadable file
+    fn fail_folder(&mut self, dir: &Path, e: std::io::Error) {
+        self.outcome.failed.push(FailedFile {
+            path: dir.to_string_lossy().to_string(),
+            error: e.to_string(),
+        });
+    }
+    
+    /// Walks a folder. Only an unreadable root fails the scan; a subfolder
+    /// that cannot be listed is recorded as failed and the walk goes on.
+    fn walk_dir(&mut self, dir: &Path) -> std::io::Result<()> {
         if dir.is_dir() {
-            for entry in fs::read_dir(dir)? {
-                let entry = entry?;
+            let entries = match fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(e) if dir == self.root => return Err(e),
+                Err(e) => {
+                    self.fail_folder(dir, e);
+                    return Ok(());
+                }
+            };
+            for entry in entries {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        self.fail_folder(dir, e);
+                        break;
+                    }
+                };
                 let path = entry.path();
+                let ignored = self.options.is_ignored(&entry.file_name().to_string_lossy());
                 
                 if path.is_dir() {
-                    walk_dir(&path, root, files)?;
+                    if ignored {
+                        self.outcome.ignored_folders += 1;
+                    } else {
+                        self.walk_dir(&path)?;
+                    }
                 } else if path.is_file() {
-                    match FileMetadata::from_path(root, &path) {
-                        Ok(metadata) => files.push(metadata),
-                        Err(e) => eprintln!("Error reading file {:?}: {}", path, e),
+                    if ignored {
+                        self.outcome.ignored_files += 1;
+                    } else {
+                        self.visit_file(&path)?;
                     }
                 }
             }
@@ -162,7 +592,234 @@ pub fn scan_folder(root_path: &Path) -> std::io::Result<Vec<FileMetadata>> {
         Ok(())
     }
     
-    walk_dir(root_path, root_path, &mut files)?;
-    Ok(files)
+    fn visit_file(&mut self, path: &Path) -> std::io::Result<()> {
+        let (root, options) = (self.root, self.options);
+        match read_metadata_with_retry(root, path, options) {
+            Ok(metadata) if metadata.is_placeholder => {
+                self.outcome.placeholders.push(metadata.absolute_path.clone());
+                match options.placeholder_policy {
+                    PlaceholderPolicy::Include => self.accept(metadata)?,
+                    PlaceholderPolicy::Skip => {}
+                    PlaceholderPolicy::Hydrate => match retry_transient(options, || hydrate_placeholder(path))
+                        .and_then(|_| read_metadata_with_retry(root, path, options))
+                    {
+                        Ok(hydrated) => self.accept(hydrated)?,
+                        Err(e) => self.outcome.failed.push(FailedFile {
+                            path: metadata.absolute_path,
+                            error: e.to_string(),
+                        }),
+                    },
+                }
+            }
+            Ok(metadata) => self.accept(metadata)?,
+            Err(e) => self.outcome.failed.push(FailedFile {
+                path: path.to_string_lossy().to_string(),
+                error: e.to_string(),
+            }),
+        }
+        Ok(())
+    }
+}
+
+/// Scans `folder`, a subfolder of `root_path` that failed to list before,
+/// with paths and folder names relative to `root_path` as the original scan
+/// gave them. If the folder still cannot be listed it is in `failed`.
+pub fn rescan_folder(root_path: &Path, folder: &Path, options: &ScanOptions) -> std::io::Result<ScanOutcome> {
+    let mut files = Vec::new();
+    let mut outcome = {
+        let mut scan = StreamingScan {
+            root: root_path,
+            options,
+            batch_size: usize::MAX,
+            pending: Vec::new(),
+            outcome: ScanOutcome::default(),
+            on_batch: |batch: Vec<FileMetadata>| {
+                files.extend(batch);
+                Ok(())
+            },
+        };
+        scan.walk_dir(folder)?;
+        scan.flush()?;
+        scan.outcome
+    };
+    outcome.files = files;
+    Ok(outcome)
 }
 
+/// Scans a folder tree, handing files to `on_batch` in groups of at most
+/// `batch_size` while the walk is still running, so a huge tree never has to be
+/// held in memory at once. The returned outcome's `files` is left empty; failed
+/// files and placeholders are still collected. An error from `on_batch` stops
+/// the scan.
+pub fn scan_folder_streaming<F>(
+    root_path: &Path,
+    options: &ScanOptions,
+    batch_size: usize,
+    on_batch: F,
+) -> std::io::Result<ScanOutcome>
+where
+    F: FnMut(Vec<FileMetadata>) -> std::io::Result<()>,
+{
+    let mut scan = StreamingScan {
+        root: root_path,
+        options,
+        batch_size: batch_size.max(1),
+        pending: Vec::new(),
+        outcome: ScanOutcome::default(),
+        on_batch,
+    };
+    
+    scan.walk_dir(root_path)?;
+    scan.flush()?;
+    Ok(scan.outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates the files under `dir`, each with the given content
+    fn create_files(dir: &Path, files: &[(&str, &str)]) {
+        let _ = fs::remove_dir_all(dir);
+        for (relative, content) in files {
+            let path = dir.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+    }
+
+    fn scanned_names(outcome: &ScanOutcome) -> Vec<String> {
+        let mut names: Vec<String> = outcome
+            .files
+            .iter()
+            .map(|file| format!("{}.{}", file.file_name, file.file_type.to_lowercase()))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn locked_files_are_retried_until_they_can_be_read() {
+        let dir = std::env::temp_dir().join(format!("scanner-retry-{}", std::process::id()));
+        create_files(&dir, &[("Mail/archive.pst", "pst")]);
+        let file = dir.join("Mail/archive.pst");
+        let options = ScanOptions { retry_delay_ms: 1, ..ScanOptions::default() };
+
+        // Held by another process for the first two attempts, then released
+        let mut attempts = 0;
+        let metadata = retry_transient(&options, || {
+            attempts += 1;
+            if attempts <= 2 {
+                return Err(std::io::Error::new(ErrorKind::WouldBlock, "file is locked"));
+            }
+            check_readable(&file)?;
+            FileMetadata::from_path(&dir, &file)
+        })
+        .unwrap();
+        assert_eq!((attempts, metadata.file_name.as_str(), metadata.size_bytes), (3, "archive", 3));
+
+        // One lock too many, and errors that will not clear, are given up on
+        let mut attempts = 0;
+        let locked = retry_transient(&options, || -> std::io::Result<()> {
+            attempts += 1;
+            Err(std::io::Error::new(ErrorKind::WouldBlock, "file is locked"))
+        });
+        assert_eq!((locked.unwrap_err().kind(), attempts), (ErrorKind::WouldBlock, 3));
+        let mut attempts = 0;
+        let missing = retry_transient(&options, || {
+            attempts += 1;
+            check_readable(&dir.join("missing.pst"))
+        });
+        assert_eq!((missing.unwrap_err().kind(), attempts), (ErrorKind::NotFound, 1));
+
+        assert_eq!(read_metadata_with_retry(&dir, &file, &options).unwrap().folder_path, "Mail");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn ignore_patterns_match_names_with_wildcards() {
+        let options = ScanOptions::default();
+        assert!(options.is_ignored("thumbs.DB"));
+        assert!(options.is_ignored("~$Budget.xlsx"));
+        assert!(options.is_ignored("._report.pdf"));
+        assert!(options.is_ignored(".Trash-1000"));
+        assert!(!options.is_ignored("Budget.xlsx"));
+        assert!(!options.is_ignored("my node_modules notes.txt"));
+
+        let custom = ScanOptions {
+            use_default_ignores: false,
+            ignore_patterns: vec!["*.tmp".to_string(), "draft*v*.docx".to_string()],
+            ..ScanOptions::default()
+        };
+        assert!(!custom.is_ignored("Thumbs.db"));
+        assert!(custom.is_ignored("upload.TMP"));
+        assert!(custom.is_ignored("draft letter v2.docx"));
+        assert!(!custom.is_ignored("draft letter.docx"));
+    }
+
+    #[test]
+    fn ignored_files_and_folders_are_counted_not_scanned() {
+        let dir = std::env::temp_dir().join(format!("scanner-ignore-{}", std::process::id()));
+        create_files(
+            &dir,
+            &[
+                ("a.pdf", "a"),
+                ("Thumbs.db", "x"),
+                ("Bank/.DS_Store", "x"),
+                ("Bank/b.pdf", "b"),
+                ("node_modules/pkg/index.js", "x"),
+                ("node_modules/pkg/readme.md", "x"),
+            ],
+        );
+
+        let outcome = scan_folder_with_options(&dir, &ScanOptions::default()).unwrap();
+        assert_eq!(scanned_names(&outcome), ["a.pdf", "b.pdf"]);
+        assert_eq!(outcome.ignored_files, 2);
+        assert_eq!(outcome.ignored_folders, 1);
+        assert_eq!(count_files(&dir, &ScanOptions::default()).unwrap(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn small_files_are_reported_under_every_policy() {
+        let dir = std::env::temp_dir().join(format!("scanner-small-{}", std::process::id()));
+        create_files(&dir, &[("empty.pdf", ""), ("tiny.txt", "abc"), ("full.pdf", "0123456789")]);
+        let scan = |small_file_policy, small_file_threshold_bytes| {
+            let options = ScanOptions { small_file_policy, small_file_threshold_bytes, ..ScanOptions::default() };
+            let outcome = scan_folder_with_options(&dir, &options).unwrap();
+            let mut small: Vec<u64> = outcome.small_files.iter().map(|file| file.size_bytes).collect();
+            small.sort();
+            (scanned_names(&outcome), small)
+        };
+
+        // The default threshold of 0 covers only empty files
+        assert_eq!(scan(SmallFilePolicy::Include, 0), (vec!["empty.pdf".into(), "full.pdf".into(), "tiny.txt".into()], vec![0]));
+        assert_eq!(scan(SmallFilePolicy::Flag, 3), (vec!["empty.pdf".into(), "full.pdf".into(), "tiny.txt".into()], vec![0, 3]));
+        assert_eq!(scan(SmallFilePolicy::Ignore, 3), (vec!["full.pdf".to_string()], vec![0, 3]));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn type_policies_skip_files_but_keep_their_stats() {
+        let dir = std::env::temp_dir().join(format!("scanner-types-{}", std::process::id()));
+        create_files(&dir, &[("setup.exe", "12345"), ("tool.EXE", "123"), ("ledger.db", "1"), ("a.pdf", "12")]);
+        let options = ScanOptions {
+            type_policies: HashMap::from([
+                (".exe".to_string(), TypePolicy::Skip),
+                ("DB".to_string(), TypePolicy::Flag),
+            ]),
+            ..ScanOptions::default()
+        };
+        assert_eq!(options.type_policy("EXE"), TypePolicy::Skip);
+        assert_eq!(options.type_policy("db"), TypePolicy::Flag);
+        assert_eq!(options.type_policy("PDF"), TypePolicy::Include);
+
+        let outcome = scan_folder_with_options(&dir, &options).unwrap();
+        assert_eq!(scanned_names(&outcome), ["a.pdf", "ledger.db"]);
+        let exe = &outcome.extension_stats["EXE"];
+        assert_eq!((exe.count, exe.total_bytes, exe.skipped), (2, 8, 2));
+        let db = &outcome.extension_stats["DB"];
+        assert_eq!((db.count, db.total_bytes, db.skipped), (1, 1, 0));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,262 @@
+use crate::export::{CollisionPolicy, ExportOptions};
+use crate::json_store;
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const SCHEDULES_FILE: &str = "export_schedules.json";
+const RUNS_FILE: &str = "export_schedule_runs.json";
+
+/// Runs kept per schedule; older ones are dropped
+const MAX_RUNS_PER_SCHEDULE: usize = 50;
+
+/// An export run automatically on a cron-like schedule, e.g. a nightly CSV
+/// snapshot to a network share
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSchedule {
+    pub id: String,
+    pub case_number: String,
+    /// Five cron fields: minute, hour, day of month, month and day of week
+    /// (0 is Sunday). Each is `*`, a number, a range `a-b`, a list `a,b` or
+    /// a step `*/n`, e.g. `30 2 * * *` for 02:30 every night.
+    pub cron: String,
+    /// "xlsx", "csv", "json" or "sqlite"
+    pub format: String,
+    pub directory: String,
+    /// File name pattern, e.g. `{case_id}_{date}`
+    pub pattern: String,
+    #[serde(default)]
+    pub collision: CollisionPolicy,
+    #[serde(default)]
+    pub options: ExportOptions,
+}
+
+/// One run of a schedule
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportRun {
+    pub schedule_id: String,
+    pub case_number: String,
+    pub started_at: String,
+    /// Where the export was written, if it was
+    pub output_path: Option<String>,
+    pub item_count: usize,
+    /// Why the run failed
+    pub error: Option<String>,
+}
+
+/// Accepted values of one cron field
+#[derive(Debug, Clone, PartialEq)]
+struct CronField(Vec<u32>);
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<CronField, String> {
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|step| *step > 0).ok_or_else(|| part.to_string())?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (
+                    start.parse().map_err(|_| part.to_string())?,
+                    end.parse().map_err(|_| part.to_string())?,
+                ),
+                None => {
+                    let value = range.parse().map_err(|_| part.to_string())?;
+                    (value, if step > 1 { max } else { value })
+                }
+            },
+        };
+        if start < min || end > max || start > end {
+            return Err(part.to_string());
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+    Ok(CronField(values))
+}
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cron {
+    minute: CronField,
+    hour: CronField,
+    day: CronField,
+    month: CronField,
+    weekday: CronField,
+}
+
+impl Cron {
+    /// Parses five cron fields. Returns the offending field if one is not
+    /// valid.
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields.as_slice() else {
+            return Err(format!("expected 5 fields, found {}", fields.len()));
+        };
+        Ok(Self {
+            minute: parse_field(minute, 0, 59)?,
+            hour: parse_field(hour, 0, 23)?,
+            day: parse_field(day, 1, 31)?,
+            month: parse_field(month, 1, 12)?,
+            weekday: parse_field(weekday, 0, 6)?,
+        })
+    }
+
+    /// Whether the schedule fires in the minute `time` falls in
+    pub fn matches(&self, time: &NaiveDateTime) -> bool {
+        self.minute.0.contains(&time.minute())
+            && self.hour.0.contains(&time.hour())
+            && self.day.0.contains(&time.day())
+            && self.month.0.contains(&time.month())
+            && self.weekday.0.contains(&time.weekday().num_days_from_sunday())
+    }
+}
+
+/// Schedules due in the minute `time` falls in. Schedules with a cron
+/// expression that no longer parses never run.
+pub fn due_schedules<'a>(schedules: &'a [ExportSchedule], time: &NaiveDateTime) -> Vec<&'a ExportSchedule> {
+    schedules
+        .iter()
+        .filter(|schedule| Cron::parse(&schedule.cron).is_ok_and(|cron| cron.matches(time)))
+        .collect()
+}
+
+fn schedules_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(SCHEDULES_FILE)
+}
+
+fn runs_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(RUNS_FILE)
+}
+
+/// Every stored schedule, or only a case's
+pub fn list_schedules(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<ExportSchedule>, Box<dyn std::error::Error>> {
+    let schedules: Vec<ExportSchedule> = json_store::read(&schedules_path(data_dir))?;
+    Ok(schedules
+        .into_iter()
+        .filter(|schedule| case_number.is_none_or(|case_number| schedule.case_number == case_number))
+        .collect())
+}
+
+/// Adds a schedule, or replaces the one with the same id
+pub fn save_schedule(data_dir: &Path, schedule: ExportSchedule) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&schedules_path(data_dir), |all: &mut Vec<ExportSchedule>| {
+        match all.iter_mut().find(|existing| existing.id == schedule.id) {
+            Some(existing) => *existing = schedule,
+            None => all.push(schedule),
+        }
+    })
+}
+
+/// Removes a schedule and its run history
+pub fn remove_schedule(data_dir: &Path, id: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut removed = false;
+    json_store::update(&schedules_path(data_dir), |all: &mut Vec<ExportSchedule>| {
+        let before = all.len();
+        all.retain(|schedule| schedule.id != id);
+        removed = all.len() != before;
+    })?;
+    json_store::update(&runs_path(data_dir), |all: &mut Vec<ExportRun>| all.retain(|run| run.schedule_id != id))?;
+    Ok(removed)
+}
+
+pub fn record_run(data_dir: &Path, run: &ExportRun) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&runs_path(data_dir), |all: &mut Vec<ExportRun>| {
+        all.push(run.clone());
+        let kept = all.iter().filter(|kept| kept.schedule_id == run.schedule_id).count();
+        let mut excess = kept.saturating_sub(MAX_RUNS_PER_SCHEDULE);
+        all.retain(|kept| {
+            let drop = excess > 0 && kept.schedule_id == run.schedule_id;
+            excess -= usize::from(drop);
+            !drop
+        });
+    })
+}
+
+/// A case's runs, newest first, optionally for one schedule only
+pub fn list_runs(data_dir: &Path, case_number: &str, schedule_id: Option<&str>) -> Result<Vec<ExportRun>, Box<dyn std::error::Error>> {
+    let runs: Vec<ExportRun> = json_store::read(&runs_path(data_dir))?;
+    Ok(runs
+        .into_iter()
+        .rev()
+        .filter(|run| run.case_number == case_number && schedule_id.is_none_or(|id| run.schedule_id == id))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::fs;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // March 2026 starts on a Sunday
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn schedule(id: &str, cron: &str) -> ExportSchedule {
+        ExportSchedule {
+            id: id.to_string(),
+            case_number: "A-1".to_string(),
+            cron: cron.to_string(),
+            format: "csv".to_string(),
+            directory: "/share".to_string(),
+            pattern: "{case_id}_{date}".to_string(),
+            collision: CollisionPolicy::default(),
+            options: ExportOptions::default(),
+        }
+    }
+
+    #[test]
+    fn cron_fields_match_the_minute() {
+        let nightly = Cron::parse("30 2 * * *").unwrap();
+        assert!(nightly.matches(&at(4, 2, 30)));
+        assert!(!nightly.matches(&at(4, 2, 31)));
+
+        let weekdays = Cron::parse("*/15 9-17 * * 1-5").unwrap();
+        assert!(weekdays.matches(&at(2, 9, 45)));
+        assert!(!weekdays.matches(&at(1, 9, 45)), "Sunday");
+        assert!(!weekdays.matches(&at(2, 18, 0)));
+
+        let listed = Cron::parse("0 0 1,15 3 *").unwrap();
+        assert!(listed.matches(&at(15, 0, 0)) && !listed.matches(&at(14, 0, 0)));
+
+        assert_eq!(Cron::parse("60 * * * *"), Err("60".to_string()));
+        assert_eq!(Cron::parse("*/0 * * * *"), Err("*/0".to_string()));
+        assert!(Cron::parse("* * * *").is_err());
+
+        let schedules = [schedule("nightly", "30 2 * * *"), schedule("broken", "oops")];
+        let due: Vec<&str> = due_schedules(&schedules, &at(4, 2, 30)).iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(due, ["nightly"]);
+    }
+
+    #[test]
+    fn runs_are_kept_per_schedule_newest_first() {
+        let dir = std::env::temp_dir().join(format!("schedules-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        save_schedule(&dir, schedule("nightly", "30 2 * * *")).unwrap();
+        save_schedule(&dir, schedule("nightly", "0 3 * * *")).unwrap();
+        assert_eq!(list_schedules(&dir, Some("A-1")).unwrap()[0].cron, "0 3 * * *");
+        assert!(list_schedules(&dir, Some("B-2")).unwrap().is_empty());
+
+        for minute in 0..MAX_RUNS_PER_SCHEDULE + 2 {
+            let run = ExportRun {
+                schedule_id: "nightly".to_string(),
+                case_number: "A-1".to_string(),
+                started_at: format!("2026-03-01 03:{:02}:00", minute),
+                output_path: None,
+                item_count: 0,
+                error: Some("offline".to_string()),
+            };
+            record_run(&dir, &run).unwrap();
+        }
+        let runs = list_runs(&dir, "A-1", Some("nightly")).unwrap();
+        assert_eq!(runs.len(), MAX_RUNS_PER_SCHEDULE);
+        assert_eq!(runs[0].started_at, "2026-03-01 03:51:00");
+
+        assert!(remove_schedule(&dir, "nightly").unwrap());
+        assert!(list_runs(&dir, "A-1", None).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,305 @@
+use crate::links::FileLink;
+use crate::notes::FileNote;
+use crate::{InventoryItem, INVENTORY_FIELDS};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Number of matching items at or below a folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderHits {
+    pub folder_path: String,
+    pub hits: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderSearchResult {
+    pub matches: Vec<InventoryItem>,
+    /// Hit counts rolled up to every ancestor folder, sorted by path
+    pub hits_per_folder: Vec<FolderHits>,
+}
+
+/// Where a search hit was found
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSource {
+    File,
+    Note,
+    Link,
+}
+
+/// One hit from searching a whole case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub source: SearchSource,
+    /// The file the hit is about: the item, the noted file or the link's
+    /// source
+    pub absolute_path: String,
+    /// First field that matched, e.g. "document_description", "content" or
+    /// "relationship"
+    pub field: String,
+    pub value: String,
+    /// Set for note hits
+    #[serde(default)]
+    pub note_id: Option<u64>,
+}
+
+/// What `search_all` returns and how file hits are narrowed. Sources and
+/// file filters apply before counting; `offset` and `limit_per_source`
+/// page each source's hits separately.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchAllOptions {
+    /// Sources to search; empty searches all of them
+    #[serde(default)]
+    pub sources: Vec<SearchSource>,
+    /// Exact `field == value` matches required of file hits, e.g. a
+    /// document type or file category
+    #[serde(default)]
+    pub filter: HashMap<String, String>,
+    /// Inclusive document year range for file hits
+    #[serde(default)]
+    pub year_from: Option<i32>,
+    #[serde(default)]
+    pub year_to: Option<i32>,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default)]
+    pub limit_per_source: Option<usize>,
+}
+
+/// Total hits found in one source, before paging
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceCount {
+    pub source: SearchSource,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchAllResults {
+    /// The requested page of each source's hits: files, then notes, then
+    /// links
+    pub hits: Vec<SearchHit>,
+    /// One count per searched source
+    pub counts: Vec<SourceCount>,
+}
+
+/// Case-insensitive substring match against every user-facing field
+pub fn item_matches(item: &InventoryItem, query_lower: &str) -> bool {
+    INVENTORY_FIELDS.iter().any(|field| {
+        item.field_value(field)
+            .map(|value| value.to_lowercase().contains(query_lower))
+            .unwrap_or(false)
+    })
+}
+
+/// True if `folder_path` is the prefix folder itself or nested inside it
+pub(crate) fn is_in_folder(folder_path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    prefix.is_empty()
+        || folder_path == prefix
+        || folder_path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn first_match<'a>(fields: &[(&'a str, &'a str)], query_lower: &str) -> Option<(&'a str, &'a str)> {
+    fields
+        .iter()
+        .find(|(_, value)| value.to_lowercase().contains(query_lower))
+        .copied()
+}
+
+fn passes_file_filters(item: &InventoryItem, options: &SearchAllOptions) -> bool {
+    options
+        .filter
+        .iter()
+        .all(|(field, value)| item.field_value(field).is_some_and(|actual| actual == *value))
+        && options.year_from.is_none_or(|year| item.doc_year >= year)
+        && options.year_to.is_none_or(|year| item.doc_year <= year)
+}
+
+/// Case-insensitive search over inventory fields, note text and link
+/// relationships and notes, so content kept outside the inventory columns
+/// is not missed. An empty query finds nothing.
+pub fn search_all(
+    items: &[InventoryItem],
+    notes: &[FileNote],
+    links: &[FileLink],
+    query: &str,
+    options: &SearchAllOptions,
+) -> SearchAllResults {
+    let query_lower = query.trim().to_lowercase();
+    let searched = |source| {
+        !query_lower.is_empty() && (options.sources.is_empty() || options.sources.contains(&source))
+    };
+
+    let files = items.iter().filter(|item| passes_file_filters(item, options)).filter_map(|item| {
+        let field = INVENTORY_FIELDS.iter().find(|field| {
+            item.field_value(field)
+                .is_some_and(|value| value.to_lowercase().contains(&query_lower))
+        })?;
+        Some(SearchHit {
+            source: SearchSource::File,
+            absolute_path: item.absolute_path.clone(),
+            field: field.to_string(),
+            value: item.field_value(field).unwrap_or_default(),
+            note_id: None,
+        })
+    });
+    let notes = notes.iter().filter_map(|note| {
+        let (field, value) = first_match(&[("content", &note.content), ("author", &note.author)], &query_lower)?;
+        Some(SearchHit {
+            source: SearchSource::Note,
+            absolute_path: note.absolute_path.clone(),
+            field: field.to_string(),
+            value: value.to_string(),
+            note_id: Some(note.id),
+        })
+    });
+    let links = links.iter().filter_map(|link| {
+        let (field, value) = first_match(&[("relationship", &link.relationship), ("note", &link.note)], &query_lower)?;
+        Some(SearchHit {
+            source: SearchSource::Link,
+            absolute_path: link.source.clone(),
+            field: field.to_string(),
+            value: value.to_string(),
+            note_id: None,
+        })
+    });
+
+    let mut results = SearchAllResults { hits: Vec::new(), counts: Vec::new() };
+    let mut add = |source, hits: Vec<SearchHit>| {
+        results.counts.push(SourceCount { source, total: hits.len() });
+        let page = hits.into_iter().skip(options.offset).take(options.limit_per_source.unwrap_or(usize::MAX));
+        results.hits.extend(page);
+    };
+    if searched(SearchSource::File) {
+        add(SearchSource::File, files.collect());
+    }
+    if searched(SearchSource::Note) {
+        add(SearchSource::Note, notes.collect());
+    }
+    if searched(SearchSource::Link) {
+        add(SearchSource::Link, links.collect());
+    }
+    results
+}
+
+pub fn search_in_folder(
+    items: Vec<InventoryItem>,
+    folder_path_prefix: &str,
+    query: &str,
+) -> FolderSearchResult {
+    let query_lower = query.trim().to_lowercase();
+    let mut hits: BTreeMap<String, usize> = BTreeMap::new();
+
+    let matches: Vec<InventoryItem> = items
+        .into_iter()
+        .filter(|item| is_in_folder(&item.folder_path, folder_path_prefix))
+        .filter(|item| item_matches(item, &query_lower))
+        .collect();
+
+    for item in &matches {
+        // Count the hit against the folder and each of its ancestors
+        let mut ancestor = String::new();
+        for segment in item.folder_path.split('/').filter(|s| !s.is_empty()) {
+            if !ancestor.is_empty() {
+                ancestor.push('/');
+            }
+            ancestor.push_str(segment);
+            *hits.entry(ancestor.clone()).or_insert(0) += 1;
+        }
+    }
+
+    FolderSearchResult {
+        matches,
+        hits_per_folder: hits
+            .into_iter()
+            .map(|(folder_path, hits)| FolderHits { folder_path, hits })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+
+    fn item(file_name: &str, document_description: &str) -> InventoryItem {
+        InventoryItem {
+            document_type: "Statement".to_string(),
+            document_description: document_description.to_string(),
+            folder_name: "Bank".to_string(),
+            folder_path: "Bank".to_string(),
+            ..test_item(&format!("/c/Bank/{}", file_name))
+        }
+    }
+
+    fn note(id: u64, absolute_path: &str, content: &str) -> FileNote {
+        FileNote {
+            id,
+            case_number: None,
+            absolute_path: absolute_path.to_string(),
+            parent_id: None,
+            author: String::new(),
+            content: content.to_string(),
+            created_at: String::new(),
+            resolved: false,
+            mentions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn search_all_finds_files_notes_and_links() {
+        let items = vec![item("jan.pdf", "Wire to escrow"), item("feb.pdf", "Deposits")];
+        let notes = vec![note(1, "/c/Bank/feb.pdf", "Escrow wire missing"), note(2, "/c/Bank/jan.pdf", "OK")];
+        let links = vec![FileLink {
+            case_number: None,
+            source: "/c/Bank/feb.pdf".to_string(),
+            target: "/c/Bank/jan.pdf".to_string(),
+            relationship: "follow-up to".to_string(),
+            note: "escrow release".to_string(),
+            created_at: String::new(),
+        }];
+
+        let hits = search_all(&items, &notes, &links, " ESCROW ", &SearchAllOptions::default()).hits;
+        let found: Vec<(SearchSource, &str, &str)> = hits
+            .iter()
+            .map(|hit| (hit.source, hit.absolute_path.as_str(), hit.field.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (SearchSource::File, "/c/Bank/jan.pdf", "document_description"),
+                (SearchSource::Note, "/c/Bank/feb.pdf", "content"),
+                (SearchSource::Link, "/c/Bank/feb.pdf", "note"),
+            ]
+        );
+        assert_eq!(hits[1].note_id, Some(1));
+        assert!(search_all(&items, &notes, &links, "  ", &SearchAllOptions::default()).hits.is_empty());
+    }
+
+    #[test]
+    fn search_all_filters_counts_and_pages_each_source() {
+        let mut items: Vec<InventoryItem> = (1..=5).map(|n| item(&format!("{}.pdf", n), "Deposit")).collect();
+        items[4].doc_year = 2020;
+        items[3].document_type = "Letter".to_string();
+        let notes = vec![note(1, "/c/Bank/1.pdf", "deposit slip")];
+
+        let options = SearchAllOptions {
+            sources: vec![SearchSource::File],
+            filter: HashMap::from([("document_type".to_string(), "Statement".to_string())]),
+            year_from: Some(2021),
+            offset: 1,
+            limit_per_source: Some(2),
+            ..SearchAllOptions::default()
+        };
+        let results = search_all(&items, &notes, &[], "deposit", &options);
+        assert_eq!(results.counts, [SourceCount { source: SearchSource::File, total: 3 }]);
+        let paths: Vec<&str> = results.hits.iter().map(|hit| hit.absolute_path.as_str()).collect();
+        assert_eq!(paths, ["/c/Bank/2.pdf", "/c/Bank/3.pdf"]);
+
+        let all = search_all(&items, &notes, &[], "deposit", &SearchAllOptions::default());
+        let totals: Vec<usize> = all.counts.iter().map(|count| count.total).collect();
+        assert_eq!(totals, [5, 1, 0]);
+    }
+}
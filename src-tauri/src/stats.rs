@@ -0,0 +1,119 @@
+use crate::{InventoryItem, INVENTORY_FIELDS};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Distinct value of a field and the number of items that have it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Returns true when the item matches every `field == value` pair in the filter
+pub fn matches_filter(item: &InventoryItem, filter: &HashMap<String, String>) -> bool {
+    filter
+        .iter()
+        .all(|(field, value)| item.field_value(field).as_deref() == Some(value.as_str()))
+}
+
+/// Counts distinct values of `field` across items matching the filter,
+/// most common first. Returns None if the field name is unknown.
+pub fn field_facets(
+    items: &[InventoryItem],
+    field: &str,
+    filter: &HashMap<String, String>,
+) -> Option<Vec<FacetCount>> {
+    if !INVENTORY_FIELDS.contains(&field) {
+        return None;
+    }
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for item in items.iter().filter(|item| matches_filter(item, filter)) {
+        *counts.entry(item.field_value(field)?).or_insert(0) += 1;
+    }
+
+    let mut facets: Vec<FacetCount> = counts
+        .into_iter()
+        .map(|(value, count)| FacetCount { value, count })
+        .collect();
+    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    Some(facets)
+}
+
+/// Histogram bucket size
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistogramBucket {
+    Year,
+    Month,
+}
+
+/// Number of items falling in one bucket, keyed "YYYY" or "YYYY-MM"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistogramBin {
+    pub bucket: String,
+    pub count: usize,
+}
+
+/// Parses the start of a "01-Sep-25 to 30-Sep-25" style date range
+fn parse_date_range_start(range: &str) -> Option<NaiveDate> {
+    let start = range.split(" to ").next()?.trim();
+    NaiveDate::parse_from_str(start, "%d-%b-%y").ok()
+}
+
+/// Returns (year, month) for the item's value of `field`. Month is None when
+/// the field only carries a year.
+fn item_period(item: &InventoryItem, field: &str) -> Option<(i32, Option<u32>)> {
+    match field {
+        "doc_year" if item.doc_year > 0 => Some((item.doc_year, None)),
+        "doc_date_range" => {
+            parse_date_range_start(&item.doc_date_range).map(|d| (d.year(), Some(d.month())))
+        }
+        // Date received is entered in the UI as MM/DD/YYYY
+        "date_rcvd" => NaiveDate::parse_from_str(item.date_rcvd.trim(), "%m/%d/%Y")
+            .ok()
+            .map(|d| (d.year(), Some(d.month()))),
+        _ => None,
+    }
+}
+
+/// Buckets items by date, oldest first, including empty buckets between the
+/// first and last so gaps in coverage are visible. Returns None if the field
+/// is not a date field. Items without a parseable date are skipped; with
+/// monthly buckets, items that only carry a year are skipped too.
+pub fn date_histogram(
+    items: &[InventoryItem],
+    field: &str,
+    bucket: HistogramBucket,
+) -> Option<Vec<HistogramBin>> {
+    if !matches!(field, "doc_year" | "doc_date_range" | "date_rcvd") {
+        return None;
+    }
+
+    // Buckets are keyed by a month index (year * 12 + month) so gaps are easy to fill
+    let mut counts: BTreeMap<i32, usize> = BTreeMap::new();
+    for item in items {
+        let key = match (item_period(item, field), bucket) {
+            (Some((year, _)), HistogramBucket::Year) => year,
+            (Some((year, Some(month))), HistogramBucket::Month) => year * 12 + month as i32 - 1,
+            _ => continue,
+        };
+        *counts.entry(key).or_insert(0) += 1;
+    }
+
+    let (Some(&first), Some(&last)) = (counts.keys().next(), counts.keys().next_back()) else {
+        return Some(Vec::new());
+    };
+
+    let bins = (first..=last)
+        .map(|key| HistogramBin {
+            bucket: match bucket {
+                HistogramBucket::Year => key.to_string(),
+                HistogramBucket::Month => format!("{:04}-{:02}", key.div_euclid(12), key.rem_euclid(12) + 1),
+            },
+            count: counts.get(&key).copied().unwrap_or(0),
+        })
+        .collect();
+    Some(bins)
+}
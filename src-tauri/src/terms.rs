@@ -0,0 +1,168 @@
+use crate::stats::matches_filter;
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Common English words and file-name noise that make poor search terms
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "have", "in", "is", "it", "its", "of",
+    "on", "or", "that", "the", "this", "to", "was", "were", "will", "with", "copy", "final", "scan", "scanned",
+    "doc", "file", "page", "pages", "new", "old", "version", "v1", "v2", "draft",
+];
+
+/// Minimum token length; shorter tokens are mostly initials and noise
+const MIN_TERM_LENGTH: usize = 3;
+
+/// A term or two-word phrase and how often it occurs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TermCount {
+    pub term: String,
+    /// Total occurrences across all items
+    pub occurrences: usize,
+    /// Items containing the term at least once
+    pub documents: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TermFrequencies {
+    pub documents: usize,
+    pub terms: Vec<TermCount>,
+    pub bigrams: Vec<TermCount>,
+}
+
+/// Text an item contributes: its file name without extension, type,
+/// description and notes. Kept separate so bigrams never span two fields.
+pub(crate) fn item_texts(item: &InventoryItem) -> [String; 4] {
+    let stem = Path::new(&item.file_name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    [stem, item.document_type.clone(), item.document_description.clone(), item.notes.clone()]
+}
+
+/// Lowercase word tokens; runs of digits count as words so account and
+/// invoice numbers surface, but pure years and short numbers are dropped
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|token| token.to_lowercase())
+        .filter(|token| {
+            let numeric = token.chars().all(|c| c.is_ascii_digit());
+            token.chars().count() >= MIN_TERM_LENGTH
+                && !STOPWORDS.contains(&token.as_str())
+                && !(numeric && token.len() <= 4)
+        })
+        .collect()
+}
+
+fn top_counts(occurrences: HashMap<String, usize>, documents: &HashMap<String, usize>, limit: usize) -> Vec<TermCount> {
+    let mut counts: Vec<TermCount> = occurrences
+        .into_iter()
+        .map(|(term, occurrences)| TermCount {
+            documents: documents.get(&term).copied().unwrap_or(0),
+            term,
+            occurrences,
+        })
+        .collect();
+    counts.sort_by(|a, b| {
+        b.documents
+            .cmp(&a.documents)
+            .then(b.occurrences.cmp(&a.occurrences))
+            .then(a.term.cmp(&b.term))
+    });
+    counts.truncate(limit);
+    counts
+}
+
+/// Counts terms and adjacent-word bigrams over the items matching `filter`,
+/// ranked by how many items contain them
+pub fn compute_term_frequencies(
+    items: &[InventoryItem],
+    filter: &HashMap<String, String>,
+    limit: usize,
+) -> TermFrequencies {
+    let mut term_occurrences: HashMap<String, usize> = HashMap::new();
+    let mut term_documents: HashMap<String, usize> = HashMap::new();
+    let mut bigram_occurrences: HashMap<String, usize> = HashMap::new();
+    let mut bigram_documents: HashMap<String, usize> = HashMap::new();
+    let mut documents = 0;
+
+    for item in items.iter().filter(|item| matches_filter(item, filter)) {
+        documents += 1;
+        let mut seen_terms = HashSet::new();
+        let mut seen_bigrams = HashSet::new();
+
+        for text in item_texts(item) {
+            let tokens = tokenize(&text);
+            for token in &tokens {
+                *term_occurrences.entry(token.clone()).or_insert(0) += 1;
+                if seen_terms.insert(token.clone()) {
+                    *term_documents.entry(token.clone()).or_insert(0) += 1;
+                }
+            }
+            for pair in tokens.windows(2).filter(|pair| pair[0] != pair[1]) {
+                let bigram = format!("{} {}", pair[0], pair[1]);
+                *bigram_occurrences.entry(bigram.clone()).or_insert(0) += 1;
+                if seen_bigrams.insert(bigram.clone()) {
+                    *bigram_documents.entry(bigram).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    // A bigram seen in a single item says nothing about the population
+    bigram_occurrences.retain(|bigram, _| bigram_documents.get(bigram).copied().unwrap_or(0) > 1);
+
+    TermFrequencies {
+        documents,
+        terms: top_counts(term_occurrences, &term_documents, limit),
+        bigrams: top_counts(bigram_occurrences, &bigram_documents, limit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+
+    fn item(file_name: &str, document_type: &str, document_description: &str) -> InventoryItem {
+        InventoryItem {
+            document_type: document_type.to_string(),
+            document_description: document_description.to_string(),
+            ..test_item(file_name)
+        }
+    }
+
+    #[test]
+    fn tokens_drop_stopwords_short_words_and_years() {
+        assert_eq!(
+            tokenize("Scan of the Wire Transfer 2024-03 acct 100234 to J.R. Smith"),
+            ["wire", "transfer", "acct", "100234", "smith"]
+        );
+    }
+
+    #[test]
+    fn terms_are_ranked_by_the_items_containing_them() {
+        let items = vec![
+            item("wire_transfer_jan.pdf", "Statement", "Wire transfer to escrow"),
+            item("wire_transfer_feb.pdf", "Statement", "Escrow deposit"),
+            item("lease.pdf", "Contract", "Lease lease lease"),
+        ];
+
+        let all = compute_term_frequencies(&items, &HashMap::new(), 3);
+        assert_eq!(all.documents, 3);
+        let terms: Vec<(&str, usize, usize)> =
+            all.terms.iter().map(|term| (term.term.as_str(), term.documents, term.occurrences)).collect();
+        assert_eq!(terms, [("transfer", 2, 3), ("wire", 2, 3), ("escrow", 2, 2)]);
+        // Bigrams never span fields, and one item alone does not count
+        let bigrams: Vec<&str> = all.bigrams.iter().map(|bigram| bigram.term.as_str()).collect();
+        assert_eq!(bigrams, ["wire transfer"]);
+        assert_eq!((all.bigrams[0].documents, all.bigrams[0].occurrences), (2, 3));
+
+        let contracts = HashMap::from([("document_type".to_string(), "Contract".to_string())]);
+        let filtered = compute_term_frequencies(&items, &contracts, 10);
+        assert_eq!(filtered.documents, 1);
+        assert_eq!((filtered.terms[0].term.as_str(), filtered.terms[0].occurrences), ("lease", 4));
+        assert!(filtered.bigrams.is_empty());
+    }
+}
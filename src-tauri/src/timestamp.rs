@@ -0,0 +1,372 @@
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// DER of the SHA-256 algorithm identifier (2.16.840.1.101.3.4.2.1, NULL params)
+const SHA256_ALGORITHM: &[u8] = &[
+    0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00,
+];
+
+const TSA_TIMEOUT: Duration = Duration::from_secs(30);
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_UTF8_STRING: u8 = 0x0c;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+const TAG_EXPLICIT_0: u8 = 0xa0;
+
+/// A trusted timestamp obtained for a digest. The full TSA response is saved
+/// as a `.tsr` file that `openssl ts -verify` accepts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimestampReceipt {
+    pub tsa_url: String,
+    /// SHA-256 digest that was timestamped, hex
+    pub digest: String,
+    /// Time asserted by the TSA, UTC
+    pub gen_time: String,
+    /// TSA serial number of the token, hex
+    pub serial_number: String,
+    pub token_path: String,
+}
+
+/// Token details read back from a granted response
+pub struct GrantedToken {
+    pub response: Vec<u8>,
+    pub gen_time: String,
+    pub serial_number: String,
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim();
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len.to_be_bytes().into_iter().skip_while(|byte| *byte == 0).collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+/// Minimal two's-complement content bytes for an unsigned integer
+fn integer_content(value: u64) -> Vec<u8> {
+    let mut bytes: Vec<u8> = value.to_be_bytes().into_iter().skip_while(|byte| *byte == 0).collect();
+    if bytes.first().is_none_or(|byte| byte & 0x80 != 0) {
+        bytes.insert(0, 0);
+    }
+    bytes
+}
+
+/// Splits one DER element off `data`: (tag, content, remainder)
+fn read_tlv(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = data.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        // Indefinite lengths are BER only; TSAs answer in DER
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count].iter().fold(0usize, |len, byte| (len << 8) | *byte as usize);
+        (len, &rest[count..])
+    };
+    if rest.len() < len {
+        return None;
+    }
+    Some((tag, &rest[..len], &rest[len..]))
+}
+
+fn expect_tlv(data: &[u8], tag: u8) -> Result<(&[u8], &[u8]), String> {
+    match read_tlv(data) {
+        Some((found, content, rest)) if found == tag => Ok((content, rest)),
+        _ => Err("malformed timestamp response".to_string()),
+    }
+}
+
+/// TimeStampReq for a SHA-256 digest, asking for the TSA certificate to be
+/// included so the token can be verified on its own
+fn build_request(digest: &[u8], nonce: u64) -> Vec<u8> {
+    let imprint = der(TAG_SEQUENCE, &[SHA256_ALGORITHM, &der(TAG_OCTET_STRING, digest)].concat());
+    let body = [
+        der(TAG_INTEGER, &[1]),
+        imprint,
+        der(TAG_INTEGER, &integer_content(nonce)),
+        der(TAG_BOOLEAN, &[0xff]),
+    ]
+    .concat();
+    der(TAG_SEQUENCE, &body)
+}
+
+/// Posts the request over plain HTTP/1.0, which keeps the reply unchunked.
+/// Tokens are signed by the TSA, so RFC 3161 services are commonly served
+/// over http; https endpoints are not supported.
+fn post(tsa_url: &str, body: &[u8]) -> Result<Vec<u8>, String> {
+    let Some(rest) = tsa_url.trim().strip_prefix("http://") else {
+        return Err(format!("TSA URL must start with http:// ({})", tsa_url));
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let address = if authority.contains(':') { authority.to_string() } else { format!("{}:80", authority) };
+    let socket = address
+        .to_socket_addrs()
+        .map_err(|e| format!("{}: {}", authority, e))?
+        .next()
+        .ok_or_else(|| format!("{}: no address", authority))?;
+
+    let mut stream = TcpStream::connect_timeout(&socket, TSA_TIMEOUT).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(TSA_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(TSA_TIMEOUT)).map_err(|e| e.to_string())?;
+    let header = format!(
+        "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/timestamp-query\r\nAccept: application/timestamp-reply\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        path,
+        authority,
+        body.len()
+    );
+    stream.write_all(header.as_bytes()).map_err(|e| e.to_string())?;
+    stream.write_all(body).map_err(|e| e.to_string())?;
+
+    let mut reply = Vec::new();
+    stream.read_to_end(&mut reply).map_err(|e| e.to_string())?;
+    let split = reply
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| "incomplete HTTP response".to_string())?;
+    let status_line = String::from_utf8_lossy(&reply[..split]).lines().next().unwrap_or_default().to_string();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("TSA returned {}", status_line));
+    }
+    Ok(reply[split + 4..].to_vec())
+}
+
+/// "20250101123000Z" or with fractional seconds, as "2025-01-01 12:30:00 UTC"
+fn format_generalized_time(raw: &[u8]) -> String {
+    let text = String::from_utf8_lossy(raw);
+    let digits = text.trim_end_matches('Z');
+    match (digits.get(0..4), digits.get(4..6), digits.get(6..8), digits.get(8..10), digits.get(10..12), digits.get(12..)) {
+        (Some(y), Some(mo), Some(d), Some(h), Some(mi), Some(s)) => {
+            format!("{}-{}-{} {}:{}:{} UTC", y, mo, d, h, mi, s)
+        }
+        _ => text.to_string(),
+    }
+}
+
+/// Checks the response status and that the token covers our digest and
+/// nonce. The TSA's signature is not checked here; `openssl ts -verify`
+/// with the TSA's CA certificate does that.
+fn parse_response(response: &[u8], digest: &[u8], nonce: u64) -> Result<(String, String), String> {
+    let (resp, _) = expect_tlv(response, TAG_SEQUENCE)?;
+    let (status_info, token) = expect_tlv(resp, TAG_SEQUENCE)?;
+    let (status, status_rest) = expect_tlv(status_info, TAG_INTEGER)?;
+    // 0 = granted, 1 = granted with modifications
+    if !matches!(status, [0] | [1]) {
+        let text = expect_tlv(status_rest, TAG_SEQUENCE)
+            .and_then(|(free_text, _)| expect_tlv(free_text, TAG_UTF8_STRING))
+            .map(|(line, _)| format!(": {}", String::from_utf8_lossy(line)))
+            .unwrap_or_default();
+        return Err(format!("TSA rejected the request (status {}){}", status.last().copied().unwrap_or(0), text));
+    }
+
+    // ContentInfo -> SignedData -> encapContentInfo -> TSTInfo
+    let (content_info, _) = expect_tlv(token, TAG_SEQUENCE)?;
+    let (_, rest) = expect_tlv(content_info, TAG_OID)?;
+    let (explicit, _) = expect_tlv(rest, TAG_EXPLICIT_0)?;
+    let (signed_data, _) = expect_tlv(explicit, TAG_SEQUENCE)?;
+    let (_, rest) = expect_tlv(signed_data, TAG_INTEGER)?;
+    let (_, rest) = expect_tlv(rest, TAG_SET)?;
+    let (encap, _) = expect_tlv(rest, TAG_SEQUENCE)?;
+    let (_, rest) = expect_tlv(encap, TAG_OID)?;
+    let (explicit, _) = expect_tlv(rest, TAG_EXPLICIT_0)?;
+    let (tst_der, _) = expect_tlv(explicit, TAG_OCTET_STRING)?;
+    let (tst_info, _) = expect_tlv(tst_der, TAG_SEQUENCE)?;
+
+    let (_, rest) = expect_tlv(tst_info, TAG_INTEGER)?;
+    let (_, rest) = expect_tlv(rest, TAG_OID)?;
+    let (imprint, rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+    let (serial, rest) = expect_tlv(rest, TAG_INTEGER)?;
+    let (gen_time, mut rest) = expect_tlv(rest, TAG_GENERALIZED_TIME)?;
+
+    let (_, hashed) = expect_tlv(imprint, TAG_SEQUENCE)?;
+    let (hashed, _) = expect_tlv(hashed, TAG_OCTET_STRING)?;
+    if hashed != digest {
+        return Err("timestamp token covers a different digest".to_string());
+    }
+    // Optional accuracy and ordering precede the nonce
+    let mut echoed = None;
+    while let Some((tag, content, next)) = read_tlv(rest) {
+        if tag == TAG_INTEGER {
+            echoed = Some(content);
+            break;
+        }
+        rest = next;
+    }
+    if echoed != Some(integer_content(nonce).as_slice()) {
+        return Err("timestamp token does not echo the request nonce".to_string());
+    }
+
+    Ok((format_generalized_time(gen_time), to_hex(serial)))
+}
+
+/// Requests an RFC 3161 timestamp token for a SHA-256 digest from `tsa_url`
+pub fn request_timestamp(tsa_url: &str, digest: &[u8]) -> Result<GrantedToken, String> {
+    if digest.len() != 32 {
+        return Err("digest must be SHA-256".to_string());
+    }
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default()
+        ^ ((std::process::id() as u64) << 32);
+    let response = post(tsa_url, &build_request(digest, nonce))?;
+    let (gen_time, serial_number) = parse_response(&response, digest, nonce)?;
+    Ok(GrantedToken {
+        response,
+        gen_time,
+        serial_number,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: [u8; 32] = [0xab; 32];
+    const NONCE: u64 = 0x0102_0304_0506_0708;
+
+    /// A granted response with the fields `parse_response` reads
+    fn granted_response() -> Vec<u8> {
+        let imprint = der(TAG_SEQUENCE, &[SHA256_ALGORITHM, &der(TAG_OCTET_STRING, &DIGEST)].concat());
+        let tst_info = der(
+            TAG_SEQUENCE,
+            &[
+                der(TAG_INTEGER, &[1]),
+                der(TAG_OID, &[0x2a, 0x03]),
+                imprint,
+                der(TAG_INTEGER, &[0x12, 0x34]),
+                der(TAG_GENERALIZED_TIME, b"20250101123000Z"),
+                der(TAG_INTEGER, &integer_content(NONCE)),
+            ]
+            .concat(),
+        );
+        let encap = der(
+            TAG_SEQUENCE,
+            &[der(TAG_OID, &[0x2a, 0x04]), der(TAG_EXPLICIT_0, &der(TAG_OCTET_STRING, &tst_info))].concat(),
+        );
+        let signed_data = der(TAG_SEQUENCE, &[der(TAG_INTEGER, &[3]), der(TAG_SET, &[]), encap].concat());
+        let token = der(
+            TAG_SEQUENCE,
+            &[der(TAG_OID, &[0x2a, 0x05]), der(TAG_EXPLICIT_0, &signed_data)].concat(),
+        );
+        der(TAG_SEQUENCE, &[der(TAG_SEQUENCE, &der(TAG_INTEGER, &[0])), token].concat())
+    }
+
+    #[test]
+    fn read_tlv_rejects_malformed_lengths() {
+        let cases: &[(&str, &[u8])] = &[
+            ("empty", &[]),
+            ("tag only", &[TAG_SEQUENCE]),
+            ("short form past the end", &[TAG_SEQUENCE, 0x03, 0x01, 0x02]),
+            ("indefinite length", &[TAG_SEQUENCE, 0x80, 0x00, 0x00]),
+            ("too many length bytes", &[TAG_SEQUENCE, 0x85, 0x01, 0x00, 0x00, 0x00, 0x00]),
+            ("truncated length bytes", &[TAG_SEQUENCE, 0x82, 0x01]),
+            ("long form past the end", &[TAG_SEQUENCE, 0x84, 0xff, 0xff, 0xff, 0xff, 0x00]),
+        ];
+        for (name, data) in cases {
+            assert_eq!(read_tlv(data), None, "{}", name);
+        }
+    }
+
+    /// (data, tag, content, remainder)
+    type TlvCase = (&'static [u8], u8, &'static [u8], &'static [u8]);
+
+    #[test]
+    fn read_tlv_splits_elements() {
+        let cases: &[TlvCase] = &[
+            (&[TAG_INTEGER, 0x00], TAG_INTEGER, &[], &[]),
+            (&[TAG_INTEGER, 0x01, 0x07, 0xff], TAG_INTEGER, &[0x07], &[0xff]),
+            (&[TAG_OCTET_STRING, 0x81, 0x02, 0x0a, 0x0b], TAG_OCTET_STRING, &[0x0a, 0x0b], &[]),
+        ];
+        for (data, tag, content, rest) in cases {
+            assert_eq!(read_tlv(data), Some((*tag, *content, *rest)));
+        }
+        let long = der(TAG_OCTET_STRING, &[0x55; 300]);
+        assert_eq!(read_tlv(&long), Some((TAG_OCTET_STRING, &[0x55; 300][..], &[][..])));
+    }
+
+    #[test]
+    fn parse_response_reads_a_granted_token() {
+        let (gen_time, serial) = parse_response(&granted_response(), &DIGEST, NONCE).unwrap();
+        assert_eq!(gen_time, "2025-01-01 12:30:00 UTC");
+        assert_eq!(serial, "1234");
+    }
+
+    #[test]
+    fn parse_response_rejects_mismatches() {
+        let response = granted_response();
+        assert!(parse_response(&response, &[0xcd; 32], NONCE).is_err());
+        assert!(parse_response(&response, &DIGEST, NONCE + 1).is_err());
+    }
+
+    #[test]
+    fn parse_response_rejects_truncated_and_garbage_input() {
+        let response = granted_response();
+        for len in 0..response.len() {
+            assert!(parse_response(&response[..len], &DIGEST, NONCE).is_err(), "truncated to {} bytes", len);
+        }
+        let cases: &[&[u8]] = &[
+            &[0xff; 64],
+            &[TAG_SEQUENCE, 0x84, 0xff, 0xff, 0xff, 0xff],
+            &[TAG_SEQUENCE, 0x03, TAG_SEQUENCE, 0x01, TAG_INTEGER],
+            b"HTTP/1.0 200 OK",
+        ];
+        for data in cases {
+            assert!(parse_response(data, &DIGEST, NONCE).is_err());
+        }
+    }
+
+    #[test]
+    fn parse_response_reports_rejections() {
+        let status = der(
+            TAG_SEQUENCE,
+            &[der(TAG_INTEGER, &[2]), der(TAG_SEQUENCE, &der(TAG_UTF8_STRING, b"bad request"))].concat(),
+        );
+        let response = der(TAG_SEQUENCE, &status);
+        assert_eq!(
+            parse_response(&response, &DIGEST, NONCE),
+            Err("TSA rejected the request (status 2): bad request".to_string())
+        );
+    }
+
+    #[test]
+    fn from_hex_rejects_invalid_input() {
+        assert_eq!(from_hex("0aff"), Some(vec![0x0a, 0xff]));
+        for hex in ["abc", "zz", "éé"] {
+            assert_eq!(from_hex(hex), None, "{}", hex);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::hashing::{hash_file, HashAlgorithm};
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const TOOLS_FILE: &str = "external_tools.json";
+
+/// Tools still running after this long are killed
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// Output beyond this many bytes per stream is dropped
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// A "send to tool" action, e.g. exiftool or an in-house script. The
+/// program runs directly, never through a shell; `{path}`, `{hash}` and
+/// `{name}` in an argument are replaced with the document's absolute path,
+/// SHA-256 and file name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExternalTool {
+    pub id: String,
+    pub name: String,
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// What a tool run printed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolOutput {
+    /// Exit code; none if the tool was killed or ended by a signal
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl ToolOutput {
+    /// The run as note text: the command line, then whatever it printed
+    pub fn note_text(&self, tool: &ExternalTool, args: &[String]) -> String {
+        let mut text = format!("$ {} {}", tool.program, args.join(" ")).trim_end().to_string();
+        for output in [&self.stdout, &self.stderr] {
+            if !output.trim().is_empty() {
+                text.push_str("\n\n");
+                text.push_str(output.trim_end());
+            }
+        }
+        match (self.timed_out, self.exit_code) {
+            (true, _) => text.push_str("\n\n(killed after timing out)"),
+            (false, Some(0)) => {}
+            (false, Some(code)) => text.push_str(&format!("\n\n(exited with code {})", code)),
+            (false, None) => text.push_str("\n\n(ended by a signal)"),
+        }
+        text
+    }
+}
+
+fn tools_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(TOOLS_FILE)
+}
+
+pub fn list_tools(data_dir: &Path) -> Result<Vec<ExternalTool>, Box<dyn std::error::Error>> {
+    json_store::read(&tools_path(data_dir))
+}
+
+pub fn find_tool(data_dir: &Path, id: &str) -> Result<Option<ExternalTool>, Box<dyn std::error::Error>> {
+    Ok(list_tools(data_dir)?.into_iter().find(|tool| tool.id == id))
+}
+
+/// Adds a tool, or replaces the one with the same id
+pub fn save_tool(data_dir: &Path, tool: ExternalTool) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&tools_path(data_dir), |all: &mut Vec<ExternalTool>| {
+        match all.iter_mut().find(|existing| existing.id == tool.id) {
+            Some(existing) => *existing = tool,
+            None => all.push(tool),
+        }
+    })
+}
+
+pub fn remove_tool(data_dir: &Path, id: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut removed = false;
+    json_store::update(&tools_path(data_dir), |all: &mut Vec<ExternalTool>| {
+        let before = all.len();
+        all.retain(|tool| tool.id != id);
+        removed = all.len() != before;
+    })?;
+    Ok(removed)
+}
+
+/// The tool's arguments with the placeholders filled in. The file is only
+/// hashed when an argument asks for `{hash}`.
+pub fn expand_args(tool: &ExternalTool, path: &Path) -> std::io::Result<Vec<String>> {
+    let hash = if tool.args.iter().any(|arg| arg.contains("{hash}")) {
+        hash_file(path, HashAlgorithm::Sha256)?
+    } else {
+        String::new()
+    };
+    let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let path = path.to_string_lossy();
+    let values = [("{path}", path.as_ref()), ("{hash}", hash.as_str()), ("{name}", name.as_str())];
+    Ok(tool.args.iter().map(|arg| expand_placeholders(arg, &values)).collect())
+}
+
+/// Replaces placeholders in one left-to-right pass, so a value that itself
+/// contains a placeholder (a file named `x{name}.pdf`) is kept as it is
+fn expand_placeholders(arg: &str, values: &[(&str, &str)]) -> String {
+    let mut expanded = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match values.iter().find(|(placeholder, _)| rest.starts_with(placeholder)) {
+            Some((placeholder, value)) => {
+                expanded.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                expanded.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Once the tool has exited, how long to wait for its output to close. A
+/// background process it started can keep the streams open indefinitely.
+const OUTPUT_GRACE: Duration = Duration::from_secs(2);
+
+/// Reads one output stream on its own thread, keeping the first
+/// `MAX_OUTPUT_BYTES` and draining the rest so a chatty tool never blocks on
+/// a full pipe
+struct OutputReader {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    done: mpsc::Receiver<()>,
+}
+
+impl OutputReader {
+    fn spawn(stream: Option<impl Read + Send + 'static>) -> Self {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let (sender, done) = mpsc::channel();
+        if let Some(mut stream) = stream {
+            let buffer = Arc::clone(&buffer);
+            thread::spawn(move || {
+                let mut chunk = [0u8; 8192];
+                loop {
+                    match stream.read(&mut chunk) {
+                        Ok(0) => break,
+                        Ok(read) => {
+                            let mut buffer = buffer.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                            let room = MAX_OUTPUT_BYTES.saturating_sub(buffer.len());
+                            buffer.extend_from_slice(&chunk[..read.min(room)]);
+                        }
+                        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                        Err(_) => break,
+                    }
+                }
+                let _ = sender.send(());
+            });
+        }
+        Self { buffer, done }
+    }
+
+    /// Whatever was read by `deadline`, whether or not the stream has closed
+    fn collect(self, deadline: Instant) -> String {
+        let _ = self.done.recv_timeout(deadline.saturating_duration_since(Instant::now()));
+        let buffer = self.buffer.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        String::from_utf8_lossy(&buffer).to_string()
+    }
+}
+
+/// Runs the tool with already expanded arguments, killing it once it runs
+/// past its timeout
+pub fn run_tool(tool: &ExternalTool, args: &[String]) -> std::io::Result<ToolOutput> {
+    let mut child = Command::new(&tool.program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = OutputReader::spawn(child.stdout.take());
+    let stderr = OutputReader::spawn(child.stderr.take());
+
+    let deadline = Instant::now() + Duration::from_secs(tool.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let (status, timed_out) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (Some(status), false);
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            break (None, true);
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    let output_deadline = Instant::now() + OUTPUT_GRACE;
+    Ok(ToolOutput {
+        exit_code: status.and_then(|status| status.code()),
+        timed_out,
+        stdout: stdout.collect(output_deadline),
+        stderr: stderr.collect(output_deadline),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn tool(program: &str, args: &[&str]) -> ExternalTool {
+        ExternalTool {
+            id: "t".to_string(),
+            name: "Tool".to_string(),
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn placeholders_are_filled_per_argument() {
+        let dir = std::env::temp_dir().join(format!("tools-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("it's; rm -rf.txt");
+        fs::write(&file, "abc").unwrap();
+
+        let args = expand_args(&tool("pdfid", &["-n", "{path}", "--sha={hash}", "{name}"]), &file).unwrap();
+        assert_eq!(args[1], file.to_string_lossy());
+        assert_eq!(args[2], "--sha=ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(args[3], "it's; rm -rf.txt");
+
+        // File names holding placeholders are not expanded again
+        let file = dir.join("x{name}{hash}.pdf");
+        fs::write(&file, "abc").unwrap();
+        let args = expand_args(&tool("pdfid", &["{name}", "{path}:{name}", "{other}{name"]), &file).unwrap();
+        assert_eq!(args[0], "x{name}{hash}.pdf");
+        assert_eq!(args[1], format!("{}:x{{name}}{{hash}}.pdf", file.to_string_lossy()));
+        assert_eq!(args[2], "{other}{name");
+
+        save_tool(&dir, tool("exiftool", &["{path}"])).unwrap();
+        save_tool(&dir, tool("exiftool", &["-j", "{path}"])).unwrap();
+        assert_eq!(list_tools(&dir).unwrap().len(), 1);
+        assert_eq!(find_tool(&dir, "t").unwrap().unwrap().args, ["-j", "{path}"]);
+        assert!(remove_tool(&dir, "t").unwrap());
+        assert!(!remove_tool(&dir, "t").unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_without_a_shell_and_captures_output() {
+        let echo = tool("echo", &[]);
+        let output = run_tool(&echo, &["a; echo b".to_string()]).unwrap();
+        assert_eq!(output.stdout, "a; echo b\n");
+        assert_eq!(output.exit_code, Some(0));
+        assert_eq!(output.note_text(&echo, &["x".to_string()]), "$ echo x\n\na; echo b");
+
+        let mut sleep = tool("sleep", &[]);
+        sleep.timeout_secs = Some(0);
+        let output = run_tool(&sleep, &["5".to_string()]).unwrap();
+        assert!(output.timed_out);
+        assert!(output.note_text(&sleep, &[]).ends_with("(killed after timing out)"));
+
+        // A background process holding the output open does not hold up the run
+        let started = Instant::now();
+        let output = run_tool(&tool("sh", &[]), &["-c".to_string(), "sleep 10 & echo started".to_string()]).unwrap();
+        assert_eq!(output.stdout, "started\n");
+        assert_eq!(output.exit_code, Some(0));
+        assert!(started.elapsed() < Duration::from_secs(8));
+    }
+}
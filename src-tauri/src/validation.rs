@@ -0,0 +1,256 @@
+use crate::{json_store, InventoryItem};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const FIELD_RULES_FILE: &str = "field_rules.json";
+
+/// Compiled size allowed for one format pattern, so a huge pattern is
+/// refused instead of slowing every edit
+const PATTERN_SIZE_LIMIT: usize = 1 << 16;
+
+/// Format patterns compiled so far, by pattern text. Rules are checked on
+/// every edit and against every row at finalization, so each is compiled
+/// only once.
+static COMPILED_PATTERNS: Mutex<Option<HashMap<String, Regex>>> = Mutex::new(None);
+
+/// Constraints on one inventory field, checked on edit and before finalizing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldRule {
+    pub field: String,
+    /// The field must be filled in before the inventory is finalized
+    #[serde(default)]
+    pub required: bool,
+    /// Format as a regular expression that must match the whole value, e.g.
+    /// `ABC\d{6}` for Bates numbers. Case-insensitive.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// When not empty, the only values accepted (case-insensitive)
+    #[serde(default)]
+    pub allowed_values: Vec<String>,
+}
+
+/// A value that breaks a field rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleViolation {
+    pub absolute_path: String,
+    pub file_name: String,
+    pub field: String,
+    pub value: String,
+    pub problem: String,
+}
+
+/// The field rules saved for one case
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CaseFieldRules {
+    case_number: Option<String>,
+    rules: Vec<FieldRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalizationCheck {
+    /// True when no item breaks any rule
+    pub ready: bool,
+    pub violations: Vec<RuleViolation>,
+}
+
+/// Compiles a format pattern, anchored so it must match the whole value
+pub fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    let mut compiled = COMPILED_PATTERNS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let compiled = compiled.get_or_insert_with(HashMap::new);
+    if let Some(regex) = compiled.get(pattern) {
+        return Ok(regex.clone());
+    }
+    let regex = RegexBuilder::new(&format!("^(?:{})$", pattern))
+        .case_insensitive(true)
+        .size_limit(PATTERN_SIZE_LIMIT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    compiled.insert(pattern.to_string(), regex.clone());
+    Ok(regex)
+}
+
+/// Checks a value against a rule. Empty values only break `required`, so
+/// format and allowed-value rules never block leaving a field blank.
+pub fn check_value(rule: &FieldRule, value: &str, finalizing: bool) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return (finalizing && rule.required).then(|| "Required".to_string());
+    }
+    if let Some(pattern) = rule.pattern.as_deref().filter(|p| !p.is_empty()) {
+        match compile_pattern(pattern) {
+            Ok(regex) if regex.is_match(value) => {}
+            Ok(_) => return Some(format!("Does not match format {}", pattern)),
+            Err(e) => return Some(format!("Format {} is not valid: {}", pattern, e)),
+        }
+    }
+    if !rule.allowed_values.is_empty() && !rule.allowed_values.iter().any(|v| v.trim().eq_ignore_ascii_case(value)) {
+        return Some(format!("Not one of: {}", rule.allowed_values.join(", ")));
+    }
+    None
+}
+
+fn rules_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(FIELD_RULES_FILE)
+}
+
+/// The field rules saved for a case
+pub fn case_field_rules(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<FieldRule>, Box<dyn std::error::Error>> {
+    Ok(json_store::read::<Vec<CaseFieldRules>>(&rules_path(data_dir))?
+        .into_iter()
+        .find(|saved| saved.case_number.as_deref() == case_number)
+        .map(|saved| saved.rules)
+        .unwrap_or_default())
+}
+
+/// Replaces a case's field rules; an empty list removes them
+pub fn save_field_rules(data_dir: &Path, case_number: Option<&str>, rules: Vec<FieldRule>) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&rules_path(data_dir), |all: &mut Vec<CaseFieldRules>| {
+        all.retain(|saved| saved.case_number.as_deref() != case_number);
+        if !rules.is_empty() {
+            all.push(CaseFieldRules {
+                case_number: case_number.map(|s| s.to_string()),
+                rules,
+            });
+        }
+    })
+}
+
+/// The first rule on `field` that `value` breaks while editing, if any
+pub fn check_edit(rules: &[FieldRule], field: &str, value: &str) -> Option<String> {
+    rules.iter().filter(|rule| rule.field == field).find_map(|rule| check_value(rule, value, false))
+}
+
+/// Lists every value that would block finalization, in item order
+pub fn validate_for_finalization(items: &[InventoryItem], rules: &[FieldRule]) -> FinalizationCheck {
+    let mut violations = Vec::new();
+    for item in items {
+        for rule in rules {
+            let Some(value) = item.field_value(&rule.field) else {
+                continue;
+            };
+            if let Some(problem) = check_value(rule, &value, true) {
+                violations.push(RuleViolation {
+                    absolute_path: item.absolute_path.clone(),
+                    file_name: item.file_name.clone(),
+                    field: rule.field.clone(),
+                    value,
+                    problem,
+                });
+            }
+        }
+    }
+
+    FinalizationCheck {
+        ready: violations.is_empty(),
+        violations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+    use std::fs;
+
+    fn rule(field: &str) -> FieldRule {
+        FieldRule {
+            field: field.to_string(),
+            required: false,
+            pattern: None,
+            allowed_values: Vec::new(),
+        }
+    }
+
+    fn item(file_name: &str, bates_stamp: &str, notes: &str) -> InventoryItem {
+        InventoryItem {
+            document_type: "Statement".to_string(),
+            folder_name: "Bank".to_string(),
+            folder_path: "Bank".to_string(),
+            bates_stamp: bates_stamp.to_string(),
+            notes: notes.to_string(),
+            ..test_item(&format!("/c/Bank/{}", file_name))
+        }
+    }
+
+    #[test]
+    fn required_values_only_block_finalizing() {
+        let required = FieldRule { required: true, ..rule("bates_stamp") };
+        assert_eq!(check_value(&required, "  ", true).as_deref(), Some("Required"));
+        assert!(check_value(&required, "", false).is_none());
+        assert!(check_value(&required, "ABC000001", true).is_none());
+    }
+
+    #[test]
+    fn formats_must_match_the_whole_value() {
+        let bates = FieldRule { pattern: Some(r"ABC\d{6}".to_string()), ..rule("bates_stamp") };
+        assert!(check_value(&bates, "abc000123", false).is_none());
+        assert!(check_value(&bates, "ABC00012", false).is_some());
+        assert!(check_value(&bates, "XABC000123", false).is_some());
+        assert!(check_value(&bates, "", true).is_none());
+
+        // Matching takes linear time, so patterns that backtrack badly elsewhere stay fast
+        let nested = FieldRule { pattern: Some("(a*)*b".to_string()), ..rule("notes") };
+        assert!(check_value(&nested, &"a".repeat(10_000), false).is_some());
+
+        let broken = FieldRule { pattern: Some("ABC(".to_string()), ..rule("bates_stamp") };
+        assert!(compile_pattern("ABC(").is_err());
+        assert!(check_value(&broken, "ABC", false).unwrap().contains("not valid"));
+        assert!(compile_pattern(&format!("a{{{}}}", 100_000)).is_err());
+    }
+
+    #[test]
+    fn allowed_values_ignore_case_and_padding() {
+        let status = FieldRule {
+            allowed_values: vec!["Produced".to_string(), " Withheld ".to_string()],
+            ..rule("notes")
+        };
+        assert!(check_value(&status, "withheld", false).is_none());
+        assert_eq!(check_value(&status, "Pending", false).as_deref(), Some("Not one of: Produced,  Withheld "));
+    }
+
+    #[test]
+    fn finalization_lists_every_breaking_value_in_item_order() {
+        let rules = vec![
+            FieldRule { required: true, pattern: Some(r"ABC\d{6}".to_string()), ..rule("bates_stamp") },
+            FieldRule { allowed_values: vec!["Produced".to_string()], ..rule("notes") },
+        ];
+        let items = vec![
+            item("jan.pdf", "", "Produced"),
+            item("feb.pdf", "ABC000002", ""),
+            item("mar.pdf", "ABC3", "Pending"),
+        ];
+
+        let check = validate_for_finalization(&items, &rules);
+        assert!(!check.ready);
+        let found: Vec<(&str, &str, &str)> = check
+            .violations
+            .iter()
+            .map(|v| (v.file_name.as_str(), v.field.as_str(), v.value.as_str()))
+            .collect();
+        assert_eq!(found, [("jan.pdf", "bates_stamp", ""), ("mar.pdf", "bates_stamp", "ABC3"), ("mar.pdf", "notes", "Pending")]);
+        assert!(validate_for_finalization(&items[1..2], &rules).ready);
+    }
+
+    #[test]
+    fn rules_are_saved_per_case_and_checked_on_edit() {
+        let dir = std::env::temp_dir().join(format!("field-rules-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let status = FieldRule { allowed_values: vec!["Produced".to_string()], ..rule("notes") };
+        save_field_rules(&dir, Some("A-1"), vec![status]).unwrap();
+        save_field_rules(&dir, Some("B-2"), vec![rule("bates_stamp")]).unwrap();
+
+        let rules = case_field_rules(&dir, Some("A-1")).unwrap();
+        assert!(check_edit(&rules, "notes", "Withheld").is_some());
+        assert!(check_edit(&rules, "notes", "produced").is_none());
+        assert!(check_edit(&rules, "bates_stamp", "Withheld").is_none());
+
+        save_field_rules(&dir, Some("A-1"), Vec::new()).unwrap();
+        assert!(case_field_rules(&dir, Some("A-1")).unwrap().is_empty());
+        assert_eq!(case_field_rules(&dir, Some("B-2")).unwrap().len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
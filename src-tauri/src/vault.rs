@@ -0,0 +1,215 @@
+use crate::hashing::{copy_verified, hash_file, verification_error, HashAlgorithm};
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const VAULT_INDEX_FILE: &str = "vault_index.json";
+const VAULT_SETTINGS_FILE: &str = "vault.json";
+
+/// Directory under the case's data directory holding vaulted copies, named
+/// by content hash so identical files are stored once
+const VAULT_DIR: &str = "vault";
+
+/// A file whose content is kept in the case's vault
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VaultEntry {
+    pub case_number: Option<String>,
+    pub absolute_path: String,
+    /// SHA-256 of the content; also names the stored copy
+    pub hash: String,
+    pub size_bytes: u64,
+    pub stored_at: String,
+    /// Whether the stored copy was read back and matched the source's hash
+    #[serde(default)]
+    pub verified: bool,
+}
+
+/// How much a case's vault holds
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct VaultReport {
+    pub enabled: bool,
+    /// Files with a copy in the vault
+    pub files: usize,
+    /// Distinct contents actually stored
+    pub stored_copies: usize,
+    /// Bytes the stored copies take up
+    pub stored_bytes: u64,
+    /// Bytes the files would take without deduplication
+    pub file_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CaseVault {
+    case_number: Option<String>,
+    enabled: bool,
+}
+
+fn index_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(VAULT_INDEX_FILE)
+}
+
+fn vault_dir(data_dir: &Path, case_number: Option<&str>) -> PathBuf {
+    match case_number.filter(|c| !c.trim().is_empty()) {
+        Some(case_number) => json_store::case_data_dir(data_dir, case_number).join(VAULT_DIR),
+        None => data_dir.join(VAULT_DIR),
+    }
+}
+
+pub fn vault_enabled(data_dir: &Path, case_number: Option<&str>) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(json_store::read::<Vec<CaseVault>>(&data_dir.join(VAULT_SETTINGS_FILE))?
+        .into_iter()
+        .any(|setting| setting.case_number.as_deref() == case_number && setting.enabled))
+}
+
+/// Turns the vault on or off for a case. Turning it off keeps the copies
+/// already stored.
+pub fn set_vault_enabled(data_dir: &Path, case_number: Option<&str>, enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&data_dir.join(VAULT_SETTINGS_FILE), |all: &mut Vec<CaseVault>| {
+        all.retain(|setting| setting.case_number.as_deref() != case_number);
+        if enabled {
+            all.push(CaseVault {
+                case_number: case_number.map(|s| s.to_string()),
+                enabled,
+            });
+        }
+    })
+}
+
+fn case_entries(data_dir: &Path, case_number: Option<&str>) -> Result<Vec<VaultEntry>, Box<dyn std::error::Error>> {
+    let entries: Vec<VaultEntry> = json_store::read(&index_path(data_dir))?;
+    Ok(entries.into_iter().filter(|entry| entry.case_number.as_deref() == case_number).collect())
+}
+
+/// Copies a file into the case's vault unless identical content is already
+/// there, and records it under its path, replacing an older entry. The copy
+/// is read back and checked against the source's hash; an existing copy that
+/// no longer matches is replaced.
+pub fn store_file(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    path: &Path,
+    stored_at: &str,
+) -> Result<VaultEntry, Box<dyn std::error::Error>> {
+    let hash = hash_file(path, HashAlgorithm::Sha256)?;
+    let vault = vault_dir(data_dir, case_number);
+    fs::create_dir_all(&vault)?;
+    let copy = vault.join(&hash);
+    if !(copy.exists() && hash_file(&copy, HashAlgorithm::Sha256)? == hash) {
+        // Written under a temporary name and renamed into place, so the hash
+        // never names a half-written copy; the health check clears any left
+        // behind by a crash
+        let partial = tempfile::Builder::new().suffix(".partial").tempfile_in(&vault)?.into_temp_path();
+        let copied = copy_verified(path, &partial)?;
+        if copied != hash {
+            // The source changed between hashing and copying
+            return Err(verification_error(&path.to_string_lossy(), &hash, &copied).into());
+        }
+        partial.persist(&copy)?;
+    }
+    let entry = VaultEntry {
+        case_number: case_number.map(|s| s.to_string()),
+        absolute_path: path.to_string_lossy().to_string(),
+        size_bytes: fs::metadata(&copy)?.len(),
+        hash,
+        stored_at: stored_at.to_string(),
+        verified: true,
+    };
+    json_store::update(&index_path(data_dir), |all: &mut Vec<VaultEntry>| {
+        all.retain(|existing| existing.case_number != entry.case_number || existing.absolute_path != entry.absolute_path);
+        all.push(entry.clone());
+    })?;
+    Ok(entry)
+}
+
+/// The vaulted copy of a file, if it has one
+pub fn vault_copy(data_dir: &Path, case_number: Option<&str>, absolute_path: &str) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    Ok(case_entries(data_dir, case_number)?
+        .into_iter()
+        .find(|entry| entry.absolute_path == absolute_path)
+        .map(|entry| vault_dir(data_dir, case_number).join(entry.hash)))
+}
+
+/// Drops index entries whose stored copy is gone, e.g. after the case's
+/// data directory was cleaned by hand. Returns how many were dropped.
+pub fn drop_missing_copies(data_dir: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    if !index_path(data_dir).exists() {
+        return Ok(0);
+    }
+    json_store::update(&index_path(data_dir), |all: &mut Vec<VaultEntry>| {
+        let before = all.len();
+        all.retain(|entry| vault_dir(data_dir, entry.case_number.as_deref()).join(&entry.hash).exists());
+        before - all.len()
+    })
+}
+
+pub fn vault_report(data_dir: &Path, case_number: Option<&str>) -> Result<VaultReport, Box<dyn std::error::Error>> {
+    let entries = case_entries(data_dir, case_number)?;
+    let mut stored = HashSet::new();
+    let mut report = VaultReport {
+        enabled: vault_enabled(data_dir, case_number)?,
+        files: entries.len(),
+        ..VaultReport::default()
+    };
+    for entry in &entries {
+        report.file_bytes += entry.size_bytes;
+        if stored.insert(entry.hash.as_str()) {
+            report.stored_copies += 1;
+            report.stored_bytes += entry.size_bytes;
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_files_share_one_stored_copy() {
+        let dir = std::env::temp_dir().join(format!("vault-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let source = dir.join("source");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("a.pdf"), "same").unwrap();
+        fs::write(source.join("copy of a.pdf"), "same").unwrap();
+        fs::write(source.join("b.pdf"), "different").unwrap();
+        let case = Some("A-1");
+
+        for name in ["a.pdf", "copy of a.pdf", "b.pdf", "a.pdf"] {
+            store_file(&dir, case, &source.join(name), "2026-03-01 09:00:00").unwrap();
+        }
+        let report = vault_report(&dir, case).unwrap();
+        assert_eq!(
+            report,
+            VaultReport {
+                enabled: false,
+                files: 3,
+                stored_copies: 2,
+                stored_bytes: 13,
+                file_bytes: 17,
+            }
+        );
+
+        // A stored copy that no longer matches its hash is replaced
+        let copy = vault_copy(&dir, case, &source.join("b.pdf").to_string_lossy()).unwrap().unwrap();
+        fs::write(&copy, "bit rot").unwrap();
+        assert!(store_file(&dir, case, &source.join("b.pdf"), "2026-03-02 09:00:00").unwrap().verified);
+        assert_eq!(fs::read_to_string(&copy).unwrap(), "different");
+        let vault = copy.parent().unwrap();
+        assert!(fs::read_dir(vault).unwrap().all(|entry| !json_store::is_partial(&entry.unwrap().path())));
+
+        // The vault still has the content once the source is gone
+        let key = source.join("b.pdf").to_string_lossy().to_string();
+        fs::remove_dir_all(&source).unwrap();
+        let copy = vault_copy(&dir, case, &key).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(copy).unwrap(), "different");
+        assert!(vault_copy(&dir, Some("B-2"), &key).unwrap().is_none());
+
+        set_vault_enabled(&dir, case, true).unwrap();
+        assert!(vault_enabled(&dir, case).unwrap());
+        assert!(!vault_enabled(&dir, None).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
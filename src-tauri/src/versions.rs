@@ -0,0 +1,156 @@
+use crate::hashing::{copy_verified, hash_file, verification_error, HashAlgorithm};
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const VERSIONS_FILE: &str = "file_versions.json";
+const VERSIONING_FILE: &str = "versioning.json";
+
+/// Directory under the case's data directory holding stored copies, named
+/// by content hash so identical versions are stored once
+const VERSION_COPIES_DIR: &str = "versions";
+
+/// A stored copy of a file's content at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileVersion {
+    pub case_number: Option<String>,
+    pub absolute_path: String,
+    /// SHA-256 of the content; also names the stored copy
+    pub hash: String,
+    pub size_bytes: u64,
+    pub captured_at: String,
+}
+
+/// Whether versioning is on for a case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CaseVersioning {
+    case_number: Option<String>,
+    enabled: bool,
+}
+
+fn versions_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(VERSIONS_FILE)
+}
+
+fn copies_dir(data_dir: &Path, case_number: Option<&str>) -> PathBuf {
+    match case_number.filter(|c| !c.trim().is_empty()) {
+        Some(case_number) => json_store::case_data_dir(data_dir, case_number).join(VERSION_COPIES_DIR),
+        None => data_dir.join(VERSION_COPIES_DIR),
+    }
+}
+
+pub fn versioning_enabled(data_dir: &Path, case_number: Option<&str>) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(json_store::read::<Vec<CaseVersioning>>(&data_dir.join(VERSIONING_FILE))?
+        .into_iter()
+        .any(|setting| setting.case_number.as_deref() == case_number && setting.enabled))
+}
+
+pub fn set_versioning(data_dir: &Path, case_number: Option<&str>, enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&data_dir.join(VERSIONING_FILE), |all: &mut Vec<CaseVersioning>| {
+        all.retain(|setting| setting.case_number.as_deref() != case_number);
+        if enabled {
+            all.push(CaseVersioning {
+                case_number: case_number.map(|s| s.to_string()),
+                enabled,
+            });
+        }
+    })
+}
+
+/// Stored versions of a file, oldest first
+pub fn list_versions(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    absolute_path: &str,
+) -> Result<Vec<FileVersion>, Box<dyn std::error::Error>> {
+    let versions: Vec<FileVersion> = json_store::read(&versions_path(data_dir))?;
+    Ok(versions
+        .into_iter()
+        .filter(|version| version.case_number.as_deref() == case_number && version.absolute_path == absolute_path)
+        .collect())
+}
+
+/// Stores a copy of the file if its content differs from its latest stored
+/// version. Returns the new version, or `None` if nothing changed.
+pub fn capture_version(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    path: &Path,
+    captured_at: &str,
+) -> Result<Option<FileVersion>, Box<dyn std::error::Error>> {
+    let absolute_path = path.to_string_lossy().to_string();
+    let hash = hash_file(path, HashAlgorithm::Sha256)?;
+    let latest = list_versions(data_dir, case_number, &absolute_path)?.pop();
+    if latest.is_some_and(|latest| latest.hash == hash) {
+        return Ok(None);
+    }
+
+    let copies = copies_dir(data_dir, case_number);
+    fs::create_dir_all(&copies)?;
+    let copy = copies.join(&hash);
+    if !copy.exists() {
+        let copied = copy_verified(path, &copy)?;
+        if copied != hash {
+            // The file changed between hashing and copying
+            let _ = fs::remove_file(&copy);
+            return Err(verification_error(&absolute_path, &hash, &copied).into());
+        }
+    }
+    let version = FileVersion {
+        case_number: case_number.map(|s| s.to_string()),
+        absolute_path,
+        size_bytes: fs::metadata(&copy)?.len(),
+        hash,
+        captured_at: captured_at.to_string(),
+    };
+    json_store::update(&versions_path(data_dir), |all: &mut Vec<FileVersion>| all.push(version.clone()))?;
+    Ok(Some(version))
+}
+
+/// Where a stored version of the file is kept, if the file has that version
+pub fn version_copy(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    absolute_path: &str,
+    hash: &str,
+) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    let stored = list_versions(data_dir, case_number, absolute_path)?
+        .iter()
+        .any(|version| version.hash == hash);
+    Ok(stored.then(|| copies_dir(data_dir, case_number).join(hash)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_content_is_stored_once_per_version() {
+        let dir = std::env::temp_dir().join(format!("versions-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("draft.txt");
+        let key = file.to_string_lossy().to_string();
+        let case = Some("A-1");
+
+        fs::write(&file, "first").unwrap();
+        let first = capture_version(&dir, case, &file, "2026-03-01 09:00:00").unwrap().unwrap();
+        assert!(capture_version(&dir, case, &file, "2026-03-01 09:30:00").unwrap().is_none());
+        fs::write(&file, "second draft").unwrap();
+        capture_version(&dir, case, &file, "2026-03-01 10:00:00").unwrap().unwrap();
+
+        let versions = list_versions(&dir, case, &key).unwrap();
+        let sizes: Vec<u64> = versions.iter().map(|version| version.size_bytes).collect();
+        assert_eq!(sizes, [5, 12]);
+        let copy = version_copy(&dir, case, &key, &first.hash).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(copy).unwrap(), "first");
+        assert!(version_copy(&dir, Some("B-2"), &key, &first.hash).unwrap().is_none());
+
+        assert!(!versioning_enabled(&dir, case).unwrap());
+        set_versioning(&dir, case, true).unwrap();
+        assert!(versioning_enabled(&dir, case).unwrap());
+        assert!(!versioning_enabled(&dir, None).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
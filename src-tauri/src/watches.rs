@@ -0,0 +1,120 @@
+use crate::binders::matching_paths;
+use crate::json_store;
+use crate::InventoryItem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const WATCHES_FILE: &str = "watches.json";
+const WATCH_HITS_FILE: &str = "watch_hits.jsonl";
+
+/// A search re-run against newly synced files, e.g. an awaited account number
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Watch {
+    pub name: String,
+    pub case_number: Option<String>,
+    /// Case-insensitive text matched against every field
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Exact `field == value` matches, all required
+    #[serde(default)]
+    pub filter: HashMap<String, String>,
+}
+
+/// A newly synced file that matched a watch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchHit {
+    pub timestamp: String,
+    pub watch: String,
+    pub case_number: Option<String>,
+    pub absolute_path: String,
+    pub file_name: String,
+}
+
+fn watches_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(WATCHES_FILE)
+}
+
+fn hits_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(WATCH_HITS_FILE)
+}
+
+pub fn read_watches(data_dir: &Path) -> Result<Vec<Watch>, Box<dyn std::error::Error>> {
+    json_store::read(&watches_path(data_dir))
+}
+
+fn same_watch(watch: &Watch, case_number: Option<&str>, name: &str) -> bool {
+    watch.case_number.as_deref() == case_number && watch.name.eq_ignore_ascii_case(name.trim())
+}
+
+/// Adds a watch, replacing any watch with the same name in the case
+pub fn save_watch(data_dir: &Path, watch: Watch) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&watches_path(data_dir), |watches: &mut Vec<Watch>| {
+        watches.retain(|existing| !same_watch(existing, watch.case_number.as_deref(), &watch.name));
+        watches.push(watch);
+    })
+}
+
+/// Removes a watch; returns whether it existed
+pub fn remove_watch(data_dir: &Path, case_number: Option<&str>, name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    json_store::update(&watches_path(data_dir), |watches: &mut Vec<Watch>| {
+        let before = watches.len();
+        watches.retain(|watch| !same_watch(watch, case_number, name));
+        watches.len() != before
+    })
+}
+
+/// Checks newly synced items against the watches that apply to the case and
+/// appends any hits to the hit log. Returns the new hits.
+pub fn record_watch_hits(
+    data_dir: &Path,
+    case_number: Option<&str>,
+    watches: &[Watch],
+    new_items: &[InventoryItem],
+) -> Result<Vec<WatchHit>, Box<dyn std::error::Error>> {
+    if new_items.is_empty() {
+        return Ok(Vec::new());
+    }
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut hits = Vec::new();
+    for watch in watches {
+        for path in matching_paths(new_items, &watch.filter, watch.query.as_deref()) {
+            let file_name = new_items
+                .iter()
+                .find(|item| item.absolute_path == path)
+                .map(|item| item.file_name.clone())
+                .unwrap_or_default();
+            hits.push(WatchHit {
+                timestamp: timestamp.clone(),
+                watch: watch.name.clone(),
+                case_number: case_number.map(|s| s.to_string()),
+                absolute_path: path,
+                file_name,
+            });
+        }
+    }
+    if hits.is_empty() {
+        return Ok(hits);
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(hits_path(data_dir))?;
+    for hit in &hits {
+        writeln!(file, "{}", serde_json::to_string(hit)?)?;
+    }
+    Ok(hits)
+}
+
+/// Reads recorded hits for a case, newest first
+pub fn read_watch_hits(data_dir: &Path, case_number: Option<&str>, limit: usize) -> std::io::Result<Vec<WatchHit>> {
+    let path = hits_path(data_dir);
+    let mut hits: Vec<WatchHit> = json_store::read_jsonl::<WatchHit>(&path)?
+        .into_iter()
+        .filter(|hit| hit.case_number.as_deref() == case_number)
+        .collect();
+
+    hits.reverse();
+    hits.truncate(limit);
+    Ok(hits)
+}
@@ -0,0 +1,41 @@
+use crate::json_store;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const WORKSPACE_FILE: &str = "workspace.json";
+
+/// View preferences saved for one window (columns, filters, open panels).
+/// The frontend owns their shape; case windows keep theirs across sessions
+/// because their labels are derived from the case number.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WindowWorkspace {
+    window: String,
+    preferences: serde_json::Value,
+}
+
+fn workspace_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(WORKSPACE_FILE)
+}
+
+/// Preferences saved for a window, or None if it has never saved any
+pub fn read_preferences(data_dir: &Path, window: &str) -> Result<Option<serde_json::Value>, Box<dyn std::error::Error>> {
+    Ok(json_store::read::<Vec<WindowWorkspace>>(&workspace_path(data_dir))?
+        .into_iter()
+        .find(|workspace| workspace.window == window)
+        .map(|workspace| workspace.preferences))
+}
+
+/// Replaces a window's saved preferences
+pub fn save_preferences(
+    data_dir: &Path,
+    window: &str,
+    preferences: serde_json::Value,
+) -> Result<(), Box<dyn std::error::Error>> {
+    json_store::update(&workspace_path(data_dir), |all: &mut Vec<WindowWorkspace>| {
+        all.retain(|workspace| workspace.window != window);
+        all.push(WindowWorkspace {
+            window: window.to_string(),
+            preferences,
+        });
+    })
+}
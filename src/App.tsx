@@ -1,8 +1,9 @@
-import { useRef, useState } from "react"
+import { useCallback, useMemo, useRef, useState } from "react"
 import "./index.css"
 import { DesktopLayout, type DesktopLayoutRef } from "./components/layout/DesktopLayout"
 import { LargeFolderWarningDialog } from "./components/LargeFolderWarningDialog"
 import { useInventory } from "./hooks/useInventory"
+import { useReviewScope } from "./hooks/useReviewScope"
 import { useRecentInventories } from "./hooks/useRecentInventories"
 import { useKeyboardShortcuts } from "./hooks/useKeyboardShortcuts"
 import { useInventoryStore } from "./store/inventoryStore"
@@ -31,6 +32,31 @@ function App() {
 
   const { setSelectedFolder } = useInventoryStore()
 
+  // The table shows the case's review scope; its rows and selection are in
+  // visible positions, while the store and bulk edits use store indices
+  const { visibleItems, visibleIndices, suppressedCount, showSuppressed, setShowSuppressed } =
+    useReviewScope(items, caseNumber)
+  const visibleSelection = useMemo(() => {
+    const positions = new Map(visibleIndices.map((index, position) => [index, position]))
+    return selectedIndices.flatMap((index) => {
+      const position = positions.get(index)
+      return position === undefined ? [] : [position]
+    })
+  }, [selectedIndices, visibleIndices])
+
+  const handleVisibleSelectionChange = useCallback((positions: number[]) => {
+    setSelectedIndices(positions.flatMap((position) => visibleIndices[position] ?? []))
+  }, [visibleIndices, setSelectedIndices])
+
+  const handleVisibleItemsChange = useCallback((updated: InventoryItem[]) => {
+    const next = [...items]
+    updated.forEach((item, position) => {
+      const index = visibleIndices[position]
+      if (index !== undefined) next[index] = item
+    })
+    setItems(next)
+  }, [items, visibleIndices, setItems])
+
   const [warningDialogOpen, setWarningDialogOpen] = useState(false)
   const [pendingFolderPath, setPendingFolderPath] = useState<string | null>(null)
   const [pendingFileCount, setPendingFileCount] = useState<number>(0)
@@ -80,7 +106,8 @@ function App() {
   }
 
   const handleBulkSetDateRcvd = (date: string, indices?: number[]) => {
-    bulkUpdateItems({ date_rcvd: date }, indices || selectedIndices)
+    const targets = indices && indices.length > 0 ? indices : selectedIndices
+    bulkUpdateItems({ date_rcvd: date }, targets.length > 0 ? targets : visibleIndices)
   }
 
   const handleExportComplete = (
@@ -243,7 +270,7 @@ function App() {
     },
     onSelectAll: () => {
       if (items.length > 0) {
-        setSelectedIndices(items.map((_: InventoryItem, i: number) => i))
+        setSelectedIndices(visibleIndices)
       }
     },
     onClearSelection: () => {
@@ -264,11 +291,17 @@ function App() {
           ref={desktopLayoutRef}
           items={items}
           onItemsChange={setItems}
+          visibleItems={visibleItems}
+          onVisibleItemsChange={handleVisibleItemsChange}
+          visibleSelection={visibleSelection}
+          onVisibleSelectionChange={handleVisibleSelectionChange}
+          suppressedCount={suppressedCount}
+          showSuppressed={showSuppressed}
+          onShowSuppressedChange={setShowSuppressed}
           caseNumber={caseNumber}
           onCaseNumberChange={setCaseNumber}
           onBulkSetDateRcvd={handleBulkSetDateRcvd}
           selectedIndices={selectedIndices}
-          onSelectionChange={setSelectedIndices}
           loading={loading}
           selectedFolder={selectedFolder}
           onFolderSelected={handleFolderSelected}
@@ -11,7 +11,7 @@ import {
   DialogTrigger,
 } from "./ui/dialog"
 import { Tooltip, TooltipTrigger, TooltipContent } from "./ui/tooltip"
-import { FilePlus, FileSpreadsheet, FileText, Code, Loader2 } from "lucide-react"
+import { FilePlus, FileSpreadsheet, FileText, Code, Database, Loader2 } from "lucide-react"
 import { exportInventory } from "@/services/inventoryService"
 import { createAppError, logError, ErrorCode } from "@/lib/error-handler"
 import { toast } from "@/hooks/useToast"
@@ -33,7 +33,7 @@ export function ExportDialog({ items, caseNumber, disabled, onExportComplete, se
   const [internalOpen, setInternalOpen] = useState(false)
   const open = controlledOpen !== undefined ? controlledOpen : internalOpen
   const setOpen = onOpenChange || setInternalOpen
-  const [format, setFormat] = useState<"xlsx" | "csv" | "json">("xlsx")
+  const [format, setFormat] = useState<"xlsx" | "csv" | "json" | "sqlite">("xlsx")
   const { setExporting, exporting } = useInventoryStore()
 
   const handleExport = async () => {
@@ -49,7 +49,7 @@ export function ExportDialog({ items, caseNumber, disabled, onExportComplete, se
     try {
       setExporting(true)
 
-      const ext = format === "xlsx" ? "xlsx" : format === "csv" ? "csv" : "json"
+      const ext = format
       const defaultName = `inventory.${ext}`
 
       const filePath = await save({
@@ -103,6 +103,7 @@ export function ExportDialog({ items, caseNumber, disabled, onExportComplete, se
     { value: "xlsx" as const, label: "XLSX", icon: FileSpreadsheet, desc: "Excel spreadsheet" },
     { value: "csv" as const, label: "CSV", icon: FileText, desc: "Comma-separated values" },
     { value: "json" as const, label: "JSON", icon: Code, desc: "JavaScript Object Notation" },
+    { value: "sqlite" as const, label: "SQLite", icon: Database, desc: "Queryable database file" },
   ]
 
   return (
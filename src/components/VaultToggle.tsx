@@ -0,0 +1,95 @@
+import { useEffect, useState } from "react"
+import { Checkbox } from "./ui/checkbox"
+import { Label } from "./ui/label"
+import { Archive } from "lucide-react"
+import {
+  getVaultEnabled,
+  getVaultReport,
+  setVaultEnabled,
+  vaultCaseFiles,
+  type VaultReport,
+} from "@/services/inventoryService"
+import { createAppError, logError } from "@/lib/error-handler"
+import { toast } from "@/hooks/useToast"
+import type { InventoryItem } from "@/types/inventory"
+
+interface VaultToggleProps {
+  caseNumber: string
+  items: InventoryItem[]
+  disabled?: boolean
+}
+
+/**
+ * Per-case switch for the evidence vault
+ *
+ * Turning it on also stores the files already in the inventory, so previews
+ * keep working for every file once its original is gone.
+ */
+export function VaultToggle({ caseNumber, items, disabled }: VaultToggleProps) {
+  const [report, setReport] = useState<VaultReport | null>(null)
+  const [busy, setBusy] = useState(false)
+
+  useEffect(() => {
+    let active = true
+    Promise.all([getVaultEnabled(caseNumber || null), getVaultReport(caseNumber || null)])
+      .then(([enabled, loaded]) => {
+        if (active) setReport({ ...loaded, enabled })
+      })
+      .catch((error) => {
+        logError(createAppError(error), "VaultToggle")
+        if (active) setReport(null)
+      })
+    return () => {
+      active = false
+    }
+  }, [caseNumber])
+
+  const handleChange = async (enabled: boolean) => {
+    setBusy(true)
+    try {
+      await setVaultEnabled(caseNumber || null, enabled)
+      if (enabled && items.length > 0) {
+        const stored = await vaultCaseFiles(caseNumber || null, items)
+        toast({
+          title: "Evidence vault on",
+          description: `Stored ${stored} of ${items.length} file${items.length !== 1 ? "s" : ""} in the vault`,
+          variant: stored === items.length ? "success" : "warning",
+        })
+      }
+      const loaded = await getVaultReport(caseNumber || null)
+      setReport({ ...loaded, enabled })
+    } catch (error) {
+      const appError = createAppError(error)
+      logError(appError, "VaultToggle")
+      toast({
+        title: "Failed to change the evidence vault",
+        description: appError.message,
+        variant: "destructive",
+      })
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  return (
+    <div className="space-y-1.5">
+      <div className="flex items-center gap-2">
+        <Checkbox
+          id="vault-enabled"
+          checked={report?.enabled ?? false}
+          onCheckedChange={(checked) => handleChange(checked === true)}
+          disabled={disabled || busy || report === null}
+        />
+        <Label htmlFor="vault-enabled" className="text-sm font-medium text-foreground flex items-center gap-2">
+          <Archive className="h-4 w-4 text-muted-foreground" />
+          Keep copies in the evidence vault
+        </Label>
+      </div>
+      {report && report.files > 0 && (
+        <p className="text-xs text-muted-foreground">
+          {report.files} file{report.files !== 1 ? "s" : ""} vaulted; previews fall back to them when originals are gone
+        </p>
+      )}
+    </div>
+  )
+}
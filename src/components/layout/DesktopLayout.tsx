@@ -13,11 +13,19 @@ export interface DesktopLayoutRef {
 interface DesktopLayoutProps {
   items: InventoryItem[];
   onItemsChange: (items: InventoryItem[]) => void;
+  /** Items in the table: the review scope, unless suppressed duplicates are shown */
+  visibleItems: InventoryItem[];
+  onVisibleItemsChange: (items: InventoryItem[]) => void;
+  /** Selection in visible positions */
+  visibleSelection: number[];
+  onVisibleSelectionChange: (positions: number[]) => void;
+  suppressedCount: number;
+  showSuppressed: boolean;
+  onShowSuppressedChange: (show: boolean) => void;
   caseNumber: string;
   onCaseNumberChange: (value: string) => void;
   onBulkSetDateRcvd: (date: string, indices?: number[]) => void;
   selectedIndices: number[];
-  onSelectionChange: (indices: number[]) => void;
   loading: boolean;
   selectedFolder: string | null;
   onFolderSelected: (path: string) => void;
@@ -38,11 +46,17 @@ interface DesktopLayoutProps {
 export const DesktopLayout = forwardRef<DesktopLayoutRef, DesktopLayoutProps>(function DesktopLayout({
   items,
   onItemsChange,
+  visibleItems,
+  onVisibleItemsChange,
+  visibleSelection,
+  onVisibleSelectionChange,
+  suppressedCount,
+  showSuppressed,
+  onShowSuppressedChange,
   caseNumber,
   onCaseNumberChange,
   onBulkSetDateRcvd,
   selectedIndices,
-  onSelectionChange,
   loading,
   selectedFolder,
   onFolderSelected,
@@ -129,10 +143,13 @@ export const DesktopLayout = forwardRef<DesktopLayoutRef, DesktopLayoutProps>(fu
       {/* Right Content Area */}
       <main className='flex-1 flex flex-col overflow-hidden bg-background'>
         <TableContent
-          items={items}
-          onItemsChange={onItemsChange}
-          onSelectionChange={onSelectionChange}
-          selectedIndices={selectedIndices}
+          items={visibleItems}
+          onItemsChange={onVisibleItemsChange}
+          onSelectionChange={onVisibleSelectionChange}
+          selectedIndices={visibleSelection}
+          suppressedCount={suppressedCount}
+          showSuppressed={showSuppressed}
+          onShowSuppressedChange={onShowSuppressedChange}
           loading={loading}
           selectedFolder={selectedFolder}
           recentInventories={recentInventories}
@@ -1,5 +1,6 @@
 import { FolderSelector } from "../FolderSelector"
 import { ConfigForm } from "../ConfigForm"
+import { VaultToggle } from "../VaultToggle"
 import { ExportDialog } from "../ExportDialog"
 import { ImportDialog } from "../ImportDialog"
 import { SettingsDialog } from "../SettingsDialog"
@@ -212,6 +213,7 @@ export function SidebarContent({
               totalItems={items.length}
               bulkDateInputRef={bulkDateInputRef}
             />
+            <VaultToggle caseNumber={caseNumber} items={items} disabled={loading} />
           </div>
         </div>
       </div>
@@ -2,6 +2,8 @@ import { InventoryTable } from "../InventoryTable"
 import { TableSkeleton } from "../TableSkeleton"
 import { EmptyState } from "../ui/empty-state"
 import { RecentInventories } from "../RecentInventories"
+import { Checkbox } from "../ui/checkbox"
+import { Label } from "../ui/label"
 import { FolderOpen, FileText } from "lucide-react"
 import type { InventoryItem } from "@/types/inventory"
 import type { RecentInventory } from "@/hooks/useRecentInventories"
@@ -11,6 +13,10 @@ interface TableContentProps {
   onItemsChange: (items: InventoryItem[]) => void
   onSelectionChange?: ((indices: number[]) => void) | undefined
   selectedIndices?: number[]
+  /** Duplicates the case's dedup policy suppresses from review */
+  suppressedCount?: number
+  showSuppressed?: boolean
+  onShowSuppressedChange?: ((show: boolean) => void) | undefined
   loading: boolean
   selectedFolder: string | null
   recentInventories?: RecentInventory[]
@@ -23,6 +29,9 @@ export function TableContent({
   onItemsChange,
   onSelectionChange,
   selectedIndices,
+  suppressedCount = 0,
+  showSuppressed = false,
+  onShowSuppressedChange,
   loading,
   selectedFolder,
   recentInventories = [],
@@ -109,6 +118,18 @@ export function TableContent({
               {selectedFolder && `Viewing files from: ${selectedFolder.split('/').pop()}`}
             </p>
           </div>
+          {suppressedCount > 0 && (
+            <div className="flex items-center gap-2">
+              <Checkbox
+                id="show-suppressed"
+                checked={showSuppressed}
+                onCheckedChange={(checked) => onShowSuppressedChange?.(checked === true)}
+              />
+              <Label htmlFor="show-suppressed" className="text-xs text-muted-foreground">
+                Show {suppressedCount} suppressed duplicate{suppressedCount !== 1 ? "s" : ""}
+              </Label>
+            </div>
+          )}
         </div>
       </div>
 
@@ -12,6 +12,9 @@ import { useInventoryStore } from "@/store/inventoryStore"
 vi.mock("@/services/inventoryService", () => ({
   scanDirectory: vi.fn(),
   syncInventory: vi.fn(),
+  recordFileEdit: vi.fn(),
+  recordBulkEdit: vi.fn(),
+  publishCaseInventory: vi.fn().mockResolvedValue([]),
 }))
 
 // Mock toast
@@ -46,6 +49,7 @@ describe("useInventory", () => {
         folder_name: "test",
         folder_path: "test",
         file_type: "PDF",
+        file_category: "Documents",
         bates_stamp: "",
         notes: "",
         absolute_path: "/test/test.pdf",
@@ -82,5 +86,41 @@ describe("useInventory", () => {
     expect(result.current.items).toEqual([])
     expect(result.current.loading).toBe(false)
   })
+
+  it("should record bulk edits and skip them when refused", async () => {
+    const item = {
+      date_rcvd: "",
+      doc_year: 2024,
+      doc_date_range: "",
+      document_type: "PDF",
+      document_description: "Test document",
+      file_name: "test.pdf",
+      folder_name: "test",
+      folder_path: "test",
+      file_type: "PDF",
+      file_category: "Documents",
+      bates_stamp: "",
+      notes: "",
+      absolute_path: "/test/test.pdf",
+    }
+    useInventoryStore.getState().setItems([item, { ...item, date_rcvd: "2024-01-02", absolute_path: "/test/b.pdf" }])
+    useInventoryStore.getState().setCaseNumber("A-1")
+    const { result } = renderHook(() => useInventory())
+
+    vi.mocked(inventoryService.recordBulkEdit).mockRejectedValueOnce("Case is finalized and cannot be changed: A-1")
+    await act(async () => {
+      await result.current.bulkUpdateItems({ date_rcvd: "2024-01-02" })
+    })
+    expect(result.current.items[0].date_rcvd).toBe("")
+
+    vi.mocked(inventoryService.recordBulkEdit).mockResolvedValueOnce(undefined)
+    await act(async () => {
+      await result.current.bulkUpdateItems({ date_rcvd: "2024-01-02" })
+    })
+    expect(inventoryService.recordBulkEdit).toHaveBeenLastCalledWith("A-1", "date_rcvd", "2024-01-02", [
+      { absolute_path: "/test/test.pdf", old_value: "" },
+    ])
+    expect(result.current.items[0].date_rcvd).toBe("2024-01-02")
+  })
 })
 
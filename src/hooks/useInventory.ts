@@ -6,7 +6,14 @@
 import { useEffect, useCallback, useRef, useMemo } from "react"
 import { useInventoryStore } from "@/store/inventoryStore"
 import { useSettingsStore } from "@/store/settingsStore"
-import { countDirectoryFiles, scanDirectory, syncInventory } from "@/services/inventoryService"
+import {
+  countDirectoryFiles,
+  publishCaseInventory,
+  recordBulkEdit,
+  recordFileEdit,
+  scanDirectory,
+  syncInventory,
+} from "@/services/inventoryService"
 import { createAppError, logError, ErrorCode } from "@/lib/error-handler"
 import { toast } from "./useToast"
 import type { InventoryItem } from "@/types/inventory"
@@ -58,7 +65,7 @@ export function useInventory() {
       }
       
       // Proceed with full scan
-      const scannedItems = await scanDirectory(path)
+      const scannedItems = await scanDirectory(path, store.caseNumber)
       
       // If skipping warning or count < 100, proceed normally
       store.setItems(scannedItems)
@@ -108,7 +115,7 @@ export function useInventory() {
     store.setSyncing(true)
     
     try {
-      const syncedItems = await syncInventory(folderPath, store.items)
+      const syncedItems = await syncInventory(folderPath, store.items, store.caseNumber)
       store.setItems(syncedItems)
       toast({
         title: "Inventory synced",
@@ -144,6 +151,23 @@ export function useInventory() {
     return () => clearTimeout(timeoutId)
   }, [items.length, selectedFolder, checkSyncStatus])
 
+  // Keep the backend's copy of this case current for the local API and
+  // scheduled exports
+  const caseNumber = store.caseNumber
+  useEffect(() => {
+    if (!caseNumber) {
+      return
+    }
+    
+    const timeoutId = setTimeout(() => {
+      publishCaseInventory(caseNumber, items).catch((error) => {
+        console.warn("Failed to publish the case inventory:", error)
+      })
+    }, 1000)
+    
+    return () => clearTimeout(timeoutId)
+  }, [items, caseNumber])
+
   // Polling effect for sync status
   const pollingTimeoutRef = useRef<NodeJS.Timeout | null>(null)
   const lastCheckTimeRef = useRef<number>(0)
@@ -238,6 +262,61 @@ export function useInventory() {
     }
   }, [selectedFolder, syncPollingEnabled, syncPollingInterval, items.length])
 
+  // Hand edits are recorded in each file's history before they are applied;
+  // an edit the case's field rules refuse is not applied
+  const updateItem = useCallback(async (index: number, updates: Partial<InventoryItem>) => {
+    const item = store.items[index]
+    if (item) {
+      for (const [field, value] of Object.entries(updates)) {
+        const oldValue = String(item[field as keyof InventoryItem] ?? "")
+        const newValue = String(value ?? "")
+        if (newValue !== oldValue) {
+          try {
+            await recordFileEdit(store.caseNumber, item.absolute_path, field, oldValue, newValue)
+          } catch (error) {
+            toast({
+              title: "Edit not saved",
+              description: String(error),
+              variant: "destructive",
+            })
+            return
+          }
+        }
+      }
+    }
+    store.updateItem(index, updates)
+  }, [store.items, store.caseNumber, store.updateItem])
+
+  // Bulk edits go through the same history and field rules, and like hand
+  // edits are refused for a finalized case
+  const bulkUpdateItems = useCallback(async (updates: Partial<InventoryItem>, indices?: number[]) => {
+    const targets = indices && indices.length > 0 ? indices.map((index) => store.items[index]) : store.items
+    for (const [field, value] of Object.entries(updates)) {
+      const newValue = String(value ?? "")
+      const files = targets
+        .filter((item): item is InventoryItem => item !== undefined)
+        .map((item) => ({
+          absolute_path: item.absolute_path,
+          old_value: String(item[field as keyof InventoryItem] ?? ""),
+        }))
+        .filter((file) => file.old_value !== newValue)
+      if (files.length === 0) {
+        continue
+      }
+      try {
+        await recordBulkEdit(store.caseNumber, field, newValue, files)
+      } catch (error) {
+        toast({
+          title: "Edit not saved",
+          description: String(error),
+          variant: "destructive",
+        })
+        return
+      }
+    }
+    store.bulkUpdateItems(updates, indices)
+  }, [store.items, store.caseNumber, store.bulkUpdateItems])
+
   // Memoize returned object to prevent unnecessary re-renders
   return useMemo(() => ({
     // State from store
@@ -249,8 +328,8 @@ export function useInventory() {
     
     // Actions from store
     setItems: store.setItems,
-    updateItem: store.updateItem,
-    bulkUpdateItems: store.bulkUpdateItems,
+    updateItem,
+    bulkUpdateItems,
     setCaseNumber: store.setCaseNumber,
     setSelectedIndices: store.setSelectedIndices,
     
@@ -265,8 +344,8 @@ export function useInventory() {
     store.caseNumber,
     store.selectedIndices,
     store.setItems,
-    store.updateItem,
-    store.bulkUpdateItems,
+    updateItem,
+    bulkUpdateItems,
     store.setCaseNumber,
     store.setSelectedIndices,
     scanFolder,
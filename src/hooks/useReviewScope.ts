@@ -0,0 +1,76 @@
+/**
+ * Custom hook for the case's review scope in the main table
+ * 
+ * Duplicates suppressed by the case's dedup policy are left out of the table
+ * unless the reviewer chooses to show them. The store keeps every item, so
+ * the table works in visible positions and this maps them back.
+ */
+
+import { useEffect, useMemo, useState } from "react"
+import { useInventoryStore } from "@/store/inventoryStore"
+import {
+  DEDUP_CHANGED_EVENT,
+  getDedupPolicy,
+  listenToCaseEvent,
+  type DedupPolicy,
+} from "@/services/inventoryService"
+import { createAppError, logError } from "@/lib/error-handler"
+import type { InventoryItem } from "@/types/inventory"
+
+// Matches the backend's path keys: Windows paths compare with forward
+// slashes, and case is folded only where the policy's volume ignores it
+function pathKey(path: string, caseInsensitive: boolean): string {
+  const key = /^([a-zA-Z]:\\|\\\\)/.test(path) ? path.replace(/\\/g, "/") : path
+  return caseInsensitive ? key.toLowerCase() : key
+}
+
+export function useReviewScope(items: InventoryItem[], caseNumber: string) {
+  const showSuppressed = useInventoryStore((state) => state.showSuppressed)
+  const setShowSuppressed = useInventoryStore((state) => state.setShowSuppressed)
+  const [policy, setPolicy] = useState<DedupPolicy | null>(null)
+
+  useEffect(() => {
+    let active = true
+    const load = () => {
+      getDedupPolicy(caseNumber || null)
+        .then((loaded) => {
+          if (active) setPolicy(loaded)
+        })
+        .catch((error) => {
+          logError(createAppError(error), "useReviewScope")
+          if (active) setPolicy(null)
+        })
+    }
+    load()
+    const unlisten = listenToCaseEvent(DEDUP_CHANGED_EVENT, load)
+    return () => {
+      active = false
+      unlisten.then((stop) => stop())
+    }
+  }, [caseNumber])
+
+  const suppressedIndices = useMemo(() => {
+    if (!policy || policy.suppressed.length === 0) return new Set<number>()
+    const suppressed = new Set(policy.suppressed.map((path) => pathKey(path, policy.case_insensitive)))
+    return new Set(
+      items.flatMap((item, index) =>
+        suppressed.has(pathKey(item.absolute_path, policy.case_insensitive)) ? [index] : []
+      )
+    )
+  }, [items, policy])
+
+  // Store index of each row the table shows
+  const visibleIndices = useMemo(
+    () => items.flatMap((_, index) => (showSuppressed || !suppressedIndices.has(index) ? [index] : [])),
+    [items, showSuppressed, suppressedIndices]
+  )
+  const visibleItems = useMemo(() => visibleIndices.map((index) => items[index]), [items, visibleIndices])
+
+  return {
+    visibleItems,
+    visibleIndices,
+    suppressedCount: suppressedIndices.size,
+    showSuppressed,
+    setShowSuppressed,
+  }
+}
@@ -6,9 +6,11 @@
  * with the expected data type or reject with an error.
  */
 
-import { invoke } from "@tauri-apps/api/core"
+import { Channel, convertFileSrc, invoke } from "@tauri-apps/api/core"
+import type { UnlistenFn } from "@tauri-apps/api/event"
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow"
 import { openPath } from "@tauri-apps/plugin-opener"
-import type { InventoryItem } from "@/types/inventory"
+import type { InventoryItem, InventoryItemField } from "@/types/inventory"
 
 /**
  * Result type for import operations
@@ -23,6 +25,7 @@ export interface ImportResult {
  * Counts files in a directory without loading metadata (fast)
  * 
  * @param path - Absolute path to the directory to count
+ * @param options - Scan options; names the scan would ignore are not counted
  * @returns Promise resolving to the number of files
  * @throws Error if the path doesn't exist or isn't a directory
  * 
@@ -32,14 +35,15 @@ export interface ImportResult {
  * console.log(`Found ${count} files`)
  * ```
  */
-export async function countDirectoryFiles(path: string): Promise<number> {
-  return invoke<number>("count_directory_files", { path })
+export async function countDirectoryFiles(path: string, options: ScanOptions = {}): Promise<number> {
+  return invoke<number>("count_directory_files", { path, options })
 }
 
 /**
  * Scans a directory and returns inventory items
  * 
  * @param path - Absolute path to the directory to scan
+ * @param caseNumber - Case the scan is logged under, if any
  * @returns Promise resolving to an array of InventoryItem objects
  * @throws Error if the path doesn't exist or isn't a directory
  * 
@@ -49,18 +53,248 @@ export async function countDirectoryFiles(path: string): Promise<number> {
  * console.log(`Found ${items.length} files`)
  * ```
  */
-export async function scanDirectory(path: string): Promise<InventoryItem[]> {
-  return invoke<InventoryItem[]>("scan_directory", { path })
+export async function scanDirectory(path: string, caseNumber: string | null = null): Promise<InventoryItem[]> {
+  return invoke<InventoryItem[]>("scan_directory", { path, caseNumber: caseNumber || null })
+}
+
+/**
+ * Optional export behaviour
+ */
+export interface ExportOptions {
+  /** Continue an interrupted CSV/JSON export from its last checkpoint */
+  resume?: boolean
+  /** Text fields to mask with "[REDACTED]" (recorded in every format). Masking file_name, folder_name or folder_path also drops file links and the source folder */
+  redacted_fields?: InventoryItemField[]
+  /** Column groups: merged header cells above the columns in XLSX, nested objects in JSON */
+  column_groups?: ColumnGroup[]
+  /** XLSX only: group rows by this field with collapsible outlines and subtotal rows (document count and total file size) */
+  group_by?: InventoryItemField
+  /** XLSX only: add a "Summary" sheet with counts by document type x year and by file type */
+  include_summary?: boolean
+  /** XLSX only: how the File Name column links to the source file (default plain text) */
+  link_mode?: LinkMode
+  /** Also export duplicates suppressed by the case's dedup policy */
+  include_suppressed?: boolean
+}
+
+/**
+ * Hyperlink style for exported file names
+ */
+export type LinkMode =
+  | { mode: "plain" }
+  | { mode: "absolute" }
+  /** Relative to the folder the spreadsheet will be delivered in */
+  | { mode: "relative"; root: string }
+  /** Rewrite a local prefix (e.g. "D:\Cases") to a share (e.g. "\\server\cases") */
+  | { mode: "unc"; local_prefix: string; unc_prefix: string }
+
+/**
+ * A named set of adjacent export columns, e.g. "Document Info" or "Review"
+ */
+export interface ColumnGroup {
+  name: string
+  fields: InventoryItemField[]
+}
+
+/**
+ * Options controlling how a folder is scanned
+ */
+export interface ScanOptions {
+  /** Extra attempts for files that fail with a transient error (default 2) */
+  max_retries?: number
+  /** Delay before the first retry in milliseconds, doubled per attempt (default 250) */
+  retry_delay_ms?: number
+  /** Online-only cloud placeholders (detected on Windows and macOS): keep, leave out, or download first (default "include") */
+  placeholder_policy?: "include" | "skip" | "hydrate"
+  /** Skip built-in system noise (Thumbs.db, .DS_Store, ~$ lock files, node_modules, recycle bins) (default true) */
+  use_default_ignores?: boolean
+  /** Extra file or folder names to skip; `*` matches any characters */
+  ignore_patterns?: string[]
+  /** Files at or below the threshold: inventory normally, inventory with a note, or leave out (default "include") */
+  small_file_policy?: "include" | "flag" | "ignore"
+  /** Size in bytes at or below which a file counts as small (default 0, empty files only) */
+  small_file_threshold_bytes?: number
+  /** UTC offset for file timestamps ("UTC", "+05:30", "-08:00"); omit for this machine's time zone */
+  time_zone?: string
+  /** Per-type handling keyed by extension without the dot, e.g. { EXE: "skip", DB: "flag" } */
+  type_policies?: Record<string, "include" | "flag" | "skip">
+}
+
+/**
+ * Files of one type seen during a scan
+ */
+export interface ExtensionStats {
+  file_type: string
+  count: number
+  total_bytes: number
+  /** Files of this type left out by its type policy */
+  skipped: number
+}
+
+/**
+ * A zero-byte or sub-threshold file found during a scan
+ */
+export interface SmallFile {
+  path: string
+  size_bytes: number
+}
+
+/**
+ * A file that could not be read during a scan
+ */
+export interface FailedFile {
+  path: string
+  error: string
+}
+
+/**
+ * Result of a detailed scan
+ */
+export interface ScanReport {
+  items: InventoryItem[]
+  failed_files: FailedFile[]
+  /** Absolute paths of online-only placeholders encountered */
+  placeholders: string[]
+  /** Files skipped by the ignore list */
+  ignored_files: number
+  /** Folders skipped by the ignore list, not counting their contents */
+  ignored_folders: number
+  /** Zero-byte and sub-threshold files */
+  small_files: SmallFile[]
+  /** Counts and sizes per file type, most common first */
+  extension_stats: ExtensionStats[]
+  /** Zone timestamps were rendered in: "local" or a UTC offset such as "+05:30" */
+  time_zone: string
+}
+
+/**
+ * Scans a directory and reports unreadable files alongside the items
+ * 
+ * @param path - Absolute path to the directory to scan
+ * @param options - Optional scan behaviour (retry policy)
+ * @param caseNumber - Case whose retry queue receives the unreadable files
+ * @returns Promise resolving to the scan report
+ * @throws Error if the path doesn't exist or isn't a directory
+ */
+export async function scanDirectoryReport(
+  path: string,
+  options: ScanOptions = {},
+  caseNumber?: string
+): Promise<ScanReport> {
+  return invoke<ScanReport>("scan_directory_report", { path, options, caseNumber })
+}
+
+/**
+ * Scans a directory and delivers items in batches while the scan is running,
+ * so very large folders can be ingested without waiting for the full result
+ * 
+ * @param path - Absolute path to the directory to scan
+ * @param onBatch - Called with each batch of items as it arrives
+ * @param options - Optional scan behaviour (retry policy)
+ * @param batchSize - Maximum items per batch (backend default: 500)
+ * @param caseNumber - Case whose retry queue receives the unreadable files
+ * @returns Promise resolving to the scan report; its items are empty because they were delivered via onBatch
+ * @throws Error if the path doesn't exist or isn't a directory
+ */
+export async function scanDirectoryStreaming(
+  path: string,
+  onBatch: (items: InventoryItem[]) => void,
+  options: ScanOptions = {},
+  batchSize?: number,
+  caseNumber?: string
+): Promise<ScanReport> {
+  const channel = new Channel<InventoryItem[]>()
+  channel.onmessage = onBatch
+  return invoke<ScanReport>("scan_directory_streaming", {
+    path,
+    options,
+    batchSize,
+    onBatch: channel,
+    caseNumber,
+  })
+}
+
+/**
+ * Zero-byte and sub-threshold files under a folder
+ */
+export interface SmallFileReport {
+  threshold_bytes: number
+  files: SmallFile[]
+  zero_byte_count: number
+  total_files: number
+}
+
+/**
+ * Lists zero-byte and sub-threshold files without building an inventory
+ * 
+ * @param path - Absolute path to the directory to check
+ * @param thresholdBytes - Size at or below which a file is reported (default 0)
+ * @returns Promise resolving to the small-file report
+ * @throws Error if the path doesn't exist or isn't a directory
+ */
+export async function smallFileReport(
+  path: string,
+  thresholdBytes?: number
+): Promise<SmallFileReport> {
+  return invoke<SmallFileReport>("small_file_report", { path, thresholdBytes })
+}
+
+/**
+ * A file waiting in a case's retry queue
+ */
+export interface QueuedFile {
+  case_number: string | null
+  /** Source folder the file was scanned from */
+  folder_path: string
+  path: string
+  /** Error from the most recent attempt */
+  error: string
+  /** Scans and retries that have failed on this file */
+  attempts: number
+  last_attempt: string
+}
+
+/**
+ * Lists files that failed to read during scans of a case and are waiting
+ * to be retried
+ * 
+ * @param caseNumber - Case whose queue to read
+ * @returns Promise resolving to the queued files, oldest first
+ */
+export async function getRetryQueue(caseNumber?: string): Promise<QueuedFile[]> {
+  return invoke<QueuedFile[]>("get_retry_queue", { caseNumber })
+}
+
+/**
+ * Re-attempts files that failed during a previous scan (e.g. once Outlook
+ * or Excel has released them). Recovered files leave the case's retry
+ * queue; files that still fail stay queued.
+ * 
+ * @param caseNumber - Case whose retry queue to use
+ * @param folderPath - Root folder the files were scanned from
+ * @param paths - Absolute paths to retry (default: every file queued for the case under folderPath)
+ * @param options - Optional retry policy
+ * @returns Promise resolving to recovered items and files that still fail
+ * @throws Error if a path is outside folderPath
+ */
+export async function retryFailedFiles(
+  caseNumber: string | undefined,
+  folderPath: string,
+  paths?: string[],
+  options: ScanOptions = {}
+): Promise<ScanReport> {
+  return invoke<ScanReport>("retry_failed_files", { caseNumber, folderPath, paths, options })
 }
 
 /**
  * Exports inventory to a file
  * 
  * @param items - Array of inventory items to export
- * @param format - Export format: "xlsx", "csv", or "json"
+ * @param format - Export format: "xlsx", "csv", "json", or "sqlite"
  * @param outputPath - Full path where the file should be saved
  * @param caseNumber - Optional case number to include in metadata
  * @param folderPath - Optional folder path to include in metadata
+ * @param options - Optional export behaviour (resume, redaction)
  * @returns Promise that resolves when export is complete
  * @throws Error if export fails
  * 
@@ -71,10 +305,11 @@ export async function scanDirectory(path: string): Promise<InventoryItem[]> {
  */
 export async function exportInventory(
   items: InventoryItem[],
-  format: "xlsx" | "csv" | "json",
+  format: "xlsx" | "csv" | "json" | "sqlite",
   outputPath: string,
   caseNumber: string | null,
-  folderPath: string | null
+  folderPath: string | null,
+  options: ExportOptions = {}
 ): Promise<void> {
   return invoke("export_inventory", {
     items,
@@ -82,6 +317,213 @@ export async function exportInventory(
     outputPath,
     caseNumber: caseNumber || null,
     folderPath: folderPath || null,
+    options,
+  })
+}
+
+/**
+ * How a case bundle is put together
+ */
+export interface BundleOptions {
+  format: "xlsx" | "csv" | "json" | "sqlite"
+  /** Also pack the inventoried documents under `documents/` */
+  include_documents?: boolean
+  /**
+   * AES-256 encrypts every entry (WinZip AE-2, with key stretching and an
+   * integrity tag) so 7-Zip or WinZip asks for this passphrase
+   */
+  passphrase?: string | null
+  /** Records the bundle as a production set of this name; needs `include_documents` */
+  production?: string | null
+  /** How the produced documents were chosen, kept with the production */
+  filter?: string
+}
+
+/**
+ * A production set that was delivered
+ */
+export interface ProductionSummary {
+  name: string
+  created_at: string
+  filter: string
+  bates_first: string | null
+  bates_last: string | null
+  file_count: number
+  output_path: string
+  /** SHA-256 of the delivered archive */
+  manifest_hash: string
+  /** Whether every document was read back from the archive and matched its source */
+  verified: boolean
+}
+
+/**
+ * Lists a case's production sets, oldest first
+ * 
+ * @param caseNumber - The case
+ * @returns Promise resolving to the productions
+ */
+export async function listProductions(caseNumber: string | null): Promise<ProductionSummary[]> {
+  return invoke<ProductionSummary[]>("list_productions", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Lists the production sets a file went out in
+ * 
+ * @param caseNumber - The case
+ * @param absolutePath - The file
+ * @returns Promise resolving to the productions; empty if it has not been produced
+ */
+export async function getFileProductions(caseNumber: string | null, absolutePath: string): Promise<ProductionSummary[]> {
+  return invoke<ProductionSummary[]>("get_file_productions", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * How much a case's evidence vault holds
+ */
+export interface VaultReport {
+  enabled: boolean
+  /** Files with a copy in the vault */
+  files: number
+  /** Distinct contents actually stored */
+  stored_copies: number
+  stored_bytes: number
+  /** Bytes the files would take without deduplication */
+  file_bytes: number
+}
+
+/**
+ * Turns the evidence vault on or off for a case
+ *
+ * With it on, files added by ingest or sync are copied into case storage,
+ * named by content hash, so the inventory stays usable if the originals go
+ * away. Turning it off keeps the copies already stored.
+ *
+ * @param caseNumber - The case
+ * @param enabled - Whether to vault added files
+ */
+export async function setVaultEnabled(caseNumber: string | null, enabled: boolean): Promise<void> {
+  return invoke("set_vault_enabled", { caseNumber: caseNumber || null, enabled })
+}
+
+/**
+ * Checks whether the evidence vault is on for a case
+ *
+ * @param caseNumber - The case
+ * @returns Promise resolving to true if added files are vaulted
+ */
+export async function getVaultEnabled(caseNumber: string | null): Promise<boolean> {
+  return invoke<boolean>("get_vault_enabled", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Copies the case's current files into its vault
+ *
+ * @param caseNumber - The case
+ * @param items - The items whose files to store
+ * @returns Promise resolving to the number of files stored; unreadable files are skipped
+ */
+export async function vaultCaseFiles(caseNumber: string | null, items: InventoryItem[]): Promise<number> {
+  return invoke<number>("vault_case_files", { caseNumber: caseNumber || null, items })
+}
+
+/**
+ * Reports a case's vault size
+ *
+ * @param caseNumber - The case
+ * @returns Promise resolving to the vault report
+ */
+export async function getVaultReport(caseNumber: string | null): Promise<VaultReport> {
+  return invoke<VaultReport>("get_vault_report", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Gets the path of a file's vaulted copy
+ *
+ * @param caseNumber - The case
+ * @param absolutePath - The original file
+ * @returns Promise resolving to the path of the copy
+ * @throws Error if the file has no vaulted copy
+ */
+export async function getVaultCopy(caseNumber: string | null, absolutePath: string): Promise<string> {
+  return invoke<string>("get_vault_copy", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * Result of writing a case bundle
+ */
+export interface BundleSummary {
+  output_path: string
+  entries: number
+  encrypted: boolean
+  /** Entries read back from the zip that matched their sources */
+  verified: number
+  /** Documents that could not be read and were left out */
+  missing: string[]
+}
+
+/**
+ * Writes a zip for delivery holding the inventory and, optionally, the
+ * documents, encrypted when a passphrase is given
+ * 
+ * @param items - Inventory items to bundle
+ * @param outputPath - Full path of the zip to write
+ * @param caseNumber - Optional case number to include in metadata
+ * @param folderPath - Optional folder path to include in metadata
+ * @param bundle - Inventory format, documents and passphrase
+ * @param options - Export behaviour for the inventory file
+ * @returns Promise resolving to what went into the bundle
+ * @throws Error if the export or the zip cannot be written
+ */
+export async function exportCaseBundle(
+  items: InventoryItem[],
+  outputPath: string,
+  caseNumber: string | null,
+  folderPath: string | null,
+  bundle: BundleOptions,
+  options: ExportOptions = {}
+): Promise<BundleSummary> {
+  return invoke<BundleSummary>("export_case_bundle", {
+    items,
+    outputPath,
+    caseNumber: caseNumber || null,
+    folderPath: folderPath || null,
+    bundle,
+    options,
+  })
+}
+
+/**
+ * What to do when a patterned export name is already taken
+ */
+export type CollisionPolicy = "increment" | "overwrite" | "fail"
+
+/**
+ * Builds an export path from a file name pattern
+ * 
+ * @param directory - Folder the export will be written to
+ * @param pattern - Pattern using {case_id}, {date}, {time}, {profile} and {format}, e.g. "{case_id}_{date}_{profile}"
+ * @param format - Export format; its extension is added if the pattern has none
+ * @param caseNumber - Value for {case_id}
+ * @param profile - Value for {profile}
+ * @param collision - "increment" (default) appends _2, _3...; "overwrite" reuses the name; "fail" rejects
+ * @returns Promise resolving to the full output path
+ * @throws Error if the pattern has an unknown placeholder or the name is taken under "fail"
+ */
+export async function resolveExportFilename(
+  directory: string,
+  pattern: string,
+  format: string,
+  caseNumber: string | null,
+  profile: string | null = null,
+  collision: CollisionPolicy = "increment"
+): Promise<string> {
+  return invoke<string>("resolve_export_filename", {
+    directory,
+    pattern,
+    format,
+    caseNumber: caseNumber || null,
+    profile,
+    collision,
   })
 }
 
@@ -110,16 +552,29 @@ export async function importInventory(
   })
 }
 
+/**
+ * How paths are compared when matching files. "auto" probes the volume:
+ * case is ignored on case-insensitive volumes (Windows, default macOS) and
+ * preserved on case-sensitive ones; separators are always normalized.
+ */
+export type PathCase = "auto" | "sensitive" | "insensitive"
+
 /**
  * Syncs inventory with folder contents
  * 
  * Preserves user edits to existing items and adds new files.
- * Removes items for files that no longer exist.
+ * Removes items for files that no longer exist. Fails without changing
+ * anything if the source is offline (e.g. an unplugged drive) or scans
+ * empty where files were inventoried before, unless forced.
  * 
  * @param folderPath - Path to the folder to sync with
  * @param existingItems - Current inventory items (with user edits)
+ * @param caseNumber - Case being synced, if any; a finalized case is refused and its watched searches are checked against new files
+ * @param pathCase - How paths are matched to existing items (default: probe the volume)
+ * @param force - Skip the offline checks, e.g. when the folder really was emptied
+ * @param options - The case's scan options; new files are skipped or flagged by the same type, small-file and ignore policies as a scan, and their timestamps use its time zone
  * @returns Promise resolving to updated array of InventoryItem objects
- * @throws Error if folder doesn't exist or sync fails
+ * @throws Error if folder doesn't exist, sync fails, the time zone is invalid, or the case is finalized
  * 
  * @example
  * ```ts
@@ -129,26 +584,3480 @@ export async function importInventory(
  */
 export async function syncInventory(
   folderPath: string,
-  existingItems: InventoryItem[]
+  existingItems: InventoryItem[],
+  caseNumber: string | null = null,
+  pathCase?: PathCase,
+  force = false,
+  options: ScanOptions = {}
 ): Promise<InventoryItem[]> {
   return invoke<InventoryItem[]>("sync_inventory", {
     folderPath,
     existingItems,
+    caseNumber: caseNumber || null,
+    pathCase,
+    force,
+    options,
   })
 }
 
 /**
- * Opens a folder in the system file explorer
+ * How syncing one of a case's sources went
+ */
+export interface SourceSyncResult {
+  source: string
+  /** Whether the source's new items were kept */
+  synced: boolean
+  /** Items for the source after the sync, or before it if it failed */
+  file_count: number
+  error: string | null
+}
+
+/**
+ * Result of syncing every source of a case
+ */
+export interface CaseSyncReport {
+  items: InventoryItem[]
+  sources: SourceSyncResult[]
+  /** Set when an atomic sync failed and the inventory was left as it was */
+  rolled_back: boolean
+}
+
+/**
+ * Syncs every folder a case has scanned or synced
  * 
- * @param folderPath - Absolute path to the folder to open
- * @returns Promise that resolves when the folder is opened
- * @throws Error if the folder cannot be opened
+ * Each source is synced on its own; one that fails (e.g. an offline share)
+ * keeps its existing items while the others take their changes.
  * 
- * @example
- * ```ts
- * await openFolder("/path/to/documents")
- * ```
+ * @param caseNumber - Case to sync
+ * @param existingItems - Current inventory items (with user edits)
+ * @param atomic - Leave the whole inventory unchanged if any source fails
+ * @param pathCase - How paths are matched to existing items (default: probe each volume)
+ * @param options - The case's scan options
+ * @returns Promise resolving to the new items and a result per source
+ * @throws Error if the case is finalized or the time zone is invalid
  */
-export async function openFolder(folderPath: string): Promise<void> {
-  return openPath(folderPath)
+export async function syncCaseAllSources(
+  caseNumber: string,
+  existingItems: InventoryItem[],
+  atomic = false,
+  pathCase?: PathCase,
+  options: ScanOptions = {}
+): Promise<CaseSyncReport> {
+  return invoke<CaseSyncReport>("sync_case_all_sources", { caseNumber, existingItems, pathCase, atomic, options })
+}
+
+/**
+ * Lists the folders and files a case may read
+ *
+ * Sources are recorded when a folder is scanned or synced or a file is
+ * ingested; previews and other reads outside them are refused.
+ *
+ * @param caseNumber - The case
+ * @returns Promise resolving to the source paths
+ */
+export async function listCaseSources(caseNumber: string | null): Promise<string[]> {
+  return invoke<string[]>("list_case_sources", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Allows a case to read a folder or file it has not scanned
+ *
+ * @param caseNumber - The case
+ * @param path - Folder or file to allow
+ * @throws Error if the path does not exist
+ */
+export async function addCaseSource(caseNumber: string | null, path: string): Promise<void> {
+  return invoke("add_case_source", { caseNumber: caseNumber || null, path })
+}
+
+/**
+ * Stops a case from reading files under one of its sources
+ *
+ * @param caseNumber - The case
+ * @param path - The source to remove, as listed
+ * @throws Error if the path is not one of the case's sources
+ */
+export async function removeCaseSource(caseNumber: string | null, path: string): Promise<void> {
+  return invoke("remove_case_source", { caseNumber: caseNumber || null, path })
+}
+
+/**
+ * A search re-run against files that arrive in later syncs
+ */
+export interface Watch {
+  name: string
+  case_number: string | null
+  /** Case-insensitive text matched against every field */
+  query?: string | null
+  /** Exact `field == value` matches, all required */
+  filter?: Record<string, string>
+}
+
+/**
+ * A newly synced file that matched a watch
+ */
+export interface WatchHit {
+  timestamp: string
+  watch: string
+  case_number: string | null
+  absolute_path: string
+  file_name: string
+}
+
+/** Events emitted with a `ChangeEvent` after a command changes case data */
+export const FILES_CHANGED_EVENT = "case://files-changed"
+export const BINDERS_CHANGED_EVENT = "case://binders-changed"
+export const PINS_CHANGED_EVENT = "case://pins-changed"
+export const WATCHES_CHANGED_EVENT = "case://watches-changed"
+export const RULES_CHANGED_EVENT = "case://rules-changed"
+export const LINKS_CHANGED_EVENT = "case://links-changed"
+export const NOTES_CHANGED_EVENT = "case://notes-changed"
+export const PRIVILEGE_CHANGED_EVENT = "case://privilege-changed"
+
+/**
+ * Minimal change notification; listeners re-read what they need
+ */
+export interface ChangeEvent {
+  case_number: string | null
+  change: "created" | "updated" | "deleted"
+  /** Absolute paths for files and pins (source files for links), ids for notes, names for binders, watches and rules */
+  ids: string[]
+  /** Position of this change in the case's change feed */
+  cursor: number
+}
+
+/**
+ * One change recorded in a case's change feed
+ */
+export interface FeedChange {
+  cursor: number
+  /** What changed: "files", "notes", "binders" and so on */
+  entity: string
+  change: ChangeEvent["change"]
+  ids: string[]
+}
+
+/**
+ * A case's changes after a cursor
+ */
+export interface ChangesSince {
+  /** Latest cursor, to pass next time */
+  cursor: number
+  /** False when the changes are no longer all kept; re-read everything */
+  complete: boolean
+  changes: FeedChange[]
+}
+
+/**
+ * Gets a case's changes after a cursor, so a window can refresh only what
+ * changed after a background sync
+ * 
+ * Cursors start again from 0 when the app restarts; an old cursor then
+ * comes back incomplete.
+ * 
+ * @param caseNumber - Case to read changes for
+ * @param cursor - Cursor from the last call or change event; 0 for every change kept
+ * @returns Promise resolving to the changes, oldest first
+ */
+export async function getChangesSince(caseNumber: string | undefined, cursor: number): Promise<ChangesSince> {
+  return invoke<ChangesSince>("get_changes_since", { caseNumber: caseNumber || null, cursor })
+}
+
+/** Event emitted with `WatchHit[]` when a sync brings in watched files */
+export const WATCH_HITS_EVENT = "watch-hits"
+
+/**
+ * Listens for a change event or watch hits sent to this window
+ * 
+ * Case windows only receive events for their own case. The backend can only
+ * filter listeners registered on the window, so use this rather than the
+ * global `listen`.
+ * 
+ * @param event - Event name, e.g. `FILES_CHANGED_EVENT`
+ * @param handler - Called with each event's payload
+ * @returns Promise resolving to a function that stops listening
+ */
+export async function listenToCaseEvent<T>(
+  event: string,
+  handler: (payload: T) => void
+): Promise<UnlistenFn> {
+  return getCurrentWebviewWindow().listen<T>(event, (e) => handler(e.payload))
+}
+
+/**
+ * An analyst's link from one file to another
+ */
+export interface FileLink {
+  case_number: string | null
+  /** Absolute path of the file the link is made from */
+  source: string
+  /** Absolute path of the file it points to */
+  target: string
+  /** e.g. "signed version of" */
+  relationship: string
+  note: string
+  created_at: string
+}
+
+/**
+ * One entry in a file's history
+ */
+export interface FileEvent {
+  timestamp: string
+  absolute_path: string
+  kind:
+    | "added"
+    | "removed"
+    | "field_changed"
+    | "refreshed"
+    | "modified"
+    | "pinned"
+    | "unpinned"
+    | "linked"
+    | "unlinked"
+  /** What made the change, e.g. "edit", "sync" or "rule: Bank statements" */
+  source: string
+  field?: string | null
+  old_value?: string | null
+  new_value?: string | null
+}
+
+/**
+ * Gets everything recorded for one file in a case, oldest first
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ * @returns Promise resolving to the file's history
+ */
+export async function getFileHistory(caseNumber: string | null, absolutePath: string): Promise<FileEvent[]> {
+  return invoke<FileEvent[]>("get_file_history", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * Records a field edited by hand in the file's history
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ * @param field - Edited field
+ * @param oldValue - Value before the edit
+ * @param newValue - Value after the edit
+ * @throws Error if the field is not an inventory field or the value breaks
+ * one of the case's field rules
+ */
+export async function recordFileEdit(
+  caseNumber: string | null,
+  absolutePath: string,
+  field: string,
+  oldValue: string,
+  newValue: string
+): Promise<void> {
+  return invoke("record_file_edit", { caseNumber: caseNumber || null, absolutePath, field, oldValue, newValue })
+}
+
+/**
+ * A file changed by a bulk edit, with the field's value before it
+ */
+export interface BulkEditFile {
+  absolute_path: string
+  old_value: string
+}
+
+/**
+ * Records a bulk edit, one field set to one value across many files, in
+ * each file's history
+ * 
+ * @param caseNumber - Case the files belong to
+ * @param field - Edited field
+ * @param newValue - Value after the edit
+ * @param files - Files the edit changes
+ * @throws Error if the case is finalized, the field is not an inventory
+ * field or the value breaks one of the case's field rules
+ */
+export async function recordBulkEdit(
+  caseNumber: string | null,
+  field: string,
+  newValue: string,
+  files: BulkEditFile[]
+): Promise<void> {
+  return invoke("record_bulk_edit", { caseNumber: caseNumber || null, field, newValue, files })
+}
+
+/**
+ * Links one file to another with a typed relationship. Linking the same
+ * files with the same relationship again replaces the note.
+ * 
+ * @param caseNumber - Case the files belong to
+ * @param source - Absolute path of the file to link from
+ * @param target - Absolute path of the file to link to
+ * @param relationship - How the files relate, e.g. "signed version of"
+ * @param note - Optional note on the link
+ * @returns Promise resolving to the saved link
+ * @throws Error if a file is linked to itself
+ */
+export async function linkFiles(
+  caseNumber: string | null,
+  source: string,
+  target: string,
+  relationship: string,
+  note: string | null = null
+): Promise<FileLink> {
+  return invoke<FileLink>("link_files", { caseNumber: caseNumber || null, source, target, relationship, note })
+}
+
+/**
+ * Removes a link between two files
+ * 
+ * @param caseNumber - Case the files belong to
+ * @param source - Absolute path of the file the link is made from
+ * @param target - Absolute path of the file it points to
+ * @param relationship - Relationship of the link to remove
+ * @throws Error if there is no such link
+ */
+export async function unlinkFiles(
+  caseNumber: string | null,
+  source: string,
+  target: string,
+  relationship: string
+): Promise<void> {
+  return invoke("unlink_files", { caseNumber: caseNumber || null, source, target, relationship })
+}
+
+/**
+ * Gets the links made from a file and the links pointing to it
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ * @returns Promise resolving to outbound and inbound links
+ */
+export async function getFileLinks(
+  caseNumber: string | null,
+  absolutePath: string
+): Promise<{ outbound: FileLink[]; inbound: FileLink[] }> {
+  return invoke("get_file_links", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * Lists every file link in a case, oldest first
+ * 
+ * @param caseNumber - Case to list links for
+ * @returns Promise resolving to the links
+ */
+export async function listCaseLinks(caseNumber: string | null): Promise<FileLink[]> {
+  return invoke<FileLink[]>("list_case_links", { caseNumber: caseNumber || null })
+}
+
+/**
+ * A discussion note on a file, kept per case
+ */
+export interface FileNote {
+  id: number
+  case_number: string | null
+  absolute_path: string
+  /** Note this one replies to */
+  parent_id: number | null
+  author: string
+  content: string
+  created_at: string
+  resolved: boolean
+  /** Absolute paths of the files the note mentions, resolved when it was saved */
+  mentions: string[]
+}
+
+/**
+ * A note as listed, with the number of direct replies to it
+ */
+export interface ListedNote extends FileNote {
+  reply_count: number
+}
+
+/**
+ * Adds a note on a file, or a reply to another note
+ * 
+ * `@file:<name or path>` references and Bates stamps in the text are
+ * resolved against `options.items` and saved as the note's mentions.
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - File the note is about (ignored for replies)
+ * @param content - Note text
+ * @param options - `parentId` to reply to a note, the note's author and the items mentions resolve to
+ * @returns Promise resolving to the saved note
+ * @throws Error if the parent note is not in the case
+ */
+export async function addNote(
+  caseNumber: string | null,
+  absolutePath: string,
+  content: string,
+  options: { parentId?: number; author?: string; items?: InventoryItem[] } = {}
+): Promise<FileNote> {
+  return invoke<FileNote>("add_note", {
+    caseNumber: caseNumber || null,
+    absolutePath,
+    parentId: options.parentId ?? null,
+    author: options.author ?? null,
+    content,
+    items: options.items ?? null,
+  })
+}
+
+/**
+ * Lists the notes in a case that mention a file, oldest first
+ * 
+ * @param caseNumber - Case to search
+ * @param absolutePath - File mentioned
+ * @returns Promise resolving to the notes
+ */
+export async function notesMentioningFile(caseNumber: string | null, absolutePath: string): Promise<ListedNote[]> {
+  return invoke<ListedNote[]>("notes_mentioning_file", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * Lists a case's notes, oldest first, with their reply counts
+ * 
+ * @param caseNumber - Case to list notes for
+ * @param absolutePath - Only list the notes on this file
+ * @returns Promise resolving to the notes
+ */
+export async function listNotes(caseNumber: string | null, absolutePath: string | null = null): Promise<ListedNote[]> {
+  return invoke<ListedNote[]>("list_notes", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * Marks a note resolved, or open again
+ * 
+ * @param caseNumber - Case the note belongs to
+ * @param id - Note to change
+ * @param resolved - Whether the discussion is resolved
+ */
+export async function resolveNote(caseNumber: string | null, id: number, resolved = true): Promise<void> {
+  return invoke("resolve_note", { caseNumber: caseNumber || null, id, resolved })
+}
+
+/**
+ * Removes a note together with its replies
+ * 
+ * @param caseNumber - Case the note belongs to
+ * @param id - Note to remove
+ */
+export async function removeNote(caseNumber: string | null, id: number): Promise<void> {
+  return invoke("remove_note", { caseNumber: caseNumber || null, id })
+}
+
+/**
+ * A "send to tool" action
+ * 
+ * The program runs directly, not through a shell. `{path}`, `{hash}` and
+ * `{name}` in an argument become the document's path, SHA-256 and file name.
+ */
+export interface ExternalTool {
+  id: string
+  name: string
+  program: string
+  args: string[]
+  /** Seconds before the tool is killed (default 60) */
+  timeout_secs?: number | null
+}
+
+/**
+ * Adds an external tool, or replaces the one with the same id
+ * 
+ * @param tool - The tool to save
+ */
+export async function saveExternalTool(tool: ExternalTool): Promise<void> {
+  return invoke("save_external_tool", { tool })
+}
+
+/**
+ * Lists the configured external tools
+ * 
+ * @returns Promise resolving to the tools
+ */
+export async function listExternalTools(): Promise<ExternalTool[]> {
+  return invoke<ExternalTool[]>("list_external_tools")
+}
+
+/**
+ * Removes an external tool
+ * 
+ * @param id - Tool to remove
+ */
+export async function removeExternalTool(id: string): Promise<void> {
+  return invoke("remove_external_tool", { id })
+}
+
+/**
+ * Runs an external tool on a document and keeps its output as a note
+ * 
+ * @param caseNumber - Case the document belongs to
+ * @param absolutePath - The document
+ * @param toolId - Tool to run
+ * @param author - Note author (default the tool's name)
+ * @returns Promise resolving to the note holding the tool's output
+ * @throws Error if the file is outside the case or the tool cannot start
+ */
+export async function runExternalTool(
+  caseNumber: string | null,
+  absolutePath: string,
+  toolId: string,
+  author: string | null = null
+): Promise<FileNote> {
+  return invoke<FileNote>("run_external_tool", { caseNumber: caseNumber || null, absolutePath, toolId, author })
+}
+
+/**
+ * Saves a watched search, replacing one with the same name in the case
+ * 
+ * @param watch - Watch to save
+ * @throws Error if the filter names an unknown field
+ */
+export async function saveWatch(watch: Watch): Promise<void> {
+  return invoke("save_watch", { watch })
+}
+
+/**
+ * Lists the watched searches for a case
+ * 
+ * @param caseNumber - Case to list watches for
+ * @returns Promise resolving to the watches
+ */
+export async function listWatches(caseNumber: string | null): Promise<Watch[]> {
+  return invoke<Watch[]>("list_watches", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Removes a watched search
+ * 
+ * @param caseNumber - Case the watch belongs to
+ * @param name - Watch name
+ * @throws Error if the watch does not exist
+ */
+export async function removeWatch(caseNumber: string | null, name: string): Promise<void> {
+  return invoke("remove_watch", { caseNumber: caseNumber || null, name })
+}
+
+/**
+ * Lists files that matched a watched search when they arrived, newest first
+ * 
+ * @param caseNumber - Case to list hits for
+ * @param limit - Maximum number of hits (defaults to 100)
+ * @returns Promise resolving to the hits
+ */
+export async function listWatchHits(caseNumber: string | null, limit?: number): Promise<WatchHit[]> {
+  return invoke<WatchHit[]>("list_watch_hits", { caseNumber: caseNumber || null, limit })
+}
+
+/**
+ * Writes the given columns of the items as a print-ready HTML table
+ * (landscape pages, repeated header row, page numbers); print it to PDF
+ * from the browser
+ * 
+ * @param items - The rows currently shown (already filtered)
+ * @param columns - Visible columns, in display order
+ * @param outputPath - Full path where the .html file should be saved
+ * @param caseNumber - Optional case number shown in the title
+ * @param includeSuppressed - Also print duplicates suppressed by the case's dedup policy
+ * @returns Promise that resolves when the file is written
+ * @throws Error if a column is unknown or the file cannot be written
+ */
+export async function exportPrintView(
+  items: InventoryItem[],
+  columns: InventoryItemField[],
+  outputPath: string,
+  caseNumber: string | null,
+  includeSuppressed = false
+): Promise<void> {
+  return invoke("export_print_view", {
+    items,
+    columns,
+    outputPath,
+    caseNumber: caseNumber || null,
+    includeSuppressed,
+  })
+}
+
+/**
+ * A deadline or derived date, e.g. "Answer due" 30 days after service
+ */
+export interface DateOffset {
+  label: string
+  /** YYYY-MM-DD */
+  base_date: string
+  /** Days to add; negative counts backwards */
+  days: number
+  /** Count only weekdays that are not holidays */
+  business_days?: boolean
+}
+
+/**
+ * A computed date with the calendar and business-day gap from its base date
+ */
+export interface ComputedDate {
+  label: string
+  base_date: string
+  date: string
+  weekday: string
+  calendar_days: number
+  business_days: number
+}
+
+/**
+ * Computes deadlines and derived dates for chronologies
+ * 
+ * @param offsets - Dates to derive
+ * @param holidays - Holidays (YYYY-MM-DD) skipped by business-day offsets
+ * @returns Promise resolving to the computed dates in the same order
+ * @throws Error if a base date or holiday is not a valid YYYY-MM-DD date
+ */
+export async function computeDateOffsets(
+  offsets: DateOffset[],
+  holidays: string[] = []
+): Promise<ComputedDate[]> {
+  return invoke<ComputedDate[]>("compute_date_offsets", { offsets, holidays })
+}
+
+/**
+ * Period patterns recognised in file names
+ */
+export type PeriodKind = "date_range" | "quarter" | "month" | "annual"
+
+/**
+ * A statement period found in an item's file name
+ */
+export interface ItemPeriod {
+  absolute_path: string
+  file_name: string
+  period: {
+    kind: PeriodKind
+    /** YYYY-MM-DD */
+    start: string
+    /** YYYY-MM-DD */
+    end: string
+  }
+}
+
+/**
+ * Extracts structured statement periods (start/end) from item file names
+ * 
+ * @param items - Inventory items to examine
+ * @param kinds - Patterns to try, in order (default: all, most specific first)
+ * @returns Promise resolving to the items that have a recognisable period
+ */
+export async function extractStatementPeriods(
+  items: InventoryItem[],
+  kinds?: PeriodKind[]
+): Promise<ItemPeriod[]> {
+  return invoke<ItemPeriod[]>("extract_statement_periods", { items, kinds })
+}
+
+/**
+ * Report section descriptor; rendered in the order given
+ */
+export type ReportSection =
+  | { kind: "case_summary" }
+  | { kind: "document_types" }
+  | { kind: "chronology" }
+  | { kind: "folder_summary" }
+  | { kind: "inventory_excerpt"; document_type: string | null; doc_year: number | null }
+  | { kind: "date_offsets"; offsets: DateOffset[]; holidays?: string[] }
+  /** The case's file links, see `linkFiles` */
+  | { kind: "file_links" }
+
+/**
+ * Report template: title plus the sections to include
+ */
+export interface ReportTemplate {
+  title: string
+  sections: ReportSection[]
+}
+
+/**
+ * Generates an HTML report for the inventory
+ * 
+ * @param items - Inventory items to report on
+ * @param template - Report template, or null for the default summary report
+ * @param outputPath - Full path where the report should be saved
+ * @param caseNumber - Optional case number shown in the case summary
+ * @param folderPath - Optional source folder shown in the case summary
+ * @param includeSuppressed - Also report duplicates suppressed by the case's dedup policy
+ * @returns Promise that resolves when the report is written
+ * @throws Error if the report cannot be written
+ */
+export async function generateReport(
+  items: InventoryItem[],
+  template: ReportTemplate | null,
+  outputPath: string,
+  caseNumber: string | null,
+  folderPath: string | null,
+  includeSuppressed = false
+): Promise<void> {
+  return invoke("generate_report", {
+    items,
+    template,
+    outputPath,
+    caseNumber: caseNumber || null,
+    folderPath: folderPath || null,
+    includeSuppressed,
+  })
+}
+
+/**
+ * A report template saved with a case under a name
+ */
+export interface SavedReportTemplate {
+  name: string
+  case_number: string | null
+  template: ReportTemplate
+}
+
+/**
+ * Saves a report template with its case, replacing one with the same name
+ * 
+ * @param template - Named template to save
+ */
+export async function saveReportTemplate(template: SavedReportTemplate): Promise<void> {
+  return invoke("save_report_template", { template })
+}
+
+/**
+ * Lists a case's saved report templates
+ * 
+ * @param caseNumber - Case to list templates for
+ * @returns Promise resolving to the templates
+ */
+export async function listReportTemplates(caseNumber: string | null): Promise<SavedReportTemplate[]> {
+  return invoke<SavedReportTemplate[]>("list_report_templates", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Removes a saved report template
+ * 
+ * @param caseNumber - Case the template belongs to
+ * @param name - Template name
+ * @throws Error if the template does not exist
+ */
+export async function removeReportTemplate(caseNumber: string | null, name: string): Promise<void> {
+  return invoke("remove_report_template", { caseNumber: caseNumber || null, name })
+}
+
+/**
+ * Generates an HTML report from one of the case's saved templates
+ * 
+ * @param caseNumber - Case the template belongs to, shown in the case summary
+ * @param templateName - Name of the saved template
+ * @param items - Inventory items to report on
+ * @param outputPath - Full path where the report should be saved
+ * @param folderPath - Optional source folder shown in the case summary
+ * @param includeSuppressed - Also report duplicates suppressed by the case's dedup policy
+ * @returns Promise that resolves when the report is written
+ * @throws Error if the case has no template with that name, or the report cannot be written
+ */
+export async function generateSavedReport(
+  caseNumber: string | null,
+  templateName: string,
+  items: InventoryItem[],
+  outputPath: string,
+  folderPath: string | null = null,
+  includeSuppressed = false
+): Promise<void> {
+  return invoke("generate_saved_report", {
+    caseNumber: caseNumber || null,
+    templateName,
+    items,
+    outputPath,
+    folderPath: folderPath || null,
+    includeSuppressed,
+  })
+}
+
+/**
+ * Entry in the structured activity log
+ */
+export interface ActivityEntry {
+  timestamp: string
+  kind: string
+  case_number: string | null
+  message: string
+  success: boolean
+}
+
+/**
+ * Gets recent scan/sync/export activity, newest first
+ * 
+ * @param caseNumber - Case whose own log to read; null reads the global log
+ * @param limit - Maximum number of entries (defaults to 100)
+ * @returns Promise resolving to activity entries
+ */
+export async function getRecentActivity(
+  caseNumber: string | null,
+  limit?: number
+): Promise<ActivityEntry[]> {
+  return invoke<ActivityEntry[]>("get_recent_activity", {
+    caseNumber: caseNumber || null,
+    limit,
+  })
+}
+
+/**
+ * Aggregated timings for one operation, recorded locally
+ */
+export interface OperationMetrics {
+  /** "scan", "ingest", "query", "export" or "sync" */
+  operation: string
+  runs: number
+  failures: number
+  total_ms: number
+  average_ms: number
+  max_ms: number
+  /** Duration of the most recent run */
+  last_ms: number
+  last_run: string
+  /** Items processed per second across successful runs */
+  items_per_second: number
+}
+
+/**
+ * Gets per-operation timings to diagnose slow cases. Metrics are stored on
+ * this device only.
+ * 
+ * @param caseNumber - Optional case number to limit metrics to
+ * @returns Promise resolving to one summary per operation
+ */
+export async function getPerformanceMetrics(
+  caseNumber: string | null = null
+): Promise<OperationMetrics[]> {
+  return invoke<OperationMetrics[]>("get_performance_metrics", { caseNumber })
+}
+
+/**
+ * Logs slow reads and writes of the app's data stores, for debugging large
+ * cases. Each slow operation is written to stderr with the file size and
+ * where the time went.
+ *
+ * @param thresholdMs - Operations at least this slow are logged; null turns the log off
+ */
+export async function setStoreSlowLog(thresholdMs: number | null): Promise<void> {
+  return invoke("set_store_slow_log", { thresholdMs })
+}
+
+/**
+ * What a health check of the app's data found and repaired
+ */
+export interface HealthReport {
+  checked_at: string
+  stores_checked: number
+  /** Stores that no longer parse; left in place to be restored from a backup */
+  unreadable_stores: string[]
+  /** Temporary files of interrupted writes, removed */
+  removed_partials: string[]
+  /** Vault entries whose stored copy was gone, removed from the index */
+  dropped_vault_entries: number
+  created_data_dir: boolean
+}
+
+/**
+ * Checks the app's data stores and makes the repairs that lose nothing
+ *
+ * The same check runs at startup; findings are written to the activity log.
+ *
+ * @returns Promise resolving to the health report
+ */
+export async function runHealthCheck(): Promise<HealthReport> {
+  return invoke<HealthReport>("run_health_check")
+}
+
+/**
+ * Saved configuration that can be shared as a file
+ */
+export type ConfigKind = "automation_rules" | "report_templates" | "keyword_lists" | "watches"
+
+/**
+ * Writes a case's configuration of one kind to a file without its case
+ * number, so it can be loaded into other cases or installs
+ *
+ * @param kind - Which configuration to write
+ * @param caseNumber - Case whose configuration to write; omit for the global set
+ * @param path - File to write
+ * @returns Promise resolving to the number of entries written
+ */
+export async function exportConfig(kind: ConfigKind, caseNumber: string | undefined, path: string): Promise<number> {
+  return invoke<number>("export_config", { kind, caseNumber: caseNumber || null, path })
+}
+
+/**
+ * Loads a config file into a case, replacing entries of the same name and
+ * keeping the rest
+ *
+ * Nothing is saved if any entry fails the checks its save command makes.
+ *
+ * @param kind - Which configuration the file must hold
+ * @param caseNumber - Case to load into; omit for the global set
+ * @param path - File written by exportConfig
+ * @returns Promise resolving to the names loaded
+ */
+export async function importConfig(kind: ConfigKind, caseNumber: string | undefined, path: string): Promise<string[]> {
+  return invoke<string[]>("import_config", { kind, caseNumber: caseNumber || null, path })
+}
+
+/**
+ * Configuration of one kind that applies to a case
+ */
+export type EffectiveConfig = {
+  /** Whether the case has none of its own, so the global set applies */
+  inherited: boolean
+} & (
+  | { kind: "automation_rules"; entries: AutomationRule[] }
+  | { kind: "report_templates"; entries: SavedReportTemplate[] }
+  | { kind: "keyword_lists"; entries: KeywordList[] }
+  | { kind: "watches"; entries: Watch[] }
+)
+
+/**
+ * Gets the configuration of one kind that applies to a case
+ *
+ * A case with none of its own inherits the global set; once it has any of
+ * its own, only those apply.
+ *
+ * @param kind - Which configuration to get
+ * @param caseNumber - Case to resolve for; omit for the global set
+ * @returns Promise resolving to the entries that apply
+ */
+export async function getEffectiveConfig(kind: ConfigKind, caseNumber?: string): Promise<EffectiveConfig> {
+  return invoke<EffectiveConfig>("get_effective_config", { kind, caseNumber: caseNumber || null })
+}
+
+/**
+ * Removes a case's own configuration of one kind so it inherits the global
+ * set again
+ *
+ * @param kind - Which configuration to reset
+ * @param caseNumber - Case to reset
+ * @returns Promise resolving to the names removed
+ */
+export async function resetCaseConfig(kind: ConfigKind, caseNumber: string): Promise<string[]> {
+  return invoke<string[]>("reset_case_config", { kind, caseNumber })
+}
+
+/**
+ * Distinct field value with its item count
+ */
+export interface FacetCount {
+  value: string
+  count: number
+}
+
+/**
+ * Gets the file categories in display order
+ * 
+ * @returns Promise resolving to category names, ending with "Other"
+ */
+export async function getFileCategories(): Promise<string[]> {
+  return invoke<string[]>("get_file_categories")
+}
+
+/**
+ * Fills in `file_category` for items saved before categories existed
+ * 
+ * @param items - Inventory items, some possibly without a category
+ * @returns Promise resolving to the items with categories set
+ */
+export async function categorizeItems(items: InventoryItem[]): Promise<InventoryItem[]> {
+  return invoke<InventoryItem[]>("categorize_items", { items })
+}
+
+/**
+ * Derived fields that can be recomputed without a rescan
+ */
+export type ReextractableField =
+  | "document_type"
+  | "document_description"
+  | "doc_date_range"
+  | "doc_year"
+  | "file_category"
+
+/**
+ * A field whose value changed when it was re-extracted
+ */
+export interface FieldChange {
+  absolute_path: string
+  field: string
+  old_value: string
+  new_value: string
+}
+
+export interface ReextractReport {
+  /** Every input item, with the selected fields recomputed */
+  items: InventoryItem[]
+  changes: FieldChange[]
+  /** Files whose metadata could not be read (doc_year only) */
+  failed: FailedFile[]
+}
+
+/**
+ * Recomputes only the given derived fields, e.g. just doc_date_range after a
+ * pattern fix, leaving every other field untouched
+ * 
+ * @param items - Inventory items
+ * @param fields - Fields to recompute
+ * @param caseNumber - Case the items belong to
+ * @param paths - Absolute paths of the items to update (default: all)
+ * @returns Promise resolving to the updated items and what changed
+ * @throws Error if a field cannot be re-extracted, or the case is missing or finalized
+ */
+export async function reextractFields(
+  items: InventoryItem[],
+  fields: ReextractableField[],
+  caseNumber: string,
+  paths?: string[]
+): Promise<ReextractReport> {
+  return invoke<ReextractReport>("reextract_fields", { items, paths, fields, caseNumber })
+}
+
+/**
+ * Re-runs document type and description mapping for the items under one
+ * folder, then applies the given rules to those items only
+ * 
+ * @param items - Inventory items
+ * @param folderPathPrefix - Relative folder path; nested folders are included
+ * @param caseNumber - Case the items belong to
+ * @param rules - Optional automation rules to apply to the folder's items
+ * @returns Promise resolving to the updated items and what changed
+ * @throws Error if a rule targets an unknown field, or the case is missing or finalized
+ */
+export async function reclassifyFolder(
+  items: InventoryItem[],
+  folderPathPrefix: string,
+  caseNumber: string,
+  rules?: AutomationRule[]
+): Promise<ReextractReport> {
+  return invoke<ReextractReport>("reclassify_folder", { items, folderPathPrefix, rules, caseNumber })
+}
+
+/**
+ * A term or two-word phrase and how often it occurs
+ */
+export interface TermCount {
+  term: string
+  /** Total occurrences across all items */
+  occurrences: number
+  /** Items containing the term at least once */
+  documents: number
+}
+
+export interface TermFrequencies {
+  documents: number
+  terms: TermCount[]
+  bigrams: TermCount[]
+}
+
+/**
+ * Finds the most common terms and two-word phrases in file names,
+ * descriptions and notes, to suggest search terms
+ * 
+ * @param items - Inventory items to analyze
+ * @param filter - Optional exact-match filters applied first
+ * @param limit - Maximum terms and bigrams returned (default 50 each)
+ * @returns Promise resolving to ranked terms and bigrams
+ * @throws Error if a filter field is unknown
+ */
+export async function computeTermFrequencies(
+  items: InventoryItem[],
+  filter?: Record<string, string>,
+  limit?: number
+): Promise<TermFrequencies> {
+  return invoke<TermFrequencies>("compute_term_frequencies", { items, filter, limit })
+}
+
+/**
+ * A group of items whose text is similar
+ */
+export interface DocumentCluster {
+  id: number
+  /** Highest-weighted terms, used as a label */
+  top_terms: string[]
+  paths: string[]
+}
+
+export interface ClusterReport {
+  /** Clusters with more than one member, largest first */
+  clusters: DocumentCluster[]
+  /** Items with no text or nothing similar to them */
+  unclustered: string[]
+}
+
+/**
+ * Groups similar items (TF-IDF over file names, types, descriptions and
+ * notes) so repetitive populations can be reviewed in batches
+ * 
+ * @param items - Inventory items to cluster
+ * @param threshold - Minimum cosine similarity (0-1) to join a cluster (default 0.5)
+ * @returns Promise resolving to clusters and unclustered paths
+ * @throws Error if the threshold is out of range
+ */
+export async function clusterDocuments(
+  items: InventoryItem[],
+  threshold?: number
+): Promise<ClusterReport> {
+  return invoke<ClusterReport>("cluster_documents", { items, threshold })
+}
+
+/**
+ * Counts distinct values of a field, most common first
+ * 
+ * @param items - Inventory items to aggregate
+ * @param field - Field to facet on (e.g. "document_type", "doc_year")
+ * @param filter - Optional exact-match filters applied before counting
+ * @param caseNumber - Case whose dedup policy applies
+ * @param includeSuppressed - Also count duplicates suppressed by the case's dedup policy
+ * @returns Promise resolving to facet counts
+ * @throws Error if the field name is unknown
+ */
+export async function getFieldFacets(
+  items: InventoryItem[],
+  field: InventoryItemField,
+  filter?: Partial<Record<InventoryItemField, string>>,
+  caseNumber: string | null = null,
+  includeSuppressed = false
+): Promise<FacetCount[]> {
+  return invoke<FacetCount[]>("get_field_facets", { items, field, filter, caseNumber, includeSuppressed })
+}
+
+/**
+ * One `field op value` condition of a case query; ordering comparisons are
+ * numeric when both sides are numbers
+ */
+export interface QueryCondition {
+  field: InventoryItemField
+  op: "equals" | "not_equals" | "contains" | "greater_than" | "less_than" | "is_empty" | "is_not_empty"
+  value?: string
+}
+
+/**
+ * An aggregate column of a grouped case query; `count` needs no field
+ */
+export interface QueryAggregate {
+  function: "count" | "count_distinct" | "min" | "max" | "sum"
+  field?: InventoryItemField
+}
+
+/**
+ * Structured query over inventory items. With `group_by` or `aggregates`
+ * there is one row per group; otherwise one row per item with the selected
+ * fields (all fields when none are selected).
+ */
+export interface CaseQuery {
+  select?: InventoryItemField[]
+  conditions?: QueryCondition[]
+  group_by?: InventoryItemField[]
+  aggregates?: QueryAggregate[]
+  limit?: number | null
+}
+
+/**
+ * Result table of a case query
+ */
+export interface QueryResult {
+  columns: string[]
+  rows: string[][]
+}
+
+/**
+ * Runs a structured query over the case's items, e.g. for pivot tables
+ * 
+ * @param caseNumber - Case whose dedup policy applies
+ * @param items - Inventory items to query
+ * @param query - Conditions, grouping and aggregates
+ * @param includeSuppressed - Also query duplicates suppressed by the case's dedup policy
+ * @returns Promise resolving to the result table
+ * @throws Error if the query names an unknown field
+ */
+export async function runCaseQuery(
+  caseNumber: string | null,
+  items: InventoryItem[],
+  query: CaseQuery,
+  includeSuppressed = false
+): Promise<QueryResult> {
+  return invoke<QueryResult>("run_case_query", {
+    caseNumber: caseNumber || null,
+    items,
+    query,
+    includeSuppressed,
+  })
+}
+
+/**
+ * Number of items in one histogram bucket ("YYYY" or "YYYY-MM")
+ */
+export interface HistogramBin {
+  bucket: string
+  count: number
+}
+
+/**
+ * Buckets items by date for coverage charts, oldest first
+ * 
+ * Empty buckets between the first and last date are included so gaps
+ * in coverage show up as zero counts.
+ * 
+ * @param items - Inventory items to aggregate
+ * @param field - Date field: "doc_year", "doc_date_range", or "date_rcvd"
+ * @param bucket - Bucket size: "year" or "month"
+ * @param caseNumber - Case whose dedup policy applies
+ * @param includeSuppressed - Also count duplicates suppressed by the case's dedup policy
+ * @returns Promise resolving to histogram bins
+ * @throws Error if the field does not hold dates
+ */
+export async function getDateHistogram(
+  items: InventoryItem[],
+  field: "doc_year" | "doc_date_range" | "date_rcvd",
+  bucket: "year" | "month",
+  caseNumber: string | null = null,
+  includeSuppressed = false
+): Promise<HistogramBin[]> {
+  return invoke<HistogramBin[]>("get_date_histogram", { items, field, bucket, caseNumber, includeSuppressed })
+}
+
+/**
+ * Matching item count at or below a folder
+ */
+export interface FolderHits {
+  folder_path: string
+  hits: number
+}
+
+/**
+ * Result of a folder-scoped search
+ */
+export interface FolderSearchResult {
+  matches: InventoryItem[]
+  hits_per_folder: FolderHits[]
+}
+
+/**
+ * Searches items within a folder subtree
+ * 
+ * Matches the query case-insensitively against all fields and rolls hit
+ * counts up to every ancestor folder for the navigator.
+ * 
+ * @param items - Inventory items to search
+ * @param folderPathPrefix - Relative folder path to search under ("" for all)
+ * @param query - Text to search for
+ * @param caseNumber - Case whose dedup policy applies
+ * @param includeSuppressed - Also search duplicates suppressed by the case's dedup policy
+ * @returns Promise resolving to matching items and hits per folder
+ * @throws Error if the case's dedup policy cannot be read
+ */
+export async function searchInFolder(
+  items: InventoryItem[],
+  folderPathPrefix: string,
+  query: string,
+  caseNumber: string | null = null,
+  includeSuppressed = false
+): Promise<FolderSearchResult> {
+  return invoke<FolderSearchResult>("search_in_folder", {
+    items,
+    folderPathPrefix,
+    query,
+    caseNumber,
+    includeSuppressed,
+  })
+}
+
+/**
+ * One hit from searching a whole case
+ */
+export interface SearchHit {
+  source: "file" | "note" | "link"
+  /** The item, the noted file or the link's source file */
+  absolute_path: string
+  /** First field that matched, e.g. "document_description", "content" or "relationship" */
+  field: string
+  value: string
+  /** Set for note hits */
+  note_id: number | null
+}
+
+/**
+ * Sources, file filters and paging for `searchAll`
+ */
+export interface SearchAllOptions {
+  /** Sources to search; empty or missing searches all of them */
+  sources?: SearchHit["source"][]
+  /** Exact `field == value` matches required of file hits */
+  filter?: Record<string, string>
+  /** Inclusive document year range for file hits */
+  year_from?: number | null
+  year_to?: number | null
+  /** Hits to skip in each source */
+  offset?: number
+  limit_per_source?: number | null
+}
+
+/**
+ * A page of hits from each searched source, with each source's total
+ */
+export interface SearchAllResults {
+  /** Files, then notes, then links */
+  hits: SearchHit[]
+  counts: { source: SearchHit["source"]; total: number }[]
+}
+
+/**
+ * Searches a whole case: inventory fields, notes, and link relationships and notes
+ * 
+ * @param caseNumber - Case to search
+ * @param items - Inventory items to search
+ * @param query - Text to search for
+ * @param options - Sources to search, file filters and paging
+ * @param includeSuppressed - Also search duplicates suppressed by the case's dedup policy
+ * @returns Promise resolving to the hits and per-source totals
+ * @throws Error if the filter names an unknown field
+ */
+export async function searchAll(
+  caseNumber: string | null,
+  items: InventoryItem[],
+  query: string,
+  options: SearchAllOptions = {},
+  includeSuppressed = false
+): Promise<SearchAllResults> {
+  return invoke<SearchAllResults>("search_all", {
+    caseNumber: caseNumber || null,
+    items,
+    query,
+    options,
+    includeSuppressed,
+  })
+}
+
+/**
+ * One line of a text diff
+ */
+export interface DiffLine {
+  kind: "same" | "added" | "removed"
+  text: string
+}
+
+/**
+ * Result of comparing two files
+ */
+export interface FileDiff {
+  identical: boolean
+  metadata: { field: string; a: string; b: string }[]
+  /** PDF, Office document and spreadsheet structure that differs */
+  structure: { field: string; a: string; b: string }[]
+  text_diff: DiffLine[] | null
+}
+
+/**
+ * Compares two files (e.g. near-duplicates or successive versions)
+ * 
+ * Always reports metadata differences. PDFs, Word/PowerPoint documents and
+ * spreadsheets also report structural differences (pages, properties, parts,
+ * sheets, cells). A line diff is included when both files are text, or
+ * Word/PowerPoint paragraphs and slides, and reasonably small.
+ * 
+ * Like previews, only files that are part of the case can be compared.
+ * 
+ * @param pathA - Absolute path to the first file
+ * @param pathB - Absolute path to the second file
+ * @param caseNumber - Case both files belong to
+ * @returns Promise resolving to the comparison result
+ * @throws Error if either path is missing, not a file or not part of the case
+ */
+export async function diffFiles(
+  pathA: string,
+  pathB: string,
+  caseNumber: string | null = null
+): Promise<FileDiff> {
+  return invoke<FileDiff>("diff_files", { pathA, pathB, caseNumber })
+}
+
+/**
+ * A file opened for editing outside the app
+ */
+export interface Checkout {
+  case_number: string | null
+  absolute_path: string
+  /** SHA-256 of the content at checkout */
+  hash: string
+  size_bytes: number
+  checked_out_at: string
+}
+
+/**
+ * Whether a checked-out file came back changed
+ */
+export interface CheckinResult {
+  checkout: Checkout
+  changed: boolean
+  new_hash: string
+  new_size_bytes: number
+}
+
+/**
+ * Snapshots a file's hash and opens it in its default application for editing
+ * 
+ * Call `checkinFile` when editing is done to find out whether it changed.
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ * @returns Promise resolving to the checkout
+ * @throws Error if the file is missing or not part of the case
+ */
+export async function checkoutFileForEdit(caseNumber: string | null, absolutePath: string): Promise<Checkout> {
+  const checkout = await invoke<Checkout>("checkout_file_for_edit", { caseNumber: caseNumber || null, absolutePath })
+  await openPath(absolutePath)
+  return checkout
+}
+
+/**
+ * Ends a checkout and reports whether the file changed
+ * 
+ * A change is recorded in the file's history and announced with
+ * `FILES_CHANGED_EVENT`, so the reviewer can be asked to update its status.
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ * @returns Promise resolving to the comparison with the checkout snapshot
+ * @throws Error if the file is not checked out
+ */
+export async function checkinFile(caseNumber: string | null, absolutePath: string): Promise<CheckinResult> {
+  return invoke<CheckinResult>("checkin_file", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * Lists the files checked out for editing in a case, oldest first
+ * 
+ * @param caseNumber - Case to list
+ * @returns Promise resolving to the checkouts
+ */
+export async function listCheckouts(caseNumber: string | null): Promise<Checkout[]> {
+  return invoke<Checkout[]>("list_checkouts", { caseNumber: caseNumber || null })
+}
+
+/**
+ * A stored copy of a file's content at one point in time
+ */
+export interface FileVersion {
+  case_number: string | null
+  absolute_path: string
+  /** SHA-256 of the content; identifies the version */
+  hash: string
+  size_bytes: number
+  captured_at: string
+}
+
+/**
+ * Turns versioning on or off for a case
+ * 
+ * With versioning on, files checked out for editing keep a stored copy of
+ * each version in the case's data directory.
+ * 
+ * @param caseNumber - Case to change
+ * @param enabled - Whether to store versions
+ */
+export async function setFileVersioning(caseNumber: string | null, enabled: boolean): Promise<void> {
+  return invoke("set_file_versioning", { caseNumber: caseNumber || null, enabled })
+}
+
+/**
+ * Gets whether versioning is on for a case
+ * 
+ * @param caseNumber - Case to check
+ * @returns Promise resolving to true if versions are stored
+ */
+export async function getFileVersioning(caseNumber: string | null): Promise<boolean> {
+  return invoke<boolean>("get_file_versioning", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Stores a file's current content as a version, whether or not versioning is on
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ * @returns Promise resolving to the new version, or null if it matches the latest one
+ * @throws Error if the file is missing or not part of the case
+ */
+export async function captureFileVersion(caseNumber: string | null, absolutePath: string): Promise<FileVersion | null> {
+  return invoke<FileVersion | null>("capture_file_version", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * Lists the stored versions of a file, oldest first
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ * @returns Promise resolving to the versions
+ */
+export async function listFileVersions(caseNumber: string | null, absolutePath: string): Promise<FileVersion[]> {
+  return invoke<FileVersion[]>("list_file_versions", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * Writes a stored version of a file to disk
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ * @param hash - Version to export
+ * @param outputPath - Where to write the copy
+ * @throws Error if the file has no such version
+ */
+export async function exportFileVersion(
+  caseNumber: string | null,
+  absolutePath: string,
+  hash: string,
+  outputPath: string
+): Promise<void> {
+  return invoke("export_file_version", { caseNumber: caseNumber || null, absolutePath, hash, outputPath })
+}
+
+/**
+ * Compares two stored versions of a file, or a version with the current file
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ * @param hashA - Earlier version
+ * @param hashB - Later version; omit to compare with the file as it is now
+ * @returns Promise resolving to the comparison result
+ * @throws Error if the file has no such version
+ */
+export async function diffFileVersions(
+  caseNumber: string | null,
+  absolutePath: string,
+  hashA: string,
+  hashB: string | null = null
+): Promise<FileDiff> {
+  return invoke<FileDiff>("diff_file_versions", { caseNumber: caseNumber || null, absolutePath, hashA, hashB })
+}
+
+/**
+ * A slice of a file's bytes for previews
+ */
+export interface FileChunk {
+  offset: number
+  /** Size of the whole file, so callers know how many chunks remain */
+  total_size: number
+  data: number[]
+}
+
+/**
+ * Reads part of a file so large files can be previewed page by page
+ * 
+ * The backend returns at most 4 MiB per call; reading at or past the end
+ * returns an empty chunk. Only files under a folder the case has scanned or
+ * synced, or files added to it, can be read.
+ * 
+ * @param filePath - Absolute path to the file
+ * @param offset - Byte offset to start reading at
+ * @param length - Number of bytes requested
+ * @param caseNumber - Case the file belongs to
+ * @returns Promise resolving to the chunk and the file's total size
+ * @throws Error if the path is missing, not a file or not part of the case
+ */
+export async function readFileRange(
+  filePath: string,
+  offset: number,
+  length: number,
+  caseNumber: string | null = null
+): Promise<FileChunk> {
+  return invoke<FileChunk>("read_file_range", { filePath, offset, length, caseNumber })
+}
+
+/**
+ * Builds a URL that streams a file to the webview through the `casefile`
+ * protocol, for use as an `<img>`, `<video>` or `<iframe>` source
+ * 
+ * Media elements receive byte ranges as they play, so large files are never
+ * sent through IPC. No response is over 4 MiB: a larger file requested
+ * without a Range header gets its first 4 MiB as a partial response. Files outside the case's scanned folders and added files
+ * are refused with 403. A file that is gone is served from the case's vault
+ * when it has a copy there.
+ * 
+ * @param filePath - Absolute path to the file
+ * @param caseNumber - Case the file belongs to
+ * @returns URL for the file
+ */
+export function previewUrl(filePath: string, caseNumber: string | null = null): string {
+  const url = convertFileSrc(filePath, "casefile")
+  return caseNumber ? `${url}?case=${encodeURIComponent(caseNumber)}` : url
+}
+
+/**
+ * One actionable result from a source access check
+ */
+export interface AccessFinding {
+  severity: "info" | "warning" | "error"
+  code: string
+  message: string
+}
+
+/**
+ * Result of diagnosing access to a source folder
+ */
+export interface AccessDiagnosis {
+  path: string
+  readable: boolean
+  entries_listed: number
+  files_sampled: number
+  placeholders_sampled: number
+  findings: AccessFinding[]
+}
+
+/**
+ * Explains why a folder may scan as empty or partially
+ * 
+ * Checks existence, read permission (including macOS Full Disk Access),
+ * cloud-sync placeholders, and whether sampled files can be opened.
+ * 
+ * @param path - Absolute path to the source folder
+ * @returns Promise resolving to the diagnosis with actionable findings
+ */
+export async function diagnoseSourceAccess(path: string): Promise<AccessDiagnosis> {
+  return invoke<AccessDiagnosis>("diagnose_source_access", { path })
+}
+
+/**
+ * Whether a source folder can be synced right now
+ */
+export interface SourceStatus {
+  available: boolean
+  reason: string | null
+}
+
+/**
+ * Checks that a source folder is reachable (e.g. the removable drive is
+ * mounted) before syncing
+ * 
+ * @param path - Absolute path to the source folder
+ * @returns Promise resolving to the source status
+ */
+export async function checkSourceAvailable(path: string): Promise<SourceStatus> {
+  return invoke<SourceStatus>("check_source_available", { path })
+}
+
+/**
+ * A field change applied (or previewed) from a review decisions file
+ */
+export interface DecisionChange {
+  key: string
+  absolute_path: string
+  field: string
+  old_value: string
+  new_value: string
+}
+
+/**
+ * Result of importing review decisions
+ */
+export interface DecisionImportResult {
+  items: InventoryItem[]
+  changes: DecisionChange[]
+  unmatched_keys: string[]
+  ambiguous_keys: string[]
+  ignored_columns: string[]
+  dry_run: boolean
+}
+
+/**
+ * Applies decisions from an external reviewer's CSV in bulk
+ * 
+ * The first column holds a Bates stamp or file name; other columns are
+ * inventory fields by export header (e.g. "Notes", "Document Type").
+ * Runs as a preview unless dryRun is false.
+ * 
+ * @param items - Current inventory items
+ * @param filePath - Path to the decisions CSV
+ * @param caseNumber - Case the items belong to
+ * @param dryRun - Preview changes without applying them (default true)
+ * @returns Promise resolving to the updated items and a mismatch report
+ * @throws Error if the file cannot be read, or when applying, if the case is missing or finalized
+ */
+export async function importReviewDecisions(
+  items: InventoryItem[],
+  filePath: string,
+  caseNumber: string,
+  dryRun = true
+): Promise<DecisionImportResult> {
+  return invoke<DecisionImportResult>("import_review_decisions", {
+    items,
+    filePath,
+    dryRun,
+    caseNumber,
+  })
+}
+
+/**
+ * "When an item matches, set field to value" automation rule
+ */
+export interface AutomationRule {
+  name: string
+  case_number?: string | null
+  /** Case-insensitive text that must appear in any field */
+  query?: string | null
+  /** Exact-match field filters that must all hold */
+  filter?: Partial<Record<InventoryItemField, string>>
+  set_field: InventoryItemField
+  set_value: string
+  /** Only fill the field when it is empty, preserving manual edits */
+  only_if_empty?: boolean
+}
+
+/**
+ * A change made automatically by a rule
+ */
+export interface RuleAction {
+  rule: string
+  absolute_path: string
+  field: string
+  old_value: string
+  new_value: string
+}
+
+/**
+ * Applies automation rules in order (e.g. after a scan); every change is
+ * logged in the case's activity log
+ * 
+ * @param items - Inventory items to triage
+ * @param rules - Rules to evaluate, later rules seeing earlier changes; null runs the case's stored rules
+ * @param caseNumber - Case the items belong to
+ * @returns Promise resolving to updated items and a log of actions taken
+ * @throws Error if a rule targets an unknown field, or the case is missing or finalized
+ */
+export async function applyAutomationRules(
+  items: InventoryItem[],
+  rules: AutomationRule[] | null,
+  caseNumber: string
+): Promise<{ items: InventoryItem[]; actions: RuleAction[] }> {
+  return invoke("apply_automation_rules", { items, rules, caseNumber })
+}
+
+/**
+ * Saves an automation rule, replacing one with the same name in the case.
+ * Stored rules run on every file a sync or ingest brings in.
+ * 
+ * @param rule - Rule to save, with its case number
+ * @throws Error if the rule names an unknown field
+ */
+export async function saveAutomationRule(rule: AutomationRule): Promise<void> {
+  return invoke("save_automation_rule", { rule })
+}
+
+/**
+ * Lists a case's stored automation rules, in the order they run
+ * 
+ * @param caseNumber - Case to list rules for
+ * @returns Promise resolving to the rules
+ */
+export async function listAutomationRules(caseNumber: string | null): Promise<AutomationRule[]> {
+  return invoke<AutomationRule[]>("list_automation_rules", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Removes a stored automation rule
+ * 
+ * @param caseNumber - Case the rule belongs to
+ * @param name - Rule name
+ * @throws Error if the rule does not exist
+ */
+export async function removeAutomationRule(caseNumber: string | null, name: string): Promise<void> {
+  return invoke("remove_automation_rule", { caseNumber: caseNumber || null, name })
+}
+
+/**
+ * Constraints on one inventory field
+ */
+export interface FieldRule {
+  field: InventoryItemField
+  /** Must be filled in before the inventory is finalized */
+  required?: boolean
+  /** Regular expression the whole value must match, e.g. "ABC\d{6}"; case-insensitive */
+  pattern?: string | null
+  /** When not empty, the only values accepted (case-insensitive) */
+  allowed_values?: string[]
+}
+
+export interface RuleViolation {
+  absolute_path: string
+  file_name: string
+  field: string
+  value: string
+  problem: string
+}
+
+export interface FinalizationCheck {
+  /** True when no item breaks any rule */
+  ready: boolean
+  violations: RuleViolation[]
+}
+
+/**
+ * Replaces a case's field rules
+ * 
+ * Saved rules are enforced on every recorded edit and before the case can be
+ * finalized.
+ * 
+ * @param caseNumber - Case the rules apply to
+ * @param rules - The case's rules; an empty list removes them
+ * @throws Error if a rule names an unknown field
+ */
+export async function saveFieldRules(caseNumber: string | null, rules: FieldRule[]): Promise<void> {
+  return invoke("save_field_rules", { caseNumber: caseNumber || null, rules })
+}
+
+/**
+ * Gets a case's saved field rules
+ * 
+ * @param caseNumber - Case to read
+ * @returns Promise resolving to the case's rules
+ */
+export async function listFieldRules(caseNumber: string | null): Promise<FieldRule[]> {
+  return invoke<FieldRule[]>("list_field_rules", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Checks an edited value against the field's rules before saving it
+ * 
+ * Blank values are accepted; required fields are only enforced at finalization.
+ * 
+ * @param caseNumber - Case whose saved rules apply when `rules` is omitted
+ * @param field - Field being edited
+ * @param value - New value
+ * @param rules - Field rules to check instead of the saved ones
+ * @throws Error describing why the value is not allowed
+ */
+export async function validateFieldEdit(
+  caseNumber: string | null,
+  field: InventoryItemField,
+  value: string,
+  rules?: FieldRule[]
+): Promise<void> {
+  return invoke("validate_field_edit", { caseNumber: caseNumber || null, field, value, rules: rules ?? null })
+}
+
+/**
+ * Lists the rows and fields that block finalizing the inventory
+ * 
+ * @param caseNumber - Case whose saved rules apply when `rules` is omitted
+ * @param items - Inventory items to check
+ * @param rules - Field rules to check instead of the saved ones
+ * @returns Promise resolving to whether the inventory is ready and any violations
+ * @throws Error if a rule names an unknown field
+ */
+export async function validateForFinalization(
+  caseNumber: string | null,
+  items: InventoryItem[],
+  rules?: FieldRule[]
+): Promise<FinalizationCheck> {
+  return invoke<FinalizationCheck>("validate_for_finalization", { caseNumber: caseNumber || null, items, rules: rules ?? null })
+}
+
+/**
+ * An item with key fields left blank
+ */
+export interface EmptyFields {
+  absolute_path: string
+  file_name: string
+  fields: string[]
+}
+
+export interface DuplicateBates {
+  bates_stamp: string
+  paths: string[]
+}
+
+/**
+ * Identical files whose coding disagrees on a field
+ */
+export interface InconsistentDuplicates {
+  hash: string
+  field: string
+  paths: string[]
+  values: string[]
+}
+
+export interface QcReport {
+  total_items: number
+  empty_fields: EmptyFields[]
+  duplicate_bates: DuplicateBates[]
+  /** Only filled when the duplicate check was requested */
+  inconsistent_duplicates: InconsistentDuplicates[]
+  /** Items whose source file no longer exists */
+  missing_files: string[]
+  /** True when no check found a problem */
+  passed: boolean
+}
+
+/**
+ * Runs pre-delivery quality checks over the inventory
+ * 
+ * @param items - Inventory items to check
+ * @param keyFields - Fields that must not be blank (default: document type,
+ *   description, date range and Bates stamp)
+ * @param checkDuplicates - Hash files to find identical copies coded differently (slow)
+ * @param workers - Hashing workers for the duplicate check
+ * @returns Promise resolving to the QC report
+ * @throws Error if a key field name is unknown
+ */
+export async function generateQcReport(
+  items: InventoryItem[],
+  keyFields: InventoryItemField[] | null = null,
+  checkDuplicates: boolean = false,
+  workers?: number
+): Promise<QcReport> {
+  return invoke<QcReport>("generate_qc_report", { items, keyFields, checkDuplicates, workers })
+}
+
+/**
+ * A review hint: something statistically unusual about one file
+ */
+export interface Anomaly {
+  absolute_path: string
+  file_name: string
+  /** Year far outside the case range, one-off file type, or much larger than its folder's other files */
+  kind: "date_outlier" | "rare_type" | "large_in_folder"
+  detail: string
+}
+
+/**
+ * Flags statistical outliers among the items, e.g. right after ingestion
+ * 
+ * @param items - Inventory items to check
+ * @param firstYear - Optional first year of the case range (inferred from the items when omitted)
+ * @param lastYear - Optional last year of the case range
+ * @returns Promise resolving to the review hints found
+ */
+export async function listAnomalies(
+  items: InventoryItem[],
+  firstYear?: number,
+  lastYear?: number
+): Promise<Anomaly[]> {
+  return invoke<Anomaly[]>("list_anomalies", { items, firstYear, lastYear })
+}
+
+export type InconsistencyKind =
+  | "modified_before_document"
+  | "created_after_hold"
+  | "modified_after_hold"
+  | "modified_long_after_document"
+
+/**
+ * A file whose filesystem dates disagree with its document date
+ */
+export interface DateInconsistency {
+  absolute_path: string
+  file_name: string
+  kind: InconsistencyKind
+  doc_date_range: string
+  /** YYYY-MM-DD, local time; empty when the filesystem does not record it */
+  created: string
+  modified: string
+  detail: string
+}
+
+/**
+ * Flags files whose filesystem created/modified dates disagree with their
+ * document date range, e.g. a 2015 letter created on disk after the hold
+ * 
+ * @param items - Inventory items to check
+ * @param holdDate - Litigation hold date (YYYY-MM-DD)
+ * @param maxGapDays - Also flag files modified more than this many days after their period ends
+ * @param outputPath - Optionally write the findings to this XLSX or CSV file
+ * @returns Promise resolving to the inconsistencies found
+ */
+export async function checkDateConsistency(
+  items: InventoryItem[],
+  holdDate?: string,
+  maxGapDays?: number,
+  outputPath?: string
+): Promise<DateInconsistency[]> {
+  return invoke<DateInconsistency[]>("check_date_consistency", {
+    items,
+    holdDate,
+    maxGapDays,
+    outputPath,
+  })
+}
+
+/**
+ * A trusted timestamp obtained for a digest; the TSA response is saved as a
+ * `.tsr` file that `openssl ts -verify` accepts
+ */
+export interface TimestampReceipt {
+  tsa_url: string
+  /** SHA-256 digest that was timestamped, hex */
+  digest: string
+  /** Time asserted by the TSA, UTC */
+  gen_time: string
+  serial_number: string
+  token_path: string
+}
+
+/**
+ * One file as it stood when its case was finalized
+ */
+export interface FinalizedFile {
+  absolute_path: string
+  /** SHA-256 of the file content */
+  content_hash: string
+  /** SHA-256 of the item's inventory fields */
+  metadata_hash: string
+}
+
+/**
+ * A delivered case snapshot, sealed by a Merkle root over its files
+ */
+export interface FinalizedCase {
+  case_number: string
+  finalized_at: string
+  merkle_root: string
+  files: FinalizedFile[]
+  /** RFC 3161 timestamp of the Merkle root, when one was obtained */
+  timestamp: TimestampReceipt | null
+  /** Version of the field list covered by each file's metadata hash */
+  metadata_version: number
+}
+
+/**
+ * Result of checking a case's current inventory against its finalized snapshot
+ */
+export interface CaseVerification {
+  case_number: string
+  finalized_at: string
+  merkle_root: string
+  current_root: string
+  /** True when nothing changed since finalization */
+  verified: boolean
+  modified_content: string[]
+  modified_metadata: string[]
+  missing: string[]
+  added: string[]
+}
+
+/**
+ * Finalizes a case at delivery. Afterwards, commands that would change its
+ * inventory (sync, ingest, rename, reorganize, reclassify, dedup) are refused.
+ * 
+ * @param caseNumber - Case to finalize
+ * @param items - The delivered inventory
+ * @param workers - Optional hashing worker count
+ * @returns Promise resolving to the stored snapshot
+ */
+export async function finalizeCase(
+  caseNumber: string,
+  items: InventoryItem[],
+  workers?: number
+): Promise<FinalizedCase> {
+  return invoke<FinalizedCase>("finalize_case", { caseNumber, items, workers })
+}
+
+/**
+ * Re-hashes the case's current inventory and reports what changed since it
+ * was finalized
+ * 
+ * @param caseNumber - Finalized case
+ * @param items - The case's current inventory
+ * @param workers - Optional hashing worker count
+ * @returns Promise resolving to the verification result
+ */
+export async function verifyFinalizedCase(
+  caseNumber: string,
+  items: InventoryItem[],
+  workers?: number
+): Promise<CaseVerification> {
+  return invoke<CaseVerification>("verify_finalized_case", { caseNumber, items, workers })
+}
+
+/**
+ * Gets the stored snapshot for a case
+ * 
+ * @param caseNumber - Case to look up
+ * @returns Promise resolving to the snapshot, or null when not finalized
+ */
+export async function getFinalizedCase(caseNumber: string): Promise<FinalizedCase | null> {
+  return invoke<FinalizedCase | null>("get_finalized_case", { caseNumber })
+}
+
+/**
+ * Timestamps a finalized case's Merkle root with an RFC 3161 timestamping
+ * authority and stores the receipt with the case's snapshot
+ * 
+ * @param caseNumber - Finalized case
+ * @param tsaUrl - The TSA's http:// endpoint
+ * @returns Promise resolving to the updated snapshot
+ */
+export async function timestampFinalizedCase(caseNumber: string, tsaUrl: string): Promise<FinalizedCase> {
+  return invoke<FinalizedCase>("timestamp_finalized_case", { caseNumber, tsaUrl })
+}
+
+/**
+ * Timestamps an export or manifest file with an RFC 3161 timestamping
+ * authority, saving the token next to it as `<file>.tsr`
+ * 
+ * @param filePath - Export or manifest to timestamp
+ * @param tsaUrl - The TSA's http:// endpoint
+ * @param caseNumber - Optional case for the activity log
+ * @returns Promise resolving to the timestamp receipt
+ */
+export async function timestampFile(
+  filePath: string,
+  tsaUrl: string,
+  caseNumber?: string
+): Promise<TimestampReceipt> {
+  return invoke<TimestampReceipt>("timestamp_file", { filePath, tsaUrl, caseNumber })
+}
+
+/**
+ * A named, saved set of files used to scope search, export or review
+ */
+export interface Binder {
+  name: string
+  case_number: string | null
+  /** Member files by absolute path, sorted */
+  paths: string[]
+  created_at: string
+  updated_at: string
+}
+
+/**
+ * Lists the binders saved for a case, sorted by name
+ * 
+ * @param caseNumber - Case to list binders for; null lists binders without a case
+ * @returns Promise resolving to the binders
+ */
+export async function listBinders(caseNumber: string | null): Promise<Binder[]> {
+  return invoke<Binder[]>("list_binders", { caseNumber })
+}
+
+/**
+ * Adds files to a binder, creating it if needed
+ * 
+ * Pass `paths` for a manual selection, or `items` with an optional exact-match
+ * `filter` and text `query` to add every matching item.
+ * 
+ * @param caseNumber - Case the binder belongs to
+ * @param name - Binder name (matched case-insensitively)
+ * @param selection - Paths and/or items plus filter and query
+ * @returns Promise resolving to the updated binder
+ */
+export async function addToBinder(
+  caseNumber: string | null,
+  name: string,
+  selection: {
+    paths?: string[]
+    items?: InventoryItem[]
+    filter?: Record<string, string>
+    query?: string
+  }
+): Promise<Binder> {
+  return invoke<Binder>("add_to_binder", { caseNumber, name, ...selection })
+}
+
+/**
+ * Removes files from a binder
+ * 
+ * @param caseNumber - Case the binder belongs to
+ * @param name - Binder name
+ * @param paths - Absolute paths to remove
+ * @returns Promise resolving to the updated binder
+ * @throws Error if the binder does not exist
+ */
+export async function removeFromBinder(
+  caseNumber: string | null,
+  name: string,
+  paths: string[]
+): Promise<Binder> {
+  return invoke<Binder>("remove_from_binder", { caseNumber, name, paths })
+}
+
+/**
+ * Deletes a binder (the files themselves are untouched)
+ * 
+ * @param caseNumber - Case the binder belongs to
+ * @param name - Binder name
+ * @throws Error if the binder does not exist
+ */
+export async function deleteBinder(caseNumber: string | null, name: string): Promise<void> {
+  return invoke("delete_binder", { caseNumber, name })
+}
+
+/**
+ * Narrows items to a binder's members, e.g. before searching or exporting
+ * 
+ * @param caseNumber - Case the binder belongs to
+ * @param name - Binder name
+ * @param items - Items to narrow
+ * @returns Promise resolving to the items in the binder, in their original order
+ * @throws Error if the binder does not exist
+ */
+export async function getBinderItems(
+  caseNumber: string | null,
+  name: string,
+  items: InventoryItem[]
+): Promise<InventoryItem[]> {
+  return invoke<InventoryItem[]>("get_binder_items", { caseNumber, name, items })
+}
+
+/**
+ * Pins or unpins a file so key documents stay easy to find
+ * 
+ * @param caseNumber - Case the pin belongs to
+ * @param absolutePath - File to pin or unpin
+ * @returns Promise resolving to true if the file is now pinned
+ */
+export async function toggleFilePinned(
+  caseNumber: string | null,
+  absolutePath: string
+): Promise<boolean> {
+  return invoke<boolean>("toggle_file_pinned", { caseNumber, absolutePath })
+}
+
+/**
+ * Gets pinned file paths for a case
+ * 
+ * @param caseNumber - Case to read pins for
+ * @param items - Optional items to restrict the result to (the pinned filter)
+ * @returns Promise resolving to pinned absolute paths, in item order when items are given
+ */
+export async function getPinnedFiles(
+  caseNumber: string | null,
+  items: InventoryItem[] | null = null
+): Promise<string[]> {
+  return invoke<string[]>("get_pinned_files", { caseNumber, items })
+}
+
+/**
+ * A file recently opened for review
+ */
+export interface RecentFile {
+  absolute_path: string
+  opened_at: string
+}
+
+/**
+ * Review activity for a case
+ */
+export interface ReviewStats {
+  total_opened: number
+  /** Days with at least one file opened, oldest first; `date` is `YYYY-MM-DD` */
+  days: { date: string; files_opened: number }[]
+  last_opened: RecentFile | null
+}
+
+/**
+ * Opens a file in its default application and records it in the case's recents
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ * @throws Error if the file cannot be opened
+ */
+export async function openFile(caseNumber: string | null, absolutePath: string): Promise<void> {
+  await openPath(absolutePath)
+  await recordFileOpened(caseNumber, absolutePath).catch((error) => {
+    console.warn("Failed to record recently opened file:", error)
+  })
+}
+
+/**
+ * Records that a file was opened for review
+ * 
+ * @param caseNumber - Case the file belongs to
+ * @param absolutePath - Absolute path of the file
+ */
+export async function recordFileOpened(caseNumber: string | null, absolutePath: string): Promise<void> {
+  return invoke("record_file_opened", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * Gets the files recently opened in a case, newest first
+ * 
+ * @param caseNumber - Case to list
+ * @param limit - Most files to return (default 20)
+ * @returns Promise resolving to the recent files
+ */
+export async function getRecentFiles(caseNumber: string | null, limit: number | null = null): Promise<RecentFile[]> {
+  return invoke<RecentFile[]>("get_recent_files", { caseNumber: caseNumber || null, limit })
+}
+
+/**
+ * Gets files opened per day in a case and the last file opened
+ * 
+ * @param caseNumber - Case to summarize
+ * @returns Promise resolving to the case's review stats
+ */
+export async function getReviewStats(caseNumber: string | null): Promise<ReviewStats> {
+  return invoke<ReviewStats>("get_review_stats", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Keyword (search term) hit report
+ */
+export interface KeywordReport {
+  terms: { term: string; documents: number }[]
+  /** Documents hit by at least one term; hits[i] corresponds to terms[i] */
+  documents: {
+    absolute_path: string
+    file_name: string
+    bates_stamp: string
+    hits: boolean[]
+  }[]
+  unique_documents: number
+}
+
+/**
+ * Reads a keyword list file (one term per line, # comments ignored)
+ * 
+ * @param filePath - Path to the keyword list
+ * @returns Promise resolving to the search terms
+ */
+export async function readKeywordList(filePath: string): Promise<string[]> {
+  return invoke<string[]>("read_keyword_list", { filePath })
+}
+
+/**
+ * A named list of search terms kept with a case
+ */
+export interface KeywordList {
+  name: string
+  case_number: string | null
+  terms: string[]
+}
+
+/**
+ * Saves a keyword list with its case, replacing one with the same name
+ * 
+ * @param list - Keyword list to save
+ */
+export async function saveKeywordList(list: KeywordList): Promise<void> {
+  return invoke("save_keyword_list", { list })
+}
+
+/**
+ * Reads a keyword list file and saves it with the case
+ * 
+ * @param caseNumber - Case to save the list with
+ * @param name - Name to save the list under
+ * @param filePath - Path to the keyword list (one term per line, # comments ignored)
+ * @returns Promise resolving to the saved list
+ */
+export async function importKeywordList(
+  caseNumber: string | null,
+  name: string,
+  filePath: string
+): Promise<KeywordList> {
+  return invoke<KeywordList>("import_keyword_list", { caseNumber: caseNumber || null, name, filePath })
+}
+
+/**
+ * Lists a case's saved keyword lists
+ * 
+ * @param caseNumber - Case to list keyword lists for
+ * @returns Promise resolving to the lists
+ */
+export async function listKeywordLists(caseNumber: string | null): Promise<KeywordList[]> {
+  return invoke<KeywordList[]>("list_keyword_lists", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Removes a saved keyword list
+ * 
+ * @param caseNumber - Case the list belongs to
+ * @param name - List name
+ * @throws Error if the list does not exist
+ */
+export async function removeKeywordList(caseNumber: string | null, name: string): Promise<void> {
+  return invoke("remove_keyword_list", { caseNumber: caseNumber || null, name })
+}
+
+/**
+ * Computes per-term hit counts and a per-document term matrix for one of
+ * the case's saved keyword lists
+ * 
+ * @param caseNumber - Case the list belongs to
+ * @param listName - Name of the saved keyword list
+ * @param items - Inventory items to search
+ * @param outputPath - Optional XLSX path to write the report to
+ * @returns Promise resolving to the keyword report
+ * @throws Error if the case has no list with that name
+ */
+export async function runKeywordReport(
+  caseNumber: string | null,
+  listName: string,
+  items: InventoryItem[],
+  outputPath: string | null = null
+): Promise<KeywordReport> {
+  return invoke<KeywordReport>("run_keyword_report", {
+    caseNumber: caseNumber || null,
+    listName,
+    items,
+    outputPath,
+  })
+}
+
+/**
+ * One copy of a duplicated file
+ */
+export interface DuplicateMember {
+  absolute_path: string
+  file_name: string
+  bates_stamp: string
+  /** The copy kept for review; the first in inventory order */
+  primary: boolean
+}
+
+/**
+ * Files with identical content
+ */
+export interface DuplicateGroup {
+  group: number
+  hash: string
+  size: number
+  members: DuplicateMember[]
+  /** Space taken by every copy but the primary */
+  wasted_bytes: number
+}
+
+export interface DuplicatesReport {
+  groups: DuplicateGroup[]
+  /** Non-primary copies across all groups */
+  duplicate_files: number
+  wasted_bytes: number
+}
+
+/**
+ * Finds identical files and writes a duplicate analysis report
+ * 
+ * @param items - Inventory items to check
+ * @param outputPath - Report path (.xlsx or .csv)
+ * @param format - "xlsx" or "csv" (default: from the extension)
+ * @param workers - Concurrent hashing workers (default: CPU count, max 8)
+ * @param pathCase - How paths are compared (default: probe the volume)
+ * @returns Promise resolving to the duplicate groups written
+ * @throws Error if the format is unsupported or the report cannot be written
+ */
+export async function exportDuplicatesReport(
+  items: InventoryItem[],
+  outputPath: string,
+  format?: "xlsx" | "csv",
+  workers?: number,
+  pathCase?: PathCase
+): Promise<DuplicatesReport> {
+  return invoke<DuplicatesReport>("export_duplicates_report", { items, outputPath, format, workers, pathCase })
+}
+
+/**
+ * A case's duplicate culling state. Suppressed copies are left out of default
+ * views, counts and exports unless re-included.
+ */
+export interface DedupPolicy {
+  case_number: string | null
+  /** Member paths of each duplicate group, by content hash */
+  groups: Record<string, string[]>
+  /** Reviewer-chosen primary by content hash; otherwise the first member */
+  primaries: Record<string, string>
+  /** Duplicates kept in review scope despite not being primary */
+  included: string[]
+  /** Copies excluded from review scope */
+  suppressed: string[]
+  /** Paths are matched ignoring case, as on the volume the policy was applied to */
+  case_insensitive: boolean
+}
+
+/** Event emitted with a `ChangeEvent` when a dedup policy changes */
+export const DEDUP_CHANGED_EVENT = "case://dedup-changed"
+
+/**
+ * Finds duplicates among the case's items and suppresses every copy but the
+ * primary. Earlier primary and re-include choices are kept.
+ * 
+ * @param caseNumber - Case the policy belongs to
+ * @param items - The case's inventory items
+ * @param workers - Concurrent hashing workers (default: CPU count, max 8)
+ * @param pathCase - How paths are compared (default: probe the volume)
+ * @returns Promise resolving to the updated policy
+ * @throws Error if the case number is missing or the case is finalized
+ */
+export async function applyDedupPolicy(
+  caseNumber: string,
+  items: InventoryItem[],
+  workers?: number,
+  pathCase?: PathCase
+): Promise<DedupPolicy> {
+  return invoke<DedupPolicy>("apply_dedup_policy", { caseNumber, items, workers, pathCase })
+}
+
+/**
+ * Gets the case's dedup policy (empty if none was applied)
+ */
+export async function getDedupPolicy(caseNumber: string | null): Promise<DedupPolicy> {
+  return invoke<DedupPolicy>("get_dedup_policy", { caseNumber })
+}
+
+/**
+ * Makes a file the primary of its duplicate group, suppressing the others
+ * 
+ * @throws Error if the file is not in the group with that hash, or the case is missing or finalized
+ */
+export async function setDuplicatePrimary(
+  caseNumber: string,
+  hash: string,
+  absolutePath: string
+): Promise<DedupPolicy> {
+  return invoke<DedupPolicy>("set_duplicate_primary", { caseNumber, hash, absolutePath })
+}
+
+/**
+ * Re-includes suppressed duplicates in review scope, or suppresses them again
+ * 
+ * @param included - True to re-include, false to suppress again
+ * @throws Error if the case number is missing or the case is finalized
+ */
+export async function setDuplicatesIncluded(
+  caseNumber: string,
+  paths: string[],
+  included: boolean
+): Promise<DedupPolicy> {
+  return invoke<DedupPolicy>("set_duplicates_included", { caseNumber, paths, included })
+}
+
+/**
+ * Removes the case's dedup policy so every file is back in review scope
+ * 
+ * @throws Error if the case number is missing or the case is finalized
+ */
+export async function clearDedupPolicy(caseNumber: string): Promise<void> {
+  return invoke("clear_dedup_policy", { caseNumber })
+}
+
+/**
+ * Shape and header row of one sheet (a CSV file counts as one sheet)
+ */
+export interface SheetProfile {
+  name: string
+  rows: number
+  columns: number
+  /** First non-empty row, taken as the header */
+  headers: string[]
+}
+
+/**
+ * Sheet profiles for one spreadsheet, or the error that prevented reading it
+ */
+export interface SpreadsheetProfile {
+  path: string
+  sheets: SheetProfile[]
+  error: string | null
+}
+
+/**
+ * Profiles the spreadsheets (XLSX, XLS, ODS, CSV) among the given files
+ * 
+ * @param paths - Absolute paths; non-spreadsheets are ignored
+ * @param query - Optional text to match against sheet names and headers
+ * @returns Promise resolving to profiles of the (matching) spreadsheets
+ */
+export async function profileSpreadsheets(
+  paths: string[],
+  query?: string
+): Promise<SpreadsheetProfile[]> {
+  return invoke<SpreadsheetProfile[]>("profile_spreadsheets", { paths, query })
+}
+
+/**
+ * Name, row count and columns of one database table
+ */
+export interface TableProfile {
+  name: string
+  /** Null when the table could not be counted */
+  rows: number | null
+  columns: string[]
+}
+
+/**
+ * Tables of one database file, or the error that prevented reading it
+ */
+export interface DatabaseProfile {
+  path: string
+  format: string
+  tables: TableProfile[]
+  error: string | null
+}
+
+/**
+ * Lists tables and row counts of the database files among the given paths.
+ * SQLite files are opened read-only and immutable; Access files are reported
+ * as unsupported. Each profiled file is recorded in the activity log.
+ * 
+ * @param paths - Absolute paths; files that are not databases are ignored
+ * @param caseNumber - Optional case number for the activity log entry
+ * @returns Promise resolving to one profile per database file
+ */
+export async function profileDatabases(
+  paths: string[],
+  caseNumber?: string
+): Promise<DatabaseProfile[]> {
+  return invoke<DatabaseProfile[]>("profile_databases", { paths, caseNumber })
+}
+
+/**
+ * A dated event recovered from a forensic artifact
+ */
+export interface ArtifactEvent {
+  /** UTC, YYYY-MM-DD HH:MM:SS */
+  timestamp: string
+  kind: "visit" | "download"
+  url: string
+  title: string
+  /** Where a download was saved; empty for visits */
+  target_path: string
+  /** The artifact file the event came from */
+  source_path: string
+}
+
+/**
+ * Events parsed from one browser history database, oldest first
+ */
+export interface BrowserArtifact {
+  path: string
+  browser: "chrome" | "firefox" | null
+  events: ArtifactEvent[]
+  error: string | null
+}
+
+/**
+ * Extracts page visits and downloads from Chrome and Firefox history
+ * databases among the given files. Other files are ignored.
+ * 
+ * @param paths - Absolute paths of ingested files
+ * @param caseNumber - Optional case number for the activity log entry
+ * @returns Promise resolving to one result per browser history file
+ */
+export async function parseBrowserArtifacts(
+  paths: string[],
+  caseNumber?: string
+): Promise<BrowserArtifact[]> {
+  return invoke<BrowserArtifact[]>("parse_browser_artifacts", { paths, caseNumber })
+}
+
+/**
+ * Volume a shortcut target lived on when the link was created
+ */
+export interface ShortcutVolume {
+  /** e.g. "fixed", "removable", "network" */
+  drive_type: string
+  serial_number: string
+  label: string
+}
+
+/**
+ * What a Windows shortcut (.lnk) points at, with the target's timestamps
+ */
+export interface ShortcutInfo {
+  path: string
+  target_path: string
+  /** UNC share when the target was on a network drive */
+  network_share: string
+  volume: ShortcutVolume | null
+  /** UTC, YYYY-MM-DD HH:MM:SS */
+  target_created: string | null
+  target_accessed: string | null
+  target_modified: string | null
+  target_size: number
+  description: string
+  relative_path: string
+  working_dir: string
+  arguments: string
+  error: string | null
+}
+
+/**
+ * Parses the Windows shortcuts among the given files. Files without a .lnk
+ * extension are ignored.
+ * 
+ * @param paths - Absolute paths of ingested files
+ * @returns Promise resolving to one result per shortcut
+ */
+export async function parseShortcuts(paths: string[]): Promise<ShortcutInfo[]> {
+  return invoke<ShortcutInfo[]>("parse_shortcuts", { paths })
+}
+
+/**
+ * Supported content hash algorithms
+ */
+export type HashAlgorithm = "md5" | "sha1" | "sha256"
+
+/**
+ * Hash (or error) for one file
+ */
+export interface FileHash {
+  path: string
+  hash: string | null
+  error: string | null
+}
+
+/**
+ * Hashes files on a bounded background worker pool
+ * 
+ * @param paths - Absolute paths of files to hash
+ * @param algorithm - Hash algorithm (default "sha256")
+ * @param workers - Concurrent workers (default: CPU count, max 8); use 1-2 for spinning disks
+ * @returns Promise resolving to hashes in the same order as paths
+ */
+export async function hashFiles(
+  paths: string[],
+  algorithm: HashAlgorithm = "sha256",
+  workers?: number
+): Promise<FileHash[]> {
+  return invoke<FileHash[]>("hash_files", { paths, algorithm, workers })
+}
+
+/**
+ * Measured read rate for one source (drive, share or mount)
+ */
+export interface SourceThroughput {
+  source: string
+  files: number
+  bytes: number
+  elapsed_ms: number
+  bytes_per_second: number
+  /** Workers used after the throughput probe */
+  workers: number
+  /** True when the source was slow enough to reduce parallelism */
+  slow: boolean
+}
+
+export interface HashReport {
+  /** Hashes in the same order as the requested paths */
+  hashes: FileHash[]
+  sources: SourceThroughput[]
+}
+
+/**
+ * Hashes files, probing each source's read rate first so slow network
+ * shares get fewer workers while local disks keep full concurrency
+ * 
+ * @param paths - Absolute paths of files to hash
+ * @param algorithm - Hash algorithm (default "sha256")
+ * @param workers - Workers for fast sources (default: CPU count, max 8)
+ * @returns Promise resolving to the hashes and per-source rates
+ */
+export async function hashFilesTuned(
+  paths: string[],
+  algorithm: HashAlgorithm = "sha256",
+  workers?: number
+): Promise<HashReport> {
+  return invoke<HashReport>("hash_files_tuned", { paths, algorithm, workers })
+}
+
+/**
+ * Result of adding a single document
+ */
+export interface IngestedFile {
+  item: InventoryItem
+  sha256: string
+  /** Where the file was copied to, when a copy was requested */
+  copied_to: string | null
+}
+
+/**
+ * Adds a single drag-and-dropped document without scanning a folder
+ * 
+ * @param filePath - Absolute path to the dropped file
+ * @param caseNumber - Case the file is added to (recorded in the activity log)
+ * @param copyTo - Folder to copy the file into first; omit to reference the original
+ * @param sourceFolder - The case's scanned folder; files inside it get a folder path relative to it, others the full path of their folder
+ * @returns Promise resolving to the new inventory item and its SHA-256
+ * @throws Error if the path is not a file, the copy fails, or the case is missing or finalized
+ */
+export async function ingestSingleFile(
+  filePath: string,
+  caseNumber: string,
+  copyTo: string | null = null,
+  sourceFolder: string | null = null
+): Promise<IngestedFile> {
+  return invoke<IngestedFile>("ingest_single_file", { filePath, copyTo, caseNumber, sourceFolder })
+}
+
+/**
+ * Returns the managed folder for a case's loose (dropped or pasted) documents,
+ * creating it if needed. Scan or sync it like any other source folder.
+ * 
+ * @param caseNumber - Case the folder belongs to
+ * @param baseDir - Parent folder to use instead of the app data directory
+ * @returns Promise resolving to the folder's absolute path
+ */
+export async function getLooseDocumentsFolder(
+  caseNumber: string,
+  baseDir: string | null = null
+): Promise<string> {
+  return invoke<string>("get_loose_documents_folder", { caseNumber, baseDir })
+}
+
+/**
+ * Copies a document into the case's loose documents folder and ingests the copy
+ * 
+ * @param caseNumber - Case to add the document to
+ * @param filePath - Absolute path to the document
+ * @param baseDir - Parent folder to use instead of the app data directory
+ * @returns Promise resolving to the new inventory item for the copy
+ * @throws Error if the path is not a file or the copy fails
+ */
+export async function addLooseDocument(
+  caseNumber: string,
+  filePath: string,
+  baseDir: string | null = null
+): Promise<IngestedFile> {
+  return invoke<IngestedFile>("add_loose_document", { caseNumber, filePath, baseDir })
+}
+
+/**
+ * Saves the current clipboard (an image if present, otherwise text) into the
+ * case's loose documents folder and ingests it
+ * 
+ * @param caseNumber - Case to capture into
+ * @param note - Optional note stored on the new item
+ * @returns Promise resolving to the new inventory item
+ * @throws Error if the clipboard is empty or cannot be read
+ */
+export async function captureClipboardToCase(
+  caseNumber: string,
+  note: string | null = null
+): Promise<IngestedFile> {
+  let imagePng: number[] | null = null
+  let text: string | null = null
+
+  const clipboardItems = await navigator.clipboard.read()
+  const imageItem = clipboardItems.find((item) => item.types.includes("image/png"))
+  if (imageItem) {
+    const blob = await imageItem.getType("image/png")
+    imagePng = Array.from(new Uint8Array(await blob.arrayBuffer()))
+  } else {
+    text = (await navigator.clipboard.readText()) || null
+  }
+
+  return invoke<IngestedFile>("capture_clipboard_to_case", {
+    caseNumber,
+    text,
+    imagePng,
+    note,
+  })
+}
+
+/**
+ * A source file renamed on disk
+ */
+export interface RenamedFile {
+  old_path: string
+  old_name: string
+  new_path: string
+  new_name: string
+}
+
+export interface RenameReport {
+  /** Every input item; renamed ones carry their new path and file name */
+  items: InventoryItem[]
+  renamed: RenamedFile[]
+  failed: FailedFile[]
+  dry_run: boolean
+}
+
+/**
+ * Renames source files on disk to a pattern. Placeholders are any inventory
+ * field (e.g. `{doc_year}_{document_type}_{bates_stamp}`), `{original}` and
+ * `{n}`; extensions and notes are kept, and each result records the old name.
+ * 
+ * @param items - Items whose files should be renamed
+ * @param pattern - New file name pattern
+ * @param caseNumber - Case to record the rename against in the activity log
+ * @param dryRun - Preview the new names without renaming (default true)
+ * @returns Promise resolving to the updated items and per-file results
+ * @throws Error if the pattern contains an unknown placeholder, or the case is missing or finalized
+ */
+export async function renameFiles(
+  items: InventoryItem[],
+  pattern: string,
+  caseNumber: string,
+  dryRun: boolean = true
+): Promise<RenameReport> {
+  return invoke<RenameReport>("rename_files", { items, pattern, dryRun, caseNumber })
+}
+
+/** Copy into a clean working copy, or move the original files */
+export type TransferMode = "copy" | "move"
+
+export interface ReorganizationRules {
+  target_root: string
+  /** Folder pattern such as `{doc_year}/{document_type}`; `/` separates levels */
+  folder_pattern: string
+  mode?: TransferMode
+}
+
+export interface PlannedMove {
+  source_path: string
+  target_path: string
+  /** Folder under the target root, with `/` separators */
+  target_folder: string
+}
+
+export interface ReorganizationPlan {
+  moves: PlannedMove[]
+  /** Distinct folders the plan creates, sorted */
+  folders: string[]
+  /** Items that cannot be placed, e.g. because the source file is missing */
+  skipped: FailedFile[]
+}
+
+export interface ReorganizationReport {
+  /** Every input item; placed ones point at their new location */
+  items: InventoryItem[]
+  moved: PlannedMove[]
+  failed: FailedFile[]
+}
+
+/**
+ * Proposes a new folder layout for the items without touching any files
+ * 
+ * @param items - Items to lay out
+ * @param rules - Target root, folder pattern and transfer mode
+ * @returns Promise resolving to the planned moves and folders
+ * @throws Error if the folder pattern contains an unknown placeholder
+ */
+export async function planReorganization(
+  items: InventoryItem[],
+  rules: ReorganizationRules
+): Promise<ReorganizationPlan> {
+  return invoke<ReorganizationPlan>("plan_reorganization", { items, rules })
+}
+
+/**
+ * Copies or moves files into the planned layout
+ * 
+ * @param items - Items to lay out
+ * @param rules - Target root, folder pattern and transfer mode
+ * @param caseNumber - Case to record the change against in the activity log
+ * @returns Promise resolving to the items re-pointed at their new locations
+ * @throws Error if the folder pattern contains an unknown placeholder, or the case is missing or finalized
+ */
+export async function applyReorganization(
+  items: InventoryItem[],
+  rules: ReorganizationRules,
+  caseNumber: string
+): Promise<ReorganizationReport> {
+  return invoke<ReorganizationReport>("apply_reorganization", { items, rules, caseNumber })
+}
+
+/**
+ * A manifest file whose size or hash differs from the copy on disk
+ */
+export interface ManifestMismatch {
+  path: string
+  expected_hash: string
+  /** Null when the size already differed and the file was not hashed */
+  actual_hash: string | null
+  expected_size: number | null
+  actual_size: number
+}
+
+/**
+ * Result of reconciling a hash manifest against a folder
+ */
+export interface ManifestReconciliation {
+  manifest_entries: number
+  matched: number
+  different: ManifestMismatch[]
+  missing: string[]
+  extra: string[]
+  unreadable: FailedFile[]
+}
+
+/**
+ * Files in hand whose content has a looked-up hash
+ */
+export interface HashMatch {
+  hash: string
+  paths: string[]
+}
+
+export interface HashLookup {
+  /** Hashes with at least one matching file, in lookup order */
+  found: HashMatch[]
+  /** Hashes no file matched */
+  missing: string[]
+  /** Values that are not MD5, SHA-1 or SHA-256 hex digests */
+  invalid: string[]
+  /** Files that could not be hashed */
+  unreadable: FileHash[]
+}
+
+/**
+ * Checks which hashes match files in hand. The algorithm of each hash is
+ * inferred from its length.
+ * 
+ * @param paths - Absolute paths of the files to search, from any cases
+ * @param hashes - Hashes to look up
+ * @param manifestPath - Optional production log or hash manifest whose hashes are added to the lookup
+ * @param workers - Concurrent hashing workers (default: CPU count, max 8)
+ * @returns Promise resolving to found, missing and invalid hashes
+ * @throws Error if the manifest cannot be read
+ */
+export async function lookupHashes(
+  paths: string[],
+  hashes: string[] = [],
+  manifestPath?: string,
+  workers?: number
+): Promise<HashLookup> {
+  return invoke<HashLookup>("lookup_hashes", { paths, hashes, manifestPath, workers })
+}
+
+/**
+ * Finds the files whose content has the given hash
+ * 
+ * @param paths - Absolute paths of the files to search
+ * @param hash - MD5, SHA-1 or SHA-256 hex digest
+ * @returns Promise resolving to the matching paths (empty if none)
+ */
+export async function lookupHash(paths: string[], hash: string): Promise<string[]> {
+  const result = await lookupHashes(paths, [hash])
+  return result.found[0]?.paths ?? []
+}
+
+/**
+ * Verifies an intake folder against a hash manifest from an acquisition tool.
+ * Accepts md5sum/sha256sum output or CSV/TSV with path, size and hash columns.
+ * 
+ * @param manifestPath - Absolute path to the manifest file
+ * @param folderPath - Root folder the manifest describes
+ * @param workers - Concurrent hashing workers (default: CPU count, max 8)
+ * @param pathCase - How manifest paths are compared (default: probe the volume)
+ * @returns Promise resolving to matched, different, missing and extra files
+ * @throws Error if either path is invalid or the manifest cannot be parsed
+ */
+export async function reconcileHashManifest(
+  manifestPath: string,
+  folderPath: string,
+  workers?: number,
+  pathCase?: PathCase
+): Promise<ManifestReconciliation> {
+  return invoke<ManifestReconciliation>("reconcile_hash_manifest", {
+    manifestPath,
+    folderPath,
+    workers,
+    pathCase,
+  })
+}
+
+/**
+ * Opens a folder in the system file explorer
+ * 
+ * @param folderPath - Absolute path to the folder to open
+ * @returns Promise that resolves when the folder is opened
+ * @throws Error if the folder cannot be opened
+ * 
+ * @example
+ * ```ts
+ * await openFolder("/path/to/documents")
+ * ```
+ */
+export async function openFolder(folderPath: string): Promise<void> {
+  return openPath(folderPath)
+}
+
+/**
+ * Opens a case in its own window, or focuses the window already showing it
+ * 
+ * @param caseNumber - Case to open; the new window starts with it selected
+ * @returns Promise resolving to the window label
+ * @throws Error if the case number is empty or the window cannot be created
+ */
+export async function openCaseWindow(caseNumber: string): Promise<string> {
+  return invoke<string>("open_case_window", { caseNumber })
+}
+
+/**
+ * Loads the view preferences saved by this window
+ * 
+ * Each window keeps its own; a case window gets back what it saved the last
+ * time that case was open in its own window.
+ * 
+ * @returns Promise resolving to the saved preferences, or null if none
+ */
+export async function getWorkspacePreferences<T>(): Promise<T | null> {
+  return invoke<T | null>("get_workspace_preferences")
+}
+
+/**
+ * Saves this window's view preferences (columns, filters, open panels)
+ * 
+ * @param preferences - Preferences to save, replacing the window's previous ones
+ * @returns Promise that resolves when saved
+ */
+export async function saveWorkspacePreferences<T>(preferences: T): Promise<void> {
+  return invoke("save_workspace_preferences", { preferences })
+}
+
+/**
+ * App lock state
+ */
+export interface LockStatus {
+  /** Whether a passphrase is set */
+  enabled: boolean
+  locked: boolean
+  idle_timeout_minutes: number | null
+}
+
+/**
+ * Gets whether the app lock is set up and engaged
+ * 
+ * While locked, every command but this one and `unlockApp` is refused.
+ * 
+ * @returns Promise resolving to the lock status
+ */
+export async function getLockStatus(): Promise<LockStatus> {
+  return invoke<LockStatus>("get_lock_status")
+}
+
+/**
+ * Unlocks the app
+ * 
+ * @param passphrase - The app lock passphrase
+ * @throws Error if the passphrase is wrong
+ */
+export async function unlockApp(passphrase: string): Promise<void> {
+  return invoke("unlock_app", { passphrase })
+}
+
+/**
+ * Locks the app now
+ * 
+ * @throws Error if no passphrase is set
+ */
+export async function lockApp(): Promise<void> {
+  return invoke("lock_app")
+}
+
+/**
+ * Sets, changes or removes the app lock passphrase
+ * 
+ * @param passphrase - New passphrase, or null to remove the lock
+ * @param currentPassphrase - Required when a passphrase is already set
+ * @throws Error if the current passphrase is wrong
+ */
+export async function setAppPassphrase(
+  passphrase: string | null,
+  currentPassphrase: string | null = null
+): Promise<void> {
+  return invoke("set_app_passphrase", { passphrase, currentPassphrase })
+}
+
+/**
+ * Sets how long the app may sit idle before it locks itself
+ * 
+ * @param minutes - Idle minutes, or null to turn the idle lock off
+ */
+export async function setIdleLockTimeout(minutes: number | null): Promise<void> {
+  return invoke("set_idle_lock_timeout", { minutes })
+}
+
+/**
+ * Local API server state
+ */
+export interface ApiStatus {
+  running: boolean
+  port: number | null
+  /** Sent by clients as `Authorization: Bearer <token>`; new on every start */
+  token: string | null
+}
+
+/**
+ * Starts the read-only local API on 127.0.0.1
+ * 
+ * Serves `GET /cases`, `GET /cases/{case}/files` and
+ * `GET /cases/{case}/search?q=` for inventories published with
+ * `publishCaseInventory`.
+ * 
+ * @param port - Port to listen on, or null for any free port
+ * @returns Promise resolving to the port and token to give integrations
+ * @throws Error if the API is already running or the port is taken
+ */
+export async function startApiServer(port: number | null = null): Promise<ApiStatus> {
+  return invoke<ApiStatus>("start_api_server", { port })
+}
+
+/**
+ * Stops the local API
+ */
+export async function stopApiServer(): Promise<void> {
+  return invoke("stop_api_server")
+}
+
+/**
+ * Gets whether the local API is running, and its port and token
+ * 
+ * @returns Promise resolving to the API status
+ */
+export async function getApiStatus(): Promise<ApiStatus> {
+  return invoke<ApiStatus>("get_api_status")
+}
+
+/**
+ * Makes a case's current inventory available to the local API and to
+ * scheduled exports
+ * 
+ * @param caseNumber - Case the inventory belongs to
+ * @param items - The case's inventory items
+ * @returns Promise resolving to the cases unloaded to stay within the limits
+ * @throws Error if the case has more files than one case may publish
+ */
+export async function publishCaseInventory(caseNumber: string, items: InventoryItem[]): Promise<string[]> {
+  return invoke<string[]>("publish_case_inventory", { caseNumber, items })
+}
+
+/**
+ * Drops a case's published inventory, as when its window closes
+ * 
+ * @param caseNumber - Case to drop
+ * @returns Promise resolving to whether the case was published
+ */
+export async function unpublishCaseInventory(caseNumber: string): Promise<boolean> {
+  return invoke<boolean>("unpublish_case_inventory", { caseNumber })
+}
+
+/**
+ * Caps on the inventories the backend holds; null is no cap
+ */
+export interface InventoryLimits {
+  /** Cases held at once; publishing one more unloads the least recently used */
+  max_cases: number | null
+  /** Files one case may publish */
+  max_case_files: number | null
+}
+
+/**
+ * One published case's share of backend memory
+ */
+export interface CaseResourceUsage {
+  case_number: string
+  file_count: number
+  published_at: string
+  cached_stores: number
+  cached_store_bytes: number
+}
+
+/**
+ * What the backend holds in memory, for diagnostics
+ */
+export interface ResourceUsage {
+  limits: InventoryLimits
+  /** Published cases, most recently used first */
+  cases: CaseResourceUsage[]
+  /** Every cached store, including the app-wide ones */
+  cached_stores: number
+  cached_store_bytes: number
+}
+
+/**
+ * Sets the caps on published inventories
+ * 
+ * @param limits - The new caps
+ * @returns Promise resolving to the cases unloaded to meet them
+ */
+export async function setResourceLimits(limits: InventoryLimits): Promise<string[]> {
+  return invoke<string[]>("set_resource_limits", { limits })
+}
+
+/**
+ * Gets what the backend holds in memory per case
+ * 
+ * @returns Promise resolving to the current usage and caps
+ */
+export async function getResourceUsage(): Promise<ResourceUsage> {
+  return invoke<ResourceUsage>("get_resource_usage")
+}
+
+/**
+ * An export run automatically on a cron-like schedule
+ */
+export interface ExportSchedule {
+  id: string
+  case_number: string
+  /**
+   * Minute, hour, day of month, month and day of week (0 is Sunday), each
+   * `*`, a number, a range `a-b`, a list `a,b` or a step such as `0-59/15`;
+   * e.g. `30 2 * * *` for 02:30 nightly
+   */
+  cron: string
+  format: "xlsx" | "csv" | "json" | "sqlite"
+  directory: string
+  /** File name pattern, e.g. `{case_id}_{date}` */
+  pattern: string
+  collision?: CollisionPolicy
+  options?: ExportOptions
+}
+
+/**
+ * One run of an export schedule
+ */
+export interface ExportRun {
+  schedule_id: string
+  case_number: string
+  started_at: string
+  output_path: string | null
+  item_count: number
+  /** Why the run failed */
+  error: string | null
+}
+
+/** Event emitted with the `ExportRun` when a scheduled export fails */
+export const EXPORT_SCHEDULE_FAILED_EVENT = "export-schedule-failed"
+
+/**
+ * Adds an export schedule, or replaces the one with the same id
+ * 
+ * Schedules run while the app is open, exporting the case's inventory as
+ * its window last published it.
+ * 
+ * @param schedule - The schedule to save
+ * @throws Error if the cron expression or directory is invalid
+ */
+export async function createExportSchedule(schedule: ExportSchedule): Promise<void> {
+  return invoke("create_export_schedule", { schedule })
+}
+
+/**
+ * Lists export schedules
+ * 
+ * @param caseNumber - Only this case's schedules, or null for all
+ * @returns Promise resolving to the schedules
+ */
+export async function listExportSchedules(caseNumber: string | null = null): Promise<ExportSchedule[]> {
+  return invoke<ExportSchedule[]>("list_export_schedules", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Removes an export schedule and its run history
+ * 
+ * @param id - Schedule to remove
+ */
+export async function deleteExportSchedule(id: string): Promise<void> {
+  return invoke("delete_export_schedule", { id })
+}
+
+/**
+ * Lists a case's scheduled export runs, newest first
+ * 
+ * @param caseNumber - The case
+ * @param scheduleId - Only this schedule's runs, or null for all
+ * @returns Promise resolving to the runs
+ */
+export async function listExportRuns(caseNumber: string, scheduleId: string | null = null): Promise<ExportRun[]> {
+  return invoke<ExportRun[]>("list_export_runs", { caseNumber, scheduleId })
+}
+
+/**
+ * Runs an export schedule now
+ * 
+ * @param id - Schedule to run
+ * @returns Promise resolving to the run, with its error if it failed
+ */
+export async function runExportScheduleNow(id: string): Promise<ExportRun> {
+  return invoke<ExportRun>("run_export_schedule_now", { id })
+}
+
+/**
+ * Where a document stands in privilege review: withheld in full, produced
+ * with redactions, or produced as is (left off the privilege log)
+ */
+export type PrivilegeStatus = "privileged" | "redacted" | "produced"
+
+/**
+ * A privilege call on one document, with the details the privilege log needs
+ */
+export interface PrivilegeDesignation {
+  case_number: string | null
+  absolute_path: string
+  status: PrivilegeStatus
+  date?: string
+  author?: string
+  recipients?: string
+  description?: string
+  /** e.g. "Attorney-Client Privilege" or "Work Product" */
+  basis?: string
+}
+
+/**
+ * Records a privilege designation, replacing the file's previous one
+ * 
+ * Empty date, author, recipients and description are filled from the
+ * document's email headers or PDF/Office properties.
+ * 
+ * @param designation - The designation
+ * @param propagateToFamily - Also give the status and basis to the files linked to it as attachments or containers
+ * @returns Promise resolving to the designation as stored
+ * @throws Error if the file is not part of the case
+ */
+export async function designatePrivilege(
+  designation: PrivilegeDesignation,
+  propagateToFamily = false
+): Promise<PrivilegeDesignation> {
+  return invoke<PrivilegeDesignation>("designate_privilege", {
+    designation: { ...designation, case_number: designation.case_number || null },
+    propagateToFamily,
+  })
+}
+
+/**
+ * A document family member's privilege status; null when undesignated
+ */
+export interface FamilyMemberStatus {
+  absolute_path: string
+  status: PrivilegeStatus | null
+}
+
+/**
+ * Finds document families whose members have differing privilege
+ * designations, such as a withheld email with a produced attachment
+ * 
+ * Families are files joined by attachment or containment links.
+ * 
+ * @param caseNumber - The case
+ * @returns Promise resolving to the inconsistent families, one member list each
+ */
+export async function checkPrivilegeFamilies(caseNumber: string | null): Promise<FamilyMemberStatus[][]> {
+  return invoke<FamilyMemberStatus[][]>("check_privilege_families", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Lists a case's privilege designations
+ * 
+ * @param caseNumber - The case
+ * @returns Promise resolving to the designations
+ */
+export async function listPrivilegeDesignations(caseNumber: string | null): Promise<PrivilegeDesignation[]> {
+  return invoke<PrivilegeDesignation[]>("list_privilege_designations", { caseNumber: caseNumber || null })
+}
+
+/**
+ * Removes a document's privilege designation
+ * 
+ * @param caseNumber - The case
+ * @param absolutePath - The document
+ */
+export async function clearPrivilegeDesignation(caseNumber: string | null, absolutePath: string): Promise<void> {
+  return invoke("clear_privilege_designation", { caseNumber: caseNumber || null, absolutePath })
+}
+
+/**
+ * Writes the case's privilege log as CSV
+ * 
+ * Lists every withheld or redacted document among `items`, in order, with
+ * its Bates number, date, author, recipients, description and basis.
+ * 
+ * @param caseNumber - The case
+ * @param items - The case's inventory items, in log order
+ * @param outputPath - Full path of the CSV to write
+ * @returns Promise resolving to the number of log entries
+ */
+export async function exportPrivilegeLog(
+  caseNumber: string | null,
+  items: InventoryItem[],
+  outputPath: string
+): Promise<number> {
+  return invoke<number>("export_privilege_log", { caseNumber: caseNumber || null, items, outputPath })
 }
@@ -39,6 +39,8 @@ interface InventoryState {
   // UI state
   exportDialogOpen: boolean
   importDialogOpen: boolean
+  /** Show duplicates the case's dedup policy suppresses in the table */
+  showSuppressed: boolean
   
   // Actions
   setItems: (items: InventoryItem[]) => void
@@ -55,13 +57,15 @@ interface InventoryState {
   setSyncStatus: (status: 'synced' | 'out_of_sync' | null, fileCount?: number) => void
   setExportDialogOpen: (open: boolean) => void
   setImportDialogOpen: (open: boolean) => void
+  setShowSuppressed: (show: boolean) => void
   reset: () => void
 }
 
 const initialState = {
   items: [],
   selectedFolder: null,
-  caseNumber: "",
+  // Windows opened for a specific case start on that case
+  caseNumber: window.__CASE_NUMBER__ ?? "",
   selectedIndices: [],
   loading: false,
   scanning: false,
@@ -72,6 +76,7 @@ const initialState = {
   folderFileCount: null as number | null,
   exportDialogOpen: false,
   importDialogOpen: false,
+  showSuppressed: false,
 }
 
 /**
@@ -142,5 +147,7 @@ export const useInventoryStore = create<InventoryState>((set) => ({
   
   setImportDialogOpen: (open) => set({ importDialogOpen: open }),
   
+  setShowSuppressed: (show) => set({ showSuppressed: show }),
+  
   reset: () => set(initialState),
 }))
@@ -20,6 +20,7 @@ describe("inventory types", () => {
     folder_name: "test",
     folder_path: "test",
     file_type: "PDF",
+    file_category: "Documents",
     bates_stamp: "B001",
     notes: "Test notes",
     absolute_path: "/test/test.pdf",
@@ -15,6 +15,8 @@ export interface InventoryItem {
   folder_name: string
   folder_path: string
   file_type: string
+  /** Broad category: Documents, Spreadsheets, Email, Images, AV, Archives, Databases or Other */
+  file_category: string
   bates_stamp: string
   notes: string
   absolute_path: string
@@ -44,6 +46,7 @@ export function isInventoryItemField(field: string): field is InventoryItemField
     "folder_name",
     "folder_path",
     "file_type",
+    "file_category",
     "bates_stamp",
     "notes",
     "absolute_path",
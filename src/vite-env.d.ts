@@ -1 +1,6 @@
 /// <reference types="vite/client" />
+
+interface Window {
+  /** Set by the backend when a case is opened in its own window */
+  __CASE_NUMBER__?: string
+}